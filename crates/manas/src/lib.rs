@@ -19,6 +19,7 @@
 //! * PATCH requests with n3-patch support.
 //! * Full support for conditional requests, range requests, content-negotiation.
 //! * Integrated solid-os databrowser frontend.
+//! * Solid notifications with websocket and webhook channels.
 //! * ..etc.
 //!
 //! ## For developers
@@ -57,6 +58,8 @@
 //!
 //! - [`manas_podverse`](https://docs.rs/manas_podverse): Provides traits (`Pod`, `PodSet`, and `ProvisionablePodSet`, etc.) and default implementations for defining, serving, provisioning solid pods and podsets.
 //!
+//! - [`manas_notification`](https://docs.rs/manas_notification): Provides an implementation of [solid notifications protocol](https://solidproject.org/TR/notifications-protocol) over manas storages, with `WebSocketChannel2023` channel type.
+//!
 //! - [`manas_server`](https://docs.rs/manas_server): Provides default recipes of solid server.
 //!
 //! - [`manas`](https://docs.rs/manas): All inclusive crate.
@@ -0,0 +1,200 @@
+//! I define [`AuditEmitter`], that delivers audit events to
+//! sinks in background.
+//!
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tracing::{error, warn};
+
+use super::{sink::AuditSink, AuditEvent};
+
+/// Default capacity of the audit events buffer.
+pub const DEFAULT_AUDIT_BUFFER_CAPACITY: usize = 1024;
+
+/// An emitter of audit events.
+///
+/// Emitting never blocks the caller. Events are buffered in
+/// a bounded queue, and are delivered to the sinks by a
+/// background task. If the buffer is full, events are
+/// dropped with a warning. Dropped events are counted, and
+/// the next delivered event reports the number of events
+/// dropped before it, so that gaps are visible in the audit
+/// log.
+#[derive(Debug, Clone)]
+pub struct AuditEmitter {
+    tx: mpsc::Sender<AuditEvent>,
+
+    /// Total number of dropped events.
+    dropped: Arc<AtomicU64>,
+
+    /// Number of dropped events, that are not yet reported
+    /// by a delivered event.
+    unreported_dropped: Arc<AtomicU64>,
+}
+
+impl AuditEmitter {
+    /// Create a new [`AuditEmitter`] that delivers events to
+    /// given sinks, with given buffer capacity.
+    ///
+    /// It must be called in the context of a tokio runtime,
+    /// as it spawns the delivery task.
+    pub fn new(sinks: Vec<Box<dyn AuditSink>>, buffer_capacity: usize) -> Self {
+        let (tx, rx) = mpsc::channel(buffer_capacity.max(1));
+        tokio::spawn(Self::deliver(rx, sinks));
+        Self {
+            tx,
+            dropped: Default::default(),
+            unreported_dropped: Default::default(),
+        }
+    }
+
+    /// Get the total number of events dropped so far.
+    #[inline]
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Emit given audit event.
+    pub fn emit(&self, mut event: AuditEvent) {
+        let unreported_dropped = self.unreported_dropped.swap(0, Ordering::Relaxed);
+        event.dropped_before = (unreported_dropped > 0).then_some(unreported_dropped);
+
+        let Err(e) = self.tx.try_send(event) else {
+            return;
+        };
+
+        // Event is dropped. Carry over the unreported count to
+        // the next event.
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+        self.unreported_dropped
+            .fetch_add(unreported_dropped + 1, Ordering::Relaxed);
+
+        match e {
+            TrySendError::Full(_) => {
+                warn!("Audit events buffer is full. Dropping the event.");
+            }
+            TrySendError::Closed(_) => {
+                error!("Audit events delivery task is closed. Dropping the event.");
+            }
+        }
+    }
+
+    /// Deliver received events to given sinks.
+    async fn deliver(mut rx: mpsc::Receiver<AuditEvent>, mut sinks: Vec<Box<dyn AuditSink>>) {
+        while let Some(event) = rx.recv().await {
+            for sink in sinks.iter_mut() {
+                if let Err(e) = sink.write(&event).await {
+                    error!("Error in writing audit event to sink. Error:\n {}", e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::io;
+
+    use chrono::DateTime;
+    use claims::*;
+    use futures::future::BoxFuture;
+
+    use super::*;
+    use crate::audit::AuditDecision;
+
+    /// An [`AuditSink`] that forwards events to a channel.
+    #[derive(Debug)]
+    pub(crate) struct ChannelAuditSink(pub mpsc::UnboundedSender<AuditEvent>);
+
+    impl AuditSink for ChannelAuditSink {
+        fn write<'s>(&'s mut self, event: &'s AuditEvent) -> BoxFuture<'s, io::Result<()>> {
+            Box::pin(async move {
+                self.0
+                    .send(event.clone())
+                    .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "Receiver is closed."))
+            })
+        }
+    }
+
+    /// Create a new audit event with given target. Events
+    /// have a fixed timestamp, so that their serializations
+    /// are of equal length for equal length targets.
+    pub(crate) fn new_event(target: &str) -> AuditEvent {
+        AuditEvent {
+            timestamp: DateTime::from_timestamp(1_700_000_000, 0).expect("Must be valid."),
+            agent: None,
+            client: None,
+            issuer: None,
+            target: target.to_owned(),
+            requested_ops: vec![],
+            decision: AuditDecision::Allow,
+            denied_ops: vec![],
+            grants: vec![],
+            matched_policies: vec![],
+            dropped_before: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn events_are_delivered_in_order() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let emitter = AuditEmitter::new(vec![Box::new(ChannelAuditSink(tx))], 4);
+
+        for target in ["a", "b", "c"] {
+            emitter.emit(new_event(target));
+        }
+
+        for target in ["a", "b", "c"] {
+            let event = assert_some!(rx.recv().await);
+            assert_eq!(event.target, target);
+            assert_none!(event.dropped_before);
+        }
+        assert_eq!(emitter.dropped(), 0);
+    }
+
+    #[tokio::test]
+    async fn events_are_dropped_and_counted_when_buffer_is_full() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let emitter = AuditEmitter::new(vec![Box::new(ChannelAuditSink(tx))], 2);
+
+        // Delivery task doesn't run until we yield, on the
+        // current thread runtime.
+        for target in ["a", "b", "c", "d", "e"] {
+            emitter.emit(new_event(target));
+        }
+        assert_eq!(emitter.dropped(), 3);
+
+        for target in ["a", "b"] {
+            let event = assert_some!(rx.recv().await);
+            assert_eq!(event.target, target);
+            assert_none!(event.dropped_before);
+        }
+
+        // Next delivered event reports the gap.
+        emitter.emit(new_event("f"));
+        let event = assert_some!(rx.recv().await);
+        assert_eq!(event.target, "f");
+        assert_eq!(event.dropped_before, Some(3));
+
+        // And the gap is reported only once.
+        emitter.emit(new_event("g"));
+        let event = assert_some!(rx.recv().await);
+        assert_none!(event.dropped_before);
+        assert_eq!(emitter.dropped(), 3);
+    }
+
+    #[test]
+    fn dropped_count_is_serialized_only_when_there_is_a_gap() {
+        let mut event = new_event("a");
+        let json = assert_ok!(serde_json::to_value(&event));
+        assert_none!(json.get("dropped_before"));
+
+        event.dropped_before = Some(2);
+        let json = assert_ok!(serde_json::to_value(&event));
+        assert_eq!(json["dropped_before"], 2);
+    }
+}
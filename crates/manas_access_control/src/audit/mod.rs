@@ -0,0 +1,112 @@
+//! I define types for auditing access control decisions.
+//!
+//! Audit events are emitted through an [`AuditEmitter`],
+//! which buffers them and delivers to configured
+//! [`AuditSink`](sink::AuditSink)s in background.
+//!
+
+use chrono::{DateTime, Utc};
+use manas_authentication::common::credentials::{
+    AgentCredentials, ClientCredentials, IssuerCredentials, RequestCredentials,
+};
+use rdf_utils::model::handle::Handle;
+use serde::Serialize;
+use sophia_api::term::Term;
+
+use crate::model::{pep::ResolvedAccessControlResponse, JustifiedOperation, ResolvedAccessControl};
+
+pub mod emitter;
+pub mod sink;
+
+pub use emitter::AuditEmitter;
+
+/// An enum to represent audited access decision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditDecision {
+    /// Action is allowed.
+    Allow,
+
+    /// Action is denied.
+    Deny,
+}
+
+/// A struct to represent an audit event of an access control
+/// decision.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEvent {
+    /// Time of the decision.
+    pub timestamp: DateTime<Utc>,
+
+    /// Webid of the request agent.
+    pub agent: Option<String>,
+
+    /// Id of the request client.
+    pub client: Option<String>,
+
+    /// Uri of the request issuer.
+    pub issuer: Option<String>,
+
+    /// Uri of the target resource.
+    pub target: String,
+
+    /// Requested operations.
+    pub requested_ops: Vec<JustifiedOperation>,
+
+    /// Access decision.
+    pub decision: AuditDecision,
+
+    /// Denied operations.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub denied_ops: Vec<JustifiedOperation>,
+
+    /// Iris of the granted access modes.
+    pub grants: Vec<String>,
+
+    /// Iris of the policies, that are matched in resolving
+    /// the grants.
+    pub matched_policies: Vec<String>,
+
+    /// Number of events dropped since the previously
+    /// delivered event, as audit events buffer was full.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dropped_before: Option<u64>,
+}
+
+impl AuditEvent {
+    /// Create a new audit event for given resolved access
+    /// control response.
+    pub fn new<C: RequestCredentials>(response: &ResolvedAccessControlResponse<C>) -> Self {
+        let authorization = response.resolved.authorization();
+        let credentials = &authorization.credentials;
+
+        let (decision, denied_ops) = match &response.resolved {
+            ResolvedAccessControl::Allow { .. } => (AuditDecision::Allow, vec![]),
+            ResolvedAccessControl::Deny { denied_ops, .. } => {
+                (AuditDecision::Deny, denied_ops.clone().into_vec())
+            }
+        };
+
+        Self {
+            timestamp: Utc::now(),
+            agent: credentials.of_agent().map(|c| c.webid().to_string()),
+            client: credentials.of_client().map(|c| c.client_id().clone()),
+            issuer: credentials.of_issuer().map(|c| c.uri().as_str().to_owned()),
+            target: response.action_op_list.on.as_str().to_owned(),
+            requested_ops: response.action_op_list.ops.clone(),
+            decision,
+            denied_ops,
+            grants: authorization
+                .grants
+                .iter()
+                .filter_map(|mode| mode.as_term().iri().map(|iri| iri.as_str().to_owned()))
+                .collect(),
+            matched_policies: authorization
+                .matched_policies
+                .iter()
+                .filter_map(|policy| policy.iri().map(|iri| iri.as_str().to_owned()))
+                .collect(),
+            dropped_before: None,
+        }
+    }
+}
@@ -0,0 +1,223 @@
+//! I define an implementation of [`AuditSink`] that writes
+//! audit events to rotating json-lines files.
+//!
+
+use std::{io, path::PathBuf};
+
+use futures::future::BoxFuture;
+use tokio::{
+    fs::{File, OpenOptions},
+    io::AsyncWriteExt,
+};
+
+use crate::audit::{sink::AuditSink, AuditEvent};
+
+/// Default max size of an audit log file.
+pub const DEFAULT_MAX_FILE_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Default number of rotated audit log files to retain.
+pub const DEFAULT_MAX_ROTATED_FILES: usize = 8;
+
+/// A struct to represent config of
+/// [`RotatingJsonLinesFileSink`].
+#[derive(Debug, Clone)]
+pub struct RotatingJsonLinesFileSinkConfig {
+    /// Path of the active log file. Rotated files are kept
+    /// besides it, with numeric suffixes.
+    pub path: PathBuf,
+
+    /// Max size of a log file in bytes, after which it will
+    /// be rotated.
+    pub max_file_bytes: u64,
+
+    /// Max number of rotated files to retain.
+    pub max_rotated_files: usize,
+}
+
+impl RotatingJsonLinesFileSinkConfig {
+    /// Create a new config with given log file path, and
+    /// default limits.
+    #[inline]
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+            max_rotated_files: DEFAULT_MAX_ROTATED_FILES,
+        }
+    }
+}
+
+/// An implementation of [`AuditSink`] that appends audit
+/// events as json lines to a log file. Log file is rotated,
+/// once it's size exceeds the configured limit.
+#[derive(Debug)]
+pub struct RotatingJsonLinesFileSink {
+    /// Config.
+    config: RotatingJsonLinesFileSinkConfig,
+
+    /// Active log file, with it's size.
+    active: Option<(File, u64)>,
+}
+
+impl RotatingJsonLinesFileSink {
+    /// Create a new [`RotatingJsonLinesFileSink`] with given
+    /// config.
+    #[inline]
+    pub fn new(config: RotatingJsonLinesFileSinkConfig) -> Self {
+        Self {
+            config,
+            active: None,
+        }
+    }
+
+    /// Get path of the rotated file with given index.
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut path = self.config.path.clone().into_os_string();
+        path.push(format!(".{}", index));
+        path.into()
+    }
+
+    /// Open the active log file in append mode.
+    async fn open_active(&self) -> io::Result<(File, u64)> {
+        if let Some(parent) = self.config.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.config.path)
+            .await?;
+        let size = file.metadata().await?.len();
+        Ok((file, size))
+    }
+
+    /// Rotate the log files.
+    async fn rotate(&mut self) -> io::Result<()> {
+        if let Some((mut file, _)) = self.active.take() {
+            file.flush().await?;
+        }
+
+        if self.config.max_rotated_files == 0 {
+            return tokio::fs::remove_file(&self.config.path).await;
+        }
+
+        // Shift existing rotated files, dropping the oldest.
+        for index in (1..self.config.max_rotated_files).rev() {
+            let from = self.rotated_path(index);
+            if tokio::fs::try_exists(&from).await? {
+                tokio::fs::rename(from, self.rotated_path(index + 1)).await?;
+            }
+        }
+
+        tokio::fs::rename(&self.config.path, self.rotated_path(1)).await
+    }
+}
+
+impl AuditSink for RotatingJsonLinesFileSink {
+    fn write<'s>(&'s mut self, event: &'s AuditEvent) -> BoxFuture<'s, io::Result<()>> {
+        Box::pin(async move {
+            let mut line = serde_json::to_vec(event)?;
+            line.push(b'\n');
+
+            if self.active.is_none() {
+                self.active = Some(self.open_active().await?);
+            }
+
+            let size = self.active.as_ref().map_or(0, |(_, size)| *size);
+            if size > 0 && size + line.len() as u64 > self.config.max_file_bytes {
+                self.rotate().await?;
+                self.active = Some(self.open_active().await?);
+            }
+
+            let (file, size) = self.active.as_mut().expect("Must be some.");
+            file.write_all(&line).await?;
+            file.flush().await?;
+            *size += line.len() as u64;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use claims::*;
+
+    use super::*;
+    use crate::audit::emitter::tests::new_event;
+
+    /// Read targets of the events logged in the file at given
+    /// path.
+    fn logged_targets(path: &Path) -> Vec<String> {
+        let content = assert_ok!(std::fs::read_to_string(path));
+        content
+            .lines()
+            .map(|line| {
+                let event: serde_json::Value = assert_ok!(serde_json::from_str(line));
+                assert_some!(event["target"].as_str()).to_owned()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn events_are_appended_as_json_lines() {
+        let dir = assert_ok!(tempfile::tempdir());
+        let path = dir.path().join("logs/audit.jsonl");
+
+        let mut sink =
+            RotatingJsonLinesFileSink::new(RotatingJsonLinesFileSinkConfig::new(path.clone()));
+        for target in ["a", "b"] {
+            assert_ok!(sink.write(&new_event(target)).await);
+        }
+
+        // A new sink appends to the existing file.
+        let mut sink =
+            RotatingJsonLinesFileSink::new(RotatingJsonLinesFileSinkConfig::new(path.clone()));
+        assert_ok!(sink.write(&new_event("c")).await);
+
+        assert_eq!(logged_targets(&path), ["a", "b", "c"]);
+        assert!(!sink.rotated_path(1).exists());
+    }
+
+    #[tokio::test]
+    async fn files_are_rotated_and_oldest_are_dropped() {
+        let dir = assert_ok!(tempfile::tempdir());
+        let path = dir.path().join("audit.jsonl");
+
+        let line_len = assert_ok!(serde_json::to_vec(&new_event("a"))).len() as u64 + 1;
+        let mut sink = RotatingJsonLinesFileSink::new(RotatingJsonLinesFileSinkConfig {
+            path: path.clone(),
+            // Two lines per file.
+            max_file_bytes: 2 * line_len,
+            max_rotated_files: 2,
+        });
+        for target in ["a", "b", "c", "d", "e", "f", "g"] {
+            assert_ok!(sink.write(&new_event(target)).await);
+        }
+
+        assert_eq!(logged_targets(&path), ["g"]);
+        assert_eq!(logged_targets(&sink.rotated_path(1)), ["e", "f"]);
+        assert_eq!(logged_targets(&sink.rotated_path(2)), ["c", "d"]);
+        assert!(!sink.rotated_path(3).exists());
+    }
+
+    #[tokio::test]
+    async fn file_is_truncated_without_rotated_files() {
+        let dir = assert_ok!(tempfile::tempdir());
+        let path = dir.path().join("audit.jsonl");
+
+        let mut sink = RotatingJsonLinesFileSink::new(RotatingJsonLinesFileSinkConfig {
+            path: path.clone(),
+            max_file_bytes: 1,
+            max_rotated_files: 0,
+        });
+        for target in ["a", "b", "c"] {
+            assert_ok!(sink.write(&new_event(target)).await);
+        }
+
+        assert_eq!(logged_targets(&path), ["c"]);
+        assert!(!sink.rotated_path(1).exists());
+    }
+}
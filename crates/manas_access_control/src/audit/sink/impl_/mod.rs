@@ -0,0 +1,5 @@
+//! I provide few implementations of [`AuditSink`](super::AuditSink).
+//!
+
+pub mod json_lines_file;
+pub mod tracing;
@@ -0,0 +1,28 @@
+//! I define an implementation of [`AuditSink`] that logs
+//! audit events as tracing events.
+//!
+
+use std::io;
+
+use futures::future::BoxFuture;
+
+use crate::audit::{sink::AuditSink, AuditEvent};
+
+/// Tracing target of the audit events.
+pub const AUDIT_TRACING_TARGET: &str = "manas::audit";
+
+/// An implementation of [`AuditSink`] that logs audit events
+/// as json serialized tracing events, with target
+/// [`AUDIT_TRACING_TARGET`].
+#[derive(Debug, Clone, Default)]
+pub struct TracingAuditSink;
+
+impl AuditSink for TracingAuditSink {
+    fn write<'s>(&'s mut self, event: &'s AuditEvent) -> BoxFuture<'s, io::Result<()>> {
+        Box::pin(async move {
+            let event_json = serde_json::to_string(event)?;
+            tracing::info!(target: AUDIT_TRACING_TARGET, "{}", event_json);
+            Ok(())
+        })
+    }
+}
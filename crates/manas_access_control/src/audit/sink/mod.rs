@@ -0,0 +1,17 @@
+//! I define interfaces and implementations for sinks of
+//! audit events.
+//!
+
+use std::{fmt::Debug, io};
+
+use futures::future::BoxFuture;
+
+use super::AuditEvent;
+
+pub mod impl_;
+
+/// A trait for sinks of audit events.
+pub trait AuditSink: Debug + Send + 'static {
+    /// Write given audit event to the sink.
+    fn write<'s>(&'s mut self, event: &'s AuditEvent) -> BoxFuture<'s, io::Result<()>>;
+}
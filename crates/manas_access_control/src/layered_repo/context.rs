@@ -3,6 +3,8 @@
 
 use std::{fmt::Debug, sync::Arc};
 
+use dyn_problem::ProbFuture;
+use futures::TryFutureExt;
 use manas_repo::{
     context::{LayeredRepoContext, RepoContext},
     Repo,
@@ -10,7 +12,12 @@ use manas_repo::{
 use manas_space::resource::uri::SolidResourceUri;
 
 use super::AccessControlledRepo;
-use crate::model::pep::PolicyEnforcementPoint;
+#[cfg(feature = "audit")]
+use crate::audit::{AuditEmitter, AuditEvent};
+use crate::model::{
+    pep::{PolicyEnforcementPoint, ResolvedAccessControlResponse},
+    ActionOpList,
+};
 
 /// Type of initial root acr factory.
 pub type InitialRootAcrRepFactory<IR> =
@@ -28,6 +35,10 @@ pub struct AccessControlledRepoContext<IR: Repo, PEP> {
 
     /// Initial storage root acr in ttl.
     pub initial_root_acr_rep_factory: Arc<InitialRootAcrRepFactory<IR>>,
+
+    /// Optional emitter of access decision audit events.
+    #[cfg(feature = "audit")]
+    pub audit_emitter: Option<Arc<AuditEmitter>>,
 }
 
 impl<IR: Repo, PEP: Debug> Debug for AccessControlledRepoContext<IR, PEP> {
@@ -65,8 +76,43 @@ impl<IR: Repo, PEP> AccessControlledRepoContext<IR, PEP> {
             inner,
             pep,
             initial_root_acr_rep_factory,
+            #[cfg(feature = "audit")]
+            audit_emitter: None,
         }
     }
+
+    /// Set the emitter of access decision audit events.
+    #[cfg(feature = "audit")]
+    #[inline]
+    pub fn with_audit_emitter(mut self, audit_emitter: Arc<AuditEmitter>) -> Self {
+        self.audit_emitter = Some(audit_emitter);
+        self
+    }
+}
+
+impl<IR: Repo, PEP: PolicyEnforcementPoint> AccessControlledRepoContext<IR, PEP> {
+    /// Resolve access control for the given op list with
+    /// given credentials through the pep. Resolved decision
+    /// will be audited, if an audit emitter is configured.
+    pub fn resolve_access_control(
+        &self,
+        action_op_list: ActionOpList,
+        credentials: PEP::Credentials,
+    ) -> ProbFuture<'static, ResolvedAccessControlResponse<PEP::Credentials>> {
+        #[cfg(feature = "audit")]
+        let audit_emitter = self.audit_emitter.clone();
+
+        Box::pin(
+            self.pep
+                .resolve_access_control(action_op_list, credentials)
+                .inspect_ok(move |_response| {
+                    #[cfg(feature = "audit")]
+                    if let Some(audit_emitter) = audit_emitter {
+                        audit_emitter.emit(AuditEvent::new(_response));
+                    }
+                }),
+        )
+    }
 }
 
 impl<IR, PEP> LayeredRepoContext for AccessControlledRepoContext<IR, PEP>
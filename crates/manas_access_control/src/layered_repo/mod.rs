@@ -129,6 +129,10 @@ where
     /// Initial storage root acr in ttl.
     pub initial_root_acr_rep_factory: Arc<InitialRootAcrRepFactory<IR>>,
 
+    /// Optional emitter of access decision audit events.
+    #[cfg(feature = "audit")]
+    pub audit_emitter: Option<Arc<crate::audit::AuditEmitter>>,
+
     _phantom: PhantomData<fn(IR, PEP)>,
 }
 
@@ -161,9 +165,19 @@ where
         Self {
             pep,
             initial_root_acr_rep_factory,
+            #[cfg(feature = "audit")]
+            audit_emitter: None,
             _phantom: PhantomData,
         }
     }
+
+    /// Set the emitter of access decision audit events.
+    #[cfg(feature = "audit")]
+    #[inline]
+    pub fn with_audit_emitter(mut self, audit_emitter: Arc<crate::audit::AuditEmitter>) -> Self {
+        self.audit_emitter = Some(audit_emitter);
+        self
+    }
 }
 
 impl<IR, PEP> RepoLayer<IR> for AccessControlledRepoLayer<IR, PEP>
@@ -183,6 +197,8 @@ where
             inner: inner_context,
             pep: self.pep.clone(),
             initial_root_acr_rep_factory: self.initial_root_acr_rep_factory.clone(),
+            #[cfg(feature = "audit")]
+            audit_emitter: self.audit_emitter.clone(),
         }
     }
 }
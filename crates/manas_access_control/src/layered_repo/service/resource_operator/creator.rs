@@ -17,6 +17,7 @@ use tower::{Service, ServiceExt};
 use tracing::{debug, error};
 use typed_record::TypedRecord;
 
+use super::acl_subject_res_uri;
 use crate::{
     layered_repo::AccessControlledRepo,
     model::{
@@ -122,7 +123,6 @@ where
 
             let resolved_host_access_control: ResolvedAccessControl<_> = layer_context
                 .as_ref()
-                .pep
                 .resolve_access_control(
                     ActionOpList {
                         on: host_res_uri,
@@ -166,6 +166,23 @@ where
                     e
                 })?;
 
+            // Invalidate anything cached for the created
+            // resource, like a creator resolved before it existed.
+            layer_context
+                .as_ref()
+                .pep
+                .invalidate_cached_policies(&resp.created_resource_slot.id().uri)
+                .await;
+
+            // Invalidate cached policies of the acl's subject.
+            if let Some(acl_subject_uri) = acl_subject_res_uri(&resp.created_resource_slot) {
+                layer_context
+                    .as_ref()
+                    .pep
+                    .invalidate_cached_policies(&acl_subject_uri)
+                    .await;
+            }
+
             // Attach resolved access token to extensions.
             resp.extensions
                 .insert_rec_item::<KResolvedHostAccessControl<_>>(resolved_host_access_control);
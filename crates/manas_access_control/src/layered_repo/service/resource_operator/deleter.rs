@@ -8,7 +8,7 @@ use futures::TryFutureExt;
 use manas_repo::{
     service::resource_operator::{
         common::{problem::ACCESS_DENIED, status_token::ExistingRepresentedResourceToken},
-        deleter::{ResourceDeleteRequest, ResourceDeleteResponse, ResourceDeleter},
+        deleter::{KDryRunDelete, ResourceDeleteRequest, ResourceDeleteResponse, ResourceDeleter},
     },
     Repo, RepoResourceDeleter,
 };
@@ -17,6 +17,7 @@ use tower::{Service, ServiceExt};
 use tracing::{debug, error};
 use typed_record::TypedRecord;
 
+use super::acl_subject_res_uri;
 use crate::{
     layered_repo::AccessControlledRepo,
     model::{
@@ -98,6 +99,8 @@ where
         let credentials = req.credentials.clone();
         let res_uri = req.tokens.res_token.slot().id().uri.clone();
         let opt_slot_rev_link = req.tokens.res_token.slot().slot_rev_link().cloned();
+        let opt_acl_subject_uri = acl_subject_res_uri(req.tokens.res_token.slot());
+        let is_dry_run = req.extensions.get_rv::<KDryRunDelete>().is_some();
 
         // Translate request for inner service.
         let inner_req = req.unlayer_tokens();
@@ -127,13 +130,12 @@ where
             }
 
             let action_op_list = ActionOpList {
-                on: res_uri,
+                on: res_uri.clone(),
                 ops: action_ops,
             };
 
             let resolved_access_control: ResolvedAccessControl<_> = layer_context
                 .as_ref()
-                .pep
                 .resolve_access_control(action_op_list, credentials.clone())
                 .map_err(|e| {
                     error!(
@@ -166,7 +168,6 @@ where
 
                     let resolved_host_access_control: ResolvedAccessControl<_> = layer_context
                         .as_ref()
-                        .pep
                         .resolve_access_control(
                             ActionOpList {
                                 on: slot_rev_link.target,
@@ -210,6 +211,25 @@ where
                 })?
                 .map_repo();
 
+            // Invalidate cached policies and content derivatives
+            // of the deleted resource, and cached policies of the
+            // acl's subject.
+            if !is_dry_run {
+                layer_context
+                    .as_ref()
+                    .pep
+                    .invalidate_cached_content_derivatives(&res_uri)
+                    .await;
+
+                for affected_uri in std::iter::once(res_uri).chain(opt_acl_subject_uri) {
+                    layer_context
+                        .as_ref()
+                        .pep
+                        .invalidate_cached_policies(&affected_uri)
+                        .await;
+                }
+            }
+
             // Attach resolved access token to extensions.
             resp.extensions
                 .insert_rec_item::<KResolvedAccessControl<_>>(resolved_access_control);
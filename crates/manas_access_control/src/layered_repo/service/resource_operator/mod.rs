@@ -2,7 +2,32 @@
 //! [`AccessControlledRepo`](crate::layered_repo::AccessControlledRepo).
 //!
 
+use manas_space::{
+    resource::{
+        slot::SolidResourceSlot,
+        slot_rel_type::{aux_rel_type::ACL_REL_TYPE, SlotRelationType},
+        uri::SolidResourceUri,
+    },
+    SolidStorageSpace,
+};
+
 pub mod creator;
 pub mod deleter;
 pub mod reader;
 pub mod updater;
+
+/// Resolve the uri of the subject resource, if given slot is
+/// that of an acl resource. Access control policies of the
+/// subject resource get modified with any modification to
+/// it's acl resource.
+pub(crate) fn acl_subject_res_uri<Space: SolidStorageSpace>(
+    slot: &SolidResourceSlot<Space>,
+) -> Option<SolidResourceUri> {
+    let slot_rev_link = slot.slot_rev_link()?;
+    match &slot_rev_link.rev_rel_type {
+        SlotRelationType::Auxiliary(aux_rel_type) if **aux_rel_type == *ACL_REL_TYPE => {
+            Some(slot_rev_link.target.clone())
+        }
+        _ => None,
+    }
+}
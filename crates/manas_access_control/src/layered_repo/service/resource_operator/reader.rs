@@ -118,7 +118,6 @@ where
 
             let resolved_access_control: ResolvedAccessControl<_> = layer_context
                 .as_ref()
-                .pep
                 .resolve_access_control(action_op_list, credentials)
                 .map_err(|e| {
                     error!(
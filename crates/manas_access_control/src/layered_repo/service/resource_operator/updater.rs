@@ -19,6 +19,7 @@ use tower::{Service, ServiceExt};
 use tracing::{debug, error};
 use typed_record::TypedRecord;
 
+use super::acl_subject_res_uri;
 use crate::{
     layered_repo::AccessControlledRepo,
     model::{
@@ -99,6 +100,7 @@ where
 
         let credentials = req.credentials.clone();
         let res_uri = req.tokens.res_token.slot().id().uri.clone();
+        let opt_acl_subject_uri = acl_subject_res_uri(req.tokens.res_token.slot());
 
         // Translate request for inner service.
         let inner_req = req.unlayer_tokens();
@@ -133,13 +135,12 @@ where
             }
 
             let action_op_list = ActionOpList {
-                on: res_uri,
+                on: res_uri.clone(),
                 ops: action_ops,
             };
 
             let resolved_access_control: ResolvedAccessControl<_> = layer_context
                 .as_ref()
-                .pep
                 .resolve_access_control(action_op_list, credentials)
                 .map_err(|e| {
                     error!(
@@ -174,6 +175,23 @@ where
                     e
                 })?;
 
+            // Invalidate cached derivatives of the updated
+            // content, like group documents.
+            layer_context
+                .as_ref()
+                .pep
+                .invalidate_cached_content_derivatives(&res_uri)
+                .await;
+
+            // Invalidate cached policies of the acl's subject.
+            if let Some(acl_subject_uri) = opt_acl_subject_uri {
+                layer_context
+                    .as_ref()
+                    .pep
+                    .invalidate_cached_policies(&acl_subject_uri)
+                    .await;
+            }
+
             // Attach resolved access token to extensions.
             resp.extensions
                 .insert_rec_item::<KResolvedAccessControl<_>>(resolved_access_control);
@@ -9,5 +9,8 @@
 
 pub mod model;
 
+#[cfg(feature = "audit")]
+pub mod audit;
+
 #[cfg(feature = "impl-layered-repo")]
 pub mod layered_repo;
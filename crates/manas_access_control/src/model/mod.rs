@@ -65,6 +65,10 @@ pub struct Authorization<C: RequestCredentials> {
     #[serde(serialize_with = "serialize_grants")]
     /// Granted access modes.
     pub grants: AccessGrantSet,
+
+    /// Policies, that are matched in resolving the grants.
+    #[serde(skip)]
+    pub matched_policies: Vec<ArcTerm>,
 }
 
 fn serialize_grants<S: Serializer>(grants: &AccessGrantSet, s: S) -> Result<S::Ok, S::Error> {
@@ -103,8 +107,12 @@ impl<C: RequestCredentials> Authorization<C> {
         let access_modes = self
             .grants
             .iter()
-            // TODO allow custom modes too.
-            .filter_map(|h| WAC_ALLOW_ACCESS_MODE_MAP.get(h).cloned())
+            .filter_map(|h| {
+                WAC_ALLOW_ACCESS_MODE_MAP
+                    .get(h)
+                    .cloned()
+                    .or_else(|| custom_wac_allow_access_mode(h))
+            })
             .collect();
 
         WacAllow {
@@ -116,6 +124,16 @@ impl<C: RequestCredentials> Authorization<C> {
     }
 }
 
+/// Resolve wac allow access mode for given custom access
+/// mode. Token is the lower cased local name of the mode iri.
+/// Custom modes whose token collides with a standard access
+/// mode are not advertised, as they are not equivalent to it.
+fn custom_wac_allow_access_mode(h_mode: &HAccessMode<ArcTerm>) -> Option<AccessMode> {
+    let mode_iri = h_mode.as_term().iri()?;
+    let local_name = mode_iri.as_str().rsplit(['#', '/']).next()?;
+    AccessMode::try_new_extension(&local_name.to_ascii_lowercase()).ok()
+}
+
 /// A type to represent resolved access control.
 #[derive(Debug, Clone, Serialize)]
 pub enum ResolvedAccessControl<C: RequestCredentials> {
@@ -174,3 +192,35 @@ pub struct KResolvedHostAccessControl<C: RequestCredentials> {
 impl<C: RequestCredentials> TypedRecordKey for KResolvedHostAccessControl<C> {
     type Value = ResolvedAccessControl<C>;
 }
+
+#[cfg(test)]
+mod tests {
+    use rdf_utils::model::handle::Handle;
+    use rstest::rstest;
+    use sophia_api::term::IriRef;
+
+    use super::*;
+
+    fn h_mode(iri: &str) -> HAccessMode<ArcTerm> {
+        HAccessMode::try_new(ArcTerm::Iri(IriRef::new_unchecked(iri.into())))
+            .expect("Must be valid.")
+    }
+
+    #[rstest]
+    #[case("http://example.org/ns#Share", Some("share"))]
+    #[case("http://example.org/ns/ReadNoList", Some("readnolist"))]
+    #[case("http://example.org/ns#Read", None)]
+    #[case("http://example.org/ns#write", None)]
+    #[case("http://example.org/ns#Append", None)]
+    #[case("http://example.org/ns#CONTROL", None)]
+    #[case("http://example.org/ns#Share_All", None)]
+    fn custom_wac_allow_access_mode_works_correctly(
+        #[case] mode_iri: &str,
+        #[case] expected_token: Option<&str>,
+    ) {
+        assert_eq!(
+            custom_wac_allow_access_mode(&h_mode(mode_iri)).map(|mode| mode.to_string()),
+            expected_token.map(String::from)
+        );
+    }
+}
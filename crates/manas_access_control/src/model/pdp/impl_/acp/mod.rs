@@ -5,7 +5,7 @@
 use std::{collections::HashSet, fmt::Debug, marker::PhantomData, ops::Deref, sync::Arc};
 
 use acp::{
-    engine::AcpEngine,
+    engine::{AcpEngine, JustifiedAccessGrant},
     model::{
         access_mode::{HAccessMode, H_APPEND, H_CONTROL, H_READ, H_WRITE},
         acr::DAccessControlResource,
@@ -35,6 +35,7 @@ use tracing::{error, info};
 use crate::model::{
     pdp::{AccessGrantResponse, PolicyDecisionPoint, ResourceAccessContext, INVALID_PRP_RESPONSE},
     prp::SlotAcrChain,
+    AccessGrantSet,
 };
 
 // fn sy<T: Send>(v: T) -> T {
@@ -115,6 +116,22 @@ where
             _phantom: PhantomData,
         }
     }
+
+    /// Declare given custom access modes as supported, in
+    /// addition to existing supported access modes.
+    ///
+    /// Custom access modes can be granted through policies,
+    /// and be required for operations through an
+    /// [`AccessModeResolver`](crate::model::pep::mode_resolver::AccessModeResolver).
+    pub fn with_custom_access_modes(
+        mut self,
+        custom_access_modes: impl IntoIterator<Item = HAccessMode<ArcTerm>>,
+    ) -> Self {
+        let mut supported_access_modes = self.supported_access_modes.as_ref().clone();
+        supported_access_modes.extend(custom_access_modes);
+        self.supported_access_modes = Arc::new(supported_access_modes);
+        self
+    }
 }
 
 impl<S, G> PolicyDecisionPoint for AcpDecisionPoint<S, G>
@@ -195,7 +212,10 @@ where
             return Err(INVALID_PRP_RESPONSE.new_problem());
         }
 
-        let access_grant_set = match &own_item.res_slot.slot_rev_link() {
+        let (access_grant_set, matched_policies, matched_matchers) = match &own_item
+            .res_slot
+            .slot_rev_link()
+        {
             // If resource has no slot rev link, implies it is
             // the storage root.
             None => {
@@ -205,12 +225,13 @@ where
                 );
 
                 engine
-                    .resolve_access_control(
+                    .resolve_justified_access_control(
                         own_item.acr,
                         // No ancestor acrs for storage root.
                         vec![],
                         context.into_inner().into_with_arced_graph(),
                     )
+                    .map_ok(Self::into_grants_and_matched)
                     .inspect_err(|_| {
                         error!("Error in resolving grants for nearest ipr");
                     })
@@ -227,11 +248,12 @@ where
                     info!("Resolved nearest ipr uri: {}", target_uri.as_str());
 
                     engine
-                        .resolve_access_control(
+                        .resolve_justified_access_control(
                             own_item.acr,
                             Self::resolve_inherited_acrs(acr_chain).await?,
                             context.into_inner().into_with_arced_graph(),
                         )
+                        .map_ok(Self::into_grants_and_matched)
                         .inspect_err(|_| {
                             error!("Error in resolving grants for nearest ipr");
                         })
@@ -245,12 +267,13 @@ where
                             info!("Aux resource with independent role is resolved to be nearest ipr. Uri: {}", target_uri.as_str());
 
                             engine
-                                .resolve_access_control(
+                                .resolve_justified_access_control(
                                     own_item.acr,
                                     // No ancestor acrs for aux resource with independent role.
                                     vec![],
                                     context.into_inner().into_with_arced_graph(),
                                 )
+                                .map_ok(Self::into_grants_and_matched)
                                 .inspect_err(|_| {
                                     error!("Error in resolving grants for nearest ipr");
                                 })
@@ -271,7 +294,7 @@ where
                             subject_access_context.set(&ns::acp::target, host_res_uri.deref());
 
                             // Then resolve grant for subject resource.
-                            let subject_access_grants = Self::resolve_grants_with(
+                            let subject_access_grant_response = Self::resolve_grants_with(
                                 ResourceAccessContext::new_unchecked(
                                     host_res_uri.clone(),
                                     subject_access_context,
@@ -283,12 +306,22 @@ where
                             .inspect_err(|_| {
                                 error!("Error in resolving subject resource access grants.");
                             })
-                            .await?
-                            .access_grant_set;
+                            .await?;
+
+                            let subject_access_grants =
+                                subject_access_grant_response.access_grant_set;
+                            let subject_matched_policies =
+                                subject_access_grant_response.matched_policies;
+                            let subject_matched_matchers =
+                                subject_access_grant_response.matched_matchers;
 
                             if role == AuxAccessResolutionRole::SubjectResource {
                                 // Resolve subject access grants as target's grants.
-                                Ok(subject_access_grants)
+                                Ok((
+                                    subject_access_grants,
+                                    subject_matched_policies,
+                                    subject_matched_matchers,
+                                ))
                             } else {
                                 // Resolve target's grants based on subject's `control` grant.
                                 if subject_access_grants
@@ -297,10 +330,18 @@ where
                                 {
                                     // If control grant available on subject,
                                     // then all grants are available on aux resource.
-                                    Ok(supported_access_modes.as_ref().clone())
+                                    Ok((
+                                        supported_access_modes.as_ref().clone(),
+                                        subject_matched_policies,
+                                        subject_matched_matchers,
+                                    ))
                                 } else {
                                     // Or else no grants.
-                                    Ok(HashSet::new())
+                                    Ok((
+                                        HashSet::new(),
+                                        subject_matched_policies,
+                                        subject_matched_matchers,
+                                    ))
                                 }
                             }
                         }
@@ -312,9 +353,31 @@ where
         Ok(AccessGrantResponse {
             res_slot: Some(own_item.res_slot),
             access_grant_set,
+            matched_policies,
+            matched_matchers,
         })
     }
 
+    /// Split given justified grant into grants, matched
+    /// policies and matched matchers.
+    fn into_grants_and_matched(
+        justified_grant: JustifiedAccessGrant,
+    ) -> (AccessGrantSet, Vec<ArcTerm>, Vec<ArcTerm>) {
+        (
+            justified_grant.grants,
+            justified_grant
+                .satisfied_policies
+                .into_iter()
+                .map(Handle::into_term)
+                .collect(),
+            justified_grant
+                .satisfied_matchers
+                .into_iter()
+                .map(Handle::into_term)
+                .collect(),
+        )
+    }
+
     async fn resolve_inherited_acrs(
         mut acr_chain: SlotAcrChain<S, G, Arc<G>>,
     ) -> ProbResult<Vec<Option<DAccessControlResource<G, Arc<G>>>>> {
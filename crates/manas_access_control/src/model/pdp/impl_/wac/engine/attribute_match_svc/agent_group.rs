@@ -1 +1,439 @@
+//! I define `acl:agentGroup` attribute match service as defined by wac specification.
+//!
 
+use std::{
+    borrow::Borrow,
+    collections::{HashSet, VecDeque},
+    fmt::Debug,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::Poll,
+};
+
+use acp::attribute_match_svc::AttributeMatchRequest;
+use dyn_problem::{ProbFuture, Problem};
+use http_uri::invariant::NormalAbsoluteHttpUri;
+use rdf_utils::model::{description::DescriptionExt, graph::InfallibleGraph, term::ArcTerm};
+use rdf_vocabularies::ns;
+use sophia_api::{
+    graph::Graph,
+    term::{matcher::Any, Term},
+    triple::Triple,
+};
+use tower::Service;
+use tracing::{debug, warn};
+use unwrap_infallible::UnwrapInfallible;
+
+use crate::model::pdp::impl_::wac::group_doc_resolver::AgentGroupDocResolver;
+
+/// An [`AttributeMatchService`](acp::attribute_match_svc::AttributeMatchService) that resolves match
+/// for `acl::agentGroup` attribute.
+///
+/// It dereferences the group document, and checks if any
+/// context agent is a `vcard:hasMember` of the group. Member
+/// groups are resolved transitively up to a configured
+/// depth, and each group is visited at most once.
+///
+/// Total number of groups visited is bounded by a configured
+/// budget. As each visited group costs one document
+/// resolution, it also bounds remote fetches. Budget is
+/// shared by all matches of a decision scoped service (see
+/// [`Self::for_decision`]), and is per match otherwise.
+///
+/// Groups whose documents cannot be resolved, or that
+/// cannot be visited within the budget, are treated as
+/// having no members.
+pub struct AgentGroupMatchService<T, G, WG> {
+    /// Group doc resolver.
+    doc_resolver: Arc<dyn AgentGroupDocResolver>,
+
+    /// Max depth of nested groups to resolve.
+    max_depth: usize,
+
+    /// Max number of groups to visit.
+    max_visited_groups: usize,
+
+    /// Remaining visits budget of the decision, if decision
+    /// scoped.
+    decision_budget: Option<Arc<AtomicUsize>>,
+
+    _phantom: PhantomData<fn(T, G, WG)>,
+}
+
+impl<T, G, WG> Debug for AgentGroupMatchService<T, G, WG> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AgentGroupMatchService")
+            .field("doc_resolver", &self.doc_resolver)
+            .field("max_depth", &self.max_depth)
+            .field("max_visited_groups", &self.max_visited_groups)
+            .finish()
+    }
+}
+
+impl<T, G, WG> Clone for AgentGroupMatchService<T, G, WG> {
+    fn clone(&self) -> Self {
+        Self {
+            doc_resolver: self.doc_resolver.clone(),
+            max_depth: self.max_depth,
+            max_visited_groups: self.max_visited_groups,
+            decision_budget: self.decision_budget.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, G, WG> AgentGroupMatchService<T, G, WG> {
+    /// Create a new [`AgentGroupMatchService`] with given
+    /// group doc resolver, max nested group depth, and max
+    /// number of groups to visit.
+    #[inline]
+    pub fn new(
+        doc_resolver: Arc<dyn AgentGroupDocResolver>,
+        max_depth: usize,
+        max_visited_groups: usize,
+    ) -> Self {
+        Self {
+            doc_resolver,
+            max_depth,
+            max_visited_groups,
+            decision_budget: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Get a copy of this service, whose visits budget is
+    /// shared by all matches resolved through it, and it's
+    /// clones. It should be used for all matches of a single
+    /// decision.
+    pub fn for_decision(&self) -> Self {
+        Self {
+            doc_resolver: self.doc_resolver.clone(),
+            max_depth: self.max_depth,
+            max_visited_groups: self.max_visited_groups,
+            decision_budget: Some(Arc::new(AtomicUsize::new(self.max_visited_groups))),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, G, WG> Service<AttributeMatchRequest<T, G, WG>> for AgentGroupMatchService<T, G, WG>
+where
+    T: Term,
+    G: InfallibleGraph,
+    WG: Borrow<G> + Debug,
+{
+    type Response = bool;
+
+    type Error = Problem;
+
+    type Future = ProbFuture<'static, bool>;
+
+    #[inline]
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[tracing::instrument(skip_all, name = "AgentGroupMatchService::call")]
+    fn call(&mut self, req: AttributeMatchRequest<T, G, WG>) -> Self::Future {
+        let AttributeMatchRequest {
+            value: m_group_id,
+            context,
+        } = req;
+
+        let m_group: ArcTerm = m_group_id.into_term();
+        let agents: HashSet<ArcTerm> = context
+            .get_all(&ns::acl::agent)
+            .map(|a| a.into_term())
+            .collect();
+
+        let doc_resolver = self.doc_resolver.clone();
+        let max_depth = self.max_depth;
+        let budget = self
+            .decision_budget
+            .clone()
+            .unwrap_or_else(|| Arc::new(AtomicUsize::new(self.max_visited_groups)));
+
+        Box::pin(async move {
+            // No agent, no membership.
+            if agents.is_empty() {
+                return Ok(false);
+            }
+            Ok(Self::resolve_membership(m_group, agents, doc_resolver, max_depth, &budget).await)
+        })
+    }
+}
+
+impl<T, G, WG> AgentGroupMatchService<T, G, WG> {
+    /// Resolve if any of given agents is a member of given
+    /// group, visiting groups within given budget.
+    async fn resolve_membership(
+        group: ArcTerm,
+        agents: HashSet<ArcTerm>,
+        doc_resolver: Arc<dyn AgentGroupDocResolver>,
+        max_depth: usize,
+        budget: &AtomicUsize,
+    ) -> bool {
+        let mut visited = HashSet::new();
+        let mut pending = VecDeque::from([(group, 0)]);
+
+        while let Some((group, depth)) = pending.pop_front() {
+            // Guard against cyclic group references.
+            if !visited.insert(group.clone()) {
+                continue;
+            }
+
+            let doc_uri = if let Some(uri) = group_doc_uri(&group) {
+                uri
+            } else {
+                warn!("Agent group id is not a valid http uri.");
+                continue;
+            };
+
+            // Consume a visit from the budget.
+            if budget
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+                .is_err()
+            {
+                warn!("Agent group visits budget is exhausted.");
+                return false;
+            }
+
+            let doc = match doc_resolver.resolve(doc_uri).await {
+                Ok(doc) => doc,
+                Err(e) => {
+                    warn!("Error in resolving agent group doc. Error:\n {}", e);
+                    continue;
+                }
+            };
+
+            let members: Vec<ArcTerm> = doc
+                .triples_matching([&group], [ns::vcard::hasMember], Any)
+                .map(|t| t.unwrap_infallible().o().clone())
+                .collect();
+
+            if members.iter().any(|m| agents.contains(m)) {
+                return true;
+            }
+
+            if depth >= max_depth {
+                debug!("Max nested group depth reached.");
+                continue;
+            }
+
+            // Resolve nested groups.
+            pending.extend(
+                members
+                    .into_iter()
+                    .filter(|m| {
+                        doc.triples_matching([m], [ns::rdf::type_], [ns::vcard::Group])
+                            .next()
+                            .is_some()
+                    })
+                    .map(|m| (m, depth + 1)),
+            );
+        }
+
+        false
+    }
+}
+
+/// Get the uri of the document describing given group.
+fn group_doc_uri(group: &ArcTerm) -> Option<NormalAbsoluteHttpUri> {
+    let group_iri = group.iri()?;
+    let doc_uri_str = group_iri
+        .as_str()
+        .split_once('#')
+        .map_or(group_iri.as_str(), |(doc_uri_str, _)| doc_uri_str);
+
+    NormalAbsoluteHttpUri::try_new_from(doc_uri_str).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Mutex};
+
+    use rdf_utils::model::triple::ArcTriple;
+    use rstest::*;
+    use sophia_api::term::IriRef;
+
+    use super::*;
+    use crate::model::pdp::impl_::wac::group_doc_resolver::{
+        AgentGroupDoc, UNRESOLVABLE_AGENT_GROUP_DOC,
+    };
+
+    const HAS_MEMBER: &str = "http://www.w3.org/2006/vcard/ns#hasMember";
+    const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+    const VCARD_GROUP: &str = "http://www.w3.org/2006/vcard/ns#Group";
+
+    const ALICE: &str = "http://alice.example.org/profile/card#me";
+
+    fn iri(s: &str) -> ArcTerm {
+        ArcTerm::Iri(IriRef::new_unchecked(s.into()))
+    }
+
+    fn group(n: usize) -> String {
+        format!("http://pod.example.org/groups/{}#group", n)
+    }
+
+    /// A mock resolver, that resolves documents from a
+    /// fixed set, and records resolved document uris.
+    #[derive(Debug, Default)]
+    struct MockResolver {
+        docs: HashMap<String, Arc<AgentGroupDoc>>,
+        resolutions: Mutex<Vec<String>>,
+    }
+
+    impl MockResolver {
+        /// Create a new mock resolver, with given
+        /// `(group, members, member groups)` entries.
+        fn new(entries: &[(String, Vec<String>, Vec<String>)]) -> Arc<Self> {
+            let mut docs = HashMap::<String, AgentGroupDoc>::new();
+
+            for (group, members, member_groups) in entries {
+                let doc_uri = group.split_once('#').expect("Must have fragment.").0;
+                let doc = docs.entry(doc_uri.to_owned()).or_default();
+
+                doc.extend(
+                    members
+                        .iter()
+                        .chain(member_groups)
+                        .map(|m| -> ArcTriple { [iri(group), iri(HAS_MEMBER), iri(m)] }),
+                );
+                doc.extend(
+                    member_groups
+                        .iter()
+                        .map(|g| -> ArcTriple { [iri(g), iri(RDF_TYPE), iri(VCARD_GROUP)] }),
+                );
+            }
+
+            Arc::new(Self {
+                docs: docs.into_iter().map(|(k, v)| (k, Arc::new(v))).collect(),
+                resolutions: Default::default(),
+            })
+        }
+
+        fn resolutions(&self) -> Vec<String> {
+            self.resolutions
+                .lock()
+                .expect("Must not be poisoned.")
+                .clone()
+        }
+    }
+
+    impl AgentGroupDocResolver for MockResolver {
+        fn resolve(
+            &self,
+            doc_uri: NormalAbsoluteHttpUri,
+        ) -> ProbFuture<'static, Arc<AgentGroupDoc>> {
+            self.resolutions
+                .lock()
+                .expect("Must not be poisoned.")
+                .push(doc_uri.as_str().to_owned());
+
+            let doc = self.docs.get(doc_uri.as_str()).cloned();
+            Box::pin(async move { doc.ok_or_else(|| UNRESOLVABLE_AGENT_GROUP_DOC.new_problem()) })
+        }
+    }
+
+    async fn is_member_within(
+        resolver: Arc<MockResolver>,
+        group: String,
+        max_depth: usize,
+        budget: &AtomicUsize,
+    ) -> bool {
+        AgentGroupMatchService::<ArcTerm, AgentGroupDoc, Arc<AgentGroupDoc>>::resolve_membership(
+            iri(&group),
+            HashSet::from([iri(ALICE)]),
+            resolver,
+            max_depth,
+            budget,
+        )
+        .await
+    }
+
+    async fn is_member(resolver: Arc<MockResolver>, group: String, max_depth: usize) -> bool {
+        is_member_within(resolver, group, max_depth, &AtomicUsize::new(usize::MAX)).await
+    }
+
+    #[rstest]
+    #[case::direct_member(group(2), 0, true)]
+    #[case::nested_member_within_depth(group(0), 2, true)]
+    #[case::nested_member_beyond_depth(group(0), 1, false)]
+    #[tokio::test]
+    async fn nested_groups_are_resolved_up_to_max_depth(
+        #[case] group_id: String,
+        #[case] max_depth: usize,
+        #[case] expected: bool,
+    ) {
+        let resolver = MockResolver::new(&[
+            (group(0), vec![], vec![group(1)]),
+            (group(1), vec![], vec![group(2)]),
+            (group(2), vec![ALICE.to_owned()], vec![]),
+        ]);
+
+        assert_eq!(is_member(resolver, group_id, max_depth).await, expected);
+    }
+
+    #[tokio::test]
+    async fn untyped_members_are_not_resolved_as_groups() {
+        let resolver = MockResolver::new(&[
+            (group(0), vec![group(1)], vec![]),
+            (group(1), vec![ALICE.to_owned()], vec![]),
+        ]);
+
+        assert!(!is_member(resolver.clone(), group(0), 4).await);
+        assert_eq!(resolver.resolutions().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn cyclic_groups_are_resolved_once() {
+        let resolver = MockResolver::new(&[
+            (group(0), vec![], vec![group(1)]),
+            (group(1), vec![], vec![group(0)]),
+        ]);
+
+        assert!(!is_member(resolver.clone(), group(0), 16).await);
+
+        let mut resolutions = resolver.resolutions();
+        resolutions.sort();
+        assert_eq!(
+            resolutions,
+            vec![
+                "http://pod.example.org/groups/0",
+                "http://pod.example.org/groups/1"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn unresolvable_groups_have_no_members() {
+        let resolver = MockResolver::new(&[
+            (group(0), vec![], vec![group(1), group(2)]),
+            (group(2), vec![ALICE.to_owned()], vec![]),
+        ]);
+
+        assert!(is_member(resolver, group(0), 1).await);
+    }
+
+    #[tokio::test]
+    async fn visited_groups_are_bounded_by_budget() {
+        let resolver = MockResolver::new(&[
+            (group(0), vec![], vec![group(1)]),
+            (group(1), vec![], vec![group(2)]),
+            (group(2), vec![ALICE.to_owned()], vec![]),
+        ]);
+
+        let budget = AtomicUsize::new(2);
+        assert!(!is_member_within(resolver.clone(), group(0), 4, &budget).await);
+        assert_eq!(resolver.resolutions().len(), 2);
+        assert_eq!(budget.load(Ordering::Relaxed), 0);
+
+        // Exhausted budget is not replenished for further
+        // matches.
+        assert!(!is_member_within(resolver.clone(), group(2), 4, &budget).await);
+        assert_eq!(resolver.resolutions().len(), 2);
+    }
+}
@@ -8,5 +8,5 @@ mod origin;
 
 pub use agent::*;
 pub use agent_class::*;
-// pub use agent_group::*;
+pub use agent_group::*;
 pub use origin::*;
@@ -6,6 +6,7 @@ use std::{
     collections::{HashMap, HashSet},
     fmt::Debug,
     sync::Arc,
+    time::Duration,
 };
 
 use acp::{
@@ -17,7 +18,7 @@ use acp::{
     },
 };
 use dyn_problem::{ProbResult, Problem};
-use futures::{stream::FuturesUnordered, TryFutureExt};
+use futures::{future::Either, stream::FuturesUnordered, TryFutureExt};
 use http_uri::invariant::NormalAbsoluteHttpUri;
 use rdf_utils::{
     define_handle_and_description_types,
@@ -37,11 +38,40 @@ use tower::ServiceExt;
 use tracing::{debug, error, info};
 use unwrap_infallible::UnwrapInfallible;
 
-use self::attribute_match_svc::{AgentClassMatchService, AgentMatchService, OriginMatchService};
+use self::attribute_match_svc::{
+    AgentClassMatchService, AgentGroupMatchService, AgentMatchService, OriginMatchService,
+};
+use super::group_doc_resolver::{
+    impl_::{CachingAgentGroupDocResolver, RoutingAgentGroupDocResolver},
+    AgentGroupDocResolver,
+};
 use crate::model::AccessGrantSet;
 
 pub mod attribute_match_svc;
 
+/// A struct to represent an access grant, along with the
+/// matched authorizations justifying it.
+#[derive(Debug, Clone, Default)]
+pub struct JustifiedAccessGrant {
+    /// Granted access modes.
+    pub grants: AccessGrantSet,
+
+    /// Matched applicable authorizations.
+    pub matched_authorizations: Vec<HAuthorization<ArcTerm>>,
+}
+
+/// Default max depth of nested agent groups.
+pub const DEFAULT_AGENT_GROUP_MAX_DEPTH: usize = 4;
+
+/// Default max number of agent groups visited in a decision.
+pub const DEFAULT_AGENT_GROUP_MAX_VISITED_GROUPS: usize = 32;
+
+/// Default max capacity of agent group doc cache.
+pub const DEFAULT_AGENT_GROUP_DOC_CACHE_MAX_CAPACITY: u64 = 1000;
+
+/// Default time to live of cached agent group docs.
+pub const DEFAULT_AGENT_GROUP_DOC_CACHE_TTL: Duration = Duration::from_secs(60);
+
 /// A struct to represent resolved acl context.
 #[derive(Debug, Clone)]
 pub struct ResolvedAclContext<G, WG>
@@ -68,6 +98,10 @@ where
 {
     /// Attribute match services.
     subject_attribute_match_svcs: Arc<HashMap<ArcTerm, BoxedAttributeMatchService<ArcTerm, G, WG>>>,
+
+    /// `acl:agentGroup` attribute match service, that is
+    /// scoped to each decision.
+    agent_group_match_svc: Option<AgentGroupMatchService<ArcTerm, G, WG>>,
 }
 
 impl<G, WG> Clone for WacEngine<G, WG>
@@ -79,6 +113,7 @@ where
     fn clone(&self) -> Self {
         Self {
             subject_attribute_match_svcs: self.subject_attribute_match_svcs.clone(),
+            agent_group_match_svc: self.agent_group_match_svc.clone(),
         }
     }
 }
@@ -100,21 +135,48 @@ where
 {
     #[inline]
     fn default() -> Self {
-        Self::new(Arc::new(
-            [
-                (
-                    ns::acl::agent,
-                    Box::new(AgentMatchService) as BoxedAttributeMatchService<ArcTerm, G, Arc<G>>,
-                ),
-                (ns::acl::agentClass, Box::new(AgentClassMatchService)),
-                (ns::acl::origin, Box::new(OriginMatchService)),
-                // TODO
-                // (ns::acl::agentGroup, Box::new(AgentGroupMatchService)),
-            ]
-            .into_iter()
-            .map(|(a, s)| (a.into_term(), s))
-            .collect(),
-        ))
+        Self::new_with_agent_group_doc_resolver(Arc::new(CachingAgentGroupDocResolver::new(
+            Arc::new(RoutingAgentGroupDocResolver::default()),
+            DEFAULT_AGENT_GROUP_DOC_CACHE_MAX_CAPACITY,
+            DEFAULT_AGENT_GROUP_DOC_CACHE_TTL,
+        )))
+    }
+}
+
+impl<G> WacEngine<G, Arc<G>>
+where
+    G: InfallibleGraph + Clone + Send + Sync + 'static,
+{
+    /// Get a new [`WacEngine`] with default subject attribute
+    /// match services, that resolves agent group documents
+    /// with given resolver.
+    ///
+    /// Agent groups visited in resolving a decision are
+    /// bounded by [`DEFAULT_AGENT_GROUP_MAX_VISITED_GROUPS`].
+    pub fn new_with_agent_group_doc_resolver(
+        agent_group_doc_resolver: Arc<dyn AgentGroupDocResolver>,
+    ) -> Self {
+        Self {
+            subject_attribute_match_svcs: Arc::new(
+                [
+                    (
+                        ns::acl::agent,
+                        Box::new(AgentMatchService)
+                            as BoxedAttributeMatchService<ArcTerm, G, Arc<G>>,
+                    ),
+                    (ns::acl::agentClass, Box::new(AgentClassMatchService)),
+                    (ns::acl::origin, Box::new(OriginMatchService)),
+                ]
+                .into_iter()
+                .map(|(a, s)| (a.into_term(), s))
+                .collect(),
+            ),
+            agent_group_match_svc: Some(AgentGroupMatchService::new(
+                agent_group_doc_resolver,
+                DEFAULT_AGENT_GROUP_MAX_DEPTH,
+                DEFAULT_AGENT_GROUP_MAX_VISITED_GROUPS,
+            )),
+        }
     }
 }
 
@@ -132,13 +194,18 @@ where
     ) -> Self {
         Self {
             subject_attribute_match_svcs,
+            agent_group_match_svc: None,
         }
     }
 
     /// Get the list of supported subject attributes.
     #[inline]
     pub fn supported_attrs(&self) -> impl Iterator<Item = ArcTerm> + '_ {
-        self.subject_attribute_match_svcs.keys().cloned()
+        self.subject_attribute_match_svcs.keys().cloned().chain(
+            self.agent_group_match_svc
+                .as_ref()
+                .map(|_| ns::acl::agentGroup.into_term()),
+        )
     }
 
     /// Resolve [access control](https://solid.github.io/web-access-control-spec/#authorization-evaluation)
@@ -151,6 +218,20 @@ where
         resolved_acl_context: Option<ResolvedAclContext<G, WG>>,
         access_context: DContext<G, WG>,
     ) -> Result<AccessGrantSet, Problem> {
+        Ok(self
+            .resolve_justified_access_control(resolved_acl_context, access_context)
+            .await?
+            .grants)
+    }
+
+    /// Resolve access control for given access context with
+    /// given acls, along with matched authorizations
+    /// justifying the grants.
+    pub async fn resolve_justified_access_control(
+        &self,
+        resolved_acl_context: Option<ResolvedAclContext<G, WG>>,
+        access_context: DContext<G, WG>,
+    ) -> Result<JustifiedAccessGrant, Problem> {
         // If no resolved acl context, then return empty set.
         let resolved_acl_context = if let Some(context) = resolved_acl_context {
             context
@@ -161,12 +242,12 @@ where
         // Gather applicable authorizations.
         let authorizations = self.gather_applicable_authorizations(resolved_acl_context);
 
-        let allowed_access_modes = self
+        let justified_grant = self
             .grant_access_modes(authorizations, access_context)
             .inspect_err(|e| error!("Error in resolving granted access modes. Error:\n {}", e))
             .await?;
 
-        Ok(allowed_access_modes)
+        Ok(justified_grant)
     }
 
     /// Gather [applicable authorizations](https://solid.github.io/web-access-control-spec/#authorization-conformance).
@@ -220,20 +301,36 @@ where
         &self,
         authorizations: Vec<DAuthorization<G, WG>>,
         access_context: DContext<G, WG>,
-    ) -> ProbResult<AccessGrantSet> {
+    ) -> ProbResult<JustifiedAccessGrant> {
         let mut allowed_access_modes = HashSet::new();
+        let mut matched_authorizations = Vec::new();
+
+        // Agent group visits budget is shared by all
+        // authorizations of the decision.
+        let agent_group_match_svc = self
+            .agent_group_match_svc
+            .as_ref()
+            .map(AgentGroupMatchService::for_decision);
 
         // Gather allowed access modes from satisfied authorizations
         for authorization in authorizations.into_iter() {
             if self
-                .is_matched_authorization(authorization.clone(), access_context.clone())
+                .is_matched_authorization(
+                    authorization.clone(),
+                    access_context.clone(),
+                    agent_group_match_svc.as_ref(),
+                )
                 .await?
             {
                 allowed_access_modes.extend(authorization.h_mode());
+                matched_authorizations.push(authorization.handle().clone());
             }
         }
 
-        Ok(allowed_access_modes)
+        Ok(JustifiedAccessGrant {
+            grants: allowed_access_modes,
+            matched_authorizations,
+        })
     }
 
     /// Resolves if an authorization is matched against access
@@ -248,6 +345,7 @@ where
         &self,
         authorization: DAuthorization<G, WG>,
         access_context: DContext<G, WG>,
+        agent_group_match_svc: Option<&AgentGroupMatchService<ArcTerm, G, WG>>,
     ) -> ProbResult<bool> {
         let attribute_match_futs = self
             .subject_attribute_match_svcs
@@ -261,14 +359,28 @@ where
                         .get_all(attribute)
                         .into_term_owning::<ArcTerm>()
                         .map(|value| {
-                            svc.clone().oneshot(AttributeMatchRequest {
+                            Either::Left(svc.clone().oneshot(AttributeMatchRequest {
                                 value,
                                 context: access_context.clone(),
-                            })
+                            }))
                         })
                         .collect::<FuturesUnordered<_>>(),
                 )
             })
+            .chain(agent_group_match_svc.map(|svc| {
+                let attribute: ArcTerm = ns::acl::agentGroup.into_term();
+                let futs = authorization
+                    .get_all(&attribute)
+                    .into_term_owning::<ArcTerm>()
+                    .map(|value| {
+                        Either::Right(svc.clone().oneshot(AttributeMatchRequest {
+                            value,
+                            context: access_context.clone(),
+                        }))
+                    })
+                    .collect::<FuturesUnordered<_>>();
+                (attribute, futs)
+            }))
             .filter(|(_, futs)| !futs.is_empty())
             .collect::<HashMap<_, _>>();
 
@@ -0,0 +1,182 @@
+//! I define an implementation of [`AgentGroupDocResolver`]
+//! that caches resolved group documents.
+//!
+
+use std::{sync::Arc, time::Duration};
+
+use dyn_problem::ProbFuture;
+use http_uri::invariant::NormalAbsoluteHttpUri;
+use moka::future::{Cache, CacheBuilder};
+
+use crate::model::pdp::impl_::wac::group_doc_resolver::{AgentGroupDoc, AgentGroupDocResolver};
+
+/// An implementation of [`AgentGroupDocResolver`] that
+/// caches group documents resolved by an inner resolver, for
+/// a configured time to live.
+///
+/// Resolution errors are not cached. Cached documents must be
+/// invalidated with [`invalidate`](Self::invalidate), when
+/// they are modified or deleted.
+#[derive(Debug, Clone)]
+pub struct CachingAgentGroupDocResolver {
+    /// Inner resolver.
+    inner: Arc<dyn AgentGroupDocResolver>,
+
+    /// Cache.
+    cache: Cache<NormalAbsoluteHttpUri, Arc<AgentGroupDoc>>,
+}
+
+impl CachingAgentGroupDocResolver {
+    /// Create a new [`CachingAgentGroupDocResolver`] with
+    /// given inner resolver, cache capacity and time to live.
+    pub fn new(
+        inner: Arc<dyn AgentGroupDocResolver>,
+        cache_max_capacity: u64,
+        cache_time_to_live: Duration,
+    ) -> Self {
+        Self {
+            inner,
+            cache: CacheBuilder::new(cache_max_capacity)
+                .time_to_live(cache_time_to_live)
+                .support_invalidation_closures()
+                .build(),
+        }
+    }
+
+    /// Invalidate cached document with given uri, if any. If
+    /// uri is of a container, cached documents in it's
+    /// namespace are invalidated too, as they are deleted
+    /// along with it.
+    pub async fn invalidate(&self, doc_uri: &NormalAbsoluteHttpUri) {
+        self.cache.invalidate(doc_uri).await;
+
+        if doc_uri.as_str().ends_with('/') {
+            let prefix = doc_uri.as_str().to_owned();
+            self.cache
+                .invalidate_entries_if(move |uri, _| uri.as_str().starts_with(&prefix))
+                .expect("Must be supported, as enabled while building.");
+        }
+    }
+}
+
+impl AgentGroupDocResolver for CachingAgentGroupDocResolver {
+    #[tracing::instrument(
+        skip_all,
+        name = "CachingAgentGroupDocResolver::resolve",
+        fields(doc_uri)
+    )]
+    fn resolve(&self, doc_uri: NormalAbsoluteHttpUri) -> ProbFuture<'static, Arc<AgentGroupDoc>> {
+        let inner = self.inner.clone();
+        let cache = self.cache.clone();
+
+        Box::pin(async move {
+            if let Some(doc) = cache.get(&doc_uri).await {
+                return Ok(doc);
+            }
+
+            let doc = inner.resolve(doc_uri.clone()).await?;
+            cache.insert(doc_uri, doc.clone()).await;
+            Ok(doc)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use claims::*;
+
+    use super::*;
+    use crate::model::pdp::impl_::wac::group_doc_resolver::UNRESOLVABLE_AGENT_GROUP_DOC;
+
+    /// A mock resolver, that records resolved document uris,
+    /// and fails for documents with `missing` in their uri.
+    #[derive(Debug, Default)]
+    struct MockResolver {
+        resolutions: Mutex<Vec<String>>,
+    }
+
+    impl MockResolver {
+        fn resolution_count(&self) -> usize {
+            self.resolutions
+                .lock()
+                .expect("Must not be poisoned.")
+                .len()
+        }
+    }
+
+    impl AgentGroupDocResolver for MockResolver {
+        fn resolve(
+            &self,
+            doc_uri: NormalAbsoluteHttpUri,
+        ) -> ProbFuture<'static, Arc<AgentGroupDoc>> {
+            self.resolutions
+                .lock()
+                .expect("Must not be poisoned.")
+                .push(doc_uri.as_str().to_owned());
+
+            let missing = doc_uri.as_str().contains("missing");
+            Box::pin(async move {
+                if missing {
+                    Err(UNRESOLVABLE_AGENT_GROUP_DOC.new_problem())
+                } else {
+                    Ok(Default::default())
+                }
+            })
+        }
+    }
+
+    fn uri(s: &str) -> NormalAbsoluteHttpUri {
+        NormalAbsoluteHttpUri::try_new_from(s).expect("Must be valid.")
+    }
+
+    fn caching_resolver() -> (Arc<MockResolver>, CachingAgentGroupDocResolver) {
+        let inner = Arc::new(MockResolver::default());
+        let resolver =
+            CachingAgentGroupDocResolver::new(inner.clone(), 16, Duration::from_secs(60));
+        (inner, resolver)
+    }
+
+    #[tokio::test]
+    async fn resolved_docs_are_cached_until_invalidated() {
+        let (inner, resolver) = caching_resolver();
+        let doc_uri = uri("http://pod.example.org/groups/a");
+
+        assert_ok!(resolver.resolve(doc_uri.clone()).await);
+        assert_ok!(resolver.resolve(doc_uri.clone()).await);
+        assert_eq!(inner.resolution_count(), 1);
+
+        resolver.invalidate(&doc_uri).await;
+        assert_ok!(resolver.resolve(doc_uri).await);
+        assert_eq!(inner.resolution_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn container_invalidation_invalidates_contained_docs() {
+        let (inner, resolver) = caching_resolver();
+        let contained_uri = uri("http://pod.example.org/groups/a");
+        let other_uri = uri("http://pod.example.org/other/b");
+
+        assert_ok!(resolver.resolve(contained_uri.clone()).await);
+        assert_ok!(resolver.resolve(other_uri.clone()).await);
+
+        resolver
+            .invalidate(&uri("http://pod.example.org/groups/"))
+            .await;
+
+        assert_ok!(resolver.resolve(contained_uri).await);
+        assert_ok!(resolver.resolve(other_uri).await);
+        assert_eq!(inner.resolution_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn resolution_errors_are_not_cached() {
+        let (inner, resolver) = caching_resolver();
+        let doc_uri = uri("http://pod.example.org/groups/missing");
+
+        assert_err!(resolver.resolve(doc_uri.clone()).await);
+        assert_err!(resolver.resolve(doc_uri).await);
+        assert_eq!(inner.resolution_count(), 2);
+    }
+}
@@ -0,0 +1,206 @@
+//! I define an implementation of [`AgentGroupDocResolver`]
+//! that dereferences group documents over http.
+//!
+
+use std::{convert::Infallible, sync::Arc, time::Duration};
+
+use bytes::BytesMut;
+use dyn_problem::{type_::UNKNOWN_IO_ERROR, ProbFuture};
+use futures::{StreamExt, TryStreamExt};
+use http_uri::invariant::NormalAbsoluteHttpUri;
+use manas_http::public_addr::{has_non_public_ip_host, PublicAddrResolver};
+use rdf_dynsyn::{
+    parser::triples::DynSynTripleParserFactory, syntax::invariant::triples_parsable::TP_TURTLE,
+};
+use rdf_utils::model::term::ArcTerm;
+use reqwest::{
+    header::{ACCEPT, CONTENT_TYPE},
+    redirect::Policy,
+    Client, Url,
+};
+use sophia_api::prelude::Iri;
+use tracing::error;
+
+use crate::model::pdp::impl_::wac::group_doc_resolver::{
+    AgentGroupDoc, AgentGroupDocResolver, AGENT_GROUP_DOC_TOO_LARGE, INVALID_AGENT_GROUP_DOC,
+    UNRESOLVABLE_AGENT_GROUP_DOC,
+};
+
+/// Default timeout for connecting to group doc hosts.
+pub const DEFAULT_AGENT_GROUP_DOC_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default timeout for group doc requests, including loading
+/// the body.
+pub const DEFAULT_AGENT_GROUP_DOC_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An implementation of [`AgentGroupDocResolver`] that
+/// dereferences group documents over http.
+///
+/// It requests as a public client, and is capable of
+/// parsing only turtle group documents.
+///
+/// As group document uris are chosen by acl authors, it
+/// requests only publicly routable addresses, doesn't follow
+/// redirects, and bounds each request by a timeout.
+#[derive(Debug, Clone)]
+pub struct HttpAgentGroupDocResolver {
+    /// Http client.
+    client: Client,
+
+    /// Triple parser factory.
+    triple_parser_factory: Arc<DynSynTripleParserFactory>,
+
+    /// Max size of group documents in bytes.
+    max_doc_size: u64,
+}
+
+impl Default for HttpAgentGroupDocResolver {
+    #[inline]
+    fn default() -> Self {
+        // 1 MiB by default.
+        Self::new(1024 * 1024)
+    }
+}
+
+impl HttpAgentGroupDocResolver {
+    /// Create a new [`HttpAgentGroupDocResolver`] with given
+    /// max doc size, and default timeouts.
+    #[inline]
+    pub fn new(max_doc_size: u64) -> Self {
+        Self::new_with_timeouts(
+            max_doc_size,
+            DEFAULT_AGENT_GROUP_DOC_CONNECT_TIMEOUT,
+            DEFAULT_AGENT_GROUP_DOC_REQUEST_TIMEOUT,
+        )
+    }
+
+    /// Create a new [`HttpAgentGroupDocResolver`] with given
+    /// max doc size, connect timeout, and request timeout.
+    pub fn new_with_timeouts(
+        max_doc_size: u64,
+        connect_timeout: Duration,
+        request_timeout: Duration,
+    ) -> Self {
+        let client = Client::builder()
+            .connect_timeout(connect_timeout)
+            .timeout(request_timeout)
+            // Redirects may lead to internal addresses.
+            .redirect(Policy::none())
+            // Proxies would resolve targets on our behalf.
+            .no_proxy()
+            .dns_resolver(Arc::new(PublicAddrResolver))
+            .build()
+            .expect("Tls backend cannot be initialized for reqwest client.");
+
+        Self {
+            client,
+            triple_parser_factory: Arc::new(DynSynTripleParserFactory::new(Default::default())),
+            max_doc_size,
+        }
+    }
+
+    /// Get max size of group documents in bytes.
+    #[inline]
+    pub fn max_doc_size(&self) -> u64 {
+        self.max_doc_size
+    }
+}
+
+impl AgentGroupDocResolver for HttpAgentGroupDocResolver {
+    #[tracing::instrument(skip_all, name = "HttpAgentGroupDocResolver::resolve", fields(doc_uri))]
+    fn resolve(&self, doc_uri: NormalAbsoluteHttpUri) -> ProbFuture<'static, Arc<AgentGroupDoc>> {
+        let client = self.client.clone();
+        let triple_parser_factory = self.triple_parser_factory.clone();
+        let max_doc_size = self.max_doc_size;
+
+        Box::pin(async move {
+            // Ip literal hosts are not resolved through the
+            // resolver, hence must be checked here.
+            let url = Url::parse(doc_uri.as_str()).map_err(|e| {
+                error!("Group doc uri is not a valid url. Error:\n {}", e);
+                UNRESOLVABLE_AGENT_GROUP_DOC.new_problem()
+            })?;
+            if has_non_public_ip_host(&url) {
+                error!("Group doc uri doesn't target a public address.");
+                return Err(UNRESOLVABLE_AGENT_GROUP_DOC.new_problem());
+            }
+
+            let resp = client
+                .get(url)
+                .header(ACCEPT, "text/turtle")
+                .send()
+                .await
+                .map_err(|e| {
+                    error!(
+                        "Unknown io error in dereferencing group doc. Error:\n {}",
+                        e
+                    );
+                    UNKNOWN_IO_ERROR.new_problem_builder().source(e).finish()
+                })?;
+
+            if !resp.status().is_success() {
+                error!("Deref request not successful. status: {}", resp.status());
+                return Err(UNRESOLVABLE_AGENT_GROUP_DOC.new_problem());
+            }
+
+            // If turtle is not available, reject.
+            if !resp
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| {
+                    v.split(';')
+                        .next()
+                        .unwrap_or_default()
+                        .trim()
+                        .eq_ignore_ascii_case("text/turtle")
+                })
+                .unwrap_or_default()
+            {
+                error!("Group doc is not available in turtle.");
+                return Err(INVALID_AGENT_GROUP_DOC.new_problem());
+            }
+
+            // Reject early, if declared length exceeds the limit.
+            if resp.content_length().unwrap_or_default() > max_doc_size {
+                error!("Declared group doc length exceeds the limit.");
+                return Err(AGENT_GROUP_DOC_TOO_LARGE.new_problem());
+            }
+
+            let base_iri = Iri::new_unchecked(resp.url().to_string());
+
+            // Load the doc, while enforcing the limit.
+            let mut body = BytesMut::new();
+            let mut chunks = resp.bytes_stream();
+            while let Some(chunk) = chunks.next().await {
+                let chunk = chunk.map_err(|e| {
+                    error!("Unknown io error in loading group doc. Error:\n {}", e);
+                    UNKNOWN_IO_ERROR.new_problem_builder().source(e).finish()
+                })?;
+
+                if (body.len() + chunk.len()) as u64 > max_doc_size {
+                    error!("Group doc length exceeds the limit.");
+                    return Err(AGENT_GROUP_DOC_TOO_LARGE.new_problem());
+                }
+                body.extend_from_slice(&chunk);
+            }
+
+            // Parse the doc.
+            let doc =
+                triple_parser_factory
+                    .new_async_parser(TP_TURTLE, Some(base_iri))
+                    .parse_stream::<ArcTerm, _>(futures::stream::once(futures::future::ready(
+                        Ok::<_, Infallible>(body.freeze()),
+                    )))
+                    .await
+                    .try_collect::<AgentGroupDoc>()
+                    .await
+                    .map_err(|e| {
+                        error!("Error in parsing group doc. Error:\n {}", e);
+                        INVALID_AGENT_GROUP_DOC.new_problem()
+                    })?;
+
+            Ok(Arc::new(doc))
+        })
+    }
+}
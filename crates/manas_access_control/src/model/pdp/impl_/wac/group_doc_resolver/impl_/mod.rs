@@ -0,0 +1,10 @@
+//! I define few implementations of [`AgentGroupDocResolver`](super::AgentGroupDocResolver).
+//!
+
+mod caching;
+mod http;
+mod routing;
+
+pub use caching::*;
+pub use http::*;
+pub use routing::*;
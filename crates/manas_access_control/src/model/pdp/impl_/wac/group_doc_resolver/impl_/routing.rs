@@ -0,0 +1,221 @@
+//! I define an implementation of [`AgentGroupDocResolver`]
+//! that routes resolution to local or remote resolvers.
+//!
+
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock, Weak,
+    },
+};
+
+use dyn_problem::ProbFuture;
+use http_uri::invariant::NormalAbsoluteHttpUri;
+
+use super::HttpAgentGroupDocResolver;
+use crate::model::pdp::impl_::wac::group_doc_resolver::{AgentGroupDoc, AgentGroupDocResolver};
+
+/// Type of local resolver entries, with their namespace uri
+/// and registration id.
+type LocalResolverEntry = (NormalAbsoluteHttpUri, u64, Arc<dyn AgentGroupDocResolver>);
+
+/// An implementation of [`AgentGroupDocResolver`] that
+/// routes resolution of documents in registered local
+/// namespaces to corresponding local resolvers, and of all
+/// other documents to a remote resolver.
+///
+/// Local resolvers are typically registered for storages
+/// served by the same server, so that their group documents
+/// are read through the repo. They stay registered as long
+/// as their [`LocalResolverRegistration`] is alive.
+pub struct RoutingAgentGroupDocResolver {
+    /// Local resolvers, keyed by their namespace uri, along
+    /// with their registration ids.
+    local_resolvers: RwLock<Vec<LocalResolverEntry>>,
+
+    /// Id of the next registration.
+    next_registration_id: AtomicU64,
+
+    /// Remote resolver.
+    remote_resolver: Arc<dyn AgentGroupDocResolver>,
+}
+
+impl Debug for RoutingAgentGroupDocResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RoutingAgentGroupDocResolver")
+            .field("remote_resolver", &self.remote_resolver)
+            .finish()
+    }
+}
+
+impl Default for RoutingAgentGroupDocResolver {
+    #[inline]
+    fn default() -> Self {
+        Self::new(Arc::new(HttpAgentGroupDocResolver::default()))
+    }
+}
+
+impl RoutingAgentGroupDocResolver {
+    /// Create a new [`RoutingAgentGroupDocResolver`] with
+    /// given remote resolver, and no local resolvers.
+    pub fn new(remote_resolver: Arc<dyn AgentGroupDocResolver>) -> Self {
+        Self {
+            local_resolvers: Default::default(),
+            next_registration_id: Default::default(),
+            remote_resolver,
+        }
+    }
+
+    /// Register given local resolver for documents in
+    /// namespace with given uri. Any resolver previously
+    /// registered for the same namespace will be replaced.
+    ///
+    /// Resolver is unregistered, when returned registration
+    /// is dropped.
+    pub fn register_local(
+        self: &Arc<Self>,
+        ns_uri: NormalAbsoluteHttpUri,
+        resolver: Arc<dyn AgentGroupDocResolver>,
+    ) -> LocalResolverRegistration {
+        let id = self.next_registration_id.fetch_add(1, Ordering::Relaxed);
+
+        let mut local_resolvers = self
+            .local_resolvers
+            .write()
+            .expect("Lock must not be poisoned.");
+
+        local_resolvers.retain(|(uri, _, _)| uri != &ns_uri);
+        local_resolvers.push((ns_uri.clone(), id, resolver));
+
+        LocalResolverRegistration {
+            router: Arc::downgrade(self),
+            ns_uri,
+            id,
+        }
+    }
+
+    /// Resolve the resolver for document with given uri.
+    fn resolver_for(&self, doc_uri: &NormalAbsoluteHttpUri) -> Arc<dyn AgentGroupDocResolver> {
+        self.local_resolvers
+            .read()
+            .expect("Lock must not be poisoned.")
+            .iter()
+            .filter(|(ns_uri, _, _)| doc_uri.as_str().starts_with(ns_uri.as_str()))
+            // Most specific namespace wins.
+            .max_by_key(|(ns_uri, _, _)| ns_uri.as_str().len())
+            .map(|(_, _, resolver)| resolver.clone())
+            .unwrap_or_else(|| self.remote_resolver.clone())
+    }
+}
+
+impl AgentGroupDocResolver for RoutingAgentGroupDocResolver {
+    #[inline]
+    fn resolve(&self, doc_uri: NormalAbsoluteHttpUri) -> ProbFuture<'static, Arc<AgentGroupDoc>> {
+        self.resolver_for(&doc_uri).resolve(doc_uri)
+    }
+}
+
+/// A registration of a local resolver with a
+/// [`RoutingAgentGroupDocResolver`]. Resolver is
+/// unregistered, when this is dropped, unless it is already
+/// replaced by a later registration.
+#[must_use]
+#[derive(Debug)]
+pub struct LocalResolverRegistration {
+    router: Weak<RoutingAgentGroupDocResolver>,
+    ns_uri: NormalAbsoluteHttpUri,
+    id: u64,
+}
+
+impl Drop for LocalResolverRegistration {
+    fn drop(&mut self) {
+        if let Some(router) = self.router.upgrade() {
+            router
+                .local_resolvers
+                .write()
+                .expect("Lock must not be poisoned.")
+                .retain(|(uri, id, _)| !(uri == &self.ns_uri && id == &self.id));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use claims::*;
+    use rdf_utils::model::term::ArcTerm;
+    use sophia_api::term::IriRef;
+
+    use super::*;
+
+    /// A mock resolver, that resolves to a fixed document.
+    #[derive(Debug, Default)]
+    struct MockResolver {
+        doc: Arc<AgentGroupDoc>,
+    }
+
+    impl MockResolver {
+        fn new(size: usize) -> Arc<Self> {
+            let doc = (0..size)
+                .map(|i| {
+                    let term = ArcTerm::Iri(IriRef::new_unchecked(
+                        format!("http://example.org/{}", i).into(),
+                    ));
+                    [term.clone(), term.clone(), term]
+                })
+                .collect();
+            Arc::new(Self { doc: Arc::new(doc) })
+        }
+    }
+
+    impl AgentGroupDocResolver for MockResolver {
+        fn resolve(
+            &self,
+            _doc_uri: NormalAbsoluteHttpUri,
+        ) -> ProbFuture<'static, Arc<AgentGroupDoc>> {
+            let doc = self.doc.clone();
+            Box::pin(async move { Ok(doc) })
+        }
+    }
+
+    async fn resolved_doc_size(router: &RoutingAgentGroupDocResolver) -> usize {
+        assert_ok!(
+            router
+                .resolve(
+                    NormalAbsoluteHttpUri::try_new_from("http://pod.example.org/groups")
+                        .expect("Must be valid.")
+                )
+                .await
+        )
+        .len()
+    }
+
+    fn ns_uri() -> NormalAbsoluteHttpUri {
+        NormalAbsoluteHttpUri::try_new_from("http://pod.example.org/").expect("Must be valid.")
+    }
+
+    #[tokio::test]
+    async fn dropped_registration_unregisters_resolver() {
+        let router = Arc::new(RoutingAgentGroupDocResolver::new(MockResolver::new(0)));
+
+        let registration = router.register_local(ns_uri(), MockResolver::new(1));
+        assert_eq!(resolved_doc_size(&router).await, 1);
+
+        drop(registration);
+        assert_eq!(resolved_doc_size(&router).await, 0);
+    }
+
+    #[tokio::test]
+    async fn replaced_registration_does_not_unregister_replacement() {
+        let router = Arc::new(RoutingAgentGroupDocResolver::new(MockResolver::new(0)));
+
+        let registration = router.register_local(ns_uri(), MockResolver::new(1));
+        let replacement = router.register_local(ns_uri(), MockResolver::new(2));
+
+        drop(registration);
+        assert_eq!(resolved_doc_size(&router).await, 2);
+
+        drop(replacement);
+        assert_eq!(resolved_doc_size(&router).await, 0);
+    }
+}
@@ -0,0 +1,38 @@
+//! I define traits and types for resolving agent group
+//! documents, that describe `vcard:Group`s referred to by
+//! `acl:agentGroup` attribute.
+//!
+
+use std::{collections::HashSet, fmt::Debug, sync::Arc};
+
+use dyn_problem::{define_anon_problem_types, ProbFuture};
+use http_uri::invariant::NormalAbsoluteHttpUri;
+use rdf_utils::model::triple::ArcTriple;
+
+pub mod impl_;
+
+/// Type of agent group documents.
+pub type AgentGroupDoc = HashSet<ArcTriple>;
+
+/// A trait for resolvers of agent group documents.
+pub trait AgentGroupDocResolver: Debug + Send + Sync + 'static {
+    /// Resolve the agent group document with given uri.
+    fn resolve(&self, doc_uri: NormalAbsoluteHttpUri) -> ProbFuture<'static, Arc<AgentGroupDoc>>;
+}
+
+define_anon_problem_types!(
+    /// Unresolvable agent group doc.
+    UNRESOLVABLE_AGENT_GROUP_DOC: (
+        "Unresolvable agent group doc."
+    );
+
+    /// Invalid agent group doc.
+    INVALID_AGENT_GROUP_DOC: (
+        "Invalid agent group doc."
+    );
+
+    /// Agent group doc is too large.
+    AGENT_GROUP_DOC_TOO_LARGE: (
+        "Agent group doc is too large."
+    );
+);
@@ -10,7 +10,7 @@ use acp::model::{
 };
 use async_recursion::async_recursion;
 use dyn_problem::{type_::UNKNOWN_IO_ERROR, ProbFuture, ProbResult};
-use futures::{StreamExt, TryFutureExt};
+use futures::{future::BoxFuture, StreamExt, TryFutureExt};
 use http_uri::invariant::NormalAbsoluteHttpUri;
 use manas_space::{
     resource::{
@@ -29,13 +29,21 @@ use rdf_vocabularies::ns;
 use sophia_api::{graph::SetGraph, term::Term};
 use tracing::{error, info};
 
-use self::engine::{ResolvedAclContext, WacEngine};
+use self::{
+    engine::{
+        JustifiedAccessGrant, ResolvedAclContext, WacEngine,
+        DEFAULT_AGENT_GROUP_DOC_CACHE_MAX_CAPACITY, DEFAULT_AGENT_GROUP_DOC_CACHE_TTL,
+    },
+    group_doc_resolver::impl_::{CachingAgentGroupDocResolver, RoutingAgentGroupDocResolver},
+};
 use crate::model::{
     pdp::{AccessGrantResponse, PolicyDecisionPoint, ResourceAccessContext, INVALID_PRP_RESPONSE},
     prp::SlotAcrChain,
+    AccessGrantSet,
 };
 
 pub mod engine;
+pub mod group_doc_resolver;
 
 /// An implementation of [`PolicyDecisionPoint`] that
 /// confirms to `WAC` specification.
@@ -54,6 +62,14 @@ where
     ///Supported attributes.
     supported_attrs: Arc<HashSet<HAttribute<ArcTerm>>>,
 
+    /// Agent group doc router, through which local group
+    /// doc resolvers can be registered.
+    agent_group_doc_router: Option<Arc<RoutingAgentGroupDocResolver>>,
+
+    /// Agent group doc cache of the engine, through which
+    /// cached group documents are invalidated.
+    agent_group_doc_cache: Option<CachingAgentGroupDocResolver>,
+
     _phantom: PhantomData<fn(S)>,
 }
 
@@ -73,7 +89,14 @@ where
     G: InfallibleMutableGraph + Default + Send + Sync + Clone + 'static,
 {
     fn default() -> Self {
-        let engine = WacEngine::default();
+        let agent_group_doc_router = Arc::new(RoutingAgentGroupDocResolver::default());
+        let agent_group_doc_cache = CachingAgentGroupDocResolver::new(
+            agent_group_doc_router.clone(),
+            DEFAULT_AGENT_GROUP_DOC_CACHE_MAX_CAPACITY,
+            DEFAULT_AGENT_GROUP_DOC_CACHE_TTL,
+        );
+        let engine =
+            WacEngine::new_with_agent_group_doc_resolver(Arc::new(agent_group_doc_cache.clone()));
         let supported_attrs = Arc::new(
             engine
                 .supported_attrs()
@@ -90,6 +113,8 @@ where
             ),
             supported_attrs,
         )
+        .with_agent_group_doc_router(agent_group_doc_router)
+        .with_agent_group_doc_cache(agent_group_doc_cache)
     }
 }
 
@@ -109,9 +134,61 @@ where
             engine,
             supported_access_modes,
             supported_attrs,
+            agent_group_doc_router: None,
+            agent_group_doc_cache: None,
             _phantom: PhantomData,
         }
     }
+
+    /// Set the agent group doc router, that the engine
+    /// resolves agent group documents through.
+    #[inline]
+    pub fn with_agent_group_doc_router(
+        mut self,
+        agent_group_doc_router: Arc<RoutingAgentGroupDocResolver>,
+    ) -> Self {
+        self.agent_group_doc_router = Some(agent_group_doc_router);
+        self
+    }
+
+    /// Set the agent group doc cache, that the engine
+    /// resolves agent group documents through. Cached
+    /// documents are invalidated through it, when they are
+    /// modified or deleted.
+    #[inline]
+    pub fn with_agent_group_doc_cache(
+        mut self,
+        agent_group_doc_cache: CachingAgentGroupDocResolver,
+    ) -> Self {
+        self.agent_group_doc_cache = Some(agent_group_doc_cache);
+        self
+    }
+
+    /// Declare given custom access modes as supported, in
+    /// addition to existing supported access modes.
+    ///
+    /// Custom access modes can be granted through policies,
+    /// and be required for operations through an
+    /// [`AccessModeResolver`](crate::model::pep::mode_resolver::AccessModeResolver).
+    pub fn with_custom_access_modes(
+        mut self,
+        custom_access_modes: impl IntoIterator<Item = HAccessMode<ArcTerm>>,
+    ) -> Self {
+        let mut supported_access_modes = self.supported_access_modes.as_ref().clone();
+        supported_access_modes.extend(custom_access_modes);
+        self.supported_access_modes = Arc::new(supported_access_modes);
+        self
+    }
+
+    /// Get the agent group doc router, if any.
+    ///
+    /// Local group doc resolvers can be registered with it,
+    /// so that group documents in local storages are
+    /// resolved through their repos.
+    #[inline]
+    pub fn agent_group_doc_router(&self) -> Option<&Arc<RoutingAgentGroupDocResolver>> {
+        self.agent_group_doc_router.as_ref()
+    }
 }
 
 impl<S, G> PolicyDecisionPoint for WacDecisionPoint<S, G>
@@ -146,6 +223,17 @@ where
             Self::resolve_grants_with(context, acr_chain, engine, supported_access_modes).await
         })
     }
+
+    fn invalidate_cached(&self, resource_uri: &NormalAbsoluteHttpUri) -> BoxFuture<'static, ()> {
+        let opt_agent_group_doc_cache = self.agent_group_doc_cache.clone();
+        let resource_uri = resource_uri.clone();
+
+        Box::pin(async move {
+            if let Some(agent_group_doc_cache) = opt_agent_group_doc_cache {
+                agent_group_doc_cache.invalidate(&resource_uri).await;
+            }
+        })
+    }
 }
 
 impl<S, G> WacDecisionPoint<S, G>
@@ -192,7 +280,10 @@ where
             return Err(INVALID_PRP_RESPONSE.new_problem());
         }
 
-        let access_grant_set = match &own_item.res_slot.slot_rev_link() {
+        let (access_grant_set, matched_policies, matched_matchers) = match &own_item
+            .res_slot
+            .slot_rev_link()
+        {
             // If resource has no slot rev link, implies it is
             // the storage root.
             None => {
@@ -202,7 +293,7 @@ where
                 );
 
                 engine
-                    .resolve_access_control(
+                    .resolve_justified_access_control(
                         own_item.acr.map(|acl| ResolvedAclContext {
                             target_uri: target_uri.clone(),
                             resolved_acl_subject_uri: own_item.res_slot.id().uri.clone(),
@@ -210,6 +301,7 @@ where
                         }),
                         context.into_inner().into_with_arced_graph(),
                     )
+                    .map_ok(Self::into_grants_and_matched)
                     .inspect_err(|_| {
                         error!("Error in resolving grants for nearest ipr");
                     })
@@ -226,7 +318,7 @@ where
                     info!("Resolved nearest ipr uri: {}", target_uri.as_str());
 
                     engine
-                        .resolve_access_control(
+                        .resolve_justified_access_control(
                             if let Some(acl) = own_item.acr {
                                 Some(ResolvedAclContext {
                                     target_uri: target_uri.clone(),
@@ -239,6 +331,7 @@ where
                             },
                             context.into_inner().into_with_arced_graph(),
                         )
+                        .map_ok(Self::into_grants_and_matched)
                         .inspect_err(|_| {
                             error!("Error in resolving grants for nearest ipr");
                         })
@@ -252,7 +345,7 @@ where
                             info!("Aux resource with independent role is resolved to be nearest ipr. Uri: {}", target_uri.as_str());
 
                             engine
-                                .resolve_access_control(
+                                .resolve_justified_access_control(
                                     own_item.acr.map(|acl| ResolvedAclContext {
                                         target_uri: target_uri.clone(),
                                         resolved_acl_subject_uri: own_item
@@ -264,6 +357,7 @@ where
                                     }),
                                     context.into_inner().into_with_arced_graph(),
                                 )
+                                .map_ok(Self::into_grants_and_matched)
                                 .inspect_err(|_| {
                                     error!("Error in resolving grants for nearest ipr");
                                 })
@@ -284,7 +378,7 @@ where
                             subject_access_context.set(&ns::acp::target, host_res_uri.deref());
 
                             // Then resolve grant for subject resource.
-                            let subject_access_grants = Self::resolve_grants_with(
+                            let subject_access_grant_response = Self::resolve_grants_with(
                                 ResourceAccessContext::new_unchecked(
                                     host_res_uri.clone(),
                                     subject_access_context,
@@ -296,12 +390,22 @@ where
                             .inspect_err(|_| {
                                 error!("Error in resolving subject resource access grants.");
                             })
-                            .await?
-                            .access_grant_set;
+                            .await?;
+
+                            let subject_access_grants =
+                                subject_access_grant_response.access_grant_set;
+                            let subject_matched_policies =
+                                subject_access_grant_response.matched_policies;
+                            let subject_matched_matchers =
+                                subject_access_grant_response.matched_matchers;
 
                             if role == AuxAccessResolutionRole::SubjectResource {
                                 // Resolve subject access grants as target's grants.
-                                Ok(subject_access_grants)
+                                Ok((
+                                    subject_access_grants,
+                                    subject_matched_policies,
+                                    subject_matched_matchers,
+                                ))
                             } else {
                                 // Resolve target's grants based on subject's `control` grant.
                                 if subject_access_grants
@@ -310,10 +414,18 @@ where
                                 {
                                     // If control grant available on subject,
                                     // then all grants are available on aux resource.
-                                    Ok(supported_access_modes.as_ref().clone())
+                                    Ok((
+                                        supported_access_modes.as_ref().clone(),
+                                        subject_matched_policies,
+                                        subject_matched_matchers,
+                                    ))
                                 } else {
                                     // Or else no grants.
-                                    Ok(HashSet::new())
+                                    Ok((
+                                        HashSet::new(),
+                                        subject_matched_policies,
+                                        subject_matched_matchers,
+                                    ))
                                 }
                             }
                         }
@@ -325,9 +437,29 @@ where
         Ok(AccessGrantResponse {
             res_slot: Some(own_item.res_slot),
             access_grant_set,
+            matched_policies,
+            matched_matchers,
         })
     }
 
+    /// Split given justified grant into grants, matched
+    /// policies and matched matchers. Authorizations are
+    /// reported as matched policies, as wac has no separate
+    /// matchers.
+    fn into_grants_and_matched(
+        justified_grant: JustifiedAccessGrant,
+    ) -> (AccessGrantSet, Vec<ArcTerm>, Vec<ArcTerm>) {
+        (
+            justified_grant.grants,
+            justified_grant
+                .matched_authorizations
+                .into_iter()
+                .map(Handle::into_term)
+                .collect(),
+            vec![],
+        )
+    }
+
     async fn resolve_fallback_acl_context(
         target_uri: NormalAbsoluteHttpUri,
         mut acr_chain: SlotAcrChain<S, G, Arc<G>>,
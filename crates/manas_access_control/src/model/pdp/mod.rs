@@ -6,6 +6,7 @@ use std::{collections::HashSet, fmt::Debug, ops::Deref, sync::Arc};
 
 use acp::model::{access_mode::HAccessMode, attribute::HAttribute, context::DContext};
 use dyn_problem::{define_anon_problem_types, ProbFuture};
+use futures::future::BoxFuture;
 use http_uri::invariant::NormalAbsoluteHttpUri;
 use itertools::Itertools;
 use manas_space::{resource::slot::SolidResourceSlot, SolidStorageSpace};
@@ -30,6 +31,16 @@ pub struct AccessGrantResponse<Space: SolidStorageSpace> {
 
     /// Resolved access grant set on the resource.
     pub access_grant_set: AccessGrantSet,
+
+    /// Policies, that are matched in resolving the grants.
+    /// For wac, they are the matched authorizations, and for
+    /// acp, they are the satisfied policies.
+    pub matched_policies: Vec<ArcTerm>,
+
+    /// Matchers, that are matched in resolving the grants.
+    /// For acp, they are the satisfied matchers of satisfied
+    /// policies. Wac has no separate matchers.
+    pub matched_matchers: Vec<ArcTerm>,
 }
 
 /// A trait for access control policy decision points.
@@ -55,6 +66,16 @@ pub trait PolicyDecisionPoint: Debug + Send + Sync + 'static {
         context: ResourceAccessContext<Self::Graph>,
         acr_chain: SlotAcrChain<Self::StSpace, Self::Graph, Arc<Self::Graph>>,
     ) -> ProbFuture<'static, AccessGrantResponse<Self::StSpace>>;
+
+    /// Invalidate any cached data derived from the content of
+    /// the resource with given uri, like agent group documents.
+    ///
+    /// Default implementation does nothing, as it caches
+    /// nothing.
+    #[allow(unused_variables)]
+    fn invalidate_cached(&self, resource_uri: &NormalAbsoluteHttpUri) -> BoxFuture<'static, ()> {
+        Box::pin(futures::future::ready(()))
+    }
 }
 
 /// A struct to represent resource access context.
@@ -0,0 +1,142 @@
+//! I define types to represent explanations of access
+//! control resolutions.
+//!
+
+use std::ops::Deref;
+
+use manas_space::resource::uri::SolidResourceUri;
+use rdf_utils::model::{graph::InfallibleGraph, handle::Handle, term::ArcTerm, triple::ArcTriple};
+use rdf_vocabularies::ns;
+use sophia_api::{
+    term::{BnodeId, Term},
+    triple::Triple,
+};
+use unwrap_infallible::UnwrapInfallible;
+
+use crate::model::AccessGrantSet;
+
+/// A struct to represent an evaluated acr.
+#[derive(Debug, Clone)]
+pub struct ExplainedAcr {
+    /// Handle term of the acr.
+    pub handle: ArcTerm,
+
+    /// Statements of the acr.
+    pub statements: Vec<ArcTriple>,
+}
+
+/// A struct to represent an item of evaluated acr chain.
+#[derive(Debug, Clone)]
+pub struct ExplainedAcrChainItem {
+    /// Uri of the resource.
+    pub res_uri: SolidResourceUri,
+
+    /// Acr of the resource, if any.
+    pub acr: Option<ExplainedAcr>,
+}
+
+/// A struct to represent explanation of an access control
+/// resolution.
+#[derive(Debug, Clone)]
+pub struct AccessControlExplanation {
+    /// Uri of the target resource.
+    pub target: SolidResourceUri,
+
+    /// Handle term of the evaluated access context.
+    pub context_handle: ArcTerm,
+
+    /// Statements of the evaluated access context.
+    pub context_statements: Vec<ArcTriple>,
+
+    /// Evaluated acr chain, from the target resource to the
+    /// storage root.
+    pub acr_chain: Vec<ExplainedAcrChainItem>,
+
+    /// Resolved grants.
+    pub grants: AccessGrantSet,
+
+    /// Policies, that are matched in resolving the grants.
+    pub matched_policies: Vec<ArcTerm>,
+
+    /// Matchers, that are matched in resolving the grants.
+    pub matched_matchers: Vec<ArcTerm>,
+}
+
+impl AccessControlExplanation {
+    /// Get the rdf statements of the explanation.
+    ///
+    /// Explanation is described as an `acp:AccessGrant`, that
+    /// links to the evaluated context, and granted access
+    /// modes. It is linked to evaluated acrs with
+    /// `prov:used`, and to matched policies and matchers with
+    /// `prov:wasDerivedFrom`. Statements of the context and
+    /// the acrs are included.
+    pub fn to_statements(&self) -> Vec<ArcTriple> {
+        let h_explanation: ArcTerm = BnodeId::new_unchecked("explanation").into_term();
+
+        let mut statements = vec![
+            [
+                h_explanation.clone(),
+                ns::rdf::type_.into_term(),
+                ns::acp::AccessGrant.into_term(),
+            ],
+            [
+                h_explanation.clone(),
+                ns::acp::context.into_term(),
+                self.context_handle.clone(),
+            ],
+        ];
+
+        statements.extend(self.context_statements.iter().cloned());
+
+        statements.extend(self.grants.iter().map(|mode| {
+            [
+                h_explanation.clone(),
+                ns::acp::grant.into_term(),
+                mode.as_term().clone(),
+            ]
+        }));
+
+        for item in &self.acr_chain {
+            if let Some(acr) = &item.acr {
+                statements.push([
+                    item.res_uri.deref().into_term(),
+                    ns::acp::accessControlResource.into_term(),
+                    acr.handle.clone(),
+                ]);
+                statements.push([
+                    h_explanation.clone(),
+                    ns::prov::used.into_term(),
+                    acr.handle.clone(),
+                ]);
+                statements.extend(acr.statements.iter().cloned());
+            }
+        }
+
+        statements.extend(
+            self.matched_policies
+                .iter()
+                .chain(self.matched_matchers.iter())
+                .map(|h| {
+                    [
+                        h_explanation.clone(),
+                        ns::prov::wasDerivedFrom.into_term(),
+                        h.clone(),
+                    ]
+                }),
+        );
+
+        statements
+    }
+}
+
+/// Collect statements of given graph.
+pub(crate) fn collect_statements<G: InfallibleGraph>(graph: &G) -> Vec<ArcTriple> {
+    graph
+        .triples()
+        .map(|triple| {
+            let [s, p, o] = triple.unwrap_infallible().to_spo();
+            [s.into_term(), p.into_term(), o.into_term()]
+        })
+        .collect()
+}
@@ -2,6 +2,7 @@
 //!
 
 use std::{
+    borrow::Borrow,
     collections::{HashMap, HashSet},
     fmt::Debug,
     marker::PhantomData,
@@ -14,15 +15,22 @@ use acp::model::{
     context::HContext,
 };
 use dyn_problem::{type_::UNKNOWN_IO_ERROR, ProbFuture};
-use futures::TryFutureExt;
+use futures::{future::BoxFuture, StreamExt, TryFutureExt, TryStreamExt};
+use http_uri::invariant::NormalAbsoluteHttpUri;
 use manas_authentication::common::credentials::{
     impl_::{basic::BasicRequestCredentials, void::VoidCredentials},
     AgentCredentials, RequestCredentials, ToContext,
 };
-use manas_space::{resource::operation::SolidResourceOperation, SolidStorageSpace};
+use manas_space::{
+    resource::{operation::SolidResourceOperation, uri::SolidResourceUri},
+    SolidStorageSpace,
+};
 use once_cell::sync::Lazy;
 use rdf_utils::model::{
-    description::DescriptionExt, graph::InfallibleMutableGraph, handle::Handle, triple::ArcTriple,
+    description::{Description, DescriptionExt},
+    graph::InfallibleMutableGraph,
+    handle::Handle,
+    triple::ArcTriple,
 };
 use rdf_vocabularies::ns;
 use sophia_api::term::{BnodeId, Term};
@@ -33,6 +41,10 @@ use crate::model::{
         AccessGrantResponse, PolicyDecisionPoint, ResourceAccessContext, UNKNOWN_TARGET_RESOURCE,
     },
     pep::{
+        explanation::{
+            collect_statements, AccessControlExplanation, ExplainedAcr, ExplainedAcrChainItem,
+        },
+        mode_resolver::AccessModeResolver,
         ActionOpList, PolicyEnforcementPoint, ResolvedAccessControl, ResolvedAccessControlResponse,
     },
     prp::PolicyRetrievalPoint,
@@ -111,6 +123,20 @@ pub struct SolidCompatPolicyEnforcementPoint<Setup: SolidCompatPolicyEnforcement
 
     /// Policy decision point.
     pub pdp: Arc<Setup::PDP>,
+
+    /// Resolver of access modes required for operations.
+    /// Use [`Self::setup_mode_resolver`] to resolve them as
+    /// per the setup's least privilege map.
+    pub mode_resolver: Arc<dyn AccessModeResolver>,
+}
+
+impl<Setup: SolidCompatPolicyEnforcementPointSetup> SolidCompatPolicyEnforcementPoint<Setup> {
+    /// Get a mode resolver, that resolves required access
+    /// modes as per the least privilege map of the setup.
+    #[inline]
+    pub fn setup_mode_resolver() -> Arc<dyn AccessModeResolver> {
+        Arc::new(Setup::least_privilege_map())
+    }
 }
 
 impl<Setup: SolidCompatPolicyEnforcementPointSetup> Clone
@@ -121,6 +147,7 @@ impl<Setup: SolidCompatPolicyEnforcementPointSetup> Clone
             storage_space: self.storage_space.clone(),
             prp: self.prp.clone(),
             pdp: self.pdp.clone(),
+            mode_resolver: self.mode_resolver.clone(),
         }
     }
 }
@@ -142,6 +169,107 @@ impl<Setup: SolidCompatPolicyEnforcementPointSetup> PolicyEnforcementPoint
         action_op_list: ActionOpList,
         credentials: Setup::Credentials,
     ) -> ProbFuture<'static, ResolvedAccessControlResponse<Setup::Credentials>> {
+        let grant_resolution =
+            self.resolve_access_grant(action_op_list.on.clone(), &credentials, false);
+        let mode_resolver = self.mode_resolver.clone();
+
+        Box::pin(async move {
+            let (access_grant_response, _) = grant_resolution.await?;
+
+            // Resolve list of denied ops.
+            let denied_ops = action_op_list
+                .ops
+                .iter()
+                .filter(|op| {
+                    // Op is allowed, if any of it's generalized op
+                    // is allowed.
+                    !op.op.generalized().any(|gop| {
+                        mode_resolver
+                            .resolve_required_modes(&gop)
+                            .map(|required_modes| {
+                                access_grant_response
+                                    .access_grant_set
+                                    .is_superset(required_modes)
+                            })
+                            .unwrap_or_else(|| {
+                                warn!("Required access modes are not resolved for op. Op: {}", gop);
+                                false
+                            })
+                    })
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+
+            let authorization = Authorization {
+                target: action_op_list.on.clone(),
+                credentials,
+                grants: access_grant_response.access_grant_set,
+                matched_policies: access_grant_response.matched_policies,
+            };
+
+            Ok(ResolvedAccessControlResponse {
+                action_op_list,
+                resolved: if let Ok(denied_ops1) = denied_ops.try_into() {
+                    ResolvedAccessControl::Deny {
+                        authorization,
+                        denied_ops: denied_ops1,
+                    }
+                } else {
+                    ResolvedAccessControl::Allow { authorization }
+                },
+            })
+        })
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        name = "SolidCompatPolicyEnforcementPoint::explain_access_control",
+        fields(target, credentials)
+    )]
+    fn explain_access_control(
+        &self,
+        target: SolidResourceUri,
+        credentials: Self::Credentials,
+    ) -> ProbFuture<'static, Option<AccessControlExplanation>> {
+        Box::pin(
+            self.resolve_access_grant(target, &credentials, true)
+                .map_ok(|(_, explanation)| explanation),
+        )
+    }
+
+    #[inline]
+    fn invalidate_cached_policies(
+        &self,
+        resource_uri: &NormalAbsoluteHttpUri,
+    ) -> BoxFuture<'static, ()> {
+        self.prp.invalidate_cached(resource_uri)
+    }
+
+    #[inline]
+    fn invalidate_cached_content_derivatives(
+        &self,
+        resource_uri: &NormalAbsoluteHttpUri,
+    ) -> BoxFuture<'static, ()> {
+        self.pdp.invalidate_cached(resource_uri)
+    }
+}
+
+impl<Setup: SolidCompatPolicyEnforcementPointSetup> SolidCompatPolicyEnforcementPoint<Setup> {
+    /// Resolve access grant on the target resource for given
+    /// credentials. If `explain` is true, it also resolves
+    /// an explanation of the resolution.
+    fn resolve_access_grant(
+        &self,
+        target: SolidResourceUri,
+        credentials: &Setup::Credentials,
+        explain: bool,
+    ) -> ProbFuture<
+        'static,
+        (
+            AccessGrantResponse<Setup::StSpace>,
+            Option<AccessControlExplanation>,
+        ),
+    > {
         // Convert credentials to context.
         let mut context = credentials.to_context::<Setup::Graph>(
             HContext::try_new(BnodeId::new_unchecked("access_context").into_term())
@@ -149,7 +277,10 @@ impl<Setup: SolidCompatPolicyEnforcementPointSetup> PolicyEnforcementPoint
         );
 
         // Set target.
-        context.set(&ns::acp::target, action_op_list.on.deref());
+        context.set(&ns::acp::target, target.deref());
+
+        // Set owner.
+        context.set(&ns::acp::owner, self.storage_space.owner_id());
 
         // If agent is the storage owner.
         let agent_is_storage_owner = credentials
@@ -157,35 +288,76 @@ impl<Setup: SolidCompatPolicyEnforcementPointSetup> PolicyEnforcementPoint
             .map(|agent_creds| agent_creds.webid() == self.storage_space.owner_id())
             .unwrap_or(false);
 
-        // TODO setup resource creator,
-
-        let res_access_context =
-            ResourceAccessContext::new_unchecked(action_op_list.on.clone(), context);
-
         let prp = self.prp.clone();
         let pdp = self.pdp.clone();
 
         Box::pin(async move {
             // Retrieve acr chain from prp.
-            let acr_chain = prp
-                .retrieve(res_access_context.target_uri().clone(), false)
+            let mut acr_chain = prp.retrieve(target.clone(), false).await.map_err(|e| {
+                if UNKNOWN_TARGET_RESOURCE.is_type_of(&e) {
+                    // TODO
+                    error!("Target resource is unknown to prp. Error:\n {}", e);
+                    UNKNOWN_TARGET_RESOURCE.new_problem_builder()
+                } else {
+                    error!(
+                        "Unknown io error in retrieving policies from prp. Error:\n {}",
+                        e
+                    );
+                    UNKNOWN_IO_ERROR.new_problem_builder()
+                }
+                .source(e)
+                .finish()
+            })?;
+
+            // Set resource creator.
+            if let Some(creator_agent) = prp
+                .resolve_creator(&target)
                 .await
                 .map_err(|e| {
-                    if UNKNOWN_TARGET_RESOURCE.is_type_of(&e) {
-                        // TODO
-                        error!("Target resource is unknown to prp. Error:\n {}", e);
-                        UNKNOWN_TARGET_RESOURCE.new_problem_builder()
-                    } else {
-                        error!(
-                            "Unknown io error in retrieving policies from prp. Error:\n {}",
-                            e
-                        );
-                        UNKNOWN_IO_ERROR.new_problem_builder()
-                    }
-                    .source(e)
-                    .finish()
+                    error!(
+                        "Unknown io error in resolving resource creator from prp. Error:\n {}",
+                        e
+                    );
+                    UNKNOWN_IO_ERROR.new_problem_builder().source(e).finish()
+                })?
+                .and_then(|creator| creator.agent)
+            {
+                context.set(&ns::acp::creator, &creator_agent);
+            }
+
+            // Capture evaluated context and acr chain, if explanation is requested.
+            let mut explained = None;
+            if explain {
+                let chain_items: Vec<_> = acr_chain.try_collect().await.map_err(|e| {
+                    error!(
+                        "Unknown io error in retrieving policies from prp. Error:\n {}",
+                        e
+                    );
+                    UNKNOWN_IO_ERROR.new_problem_builder().source(e).finish()
                 })?;
 
+                let explained_acr_chain = chain_items
+                    .iter()
+                    .map(|item| ExplainedAcrChainItem {
+                        res_uri: item.res_slot.id().uri.clone(),
+                        acr: item.acr.as_ref().map(|acr| ExplainedAcr {
+                            handle: acr.handle().as_term().clone(),
+                            statements: collect_statements(acr.graph().borrow()),
+                        }),
+                    })
+                    .collect::<Vec<_>>();
+
+                explained = Some((
+                    context.handle().as_term().clone(),
+                    collect_statements(context.graph()),
+                    explained_acr_chain,
+                ));
+
+                acr_chain = futures::stream::iter(chain_items.into_iter().map(Ok)).boxed();
+            }
+
+            let res_access_context = ResourceAccessContext::new_unchecked(target.clone(), context);
+
             // Get resolved access grant from pdp.
             let mut access_grant_response: AccessGrantResponse<Setup::StSpace> = pdp
                 .resolve_grants(res_access_context, acr_chain)
@@ -216,47 +388,19 @@ impl<Setup: SolidCompatPolicyEnforcementPointSetup> PolicyEnforcementPoint
                 }
             }
 
-            // Resolve list of denied ops.
-            let denied_ops = action_op_list
-                .ops
-                .iter()
-                .filter(|op| {
-                    // Op is allowed, if any of it's generalized op
-                    // is allowed.
-                    !op.op.generalized().any(|gop| {
-                        Setup::least_privilege_map()
-                            .get(&gop)
-                            .map(|required_modes| {
-                                access_grant_response
-                                    .access_grant_set
-                                    .is_superset(required_modes)
-                            })
-                            .unwrap_or_else(|| {
-                                warn!("Least privilege set is not configured for op. Op: {}", gop);
-                                false
-                            })
-                    })
-                })
-                .cloned()
-                .collect::<Vec<_>>();
-
-            let authorization = Authorization {
-                target: action_op_list.on.clone(),
-                credentials,
-                grants: access_grant_response.access_grant_set,
-            };
+            let explanation = explained.map(|(context_handle, context_statements, acr_chain)| {
+                AccessControlExplanation {
+                    target,
+                    context_handle,
+                    context_statements,
+                    acr_chain,
+                    grants: access_grant_response.access_grant_set.clone(),
+                    matched_policies: access_grant_response.matched_policies.clone(),
+                    matched_matchers: access_grant_response.matched_matchers.clone(),
+                }
+            });
 
-            Ok(ResolvedAccessControlResponse {
-                action_op_list,
-                resolved: if let Ok(denied_ops1) = denied_ops.try_into() {
-                    ResolvedAccessControl::Deny {
-                        authorization,
-                        denied_ops: denied_ops1,
-                    }
-                } else {
-                    ResolvedAccessControl::Allow { authorization }
-                },
-            })
+            Ok((access_grant_response, explanation))
         })
     }
 }
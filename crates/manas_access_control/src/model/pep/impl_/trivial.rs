@@ -60,6 +60,7 @@ where
                     target: action_op_list.on.clone(),
                     credentials,
                     grants: self.all_access_modes.clone(),
+                    matched_policies: vec![],
                 },
             },
             action_op_list,
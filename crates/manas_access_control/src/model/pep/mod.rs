@@ -5,12 +5,17 @@
 use std::fmt::Debug;
 
 use dyn_problem::ProbFuture;
+use futures::future::BoxFuture;
+use http_uri::invariant::NormalAbsoluteHttpUri;
 use manas_authentication::common::credentials::{impl_::void::VoidCredentials, RequestCredentials};
-use manas_space::SolidStorageSpace;
+use manas_space::{resource::uri::SolidResourceUri, SolidStorageSpace};
 
+use self::explanation::AccessControlExplanation;
 use super::{ActionOpList, ResolvedAccessControl};
 
+pub mod explanation;
 pub mod impl_;
+pub mod mode_resolver;
 
 /// A type to represent resolved access control response.
 #[derive(Debug, Clone)]
@@ -39,4 +44,50 @@ pub trait PolicyEnforcementPoint: Debug + Send + Sync + 'static {
         action_op_list: ActionOpList,
         credentials: Self::Credentials,
     ) -> ProbFuture<'static, ResolvedAccessControlResponse<Self::Credentials>>;
+
+    /// Invalidate any cached policies of the resource with
+    /// given uri. It must be called after the access control
+    /// policies of the resource are modified, or the resource
+    /// is deleted.
+    ///
+    /// Default implementation does nothing, as it caches
+    /// nothing.
+    #[allow(unused_variables)]
+    fn invalidate_cached_policies(
+        &self,
+        resource_uri: &NormalAbsoluteHttpUri,
+    ) -> BoxFuture<'static, ()> {
+        Box::pin(futures::future::ready(()))
+    }
+
+    /// Invalidate any cached data derived from the content of
+    /// the resource with given uri, like agent group documents.
+    /// It must be called after the resource is modified, or
+    /// deleted.
+    ///
+    /// Default implementation does nothing, as it caches
+    /// nothing.
+    #[allow(unused_variables)]
+    fn invalidate_cached_content_derivatives(
+        &self,
+        resource_uri: &NormalAbsoluteHttpUri,
+    ) -> BoxFuture<'static, ()> {
+        Box::pin(futures::future::ready(()))
+    }
+
+    /// Explain the access control resolution on the resource
+    /// with given uri, for given credentials. Explanation
+    /// includes the evaluated context, acr chain, matched
+    /// policies and matchers, and resolved grants.
+    ///
+    /// Default implementation resolves to `None`, as it
+    /// doesn't support explanations.
+    #[allow(unused_variables)]
+    fn explain_access_control(
+        &self,
+        target: SolidResourceUri,
+        credentials: Self::Credentials,
+    ) -> ProbFuture<'static, Option<AccessControlExplanation>> {
+        Box::pin(futures::future::ready(Ok(None)))
+    }
 }
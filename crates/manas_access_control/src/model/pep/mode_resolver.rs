@@ -0,0 +1,35 @@
+//! I define interfaces and implementations for resolving
+//! access modes required for resource operations.
+//!
+
+use std::{collections::HashMap, fmt::Debug};
+
+use manas_space::resource::operation::SolidResourceOperation;
+
+use crate::model::AccessGrantSet;
+
+/// A trait for resolvers of access modes, that are required
+/// for resource operations.
+///
+/// It allows mapping operations to custom access modes
+/// declared by policy decision points.
+pub trait AccessModeResolver: Debug + Send + Sync + 'static {
+    /// Resolve minimal set of access modes required for given
+    /// resource operation. Returns `None`, if operation is
+    /// not known to the resolver.
+    fn resolve_required_modes(&self, op: &SolidResourceOperation) -> Option<&AccessGrantSet>;
+}
+
+impl AccessModeResolver for HashMap<SolidResourceOperation, AccessGrantSet> {
+    #[inline]
+    fn resolve_required_modes(&self, op: &SolidResourceOperation) -> Option<&AccessGrantSet> {
+        self.get(op)
+    }
+}
+
+impl<R: AccessModeResolver + Sync + ?Sized> AccessModeResolver for &'static R {
+    #[inline]
+    fn resolve_required_modes(&self, op: &SolidResourceOperation) -> Option<&AccessGrantSet> {
+        (**self).resolve_required_modes(op)
+    }
+}
@@ -1,4 +1,547 @@
 //! I define a cache layered implementation of [`PolicyRetrievalPoint`]
 //!
 
-// TODO after notifications setup.
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use dyn_problem::ProbFuture;
+use futures::{future::BoxFuture, StreamExt};
+use http_uri::invariant::NormalAbsoluteHttpUri;
+use manas_authentication::common::credentials::CreatorCredentials;
+use moka::future::{Cache, CacheBuilder};
+use tracing::debug;
+
+use crate::model::prp::{PolicyRetrievalPoint, SlotAcrChain, SlotAcrChainItem};
+
+/// Type of cached acr chain items of a prp.
+type CachedItem<PRP> = SlotAcrChainItem<
+    <PRP as PolicyRetrievalPoint>::StSpace,
+    <PRP as PolicyRetrievalPoint>::Graph,
+    <PRP as PolicyRetrievalPoint>::WGraph,
+>;
+
+/// A cache layered implementation of [`PolicyRetrievalPoint`].
+///
+/// It caches acr chain items retrieved by the inner prp per
+/// resource slot, and serves the cached prefix of any
+/// requested chain from the cache, delegating only the rest
+/// to the inner prp.
+///
+/// Cached items must be invalidated with
+/// [`invalidate_cached`](PolicyRetrievalPoint::invalidate_cached),
+/// when acr of a resource is modified, or the resource is
+/// deleted. Access controlled repo layer does that for all
+/// modifications through it. Invalidation drops the items of
+/// the resource and all of it's descendants.
+///
+/// Items also expire after a configured time to live, to
+/// bound staleness from out of band modifications. As the
+/// cache is per process, that includes modifications made
+/// through other replicas of a horizontally scaled
+/// deployment. Such deployments must choose the time to live
+/// as per their staleness tolerance.
+///
+/// Resolved creators of resources are cached alike, and are
+/// invalidated along with the chain items. Access controlled
+/// repo layer invalidates the uri of each created resource,
+/// so that a `None` resolved before creation is not served
+/// after it.
+pub struct CacheLayeredPolicyRetrievalPoint<Inner: PolicyRetrievalPoint> {
+    /// Inner prp.
+    inner: Arc<Inner>,
+
+    /// Cache of chain items, keyed by resource uri.
+    cache: Cache<NormalAbsoluteHttpUri, CachedItem<Inner>>,
+
+    /// Cache of resolved creators, keyed by resource uri.
+    creator_cache: Cache<NormalAbsoluteHttpUri, Option<CreatorCredentials>>,
+
+    /// Invalidation epoch. It is incremented on every
+    /// invalidation, so that items retrieved before an
+    /// invalidation are not cached after it.
+    epoch: Arc<AtomicU64>,
+}
+
+impl<Inner: PolicyRetrievalPoint> Debug for CacheLayeredPolicyRetrievalPoint<Inner> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CacheLayeredPolicyRetrievalPoint")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<Inner: PolicyRetrievalPoint> Clone for CacheLayeredPolicyRetrievalPoint<Inner>
+where
+    CachedItem<Inner>: Clone + Send + Sync + 'static,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            cache: self.cache.clone(),
+            creator_cache: self.creator_cache.clone(),
+            epoch: self.epoch.clone(),
+        }
+    }
+}
+
+impl<Inner: PolicyRetrievalPoint> CacheLayeredPolicyRetrievalPoint<Inner>
+where
+    CachedItem<Inner>: Clone + Send + Sync + 'static,
+{
+    /// Create a new [`CacheLayeredPolicyRetrievalPoint`]
+    /// with given inner prp, cache capacity and time to live.
+    pub fn new(inner: Arc<Inner>, cache_max_capacity: u64, cache_time_to_live: Duration) -> Self {
+        Self {
+            inner,
+            cache: CacheBuilder::new(cache_max_capacity)
+                .time_to_live(cache_time_to_live)
+                .support_invalidation_closures()
+                .build(),
+            creator_cache: CacheBuilder::new(cache_max_capacity)
+                .time_to_live(cache_time_to_live)
+                .support_invalidation_closures()
+                .build(),
+            epoch: Default::default(),
+        }
+    }
+
+    /// Get the inner prp.
+    #[inline]
+    pub fn inner(&self) -> &Arc<Inner> {
+        &self.inner
+    }
+}
+
+impl<Inner: PolicyRetrievalPoint> PolicyRetrievalPoint for CacheLayeredPolicyRetrievalPoint<Inner>
+where
+    CachedItem<Inner>: Clone + Send + Sync + 'static,
+{
+    type StSpace = Inner::StSpace;
+
+    type Graph = Inner::Graph;
+
+    type WGraph = Inner::WGraph;
+
+    #[tracing::instrument(
+        skip_all,
+        name = "CacheLayeredPolicyRetrievalPoint::retrieve",
+        fields(resource_uri)
+    )]
+    fn retrieve(
+        &self,
+        resource_uri: NormalAbsoluteHttpUri,
+        deduced_containment_is_sufficient: bool,
+    ) -> ProbFuture<'static, SlotAcrChain<Self::StSpace, Self::Graph, Self::WGraph>> {
+        let inner = self.inner.clone();
+        let cache = self.cache.clone();
+        let epoch = self.epoch.clone();
+        let retrieval_epoch = epoch.load(Ordering::Acquire);
+
+        Box::pin(async move {
+            // Resolve the cached prefix of the chain.
+            let mut cached_prefix = Vec::new();
+            let mut next_uri = Some(resource_uri);
+
+            while let Some(uri) = next_uri.take() {
+                if let Some(item) = cache.get(&uri).await {
+                    next_uri = item
+                        .res_slot
+                        .slot_rev_link()
+                        .map(|rev_link| rev_link.target.clone());
+                    cached_prefix.push(item);
+                } else {
+                    next_uri = Some(uri);
+                    break;
+                }
+            }
+
+            debug!("Resolved {} chain items from cache.", cached_prefix.len());
+
+            let cached_prefix_stream = futures::stream::iter(cached_prefix.into_iter().map(Ok));
+
+            // If chain is completely cached, return.
+            let uri = if let Some(uri) = next_uri {
+                uri
+            } else {
+                return Ok(cached_prefix_stream.boxed() as SlotAcrChain<_, _, _>);
+            };
+
+            // Retrieve rest of the chain from inner, while
+            // caching the items.
+            let rest = inner
+                .retrieve(uri, deduced_containment_is_sufficient)
+                .await?
+                .then(move |item_result| {
+                    let cache = cache.clone();
+                    let is_fresh = epoch.load(Ordering::Acquire) == retrieval_epoch;
+                    async move {
+                        // Cache only if no invalidation happened
+                        // since retrieval.
+                        if let (Ok(item), true) = (&item_result, is_fresh) {
+                            cache
+                                .insert(item.res_slot.id().uri.clone(), item.clone())
+                                .await;
+                        }
+                        item_result
+                    }
+                });
+
+            Ok(cached_prefix_stream.chain(rest).boxed() as SlotAcrChain<_, _, _>)
+        })
+    }
+
+    fn invalidate_cached(&self, resource_uri: &NormalAbsoluteHttpUri) -> BoxFuture<'static, ()> {
+        let inner = self.inner.clone();
+        let cache = self.cache.clone();
+        let creator_cache = self.creator_cache.clone();
+        let resource_uri = resource_uri.clone();
+        self.epoch.fetch_add(1, Ordering::AcqRel);
+
+        Box::pin(async move {
+            cache.invalidate(&resource_uri).await;
+            creator_cache.invalidate(&resource_uri).await;
+
+            // Invalidate items of descendants too, as they are
+            // deleted along with a container.
+            let prefix = resource_uri.as_str().to_owned();
+            cache
+                .invalidate_entries_if({
+                    let prefix = prefix.clone();
+                    move |uri, _| uri.as_str().starts_with(&prefix)
+                })
+                .expect("Must be supported, as enabled while building.");
+            creator_cache
+                .invalidate_entries_if(move |uri, _| uri.as_str().starts_with(&prefix))
+                .expect("Must be supported, as enabled while building.");
+
+            inner.invalidate_cached(&resource_uri).await;
+        })
+    }
+
+    fn resolve_creator(
+        &self,
+        resource_uri: &NormalAbsoluteHttpUri,
+    ) -> ProbFuture<'static, Option<CreatorCredentials>> {
+        let inner = self.inner.clone();
+        let creator_cache = self.creator_cache.clone();
+        let epoch = self.epoch.clone();
+        let resolution_epoch = epoch.load(Ordering::Acquire);
+        let resource_uri = resource_uri.clone();
+
+        Box::pin(async move {
+            if let Some(creator) = creator_cache.get(&resource_uri).await {
+                return Ok(creator);
+            }
+
+            let creator = inner.resolve_creator(&resource_uri).await?;
+
+            // Cache only if no invalidation happened since
+            // resolution.
+            if epoch.load(Ordering::Acquire) == resolution_epoch {
+                creator_cache.insert(resource_uri, creator.clone()).await;
+            }
+
+            Ok(creator)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashMap, HashSet},
+        sync::Mutex,
+    };
+
+    use futures::TryStreamExt;
+    use manas_space::{
+        mock::MockSolidStorageSpace,
+        resource::{
+            kind::SolidResourceKind, slot::SolidResourceSlot, slot_path::SolidResourceSlotPath,
+            slot_rel_type::mock::SlotRelationTypeHint,
+        },
+    };
+    use rdf_utils::model::triple::ArcTriple;
+    use rstest::*;
+
+    use super::*;
+
+    /// A mock prp, that records uris it is asked to retrieve
+    /// chains for.
+    #[derive(Debug)]
+    struct MockPRP {
+        slots: HashMap<NormalAbsoluteHttpUri, SolidResourceSlot<MockSolidStorageSpace>>,
+        retrievals: Mutex<Vec<String>>,
+        creator_resolutions: Mutex<Vec<String>>,
+    }
+
+    impl PolicyRetrievalPoint for MockPRP {
+        type StSpace = MockSolidStorageSpace;
+
+        type Graph = HashSet<ArcTriple>;
+
+        type WGraph = HashSet<ArcTriple>;
+
+        fn retrieve(
+            &self,
+            resource_uri: NormalAbsoluteHttpUri,
+            _deduced_containment_is_sufficient: bool,
+        ) -> ProbFuture<'static, SlotAcrChain<Self::StSpace, Self::Graph, Self::WGraph>> {
+            self.retrievals
+                .lock()
+                .unwrap()
+                .push(resource_uri.as_str().to_owned());
+
+            let mut items = Vec::new();
+            let mut next_uri = Some(resource_uri);
+            while let Some(uri) = next_uri.take() {
+                let res_slot = self.slots.get(&uri).expect("Must be known.").clone();
+                next_uri = res_slot
+                    .slot_rev_link()
+                    .map(|rev_link| rev_link.target.clone());
+                items.push(Ok(SlotAcrChainItem {
+                    res_slot,
+                    acr: None,
+                }));
+            }
+
+            Box::pin(
+                async move { Ok(futures::stream::iter(items).boxed() as SlotAcrChain<_, _, _>) },
+            )
+        }
+
+        fn resolve_creator(
+            &self,
+            resource_uri: &NormalAbsoluteHttpUri,
+        ) -> ProbFuture<'static, Option<CreatorCredentials>> {
+            self.creator_resolutions
+                .lock()
+                .unwrap()
+                .push(resource_uri.as_str().to_owned());
+
+            let creator = self
+                .slots
+                .contains_key(resource_uri)
+                .then(CreatorCredentials::default);
+            Box::pin(async move { Ok(creator) })
+        }
+    }
+
+    fn uri(uri_str: &str) -> NormalAbsoluteHttpUri {
+        NormalAbsoluteHttpUri::try_new_from(uri_str).expect("Must be valid.")
+    }
+
+    #[fixture]
+    fn prp() -> CacheLayeredPolicyRetrievalPoint<MockPRP> {
+        let space = Arc::new(MockSolidStorageSpace::new_from_valid_root_uri_str(
+            "http://pod.example.org/",
+        ));
+
+        let slot_paths = [
+            vec![
+                (
+                    SlotRelationTypeHint::Contains,
+                    "http://pod.example.org/a/",
+                    SolidResourceKind::Container,
+                ),
+                (
+                    SlotRelationTypeHint::Contains,
+                    "http://pod.example.org/a/b/",
+                    SolidResourceKind::Container,
+                ),
+                (
+                    SlotRelationTypeHint::Contains,
+                    "http://pod.example.org/a/b/c.ttl",
+                    SolidResourceKind::NonContainer,
+                ),
+            ],
+            vec![
+                (
+                    SlotRelationTypeHint::Contains,
+                    "http://pod.example.org/a/",
+                    SolidResourceKind::Container,
+                ),
+                (
+                    SlotRelationTypeHint::Contains,
+                    "http://pod.example.org/a/d.ttl",
+                    SolidResourceKind::NonContainer,
+                ),
+            ],
+            vec![(
+                SlotRelationTypeHint::Contains,
+                "http://pod.example.org/e.ttl",
+                SolidResourceKind::NonContainer,
+            )],
+        ];
+
+        let slots = slot_paths
+            .into_iter()
+            .flat_map(|hint| {
+                SolidResourceSlotPath::new_mock(space.clone(), Some(hint))
+                    .slots()
+                    .to_vec()
+            })
+            .map(|slot| (slot.id().uri.clone(), slot))
+            .collect();
+
+        CacheLayeredPolicyRetrievalPoint::new(
+            Arc::new(MockPRP {
+                slots,
+                retrievals: Default::default(),
+                creator_resolutions: Default::default(),
+            }),
+            100,
+            Duration::from_secs(300),
+        )
+    }
+
+    async fn retrieve_chain(
+        prp: &CacheLayeredPolicyRetrievalPoint<MockPRP>,
+        uri_str: &str,
+    ) -> Vec<String> {
+        let chain: Vec<_> = prp
+            .retrieve(uri(uri_str), false)
+            .await
+            .expect("Must be ok.")
+            .try_collect()
+            .await
+            .expect("Must be ok.");
+
+        chain
+            .into_iter()
+            .map(|item| item.res_slot.id().uri.as_str().to_owned())
+            .collect()
+    }
+
+    fn take_retrievals(prp: &CacheLayeredPolicyRetrievalPoint<MockPRP>) -> Vec<String> {
+        std::mem::take(&mut prp.inner().retrievals.lock().unwrap())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn cached_chains_are_served_from_cache(prp: CacheLayeredPolicyRetrievalPoint<MockPRP>) {
+        let chain = retrieve_chain(&prp, "http://pod.example.org/a/b/c.ttl").await;
+        assert_eq!(
+            chain,
+            vec![
+                "http://pod.example.org/a/b/c.ttl",
+                "http://pod.example.org/a/b/",
+                "http://pod.example.org/a/",
+                "http://pod.example.org/",
+            ]
+        );
+        assert_eq!(
+            take_retrievals(&prp),
+            vec!["http://pod.example.org/a/b/c.ttl"]
+        );
+
+        assert_eq!(
+            retrieve_chain(&prp, "http://pod.example.org/a/b/c.ttl").await,
+            chain
+        );
+        assert!(take_retrievals(&prp).is_empty());
+
+        // Only the uncached suffix is delegated.
+        assert_eq!(
+            retrieve_chain(&prp, "http://pod.example.org/a/d.ttl").await,
+            vec![
+                "http://pod.example.org/a/d.ttl",
+                "http://pod.example.org/a/",
+                "http://pod.example.org/",
+            ]
+        );
+        assert_eq!(
+            take_retrievals(&prp),
+            vec!["http://pod.example.org/a/d.ttl"]
+        );
+    }
+
+    #[rstest]
+    #[case::container("http://pod.example.org/a/", &[
+        "http://pod.example.org/a/b/c.ttl",
+        "http://pod.example.org/a/d.ttl",
+    ])]
+    #[case::nested_container("http://pod.example.org/a/b/", &[
+        "http://pod.example.org/a/b/c.ttl",
+    ])]
+    #[case::non_container("http://pod.example.org/a/d.ttl", &[
+        "http://pod.example.org/a/d.ttl",
+    ])]
+    #[tokio::test]
+    async fn invalidation_drops_resource_and_descendants(
+        prp: CacheLayeredPolicyRetrievalPoint<MockPRP>,
+        #[case] invalidated_uri_str: &str,
+        #[case] expected_retrievals: &[&str],
+    ) {
+        let uri_strs = [
+            "http://pod.example.org/a/b/c.ttl",
+            "http://pod.example.org/a/d.ttl",
+            "http://pod.example.org/e.ttl",
+        ];
+
+        for uri_str in uri_strs {
+            retrieve_chain(&prp, uri_str).await;
+        }
+        take_retrievals(&prp);
+
+        prp.invalidate_cached(&uri(invalidated_uri_str)).await;
+
+        for uri_str in uri_strs {
+            retrieve_chain(&prp, uri_str).await;
+        }
+        assert_eq!(take_retrievals(&prp), expected_retrievals);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn resolved_creators_are_cached_and_invalidated(
+        prp: CacheLayeredPolicyRetrievalPoint<MockPRP>,
+    ) {
+        let take_resolutions =
+            || std::mem::take(&mut *prp.inner().creator_resolutions.lock().unwrap());
+
+        let uri_strs = [
+            "http://pod.example.org/a/b/c.ttl",
+            "http://pod.example.org/a/d.ttl",
+            "http://pod.example.org/e.ttl",
+            "http://pod.example.org/f.ttl",
+        ];
+
+        for _ in 0..2 {
+            for uri_str in uri_strs {
+                let creator = prp
+                    .resolve_creator(&uri(uri_str))
+                    .await
+                    .expect("Must be ok.");
+                assert_eq!(creator.is_some(), uri_str != "http://pod.example.org/f.ttl");
+            }
+        }
+        assert_eq!(take_resolutions(), uri_strs);
+
+        prp.invalidate_cached(&uri("http://pod.example.org/a/"))
+            .await;
+        prp.invalidate_cached(&uri("http://pod.example.org/f.ttl"))
+            .await;
+
+        for uri_str in uri_strs {
+            prp.resolve_creator(&uri(uri_str))
+                .await
+                .expect("Must be ok.");
+        }
+        assert_eq!(
+            take_resolutions(),
+            vec![
+                "http://pod.example.org/a/b/c.ttl",
+                "http://pod.example.org/a/d.ttl",
+                "http://pod.example.org/f.ttl",
+            ]
+        );
+    }
+}
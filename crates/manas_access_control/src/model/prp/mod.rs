@@ -6,7 +6,9 @@ use std::{borrow::Borrow, fmt::Debug};
 
 use acp::model::acr::DAccessControlResource;
 use dyn_problem::{ProbFuture, ProbStream};
+use futures::future::BoxFuture;
 use http_uri::invariant::NormalAbsoluteHttpUri;
+use manas_authentication::common::credentials::CreatorCredentials;
 use manas_space::{resource::slot::SolidResourceSlot, SolidStorageSpace};
 use rdf_utils::model::graph::InfallibleGraph;
 
@@ -30,6 +32,29 @@ pub trait PolicyRetrievalPoint: Debug + Send + Sync + 'static {
         resource_uri: NormalAbsoluteHttpUri,
         deduced_containment_is_sufficient: bool,
     ) -> ProbFuture<'static, SlotAcrChain<Self::StSpace, Self::Graph, Self::WGraph>>;
+
+    /// Invalidate any cached acr chain item of the resource
+    /// with given uri.
+    ///
+    /// Default implementation does nothing, as it caches
+    /// nothing.
+    #[allow(unused_variables)]
+    fn invalidate_cached(&self, resource_uri: &NormalAbsoluteHttpUri) -> BoxFuture<'static, ()> {
+        Box::pin(futures::future::ready(()))
+    }
+
+    /// Resolve recorded credentials of the creator of the
+    /// resource with given uri.
+    ///
+    /// Default implementation resolves to `None`, as it
+    /// records nothing.
+    #[allow(unused_variables)]
+    fn resolve_creator(
+        &self,
+        resource_uri: &NormalAbsoluteHttpUri,
+    ) -> ProbFuture<'static, Option<CreatorCredentials>> {
+        Box::pin(futures::future::ready(Ok(None)))
+    }
 }
 
 /// A type alias for slot acr chain stream.
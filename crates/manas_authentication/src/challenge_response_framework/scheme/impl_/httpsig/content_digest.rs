@@ -0,0 +1,307 @@
+//! I define an implementation of [`HttpService`] that
+//! verifies `Content-Digest` fields of requests against
+//! their bodies, as specified in
+//! [RFC 9530](https://www.rfc-editor.org/rfc/rfc9530).
+//!
+//! Http message signatures cover request bodies only
+//! through `Content-Digest` field. Hence this service must
+//! be layered along with [`HttpSigScheme`](super::HttpSigScheme),
+//! for signatures to protect request bodies.
+//!
+
+use std::{convert::Infallible, task::Poll};
+
+use futures::TryStreamExt;
+use http::{
+    header::{CONTENT_LENGTH, TRANSFER_ENCODING},
+    HeaderMap, HeaderName, Method, Request, Response, StatusCode,
+};
+use manas_http::{
+    body::Body,
+    service::{BoxHttpResponseFuture, HttpService},
+};
+use picky::hash::HashAlgorithm;
+use tower::{Layer, Service, ServiceExt};
+use tracing::error;
+
+use super::{header_value_str, message::parse_content_digests};
+
+/// `Content-Digest` header name.
+pub static CONTENT_DIGEST: HeaderName = HeaderName::from_static("content-digest");
+
+/// Default maximum size of request bodies, that can be
+/// verified.
+///
+/// Bodies are buffered before authentication, hence it is
+/// kept small.
+pub const DEFAULT_MAX_VERIFIABLE_BODY_SIZE: usize = 1024 * 1024;
+
+/// Resolve the hash algorithm with given registered
+/// digest algorithm name, if supported.
+fn resolve_hash_algorithm(name: &str) -> Option<HashAlgorithm> {
+    Some(match name {
+        "sha-256" => HashAlgorithm::SHA2_256,
+        "sha-512" => HashAlgorithm::SHA2_512,
+        _ => return None,
+    })
+}
+
+/// Verify given `Content-Digest` field value against given
+/// content.
+///
+/// Digests with unsupported algorithms are ignored. Returns
+/// `false`, if field is invalid, or if it has no digest with
+/// supported algorithm, or if any digest with supported
+/// algorithm doesn't match.
+pub fn verify_content_digest(field_value: &str, content: &[u8]) -> bool {
+    let Some(digests) = parse_content_digests(field_value) else {
+        return false;
+    };
+
+    let mut has_supported_digest = false;
+    for (alg_name, digest) in digests {
+        if let Some(alg) = resolve_hash_algorithm(&alg_name) {
+            if alg.digest(content) != digest {
+                return false;
+            }
+            has_supported_digest = true;
+        }
+    }
+    has_supported_digest
+}
+
+/// An implementation of [`HttpService`] that verifies
+/// `Content-Digest` field of requests against their bodies,
+/// before delegating to inner service.
+///
+/// Requests without the field are delegated as they are. For
+/// others, body is buffered, and verified. Requests with
+/// bodies larger than configured maximum are rejected with
+/// `413`, and those with non-matching digests are rejected
+/// with `400`. Requests whose `Content-Length` exceeds the
+/// maximum are rejected without reading their bodies.
+#[derive(Debug, Clone)]
+pub struct ContentDigestVerifyingService<Inner> {
+    /// Inner service.
+    inner: Inner,
+
+    /// Maximum size of request bodies, that can be verified.
+    max_body_size: usize,
+}
+
+impl<Inner> ContentDigestVerifyingService<Inner> {
+    /// Create a new [`ContentDigestVerifyingService`] with
+    /// given params.
+    #[inline]
+    pub fn new(inner: Inner, max_body_size: usize) -> Self {
+        Self {
+            inner,
+            max_body_size,
+        }
+    }
+}
+
+impl<Inner> Service<Request<Body>> for ContentDigestVerifyingService<Inner>
+where
+    Inner: HttpService<Body, Body> + Clone,
+{
+    type Response = Response<Body>;
+
+    type Error = Infallible;
+
+    type Future = BoxHttpResponseFuture<Body>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    #[tracing::instrument(skip_all, name = "ContentDigestVerifyingService::call")]
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let Some(field_value) = header_value_str(req.headers(), &CONTENT_DIGEST) else {
+            return self.inner.call(req);
+        };
+
+        let max_body_size = self.max_body_size;
+        if content_length(req.headers()).map_or(false, |len| len > max_body_size as u64) {
+            error!("Request body is too large to verify.");
+            return Box::pin(async { Ok(error_response(StatusCode::PAYLOAD_TOO_LARGE)) });
+        }
+
+        let mut inner_svc = self.inner.clone();
+
+        Box::pin(async move {
+            let (parts, body) = req.into_parts();
+
+            let content_result = body
+                .into_data_stream()
+                .map_err(|e| {
+                    error!("Error in reading request body. Error:\n {}", e);
+                    StatusCode::BAD_REQUEST
+                })
+                .try_fold(Vec::new(), |mut content, chunk| async move {
+                    if content.len() + chunk.len() > max_body_size {
+                        error!("Request body is too large to verify.");
+                        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+                    }
+                    content.extend_from_slice(&chunk);
+                    Ok(content)
+                })
+                .await;
+
+            let content = match content_result {
+                Ok(content) => content,
+                Err(status) => return Ok(error_response(status)),
+            };
+
+            if !verify_content_digest(&field_value, &content) {
+                error!("Content-Digest doesn't match request body.");
+                return Ok(error_response(StatusCode::BAD_REQUEST));
+            }
+
+            let req = Request::from_parts(parts, Body::from(content));
+            inner_svc.ready().await?.call(req).await
+        })
+    }
+}
+
+/// Get an error response with given status.
+fn error_response(status: StatusCode) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .body(Body::empty())
+        .expect("Must be valid response.")
+}
+
+/// A layer that wraps [`ContentDigestVerifyingService`] over
+/// inner service.
+#[derive(Debug, Clone)]
+pub struct ContentDigestVerifyingLayer {
+    /// Maximum size of request bodies, that can be verified.
+    max_body_size: usize,
+}
+
+impl ContentDigestVerifyingLayer {
+    /// Create a new [`ContentDigestVerifyingLayer`] with
+    /// given maximum verifiable body size.
+    #[inline]
+    pub fn new(max_body_size: usize) -> Self {
+        Self { max_body_size }
+    }
+}
+
+impl Default for ContentDigestVerifyingLayer {
+    #[inline]
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_VERIFIABLE_BODY_SIZE)
+    }
+}
+
+impl<Inner> Layer<Inner> for ContentDigestVerifyingLayer {
+    type Service = ContentDigestVerifyingService<Inner>;
+
+    #[inline]
+    fn layer(&self, inner: Inner) -> Self::Service {
+        ContentDigestVerifyingService::new(inner, self.max_body_size)
+    }
+}
+
+/// Resolve the `Content-Length` of a request with given
+/// headers, if valid.
+fn content_length(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+}
+
+/// Check if request with given method and headers may have
+/// a body.
+pub(super) fn may_have_body(method: &Method, headers: &HeaderMap) -> bool {
+    [Method::POST, Method::PUT, Method::PATCH].contains(method)
+        || headers.contains_key(TRANSFER_ENCODING)
+        || content_length(headers).map_or(false, |len| len > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+    use tower::service_fn;
+
+    use super::*;
+
+    /// Example content from RFC 9530, Section 2.
+    const CONTENT: &[u8] = br#"{"hello": "world"}"#;
+
+    /// `Content-Digest` of the example content.
+    const CONTENT_DIGEST_VALUE: &str = "sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:";
+
+    // Examples from RFC 9530, Section 2.
+    #[rstest]
+    #[case("sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:", true)]
+    #[case(
+        "sha-512=:WZDPaVn/7XgHaAy8pmojAkGWoRx2UFChF41A2svX+TaPm+AbwAgBWnrIiYllu7BNNyealdVLvRwEmTHWXvJwew==:",
+        true
+    )]
+    #[case(
+        "sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:, unixsum=:MTIz:",
+        true
+    )]
+    #[case("sha-256=:RK/0qy18MlBSVnWgjwz6lZEWjP/lF5HF9bvEF8FabDg=:", false)]
+    #[case(
+        "sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:, sha-512=:AAAA:",
+        false
+    )]
+    #[case("unixsum=:MTIz:", false)]
+    #[case("sha-256=X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=", false)]
+    fn verify_content_digest_works_correctly(#[case] field_value: &str, #[case] expected: bool) {
+        assert_eq!(verify_content_digest(field_value, CONTENT), expected);
+    }
+
+    #[rstest]
+    #[case::matching(Some(CONTENT_DIGEST_VALUE), None, 1024, StatusCode::OK)]
+    #[case::no_digest(None, Some("2048"), 8, StatusCode::OK)]
+    #[case::mismatching(
+        Some("sha-256=:RK/0qy18MlBSVnWgjwz6lZEWjP/lF5HF9bvEF8FabDg=:"),
+        None,
+        1024,
+        StatusCode::BAD_REQUEST
+    )]
+    #[case::large_content_length(
+        Some(CONTENT_DIGEST_VALUE),
+        Some("2048"),
+        1024,
+        StatusCode::PAYLOAD_TOO_LARGE
+    )]
+    #[case::large_body(Some(CONTENT_DIGEST_VALUE), None, 8, StatusCode::PAYLOAD_TOO_LARGE)]
+    #[tokio::test]
+    async fn digest_verifying_service_works_correctly(
+        #[case] content_digest: Option<&str>,
+        #[case] content_length: Option<&str>,
+        #[case] max_body_size: usize,
+        #[case] expected_status: StatusCode,
+    ) {
+        let svc = ContentDigestVerifyingService::new(
+            service_fn(|req: Request<Body>| -> BoxHttpResponseFuture<Body> {
+                Box::pin(async move { Ok(Response::new(req.into_body())) })
+            }),
+            max_body_size,
+        );
+
+        let mut builder = Request::builder()
+            .method(Method::POST)
+            .uri("https://pod.example.org/a");
+        if let Some(content_digest) = content_digest {
+            builder = builder.header(CONTENT_DIGEST.clone(), content_digest);
+        }
+        if let Some(content_length) = content_length {
+            builder = builder.header(CONTENT_LENGTH, content_length);
+        }
+        let req = builder
+            .body(Body::from(CONTENT.to_vec()))
+            .expect("Must be valid.");
+
+        let resp = svc.oneshot(req).await.expect("Must be infallible.");
+        assert_eq!(resp.status(), expected_status);
+    }
+}
@@ -0,0 +1,169 @@
+//! I define default implementation of [`AgentKeyResolver`].
+//!
+
+use std::{collections::HashSet, fmt::Debug, marker::PhantomData, sync::Arc, time::Duration};
+
+use futures::{future::BoxFuture, TryFutureExt};
+use http_uri::{
+    invariant::SecureHttpUri, security::transport_policy::SecureTransportPolicy, HttpUri,
+};
+use moka::future::{Cache, CacheBuilder};
+use picky::jose::jwk::Jwk;
+use rdf_vocabularies::ns;
+use sophia_api::{
+    graph::Graph,
+    term::{matcher::Any, IriRef, SimpleTerm, Term},
+    triple::Triple,
+};
+use tracing::error;
+use unwrap_infallible::UnwrapInfallible;
+use webid::{profile_req_agent::WebIdProfileReqAgent, WebId};
+
+use crate::challenge_response_framework::scheme::impl_::httpsig::key_resolver::{
+    AgentKey, AgentKeyResolutionError, AgentKeyResolver,
+};
+
+/// Iri of the `sec:publicKeyJwk` property.
+const SEC_PUBLIC_KEY_JWK: &str = "https://w3id.org/security#publicKeyJwk";
+
+/// A default implementation of [`AgentKeyResolver`].
+///
+/// It dereferences the key id, and expects the key to be
+/// described in the agent's webid profile document as
+/// follows:
+///
+/// ```turtle
+/// <#me> cert:key <#key1> .
+/// <#key1> sec:publicKeyJwk "{\"kty\":\"OKP\", ...}" .
+/// ```
+///
+/// The webid that links to the key must be in the same
+/// document as the key, so that the document is
+/// authoritative for both.
+#[derive(Debug)]
+pub struct DefaultAgentKeyResolver<STP> {
+    /// Webid profile request agent.
+    profile_req_agent: WebIdProfileReqAgent,
+
+    /// Cache.
+    cache: Cache<HttpUri, AgentKey>,
+
+    _phantom: PhantomData<fn(STP)>,
+}
+
+impl<STP> Clone for DefaultAgentKeyResolver<STP> {
+    fn clone(&self) -> Self {
+        Self {
+            profile_req_agent: self.profile_req_agent.clone(),
+            cache: self.cache.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<STP: SecureTransportPolicy> AgentKeyResolver for DefaultAgentKeyResolver<STP> {
+    #[tracing::instrument(skip_all, name = "DefaultAgentKeyResolver::resolve", fields(key_id))]
+    fn resolve(
+        &self,
+        key_id: HttpUri,
+    ) -> BoxFuture<'_, Result<AgentKey, Arc<AgentKeyResolutionError>>> {
+        Box::pin(
+            self.cache
+                .try_get_with(key_id.clone(), self.resolve_fresh(key_id)),
+        )
+    }
+}
+
+impl<STP: SecureTransportPolicy> Default for DefaultAgentKeyResolver<STP> {
+    fn default() -> Self {
+        // 5 minutes by default.
+        Self::new(5000, Duration::from_secs(300))
+    }
+}
+
+impl<STP: SecureTransportPolicy> DefaultAgentKeyResolver<STP> {
+    /// Create a new [`DefaultAgentKeyResolver`], with given
+    /// cache capacity and time to live.
+    pub fn new(cache_max_capacity: u64, cache_time_to_live: Duration) -> Self {
+        let cache = CacheBuilder::new(cache_max_capacity)
+            .time_to_live(cache_time_to_live)
+            .build();
+
+        Self {
+            profile_req_agent: WebIdProfileReqAgent::new(),
+            cache,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Resolve the agent key afresh.
+    async fn resolve_fresh(&self, key_id: HttpUri) -> Result<AgentKey, AgentKeyResolutionError> {
+        // Verify key id security as per stp.
+        let _key_id_secure = SecureHttpUri::<STP>::try_new(key_id.clone()).map_err(|_| {
+            error!("Key id is insecure.");
+            AgentKeyResolutionError::InsecureKeyId
+        })?;
+
+        // Resolve the profile document, in which key is described.
+        let profile = self
+            .profile_req_agent
+            .try_get_profile_document::<HashSet<[SimpleTerm; 3]>>(&WebId::from(key_id.clone()))
+            .inspect_err(|e| {
+                error!("Error in resolving key's profile document. Error:\n {}", e);
+            })
+            .await?;
+
+        let key_doc_uri = key_id.to_absolute();
+        let key_term = IriRef::new_unchecked(key_id.as_str());
+
+        // Resolve the webid, that links to the key from the
+        // same document.
+        let webid = profile
+            .triples_matching(Any, [ns::cert::key], [key_term])
+            .filter_map(|tr| {
+                let tr = tr.unwrap_infallible();
+                let webid = WebId::try_from(tr.s().iri()?.as_str()).ok()?;
+                (webid.to_absolute() == key_doc_uri).then_some(webid)
+            })
+            .next()
+            .ok_or_else(|| {
+                error!("Key is not linked from any webid in it's profile document.");
+                AgentKeyResolutionError::UnlinkedKey
+            })?;
+
+        // Resolve the key jwk.
+        let jwk_str = profile
+            .triples_matching([key_term], [IriRef::new_unchecked(SEC_PUBLIC_KEY_JWK)], Any)
+            .filter_map(|tr| {
+                tr.unwrap_infallible()
+                    .o()
+                    .lexical_form()
+                    .map(|v| v.to_string())
+            })
+            .next()
+            .ok_or_else(|| {
+                error!("Key is not described with a jwk.");
+                AgentKeyResolutionError::InvalidKeyJwk
+            })?;
+
+        let jwk_value: serde_json::Value = serde_json::from_str(&jwk_str).map_err(|e| {
+            error!("Key jwk is not valid json. Error:\n {}", e);
+            AgentKeyResolutionError::InvalidKeyJwk
+        })?;
+
+        let jwk = Jwk::from_json(&jwk_str).map_err(|e| {
+            error!("Key jwk is invalid. Error:\n {}", e);
+            AgentKeyResolutionError::InvalidKeyJwk
+        })?;
+
+        Ok(AgentKey {
+            webid,
+            jwk,
+            kty: jwk_value["kty"]
+                .as_str()
+                .ok_or(AgentKeyResolutionError::InvalidKeyJwk)?
+                .to_owned(),
+            crv: jwk_value["crv"].as_str().map(ToOwned::to_owned),
+        })
+    }
+}
@@ -0,0 +1,4 @@
+//! I provide few implementations of [`AgentKeyResolver`](super::AgentKeyResolver).
+//!
+
+pub mod default;
@@ -0,0 +1,57 @@
+//! I define functionality to resolve agent keys, that sign
+//! http messages.
+//!
+
+use std::{fmt::Debug, sync::Arc};
+
+use futures::future::BoxFuture;
+use http_uri::HttpUri;
+use picky::jose::jwk::Jwk;
+use webid::{profile_req_agent::ProfileDocResolutionError, WebId};
+
+pub mod impl_;
+
+/// A struct for representing a resolved agent key.
+#[derive(Debug, Clone)]
+pub struct AgentKey {
+    /// Webid of the agent, that controls the key.
+    pub webid: WebId,
+
+    /// Public key jwk.
+    pub jwk: Jwk,
+
+    /// Key type, as specified in jwk `kty` parameter.
+    pub kty: String,
+
+    /// Curve, as specified in jwk `crv` parameter.
+    pub crv: Option<String>,
+}
+
+/// A trait for agent key resolvers.
+pub trait AgentKeyResolver: Debug + Send + Sync + 'static {
+    /// Resolve the agent key with given key id.
+    fn resolve(
+        &self,
+        key_id: HttpUri,
+    ) -> BoxFuture<'_, Result<AgentKey, Arc<AgentKeyResolutionError>>>;
+}
+
+/// An error type for representing errors in agent key resolution.
+#[derive(Debug, thiserror::Error)]
+pub enum AgentKeyResolutionError {
+    /// Error in profile doc resolution.
+    #[error("Error in profile doc resolution.")]
+    ProfileDocResolutionError(#[from] ProfileDocResolutionError),
+
+    /// Key id is insecure.
+    #[error("Key id is insecure.")]
+    InsecureKeyId,
+
+    /// Key is not linked from any webid in it's profile document.
+    #[error("Key is not linked from any webid in it's profile document.")]
+    UnlinkedKey,
+
+    /// Key is not described with a valid jwk.
+    #[error("Key is not described with a valid jwk.")]
+    InvalidKeyJwk,
+}
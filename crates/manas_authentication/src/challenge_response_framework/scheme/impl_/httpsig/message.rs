@@ -0,0 +1,608 @@
+//! I define types and functions to parse http message
+//! signature fields, and to construct signature bases, as
+//! specified in [RFC 9421](https://www.rfc-editor.org/rfc/rfc9421).
+//!
+//! Only the subset of structured field syntax used by
+//! signature fields is supported. Covered components with
+//! parameters are not supported.
+//!
+
+use std::fmt::Write;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use http::{HeaderMap, Method};
+use http_uri::invariant::AbsoluteHttpUri;
+
+/// Name of the `@signature-params` component.
+const SIGNATURE_PARAMS_COMPONENT: &str = "@signature-params";
+
+/// A bare item value of a signature parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamValue {
+    /// An integer.
+    Integer(i64),
+
+    /// A string.
+    String(String),
+
+    /// A token.
+    Token(String),
+
+    /// A byte sequence.
+    ByteSeq(Vec<u8>),
+
+    /// A boolean.
+    Boolean(bool),
+}
+
+impl ParamValue {
+    /// Get the value as an integer, if it is one.
+    #[inline]
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Self::Integer(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Get the value as a string, if it is one.
+    #[inline]
+    pub fn as_string(&self) -> Option<&str> {
+        match self {
+            Self::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Get the value as a byte sequence, if it is one.
+    #[inline]
+    pub fn as_byte_seq(&self) -> Option<&[u8]> {
+        match self {
+            Self::ByteSeq(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+/// A signature input, as specified in a member of
+/// `Signature-Input` field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureInput {
+    /// Names of the covered components, in order.
+    pub components: Vec<String>,
+
+    /// Signature parameters, in order.
+    pub params: Vec<(String, ParamValue)>,
+}
+
+impl SignatureInput {
+    /// Get the value of the signature parameter with given
+    /// name.
+    pub fn param(&self, name: &str) -> Option<&ParamValue> {
+        self.params
+            .iter()
+            .find_map(|(k, v)| (k == name).then_some(v))
+    }
+
+    /// Check if given component is covered.
+    #[inline]
+    pub fn covers(&self, component: &str) -> bool {
+        self.components.iter().any(|c| c == component)
+    }
+
+    /// Serialize the signature input, as the value of the
+    /// `@signature-params` component.
+    pub fn serialize(&self) -> String {
+        let mut out = String::from("(");
+        for (i, component) in self.components.iter().enumerate() {
+            if i > 0 {
+                out.push(' ');
+            }
+            write_sf_string(&mut out, component);
+        }
+        out.push(')');
+
+        for (key, value) in &self.params {
+            out.push(';');
+            out.push_str(key);
+            match value {
+                ParamValue::Boolean(true) => {}
+                ParamValue::Boolean(false) => out.push_str("=?0"),
+                ParamValue::Integer(v) => {
+                    write!(out, "={}", v).expect("Must be infallible.");
+                }
+                ParamValue::String(v) => {
+                    out.push('=');
+                    write_sf_string(&mut out, v);
+                }
+                ParamValue::Token(v) => {
+                    out.push('=');
+                    out.push_str(v);
+                }
+                ParamValue::ByteSeq(v) => {
+                    write!(out, "=:{}:", STANDARD.encode(v)).expect("Must be infallible.");
+                }
+            }
+        }
+        out
+    }
+
+    /// Construct the signature base for a request with given
+    /// params. Returns `None`, if any covered component
+    /// cannot be resolved.
+    pub fn signature_base(
+        &self,
+        uri: &AbsoluteHttpUri,
+        method: &Method,
+        headers: &HeaderMap,
+    ) -> Option<String> {
+        let mut base = String::new();
+        for component in &self.components {
+            let value = resolve_component_value(component, uri, method, headers)?;
+            // Component values must not contain newlines.
+            if value.contains('\n') {
+                return None;
+            }
+            write_sf_string(&mut base, component);
+            writeln!(base, ": {}", value).expect("Must be infallible.");
+        }
+        write_sf_string(&mut base, SIGNATURE_PARAMS_COMPONENT);
+        write!(base, ": {}", self.serialize()).expect("Must be infallible.");
+        Some(base)
+    }
+}
+
+/// Resolve the value of the covered component with given
+/// name.
+fn resolve_component_value(
+    component: &str,
+    uri: &AbsoluteHttpUri,
+    method: &Method,
+    headers: &HeaderMap,
+) -> Option<String> {
+    let path = || {
+        let path = uri.path_str();
+        if path.is_empty() {
+            "/".to_owned()
+        } else {
+            path.to_owned()
+        }
+    };
+
+    Some(match component {
+        "@method" => method.as_str().to_owned(),
+        "@target-uri" => uri.as_str().to_owned(),
+        "@authority" => uri.authority_str().to_ascii_lowercase(),
+        "@scheme" => uri.scheme_str().to_ascii_lowercase(),
+        "@path" => path(),
+        "@query" => format!("?{}", uri.query_str().unwrap_or_default()),
+        "@request-target" => match uri.query_str() {
+            Some(query) => format!("{}?{}", path(), query),
+            None => path(),
+        },
+        c if c.starts_with('@') => return None,
+        field_name => {
+            let mut values = headers.get_all(field_name).iter().peekable();
+            values.peek()?;
+            values
+                .map(|v| v.to_str().map(str::trim))
+                .collect::<Result<Vec<_>, _>>()
+                .ok()?
+                .join(", ")
+        }
+    })
+}
+
+/// Write given string as a structured field string.
+fn write_sf_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('"');
+}
+
+/// Parse the members of a `Signature-Input` field.
+pub fn parse_signature_inputs(value: &str) -> Option<Vec<(String, SignatureInput)>> {
+    parse_dictionary(value, |p| {
+        let components = p.parse_inner_list()?;
+        let params = p.parse_params()?;
+        Some(SignatureInput { components, params })
+    })
+}
+
+/// Parse the members of a `Signature` field.
+#[inline]
+pub fn parse_signatures(value: &str) -> Option<Vec<(String, Vec<u8>)>> {
+    parse_byte_seq_dictionary(value)
+}
+
+/// Parse the members of a `Content-Digest` field, as
+/// specified in [RFC 9530](https://www.rfc-editor.org/rfc/rfc9530).
+#[inline]
+pub fn parse_content_digests(value: &str) -> Option<Vec<(String, Vec<u8>)>> {
+    parse_byte_seq_dictionary(value)
+}
+
+/// Parse a structured field dictionary, whose member values
+/// are byte sequences. Any member parameters are ignored.
+fn parse_byte_seq_dictionary(value: &str) -> Option<Vec<(String, Vec<u8>)>> {
+    parse_dictionary(value, |p| {
+        let bytes = match p.parse_bare_item()? {
+            ParamValue::ByteSeq(v) => v,
+            _ => return None,
+        };
+        // Ignore any parameters.
+        p.parse_params()?;
+        Some(bytes)
+    })
+}
+
+/// Parse a structured field dictionary, with given member
+/// value parser.
+fn parse_dictionary<V>(
+    value: &str,
+    parse_member_value: impl Fn(&mut Parser) -> Option<V>,
+) -> Option<Vec<(String, V)>> {
+    let mut parser = Parser {
+        input: value.as_bytes(),
+        pos: 0,
+    };
+    let mut members = Vec::new();
+
+    parser.skip_sp();
+    while !parser.is_done() {
+        let key = parser.parse_key()?;
+        if !parser.consume(b'=') {
+            return None;
+        }
+        let member_value = parse_member_value(&mut parser)?;
+        members.push((key, member_value));
+
+        parser.skip_ows();
+        if parser.is_done() {
+            break;
+        }
+        if !parser.consume(b',') {
+            return None;
+        }
+        parser.skip_ows();
+        if parser.is_done() {
+            return None;
+        }
+    }
+
+    Some(members)
+}
+
+/// A minimal structured field parser.
+struct Parser<'i> {
+    input: &'i [u8],
+    pos: usize,
+}
+
+impl<'i> Parser<'i> {
+    #[inline]
+    fn is_done(&self) -> bool {
+        self.pos >= self.input.len()
+    }
+
+    #[inline]
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    fn consume(&mut self, c: u8) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_sp(&mut self) {
+        while self.peek() == Some(b' ') {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_ows(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t')) {
+            self.pos += 1;
+        }
+    }
+
+    fn take_while(&mut self, pred: impl Fn(u8) -> bool) -> &'i str {
+        let start = self.pos;
+        while self.peek().map_or(false, &pred) {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.input[start..self.pos]).expect("Must be ascii.")
+    }
+
+    fn parse_key(&mut self) -> Option<String> {
+        if !matches!(self.peek(), Some(b'a'..=b'z' | b'*')) {
+            return None;
+        }
+        Some(
+            self.take_while(|c| matches!(c, b'a'..=b'z' | b'0'..=b'9' | b'_' | b'-' | b'.' | b'*'))
+                .to_owned(),
+        )
+    }
+
+    /// Parse an inner list of strings without parameters.
+    fn parse_inner_list(&mut self) -> Option<Vec<String>> {
+        if !self.consume(b'(') {
+            return None;
+        }
+        let mut items = Vec::new();
+        loop {
+            self.skip_sp();
+            if self.consume(b')') {
+                return Some(items);
+            }
+            match self.parse_bare_item()? {
+                ParamValue::String(v) => items.push(v),
+                _ => return None,
+            }
+            // Parameterized components are not supported.
+            if !matches!(self.peek(), Some(b' ' | b')')) {
+                return None;
+            }
+        }
+    }
+
+    fn parse_params(&mut self) -> Option<Vec<(String, ParamValue)>> {
+        let mut params = Vec::new();
+        while self.consume(b';') {
+            self.skip_sp();
+            let key = self.parse_key()?;
+            let value = if self.consume(b'=') {
+                self.parse_bare_item()?
+            } else {
+                ParamValue::Boolean(true)
+            };
+            params.push((key, value));
+        }
+        Some(params)
+    }
+
+    fn parse_bare_item(&mut self) -> Option<ParamValue> {
+        match self.peek()? {
+            b'"' => {
+                self.pos += 1;
+                let mut v = String::new();
+                loop {
+                    match self.peek()? {
+                        b'\\' => {
+                            self.pos += 1;
+                            match self.peek()? {
+                                c @ (b'"' | b'\\') => v.push(c as char),
+                                _ => return None,
+                            }
+                        }
+                        b'"' => {
+                            self.pos += 1;
+                            return Some(ParamValue::String(v));
+                        }
+                        c @ 0x20..=0x7e => v.push(c as char),
+                        _ => return None,
+                    }
+                    self.pos += 1;
+                }
+            }
+            b'-' | b'0'..=b'9' => {
+                let start = self.pos;
+                self.consume(b'-');
+                let digits = self.take_while(|c| c.is_ascii_digit());
+                if digits.is_empty() || digits.len() > 15 || self.peek() == Some(b'.') {
+                    return None;
+                }
+                std::str::from_utf8(&self.input[start..self.pos])
+                    .ok()?
+                    .parse()
+                    .ok()
+                    .map(ParamValue::Integer)
+            }
+            b':' => {
+                self.pos += 1;
+                let encoded = self
+                    .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, b'+' | b'/' | b'='));
+                if !self.consume(b':') {
+                    return None;
+                }
+                STANDARD.decode(encoded).ok().map(ParamValue::ByteSeq)
+            }
+            b'?' => {
+                self.pos += 1;
+                let v = match self.peek()? {
+                    b'1' => true,
+                    b'0' => false,
+                    _ => return None,
+                };
+                self.pos += 1;
+                Some(ParamValue::Boolean(v))
+            }
+            c if c.is_ascii_alphabetic() || c == b'*' => Some(ParamValue::Token(
+                self.take_while(|c| c.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~:/".contains(&c))
+                    .to_owned(),
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use claims::*;
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case(
+        r#"sig1=("@method" "@target-uri");created=1618884473;keyid="test-key""#,
+        vec![(
+            "sig1",
+            SignatureInput {
+                components: vec!["@method".into(), "@target-uri".into()],
+                params: vec![
+                    ("created".into(), ParamValue::Integer(1618884473)),
+                    ("keyid".into(), ParamValue::String("test-key".into())),
+                ],
+            },
+        )]
+    )]
+    #[case(
+        r#"sig-a=();nonce="a\"b\\c";alg=ed25519;flag;off=?0;bin=:AQI=:, sig-b=("date")"#,
+        vec![
+            (
+                "sig-a",
+                SignatureInput {
+                    components: vec![],
+                    params: vec![
+                        ("nonce".into(), ParamValue::String(r#"a"b\c"#.into())),
+                        ("alg".into(), ParamValue::Token("ed25519".into())),
+                        ("flag".into(), ParamValue::Boolean(true)),
+                        ("off".into(), ParamValue::Boolean(false)),
+                        ("bin".into(), ParamValue::ByteSeq(vec![1, 2])),
+                    ],
+                },
+            ),
+            (
+                "sig-b",
+                SignatureInput {
+                    components: vec!["date".into()],
+                    params: vec![],
+                },
+            ),
+        ]
+    )]
+    fn parse_signature_inputs_works_for_valid_values(
+        #[case] value: &str,
+        #[case] expected: Vec<(&str, SignatureInput)>,
+    ) {
+        let parsed = assert_some!(parse_signature_inputs(value));
+        let expected = expected
+            .into_iter()
+            .map(|(k, v)| (k.to_owned(), v))
+            .collect::<Vec<_>>();
+        assert_eq!(parsed, expected);
+    }
+
+    #[rstest]
+    #[case("sig1")]
+    #[case("sig1=")]
+    #[case(r#"sig1=("@method""#)]
+    #[case(r#"sig1=("@method";req)"#)]
+    #[case(r#"sig1=("@method");created=1.5"#)]
+    #[case(r#"sig1=("@method");keyid="unterminated"#)]
+    #[case(r#"Sig1=("@method")"#)]
+    #[case(r#"sig1=("@method"),"#)]
+    fn parse_signature_inputs_rejects_invalid_values(#[case] value: &str) {
+        assert_none!(parse_signature_inputs(value));
+    }
+
+    #[rstest]
+    #[case("sig1=:AQID:", vec![("sig1", vec![1, 2, 3])])]
+    #[case("sig1=:AQID:;p=1, sig2=::", vec![("sig1", vec![1, 2, 3]), ("sig2", vec![])])]
+    fn parse_signatures_works_for_valid_values(
+        #[case] value: &str,
+        #[case] expected: Vec<(&str, Vec<u8>)>,
+    ) {
+        let parsed = assert_some!(parse_signatures(value));
+        let expected = expected
+            .into_iter()
+            .map(|(k, v)| (k.to_owned(), v))
+            .collect::<Vec<_>>();
+        assert_eq!(parsed, expected);
+    }
+
+    #[rstest]
+    #[case("sig1=AQID")]
+    #[case(r#"sig1="AQID""#)]
+    #[case("sig1=:AQ!D:")]
+    fn parse_signatures_rejects_invalid_values(#[case] value: &str) {
+        assert_none!(parse_signatures(value));
+    }
+
+    #[rstest]
+    #[case(r#"sig1=("@method" "@target-uri");created=1;keyid="k""#)]
+    #[case(r#"sig1=();nonce="a\"b";alg=ed25519;flag;off=?0;bin=:AQI=:"#)]
+    fn serialize_round_trips(#[case] value: &str) {
+        let (_, input) = assert_some!(parse_signature_inputs(value)).remove(0);
+        assert_eq!(format!("sig1={}", input.serialize()), value);
+    }
+
+    // Test vector from RFC 9421, Appendix B.2.1.
+    #[test]
+    fn signature_base_works_for_minimal_signature() {
+        let (_, input) = assert_some!(parse_signature_inputs(
+            r#"sig-b21=();created=1618884473;keyid="test-key-rsa-pss";nonce="b3k2pp5k7z-50gnwp.yemd""#
+        ))
+        .remove(0);
+
+        let base = assert_some!(input.signature_base(
+            &AbsoluteHttpUri::try_new_from("http://example.com/foo?param=Value&Pet=dog")
+                .expect("Must be valid."),
+            &Method::POST,
+            &HeaderMap::new(),
+        ));
+
+        assert_eq!(
+            base,
+            r#""@signature-params": ();created=1618884473;keyid="test-key-rsa-pss";nonce="b3k2pp5k7z-50gnwp.yemd""#
+        );
+    }
+
+    // Test vector from RFC 9421, Appendix B.2.6.
+    #[test]
+    fn signature_base_works_for_covered_fields() {
+        let (_, input) = assert_some!(parse_signature_inputs(
+            r#"sig-b26=("date" "@method" "@path" "@authority" "content-type" "content-length");created=1618884473;keyid="test-key-ed25519""#,
+        ))
+        .remove(0);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("date", "Tue, 20 Apr 2021 02:07:55 GMT".parse().unwrap());
+        headers.insert("content-type", "application/json".parse().unwrap());
+        headers.insert("content-length", "18".parse().unwrap());
+
+        let base = assert_some!(input.signature_base(
+            &AbsoluteHttpUri::try_new_from("http://example.com/foo?param=Value&Pet=dog")
+                .expect("Must be valid."),
+            &Method::POST,
+            &headers,
+        ));
+
+        assert_eq!(
+            base,
+            [
+                r#""date": Tue, 20 Apr 2021 02:07:55 GMT"#,
+                r#""@method": POST"#,
+                r#""@path": /foo"#,
+                r#""@authority": example.com"#,
+                r#""content-type": application/json"#,
+                r#""content-length": 18"#,
+                r#""@signature-params": ("date" "@method" "@path" "@authority" "content-type" "content-length");created=1618884473;keyid="test-key-ed25519""#,
+            ]
+            .join("\n")
+        );
+    }
+
+    #[rstest]
+    #[case(r#"sig1=("x-absent")"#)]
+    #[case(r#"sig1=("@unknown")"#)]
+    fn signature_base_fails_for_unresolvable_components(#[case] value: &str) {
+        let (_, input) = assert_some!(parse_signature_inputs(value)).remove(0);
+        assert_none!(input.signature_base(
+            &AbsoluteHttpUri::try_new_from("http://example.com/").expect("Must be valid."),
+            &Method::GET,
+            &HeaderMap::new(),
+        ));
+    }
+}
@@ -0,0 +1,877 @@
+//! I define an implementation of authentication scheme
+//! that verifies http message signatures, as specified in
+//! [RFC 9421](https://www.rfc-editor.org/rfc/rfc9421).
+//!
+//! Clients indicate the scheme with an `Authorization`
+//! header like `HttpSig proof=sig1`, where `proof`
+//! parameter is the label of the signature to be verified.
+//! The `keyid` signature parameter must be the uri of the
+//! key, described in the agent's webid profile document.
+//!
+//! Every signature must carry a `nonce` parameter, which is
+//! remembered for the signature validity window, to reject
+//! replayed signatures. Signatures of requests that may have
+//! a body must also cover the `content-digest` field. As
+//! signatures don't cover the body itself, services must be
+//! layered with [`ContentDigestVerifyingLayer`](content_digest::ContentDigestVerifyingLayer)
+//! for the field to be verified against the body.
+//!
+
+use std::{
+    fmt::Debug,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use dpop::proof::payload::jkt::Jkt;
+use dyn_problem::{type_::UNKNOWN_IO_ERROR, Problem};
+use either::Either;
+use futures::future::BoxFuture;
+use http::{header::AUTHORIZATION, HeaderMap, HeaderName, HeaderValue, Method};
+use http_typed_headers::{
+    common::field::rules::{
+        parameter::FieldParameter, parameter_name::FieldParameterName,
+        parameter_value::FieldParameterValue, parameters::FieldParameters, token::Token,
+    },
+    www_authenticate::{Challenge, WWWAuthenticate},
+};
+use http_uri::{
+    invariant::AbsoluteHttpUri, security::transport_policy::LocalhostExemptingSTP, HttpUri,
+};
+use once_cell::sync::Lazy;
+use picky::{hash::HashAlgorithm, signature::SignatureAlgorithm};
+use tracing::error;
+use unicase::Ascii;
+use webid::profile_req_agent::ProfileDocResolutionError;
+
+use self::{
+    key_resolver::{
+        impl_::default::DefaultAgentKeyResolver, AgentKey, AgentKeyResolutionError,
+        AgentKeyResolver,
+    },
+    message::{parse_signature_inputs, parse_signatures, ParamValue, SignatureInput},
+    nonce_cache::SignatureNonceReplayCache,
+};
+use crate::{
+    challenge_response_framework::scheme::{
+        CRAuthenticationChallenge, CRAuthenticationScheme, CRResolutionResult,
+    },
+    common::{
+        credentials::impl_::basic::{BasicAgentCredentials, BasicRequestCredentials},
+        replay_cache::ReplayCacheError,
+    },
+};
+
+pub mod content_digest;
+pub mod key_resolver;
+pub mod message;
+pub mod nonce_cache;
+
+/// Name of the authentication scheme.
+pub const HTTPSIG_SCHEME: &str = "HttpSig";
+
+/// Scheme name static.
+static SCHEME_NAME: Lazy<Ascii<Token>> =
+    Lazy::new(|| HTTPSIG_SCHEME.parse().expect("Must be a valid token."));
+
+/// `Signature-Input` header name.
+static SIGNATURE_INPUT: HeaderName = HeaderName::from_static("signature-input");
+
+/// `Signature` header name.
+static SIGNATURE: HeaderName = HeaderName::from_static("signature");
+
+/// `Accept-Signature` header name.
+static ACCEPT_SIGNATURE: HeaderName = HeaderName::from_static("accept-signature");
+
+/// Algs field param name for challenge.
+static FPN_ALGS: Lazy<FieldParameterName> =
+    Lazy::new(|| "algs".parse().expect("Must be a valid name."));
+
+/// Error field param name for challenge.
+static FPN_ERROR: Lazy<FieldParameterName> =
+    Lazy::new(|| "error".parse().expect("Must be a valid name."));
+
+/// Error description field param name for challenge.
+static FPN_ERROR_DESCR: Lazy<FieldParameterName> =
+    Lazy::new(|| "error_description".parse().expect("Must be a valid name."));
+
+/// Supported algs as field param value for challenge.
+static FPV_SUPPORTED_ALGS: Lazy<FieldParameterValue> = Lazy::new(|| {
+    "ed25519 ecdsa-p256-sha256 ecdsa-p384-sha384 rsa-v1_5-sha256"
+        .try_into()
+        .expect("Must be a valid value.")
+});
+
+/// `invalid_request` field param value for challenge.
+static FPV_INVALID_REQ: Lazy<FieldParameterValue> = Lazy::new(|| {
+    "invalid_request"
+        .try_into()
+        .expect("Must be a valid value.")
+});
+
+/// `invalid_signature` field param value for challenge.
+static FPV_INVALID_SIGNATURE: Lazy<FieldParameterValue> = Lazy::new(|| {
+    "invalid_signature"
+        .try_into()
+        .expect("Must be a valid value.")
+});
+
+/// Value of `Accept-Signature` header, that is sent with
+/// challenges.
+static ACCEPT_SIGNATURE_VALUE: HeaderValue =
+    HeaderValue::from_static(r#"sig1=("@method" "@target-uri");created;keyid;nonce"#);
+
+/// Components, that every signature must cover.
+const REQUIRED_COMPONENTS: &[&str] = &["@method", "@target-uri"];
+
+/// Default maximum number of nonces, that are remembered.
+const DEFAULT_MAX_REMEMBERED_NONCES: usize = 100_000;
+
+/// An enum of supported signature algorithms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpSigAlg {
+    /// `ed25519` algorithm.
+    Ed25519,
+
+    /// `ecdsa-p256-sha256` algorithm.
+    EcdsaP256Sha256,
+
+    /// `ecdsa-p384-sha384` algorithm.
+    EcdsaP384Sha384,
+
+    /// `rsa-v1_5-sha256` algorithm.
+    RsaV15Sha256,
+}
+
+impl HttpSigAlg {
+    /// Resolve the algorithm from given registered name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "ed25519" => Self::Ed25519,
+            "ecdsa-p256-sha256" => Self::EcdsaP256Sha256,
+            "ecdsa-p384-sha384" => Self::EcdsaP384Sha384,
+            "rsa-v1_5-sha256" => Self::RsaV15Sha256,
+            _ => return None,
+        })
+    }
+
+    /// Infer the algorithm from given key.
+    pub fn infer_for_key(key: &AgentKey) -> Option<Self> {
+        Some(match (key.kty.as_str(), key.crv.as_deref()) {
+            ("OKP", Some("Ed25519")) => Self::Ed25519,
+            ("EC", Some("P-256")) => Self::EcdsaP256Sha256,
+            ("EC", Some("P-384")) => Self::EcdsaP384Sha384,
+            ("RSA", _) => Self::RsaV15Sha256,
+            _ => return None,
+        })
+    }
+
+    /// Check if algorithm can be used with given key.
+    #[inline]
+    pub fn is_compatible_with(&self, key: &AgentKey) -> bool {
+        Self::infer_for_key(key) == Some(*self)
+    }
+
+    /// Verify given signature over given message with given
+    /// key.
+    pub fn verify(&self, key: &AgentKey, msg: &[u8], signature: &[u8]) -> bool {
+        let Ok(public_key) = key.jwk.to_public_key() else {
+            error!("Key jwk cannot be converted to public key.");
+            return false;
+        };
+
+        let (algorithm, signature) = match self {
+            Self::Ed25519 => (SignatureAlgorithm::Ed25519, signature.to_vec()),
+            Self::RsaV15Sha256 => (
+                SignatureAlgorithm::RsaPkcs1v15(HashAlgorithm::SHA2_256),
+                signature.to_vec(),
+            ),
+            Self::EcdsaP256Sha256 | Self::EcdsaP384Sha384 => {
+                let (hash_algo, scalar_len) = if *self == Self::EcdsaP256Sha256 {
+                    (HashAlgorithm::SHA2_256, 32)
+                } else {
+                    (HashAlgorithm::SHA2_384, 48)
+                };
+                // Http message signatures encode ecdsa
+                // signatures as raw `r || s`.
+                if signature.len() != 2 * scalar_len {
+                    return false;
+                }
+                (
+                    SignatureAlgorithm::Ecdsa(hash_algo),
+                    ecdsa_raw_to_der(signature),
+                )
+            }
+        };
+
+        algorithm.verify(&public_key, msg, &signature).is_ok()
+    }
+}
+
+/// Encode given raw `r || s` ecdsa signature in der.
+fn ecdsa_raw_to_der(raw: &[u8]) -> Vec<u8> {
+    let (r, s) = raw.split_at(raw.len() / 2);
+
+    let encode_int = |v: &[u8]| {
+        let v = &v[v.iter().take_while(|b| **b == 0).count().min(v.len() - 1)..];
+        let mut out = vec![0x02];
+        if v[0] & 0x80 != 0 {
+            out.push(v.len() as u8 + 1);
+            out.push(0);
+        } else {
+            out.push(v.len() as u8);
+        }
+        out.extend_from_slice(v);
+        out
+    };
+
+    let body = [encode_int(r), encode_int(s)].concat();
+    // Body length is always less than 128 for supported
+    // curves.
+    let mut out = vec![0x30, body.len() as u8];
+    out.extend(body);
+    out
+}
+
+/// An implementation of authentication scheme that verifies
+/// http message signatures, with keys resolved from agents'
+/// webid profiles.
+#[derive(Debug)]
+pub struct HttpSigScheme<Resolver> {
+    /// Agent key resolver.
+    pub key_resolver: Arc<Resolver>,
+
+    /// Maximum age of a signature, as indicated by it's
+    /// `created` parameter.
+    pub max_signature_age: Duration,
+
+    /// Signature timestamp verification leeway.
+    pub time_leeway: Duration,
+
+    /// Cache of seen signature nonces. It's entries live for
+    /// `max_signature_age + 2 * time_leeway`.
+    pub nonce_cache: SignatureNonceReplayCache,
+}
+
+impl<Resolver> Clone for HttpSigScheme<Resolver> {
+    fn clone(&self) -> Self {
+        Self {
+            key_resolver: self.key_resolver.clone(),
+            max_signature_age: self.max_signature_age,
+            time_leeway: self.time_leeway,
+            nonce_cache: self.nonce_cache.clone(),
+        }
+    }
+}
+
+impl<Resolver: AgentKeyResolver> HttpSigScheme<Resolver> {
+    /// Resolve credentials for given request.
+    #[tracing::instrument(
+        skip_all,
+        name = "HttpSigScheme::resolve_credentials",
+        fields(uri, method)
+    )]
+    async fn resolve_credentials(
+        self,
+        uri: AbsoluteHttpUri,
+        method: Method,
+        headers: HeaderMap,
+        opt_label: Option<String>,
+    ) -> CRResolutionResult<BasicRequestCredentials> {
+        let (signature_input, signature) = Self::resolve_signature(&headers, opt_label)?;
+
+        // Verify signature parameters.
+        if let Some(component) = REQUIRED_COMPONENTS
+            .iter()
+            .find(|c| !signature_input.covers(c))
+        {
+            error!("Signature doesn't cover required component {}.", component);
+            return Err(Self::challenge(
+                Some(&*FPV_INVALID_REQ),
+                Some("Signature must cover @method and @target-uri components."),
+            ));
+        }
+
+        // Signature of a request with body must cover the
+        // body digest.
+        if content_digest::may_have_body(&method, &headers)
+            && !signature_input.covers(content_digest::CONTENT_DIGEST.as_str())
+        {
+            error!("Signature doesn't cover content-digest of request with body.");
+            return Err(Self::challenge(
+                Some(&*FPV_INVALID_REQ),
+                Some("Signature must cover content-digest of request with body."),
+            ));
+        }
+
+        self.verify_timestamps(&signature_input)?;
+
+        let nonce = signature_input
+            .param("nonce")
+            .and_then(ParamValue::as_string)
+            .filter(|v| !v.is_empty())
+            .map(ToOwned::to_owned)
+            .ok_or_else(|| {
+                error!("Invalid or absent nonce signature parameter.");
+                Self::challenge(
+                    Some(&*FPV_INVALID_REQ),
+                    Some("nonce parameter is required."),
+                )
+            })?;
+
+        let key_id = signature_input
+            .param("keyid")
+            .and_then(ParamValue::as_string)
+            .and_then(|v| HttpUri::try_from(v).ok())
+            .ok_or_else(|| {
+                error!("Invalid or absent keyid signature parameter.");
+                Self::challenge(
+                    Some(&*FPV_INVALID_REQ),
+                    Some("keyid parameter must be an http uri."),
+                )
+            })?;
+
+        // Resolve the key.
+        let key = self
+            .key_resolver
+            .resolve(key_id.clone())
+            .await
+            .map_err(Self::map_key_resolution_error)?;
+
+        // Resolve the algorithm.
+        let alg = match signature_input.param("alg") {
+            Some(alg_param) => alg_param
+                .as_string()
+                .and_then(HttpSigAlg::from_name)
+                .filter(|alg| alg.is_compatible_with(&key))
+                .ok_or_else(|| {
+                    error!("Unsupported alg, or alg incompatible with key.");
+                    Self::challenge(
+                        Some(&*FPV_INVALID_REQ),
+                        Some("Unsupported alg, or alg incompatible with key."),
+                    )
+                })?,
+            None => HttpSigAlg::infer_for_key(&key).ok_or_else(|| {
+                error!("Unsupported key type.");
+                Self::challenge(Some(&*FPV_INVALID_REQ), Some("Unsupported key type."))
+            })?,
+        };
+
+        // Construct the signature base.
+        let signature_base = signature_input
+            .signature_base(&uri, &method, &headers)
+            .ok_or_else(|| {
+                error!("Cannot construct signature base.");
+                Self::challenge(
+                    Some(&*FPV_INVALID_REQ),
+                    Some("Covered components are either absent or unsupported."),
+                )
+            })?;
+
+        // Verify the signature.
+        if !alg.verify(&key, signature_base.as_bytes(), &signature) {
+            error!("Invalid signature.");
+            return Err(Self::challenge(
+                Some(&*FPV_INVALID_SIGNATURE),
+                Some("Signature verification failed."),
+            ));
+        }
+
+        // Reject replayed signatures. Nonces are recorded only
+        // after verification, so that forged signatures
+        // cannot exhaust them.
+        // If the cache is full, signature is rejected, as it
+        // cannot be checked for replay.
+        self.nonce_cache
+            .record(
+                key_id.as_ref(),
+                &nonce,
+                self.max_signature_age + 2 * self.time_leeway,
+            )
+            .map_err(|e| {
+                error!("Signature nonce is rejected by nonce cache. Error:\n {}", e);
+                Self::challenge(
+                    Some(&*FPV_INVALID_SIGNATURE),
+                    Some(match e {
+                        ReplayCacheError::Replayed => "Signature is replayed.",
+                        ReplayCacheError::Full => {
+                            "Signature cannot be checked for replay. Retry later."
+                        }
+                    }),
+                )
+            })?;
+
+        Ok(BasicRequestCredentials {
+            of_agent: Some(BasicAgentCredentials {
+                bound_key_jkt: Some(Jkt::new(&key.jwk).into()),
+                webid: key.webid,
+            }),
+            of_client: None,
+            of_issuer: None,
+            of_vcs: Vec::new(),
+        })
+    }
+
+    /// Resolve the signature input and signature with given
+    /// label.
+    fn resolve_signature(
+        headers: &HeaderMap,
+        opt_label: Option<String>,
+    ) -> CRResolutionResult<(SignatureInput, Vec<u8>)> {
+        let invalid_req = |descr| {
+            error!("{}", descr);
+            Self::challenge(Some(&*FPV_INVALID_REQ), Some(descr))
+        };
+
+        let mut signature_inputs = header_value_str(headers, &SIGNATURE_INPUT)
+            .and_then(|v| parse_signature_inputs(&v))
+            .ok_or_else(|| invalid_req("Invalid or absent Signature-Input header."))?;
+
+        let signatures = header_value_str(headers, &SIGNATURE)
+            .and_then(|v| parse_signatures(&v))
+            .ok_or_else(|| invalid_req("Invalid or absent Signature header."))?;
+
+        let label = match opt_label {
+            Some(label) => label,
+            // If there is a single signature, it is the proof.
+            None if signature_inputs.len() == 1 => signature_inputs[0].0.clone(),
+            None => return Err(invalid_req("Proof signature label is not specified.")),
+        };
+
+        let signature_input = signature_inputs
+            .drain(..)
+            .find_map(|(k, v)| (k == label).then_some(v))
+            .ok_or_else(|| invalid_req("No signature input with proof label."))?;
+
+        let signature = signatures
+            .into_iter()
+            .find_map(|(k, v)| (k == label).then_some(v))
+            .ok_or_else(|| invalid_req("No signature with proof label."))?;
+
+        Ok((signature_input, signature))
+    }
+
+    /// Verify signature timestamps.
+    fn verify_timestamps(&self, signature_input: &SignatureInput) -> CRResolutionResult<()> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards.")
+            .as_secs() as i64;
+        let leeway = self.time_leeway.as_secs() as i64;
+        let max_age = self.max_signature_age.as_secs() as i64;
+
+        let created = signature_input
+            .param("created")
+            .and_then(ParamValue::as_integer)
+            .ok_or_else(|| {
+                error!("Invalid or absent created signature parameter.");
+                Self::challenge(
+                    Some(&*FPV_INVALID_REQ),
+                    Some("created parameter is required."),
+                )
+            })?;
+
+        if created > now + leeway || created < now - max_age - leeway {
+            error!("Signature creation time is out of window.");
+            return Err(Self::challenge(
+                Some(&*FPV_INVALID_SIGNATURE),
+                Some("Signature creation time is out of window."),
+            ));
+        }
+
+        if let Some(expires) = signature_input.param("expires") {
+            if expires
+                .as_integer()
+                .map_or(true, |expires| expires < now - leeway)
+            {
+                error!("Signature expired.");
+                return Err(Self::challenge(
+                    Some(&*FPV_INVALID_SIGNATURE),
+                    Some("Signature expired."),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Map key resolution error.
+    fn map_key_resolution_error(
+        e: Arc<AgentKeyResolutionError>,
+    ) -> Either<CRAuthenticationChallenge, Problem> {
+        let err_descr = match e.as_ref() {
+            AgentKeyResolutionError::ProfileDocResolutionError(ie) => match ie {
+                ProfileDocResolutionError::InvalidDerefResponse => {
+                    "Invalid key profile deref response."
+                }
+                ProfileDocResolutionError::InvalidProfileContent => "Invalid key profile content.",
+                ProfileDocResolutionError::UnknownIoError(_) => {
+                    error!("Unknown io error in resolving key profile.");
+                    return Either::Right(UNKNOWN_IO_ERROR.new_problem());
+                }
+            },
+            AgentKeyResolutionError::InsecureKeyId => "Key id is insecure.",
+            AgentKeyResolutionError::UnlinkedKey => {
+                "Key is not linked from any webid in it's profile document."
+            }
+            AgentKeyResolutionError::InvalidKeyJwk => "Key is not described with a valid jwk.",
+        };
+        error!("Error in resolving key. {}", err_descr);
+        Self::challenge(Some(&*FPV_INVALID_SIGNATURE), Some(err_descr))
+    }
+
+    /// Return a challenge with given params.
+    fn challenge(
+        error: Option<&FieldParameterValue>,
+        error_descr: Option<&str>,
+    ) -> Either<CRAuthenticationChallenge, Problem> {
+        // Challenge's ext-params.
+        let mut ext_params = vec![FieldParameter {
+            name: (*FPN_ALGS).clone(),
+            value: (*FPV_SUPPORTED_ALGS).clone(),
+        }];
+
+        if let Some(error) = error {
+            ext_params.push(FieldParameter {
+                name: (*FPN_ERROR).clone(),
+                value: error.clone(),
+            });
+        }
+
+        if let Some(error_descr) = error_descr.and_then(|v| FieldParameterValue::try_from(v).ok()) {
+            ext_params.push(FieldParameter {
+                name: (*FPN_ERROR_DESCR).clone(),
+                value: error_descr,
+            });
+        }
+
+        // Signal the signature requirements.
+        // See: <https://www.rfc-editor.org/rfc/rfc9421#section-5.1>
+        let mut ext_headers = HeaderMap::new();
+        ext_headers.insert(ACCEPT_SIGNATURE.clone(), ACCEPT_SIGNATURE_VALUE.clone());
+
+        Either::Left(CRAuthenticationChallenge {
+            www_authenticate: WWWAuthenticate {
+                challenges: vec![Challenge {
+                    auth_scheme: (*SCHEME_NAME).clone(),
+                    ext_info: Either::Right(FieldParameters::new(ext_params)),
+                }],
+            },
+            ext_headers,
+        })
+    }
+}
+
+/// Get the combined value of the header with given name.
+fn header_value_str(headers: &HeaderMap, name: &HeaderName) -> Option<String> {
+    let values = headers
+        .get_all(name)
+        .iter()
+        .map(|v| v.to_str())
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    (!values.is_empty()).then(|| values.join(", "))
+}
+
+/// Resolve the proof label from the `Authorization`
+/// header, if it is of this scheme.
+///
+/// Returns `None` if header is not of this scheme, and
+/// `Some(None)` if the header doesn't specify the label.
+fn resolve_proof_label(headers: &HeaderMap) -> Option<Option<String>> {
+    let value = headers.get(AUTHORIZATION)?.to_str().ok()?.trim();
+    let (scheme, params) = value.split_once(' ').unwrap_or((value, ""));
+
+    if !scheme.eq_ignore_ascii_case(HTTPSIG_SCHEME) {
+        return None;
+    }
+
+    Some(params.split(',').find_map(|param| {
+        let (k, v) = param.trim().split_once('=')?;
+        k.trim()
+            .eq_ignore_ascii_case("proof")
+            .then(|| v.trim().trim_matches('"').to_owned())
+    }))
+}
+
+impl<Resolver: AgentKeyResolver> CRAuthenticationScheme for HttpSigScheme<Resolver> {
+    type Credentials = BasicRequestCredentials;
+
+    fn resolve_or_challenge(
+        &self,
+        uri: &AbsoluteHttpUri,
+        method: &Method,
+        headers: &HeaderMap,
+    ) -> BoxFuture<'static, CRResolutionResult<BasicRequestCredentials>> {
+        let opt_label = resolve_proof_label(headers).ok_or_else(|| {
+            error!("No Authorization header for this scheme.");
+            Self::challenge(None, None)
+        });
+
+        let this = self.clone();
+        let uri = uri.clone();
+        let method = method.clone();
+        let headers = headers.clone();
+
+        Box::pin(async move {
+            this.resolve_credentials(uri, method, headers, opt_label?)
+                .await
+                // Convert any io problems to re-challenges.
+                .map_err(|e| match e {
+                    Either::Left(challenge) => Either::Left(challenge),
+                    Either::Right(problem) => {
+                        if UNKNOWN_IO_ERROR.is_type_of(&problem) {
+                            Self::challenge(
+                                Some(&*FPV_INVALID_SIGNATURE),
+                                Some("Unknown io error."),
+                            )
+                        } else {
+                            Either::Right(problem)
+                        }
+                    }
+                })
+        })
+    }
+}
+
+/// Type of default http signature scheme.
+pub type DefaultHttpSigScheme = HttpSigScheme<DefaultAgentKeyResolver<LocalhostExemptingSTP>>;
+
+impl<Resolver: AgentKeyResolver + Default> Default for HttpSigScheme<Resolver> {
+    fn default() -> Self {
+        let max_signature_age = Duration::from_secs(300);
+        let time_leeway = Duration::from_secs(60);
+        Self {
+            key_resolver: Default::default(),
+            max_signature_age,
+            time_leeway,
+            nonce_cache: SignatureNonceReplayCache::new(DEFAULT_MAX_REMEMBERED_NONCES),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use claims::*;
+    use rstest::*;
+
+    use super::*;
+
+    /// Signature base of the request in RFC 9421, Appendix
+    /// B.2.6.
+    const RFC_B26_SIGNATURE_BASE: &str = concat!(
+        r#""date": Tue, 20 Apr 2021 02:07:55 GMT"#,
+        "\n",
+        r#""@method": POST"#,
+        "\n",
+        r#""@path": /foo"#,
+        "\n",
+        r#""@authority": example.com"#,
+        "\n",
+        r#""content-type": application/json"#,
+        "\n",
+        r#""content-length": 18"#,
+        "\n",
+        r#""@signature-params": ("date" "@method" "@path" "@authority" "content-type" "content-length");created=1618884473;keyid="test-key-ed25519""#,
+    );
+
+    /// Signature of the request in RFC 9421, Appendix B.2.6.
+    const RFC_B26_SIGNATURE: &str =
+        "wqcAqbmYJ2ji2glfAMaRy4gruYYnx2nEFN2HN6jrnDnQCK1u02Gb04v9EDgwUPiu4A0w6vuQv5lIp5WPpBKRCw==";
+
+    /// Get the `test-key-ed25519` key from RFC 9421, Appendix
+    /// B.1.4.
+    fn rfc_ed25519_key() -> AgentKey {
+        AgentKey {
+            webid: "https://example.com/profile#me"
+                .parse()
+                .expect("Must be valid."),
+            jwk: serde_json::from_str(
+                r#"{"kty":"OKP","crv":"Ed25519","x":"JrQLj5P_89iXES9-vFgrIy29clF9CC_oPPsw3c5D0bs"}"#,
+            )
+            .expect("Must be valid."),
+            kty: "OKP".into(),
+            crv: Some("Ed25519".into()),
+        }
+    }
+
+    #[rstest]
+    #[case(RFC_B26_SIGNATURE_BASE, true)]
+    #[case(&RFC_B26_SIGNATURE_BASE.replace("/foo", "/bar"), false)]
+    fn ed25519_verification_works_for_rfc_vector(#[case] base: &str, #[case] expected: bool) {
+        use base64::{engine::general_purpose::STANDARD, Engine};
+
+        let signature = STANDARD.decode(RFC_B26_SIGNATURE).expect("Must be valid.");
+        assert_eq!(
+            HttpSigAlg::Ed25519.verify(&rfc_ed25519_key(), base.as_bytes(), &signature),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(HttpSigAlg::Ed25519, true)]
+    #[case(HttpSigAlg::EcdsaP256Sha256, false)]
+    #[case(HttpSigAlg::RsaV15Sha256, false)]
+    fn alg_compatibility_works_correctly(#[case] alg: HttpSigAlg, #[case] expected: bool) {
+        assert_eq!(alg.is_compatible_with(&rfc_ed25519_key()), expected);
+    }
+
+    #[rstest]
+    #[case(&[0, 1], &[0, 3], &[0x30, 6, 0x02, 1, 1, 0x02, 1, 3])]
+    #[case(&[1, 2], &[3, 4], &[0x30, 8, 0x02, 2, 1, 2, 0x02, 2, 3, 4])]
+    #[case(&[0, 0x80], &[0, 0], &[0x30, 7, 0x02, 2, 0, 0x80, 0x02, 1, 0])]
+    fn ecdsa_raw_to_der_works_correctly(
+        #[case] r: &[u8],
+        #[case] s: &[u8],
+        #[case] expected: &[u8],
+    ) {
+        assert_eq!(ecdsa_raw_to_der(&[r, s].concat()), expected);
+    }
+
+    #[rstest]
+    #[case("HttpSig proof=sig1", Some(Some("sig1")))]
+    #[case(r#"httpsig proof="sig-b26""#, Some(Some("sig-b26")))]
+    #[case("HttpSig", Some(None))]
+    #[case("DPoP abc", None)]
+    fn resolve_proof_label_works_correctly(
+        #[case] authorization: &str,
+        #[case] expected: Option<Option<&str>>,
+    ) {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            authorization.parse().expect("Must be valid."),
+        );
+        assert_eq!(
+            resolve_proof_label(&headers),
+            expected.map(|v| v.map(ToOwned::to_owned))
+        );
+    }
+
+    #[test]
+    fn nonce_cache_rejects_replayed_nonces() {
+        let cache = SignatureNonceReplayCache::new(10);
+        let ttl = Duration::from_secs(60);
+
+        assert_ok!(cache.record("https://example.com/key1", "n1", ttl));
+        assert_err_eq!(
+            cache.record("https://example.com/key1", "n1", ttl),
+            ReplayCacheError::Replayed
+        );
+        assert_ok!(cache.record("https://example.com/key1", "n2", ttl));
+        assert_ok!(cache.record("https://example.com/key2", "n1", ttl));
+    }
+
+    #[test]
+    fn full_nonce_cache_rejects_new_nonces() {
+        let cache = SignatureNonceReplayCache::new(1);
+        let ttl = Duration::from_secs(60);
+
+        assert_ok!(cache.record("https://example.com/key1", "n1", ttl));
+        assert_err_eq!(
+            cache.record("https://example.com/key1", "n2", ttl),
+            ReplayCacheError::Full
+        );
+    }
+
+    #[rstest]
+    #[case(Method::POST, &[], true)]
+    #[case(Method::GET, &[], false)]
+    #[case(Method::GET, &[("content-length", "0")], false)]
+    #[case(Method::DELETE, &[("content-length", "3")], true)]
+    #[case(Method::DELETE, &[("transfer-encoding", "chunked")], true)]
+    fn may_have_body_works_correctly(
+        #[case] method: Method,
+        #[case] headers: &[(&'static str, &'static str)],
+        #[case] expected: bool,
+    ) {
+        let headers = headers
+            .iter()
+            .map(|(k, v)| {
+                (
+                    HeaderName::from_static(k),
+                    HeaderValue::from_str(v).expect("Must be valid."),
+                )
+            })
+            .collect::<HeaderMap>();
+        assert_eq!(content_digest::may_have_body(&method, &headers), expected);
+    }
+
+    /// A key resolver, that always resolves to the rfc test
+    /// key.
+    #[derive(Debug, Default)]
+    struct MockKeyResolver;
+
+    impl AgentKeyResolver for MockKeyResolver {
+        fn resolve(
+            &self,
+            _key_id: HttpUri,
+        ) -> BoxFuture<'_, Result<AgentKey, Arc<AgentKeyResolutionError>>> {
+            Box::pin(async { Ok(rfc_ed25519_key()) })
+        }
+    }
+
+    #[rstest]
+    #[case::absent_nonce(
+        Method::GET,
+        r#"sig1=("@method" "@target-uri");created={now};keyid="https://example.com/key""#,
+        "nonce parameter is required."
+    )]
+    #[case::uncovered_content_digest(
+        Method::POST,
+        r#"sig1=("@method" "@target-uri");created={now};keyid="https://example.com/key";nonce="n1""#,
+        "Signature must cover content-digest of request with body."
+    )]
+    #[case::stale_signature(
+        Method::GET,
+        r#"sig1=("@method" "@target-uri");created=1618884473;keyid="https://example.com/key";nonce="n1""#,
+        "Signature creation time is out of window."
+    )]
+    #[case::invalid_signature(
+        Method::POST,
+        r#"sig1=("@method" "@target-uri" "content-digest");created={now};keyid="https://example.com/key";nonce="n1""#,
+        "Signature verification failed."
+    )]
+    #[tokio::test]
+    async fn scheme_rejects_invalid_signatures(
+        #[case] method: Method,
+        #[case] signature_input: &str,
+        #[case] expected_descr: &str,
+    ) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Must be valid.")
+            .as_secs();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_static("HttpSig proof=sig1"),
+        );
+        headers.insert(
+            SIGNATURE_INPUT.clone(),
+            signature_input
+                .replace("{now}", &now.to_string())
+                .parse()
+                .expect("Must be valid."),
+        );
+        headers.insert(SIGNATURE.clone(), HeaderValue::from_static("sig1=:AQID:"));
+        headers.insert(
+            content_digest::CONTENT_DIGEST.clone(),
+            HeaderValue::from_static("sha-256=:47DEQpj8HBSa+/TImW+5JEeuQeRkm5NMpJWZG3hSuFU=:"),
+        );
+
+        let scheme = HttpSigScheme::<MockKeyResolver>::default();
+        let result = scheme
+            .resolve_or_challenge(
+                &AbsoluteHttpUri::try_new_from("https://example.com/foo").expect("Must be valid."),
+                &method,
+                &headers,
+            )
+            .await;
+
+        let challenge = assert_some!(assert_err!(result).left());
+        let challenge_str = format!("{:?}", challenge.www_authenticate);
+        assert!(challenge_str.contains(expected_descr), "{}", challenge_str);
+    }
+}
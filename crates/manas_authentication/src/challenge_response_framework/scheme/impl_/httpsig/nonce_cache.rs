@@ -0,0 +1,45 @@
+//! I define a bounded cache of seen signature `nonce`s, to
+//! detect replayed signatures.
+//!
+//! See: <https://www.rfc-editor.org/rfc/rfc9421#section-7.2.2>
+//!
+
+use std::time::Duration;
+
+use crate::common::replay_cache::{ReplayCache, ReplayCacheError};
+
+/// A bounded cache of seen signature `nonce`s, scoped by
+/// the signing keys.
+///
+/// Entries must live at least as long as the window in which
+/// a signature is considered fresh, so that a signature
+/// cannot be replayed within it's validity window. Once the
+/// cache is full, new signatures are rejected, until seen
+/// nonces expire.
+#[derive(Debug, Clone)]
+pub struct SignatureNonceReplayCache {
+    /// Seen key scoped nonces.
+    seen: ReplayCache<(String, String)>,
+}
+
+impl SignatureNonceReplayCache {
+    /// Create a new [`SignatureNonceReplayCache`] with given
+    /// max capacity.
+    pub fn new(max_capacity: usize) -> Self {
+        Self {
+            seen: ReplayCache::new(max_capacity),
+        }
+    }
+
+    /// Record given nonce of the signature by the key with
+    /// given id as seen, for given time to live.
+    pub fn record(
+        &self,
+        key_id: &str,
+        nonce: &str,
+        time_to_live: Duration,
+    ) -> Result<(), ReplayCacheError> {
+        self.seen
+            .record((key_id.to_owned(), nonce.to_owned()), time_to_live)
+    }
+}
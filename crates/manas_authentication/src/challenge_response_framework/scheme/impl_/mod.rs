@@ -6,5 +6,5 @@ pub mod union;
 #[cfg(feature = "scheme-impl-solid-oidc")]
 pub mod solid_oidc;
 
-// #[cfg(feature = "scheme-impl-httpsig")]
-// pub mod httpsig;
+#[cfg(feature = "scheme-impl-httpsig")]
+pub mod httpsig;
@@ -0,0 +1,115 @@
+//! I define a bounded cache of seen dpop-proof `jti`s, to
+//! detect replayed proofs.
+//!
+//! See: <https://datatracker.ietf.org/doc/html/rfc9449#section-11.1>
+//!
+
+use std::time::Duration;
+
+use crate::common::replay_cache::{ReplayCache, ReplayCacheError};
+
+/// Margin added to the freshness window of proofs, to
+/// resolve the time to live of seen jtis.
+const JTI_TTL_MARGIN: Duration = Duration::from_secs(5);
+
+/// A bounded cache of seen dpop-proof `jti`s.
+///
+/// Entries live as long as the window in which a proof is
+/// considered fresh, so that a proof cannot be replayed
+/// within it's validity window. Jtis are recorded per
+/// thumbprint of the key that signed the proof, and each
+/// key can hold only a bounded share of the cache. Once a
+/// key's share, or the cache is full, new proofs are
+/// rejected, until seen jtis expire.
+#[derive(Debug, Clone)]
+pub struct DPoPJtiReplayCache {
+    /// Seen jtis, partitioned by key thumbprint.
+    seen: ReplayCache<String, String>,
+}
+
+impl DPoPJtiReplayCache {
+    /// Create a new [`DPoPJtiReplayCache`] with given max
+    /// capacity, and max capacity for each key.
+    pub fn new(max_capacity: usize, max_capacity_per_key: usize) -> Self {
+        Self {
+            seen: ReplayCache::new_partitioned(max_capacity, max_capacity_per_key),
+        }
+    }
+
+    /// Record given jti of a proof signed by key with given
+    /// thumbprint, and verified with given time leeway as
+    /// seen.
+    ///
+    /// Proofs are fresh within the leeway on either side of
+    /// their `iat`. Hence jti is remembered for twice the
+    /// leeway, with a margin.
+    pub fn record(
+        &self,
+        jkt: &str,
+        jti: &str,
+        time_leeway: Duration,
+    ) -> Result<(), ReplayCacheError> {
+        self.seen.record_in(
+            jkt.to_owned(),
+            jti.to_owned(),
+            2 * time_leeway + JTI_TTL_MARGIN,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use claims::*;
+
+    use super::*;
+
+    #[test]
+    fn replayed_jti_is_rejected() {
+        let cache = DPoPJtiReplayCache::new(10, 10);
+        let leeway = Duration::from_secs(120);
+
+        assert_ok!(cache.record("jkt", "jti-1", leeway));
+        assert_err_eq!(
+            cache.record("jkt", "jti-1", leeway),
+            ReplayCacheError::Replayed
+        );
+        assert_ok!(cache.record("jkt", "jti-2", leeway));
+    }
+
+    #[test]
+    fn jti_is_remembered_beyond_twice_the_leeway() {
+        let cache = DPoPJtiReplayCache::new(10, 10);
+        let leeway = Duration::from_millis(10);
+
+        assert_ok!(cache.record("jkt", "jti-1", leeway));
+        std::thread::sleep(2 * leeway);
+        assert_err_eq!(
+            cache.record("jkt", "jti-1", leeway),
+            ReplayCacheError::Replayed
+        );
+    }
+
+    #[test]
+    fn full_cache_rejects_new_jtis() {
+        let cache = DPoPJtiReplayCache::new(1, 1);
+        let leeway = Duration::from_secs(120);
+
+        assert_ok!(cache.record("jkt", "jti-1", leeway));
+        assert_err_eq!(cache.record("jkt", "jti-2", leeway), ReplayCacheError::Full);
+    }
+
+    #[test]
+    fn full_key_share_does_not_affect_other_keys() {
+        let cache = DPoPJtiReplayCache::new(10, 2);
+        let leeway = Duration::from_secs(120);
+
+        assert_ok!(cache.record("jkt-1", "jti-1", leeway));
+        assert_ok!(cache.record("jkt-1", "jti-2", leeway));
+        assert_err_eq!(
+            cache.record("jkt-1", "jti-3", leeway),
+            ReplayCacheError::Full
+        );
+
+        assert_ok!(cache.record("jkt-2", "jti-3", leeway));
+    }
+}
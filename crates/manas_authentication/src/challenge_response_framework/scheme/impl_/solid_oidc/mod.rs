@@ -10,7 +10,9 @@ use std::{
 };
 
 use dpop::{
-    http_header::{authorization::credentials::DPoPAuthorizationCredentials, dpop::DPoP},
+    http_header::{
+        authorization::credentials::DPoPAuthorizationCredentials, dpop::DPoP, dpop_nonce::DPoPNonce,
+    },
     proof::{
         context::{DPoPProofContext, KeyBoundAccessToken},
         raw::RawDPoPProof,
@@ -46,6 +48,8 @@ use webid::{invariant::SecureWebId, profile_req_agent::ProfileDocResolutionError
 
 use self::{
     issuer_jwks::{OidcIssuerJwksResolutionError, OidcIssuerJwksResolver},
+    jti_cache::DPoPJtiReplayCache,
+    nonce::DPoPNonceIssuer,
     setup::{impl_::BasicSolidOidcDpopSchemeSetup, SolidOidcDpopSchemeSetup},
     trusted_issuers::WebIdTrustedIssuersResolver,
 };
@@ -54,13 +58,18 @@ use crate::{
         impl_::solid_oidc::trusted_issuers::WebIdTrustedIssuersResolutionError,
         CRAuthenticationChallenge, CRAuthenticationScheme, CRResolutionResult,
     },
-    common::credentials::impl_::basic::{
-        BasicAgentCredentials, BasicClientCredentials, BasicIssuerCredentials,
-        BasicRequestCredentials,
+    common::{
+        credentials::impl_::basic::{
+            BasicAgentCredentials, BasicClientCredentials, BasicIssuerCredentials,
+            BasicRequestCredentials,
+        },
+        replay_cache::ReplayCacheError,
     },
 };
 
 pub mod issuer_jwks;
+pub mod jti_cache;
+pub mod nonce;
 pub mod setup;
 pub mod trusted_issuers;
 
@@ -111,6 +120,11 @@ static FPV_INVALID_DPOP_PROOF: Lazy<FieldParameterValue> = Lazy::new(|| {
         .expect("Must be a valid value.")
 });
 
+/// `use_dpop_nonce` field param value for challenge.
+/// See: <https://datatracker.ietf.org/doc/html/rfc9449#section-9>
+static FPV_USE_DPOP_NONCE: Lazy<FieldParameterValue> =
+    Lazy::new(|| "use_dpop_nonce".try_into().expect("Must be a valid value."));
+
 /// An implementation of authentication scheme
 /// that confirms to solid-oidc specification.
 #[derive(Debug)]
@@ -123,7 +137,16 @@ pub struct SolidOidcDpopScheme<Setup: SolidOidcDpopSchemeSetup> {
 
     /// Dpop verification time leeway
     pub dpop_time_leeway: Duration,
-    // TODO jti, nonce cache for jti verification.
+
+    /// Dpop nonce issuer. If provided, dpop proofs must
+    /// contain a server provided nonce.
+    pub nonce_issuer: Option<Arc<DPoPNonceIssuer>>,
+
+    /// Dpop-proof jti replay cache. If provided, proofs with
+    /// already seen jtis will be rejected. Jtis are
+    /// remembered per proof key for a window derived from
+    /// the `dpop_time_leeway`.
+    pub jti_cache: Option<DPoPJtiReplayCache>,
 }
 
 impl<Setup: SolidOidcDpopSchemeSetup> Clone for SolidOidcDpopScheme<Setup> {
@@ -132,6 +155,8 @@ impl<Setup: SolidOidcDpopSchemeSetup> Clone for SolidOidcDpopScheme<Setup> {
             webid_issuers_resolver: self.webid_issuers_resolver.clone(),
             issuer_jwks_resolver: self.issuer_jwks_resolver.clone(),
             dpop_time_leeway: self.dpop_time_leeway,
+            nonce_issuer: self.nonce_issuer.clone(),
+            jti_cache: self.jti_cache.clone(),
         }
     }
 }
@@ -164,6 +189,7 @@ impl<Setup: SolidOidcDpopSchemeSetup> SolidOidcDpopScheme<Setup> {
         let iss = id_token_claims.iss.clone();
         let webid = id_token_claims.webid.clone();
         let azp = id_token_claims.azp.clone();
+        let bound_key_jkt = String::from(id_token_claims.cnf.jkt.clone());
 
         // Verify security as per policy.
         self.verify_stp_security(&webid, &iss)?;
@@ -175,16 +201,23 @@ impl<Setup: SolidOidcDpopSchemeSetup> SolidOidcDpopScheme<Setup> {
         let verified_id_token = self.verify_id_token(raw_id_token).await?;
 
         // Verify dpop-proof.
-        let _verified_dpop_proof =
+        let verified_dpop_proof =
             self.verify_dpop_proof(uri, method, h_dpop.0, verified_id_token)?;
 
+        // Ensure that proof is not a replayed one.
+        self.verify_not_replayed(&bound_key_jkt, &verified_dpop_proof)?;
+
         Ok(BasicRequestCredentials {
-            of_agent: Some(BasicAgentCredentials { webid }),
+            of_agent: Some(BasicAgentCredentials {
+                webid,
+                bound_key_jkt: Some(bound_key_jkt),
+            }),
             of_client: Some(BasicClientCredentials {
                 client_id: azp,
                 client_web_id: None,
             }),
             of_issuer: Some(BasicIssuerCredentials { uri: iss }),
+            of_vcs: Vec::new(),
         })
     }
 
@@ -316,6 +349,39 @@ impl<Setup: SolidOidcDpopSchemeSetup> SolidOidcDpopScheme<Setup> {
         })
     }
 
+    /// Verify that the dpop-proof signed by the key with
+    /// given thumbprint is not a replayed one.
+    ///
+    /// If the jti cache, or the key's share of it is full,
+    /// proof is rejected, as it cannot be checked for replay.
+    fn verify_not_replayed(
+        &self,
+        jkt: &str,
+        dpop_proof: &ValidatedDPoPProof,
+    ) -> CRResolutionResult<()> {
+        if let Some(jti_cache) = self.jti_cache.as_ref() {
+            jti_cache
+                .record(
+                    jkt,
+                    &dpop_proof.decoded_essence().claims.jti,
+                    self.dpop_time_leeway,
+                )
+                .map_err(|e| {
+                    error!("Dpop proof is rejected by jti cache. Error:\n {}", e);
+                    Self::challenge(
+                        Some(&*FPV_INVALID_DPOP_PROOF),
+                        Some(match e {
+                            ReplayCacheError::Replayed => "Dpop proof is replayed.",
+                            ReplayCacheError::Full => {
+                                "Dpop proof cannot be checked for replay. Retry later."
+                            }
+                        }),
+                    )
+                })?;
+        }
+        Ok(())
+    }
+
     /// Verify dpop-proof.
     fn verify_dpop_proof(
         &self,
@@ -324,6 +390,18 @@ impl<Setup: SolidOidcDpopSchemeSetup> SolidOidcDpopScheme<Setup> {
         raw_dpop_proof: RawDPoPProof<'static>,
         id_token: ValidatedIdToken,
     ) -> CRResolutionResult<ValidatedDPoPProof> {
+        // Resolve the active nonce. If the proof contains any
+        // of the active nonces, then it is the effective one.
+        let active_nonce = self.nonce_issuer.as_ref().map(|issuer| {
+            raw_dpop_proof
+                .decoded_essence()
+                .claims
+                .nonce
+                .clone()
+                .filter(|nonce| issuer.is_active(nonce))
+                .unwrap_or_else(|| issuer.current())
+        });
+
         // Construct dpop-proof context.
         let context = DPoPProofContext {
             req_method: method,
@@ -334,7 +412,7 @@ impl<Setup: SolidOidcDpopSchemeSetup> SolidOidcDpopScheme<Setup> {
                 .as_secs()
                 .try_into()
                 .expect("Must be representable."),
-            active_nonce: None,
+            active_nonce,
             nonce_timestamp: None,
             time_leeway: self.dpop_time_leeway.as_secs().try_into().unwrap_or(120),
             key_bound_access_token: Some(KeyBoundAccessToken {
@@ -345,6 +423,16 @@ impl<Setup: SolidOidcDpopSchemeSetup> SolidOidcDpopScheme<Setup> {
 
         ValidatedDPoPProof::try_new(raw_dpop_proof, context).map_err(|e| {
             error!("Error in dpop proof validation.");
+            // > the resource server responds with error code
+            // > `use_dpop_nonce`, if proof doesn't contain
+            // > server provided nonce.
+            if let InvalidDPoPProof::NonceClaimMismatch = e.error {
+                return Self::challenge(
+                    Some(&*FPV_USE_DPOP_NONCE),
+                    Some("Resource server requires nonce in DPoP proof."),
+                );
+            }
+
             let err_descr = match e.error {
                 InvalidDPoPProof::InvalidPublicKeyJwk(_) => {
                     "Invalid jwk claim in dpop-proof header."
@@ -352,7 +440,6 @@ impl<Setup: SolidOidcDpopSchemeSetup> SolidOidcDpopScheme<Setup> {
                 InvalidDPoPProof::InvalidSignature(_) => "Invalid signature.",
                 InvalidDPoPProof::HtmClaimMismatch => "htm claim mismatch.",
                 InvalidDPoPProof::HtuClaimMismatch => "htu claim mismatch.",
-                // TODO Should provide proper support.
                 InvalidDPoPProof::NonceClaimMismatch => "nonce claim mismatch",
                 InvalidDPoPProof::AthClaimMismatch => "ath claim mismatch.",
                 InvalidDPoPProof::BindingKeyMisMatch => "Binding key mismatch.",
@@ -362,6 +449,25 @@ impl<Setup: SolidOidcDpopSchemeSetup> SolidOidcDpopScheme<Setup> {
         })
     }
 
+    /// Attach the current nonce to given challenge, if nonces
+    /// are enabled.
+    fn attach_nonce(
+        &self,
+        e: Either<CRAuthenticationChallenge, Problem>,
+    ) -> Either<CRAuthenticationChallenge, Problem> {
+        match (e, self.nonce_issuer.as_ref()) {
+            (Either::Left(mut challenge), Some(issuer)) => {
+                // > the resource server provides the nonce
+                // > value in the `DPoP-Nonce` header.
+                challenge
+                    .ext_headers
+                    .typed_insert(DPoPNonce(issuer.current()));
+                Either::Left(challenge)
+            }
+            (e, _) => e,
+        }
+    }
+
     /// Return a challenge  with given params.
     /// @see: <https://datatracker.ietf.org/doc/html/draft-ietf-oauth-dpop#section-7.1-9>.
     fn challenge(
@@ -436,8 +542,16 @@ impl<Setup: SolidOidcDpopSchemeSetup> CRAuthenticationScheme for SolidOidcDpopSc
         let method = method.clone();
 
         Box::pin(async move {
-            this.resolve_credentials(uri, method, rh_authorization?, rh_dpop?)
-                .await
+            let resolved = match (rh_authorization, rh_dpop) {
+                (Ok(h_authorization), Ok(h_dpop)) => {
+                    this.clone()
+                        .resolve_credentials(uri, method, h_authorization, h_dpop)
+                        .await
+                }
+                (Err(e), _) | (_, Err(e)) => Err(e),
+            };
+
+            resolved
                 // Convert any io problems to re-challenges.
                 .map_err(|e| match e {
                     Either::Left(challenge) => Either::Left(challenge),
@@ -449,8 +563,21 @@ impl<Setup: SolidOidcDpopSchemeSetup> CRAuthenticationScheme for SolidOidcDpopSc
                         }
                     }
                 })
+                .map_err(|e| this.attach_nonce(e))
         })
     }
+
+    fn success_headers(&self, _headers: &HeaderMap) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        // Current nonce is provided on successful responses
+        // too, so that clients can pick up rotated nonces
+        // without being challenged.
+        // See: <https://datatracker.ietf.org/doc/html/rfc9449#section-9.2>
+        if let Some(issuer) = self.nonce_issuer.as_ref() {
+            headers.typed_insert(DPoPNonce(issuer.current()));
+        }
+        headers
+    }
 }
 
 /// A struct for representing cache config.
@@ -475,6 +602,33 @@ impl<STP: SecureTransportPolicy> Default
             webid_issuers_resolver: Default::default(),
             issuer_jwks_resolver: Default::default(),
             dpop_time_leeway: Duration::from_secs(120),
+            nonce_issuer: None,
+            jti_cache: Some(DPoPJtiReplayCache::new(100_000, 5_000)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use claims::*;
+
+    use super::*;
+
+    #[test]
+    fn current_nonce_is_provided_on_success() {
+        let issuer = Arc::new(DPoPNonceIssuer::new(Duration::from_secs(60)));
+        let scheme = DefaultSolidOidcDpopScheme {
+            nonce_issuer: Some(issuer.clone()),
+            ..Default::default()
+        };
+
+        let headers = scheme.success_headers(&HeaderMap::new());
+        let DPoPNonce(nonce) = assert_some!(headers.typed_get::<DPoPNonce>());
+        assert_eq!(nonce, issuer.current());
+
+        // No nonce is provided, if nonces are not enabled.
+        assert!(DefaultSolidOidcDpopScheme::default()
+            .success_headers(&HeaderMap::new())
+            .is_empty());
+    }
+}
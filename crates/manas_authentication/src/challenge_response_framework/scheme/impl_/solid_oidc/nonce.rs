@@ -0,0 +1,162 @@
+//! I define a dpop nonce issuer, that issues server
+//! provided nonces for dpop proofs.
+//!
+//! See: <https://datatracker.ietf.org/doc/html/rfc9449#section-9>
+//!
+
+use std::{
+    sync::RwLock,
+    time::{Duration, Instant},
+};
+
+use dpop::proof::payload::nonce::Nonce;
+
+/// State of a [`DPoPNonceIssuer`].
+#[derive(Debug)]
+struct DPoPNonceIssuerState {
+    /// Current nonce.
+    current: Nonce,
+
+    /// Previous nonce, that is still accepted during the
+    /// current rotation interval.
+    previous: Option<Nonce>,
+
+    /// Instant at which current nonce was issued.
+    issued_at: Instant,
+}
+
+/// A dpop nonce issuer, that rotates nonces on a configured
+/// interval.
+///
+/// Nonce from the previous interval remains valid during
+/// the current interval, so that in-flight proofs are not
+/// rejected at rotation boundaries.
+#[derive(Debug)]
+pub struct DPoPNonceIssuer {
+    /// Rotation interval.
+    rotation_interval: Duration,
+
+    /// State.
+    state: RwLock<DPoPNonceIssuerState>,
+}
+
+impl DPoPNonceIssuer {
+    /// Create a new [`DPoPNonceIssuer`] with given rotation
+    /// interval.
+    pub fn new(rotation_interval: Duration) -> Self {
+        Self {
+            rotation_interval,
+            state: RwLock::new(DPoPNonceIssuerState {
+                current: Nonce::new_from_uuid4(),
+                previous: None,
+                issued_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Get the rotation interval.
+    #[inline]
+    pub fn rotation_interval(&self) -> Duration {
+        self.rotation_interval
+    }
+
+    /// Rotate the nonces, if current nonce is outdated.
+    fn rotate_if_outdated(&self) {
+        let elapsed = self
+            .state
+            .read()
+            .expect("Lock must not be poisoned.")
+            .issued_at
+            .elapsed();
+
+        if elapsed < self.rotation_interval {
+            return;
+        }
+
+        let mut state = self.state.write().expect("Lock must not be poisoned.");
+        let elapsed = state.issued_at.elapsed();
+
+        // Check again, as another thread may have rotated.
+        if elapsed < self.rotation_interval {
+            return;
+        }
+
+        let outdated = std::mem::replace(&mut state.current, Nonce::new_from_uuid4());
+        // If more than two intervals have elapsed, outdated
+        // nonce is not accepted anymore.
+        state.previous = (elapsed < 2 * self.rotation_interval).then_some(outdated);
+        state.issued_at = Instant::now();
+    }
+
+    /// Get the current nonce, to be issued to clients.
+    pub fn current(&self) -> Nonce {
+        self.rotate_if_outdated();
+        self.state
+            .read()
+            .expect("Lock must not be poisoned.")
+            .current
+            .clone()
+    }
+
+    /// Check if given nonce is an active nonce.
+    pub fn is_active(&self, nonce: &Nonce) -> bool {
+        self.rotate_if_outdated();
+        let state = self.state.read().expect("Lock must not be poisoned.");
+        &state.current == nonce || state.previous.as_ref() == Some(nonce)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROTATION_INTERVAL: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn current_nonce_is_stable_within_interval() {
+        let issuer = DPoPNonceIssuer::new(ROTATION_INTERVAL);
+        let nonce = issuer.current();
+
+        assert_eq!(issuer.current(), nonce);
+        assert!(issuer.is_active(&nonce));
+        assert!(!issuer.is_active(&Nonce::new_from_uuid4()));
+    }
+
+    #[test]
+    fn nonce_is_rotated_after_interval() {
+        let issuer = DPoPNonceIssuer::new(ROTATION_INTERVAL);
+        let nonce = issuer.current();
+
+        std::thread::sleep(ROTATION_INTERVAL + Duration::from_millis(20));
+        let rotated = issuer.current();
+
+        assert_ne!(rotated, nonce);
+        assert!(issuer.is_active(&rotated));
+        // Previous nonce is still accepted during current
+        // interval.
+        assert!(issuer.is_active(&nonce));
+    }
+
+    #[test]
+    fn stale_nonce_is_rejected() {
+        let issuer = DPoPNonceIssuer::new(ROTATION_INTERVAL);
+        let nonce = issuer.current();
+
+        std::thread::sleep(ROTATION_INTERVAL + Duration::from_millis(20));
+        let rotated = issuer.current();
+        std::thread::sleep(ROTATION_INTERVAL + Duration::from_millis(20));
+
+        assert!(!issuer.is_active(&nonce));
+        assert!(issuer.is_active(&rotated));
+    }
+
+    #[test]
+    fn nonce_is_rejected_after_two_idle_intervals() {
+        let issuer = DPoPNonceIssuer::new(ROTATION_INTERVAL);
+        let nonce = issuer.current();
+
+        std::thread::sleep(2 * ROTATION_INTERVAL + Duration::from_millis(20));
+
+        assert!(!issuer.is_active(&nonce));
+    }
+}
@@ -30,15 +30,10 @@ pub struct UnionCRAuthenticationScheme<C: RequestCredentials> {
     pub default_challenge_schemes: Vec<Token>,
 }
 
-impl<C: RequestCredentials> CRAuthenticationScheme for UnionCRAuthenticationScheme<C> {
-    type Credentials = C;
-
-    fn resolve_or_challenge(
-        &self,
-        uri: &AbsoluteHttpUri,
-        method: &Method,
-        headers: &HeaderMap,
-    ) -> BoxFuture<'static, CRResolutionResult<Self::Credentials>> {
+impl<C: RequestCredentials> UnionCRAuthenticationScheme<C> {
+    /// Get the inner scheme, that matches the authorization
+    /// header in given headers, if any.
+    fn matched_scheme(&self, headers: &HeaderMap) -> Option<Arc<DynCRAuthenticationScheme<C>>> {
         // Try resolve authn scheme name.
         let scheme_name = headers
             .get_all(AUTHORIZATION)
@@ -47,14 +42,30 @@ impl<C: RequestCredentials> CRAuthenticationScheme for UnionCRAuthenticationSche
             .exactly_one()
             .ok()
             .and_then(|v| v.to_str().ok())
+            // Scheme name is the first token of the header value.
+            .and_then(|v| v.split_whitespace().next())
             .and_then(|v| Token::from_str(v).ok());
 
+        // Scheme names are case-insensitive.
+        scheme_name.and_then(|n| {
+            self.schemes
+                .iter()
+                .find_map(|(k, s)| k.eq_ignore_ascii_case(&n).then(|| s.clone()))
+        })
+    }
+}
+
+impl<C: RequestCredentials> CRAuthenticationScheme for UnionCRAuthenticationScheme<C> {
+    type Credentials = C;
+
+    fn resolve_or_challenge(
+        &self,
+        uri: &AbsoluteHttpUri,
+        method: &Method,
+        headers: &HeaderMap,
+    ) -> BoxFuture<'static, CRResolutionResult<Self::Credentials>> {
         // Check if any inner scheme matches the request.
-        if let Some(scheme) = scheme_name
-            .as_ref()
-            .and_then(|n| self.schemes.get(n))
-            .cloned()
-        {
+        if let Some(scheme) = self.matched_scheme(headers) {
             // Delegate to matched scheme.
             scheme.resolve_or_challenge(uri, method, headers)
         } else {
@@ -71,6 +82,7 @@ impl<C: RequestCredentials> CRAuthenticationScheme for UnionCRAuthenticationSche
 
             Box::pin(async move {
                 let mut wwwauthn_challenges = vec![];
+                let mut ext_headers = HeaderMap::new();
                 // Collect challenges from inner schemes.
                 for (scheme_name, scheme) in schemes {
                     if let Err(e) = scheme
@@ -80,7 +92,8 @@ impl<C: RequestCredentials> CRAuthenticationScheme for UnionCRAuthenticationSche
                         match e {
                             // On challenge.
                             Either::Left(challenge) => {
-                                wwwauthn_challenges.extend(challenge.www_authenticate.challenges)
+                                wwwauthn_challenges.extend(challenge.www_authenticate.challenges);
+                                ext_headers.extend(challenge.ext_headers);
                             }
                             // On unknown problem, skip.
                             Either::Right(_ie) => {
@@ -97,9 +110,15 @@ impl<C: RequestCredentials> CRAuthenticationScheme for UnionCRAuthenticationSche
                     www_authenticate: WWWAuthenticate {
                         challenges: wwwauthn_challenges,
                     },
-                    ext_headers: Default::default(),
+                    ext_headers,
                 }))
             })
         }
     }
+
+    fn success_headers(&self, headers: &HeaderMap) -> HeaderMap {
+        self.matched_scheme(headers)
+            .map(|scheme| scheme.success_headers(headers))
+            .unwrap_or_default()
+    }
 }
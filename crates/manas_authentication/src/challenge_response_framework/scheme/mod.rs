@@ -26,6 +26,14 @@ pub trait CRAuthenticationScheme: Debug + Send + Sync + 'static {
         method: &Method,
         headers: &HeaderMap,
     ) -> BoxFuture<'static, CRResolutionResult<Self::Credentials>>;
+
+    /// Get any headers to be attached to the response of a
+    /// request with given headers, whose credentials are
+    /// resolved by this scheme.
+    #[inline]
+    fn success_headers(&self, _headers: &HeaderMap) -> HeaderMap {
+        HeaderMap::new()
+    }
 }
 
 /// A type for representing challenge in challenge-response auth framework.
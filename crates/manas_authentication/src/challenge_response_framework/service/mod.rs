@@ -5,9 +5,9 @@
 use std::{convert::Infallible, fmt::Debug, marker::PhantomData, sync::Arc, task::Poll};
 
 use either::Either;
-use futures::TryFutureExt;
+use futures::{future::BoxFuture, TryFutureExt};
 use headers::HeaderMapExt;
-use http::{header::AUTHORIZATION, Method, Request, Response, StatusCode};
+use http::{header::AUTHORIZATION, HeaderMap, Method, Request, Response, StatusCode};
 use http_uri::invariant::AbsoluteHttpUri;
 use manas_http::service::{BoxHttpResponseFuture, HttpService};
 use tower::{Layer, Service, ServiceExt};
@@ -78,13 +78,20 @@ where
         let should_resolve =
             req.headers().get(AUTHORIZATION).is_some() || self.required_on.contains(req.method());
 
-        // Resolve authentication.
-        let auth_resln_fut = if should_resolve {
-            self.scheme
-                .resolve_or_challenge(req_uri, req.method(), req.headers())
+        // Resolve authentication, along with headers to be
+        // attached to response on success.
+        let (auth_resln_fut, success_headers) = if should_resolve {
+            (
+                self.scheme
+                    .resolve_or_challenge(req_uri, req.method(), req.headers()),
+                self.scheme.success_headers(req.headers()),
+            )
         } else {
             // Continue with default creds.
-            Box::pin(async { Ok(Default::default()) })
+            (
+                Box::pin(async { Ok(Default::default()) }) as BoxFuture<'static, _>,
+                HeaderMap::new(),
+            )
         };
 
         let mut inner_svc = self.inner.clone();
@@ -97,7 +104,9 @@ where
                     req = Authenticator::authenticated(req, credentials);
 
                     // And delegate further handling to inner service.
-                    inner_svc.ready().and_then(|svc| svc.call(req)).await
+                    let mut resp = inner_svc.ready().and_then(|svc| svc.call(req)).await?;
+                    resp.headers_mut().extend(success_headers);
+                    Ok(resp)
                 }
 
                 // On challenge.
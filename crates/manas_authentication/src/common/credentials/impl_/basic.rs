@@ -8,6 +8,7 @@ use webid::WebId;
 use super::void::VoidCredentials;
 use crate::common::credentials::{
     AgentCredentials, ClientCredentials, ClientId, IssuerCredentials, RequestCredentials,
+    VerifiedCredential,
 };
 
 /// A basic implementation of [`AgentCredentials`].
@@ -15,6 +16,11 @@ use crate::common::credentials::{
 pub struct BasicAgentCredentials {
     /// Webid of the agent.
     pub webid: WebId,
+
+    /// Jwk thumbprint of the key, to which agent's
+    /// credentials are bound to, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bound_key_jkt: Option<String>,
 }
 
 impl AgentCredentials for BasicAgentCredentials {
@@ -22,6 +28,11 @@ impl AgentCredentials for BasicAgentCredentials {
     fn webid(&self) -> &WebId {
         &self.webid
     }
+
+    #[inline]
+    fn bound_key_jkt(&self) -> Option<&str> {
+        self.bound_key_jkt.as_deref()
+    }
 }
 
 /// A basic implementation of [`ClientCredentials`].
@@ -71,6 +82,10 @@ pub struct BasicRequestCredentials {
 
     /// Issuer credentials.
     pub of_issuer: Option<BasicIssuerCredentials>,
+
+    /// Verified credentials presented with the request.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub of_vcs: Vec<VerifiedCredential>,
 }
 
 impl RequestCredentials for BasicRequestCredentials {
@@ -94,6 +109,11 @@ impl RequestCredentials for BasicRequestCredentials {
     fn of_issuer(&self) -> Option<&Self::IssuerCredentials> {
         self.of_issuer.as_ref()
     }
+
+    #[inline]
+    fn of_vcs(&self) -> &[VerifiedCredential] {
+        &self.of_vcs
+    }
 }
 
 #[cfg(feature = "creds-context")]
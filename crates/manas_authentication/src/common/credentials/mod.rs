@@ -4,7 +4,7 @@
 use std::fmt::Debug;
 
 use http_uri::invariant::AbsoluteHttpUri;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use webid::WebId;
 
 pub mod impl_;
@@ -13,6 +13,14 @@ pub mod impl_;
 pub trait AgentCredentials: Debug + Clone + Send + Sync + 'static {
     /// Get the webid of the agent.
     fn webid(&self) -> &WebId;
+
+    /// Get the base64url encoded jwk sha-256 thumbprint
+    /// (rfc7638) of the key, to which agent's credentials
+    /// are bound to, if any.
+    #[inline]
+    fn bound_key_jkt(&self) -> Option<&str> {
+        None
+    }
 }
 
 /// A type for defining client id.
@@ -33,6 +41,62 @@ pub trait IssuerCredentials: Debug + Clone + Send + Sync + 'static {
     fn uri(&self) -> &AbsoluteHttpUri;
 }
 
+/// A struct for representing a verifiable credential, that
+/// is presented with the request, and is verified.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VerifiedCredential {
+    /// Id of the credential issuer.
+    pub issuer: String,
+
+    /// Absolute iri types of the credential.
+    pub types: Vec<String>,
+}
+
+/// A struct for representing credentials of the agent, that
+/// created a resource.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreatorCredentials {
+    /// Webid of the creator agent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<WebId>,
+
+    /// Client id of the creator client.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client: Option<ClientId>,
+
+    /// Webid of the creator client.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_web_id: Option<WebId>,
+
+    /// Uri of the creator agent's issuer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<AbsoluteHttpUri>,
+}
+
+impl CreatorCredentials {
+    /// Resolve creator credentials from given request
+    /// credentials of the creating request.
+    pub fn from_request_credentials<C: RequestCredentials>(credentials: &C) -> Self {
+        Self {
+            agent: credentials.of_agent().map(|c| c.webid().clone()),
+            client: credentials.of_client().map(|c| c.client_id().clone()),
+            client_web_id: credentials
+                .of_client()
+                .and_then(|c| c.client_web_id().cloned()),
+            issuer: credentials.of_issuer().map(|c| c.uri().clone()),
+        }
+    }
+
+    /// Check if credentials are empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.agent.is_none()
+            && self.client.is_none()
+            && self.client_web_id.is_none()
+            && self.issuer.is_none()
+    }
+}
+
 /// A trait to represent request credentials.
 pub trait RequestCredentials: Debug + Default + Clone + Send + Sync + 'static + Serialize {
     /// Type of the agent credentials.
@@ -52,6 +116,12 @@ pub trait RequestCredentials: Debug + Default + Clone + Send + Sync + 'static +
 
     /// Get issuer credentials.
     fn of_issuer(&self) -> Option<&Self::IssuerCredentials>;
+
+    /// Get verified credentials presented with the request.
+    #[inline]
+    fn of_vcs(&self) -> &[VerifiedCredential] {
+        &[]
+    }
 }
 
 #[cfg(feature = "creds-context")]
@@ -68,6 +138,7 @@ mod to_context_def {
         term::ArcTerm,
     };
     use rdf_vocabularies::ns;
+    use sophia_api::term::IriRef;
 
     use super::RequestCredentials;
     use crate::common::credentials::{AgentCredentials, ClientCredentials, IssuerCredentials};
@@ -102,6 +173,13 @@ mod to_context_def {
                 context.add(&ns::acp::issuer, issuer_creds.uri().deref());
             }
 
+            // Add verified credential types.
+            for vc_type in self.of_vcs().iter().flat_map(|vc| vc.types.iter()) {
+                if let Ok(vc_type) = IriRef::new(vc_type.as_str()) {
+                    context.add(&ns::acp::vc, &vc_type);
+                }
+            }
+
             context
         }
     }
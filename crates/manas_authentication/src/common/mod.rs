@@ -3,3 +3,6 @@
 
 pub mod credentials;
 pub mod req_authenticator;
+
+#[cfg(feature = "cr-framework")]
+pub mod replay_cache;
@@ -0,0 +1,254 @@
+//! I define a bounded cache of seen ids of single use
+//! artifacts, like dpop-proof `jti`s, or signature `nonce`s,
+//! to detect their replays.
+//!
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Debug,
+    hash::Hash,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// An error in recording an id in a [`ReplayCache`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ReplayCacheError {
+    /// Id is already seen.
+    #[error("Id is already seen.")]
+    Replayed,
+
+    /// Cache, or the partition of the id is full with live
+    /// entries.
+    #[error("Replay cache is full.")]
+    Full,
+}
+
+/// A bounded cache of seen ids.
+///
+/// Unlike general purpose caches, it never evicts a live
+/// entry to admit a new one, nor it refuses to admit a new
+/// entry while there is room. Once it is full with live
+/// entries, it fails closed, and rejects new ids until some
+/// of the entries expire. Thus a replay is never accepted.
+///
+/// Ids can be recorded in partitions, like the keys that
+/// sign the artifacts, each of which is bounded on it's
+/// own. Thus a single partition cannot fill the cache for
+/// others. Ids are unique only within their partition.
+///
+/// Entries must live at least as long as the window in
+/// which an artifact is considered fresh, so that it
+/// cannot be replayed within it's validity window.
+pub struct ReplayCache<K, P = ()> {
+    /// Maximum number of live entries.
+    max_capacity: usize,
+
+    /// Maximum number of live entries in a partition.
+    max_partition_capacity: usize,
+
+    /// State.
+    state: Arc<Mutex<ReplayCacheState<K, P>>>,
+}
+
+/// State of a [`ReplayCache`].
+struct ReplayCacheState<K, P> {
+    /// Seen ids, with their expiry.
+    seen: HashMap<(P, K), Instant>,
+
+    /// Expiry ordered seen ids.
+    expiries: BTreeSet<(Instant, P, K)>,
+
+    /// Number of live entries in each partition.
+    partition_sizes: HashMap<P, usize>,
+}
+
+impl<K, P> Clone for ReplayCache<K, P> {
+    fn clone(&self) -> Self {
+        Self {
+            max_capacity: self.max_capacity,
+            max_partition_capacity: self.max_partition_capacity,
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<K, P> Debug for ReplayCache<K, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReplayCache")
+            .field("max_capacity", &self.max_capacity)
+            .field("max_partition_capacity", &self.max_partition_capacity)
+            .finish()
+    }
+}
+
+impl<K: Hash + Ord + Clone> ReplayCache<K> {
+    /// Create a new [`ReplayCache`] with given max capacity.
+    #[inline]
+    pub fn new(max_capacity: usize) -> Self {
+        Self::new_partitioned(max_capacity, max_capacity)
+    }
+
+    /// Record given id as seen, for given time to live.
+    /// Returns an error, if it is already seen, or if cache
+    /// is full.
+    #[inline]
+    pub fn record(&self, id: K, time_to_live: Duration) -> Result<(), ReplayCacheError> {
+        self.record_in((), id, time_to_live)
+    }
+}
+
+impl<K: Hash + Ord + Clone, P: Hash + Ord + Clone> ReplayCache<K, P> {
+    /// Create a new partitioned [`ReplayCache`] with given
+    /// max capacity, and max capacity of each partition.
+    pub fn new_partitioned(max_capacity: usize, max_partition_capacity: usize) -> Self {
+        Self {
+            max_capacity,
+            max_partition_capacity,
+            state: Arc::new(Mutex::new(ReplayCacheState {
+                seen: HashMap::new(),
+                expiries: BTreeSet::new(),
+                partition_sizes: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Get the max capacity.
+    #[inline]
+    pub fn max_capacity(&self) -> usize {
+        self.max_capacity
+    }
+
+    /// Get the max capacity of each partition.
+    #[inline]
+    pub fn max_partition_capacity(&self) -> usize {
+        self.max_partition_capacity
+    }
+
+    /// Record given id as seen in given partition, for given
+    /// time to live. Returns an error, if it is already seen
+    /// in the partition, or if either cache or partition is
+    /// full.
+    pub fn record_in(
+        &self,
+        partition: P,
+        id: K,
+        time_to_live: Duration,
+    ) -> Result<(), ReplayCacheError> {
+        let now = Instant::now();
+        let mut state = self.state.lock().expect("Lock must not be poisoned.");
+
+        // Purge expired entries.
+        while let Some((expiry, _, _)) = state.expiries.first() {
+            if *expiry > now {
+                break;
+            }
+            let (_, expired_partition, expired_id) =
+                state.expiries.pop_first().expect("Must be some.");
+
+            if let Some(size) = state.partition_sizes.get_mut(&expired_partition) {
+                *size -= 1;
+                if *size == 0 {
+                    state.partition_sizes.remove(&expired_partition);
+                }
+            }
+            state.seen.remove(&(expired_partition, expired_id));
+        }
+
+        let entry_key = (partition, id);
+        if state.seen.contains_key(&entry_key) {
+            return Err(ReplayCacheError::Replayed);
+        }
+
+        let partition_size = state
+            .partition_sizes
+            .get(&entry_key.0)
+            .copied()
+            .unwrap_or(0);
+
+        if state.seen.len() >= self.max_capacity || partition_size >= self.max_partition_capacity {
+            return Err(ReplayCacheError::Full);
+        }
+
+        let expiry = now + time_to_live;
+        state
+            .expiries
+            .insert((expiry, entry_key.0.clone(), entry_key.1.clone()));
+        state
+            .partition_sizes
+            .insert(entry_key.0.clone(), partition_size + 1);
+        state.seen.insert(entry_key, expiry);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use claims::*;
+
+    use super::*;
+
+    #[test]
+    fn replayed_ids_are_rejected() {
+        let cache = ReplayCache::new(10);
+        let ttl = Duration::from_secs(60);
+
+        assert_ok!(cache.record("a", ttl));
+        assert_err_eq!(cache.record("a", ttl), ReplayCacheError::Replayed);
+        assert_ok!(cache.record("b", ttl));
+        assert_err_eq!(cache.clone().record("b", ttl), ReplayCacheError::Replayed);
+    }
+
+    #[test]
+    fn expired_ids_are_forgotten() {
+        let cache = ReplayCache::new(10);
+
+        assert_ok!(cache.record("a", Duration::from_millis(10)));
+        assert_ok!(cache.record("b", Duration::from_secs(60)));
+        std::thread::sleep(Duration::from_millis(20));
+
+        assert_ok!(cache.record("a", Duration::from_secs(60)));
+        assert_err_eq!(
+            cache.record("b", Duration::from_secs(60)),
+            ReplayCacheError::Replayed
+        );
+    }
+
+    #[test]
+    fn full_cache_fails_closed() {
+        let cache = ReplayCache::new(2);
+
+        assert_ok!(cache.record("a", Duration::from_millis(10)));
+        assert_ok!(cache.record("b", Duration::from_secs(60)));
+        assert_err_eq!(
+            cache.record("c", Duration::from_secs(60)),
+            ReplayCacheError::Full
+        );
+        // Seen ids are still detected, when full.
+        assert_err_eq!(
+            cache.record("b", Duration::from_secs(60)),
+            ReplayCacheError::Replayed
+        );
+
+        // Room is made as entries expire.
+        std::thread::sleep(Duration::from_millis(20));
+        assert_ok!(cache.record("c", Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn full_partition_does_not_affect_others() {
+        let cache = ReplayCache::new_partitioned(3, 2);
+        let ttl = Duration::from_secs(60);
+
+        assert_ok!(cache.record_in("p1", "a", ttl));
+        assert_ok!(cache.record_in("p1", "b", ttl));
+        assert_err_eq!(cache.record_in("p1", "c", ttl), ReplayCacheError::Full);
+
+        // Ids are unique only within their partition.
+        assert_ok!(cache.record_in("p2", "a", ttl));
+        assert_err_eq!(cache.record_in("p2", "a", ttl), ReplayCacheError::Replayed);
+
+        // Total capacity still bounds all partitions.
+        assert_err_eq!(cache.record_in("p3", "a", ttl), ReplayCacheError::Full);
+    }
+}
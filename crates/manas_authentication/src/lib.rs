@@ -9,3 +9,6 @@ pub mod common;
 
 #[cfg(feature = "cr-framework")]
 pub mod challenge_response_framework;
+
+#[cfg(feature = "vc-presentation")]
+pub mod vc_presentation;
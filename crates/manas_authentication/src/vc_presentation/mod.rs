@@ -0,0 +1,50 @@
+//! I define a pipeline to verify verifiable presentations
+//! presented with requests, and to attach verified
+//! credentials to the request credentials.
+//!
+//! Presentations are expected as [vc-jwt] encoded verifiable
+//! presentations in the `Vp-Token` header. A presentation
+//! must be signed with the holder key in it's `jwk` header,
+//! which must be the key the agent's authenticated session
+//! is bound to (dpop key, or http signature key). It's `iss`
+//! must be the webid of the authenticated agent, and it's
+//! `aud` must be of the request uri's origin, with path being
+//! a segment-wise prefix of the request uri path. It must
+//! have `iat` and `exp` claims spanning a short lifetime,
+//! and a `jti` claim, that must not be reused.
+//! Each contained credential must be a vc-jwt, signed by
+//! a locally configured trusted issuer, and issued to the
+//! presentation holder.
+//!
+//! [vc-jwt]: https://www.w3.org/TR/vc-data-model/#json-web-token
+//!
+
+use http::HeaderName;
+
+use crate::common::credentials::{
+    impl_::basic::BasicRequestCredentials, RequestCredentials, VerifiedCredential,
+};
+
+pub mod service;
+pub mod trusted_issuers;
+pub mod verifier;
+
+/// Name of the header, in which verifiable presentation is
+/// presented.
+pub static VP_TOKEN: HeaderName = HeaderName::from_static("vp-token");
+
+/// A trait for request credentials, that can be extended
+/// with verified credentials.
+pub trait WithVerifiedCredentials: RequestCredentials {
+    /// Get credentials extended with given verified
+    /// credentials.
+    fn with_verified_credentials(self, vcs: Vec<VerifiedCredential>) -> Self;
+}
+
+impl WithVerifiedCredentials for BasicRequestCredentials {
+    #[inline]
+    fn with_verified_credentials(mut self, vcs: Vec<VerifiedCredential>) -> Self {
+        self.of_vcs.extend(vcs);
+        self
+    }
+}
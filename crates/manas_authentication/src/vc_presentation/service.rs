@@ -0,0 +1,168 @@
+//! I define an implementation of [`HttpService`](manas_http::service::HttpService) that
+//! verifies presented verifiable presentations, before
+//! delegating to inner service.
+//!
+
+use std::{
+    marker::PhantomData,
+    task::Poll,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use http::Request;
+use http_uri::invariant::AbsoluteHttpUri;
+use tower::{Layer, Service};
+use tracing::error;
+
+use super::{verifier::VcPresentationVerifier, WithVerifiedCredentials, VP_TOKEN};
+use crate::common::credentials::AgentCredentials;
+
+/// An implementation of [`HttpService`](manas_http::service::HttpService) that verifies
+/// verifiable presentation presented with the request, and
+/// extends the request credentials with verified credentials,
+/// before delegating to inner service.
+///
+/// It must be called after authentication, as presentations
+/// are only accepted from authenticated agents. Invalid
+/// presentations are ignored.
+#[derive(Debug)]
+pub struct HttpVcPresentationService<Inner, Credentials> {
+    /// Inner service.
+    inner: Inner,
+
+    /// Presentation verifier.
+    verifier: VcPresentationVerifier,
+
+    _phantom: PhantomData<fn(Credentials)>,
+}
+
+impl<Inner: Clone, Credentials> Clone for HttpVcPresentationService<Inner, Credentials> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            verifier: self.verifier.clone(),
+            _phantom: self._phantom,
+        }
+    }
+}
+
+impl<Inner, Credentials, ReqBody> Service<Request<ReqBody>>
+    for HttpVcPresentationService<Inner, Credentials>
+where
+    Inner: Service<Request<ReqBody>>,
+    Credentials: WithVerifiedCredentials,
+{
+    type Response = Inner::Response;
+
+    type Error = Inner::Error;
+
+    type Future = Inner::Future;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    #[tracing::instrument(skip_all, name = "HttpVcPresentationService::call")]
+    fn call(&mut self, mut req: Request<ReqBody>) -> Self::Future {
+        if let Some(credentials) = self.resolve_extended_credentials(&req) {
+            req.extensions_mut().insert(credentials);
+        }
+        self.inner.call(req)
+    }
+}
+
+impl<Inner, Credentials: WithVerifiedCredentials> HttpVcPresentationService<Inner, Credentials> {
+    /// Create a new [`HttpVcPresentationService`] with given
+    /// params.
+    #[inline]
+    pub fn new(inner: Inner, verifier: VcPresentationVerifier) -> Self {
+        Self {
+            inner,
+            verifier,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Resolve request credentials extended with verified
+    /// credentials of the presented presentation, if any.
+    fn resolve_extended_credentials<ReqBody>(&self, req: &Request<ReqBody>) -> Option<Credentials> {
+        // No credential can be verified without trusted issuers.
+        if self.verifier.trusted_issuers().is_empty() {
+            return None;
+        }
+
+        let compact_vp = req.headers().get(&VP_TOKEN)?.to_str().ok()?;
+
+        let req_uri: &AbsoluteHttpUri = req.extensions().get().expect(
+            "HttpVcPresentationService must be called after configuring resource absolute uri.",
+        );
+
+        let credentials: &Credentials = req.extensions().get()?;
+        let Some(agent_creds) = credentials.of_agent() else {
+            error!("Ignoring presentation from unauthenticated agent.");
+            return None;
+        };
+
+        let current_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs() as i64;
+
+        match self.verifier.verify(
+            compact_vp,
+            agent_creds.webid(),
+            agent_creds.bound_key_jkt(),
+            req_uri.as_str(),
+            current_time,
+        ) {
+            Ok(vcs) => Some(credentials.clone().with_verified_credentials(vcs)),
+            Err(e) => {
+                error!("Ignoring invalid presentation. Error:\n {}", e);
+                None
+            }
+        }
+    }
+}
+
+/// A layer that wraps [`HttpVcPresentationService`] over
+/// inner service.
+#[derive(Debug)]
+pub struct HttpVcPresentationLayer<Credentials> {
+    /// Presentation verifier.
+    verifier: VcPresentationVerifier,
+
+    _phantom: PhantomData<fn(Credentials)>,
+}
+
+impl<Credentials> Clone for HttpVcPresentationLayer<Credentials> {
+    fn clone(&self) -> Self {
+        Self {
+            verifier: self.verifier.clone(),
+            _phantom: self._phantom,
+        }
+    }
+}
+
+impl<Credentials> HttpVcPresentationLayer<Credentials> {
+    /// Create a new [`HttpVcPresentationLayer`] with given
+    /// verifier.
+    #[inline]
+    pub fn new(verifier: VcPresentationVerifier) -> Self {
+        Self {
+            verifier,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<Inner, Credentials: WithVerifiedCredentials> Layer<Inner>
+    for HttpVcPresentationLayer<Credentials>
+{
+    type Service = HttpVcPresentationService<Inner, Credentials>;
+
+    #[inline]
+    fn layer(&self, inner: Inner) -> Self::Service {
+        HttpVcPresentationService::new(inner, self.verifier.clone())
+    }
+}
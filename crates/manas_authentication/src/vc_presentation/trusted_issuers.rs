@@ -0,0 +1,65 @@
+//! I define a registry of locally configured trusted
+//! credential issuers.
+//!
+
+use std::collections::HashMap;
+
+use picky::jose::jwk::{Jwk, JwkError, JwkKeyOps, JwkSet};
+
+/// A registry of trusted credential issuers, along with
+/// their verification keys.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedVcIssuers {
+    /// Map of issuer ids to their jwks.
+    issuers: HashMap<String, JwkSet>,
+}
+
+impl TrustedVcIssuers {
+    /// Create a new [`TrustedVcIssuers`] with given issuers.
+    #[inline]
+    pub fn new(issuers: HashMap<String, JwkSet>) -> Self {
+        Self { issuers }
+    }
+
+    /// Get registry with given issuer trusted.
+    pub fn with_issuer(mut self, id: String, jwks: JwkSet) -> Self {
+        self.issuers.insert(id, jwks);
+        self
+    }
+
+    /// Get registry with given issuer trusted, with it's jwks
+    /// encoded as given json.
+    pub fn try_with_issuer_jwks_json(self, id: String, jwks_json: &str) -> Result<Self, JwkError> {
+        Ok(self.with_issuer(id, JwkSet::from_json(jwks_json)?))
+    }
+
+    /// Check if registry is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.issuers.is_empty()
+    }
+
+    /// Check if issuer with given id is trusted.
+    #[inline]
+    pub fn is_trusted(&self, id: &str) -> bool {
+        self.issuers.contains_key(id)
+    }
+
+    /// Resolve the verification key of the trusted issuer
+    /// with given id. If kid is given, key with that kid is
+    /// resolved, else first key with `verify` key op is
+    /// resolved.
+    pub fn resolve_key(&self, id: &str, opt_kid: Option<&str>) -> Option<&Jwk> {
+        let mut keys = self.issuers.get(id)?.keys.iter();
+        if let Some(kid) = opt_kid {
+            keys.find(|jwk| jwk.kid.as_deref() == Some(kid))
+        } else {
+            keys.find(|jwk| {
+                jwk.key_ops
+                    .as_ref()
+                    .map(|ops| ops.contains(&JwkKeyOps::Verify))
+                    .unwrap_or(false)
+            })
+        }
+    }
+}
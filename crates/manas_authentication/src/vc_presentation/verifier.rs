@@ -0,0 +1,703 @@
+//! I define a verifier for vc-jwt encoded verifiable
+//! presentations.
+//!
+
+use std::{sync::Arc, time::Duration};
+
+use dpop::proof::payload::jkt::Jkt;
+use http::Uri;
+use picky::{
+    jose::{
+        jwk::JwkError,
+        jws::{JwsError, RawJws},
+    },
+    signature::SignatureAlgorithm,
+};
+use serde::Deserialize;
+use sophia_iri::Iri;
+use tracing::error;
+use webid::WebId;
+
+use super::trusted_issuers::TrustedVcIssuers;
+use crate::common::{
+    credentials::VerifiedCredential,
+    replay_cache::{ReplayCache, ReplayCacheError},
+};
+
+/// Base credential type, that is implied for every
+/// credential.
+const BASE_VC_TYPE: &str = "VerifiableCredential";
+
+/// Iri of the base credential type.
+const BASE_VC_TYPE_IRI: &str = "https://www.w3.org/2018/credentials#VerifiableCredential";
+
+/// Default max lifetime of a presentation.
+pub const DEFAULT_MAX_PRESENTATION_LIFETIME: Duration = Duration::from_secs(300);
+
+/// Default leeway for time comparisons.
+pub const DEFAULT_TIME_LEEWAY: Duration = Duration::from_secs(30);
+
+/// Default max number of presentation ids to remember for
+/// replay detection.
+const DEFAULT_JTI_CACHE_CAPACITY: usize = 100_000;
+
+/// A verifier of vc-jwt encoded verifiable presentations.
+/// It verifies signatures and validity periods locally,
+/// against configured trusted issuers.
+///
+/// Presentations must be signed with the key, to which
+/// agent's authenticated session is bound, must be short
+/// lived, and can be used only once.
+#[derive(Debug, Clone)]
+pub struct VcPresentationVerifier {
+    /// Trusted issuers.
+    trusted_issuers: Arc<TrustedVcIssuers>,
+
+    /// Max lifetime of a presentation in seconds.
+    max_lifetime: i64,
+
+    /// Leeway for time comparisons in seconds.
+    time_leeway: i64,
+
+    /// Cache of seen presentation ids, keyed by holder.
+    jti_cache: ReplayCache<(String, String)>,
+
+    /// Time to live of seen presentation ids.
+    jti_ttl: Duration,
+}
+
+impl Default for VcPresentationVerifier {
+    #[inline]
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl VcPresentationVerifier {
+    /// Create a new [`VcPresentationVerifier`] with given
+    /// trusted issuers.
+    #[inline]
+    pub fn new(trusted_issuers: Arc<TrustedVcIssuers>) -> Self {
+        Self::new_with_limits(
+            trusted_issuers,
+            DEFAULT_MAX_PRESENTATION_LIFETIME,
+            DEFAULT_TIME_LEEWAY,
+        )
+    }
+
+    /// Create a new [`VcPresentationVerifier`] with given
+    /// trusted issuers, max presentation lifetime, and time
+    /// leeway.
+    pub fn new_with_limits(
+        trusted_issuers: Arc<TrustedVcIssuers>,
+        max_lifetime: Duration,
+        time_leeway: Duration,
+    ) -> Self {
+        Self {
+            trusted_issuers,
+            max_lifetime: max_lifetime.as_secs() as i64,
+            time_leeway: time_leeway.as_secs() as i64,
+            jti_cache: ReplayCache::new(DEFAULT_JTI_CACHE_CAPACITY),
+            // Presentation ids need to be remembered only
+            // until they expire.
+            jti_ttl: max_lifetime + time_leeway * 2,
+        }
+    }
+
+    /// Set the max number of presentation ids, that are
+    /// remembered for replay detection. Once as many
+    /// unexpired ids are remembered, new presentations are
+    /// rejected.
+    pub fn with_jti_cache_capacity(mut self, capacity: usize) -> Self {
+        self.jti_cache = ReplayCache::new(capacity);
+        self
+    }
+
+    /// Get the trusted issuers.
+    #[inline]
+    pub fn trusted_issuers(&self) -> &Arc<TrustedVcIssuers> {
+        &self.trusted_issuers
+    }
+
+    /// Verify given compact vc-jwt presentation, presented by
+    /// given agent, whose credentials are bound to the key
+    /// with given thumbprint, in request to given uri, at
+    /// given time.
+    /// On success, returns verified credentials in the
+    /// presentation. Credentials that fail verification are
+    /// skipped.
+    pub fn verify(
+        &self,
+        compact_vp: &str,
+        agent: &WebId,
+        agent_bound_key_jkt: Option<&str>,
+        req_uri: &str,
+        current_time: i64,
+    ) -> Result<Vec<VerifiedCredential>, InvalidVcPresentation> {
+        // Presentations are accepted only from agents with
+        // key bound credentials, so that a leaked
+        // presentation cannot be replayed with other
+        // credentials.
+        let agent_bound_key_jkt =
+            agent_bound_key_jkt.ok_or(InvalidVcPresentation::UnboundAgentCredentials)?;
+
+        let raw_vp = RawJws::decode(compact_vp)?;
+
+        // Resolve holder key from header.
+        let holder_jwk = raw_vp
+            .header
+            .jwk
+            .clone()
+            .ok_or(InvalidVcPresentation::MissingHolderKey)?;
+
+        // Ensure holder key is the agent's bound key.
+        if &*Jkt::new(&holder_jwk) != agent_bound_key_jkt {
+            return Err(InvalidVcPresentation::HolderKeyMismatch);
+        }
+
+        if SignatureAlgorithm::try_from(raw_vp.header.alg).is_err() {
+            return Err(InvalidVcPresentation::UnsupportedAlg);
+        }
+
+        // Verify presentation signature.
+        let vp_jws = raw_vp.verify(&holder_jwk.to_public_key()?)?;
+
+        let claims: VpClaims = serde_json::from_slice(&vp_jws.payload)
+            .map_err(|_| InvalidVcPresentation::InvalidClaims)?;
+
+        if claims.iat > current_time + self.time_leeway {
+            return Err(InvalidVcPresentation::InvalidValidityPeriod);
+        }
+
+        ensure_valid_period(Some(claims.exp), claims.nbf, current_time)
+            .map_err(|_| InvalidVcPresentation::InvalidValidityPeriod)?;
+
+        if claims.exp - claims.iat > self.max_lifetime {
+            return Err(InvalidVcPresentation::ExcessiveLifetime);
+        }
+
+        if claims.iss != agent.as_str() {
+            return Err(InvalidVcPresentation::InvalidHolder);
+        }
+
+        if !claims
+            .aud
+            .into_vec()
+            .iter()
+            .any(|aud| audience_includes(aud, req_uri))
+        {
+            return Err(InvalidVcPresentation::InvalidAudience);
+        }
+
+        // Ensure presentation is not a replayed one. Ids are
+        // recorded only after verification, so that forged
+        // presentations cannot exhaust them.
+        self.jti_cache
+            .record((claims.iss.clone(), claims.jti), self.jti_ttl)
+            .map_err(|e| match e {
+                ReplayCacheError::Replayed => InvalidVcPresentation::Replayed,
+                ReplayCacheError::Full => InvalidVcPresentation::ReplayUncheckable,
+            })?;
+
+        Ok(claims
+            .vp
+            .verifiable_credential
+            .iter()
+            .filter_map(|compact_vc| {
+                self.verify_credential(compact_vc, &claims.iss, current_time)
+                    .map_err(|e| error!("Skipping invalid credential. Error:\n {}", e))
+                    .ok()
+            })
+            .collect())
+    }
+
+    /// Verify given compact vc-jwt credential, presented by
+    /// given holder, at given time.
+    fn verify_credential(
+        &self,
+        compact_vc: &str,
+        holder: &str,
+        current_time: i64,
+    ) -> Result<VerifiedCredential, InvalidCredential> {
+        let raw_vc = RawJws::decode(compact_vc)?;
+
+        if SignatureAlgorithm::try_from(raw_vc.header.alg).is_err() {
+            return Err(InvalidCredential::UnsupportedAlg);
+        }
+
+        // Resolve issuer without trusting the signature yet.
+        let unverified_claims: VcClaims = serde_json::from_slice(raw_vc.peek_payload())
+            .map_err(|_| InvalidCredential::InvalidClaims)?;
+
+        if !self.trusted_issuers.is_trusted(&unverified_claims.iss) {
+            return Err(InvalidCredential::UntrustedIssuer);
+        }
+
+        let issuer_jwk = self
+            .trusted_issuers
+            .resolve_key(&unverified_claims.iss, raw_vc.header.kid.as_deref())
+            .ok_or(InvalidCredential::UnresolvedIssuerKey)?;
+
+        // Verify credential signature.
+        let vc_jws = raw_vc.verify(&issuer_jwk.to_public_key()?)?;
+
+        let claims: VcClaims = serde_json::from_slice(&vc_jws.payload)
+            .map_err(|_| InvalidCredential::InvalidClaims)?;
+
+        ensure_valid_period(claims.exp, claims.nbf, current_time)?;
+
+        // Ensure credential is issued to the holder.
+        let subject = claims
+            .sub
+            .as_deref()
+            .or(claims.vc.credential_subject.id.as_deref());
+        if subject != Some(holder) {
+            return Err(InvalidCredential::InvalidSubject);
+        }
+
+        Ok(VerifiedCredential {
+            issuer: claims.iss,
+            types: claims
+                .vc
+                .type_
+                .into_vec()
+                .into_iter()
+                .filter_map(|vc_type| {
+                    if vc_type == BASE_VC_TYPE {
+                        return Some(BASE_VC_TYPE_IRI.to_owned());
+                    }
+                    // Compact terms other than the base type
+                    // cannot be resolved without json-ld
+                    // expansion, and hence are skipped.
+                    Iri::new(vc_type.as_str()).is_ok().then_some(vc_type)
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Check if given presentation audience includes given
+/// request uri.
+///
+/// Audience must be of the same origin as the request uri,
+/// and it's path must be a prefix of the request uri path
+/// at a segment boundary.
+fn audience_includes(aud: &str, req_uri: &str) -> bool {
+    let (Ok(aud), Ok(req_uri)) = (aud.parse::<Uri>(), req_uri.parse::<Uri>()) else {
+        return false;
+    };
+
+    if aud.scheme().is_none()
+        || aud.scheme() != req_uri.scheme()
+        || aud.authority().is_none()
+        || aud.authority() != req_uri.authority()
+        || aud.query().is_some()
+    {
+        return false;
+    }
+
+    let aud_path = aud.path();
+    req_uri
+        .path()
+        .strip_prefix(aud_path)
+        .is_some_and(|rest| aud_path.ends_with('/') || rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Ensure that given time is in validity period.
+fn ensure_valid_period(
+    opt_exp: Option<i64>,
+    opt_nbf: Option<i64>,
+    current_time: i64,
+) -> Result<(), InvalidCredential> {
+    if opt_exp.map(|exp| current_time >= exp).unwrap_or(false) {
+        return Err(InvalidCredential::IsExpired);
+    }
+    if opt_nbf.map(|nbf| current_time < nbf).unwrap_or(false) {
+        return Err(InvalidCredential::IsNotYetValid);
+    }
+    Ok(())
+}
+
+/// A type for representing one or many values.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T> OneOrMany<T> {
+    fn into_vec(self) -> Vec<T> {
+        match self {
+            Self::One(v) => vec![v],
+            Self::Many(vs) => vs,
+        }
+    }
+}
+
+/// Claims of a vc-jwt presentation.
+#[derive(Debug, Deserialize)]
+struct VpClaims {
+    iss: String,
+    aud: OneOrMany<String>,
+    exp: i64,
+    iat: i64,
+    nbf: Option<i64>,
+    jti: String,
+    vp: VpClaim,
+}
+
+/// `vp` claim of a vc-jwt presentation.
+#[derive(Debug, Deserialize)]
+struct VpClaim {
+    #[serde(rename = "verifiableCredential", default)]
+    verifiable_credential: Vec<String>,
+}
+
+/// Claims of a vc-jwt credential.
+#[derive(Debug, Deserialize)]
+struct VcClaims {
+    iss: String,
+    sub: Option<String>,
+    exp: Option<i64>,
+    nbf: Option<i64>,
+    vc: VcClaim,
+}
+
+/// `vc` claim of a vc-jwt credential.
+#[derive(Debug, Deserialize)]
+struct VcClaim {
+    #[serde(rename = "type")]
+    type_: OneOrMany<String>,
+
+    #[serde(rename = "credentialSubject", default)]
+    credential_subject: VcCredentialSubject,
+}
+
+/// Credential subject of a vc-jwt credential.
+#[derive(Debug, Default, Deserialize)]
+struct VcCredentialSubject {
+    id: Option<String>,
+}
+
+/// A type for representing errors of a presentation being
+/// invalid.
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidVcPresentation {
+    /// Invalid jws.
+    #[error("Invalid jws.\n{0}")]
+    InvalidJws(#[from] JwsError),
+
+    /// Agent credentials are not bound to a key.
+    #[error("Agent credentials are not bound to a key.")]
+    UnboundAgentCredentials,
+
+    /// Missing holder key.
+    #[error("Missing holder key.")]
+    MissingHolderKey,
+
+    /// Holder key is not the key agent credentials are bound to.
+    #[error("Holder key is not the key agent credentials are bound to.")]
+    HolderKeyMismatch,
+
+    /// Invalid holder key jwk.
+    #[error("Invalid holder key jwk.\n{0}")]
+    InvalidHolderKeyJwk(#[from] JwkError),
+
+    /// Unsupported alg.
+    #[error("Unsupported alg.")]
+    UnsupportedAlg,
+
+    /// Invalid claims.
+    #[error("Invalid claims.")]
+    InvalidClaims,
+
+    /// Presentation is expired, or is not yet valid.
+    #[error("Presentation is expired, or is not yet valid.")]
+    InvalidValidityPeriod,
+
+    /// Presentation lifetime exceeds the max lifetime.
+    #[error("Presentation lifetime exceeds the max lifetime.")]
+    ExcessiveLifetime,
+
+    /// Presentation is already presented.
+    #[error("Presentation is already presented.")]
+    Replayed,
+
+    /// Presentation cannot be checked for replay, as too
+    /// many presentations are remembered.
+    #[error("Presentation cannot be checked for replay.")]
+    ReplayUncheckable,
+
+    /// Presentation holder is not the request agent.
+    #[error("Presentation holder is not the request agent.")]
+    InvalidHolder,
+
+    /// Presentation audience doesn't include request uri.
+    #[error("Presentation audience doesn't include request uri.")]
+    InvalidAudience,
+}
+
+/// A type for representing errors of a credential being
+/// invalid.
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidCredential {
+    /// Invalid jws.
+    #[error("Invalid jws.\n{0}")]
+    InvalidJws(#[from] JwsError),
+
+    /// Unsupported alg.
+    #[error("Unsupported alg.")]
+    UnsupportedAlg,
+
+    /// Invalid claims.
+    #[error("Invalid claims.")]
+    InvalidClaims,
+
+    /// Issuer is not trusted.
+    #[error("Issuer is not trusted.")]
+    UntrustedIssuer,
+
+    /// Issuer key is not resolved.
+    #[error("Issuer key is not resolved.")]
+    UnresolvedIssuerKey,
+
+    /// Invalid issuer key jwk.
+    #[error("Invalid issuer key jwk.\n{0}")]
+    InvalidIssuerKeyJwk(#[from] JwkError),
+
+    /// Credential is expired.
+    #[error("Credential is expired.")]
+    IsExpired,
+
+    /// Credential is not yet valid.
+    #[error("Credential is not yet valid.")]
+    IsNotYetValid,
+
+    /// Credential is not issued to the holder.
+    #[error("Credential is not issued to the holder.")]
+    InvalidSubject,
+}
+
+#[cfg(test)]
+mod tests {
+    use claims::*;
+    use picky::{
+        jose::{
+            jwk::Jwk,
+            jws::{Jws, JwsAlg},
+        },
+        key::{EcCurve, PrivateKey},
+    };
+    use rstest::*;
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    const AGENT: &str = "https://alice.example/profile#me";
+
+    const NOW: i64 = 1_700_000_000;
+
+    struct Holder {
+        key: PrivateKey,
+        jwk: Jwk,
+        jkt: String,
+    }
+
+    fn holder() -> Holder {
+        let key = PrivateKey::generate_ec(EcCurve::NistP256).expect("Must generate key.");
+        let jwk = Jwk::from_public_key(&key.to_public_key().expect("Must be valid."))
+            .expect("Must be valid.");
+        let jkt = Jkt::new(&jwk).into();
+        Holder { key, jwk, jkt }
+    }
+
+    fn sign_vp(holder: &Holder, claims: &Value) -> String {
+        let mut jws = Jws::new(
+            JwsAlg::ES256,
+            serde_json::to_vec(claims).expect("Must serialize."),
+        );
+        jws.header.jwk = Some(holder.jwk.clone());
+        jws.encode(&holder.key).expect("Must sign.")
+    }
+
+    fn vp_claims() -> Value {
+        json!({
+            "iss": AGENT,
+            "aud": "https://pod.example/alice/",
+            "iat": NOW,
+            "exp": NOW + 60,
+            "jti": "vp-1",
+            "vp": { "verifiableCredential": [] },
+        })
+    }
+
+    fn verify(
+        verifier: &VcPresentationVerifier,
+        compact_vp: &str,
+        bound_key_jkt: Option<&str>,
+    ) -> Result<Vec<VerifiedCredential>, InvalidVcPresentation> {
+        verifier.verify(
+            compact_vp,
+            &AGENT.parse().expect("Must be valid."),
+            bound_key_jkt,
+            "https://pod.example/alice/notes/a.ttl",
+            NOW,
+        )
+    }
+
+    #[rstest]
+    #[case("https://pod.example/alice/", "https://pod.example/alice/a.ttl", true)]
+    #[case("https://pod.example/alice", "https://pod.example/alice/a.ttl", true)]
+    #[case("https://pod.example/alice", "https://pod.example/alice", true)]
+    #[case("https://pod.example", "https://pod.example/alice/a.ttl", true)]
+    #[case("https://POD.example/", "https://pod.example/alice/a.ttl", true)]
+    #[case("https://pod.example/alice", "https://pod.example/alice2/a.ttl", false)]
+    #[case("https://pod.example", "https://pod.example.evil/a.ttl", false)]
+    #[case("https://pod.example", "https://pod.example:8443/a.ttl", false)]
+    #[case("http://pod.example/", "https://pod.example/a.ttl", false)]
+    #[case("https://pod.example/?a", "https://pod.example/?a", false)]
+    #[case("/alice/", "https://pod.example/alice/a.ttl", false)]
+    #[case("", "https://pod.example/alice/a.ttl", false)]
+    fn audience_inclusion_works_correctly(
+        #[case] aud: &str,
+        #[case] req_uri: &str,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(audience_includes(aud, req_uri), expected);
+    }
+
+    #[test]
+    fn valid_presentation_is_accepted_only_once() {
+        let holder = holder();
+        let verifier = VcPresentationVerifier::default();
+        let compact_vp = sign_vp(&holder, &vp_claims());
+
+        assert_ok!(verify(&verifier, &compact_vp, Some(&holder.jkt)));
+        assert_matches!(
+            verify(&verifier, &compact_vp, Some(&holder.jkt)),
+            Err(InvalidVcPresentation::Replayed)
+        );
+    }
+
+    #[test]
+    fn presentation_is_rejected_when_jti_cache_is_full() {
+        let holder = holder();
+        let verifier = VcPresentationVerifier::default().with_jti_cache_capacity(1);
+
+        assert_ok!(verify(
+            &verifier,
+            &sign_vp(&holder, &vp_claims()),
+            Some(&holder.jkt)
+        ));
+
+        let mut claims = vp_claims();
+        claims["jti"] = json!("vp-2");
+        assert_matches!(
+            verify(&verifier, &sign_vp(&holder, &claims), Some(&holder.jkt)),
+            Err(InvalidVcPresentation::ReplayUncheckable)
+        );
+    }
+
+    #[test]
+    fn presentation_from_unbound_agent_is_rejected() {
+        let holder = holder();
+        let compact_vp = sign_vp(&holder, &vp_claims());
+
+        assert_matches!(
+            verify(&VcPresentationVerifier::default(), &compact_vp, None),
+            Err(InvalidVcPresentation::UnboundAgentCredentials)
+        );
+    }
+
+    #[test]
+    fn presentation_signed_with_other_key_is_rejected() {
+        let holder = holder();
+        let compact_vp = sign_vp(&holder, &vp_claims());
+
+        assert_matches!(
+            verify(
+                &VcPresentationVerifier::default(),
+                &compact_vp,
+                Some(&self::holder().jkt)
+            ),
+            Err(InvalidVcPresentation::HolderKeyMismatch)
+        );
+    }
+
+    #[rstest]
+    #[case("iat", json!(NOW - 3600))]
+    #[case("exp", json!(NOW + 3600))]
+    fn presentation_with_excessive_lifetime_is_rejected(#[case] claim: &str, #[case] value: Value) {
+        let holder = holder();
+        let mut claims = vp_claims();
+        claims[claim] = value;
+        let compact_vp = sign_vp(&holder, &claims);
+
+        assert_matches!(
+            verify(
+                &VcPresentationVerifier::default(),
+                &compact_vp,
+                Some(&holder.jkt)
+            ),
+            Err(InvalidVcPresentation::ExcessiveLifetime)
+        );
+    }
+
+    #[test]
+    fn presentation_issued_in_future_is_rejected() {
+        let holder = holder();
+        let mut claims = vp_claims();
+        claims["iat"] = json!(NOW + 120);
+        claims["exp"] = json!(NOW + 180);
+        let compact_vp = sign_vp(&holder, &claims);
+
+        assert_matches!(
+            verify(
+                &VcPresentationVerifier::default(),
+                &compact_vp,
+                Some(&holder.jkt)
+            ),
+            Err(InvalidVcPresentation::InvalidValidityPeriod)
+        );
+    }
+
+    #[rstest]
+    #[case("iat")]
+    #[case("jti")]
+    fn presentation_without_required_claim_is_rejected(#[case] claim: &str) {
+        let holder = holder();
+        let mut claims = vp_claims();
+        claims
+            .as_object_mut()
+            .expect("Must be an object.")
+            .remove(claim);
+        let compact_vp = sign_vp(&holder, &claims);
+
+        assert_matches!(
+            verify(
+                &VcPresentationVerifier::default(),
+                &compact_vp,
+                Some(&holder.jkt)
+            ),
+            Err(InvalidVcPresentation::InvalidClaims)
+        );
+    }
+
+    #[test]
+    fn presentation_for_other_audience_is_rejected() {
+        let holder = holder();
+        let mut claims = vp_claims();
+        claims["aud"] = json!(["https://pod.example/ali"]);
+        let compact_vp = sign_vp(&holder, &claims);
+
+        assert_matches!(
+            verify(
+                &VcPresentationVerifier::default(),
+                &compact_vp,
+                Some(&holder.jkt)
+            ),
+            Err(InvalidVcPresentation::InvalidAudience)
+        );
+    }
+}
@@ -25,6 +25,9 @@ pub mod problem;
 #[cfg(feature = "service")]
 pub mod service;
 
+#[cfg(feature = "public-addr")]
+pub mod public_addr;
+
 pub mod uri;
 
 #[cfg(feature = "typed-headers")]
@@ -0,0 +1,211 @@
+//! I define guards on targets of outgoing requests, that
+//! prevent untrusted parties, like webhook subscribers or acl
+//! authors, from making the server send requests to loopback,
+//! private, or otherwise internal addresses.
+//!
+
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+};
+
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    Url,
+};
+
+/// Check if given ip address is publicly routable.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ipv4(ip),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+/// Check if given ipv4 address is publicly routable.
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // "This network" (0.0.0.0/8).
+        || a == 0
+        // Shared address space (100.64.0.0/10).
+        || (a == 100 && (b & 0xc0) == 64)
+        // Ietf protocol assignments (192.0.0.0/24).
+        || (a == 192 && b == 0 && c == 0)
+        // Benchmarking (198.18.0.0/15).
+        || (a == 198 && (b & 0xfe) == 18)
+        // Reserved (240.0.0.0/4).
+        || a >= 240)
+}
+
+/// Check if given ipv6 address is publicly routable.
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let segments = ip.segments();
+
+    // Nat64 addresses (64:ff9b::/96) embed ipv4 addresses.
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        let [.., hi, lo] = segments;
+        return is_public_ipv4(Ipv4Addr::from((u32::from(hi) << 16) | u32::from(lo)));
+    }
+
+    // 6to4 addresses (2002::/16) embed ipv4 addresses.
+    if segments[0] == 0x2002 {
+        return is_public_ipv4(Ipv4Addr::from(
+            (u32::from(segments[1]) << 16) | u32::from(segments[2]),
+        ));
+    }
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Teredo addresses (2001::/32) embed obfuscated ipv4
+        // addresses of both relay and client.
+        || (segments[0] == 0x2001 && segments[1] == 0)
+        // Unique local addresses (fc00::/7).
+        || (segments[0] & 0xfe00) == 0xfc00
+        // Link local addresses (fe80::/10).
+        || (segments[0] & 0xffc0) == 0xfe80
+        // Deprecated site local addresses (fec0::/10).
+        || (segments[0] & 0xffc0) == 0xfec0
+        // Documentation addresses (2001:db8::/32).
+        || (segments[0] == 0x2001 && segments[1] == 0xdb8))
+}
+
+/// Check if given url has an ip literal host, that is not
+/// publicly routable.
+///
+/// Such hosts are not resolved through [`PublicAddrResolver`],
+/// and hence must be checked before each request.
+pub fn has_non_public_ip_host(url: &Url) -> bool {
+    match url.host_str() {
+        Some(_) => resolve_ip_host(url).map_or(false, |ip| !is_public_ip(ip)),
+        None => true,
+    }
+}
+
+/// Resolve the host of given url, if it is an ip literal.
+fn resolve_ip_host(url: &Url) -> Option<IpAddr> {
+    url.host_str()?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .ok()
+}
+
+/// Check that given url targets only publicly routable
+/// addresses.
+pub async fn ensure_public_target(url: &Url) -> io::Result<()> {
+    if has_non_public_ip_host(url) {
+        return Err(non_public_target_error());
+    }
+
+    if let (None, Some(domain)) = (resolve_ip_host(url), url.host_str()) {
+        let port = url.port_or_known_default().unwrap_or_default();
+        let mut addrs = tokio::net::lookup_host((domain, port)).await?.peekable();
+
+        if addrs.peek().is_none() || addrs.any(|addr| !is_public_ip(addr.ip())) {
+            return Err(non_public_target_error());
+        }
+    }
+
+    Ok(())
+}
+
+/// Get an error for non public targets.
+#[inline]
+fn non_public_target_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        "Target doesn't resolve to only public addresses.",
+    )
+}
+
+/// An implementation of [`Resolve`], that resolves names
+/// only to publicly routable addresses.
+///
+/// Resolution is repeated for each connection, so that
+/// names cannot be rebound to internal addresses after
+/// targets are validated.
+#[derive(Debug, Default)]
+pub struct PublicAddrResolver;
+
+impl Resolve for PublicAddrResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_owned();
+
+        Box::pin(async move {
+            let addrs = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect::<Vec<SocketAddr>>();
+
+            if addrs.is_empty() {
+                return Err(non_public_target_error().into());
+            }
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case("93.184.216.34", true)]
+    #[case("2606:2800:220:1:248:1893:25c8:1946", true)]
+    #[case("127.0.0.1", false)]
+    #[case("0.0.0.0", false)]
+    #[case("10.1.2.3", false)]
+    #[case("172.16.0.1", false)]
+    #[case("192.168.1.1", false)]
+    #[case("169.254.169.254", false)]
+    #[case("100.64.0.1", false)]
+    #[case("255.255.255.255", false)]
+    #[case("::1", false)]
+    #[case("::", false)]
+    #[case("fd00::1", false)]
+    #[case("fe80::1", false)]
+    #[case("::ffff:127.0.0.1", false)]
+    #[case("::ffff:93.184.216.34", true)]
+    #[case("64:ff9b::a00:1", false)]
+    #[case("64:ff9b::5db8:d822", true)]
+    #[case("2002:a00:1::1", false)]
+    #[case("2002:c0a8:101::1", false)]
+    #[case("2002:5db8:d822::1", true)]
+    #[case("2001:0:4136:e378:8000:63bf:f5ff:fffe", false)]
+    #[case("fec0::1", false)]
+    #[case("2001:db8::1", false)]
+    fn is_public_ip_works_correctly(#[case] ip: &str, #[case] expected: bool) {
+        assert_eq!(is_public_ip(ip.parse().unwrap()), expected);
+    }
+
+    #[rstest]
+    #[case("http://127.0.0.1:8080/inbox", true)]
+    #[case("http://[::1]/inbox", true)]
+    #[case("http://169.254.169.254/latest/meta-data", true)]
+    #[case("https://93.184.216.34/inbox", false)]
+    #[case("https://example.org/inbox", false)]
+    fn has_non_public_ip_host_works_correctly(#[case] url: &str, #[case] expected: bool) {
+        assert_eq!(has_non_public_ip_host(&url.parse().unwrap()), expected);
+    }
+
+    #[tokio::test]
+    async fn ensure_public_target_rejects_localhost() {
+        let url: Url = "http://localhost:8080/inbox".parse().unwrap();
+        assert!(ensure_public_target(&url).await.is_err());
+    }
+}
@@ -0,0 +1,8 @@
+//! I define implementations of [`NotificationChannel`](super::NotificationChannel).
+//!
+
+mod webhook;
+mod websocket;
+
+pub use webhook::*;
+pub use websocket::*;
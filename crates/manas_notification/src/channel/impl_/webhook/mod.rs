@@ -0,0 +1,1362 @@
+//! I define an implementation of [`NotificationChannel`] for
+//! `WebhookChannel2023` channel type.
+//!
+
+use std::{
+    convert::Infallible,
+    fmt::Debug,
+    io,
+    marker::PhantomData,
+    path::PathBuf,
+    sync::{Arc, Weak},
+    task::Poll,
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use dyn_problem::{type_::UNKNOWN_IO_ERROR, ProbFuture, Problem};
+use futures::StreamExt;
+use http::{header, Method, Request, Response, StatusCode};
+use http_api_problem::ApiError;
+use manas_http::{
+    body::Body,
+    problem::ApiErrorExt,
+    public_addr::{ensure_public_target, has_non_public_ip_host, PublicAddrResolver},
+    service::{BoxHttpResponseFuture, HttpService},
+    uri::invariant::{AbsoluteHttpUri, NormalAbsoluteHttpUri},
+};
+use manas_repo_layers::eventing::event::{ResourceChangeEvent, ResourceChangeEventBus};
+use manas_space::{resource::uri::SolidResourceUri, SolidStorageSpace};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    Notify,
+};
+use tower::Service;
+use tracing::{debug, error, info, warn};
+
+use self::store::DurableRecords;
+use crate::{
+    channel::{NotificationChannel, CHANNEL_LIMIT_EXCEEDED, INVALID_SUBSCRIPTION},
+    model::{channel::ChannelDescription, notification::Notification, subscription::Subscription},
+    service::resolve_subscription_res_uri,
+};
+
+mod signer;
+mod store;
+
+pub use signer::*;
+
+/// Path of the jwks resource, relative to the subscription
+/// resource of webhook channel type.
+pub const JWKS_REL_PATH: &str = "jwks";
+
+/// Upper bound on the idle time of the dispatcher, before it
+/// rechecks the queue.
+const MAX_DISPATCHER_IDLE_TIME: Duration = Duration::from_secs(60);
+
+/// Configuration for [`WebhookChannel2023`].
+#[derive(Debug, Clone)]
+pub struct WebhookChannelConfig {
+    /// Directory in which subscriptions, delivery queue, and
+    /// signing key are persisted. If not provided, they are
+    /// kept in memory only.
+    pub state_dir: Option<PathBuf>,
+
+    /// Maximum life time of a channel. Requested expiry
+    /// times are capped by this.
+    pub max_ttl: Duration,
+
+    /// Maximum number of delivery attempts of a
+    /// notification.
+    pub max_attempts: u32,
+
+    /// Backoff before the first retry. It doubles for each
+    /// subsequent retry.
+    pub initial_backoff: Duration,
+
+    /// Maximum backoff between retries.
+    pub max_backoff: Duration,
+
+    /// Timeout of each delivery request.
+    pub request_timeout: Duration,
+
+    /// Whether to allow `sendTo` uris, that resolve to
+    /// loopback, private, link-local, or other non public
+    /// addresses. It must be enabled only in trusted
+    /// deployments, as it allows subscribers to make the
+    /// server send requests to internal services.
+    pub allow_private_targets: bool,
+
+    /// Maximum number of live channels.
+    pub max_channels: usize,
+
+    /// Maximum number of live channels per subscribing
+    /// agent. Channels of unauthenticated subscribers are
+    /// counted together.
+    pub max_channels_per_agent: usize,
+
+    /// Maximum number of concurrent delivery attempts.
+    pub max_concurrent_deliveries: usize,
+
+    /// Maximum number of queued deliveries, including the
+    /// ones pending retry. Notifications are dropped, while
+    /// the queue is full.
+    pub max_queued_deliveries: usize,
+}
+
+impl Default for WebhookChannelConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            state_dir: None,
+            max_ttl: Duration::from_secs(7 * 24 * 60 * 60),
+            max_attempts: 8,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(10 * 60),
+            request_timeout: Duration::from_secs(10),
+            allow_private_targets: false,
+            max_channels: 10_000,
+            max_channels_per_agent: 32,
+            max_concurrent_deliveries: 32,
+            max_queued_deliveries: 10_000,
+        }
+    }
+}
+
+impl WebhookChannelConfig {
+    /// Resolve backoff after given number of failed attempts.
+    fn backoff(&self, attempts: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+        self.initial_backoff
+            .checked_mul(factor)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+}
+
+/// A persisted webhook subscription.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WebhookSubscription {
+    /// Topic resource uri.
+    topic: String,
+
+    /// Uri to which notifications are to be sent.
+    send_to: String,
+
+    /// Expiry time of the channel.
+    end_at: DateTime<Utc>,
+
+    /// WebId of the subscribing agent, if authenticated.
+    #[serde(default)]
+    subscriber: Option<String>,
+}
+
+impl WebhookSubscription {
+    /// Check if subscription is live at given time.
+    #[inline]
+    fn is_live(&self, now: DateTime<Utc>) -> bool {
+        self.end_at > now
+    }
+}
+
+/// A persisted job to deliver a notification.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DeliveryJob {
+    /// Id of the channel.
+    channel_id: String,
+
+    /// Serialized notification.
+    body: String,
+
+    /// Number of failed attempts so far.
+    attempts: u32,
+
+    /// Time at which next attempt is due.
+    next_attempt_at: DateTime<Utc>,
+}
+
+/// An implementation of [`NotificationChannel`] for
+/// `WebhookChannel2023` channel type.
+///
+/// It POSTs notifications to the `sendTo` uri of the
+/// subscriber. Requests are signed as per RFC 9421, with a
+/// key published at `<subscription-resource>jwks`. Failed
+/// deliveries are retried with exponential backoff, from a
+/// queue that survives restarts, if a state directory is
+/// configured.
+///
+/// Unless configured otherwise, `sendTo` uris must resolve
+/// only to public addresses, both at subscription and at
+/// delivery, and redirects are not followed.
+///
+/// Subscribers can close a channel by sending a `DELETE`
+/// request to it's uri. As channel uris are unguessable,
+/// and are disclosed only to the subscriber, they act as
+/// capabilities for closing the channels.
+///
+/// Number of live channels is bounded in total, and per
+/// subscribing agent, and so are the number of queued
+/// deliveries, and the number of concurrent delivery
+/// attempts.
+pub struct WebhookChannel2023<Space: SolidStorageSpace> {
+    inner: Arc<Inner<Space>>,
+}
+
+struct Inner<Space: SolidStorageSpace> {
+    subscription_res_uri: SolidResourceUri,
+    config: WebhookChannelConfig,
+    signer: WebhookRequestSigner,
+    client: reqwest::Client,
+    subscriptions: DurableRecords<WebhookSubscription>,
+    jobs: DurableRecords<DeliveryJob>,
+    jobs_notify: Arc<Notify>,
+    registration_lock: tokio::sync::Mutex<()>,
+    _phantom: PhantomData<fn(Space)>,
+}
+
+impl<Space: SolidStorageSpace> Clone for WebhookChannel2023<Space> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<Space: SolidStorageSpace> Debug for WebhookChannel2023<Space> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookChannel2023")
+            .field("subscription_res_uri", &self.inner.subscription_res_uri)
+            .field("config", &self.inner.config)
+            .finish()
+    }
+}
+
+impl<Space: SolidStorageSpace> WebhookChannel2023<Space> {
+    /// Try to create a new [`WebhookChannel2023`] over given
+    /// storage space, with given event bus, and config.
+    ///
+    /// Persisted subscriptions and pending deliveries are
+    /// restored from the configured state directory, and
+    /// background tasks for queueing and delivering
+    /// notifications are spawned.
+    pub async fn try_new(
+        space: &Space,
+        event_bus: ResourceChangeEventBus<Space>,
+        config: WebhookChannelConfig,
+    ) -> io::Result<Self> {
+        let subscription_res_uri =
+            resolve_subscription_res_uri(space, <Self as NotificationChannel>::SLUG);
+
+        let state_dir = config.state_dir.as_ref();
+        if let Some(state_dir) = state_dir {
+            tokio::fs::create_dir_all(state_dir).await?;
+        }
+
+        let signer = WebhookRequestSigner::load_or_generate(
+            state_dir.map(|dir| dir.join("signing_key")).as_deref(),
+            format!("{}{}", subscription_res_uri.as_str(), JWKS_REL_PATH),
+        )
+        .await?;
+
+        let subscriptions =
+            DurableRecords::load(state_dir.map(|dir| dir.join("subscriptions"))).await?;
+        let jobs = DurableRecords::load(state_dir.map(|dir| dir.join("deliveries"))).await?;
+
+        let mut client_builder = reqwest::Client::builder()
+            .timeout(config.request_timeout)
+            // Redirects may lead to internal addresses.
+            .redirect(reqwest::redirect::Policy::none())
+            // Proxies would resolve targets on our behalf.
+            .no_proxy();
+        if !config.allow_private_targets {
+            client_builder = client_builder.dns_resolver(Arc::new(PublicAddrResolver));
+        }
+        let client = client_builder.build().map_err(io::Error::other)?;
+
+        let inner = Arc::new(Inner {
+            subscription_res_uri,
+            config,
+            signer,
+            client,
+            subscriptions,
+            jobs,
+            jobs_notify: Default::default(),
+            registration_lock: Default::default(),
+            _phantom: PhantomData,
+        });
+
+        tokio::spawn(Self::listen(Arc::downgrade(&inner), event_bus.subscribe()));
+        tokio::spawn(Self::dispatch(
+            Arc::downgrade(&inner),
+            inner.jobs_notify.clone(),
+        ));
+
+        Ok(Self { inner })
+    }
+
+    /// Get the request signer of the channel.
+    #[inline]
+    pub fn signer(&self) -> &WebhookRequestSigner {
+        &self.inner.signer
+    }
+
+    /// Listen to change events, and enqueue notifications
+    /// for matching live subscriptions.
+    async fn listen(
+        inner: Weak<Inner<Space>>,
+        mut events: broadcast::Receiver<Arc<ResourceChangeEvent<Space>>>,
+    ) {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(n)) => {
+                    warn!("Webhook channel lagged behind by {} events.", n);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+
+            let Some(inner) = inner.upgrade() else {
+                break;
+            };
+
+            if let Err(e) = Self::enqueue(&inner, &event).await {
+                error!("Error in enqueuing notifications. Error:\n {}", e);
+            }
+        }
+    }
+
+    /// Enqueue notifications of given event for matching
+    /// live subscriptions.
+    async fn enqueue(inner: &Inner<Space>, event: &ResourceChangeEvent<Space>) -> io::Result<()> {
+        let now = Utc::now();
+        let mut is_enqueued = false;
+
+        for (channel_id, subscription) in inner.subscriptions.snapshot() {
+            if !subscription.is_live(now) {
+                continue;
+            }
+
+            let Some(notification) = SolidResourceUri::try_new_from(subscription.topic.as_str())
+                .ok()
+                .and_then(|topic| Notification::resolve_for_topic(event, &topic))
+            else {
+                continue;
+            };
+
+            if inner.jobs.len() >= inner.config.max_queued_deliveries {
+                warn!(
+                    "Webhook delivery queue is full. Dropping the notification to channel {}.",
+                    channel_id
+                );
+                continue;
+            }
+
+            inner
+                .jobs
+                .put(
+                    uuid::Uuid::new_v4().simple().to_string(),
+                    DeliveryJob {
+                        channel_id,
+                        body: serde_json::to_string(&notification).expect("Must be serializable."),
+                        attempts: 0,
+                        next_attempt_at: now,
+                    },
+                )
+                .await?;
+            is_enqueued = true;
+        }
+
+        if is_enqueued {
+            inner.jobs_notify.notify_one();
+        }
+        Ok(())
+    }
+
+    /// Deliver queued notifications as they become due,
+    /// until the channel is dropped.
+    async fn dispatch(inner: Weak<Inner<Space>>, jobs_notify: Arc<Notify>) {
+        loop {
+            let Some(inner) = inner.upgrade() else {
+                break;
+            };
+
+            let idle_time = match Self::dispatch_due(&inner).await {
+                Ok(Some(next_attempt_at)) => (next_attempt_at - Utc::now())
+                    .to_std()
+                    .unwrap_or_default()
+                    .min(MAX_DISPATCHER_IDLE_TIME),
+                Ok(None) => MAX_DISPATCHER_IDLE_TIME,
+                Err(e) => {
+                    error!("Error in dispatching notifications. Error:\n {}", e);
+                    MAX_DISPATCHER_IDLE_TIME
+                }
+            };
+            drop(inner);
+
+            tokio::select! {
+                _ = jobs_notify.notified() => {}
+                _ = tokio::time::sleep(idle_time) => {}
+            }
+        }
+    }
+
+    /// Deliver due notifications, and resolve the time at
+    /// which next attempt is due, if any.
+    async fn dispatch_due(inner: &Inner<Space>) -> io::Result<Option<DateTime<Utc>>> {
+        let now = Utc::now();
+
+        // Expire subscriptions.
+        inner
+            .subscriptions
+            .retain(|subscription| subscription.is_live(now))
+            .await?;
+
+        let mut due_jobs = Vec::new();
+        let mut next_attempt_at: Option<DateTime<Utc>> = None;
+
+        for (job_id, job) in inner.jobs.snapshot() {
+            let Some(subscription) = inner.subscriptions.get(&job.channel_id) else {
+                debug!("Dropping delivery to closed channel {}.", job.channel_id);
+                inner.jobs.remove(&job_id).await?;
+                continue;
+            };
+
+            if job.next_attempt_at <= now {
+                due_jobs.push(Self::deliver(inner, job_id, job, subscription.send_to));
+            } else {
+                next_attempt_at = Some(
+                    next_attempt_at.map_or(job.next_attempt_at, |t| t.min(job.next_attempt_at)),
+                );
+            }
+        }
+
+        let mut results = futures::stream::iter(due_jobs)
+            .buffer_unordered(inner.config.max_concurrent_deliveries.max(1));
+        while let Some(result) = results.next().await {
+            if let Some(retry_at) = result? {
+                next_attempt_at = Some(next_attempt_at.map_or(retry_at, |t| t.min(retry_at)));
+            }
+        }
+
+        Ok(next_attempt_at)
+    }
+
+    /// Attempt to deliver given job, and resolve the time of
+    /// next attempt, if it is to be retried.
+    async fn deliver(
+        inner: &Inner<Space>,
+        job_id: String,
+        mut job: DeliveryJob,
+        send_to: String,
+    ) -> io::Result<Option<DateTime<Utc>>> {
+        const CONTENT_TYPE: &str = "application/ld+json";
+
+        // Ip literal hosts are not resolved through the
+        // resolver, and hence are checked here.
+        let target_uri = match (
+            AbsoluteHttpUri::try_new_from(send_to.as_str()),
+            reqwest::Url::parse(&send_to),
+        ) {
+            (Ok(target_uri), Ok(url))
+                if inner.config.allow_private_targets || !has_non_public_ip_host(&url) =>
+            {
+                target_uri
+            }
+            _ => {
+                error!(
+                    "Invalid or non public target {}. Closing channel {}.",
+                    send_to, job.channel_id
+                );
+                inner.subscriptions.remove(&job.channel_id).await?;
+                inner.jobs.remove(&job_id).await?;
+                return Ok(None);
+            }
+        };
+
+        let mut request = inner
+            .client
+            .post(&send_to)
+            .header(header::CONTENT_TYPE, CONTENT_TYPE);
+        for (name, value) in inner.signer.sign(
+            &Method::POST,
+            &target_uri,
+            CONTENT_TYPE,
+            job.body.as_bytes(),
+            Utc::now().timestamp(),
+        ) {
+            request = request.header(name, value);
+        }
+
+        match request.body(job.body.clone()).send().await {
+            Ok(resp) if resp.status().is_success() => {
+                debug!("Delivered notification to {}.", send_to);
+                inner.jobs.remove(&job_id).await?;
+                return Ok(None);
+            }
+            Ok(resp) if resp.status() == StatusCode::GONE => {
+                info!("Subscriber is gone. Closing channel {}.", job.channel_id);
+                inner.subscriptions.remove(&job.channel_id).await?;
+                inner.jobs.remove(&job_id).await?;
+                return Ok(None);
+            }
+            Ok(resp) => warn!(
+                "Subscriber at {} responded to notification with status {}.",
+                send_to,
+                resp.status()
+            ),
+            Err(e) => warn!(
+                "Error in delivering notification to {}. Error:\n {}",
+                send_to, e
+            ),
+        }
+
+        job.attempts += 1;
+        if job.attempts >= inner.config.max_attempts {
+            error!(
+                "Giving up on delivering notification to {} after {} attempts.",
+                send_to, job.attempts
+            );
+            inner.jobs.remove(&job_id).await?;
+            return Ok(None);
+        }
+
+        let next_attempt_at = chrono::Duration::from_std(inner.config.backoff(job.attempts))
+            .ok()
+            .and_then(|backoff| Utc::now().checked_add_signed(backoff))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        job.next_attempt_at = next_attempt_at;
+        inner.jobs.put(job_id, job).await?;
+
+        Ok(Some(next_attempt_at))
+    }
+
+    /// Validate given `sendTo` uri.
+    async fn validate_send_to(
+        send_to: Option<&str>,
+        allow_private_targets: bool,
+    ) -> Result<String, Problem> {
+        let invalid_send_to = || {
+            INVALID_SUBSCRIPTION
+                .new_problem_builder()
+                .message("Webhook subscriptions require an absolute http(s) sendTo uri.")
+                .finish()
+        };
+
+        let send_to = send_to
+            .filter(|send_to| {
+                NormalAbsoluteHttpUri::try_new_from(*send_to).is_ok()
+                    && (send_to.starts_with("http://") || send_to.starts_with("https://"))
+            })
+            .ok_or_else(invalid_send_to)?;
+        let url = reqwest::Url::parse(send_to).map_err(|_| invalid_send_to())?;
+
+        if !allow_private_targets {
+            ensure_public_target(&url).await.map_err(|e| {
+                error!("sendTo uri is not a public target. Error:\n {}", e);
+                INVALID_SUBSCRIPTION
+                    .new_problem_builder()
+                    .message("Webhook sendTo uri must resolve only to public addresses.")
+                    .finish()
+            })?;
+        }
+
+        Ok(send_to.to_owned())
+    }
+
+    /// Register given subscription with given channel id, if
+    /// it is within the configured channel limits.
+    async fn try_register(
+        inner: &Inner<Space>,
+        channel_id: String,
+        subscription: WebhookSubscription,
+    ) -> Result<(), Problem> {
+        // Serialize registrations, so that concurrent ones
+        // cannot together exceed the limits.
+        let _guard = inner.registration_lock.lock().await;
+
+        let now = Utc::now();
+        let live_subscriptions = inner
+            .subscriptions
+            .snapshot()
+            .into_iter()
+            .filter(|(_, s)| s.is_live(now))
+            .map(|(_, s)| s)
+            .collect::<Vec<_>>();
+
+        if live_subscriptions.len() >= inner.config.max_channels {
+            warn!("Maximum number of live webhook channels reached.");
+            return Err(CHANNEL_LIMIT_EXCEEDED
+                .new_problem_builder()
+                .message("Maximum number of live channels reached.")
+                .finish());
+        }
+
+        let subscriber_channel_count = live_subscriptions
+            .iter()
+            .filter(|s| s.subscriber == subscription.subscriber)
+            .count();
+        if subscriber_channel_count >= inner.config.max_channels_per_agent {
+            warn!("Maximum number of live webhook channels reached for subscriber.");
+            return Err(CHANNEL_LIMIT_EXCEEDED
+                .new_problem_builder()
+                .message("Maximum number of live channels reached for the subscriber.")
+                .finish());
+        }
+
+        inner
+            .subscriptions
+            .put(channel_id, subscription)
+            .await
+            .map_err(|e| {
+                error!("Error in persisting subscription. Error:\n {}", e);
+                UNKNOWN_IO_ERROR
+                    .new_problem_builder()
+                    .message("Error in persisting subscription.")
+                    .source(e)
+                    .finish()
+            })
+    }
+
+    /// Close the channel with given id.
+    async fn close(&self, channel_id: &str) -> io::Result<bool> {
+        if self.inner.subscriptions.get(channel_id).is_none() {
+            return Ok(false);
+        }
+
+        self.inner.subscriptions.remove(channel_id).await?;
+        self.inner
+            .jobs
+            .retain(|job| job.channel_id != channel_id)
+            .await?;
+
+        info!("Closed webhook channel {}.", channel_id);
+        Ok(true)
+    }
+
+    /// Handle requests to the channel endpoints.
+    async fn handle(&self, req: Request<Body>) -> Response<Body> {
+        let uri = req
+            .extensions()
+            .get::<NormalAbsoluteHttpUri>()
+            .expect("Service must be called after handling uri normal validity check.");
+
+        // Handle unsubscription requests.
+        if let Some(channel_id) = uri
+            .as_str()
+            .strip_prefix(self.inner.subscription_res_uri.as_str())
+            .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric()))
+            .filter(|id| *id != JWKS_REL_PATH)
+        {
+            if req.method() != Method::DELETE {
+                error!("Method not allowed on channel resource.");
+                return ApiError::builder(StatusCode::METHOD_NOT_ALLOWED)
+                    .message("Channel resource only supports DELETE method.")
+                    .finish()
+                    .into_http_response();
+            }
+
+            return match self.close(channel_id).await {
+                Ok(true) => Response::builder()
+                    .status(StatusCode::NO_CONTENT)
+                    .body(Body::empty())
+                    .expect("Must be valid."),
+                Ok(false) => {
+                    error!("No channel with given uri.");
+                    ApiError::builder(StatusCode::NOT_FOUND)
+                        .message("No channel with given uri.")
+                        .finish()
+                        .into_http_response()
+                }
+                Err(e) => {
+                    error!("Error in closing the channel. Error:\n {}", e);
+                    ApiError::builder(StatusCode::INTERNAL_SERVER_ERROR)
+                        .finish()
+                        .into_http_response()
+                }
+            };
+        }
+
+        if uri.as_str() != self.inner.signer.key_id() {
+            error!("No webhook channel endpoint at given uri.");
+            return ApiError::builder(StatusCode::NOT_FOUND)
+                .message("No webhook channel endpoint at given uri.")
+                .finish()
+                .into_http_response();
+        }
+
+        if req.method() != Method::GET && req.method() != Method::HEAD {
+            error!("Method not allowed on jwks resource.");
+            return ApiError::builder(StatusCode::METHOD_NOT_ALLOWED)
+                .message("Jwks resource only supports GET and HEAD methods.")
+                .finish()
+                .into_http_response();
+        }
+
+        let body = if req.method() == Method::HEAD {
+            Body::empty()
+        } else {
+            Body::from(self.inner.signer.jwks().to_string())
+        };
+
+        Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "application/jwk-set+json")
+            .body(body)
+            .expect("Must be valid.")
+    }
+}
+
+impl<Space: SolidStorageSpace> NotificationChannel for WebhookChannel2023<Space> {
+    type StSpace = Space;
+
+    const CHANNEL_TYPE_IRI: &'static str =
+        "http://www.w3.org/ns/solid/notifications#WebhookChannel2023";
+
+    const CHANNEL_TYPE_TERM: &'static str = "WebhookChannel2023";
+
+    const SLUG: &'static str = "webhook";
+
+    #[inline]
+    fn subscription_res_uri(&self) -> &SolidResourceUri {
+        &self.inner.subscription_res_uri
+    }
+
+    fn open(&self, subscription: Subscription) -> ProbFuture<'static, ChannelDescription> {
+        let inner = self.inner.clone();
+
+        Box::pin(async move {
+            let send_to = Self::validate_send_to(
+                subscription.send_to.as_deref(),
+                inner.config.allow_private_targets,
+            )
+            .await?;
+
+            // Cap the expiry time.
+            let max_end_at = chrono::Duration::from_std(inner.config.max_ttl)
+                .ok()
+                .and_then(|max_ttl| Utc::now().checked_add_signed(max_ttl))
+                .unwrap_or(DateTime::<Utc>::MAX_UTC);
+            let end_at = subscription
+                .end_at
+                .map_or(max_end_at, |end_at| end_at.min(max_end_at));
+
+            let channel_id = uuid::Uuid::new_v4().simple().to_string();
+            let channel_uri = format!("{}{}", inner.subscription_res_uri.as_str(), channel_id);
+
+            Self::try_register(
+                &inner,
+                channel_id,
+                WebhookSubscription {
+                    topic: subscription.topic.as_str().to_owned(),
+                    send_to: send_to.clone(),
+                    end_at,
+                    subscriber: subscription
+                        .subscriber
+                        .as_ref()
+                        .map(|webid| webid.as_str().to_owned()),
+                },
+            )
+            .await?;
+
+            let mut description = ChannelDescription::new(
+                channel_uri.clone(),
+                Self::CHANNEL_TYPE_TERM,
+                subscription.topic.as_str().to_owned(),
+            );
+            description.send_to = Some(send_to);
+            description.end_at = Some(end_at);
+
+            info!("Opened webhook channel {}", channel_uri);
+            Ok(description)
+        })
+    }
+
+    #[inline]
+    fn endpoint_service(&self) -> Option<Box<dyn HttpService<Body, Body>>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+impl<Space: SolidStorageSpace> Service<Request<Body>> for WebhookChannel2023<Space> {
+    type Response = Response<Body>;
+
+    type Error = Infallible;
+
+    type Future = BoxHttpResponseFuture<Body>;
+
+    #[inline]
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[tracing::instrument(skip_all, name = "WebhookChannel2023::call")]
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let this = self.clone();
+        Box::pin(async move { Ok(this.handle(req).await) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::SystemTime,
+    };
+
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use claims::*;
+    use ed25519_dalek::{Signature, Verifier};
+    use manas_authentication::challenge_response_framework::scheme::impl_::httpsig::message::parse_signature_inputs;
+    use manas_repo_layers::eventing::event::ResourceChangeKind;
+    use manas_space::{
+        mock::MockSolidStorageSpace,
+        resource::{
+            kind::SolidResourceKind, slot::SolidResourceSlot, slot_id::SolidResourceSlotId,
+        },
+    };
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        sync::mpsc,
+    };
+
+    use super::*;
+
+    /// A request received by the stand-in subscriber.
+    #[derive(Debug)]
+    struct ReceivedRequest {
+        head: String,
+        body: String,
+    }
+
+    impl ReceivedRequest {
+        fn header(&self, name: &str) -> Option<&str> {
+            self.head.lines().find_map(|line| {
+                let (n, v) = line.split_once(':')?;
+                n.trim().eq_ignore_ascii_case(name).then(|| v.trim())
+            })
+        }
+    }
+
+    /// Spawn a local http stand-in of a subscriber, that
+    /// responds to requests with given statuses in order, and
+    /// then with 200. Redirection responses point to given
+    /// location.
+    async fn spawn_subscriber(
+        statuses: Vec<u16>,
+        location: Option<String>,
+    ) -> (String, mpsc::UnboundedReceiver<ReceivedRequest>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            let mut statuses = statuses.into_iter();
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+
+                let (head, body) = loop {
+                    let n = stream.read(&mut chunk).await.unwrap();
+                    buf.extend_from_slice(&chunk[..n]);
+                    let data = String::from_utf8_lossy(&buf).to_string();
+                    if let Some((head, body)) = data.split_once("\r\n\r\n") {
+                        let content_length = head
+                            .lines()
+                            .find_map(|line| {
+                                let (n, v) = line.split_once(':')?;
+                                n.eq_ignore_ascii_case("content-length")
+                                    .then(|| v.trim().parse::<usize>().ok())?
+                            })
+                            .unwrap_or_default();
+                        if body.len() >= content_length {
+                            break (head.to_owned(), body.to_owned());
+                        }
+                    }
+                };
+
+                let status = statuses.next().unwrap_or(200);
+                stream
+                    .write_all(
+                        format!(
+                            "HTTP/1.1 {} X\r\ncontent-length: 0\r\nconnection: close\r\n{}\r\n",
+                            status,
+                            location
+                                .as_ref()
+                                .map(|l| format!("location: {}\r\n", l))
+                                .unwrap_or_default()
+                        )
+                        .as_bytes(),
+                    )
+                    .await
+                    .unwrap();
+                tx.send(ReceivedRequest { head, body }).unwrap();
+            }
+        });
+
+        (format!("http://{}/inbox", addr), rx)
+    }
+
+    fn test_config() -> WebhookChannelConfig {
+        WebhookChannelConfig {
+            initial_backoff: Duration::from_millis(50),
+            // Stand-in subscribers listen on loopback.
+            allow_private_targets: true,
+            ..Default::default()
+        }
+    }
+
+    async fn open_channel(
+        channel: &WebhookChannel2023<MockSolidStorageSpace>,
+        topic: &str,
+        send_to: &str,
+    ) -> ChannelDescription {
+        assert_ok!(
+            channel
+                .open(Subscription {
+                    topic: SolidResourceUri::try_new_from(topic).unwrap(),
+                    end_at: None,
+                    send_to: Some(send_to.to_owned()),
+                    subscriber: None,
+                })
+                .await
+        )
+    }
+
+    fn publish_update(bus: &ResourceChangeEventBus<MockSolidStorageSpace>, uri: &str) {
+        bus.publish(update_event(uri));
+    }
+
+    fn update_event(uri: &str) -> ResourceChangeEvent<MockSolidStorageSpace> {
+        let slot = SolidResourceSlot::try_new(
+            SolidResourceSlotId {
+                space: Arc::new(MockSolidStorageSpace::new_from_valid_root_uri_str(
+                    "http://ex.org/",
+                )),
+                uri: SolidResourceUri::try_new_from(uri).unwrap(),
+            },
+            SolidResourceKind::NonContainer,
+            None,
+        )
+        .unwrap();
+        ResourceChangeEvent {
+            res_slot: slot,
+            kind: ResourceChangeKind::Updated,
+            deleted_aux_res_links: vec![],
+            time: SystemTime::now(),
+        }
+    }
+
+    async fn recv(rx: &mut mpsc::UnboundedReceiver<ReceivedRequest>) -> ReceivedRequest {
+        tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .expect("Request must be received in time.")
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn delivers_signed_notification() {
+        let space: MockSolidStorageSpace =
+            MockSolidStorageSpace::new_from_valid_root_uri_str("http://ex.org/");
+        let bus = ResourceChangeEventBus::default();
+        let channel =
+            assert_ok!(WebhookChannel2023::try_new(&space, bus.clone(), test_config()).await);
+
+        let (send_to, mut rx) = spawn_subscriber(vec![], None).await;
+        let description = open_channel(&channel, "http://ex.org/a", &send_to).await;
+        assert_eq!(description.send_to.as_deref(), Some(send_to.as_str()));
+
+        publish_update(&bus, "http://ex.org/a");
+        let req = recv(&mut rx).await;
+
+        assert!(req.head.starts_with("POST /inbox"));
+        let notification: serde_json::Value = serde_json::from_str(&req.body).unwrap();
+        assert_eq!(notification["type"], "Update");
+        assert_eq!(notification["object"], "http://ex.org/a");
+
+        // Verify the content digest.
+        assert_eq!(
+            req.header("content-digest").unwrap(),
+            format!(
+                "sha-256=:{}:",
+                STANDARD.encode(<sha2::Sha256 as sha2::Digest>::digest(req.body.as_bytes()))
+            )
+        );
+
+        // Verify the signature.
+        let (label, signature_input) = assert_some!(parse_signature_inputs(
+            req.header("signature-input").unwrap()
+        ))
+        .remove(0);
+        assert_eq!(label, "sig1");
+        assert!(signature_input.covers("content-digest"));
+        assert_some!(signature_input.param("nonce"));
+
+        let mut headers = http::HeaderMap::new();
+        for name in ["content-type", "content-digest"] {
+            headers.insert(name, req.header(name).unwrap().parse().unwrap());
+        }
+        let base = assert_some!(signature_input.signature_base(
+            &AbsoluteHttpUri::try_new_from(send_to.as_str()).unwrap(),
+            &Method::POST,
+            &headers,
+        ));
+        let signature_bytes = STANDARD
+            .decode(
+                req.header("signature")
+                    .unwrap()
+                    .strip_prefix("sig1=:")
+                    .unwrap()
+                    .trim_end_matches(':'),
+            )
+            .unwrap();
+        let signature = Signature::from_slice(&signature_bytes).unwrap();
+        assert_ok!(channel
+            .signer()
+            .verifying_key()
+            .verify(base.as_bytes(), &signature));
+    }
+
+    #[tokio::test]
+    async fn retries_failed_delivery() {
+        let space: MockSolidStorageSpace =
+            MockSolidStorageSpace::new_from_valid_root_uri_str("http://ex.org/");
+        let bus = ResourceChangeEventBus::default();
+        let channel =
+            assert_ok!(WebhookChannel2023::try_new(&space, bus.clone(), test_config()).await);
+
+        let (send_to, mut rx) = spawn_subscriber(vec![500, 503], None).await;
+        open_channel(&channel, "http://ex.org/a", &send_to).await;
+
+        publish_update(&bus, "http://ex.org/a");
+        let first = recv(&mut rx).await;
+        let second = recv(&mut rx).await;
+        let third = recv(&mut rx).await;
+
+        assert_eq!(first.body, second.body);
+        assert_eq!(second.body, third.body);
+
+        // Queue must be drained after success.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(channel.inner.jobs.snapshot().is_empty());
+    }
+
+    #[tokio::test]
+    async fn notifications_are_dropped_when_queue_is_full() {
+        let space: MockSolidStorageSpace =
+            MockSolidStorageSpace::new_from_valid_root_uri_str("http://ex.org/");
+        let channel = assert_ok!(
+            WebhookChannel2023::try_new(
+                &space,
+                Default::default(),
+                WebhookChannelConfig {
+                    max_queued_deliveries: 2,
+                    ..test_config()
+                }
+            )
+            .await
+        );
+
+        // Deliveries to a closed port stay queued for retry.
+        for _ in 0..3 {
+            open_channel(&channel, "http://ex.org/a", "http://127.0.0.1:9/inbox").await;
+        }
+
+        assert_ok!(
+            WebhookChannel2023::enqueue(&channel.inner, &update_event("http://ex.org/a")).await
+        );
+        assert_eq!(channel.inner.jobs.len(), 2);
+    }
+
+    /// Spawn a local http stand-in of a subscriber, that
+    /// serves connections concurrently, responding to each
+    /// request with 200 after given delay. Resolves the
+    /// count of received requests, and the maximum number of
+    /// requests in flight.
+    async fn spawn_slow_subscriber(
+        delay: Duration,
+    ) -> (String, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let received = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let in_flight = Arc::new(AtomicUsize::new(0));
+
+        {
+            let received = received.clone();
+            let max_in_flight = max_in_flight.clone();
+            tokio::spawn(async move {
+                loop {
+                    let (mut stream, _) = listener.accept().await.unwrap();
+                    let received = received.clone();
+                    let max_in_flight = max_in_flight.clone();
+                    let in_flight = in_flight.clone();
+
+                    tokio::spawn(async move {
+                        let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_in_flight.fetch_max(current, Ordering::SeqCst);
+
+                        let mut chunk = [0u8; 4096];
+                        let _ = stream.read(&mut chunk).await;
+                        tokio::time::sleep(delay).await;
+
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+                        received.fetch_add(1, Ordering::SeqCst);
+                        let _ = stream
+                            .write_all(
+                                b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                            )
+                            .await;
+                    });
+                }
+            });
+        }
+
+        (format!("http://{}/inbox", addr), received, max_in_flight)
+    }
+
+    fn subscription(send_to: &str, subscriber: Option<&str>) -> Subscription {
+        Subscription {
+            topic: SolidResourceUri::try_new_from("http://ex.org/a").unwrap(),
+            end_at: None,
+            send_to: Some(send_to.to_owned()),
+            subscriber: subscriber.map(|webid| webid::WebId::try_from(webid).unwrap()),
+        }
+    }
+
+    #[tokio::test]
+    async fn channels_are_limited_per_agent() {
+        let space: MockSolidStorageSpace =
+            MockSolidStorageSpace::new_from_valid_root_uri_str("http://ex.org/");
+        let channel = assert_ok!(
+            WebhookChannel2023::try_new(
+                &space,
+                Default::default(),
+                WebhookChannelConfig {
+                    max_channels_per_agent: 2,
+                    ..test_config()
+                }
+            )
+            .await
+        );
+        let send_to = "http://127.0.0.1:1/inbox";
+        let alice = Some("http://alice.example.org/#me");
+
+        for _ in 0..2 {
+            assert_ok!(channel.open(subscription(send_to, alice)).await);
+        }
+        let problem = assert_err!(channel.open(subscription(send_to, alice)).await);
+        assert!(CHANNEL_LIMIT_EXCEEDED.is_type_of(&problem));
+
+        // Other agents, and anonymous subscribers are not
+        // affected.
+        assert_ok!(
+            channel
+                .open(subscription(send_to, Some("http://bob.example.org/#me")))
+                .await
+        );
+        assert_ok!(channel.open(subscription(send_to, None)).await);
+        assert_eq!(channel.inner.subscriptions.snapshot().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn channels_are_limited_in_total() {
+        let space: MockSolidStorageSpace =
+            MockSolidStorageSpace::new_from_valid_root_uri_str("http://ex.org/");
+        let channel = assert_ok!(
+            WebhookChannel2023::try_new(
+                &space,
+                Default::default(),
+                WebhookChannelConfig {
+                    max_channels: 2,
+                    ..test_config()
+                }
+            )
+            .await
+        );
+        let send_to = "http://127.0.0.1:1/inbox";
+
+        assert_ok!(
+            channel
+                .open(subscription(send_to, Some("http://alice.example.org/#me")))
+                .await
+        );
+        assert_ok!(channel.open(subscription(send_to, None)).await);
+        let problem = assert_err!(
+            channel
+                .open(subscription(send_to, Some("http://bob.example.org/#me")))
+                .await
+        );
+        assert!(CHANNEL_LIMIT_EXCEEDED.is_type_of(&problem));
+        assert_eq!(channel.inner.subscriptions.snapshot().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn concurrent_deliveries_are_bounded() {
+        let space: MockSolidStorageSpace =
+            MockSolidStorageSpace::new_from_valid_root_uri_str("http://ex.org/");
+        let bus = ResourceChangeEventBus::default();
+        let channel = assert_ok!(
+            WebhookChannel2023::try_new(
+                &space,
+                bus.clone(),
+                WebhookChannelConfig {
+                    max_concurrent_deliveries: 2,
+                    ..test_config()
+                }
+            )
+            .await
+        );
+
+        let (send_to, received, max_in_flight) =
+            spawn_slow_subscriber(Duration::from_millis(100)).await;
+        for _ in 0..6 {
+            open_channel(&channel, "http://ex.org/a", &send_to).await;
+        }
+
+        publish_update(&bus, "http://ex.org/a");
+        assert_ok!(
+            tokio::time::timeout(Duration::from_secs(5), async {
+                while received.load(Ordering::SeqCst) < 6 {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+            })
+            .await
+        );
+
+        assert_eq!(received.load(Ordering::SeqCst), 6);
+        assert!(max_in_flight.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[tokio::test]
+    async fn rejects_non_http_send_to() {
+        let space: MockSolidStorageSpace =
+            MockSolidStorageSpace::new_from_valid_root_uri_str("http://ex.org/");
+        let channel = assert_ok!(
+            WebhookChannel2023::try_new(&space, Default::default(), test_config()).await
+        );
+
+        let problem = assert_err!(
+            channel
+                .open(Subscription {
+                    topic: SolidResourceUri::try_new_from("http://ex.org/a").unwrap(),
+                    end_at: None,
+                    send_to: Some("mailto:a@ex.org".to_owned()),
+                    subscriber: None,
+                })
+                .await
+        );
+        assert!(INVALID_SUBSCRIPTION.is_type_of(&problem));
+    }
+
+    #[rstest::rstest]
+    #[case("http://127.0.0.1:8080/inbox")]
+    #[case("http://localhost/inbox")]
+    #[case("http://[::1]/inbox")]
+    #[case("http://169.254.169.254/latest/meta-data")]
+    #[case("http://10.0.0.1/inbox")]
+    #[tokio::test]
+    async fn rejects_non_public_send_to(#[case] send_to: &str) {
+        let space: MockSolidStorageSpace =
+            MockSolidStorageSpace::new_from_valid_root_uri_str("http://ex.org/");
+        let channel = assert_ok!(
+            WebhookChannel2023::try_new(&space, Default::default(), Default::default()).await
+        );
+
+        let problem = assert_err!(
+            channel
+                .open(Subscription {
+                    topic: SolidResourceUri::try_new_from("http://ex.org/a").unwrap(),
+                    end_at: None,
+                    send_to: Some(send_to.to_owned()),
+                    subscriber: None,
+                })
+                .await
+        );
+        assert!(INVALID_SUBSCRIPTION.is_type_of(&problem));
+    }
+
+    #[tokio::test]
+    async fn does_not_follow_redirects() {
+        let space: MockSolidStorageSpace =
+            MockSolidStorageSpace::new_from_valid_root_uri_str("http://ex.org/");
+        let bus = ResourceChangeEventBus::default();
+        let channel = assert_ok!(
+            WebhookChannel2023::try_new(
+                &space,
+                bus.clone(),
+                WebhookChannelConfig {
+                    max_attempts: 1,
+                    ..test_config()
+                }
+            )
+            .await
+        );
+
+        let (internal_uri, mut internal_rx) = spawn_subscriber(vec![], None).await;
+        let (send_to, mut rx) = spawn_subscriber(vec![307], Some(internal_uri)).await;
+        open_channel(&channel, "http://ex.org/a", &send_to).await;
+
+        publish_update(&bus, "http://ex.org/a");
+        recv(&mut rx).await;
+
+        assert_err!(tokio::time::timeout(Duration::from_millis(300), internal_rx.recv()).await);
+    }
+
+    /// Send a request with given method to given channel
+    /// endpoint uri.
+    async fn send_to_endpoint(
+        channel: &WebhookChannel2023<MockSolidStorageSpace>,
+        method: Method,
+        uri: &str,
+    ) -> StatusCode {
+        let mut req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+        req.extensions_mut()
+            .insert(NormalAbsoluteHttpUri::try_new_from(uri).unwrap());
+
+        assert_ok!(channel.clone().call(req).await).status()
+    }
+
+    #[tokio::test]
+    async fn unsubscribe_closes_channel() {
+        let space: MockSolidStorageSpace =
+            MockSolidStorageSpace::new_from_valid_root_uri_str("http://ex.org/");
+        let bus = ResourceChangeEventBus::default();
+        let channel =
+            assert_ok!(WebhookChannel2023::try_new(&space, bus.clone(), test_config()).await);
+
+        let (send_to, mut rx) = spawn_subscriber(vec![], None).await;
+        let description = open_channel(&channel, "http://ex.org/a", &send_to).await;
+
+        assert_eq!(
+            send_to_endpoint(&channel, Method::GET, &description.id).await,
+            StatusCode::METHOD_NOT_ALLOWED
+        );
+        assert_eq!(
+            send_to_endpoint(&channel, Method::DELETE, &description.id).await,
+            StatusCode::NO_CONTENT
+        );
+        assert_eq!(
+            send_to_endpoint(&channel, Method::DELETE, &description.id).await,
+            StatusCode::NOT_FOUND
+        );
+        assert!(channel.inner.subscriptions.snapshot().is_empty());
+
+        publish_update(&bus, "http://ex.org/a");
+        assert_err!(tokio::time::timeout(Duration::from_millis(300), rx.recv()).await);
+
+        // Jwks resource must still be served.
+        assert_eq!(
+            send_to_endpoint(&channel, Method::GET, channel.signer().key_id()).await,
+            StatusCode::OK
+        );
+    }
+}
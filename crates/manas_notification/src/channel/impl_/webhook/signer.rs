@@ -0,0 +1,243 @@
+//! I define a signer for webhook requests, that signs them
+//! as per [RFC 9421](https://www.rfc-editor.org/rfc/rfc9421)
+//! http message signatures, with an ed25519 key.
+//!
+
+use std::{io, path::Path};
+
+use base64::{
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+    Engine,
+};
+use ed25519_dalek::{Signer, SigningKey, VerifyingKey, SECRET_KEY_LENGTH};
+use http::{header, HeaderMap, HeaderName, HeaderValue, Method};
+use manas_authentication::challenge_response_framework::scheme::impl_::httpsig::{
+    content_digest::CONTENT_DIGEST,
+    message::{ParamValue, SignatureInput},
+};
+use manas_http::uri::invariant::AbsoluteHttpUri;
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+use tracing::warn;
+
+/// Name of the `Signature-Input` header.
+pub static SIGNATURE_INPUT: HeaderName = HeaderName::from_static("signature-input");
+
+/// Name of the `Signature` header.
+pub static SIGNATURE: HeaderName = HeaderName::from_static("signature");
+
+/// Label of the signature in webhook requests.
+pub const SIGNATURE_LABEL: &str = "sig1";
+
+/// Covered components of webhook request signatures.
+pub const COVERED_COMPONENTS: &[&str] =
+    &["@method", "@target-uri", "content-type", "content-digest"];
+
+/// A signer for webhook requests.
+#[derive(Debug, Clone)]
+pub struct WebhookRequestSigner {
+    signing_key: SigningKey,
+    key_id: String,
+}
+
+impl WebhookRequestSigner {
+    /// Create a new [`WebhookRequestSigner`] with given
+    /// signing key and key id.
+    #[inline]
+    pub fn new(signing_key: SigningKey, key_id: String) -> Self {
+        Self {
+            signing_key,
+            key_id,
+        }
+    }
+
+    /// Load signing key from given key file path, or
+    /// generate and persist a new one if it doesn't exist.
+    /// If no path is given, an ephemeral key is generated.
+    ///
+    /// New key files are created readable only by the owner.
+    pub async fn load_or_generate(key_path: Option<&Path>, key_id: String) -> io::Result<Self> {
+        let Some(key_path) = key_path else {
+            return Ok(Self::new(SigningKey::generate(&mut OsRng), key_id));
+        };
+
+        let signing_key = match tokio::fs::read_to_string(key_path).await {
+            Ok(encoded) => {
+                warn_if_exposed(key_path).await;
+
+                let bytes: [u8; SECRET_KEY_LENGTH] = STANDARD
+                    .decode(encoded.trim())
+                    .ok()
+                    .and_then(|bytes| bytes.try_into().ok())
+                    .ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::InvalidData, "Invalid signing key file.")
+                    })?;
+                SigningKey::from_bytes(&bytes)
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                let signing_key = SigningKey::generate(&mut OsRng);
+                write_private_file(key_path, STANDARD.encode(signing_key.to_bytes()).as_bytes())
+                    .await?;
+                signing_key
+            }
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self::new(signing_key, key_id))
+    }
+
+    /// Get the key id.
+    #[inline]
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// Get the verifying key.
+    #[inline]
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.signing_key.verifying_key()
+    }
+
+    /// Get json web key set, that contains the verifying key.
+    pub fn jwks(&self) -> serde_json::Value {
+        serde_json::json!({
+            "keys": [{
+                "kty": "OKP",
+                "crv": "Ed25519",
+                "alg": "EdDSA",
+                "use": "sig",
+                "kid": self.key_id,
+                "x": URL_SAFE_NO_PAD.encode(self.verifying_key().as_bytes()),
+            }]
+        })
+    }
+
+    /// Resolve signature headers for a request with given
+    /// params.
+    pub fn sign(
+        &self,
+        method: &Method,
+        target_uri: &AbsoluteHttpUri,
+        content_type: &str,
+        body: &[u8],
+        created: i64,
+    ) -> Vec<(HeaderName, HeaderValue)> {
+        let content_digest = HeaderValue::from_str(&format!(
+            "sha-256=:{}:",
+            STANDARD.encode(Sha256::digest(body))
+        ))
+        .expect("Must be valid header value.");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_str(content_type).expect("Must be valid header value."),
+        );
+        headers.insert(CONTENT_DIGEST.clone(), content_digest.clone());
+
+        let signature_input = SignatureInput {
+            components: COVERED_COMPONENTS.iter().map(|c| (*c).to_owned()).collect(),
+            params: vec![
+                ("created".to_owned(), ParamValue::Integer(created)),
+                ("keyid".to_owned(), ParamValue::String(self.key_id.clone())),
+                ("alg".to_owned(), ParamValue::String("ed25519".to_owned())),
+                (
+                    "nonce".to_owned(),
+                    ParamValue::String(uuid::Uuid::new_v4().simple().to_string()),
+                ),
+            ],
+        };
+
+        let base = signature_input
+            .signature_base(target_uri, method, &headers)
+            .expect("Covered components must be resolvable.");
+        let signature = self.signing_key.sign(base.as_bytes());
+
+        [
+            (CONTENT_DIGEST.clone(), content_digest),
+            (
+                SIGNATURE_INPUT.clone(),
+                HeaderValue::from_str(&format!(
+                    "{}={}",
+                    SIGNATURE_LABEL,
+                    signature_input.serialize()
+                ))
+                .expect("Must be valid header value."),
+            ),
+            (
+                SIGNATURE.clone(),
+                HeaderValue::from_str(&format!(
+                    "{}=:{}:",
+                    SIGNATURE_LABEL,
+                    STANDARD.encode(signature.to_bytes())
+                ))
+                .expect("Must be valid header value."),
+            ),
+        ]
+        .into()
+    }
+}
+
+/// Create a new file at given path with given content, that
+/// is readable and writable only by the owner.
+async fn write_private_file(path: &Path, content: &[u8]) -> io::Result<()> {
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+
+    let mut file = options.open(path).await?;
+    file.write_all(content).await?;
+    file.sync_all().await
+}
+
+/// Warn if file at given path is accessible to others than
+/// the owner.
+async fn warn_if_exposed(path: &Path) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        if let Ok(metadata) = tokio::fs::metadata(path).await {
+            if metadata.permissions().mode() & 0o077 != 0 {
+                warn!(
+                    "Signing key file at {:?} is accessible to others than the owner.",
+                    path
+                );
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+#[cfg(test)]
+mod tests {
+    use claims::*;
+
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn generated_key_file_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let key_path = dir.path().join("signing_key");
+
+        let signer = assert_ok!(
+            WebhookRequestSigner::load_or_generate(Some(&key_path), "http://ex.org/jwks".into())
+                .await
+        );
+        let mode = std::fs::metadata(&key_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        // Key must be reloaded from the file.
+        let reloaded = assert_ok!(
+            WebhookRequestSigner::load_or_generate(Some(&key_path), "http://ex.org/jwks".into())
+                .await
+        );
+        assert_eq!(signer.verifying_key(), reloaded.verifying_key());
+    }
+}
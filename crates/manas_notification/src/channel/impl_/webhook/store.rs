@@ -0,0 +1,128 @@
+//! I define a simple durable record store, that mirrors
+//! records as json files in an optional directory.
+//!
+
+use std::{collections::HashMap, io, path::PathBuf, sync::Mutex};
+
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::warn;
+
+/// A store of records keyed by ids. If a directory is
+/// configured, each record is persisted as a json file in it,
+/// and records are reloaded from there on creation.
+#[derive(Debug)]
+pub(crate) struct DurableRecords<T> {
+    dir: Option<PathBuf>,
+    records: Mutex<HashMap<String, T>>,
+}
+
+impl<T: Serialize + DeserializeOwned + Clone> DurableRecords<T> {
+    /// Load records from given optional directory.
+    pub(crate) async fn load(dir: Option<PathBuf>) -> io::Result<Self> {
+        let mut records = HashMap::new();
+
+        if let Some(dir) = dir.as_ref() {
+            tokio::fs::create_dir_all(dir).await?;
+
+            let mut entries = tokio::fs::read_dir(dir).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let path = entry.path();
+                let Some(id) = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.strip_suffix(".json"))
+                    .map(ToOwned::to_owned)
+                else {
+                    continue;
+                };
+
+                match serde_json::from_slice::<T>(&tokio::fs::read(&path).await?) {
+                    Ok(record) => {
+                        records.insert(id, record);
+                    }
+                    Err(e) => warn!("Skipping invalid record at {:?}. Error:\n {}", path, e),
+                }
+            }
+        }
+
+        Ok(Self {
+            dir,
+            records: Mutex::new(records),
+        })
+    }
+
+    /// Get record with given id.
+    pub(crate) fn get(&self, id: &str) -> Option<T> {
+        self.records
+            .lock()
+            .expect("Lock must not be poisoned.")
+            .get(id)
+            .cloned()
+    }
+
+    /// Get number of records.
+    pub(crate) fn len(&self) -> usize {
+        self.records
+            .lock()
+            .expect("Lock must not be poisoned.")
+            .len()
+    }
+
+    /// Get a snapshot of all records.
+    pub(crate) fn snapshot(&self) -> Vec<(String, T)> {
+        self.records
+            .lock()
+            .expect("Lock must not be poisoned.")
+            .iter()
+            .map(|(id, record)| (id.clone(), record.clone()))
+            .collect()
+    }
+
+    /// Put given record with given id.
+    pub(crate) async fn put(&self, id: String, record: T) -> io::Result<()> {
+        if let Some(dir) = self.dir.as_ref() {
+            let data = serde_json::to_vec(&record).map_err(io::Error::other)?;
+            // Write atomically.
+            let tmp_path = dir.join(format!("{}.json.tmp", id));
+            tokio::fs::write(&tmp_path, data).await?;
+            tokio::fs::rename(&tmp_path, dir.join(format!("{}.json", id))).await?;
+        }
+
+        self.records
+            .lock()
+            .expect("Lock must not be poisoned.")
+            .insert(id, record);
+        Ok(())
+    }
+
+    /// Remove record with given id.
+    pub(crate) async fn remove(&self, id: &str) -> io::Result<()> {
+        let existing = self
+            .records
+            .lock()
+            .expect("Lock must not be poisoned.")
+            .remove(id);
+
+        if let (Some(dir), Some(_)) = (self.dir.as_ref(), existing) {
+            tokio::fs::remove_file(dir.join(format!("{}.json", id))).await?;
+        }
+        Ok(())
+    }
+
+    /// Remove records that doesn't satisfy given predicate.
+    pub(crate) async fn retain<F: Fn(&T) -> bool>(&self, predicate: F) -> io::Result<()> {
+        let stale_ids = self
+            .records
+            .lock()
+            .expect("Lock must not be poisoned.")
+            .iter()
+            .filter(|(_, record)| !predicate(record))
+            .map(|(id, _)| id.clone())
+            .collect::<Vec<_>>();
+
+        for id in stale_ids {
+            self.remove(&id).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,859 @@
+//! I define an implementation of [`NotificationChannel`] for
+//! `WebSocketChannel2023` channel type.
+//!
+
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, RwLock, Weak,
+    },
+    task::Poll,
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use dyn_problem::{ProbFuture, ProbResult};
+use futures::{SinkExt, StreamExt};
+use http::{header, HeaderValue, Method, Request, Response, StatusCode};
+use http_api_problem::ApiError;
+use hyper_util::rt::TokioIo;
+use manas_http::{
+    body::Body,
+    problem::ApiErrorExt,
+    service::{BoxHttpResponseFuture, HttpService},
+    uri::invariant::NormalAbsoluteHttpUri,
+};
+use manas_repo_layers::eventing::event::{ResourceChangeEvent, ResourceChangeEventBus};
+use manas_space::{resource::uri::SolidResourceUri, SolidStorageSpace};
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    oneshot,
+};
+use tokio_tungstenite::{
+    tungstenite::{handshake::derive_accept_key, protocol::Role, Message},
+    WebSocketStream,
+};
+use tower::Service;
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    channel::{NotificationChannel, CHANNEL_LIMIT_EXCEEDED},
+    model::{channel::ChannelDescription, notification::Notification, subscription::Subscription},
+    service::resolve_subscription_res_uri,
+};
+
+/// Configuration for [`WebSocketChannel2023`].
+#[derive(Debug, Clone)]
+pub struct WebSocketChannelConfig {
+    /// Maximum life time of a channel. Requested expiry
+    /// times are capped by this.
+    pub max_ttl: Duration,
+
+    /// Maximum number of live channels.
+    pub max_channels: usize,
+
+    /// Maximum number of live channels per subscribing
+    /// agent. Channels of unauthenticated subscribers are
+    /// counted together.
+    pub max_channels_per_agent: usize,
+
+    /// Interval at which expired channels are purged from
+    /// the registry.
+    pub purge_interval: Duration,
+}
+
+impl Default for WebSocketChannelConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            max_ttl: Duration::from_secs(24 * 60 * 60),
+            max_channels: 10_000,
+            max_channels_per_agent: 32,
+            purge_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+/// An implementation of [`NotificationChannel`] for
+/// `WebSocketChannel2023` channel type.
+///
+/// It also serves the websocket endpoints of the opened
+/// channels, from which subscribers receive notifications.
+///
+/// Number of live channels is bounded in total, and per
+/// subscribing agent. Expired channels are purged
+/// periodically, while the channel type is alive.
+///
+/// Each channel has at most one active connection. A new
+/// connection to a channel supersedes the prior one, which
+/// is closed.
+pub struct WebSocketChannel2023<Space: SolidStorageSpace> {
+    inner: Arc<Inner<Space>>,
+}
+
+struct Inner<Space: SolidStorageSpace> {
+    subscription_res_uri: SolidResourceUri,
+    event_bus: ResourceChangeEventBus<Space>,
+    config: WebSocketChannelConfig,
+    subscriptions: RwLock<HashMap<String, Subscription>>,
+    connections: Mutex<HashMap<String, ActiveConnection>>,
+    next_connection_id: AtomicU64,
+}
+
+/// An active connection to a channel.
+struct ActiveConnection {
+    /// Id of the connection.
+    id: u64,
+
+    /// Sender, on drop of which the connection is notified
+    /// that it is superseded.
+    _supersede: oneshot::Sender<()>,
+}
+
+/// A registration of an active connection to a channel. It
+/// is deregistered on drop, unless it is already
+/// superseded.
+struct ConnectionRegistration<Space: SolidStorageSpace> {
+    inner: Arc<Inner<Space>>,
+    channel_id: String,
+    id: u64,
+}
+
+impl<Space: SolidStorageSpace> Drop for ConnectionRegistration<Space> {
+    fn drop(&mut self) {
+        let mut connections = self
+            .inner
+            .connections
+            .lock()
+            .expect("Lock must not be poisoned.");
+        if connections
+            .get(&self.channel_id)
+            .map_or(false, |connection| connection.id == self.id)
+        {
+            connections.remove(&self.channel_id);
+        }
+    }
+}
+
+impl<Space: SolidStorageSpace> Clone for WebSocketChannel2023<Space> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<Space: SolidStorageSpace> Debug for WebSocketChannel2023<Space> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebSocketChannel2023")
+            .field("subscription_res_uri", &self.inner.subscription_res_uri)
+            .field("config", &self.inner.config)
+            .finish()
+    }
+}
+
+impl<Space: SolidStorageSpace> WebSocketChannel2023<Space> {
+    /// Create a new [`WebSocketChannel2023`] over given
+    /// storage space, with given event bus, and config.
+    ///
+    /// Must be called in the context of a tokio runtime, on
+    /// which expired channels are purged.
+    pub fn new(
+        space: &Space,
+        event_bus: ResourceChangeEventBus<Space>,
+        config: WebSocketChannelConfig,
+    ) -> Self {
+        let purge_interval = config.purge_interval;
+        let inner = Arc::new(Inner {
+            subscription_res_uri: resolve_subscription_res_uri(
+                space,
+                <Self as NotificationChannel>::SLUG,
+            ),
+            event_bus,
+            config,
+            subscriptions: Default::default(),
+            connections: Default::default(),
+            next_connection_id: Default::default(),
+        });
+
+        tokio::spawn(Self::purge_periodically(
+            Arc::downgrade(&inner),
+            purge_interval,
+        ));
+
+        Self { inner }
+    }
+
+    /// Purge expired subscriptions at given interval, until
+    /// the channel type is dropped.
+    async fn purge_periodically(inner: Weak<Inner<Space>>, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+            match inner.upgrade() {
+                Some(inner) => Self { inner }.purge_expired(),
+                None => break,
+            }
+        }
+    }
+
+    /// Get the websocket uri corresponding to given http
+    /// uri.
+    fn to_ws_uri(uri: &str) -> String {
+        if let Some(rest) = uri.strip_prefix("https://") {
+            format!("wss://{}", rest)
+        } else if let Some(rest) = uri.strip_prefix("http://") {
+            format!("ws://{}", rest)
+        } else {
+            uri.to_owned()
+        }
+    }
+
+    /// Remove expired subscriptions from the registry.
+    fn purge_expired(&self) {
+        let now = Utc::now();
+        self.inner
+            .subscriptions
+            .write()
+            .expect("Lock must not be poisoned.")
+            .retain(|_, subscription| subscription.end_at.map_or(true, |end_at| end_at > now));
+    }
+
+    /// Register given subscription with given channel id, if
+    /// it is within the configured channel limits.
+    fn try_register(&self, channel_id: String, subscription: Subscription) -> ProbResult<()> {
+        let config = &self.inner.config;
+        let mut subscriptions = self
+            .inner
+            .subscriptions
+            .write()
+            .expect("Lock must not be poisoned.");
+
+        if subscriptions.len() >= config.max_channels {
+            warn!("Maximum number of live websocket channels reached.");
+            return Err(CHANNEL_LIMIT_EXCEEDED
+                .new_problem_builder()
+                .message("Maximum number of live channels reached.")
+                .finish());
+        }
+
+        let subscriber_channel_count = subscriptions
+            .values()
+            .filter(|s| s.subscriber == subscription.subscriber)
+            .count();
+        if subscriber_channel_count >= config.max_channels_per_agent {
+            warn!("Maximum number of live websocket channels reached for subscriber.");
+            return Err(CHANNEL_LIMIT_EXCEEDED
+                .new_problem_builder()
+                .message("Maximum number of live channels reached for the subscriber.")
+                .finish());
+        }
+
+        subscriptions.insert(channel_id, subscription);
+        Ok(())
+    }
+
+    /// Resolve live subscription with given id.
+    fn live_subscription(&self, id: &str) -> Option<Subscription> {
+        self.purge_expired();
+        self.inner
+            .subscriptions
+            .read()
+            .expect("Lock must not be poisoned.")
+            .get(id)
+            .cloned()
+    }
+
+    /// Register a new active connection to the channel with
+    /// given id, superseding any prior connection. Returns
+    /// the registration, along with a receiver that resolves
+    /// when the connection is superseded.
+    fn register_connection(
+        &self,
+        channel_id: &str,
+    ) -> (ConnectionRegistration<Space>, oneshot::Receiver<()>) {
+        let (supersede, superseded) = oneshot::channel();
+        let id = self
+            .inner
+            .next_connection_id
+            .fetch_add(1, Ordering::Relaxed);
+
+        // Dropping the replaced connection's sender notifies
+        // it that it is superseded.
+        self.inner
+            .connections
+            .lock()
+            .expect("Lock must not be poisoned.")
+            .insert(
+                channel_id.to_owned(),
+                ActiveConnection {
+                    id,
+                    _supersede: supersede,
+                },
+            );
+
+        (
+            ConnectionRegistration {
+                inner: self.inner.clone(),
+                channel_id: channel_id.to_owned(),
+                id,
+            },
+            superseded,
+        )
+    }
+
+    /// Handle the websocket upgrade request to the channel
+    /// endpoint.
+    fn handle_upgrade(&self, mut req: Request<Body>) -> Result<Response<Body>, Response<Body>> {
+        let uri = req
+            .extensions()
+            .get::<NormalAbsoluteHttpUri>()
+            .expect("Service must be called after handling uri normal validity check.");
+
+        let channel_id = uri
+            .as_str()
+            .strip_prefix(self.inner.subscription_res_uri.as_str())
+            .unwrap_or_default();
+
+        let subscription = self.live_subscription(channel_id).ok_or_else(|| {
+            error!("No live channel with given id.");
+            ApiError::builder(StatusCode::NOT_FOUND)
+                .message("No live channel with given id.")
+                .finish()
+                .into_http_response()
+        })?;
+
+        if req.method() != Method::GET {
+            error!("Method not allowed on websocket channel endpoint.");
+            return Err(ApiError::builder(StatusCode::METHOD_NOT_ALLOWED)
+                .message("Websocket channel endpoint only supports GET method.")
+                .finish()
+                .into_http_response());
+        }
+
+        let headers = req.headers();
+        let is_upgrade = headers
+            .get(header::UPGRADE)
+            .and_then(|v| v.to_str().ok())
+            .map_or(false, |v| v.eq_ignore_ascii_case("websocket"))
+            && headers
+                .get(header::CONNECTION)
+                .and_then(|v| v.to_str().ok())
+                .map_or(false, |v| {
+                    v.split(',')
+                        .any(|token| token.trim().eq_ignore_ascii_case("upgrade"))
+                });
+        let is_supported_version = headers
+            .get(header::SEC_WEBSOCKET_VERSION)
+            .map_or(false, |v| v.as_bytes() == b"13");
+
+        let accept_key = match headers.get(header::SEC_WEBSOCKET_KEY) {
+            Some(key) if is_upgrade && is_supported_version => derive_accept_key(key.as_bytes()),
+            _ => {
+                error!("Request is not a valid websocket upgrade request.");
+                let mut resp = ApiError::builder(StatusCode::UPGRADE_REQUIRED)
+                    .message("Channel endpoint only accepts websocket connections.")
+                    .finish()
+                    .into_http_response();
+                resp.headers_mut()
+                    .insert(header::UPGRADE, HeaderValue::from_static("websocket"));
+                resp.headers_mut().insert(
+                    header::SEC_WEBSOCKET_VERSION,
+                    HeaderValue::from_static("13"),
+                );
+                return Err(resp);
+            }
+        };
+
+        // Allow only one active connection per channel, so
+        // that fan-out of a channel is bounded.
+        let (registration, superseded) = self.register_connection(channel_id);
+
+        // Subscribe before upgrade, so that no events
+        // are missed in between.
+        let events = self.inner.event_bus.subscribe();
+        let on_upgrade = hyper::upgrade::on(&mut req);
+
+        tokio::spawn(async move {
+            match on_upgrade.await {
+                Ok(upgraded) => {
+                    let ws_stream = WebSocketStream::from_raw_socket(
+                        TokioIo::new(upgraded),
+                        Role::Server,
+                        None,
+                    )
+                    .await;
+                    serve_connection(ws_stream, events, subscription, superseded).await;
+                }
+                Err(e) => error!("Error in upgrading the connection. Error:\n {}", e),
+            }
+            drop(registration);
+        });
+
+        info!("Accepted websocket connection to channel.");
+        Ok(Response::builder()
+            .status(StatusCode::SWITCHING_PROTOCOLS)
+            .header(header::CONNECTION, "upgrade")
+            .header(header::UPGRADE, "websocket")
+            .header(header::SEC_WEBSOCKET_ACCEPT, accept_key)
+            .body(Body::empty())
+            .expect("Must be valid."))
+    }
+}
+
+/// Forward notifications relevant to the subscription over
+/// given websocket stream, until the channel expires, the
+/// connection is superseded, or the connection is closed.
+async fn serve_connection<Space, S>(
+    mut ws_stream: WebSocketStream<S>,
+    mut events: broadcast::Receiver<Arc<ResourceChangeEvent<Space>>>,
+    subscription: Subscription,
+    mut superseded: oneshot::Receiver<()>,
+) where
+    Space: SolidStorageSpace,
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let expiry = sleep_until_end(subscription.end_at);
+    tokio::pin!(expiry);
+
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if let Some(notification) = Notification::resolve_for_topic(&event, &subscription.topic) {
+                        let message = serde_json::to_string(&notification).expect("Must be serializable.");
+                        if let Err(e) = ws_stream.send(Message::Text(message)).await {
+                            warn!("Error in sending notification. Error:\n {}", e);
+                            break;
+                        }
+                    }
+                }
+                Err(RecvError::Lagged(n)) => {
+                    warn!("Websocket channel lagged behind by {} events.", n);
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = ws_stream.next() => match message {
+                None | Some(Ok(Message::Close(_))) => break,
+                Some(Err(e)) => {
+                    debug!("Websocket connection errored. Error:\n {}", e);
+                    break;
+                }
+                // Pings are answered by the protocol
+                // implementation. Other messages are ignored.
+                Some(Ok(_)) => {}
+            },
+            _ = &mut expiry => {
+                info!("Websocket channel expired.");
+                break;
+            }
+            _ = &mut superseded => {
+                info!("Websocket connection is superseded by a new connection.");
+                break;
+            }
+        }
+    }
+
+    let _ = ws_stream.close(None).await;
+}
+
+/// Sleep until given end time. Never resolves, if no end
+/// time is given.
+async fn sleep_until_end(end_at: Option<DateTime<Utc>>) {
+    match end_at {
+        Some(end_at) => {
+            let duration = (end_at - Utc::now()).to_std().unwrap_or_default();
+            tokio::time::sleep(duration).await
+        }
+        None => futures::future::pending().await,
+    }
+}
+
+impl<Space: SolidStorageSpace> NotificationChannel for WebSocketChannel2023<Space> {
+    type StSpace = Space;
+
+    const CHANNEL_TYPE_IRI: &'static str =
+        "http://www.w3.org/ns/solid/notifications#WebSocketChannel2023";
+
+    const CHANNEL_TYPE_TERM: &'static str = "WebSocketChannel2023";
+
+    const SLUG: &'static str = "websocket";
+
+    #[inline]
+    fn subscription_res_uri(&self) -> &SolidResourceUri {
+        &self.inner.subscription_res_uri
+    }
+
+    fn open(&self, mut subscription: Subscription) -> ProbFuture<'static, ChannelDescription> {
+        self.purge_expired();
+
+        // Cap the expiry time.
+        let max_end_at = chrono::Duration::from_std(self.inner.config.max_ttl)
+            .ok()
+            .and_then(|max_ttl| Utc::now().checked_add_signed(max_ttl))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        let end_at = subscription
+            .end_at
+            .map_or(max_end_at, |end_at| end_at.min(max_end_at));
+        subscription.end_at = Some(end_at);
+
+        let channel_id = uuid::Uuid::new_v4().simple().to_string();
+        let channel_uri = format!("{}{}", self.inner.subscription_res_uri.as_str(), channel_id);
+
+        let mut description = ChannelDescription::new(
+            channel_uri.clone(),
+            Self::CHANNEL_TYPE_TERM,
+            subscription.topic.as_str().to_owned(),
+        );
+        description.receive_from = Some(Self::to_ws_uri(&channel_uri));
+        description.end_at = Some(end_at);
+
+        if let Err(e) = self.try_register(channel_id, subscription) {
+            return Box::pin(futures::future::ready(Err(e)));
+        }
+
+        info!("Opened websocket channel {}", channel_uri);
+        Box::pin(futures::future::ready(Ok(description)))
+    }
+
+    #[inline]
+    fn endpoint_service(&self) -> Option<Box<dyn HttpService<Body, Body>>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+impl<Space: SolidStorageSpace> Service<Request<Body>> for WebSocketChannel2023<Space> {
+    type Response = Response<Body>;
+
+    type Error = Infallible;
+
+    type Future = BoxHttpResponseFuture<Body>;
+
+    #[inline]
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[tracing::instrument(skip_all, name = "WebSocketChannel2023::call")]
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let resp = self.handle_upgrade(req).unwrap_or_else(|resp| resp);
+        Box::pin(futures::future::ready(Ok(resp)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use claims::*;
+    use manas_repo_layers::eventing::event::ResourceChangeKind;
+    use manas_space::{
+        mock::MockSolidStorageSpace,
+        resource::{
+            kind::SolidResourceKind, slot::SolidResourceSlot, slot_id::SolidResourceSlotId,
+        },
+    };
+    use rstest::*;
+    use webid::WebId;
+
+    use super::*;
+
+    fn new_channel(config: WebSocketChannelConfig) -> WebSocketChannel2023<MockSolidStorageSpace> {
+        let space = MockSolidStorageSpace::new_from_valid_root_uri_str("http://ex.org/");
+        WebSocketChannel2023::new(&space, ResourceChangeEventBus::default(), config)
+    }
+
+    fn subscription(topic: &str, subscriber: Option<&str>) -> Subscription {
+        Subscription {
+            topic: SolidResourceUri::try_new_from(topic).unwrap(),
+            end_at: None,
+            send_to: None,
+            subscriber: subscriber.map(|webid| WebId::try_from(webid).unwrap()),
+        }
+    }
+
+    fn update_event(uri: &str) -> ResourceChangeEvent<MockSolidStorageSpace> {
+        let slot = SolidResourceSlot::try_new(
+            SolidResourceSlotId {
+                space: Arc::new(MockSolidStorageSpace::new_from_valid_root_uri_str(
+                    "http://ex.org/",
+                )),
+                uri: SolidResourceUri::try_new_from(uri).unwrap(),
+            },
+            SolidResourceKind::NonContainer,
+            None,
+        )
+        .unwrap();
+        ResourceChangeEvent {
+            res_slot: slot,
+            kind: ResourceChangeKind::Updated,
+            deleted_aux_res_links: vec![],
+            time: SystemTime::now(),
+        }
+    }
+
+    fn live_channel_count(channel: &WebSocketChannel2023<MockSolidStorageSpace>) -> usize {
+        channel.inner.subscriptions.read().unwrap().len()
+    }
+
+    fn endpoint_request(uri: &str) -> Request<Body> {
+        let mut req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        req.extensions_mut()
+            .insert(NormalAbsoluteHttpUri::try_new_from(uri).unwrap());
+        req
+    }
+
+    #[tokio::test]
+    async fn opened_channel_is_described_correctly() {
+        let channel = new_channel(Default::default());
+        let description = assert_ok!(channel.open(subscription("http://ex.org/a", None)).await);
+
+        let channel_id = assert_some!(description
+            .id
+            .strip_prefix("http://ex.org/_/notifications/websocket/"));
+        assert_eq!(
+            description.receive_from.as_deref(),
+            Some(format!("ws://ex.org/_/notifications/websocket/{}", channel_id).as_str())
+        );
+        assert_some!(channel.live_subscription(channel_id));
+    }
+
+    #[rstest]
+    #[case::unbounded(None)]
+    #[case::beyond_max_ttl(Some(chrono::Duration::days(7)))]
+    #[tokio::test]
+    async fn end_time_is_capped_by_max_ttl(#[case] requested_ttl: Option<chrono::Duration>) {
+        let channel = new_channel(WebSocketChannelConfig {
+            max_ttl: Duration::from_secs(60),
+            ..Default::default()
+        });
+        let mut sub = subscription("http://ex.org/a", None);
+        sub.end_at = requested_ttl.map(|ttl| Utc::now() + ttl);
+
+        let description = assert_ok!(channel.open(sub).await);
+        let end_at = assert_some!(description.end_at);
+        assert!(end_at <= Utc::now() + chrono::Duration::seconds(60));
+    }
+
+    #[tokio::test]
+    async fn requested_end_time_within_max_ttl_is_kept() {
+        let channel = new_channel(Default::default());
+        let requested_end_at = Utc::now() + chrono::Duration::minutes(5);
+        let mut sub = subscription("http://ex.org/a", None);
+        sub.end_at = Some(requested_end_at);
+
+        let description = assert_ok!(channel.open(sub).await);
+        assert_eq!(description.end_at, Some(requested_end_at));
+    }
+
+    #[tokio::test]
+    async fn channels_are_limited_per_agent() {
+        let channel = new_channel(WebSocketChannelConfig {
+            max_channels_per_agent: 2,
+            ..Default::default()
+        });
+        let alice = Some("http://alice.example.org/#me");
+        let bob = Some("http://bob.example.org/#me");
+
+        for _ in 0..2 {
+            assert_ok!(channel.open(subscription("http://ex.org/a", alice)).await);
+        }
+        let problem = assert_err!(channel.open(subscription("http://ex.org/a", alice)).await);
+        assert!(CHANNEL_LIMIT_EXCEEDED.is_type_of(&problem));
+
+        // Other agents, and anonymous subscribers are not
+        // affected.
+        assert_ok!(channel.open(subscription("http://ex.org/a", bob)).await);
+        assert_ok!(channel.open(subscription("http://ex.org/a", None)).await);
+        assert_eq!(live_channel_count(&channel), 4);
+    }
+
+    #[tokio::test]
+    async fn channels_are_limited_in_total() {
+        let channel = new_channel(WebSocketChannelConfig {
+            max_channels: 2,
+            ..Default::default()
+        });
+
+        assert_ok!(
+            channel
+                .open(subscription(
+                    "http://ex.org/a",
+                    Some("http://alice.example.org/#me")
+                ))
+                .await
+        );
+        assert_ok!(channel.open(subscription("http://ex.org/a", None)).await);
+        let problem = assert_err!(
+            channel
+                .open(subscription(
+                    "http://ex.org/a",
+                    Some("http://bob.example.org/#me")
+                ))
+                .await
+        );
+        assert!(CHANNEL_LIMIT_EXCEEDED.is_type_of(&problem));
+    }
+
+    #[tokio::test]
+    async fn expired_channels_are_purged_periodically() {
+        let channel = new_channel(WebSocketChannelConfig {
+            purge_interval: Duration::from_millis(20),
+            ..Default::default()
+        });
+        let mut sub = subscription("http://ex.org/a", None);
+        sub.end_at = Some(Utc::now() + chrono::Duration::milliseconds(50));
+        assert_ok!(channel.open(sub).await);
+        assert_ok!(channel.open(subscription("http://ex.org/b", None)).await);
+        assert_eq!(live_channel_count(&channel), 2);
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(live_channel_count(&channel), 1);
+    }
+
+    #[tokio::test]
+    async fn expired_channels_free_their_slots() {
+        let channel = new_channel(WebSocketChannelConfig {
+            max_channels_per_agent: 1,
+            ..Default::default()
+        });
+        let mut sub = subscription("http://ex.org/a", None);
+        sub.end_at = Some(Utc::now() + chrono::Duration::milliseconds(20));
+        assert_ok!(channel.open(sub).await);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_ok!(channel.open(subscription("http://ex.org/a", None)).await);
+    }
+
+    #[tokio::test]
+    async fn request_to_unknown_channel_is_not_found() {
+        let mut channel = new_channel(Default::default());
+        let resp = assert_ok!(
+            channel
+                .call(endpoint_request(
+                    "http://ex.org/_/notifications/websocket/unknown"
+                ))
+                .await
+        );
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn non_upgrade_request_to_channel_requires_upgrade() {
+        let mut channel = new_channel(Default::default());
+        let description = assert_ok!(channel.open(subscription("http://ex.org/a", None)).await);
+
+        let resp = assert_ok!(channel.call(endpoint_request(&description.id)).await);
+        assert_eq!(resp.status(), StatusCode::UPGRADE_REQUIRED);
+        assert_eq!(
+            resp.headers().get(header::UPGRADE),
+            Some(&HeaderValue::from_static("websocket"))
+        );
+    }
+
+    #[tokio::test]
+    async fn connection_receives_notifications_for_topic() {
+        let bus = ResourceChangeEventBus::<MockSolidStorageSpace>::default();
+        let (server_io, client_io) = tokio::io::duplex(4096);
+        let server_ws = WebSocketStream::from_raw_socket(server_io, Role::Server, None).await;
+        let mut client_ws = WebSocketStream::from_raw_socket(client_io, Role::Client, None).await;
+
+        let (_supersede, superseded) = oneshot::channel();
+        tokio::spawn(serve_connection(
+            server_ws,
+            bus.subscribe(),
+            subscription("http://ex.org/a", None),
+            superseded,
+        ));
+
+        bus.publish(update_event("http://ex.org/b"));
+        bus.publish(update_event("http://ex.org/a"));
+
+        let message = assert_ok!(assert_some!(tokio::time::timeout(
+            Duration::from_secs(5),
+            client_ws.next()
+        )
+        .await
+        .expect("Notification must be received in time.")));
+        let notification: serde_json::Value =
+            serde_json::from_str(assert_ok!(message.to_text())).unwrap();
+        assert_eq!(notification["type"], "Update");
+        assert_eq!(notification["object"], "http://ex.org/a");
+    }
+
+    #[tokio::test]
+    async fn connection_is_closed_on_expiry() {
+        let bus = ResourceChangeEventBus::<MockSolidStorageSpace>::default();
+        let (server_io, client_io) = tokio::io::duplex(4096);
+        let server_ws = WebSocketStream::from_raw_socket(server_io, Role::Server, None).await;
+        let mut client_ws = WebSocketStream::from_raw_socket(client_io, Role::Client, None).await;
+
+        let mut sub = subscription("http://ex.org/a", None);
+        sub.end_at = Some(Utc::now() + chrono::Duration::milliseconds(20));
+        let (_supersede, superseded) = oneshot::channel();
+        tokio::spawn(serve_connection(
+            server_ws,
+            bus.subscribe(),
+            sub,
+            superseded,
+        ));
+
+        let message = assert_ok!(assert_some!(tokio::time::timeout(
+            Duration::from_secs(5),
+            client_ws.next()
+        )
+        .await
+        .expect("Close must be received in time.")));
+        assert!(message.is_close());
+    }
+    #[tokio::test]
+    async fn new_connection_supersedes_prior_one() {
+        let channel = new_channel(Default::default());
+
+        let (first, mut first_superseded) = channel.register_connection("c1");
+        let (_other, mut other_superseded) = channel.register_connection("c2");
+        let (second, mut second_superseded) = channel.register_connection("c1");
+
+        assert_ok!(tokio::time::timeout(Duration::from_secs(5), &mut first_superseded).await);
+        assert_err!(second_superseded.try_recv());
+        assert_err!(other_superseded.try_recv());
+
+        // Superseded registration doesn't deregister it's
+        // successor.
+        drop(first);
+        assert_eq!(channel.inner.connections.lock().unwrap().len(), 2);
+        drop(second);
+        assert_eq!(channel.inner.connections.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn connection_is_closed_when_superseded() {
+        let bus = ResourceChangeEventBus::<MockSolidStorageSpace>::default();
+        let (server_io, client_io) = tokio::io::duplex(4096);
+        let server_ws = WebSocketStream::from_raw_socket(server_io, Role::Server, None).await;
+        let mut client_ws = WebSocketStream::from_raw_socket(client_io, Role::Client, None).await;
+
+        let (supersede, superseded) = oneshot::channel();
+        tokio::spawn(serve_connection(
+            server_ws,
+            bus.subscribe(),
+            subscription("http://ex.org/a", None),
+            superseded,
+        ));
+        drop(supersede);
+
+        let message = assert_ok!(assert_some!(tokio::time::timeout(
+            Duration::from_secs(5),
+            client_ws.next()
+        )
+        .await
+        .expect("Close must be received in time.")));
+        assert!(message.is_close());
+    }
+}
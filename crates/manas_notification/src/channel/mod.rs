@@ -0,0 +1,61 @@
+//! I define traits for notification channel types.
+//!
+
+use std::fmt::Debug;
+
+use dyn_problem::{define_anon_problem_types, ProbFuture};
+use manas_http::{body::Body, service::HttpService};
+use manas_space::{resource::uri::SolidResourceUri, SolidStorageSpace};
+
+use crate::model::{channel::ChannelDescription, subscription::Subscription};
+
+pub mod impl_;
+
+define_anon_problem_types!(
+    /// Invalid subscription.
+    INVALID_SUBSCRIPTION: ("Invalid subscription.");
+
+    /// Channel limit exceeded.
+    CHANNEL_LIMIT_EXCEEDED: ("Channel limit exceeded.");
+);
+
+/// A trait for notification channel types.
+///
+/// A channel type has a subscription resource, through
+/// which clients can request new channels. Any channel
+/// specific endpoints must be namespaced under the
+/// subscription resource uri.
+pub trait NotificationChannel: Debug + Clone + Send + Sync + 'static {
+    /// Type of the storage space.
+    type StSpace: SolidStorageSpace;
+
+    /// Iri of the channel type.
+    const CHANNEL_TYPE_IRI: &'static str;
+
+    /// Term for the channel type, defined in notifications
+    /// json-ld context.
+    const CHANNEL_TYPE_TERM: &'static str;
+
+    /// Slug of the channel type, used in minting it's uris.
+    const SLUG: &'static str;
+
+    /// Get uri of the subscription resource of this channel
+    /// type.
+    fn subscription_res_uri(&self) -> &SolidResourceUri;
+
+    /// Open a new channel for given validated subscription,
+    /// and resolve it's description.
+    ///
+    /// Should resolve to [`INVALID_SUBSCRIPTION`] problem,
+    /// if subscription is not acceptable for the channel
+    /// type, and to [`CHANNEL_LIMIT_EXCEEDED`] problem, if no
+    /// more channels can be opened for the subscriber.
+    fn open(&self, subscription: Subscription) -> ProbFuture<'static, ChannelDescription>;
+
+    /// Get service that handles requests to channel specific
+    /// endpoints, if any.
+    #[inline]
+    fn endpoint_service(&self) -> Option<Box<dyn HttpService<Body, Body>>> {
+        None
+    }
+}
@@ -0,0 +1,20 @@
+//! This crate provides an implementation of
+//! [solid notifications protocol](https://solidproject.org/TR/notifications-protocol)
+//! over manas storages.
+//!
+//! Storage repos publish resource change events to a
+//! [`ResourceChangeEventBus`](manas_repo_layers::eventing::event::ResourceChangeEventBus)
+//! through an [`EventingRepo`](manas_repo_layers::eventing::EventingRepo)
+//! layer. Notification channels subscribe to that bus, and
+//! deliver notifications to subscribers over channel
+//! specific transports.
+//!
+
+#![warn(missing_docs)]
+#![cfg_attr(doc_cfg, feature(doc_auto_cfg))]
+#![deny(unused_qualifications)]
+
+pub mod channel;
+pub mod model;
+pub mod service;
+pub mod vocab;
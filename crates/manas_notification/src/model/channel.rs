@@ -0,0 +1,56 @@
+//! I define types to represent notification channel
+//! descriptions.
+//!
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::vocab::NOTIFICATION_CONTEXT_URI;
+
+/// A struct for representing description of a notification
+/// channel, that is returned to the subscriber.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChannelDescription {
+    /// Json-ld context.
+    #[serde(rename = "@context")]
+    pub context: &'static str,
+
+    /// Id of the channel.
+    pub id: String,
+
+    /// Type of the channel, as a term defined in
+    /// notifications context.
+    #[serde(rename = "type")]
+    pub channel_type: &'static str,
+
+    /// Topic of the channel.
+    pub topic: String,
+
+    /// Uri from which subscriber can receive notifications.
+    #[serde(rename = "receiveFrom", skip_serializing_if = "Option::is_none")]
+    pub receive_from: Option<String>,
+
+    /// Uri to which notifications are sent.
+    #[serde(rename = "sendTo", skip_serializing_if = "Option::is_none")]
+    pub send_to: Option<String>,
+
+    /// Expiry time of the channel.
+    #[serde(rename = "endAt", skip_serializing_if = "Option::is_none")]
+    pub end_at: Option<DateTime<Utc>>,
+}
+
+impl ChannelDescription {
+    /// Create a new [`ChannelDescription`] with given id,
+    /// type and topic.
+    pub fn new(id: String, channel_type: &'static str, topic: String) -> Self {
+        Self {
+            context: NOTIFICATION_CONTEXT_URI,
+            id,
+            channel_type,
+            topic,
+            receive_from: None,
+            send_to: None,
+            end_at: None,
+        }
+    }
+}
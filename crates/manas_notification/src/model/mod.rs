@@ -0,0 +1,7 @@
+//! I define models for subscriptions, channels and
+//! notifications of solid notifications protocol.
+//!
+
+pub mod channel;
+pub mod notification;
+pub mod subscription;
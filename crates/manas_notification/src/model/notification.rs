@@ -0,0 +1,240 @@
+//! I define types to represent notifications, and to resolve
+//! them from resource change events.
+//!
+
+use chrono::{DateTime, Utc};
+use manas_repo_layers::eventing::event::{ResourceChangeEvent, ResourceChangeKind};
+use manas_space::{
+    resource::{slot_rel_type::SlotRelationType, uri::SolidResourceUri},
+    SolidStorageSpace,
+};
+use serde::Serialize;
+
+use crate::vocab::{ACTIVITY_STREAMS_CONTEXT_URI, NOTIFICATION_CONTEXT_URI};
+
+/// An enum of activity types of notifications.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum ActivityType {
+    /// Topic resource is created.
+    Create,
+
+    /// Topic resource is updated.
+    Update,
+
+    /// Topic resource is deleted.
+    Delete,
+
+    /// A resource is added to the topic container.
+    Add,
+
+    /// A resource is removed from the topic container.
+    Remove,
+}
+
+/// A struct for representing a notification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Notification {
+    /// Json-ld context.
+    #[serde(rename = "@context")]
+    pub context: [&'static str; 2],
+
+    /// Id of the notification.
+    pub id: String,
+
+    /// Activity type of the notification.
+    #[serde(rename = "type")]
+    pub activity_type: ActivityType,
+
+    /// Object of the activity.
+    pub object: String,
+
+    /// Target of the activity, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+
+    /// Time at which the activity was published.
+    pub published: DateTime<Utc>,
+}
+
+impl Notification {
+    /// Create a new [`Notification`] with given params, and a
+    /// random id.
+    pub fn new(
+        activity_type: ActivityType,
+        object: String,
+        target: Option<String>,
+        published: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            context: [ACTIVITY_STREAMS_CONTEXT_URI, NOTIFICATION_CONTEXT_URI],
+            id: format!("urn:uuid:{}", uuid::Uuid::new_v4()),
+            activity_type,
+            object,
+            target,
+            published,
+        }
+    }
+
+    /// Resolve notification to be delivered to subscribers
+    /// of given topic, for given resource change event.
+    /// Returns `None`, if event is not relevant to the topic.
+    pub fn resolve_for_topic<Space: SolidStorageSpace>(
+        event: &ResourceChangeEvent<Space>,
+        topic: &SolidResourceUri,
+    ) -> Option<Self> {
+        let published = DateTime::<Utc>::from(event.time);
+        let res_uri = &event.res_slot.id().uri;
+
+        // If topic is the changed resource itself.
+        if res_uri == topic {
+            let activity_type = match event.kind {
+                ResourceChangeKind::Created => ActivityType::Create,
+                ResourceChangeKind::Updated => ActivityType::Update,
+                ResourceChangeKind::Deleted => ActivityType::Delete,
+            };
+            return Some(Self::new(
+                activity_type,
+                topic.as_str().to_owned(),
+                None,
+                published,
+            ));
+        }
+
+        // If topic is the container of the changed resource.
+        if let Some(slot_rev_link) = event.res_slot.slot_rev_link() {
+            if slot_rev_link.rev_rel_type == SlotRelationType::Contains
+                && &slot_rev_link.target == topic
+            {
+                let activity_type = match event.kind {
+                    ResourceChangeKind::Created => ActivityType::Add,
+                    ResourceChangeKind::Deleted => ActivityType::Remove,
+                    ResourceChangeKind::Updated => return None,
+                };
+                return Some(Self::new(
+                    activity_type,
+                    res_uri.as_str().to_owned(),
+                    Some(topic.as_str().to_owned()),
+                    published,
+                ));
+            }
+        }
+
+        // If topic is an aux resource deleted along with
+        // the changed resource.
+        if event
+            .deleted_aux_res_links
+            .iter()
+            .any(|aux_link| &aux_link.target == topic)
+        {
+            return Some(Self::new(
+                ActivityType::Delete,
+                topic.as_str().to_owned(),
+                None,
+                published,
+            ));
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use claims::{assert_none, assert_some};
+    use manas_space::{
+        mock::MockSolidStorageSpace,
+        resource::{
+            kind::SolidResourceKind, slot::SolidResourceSlot, slot_id::SolidResourceSlotId,
+            slot_rev_link::SlotRevLink,
+        },
+    };
+    use rstest::*;
+
+    use super::*;
+
+    fn uri(uri_str: &str) -> SolidResourceUri {
+        SolidResourceUri::try_new_from(uri_str).expect("Must be valid")
+    }
+
+    fn contained_slot(
+        res_uri_str: &str,
+        container_uri_str: &str,
+    ) -> SolidResourceSlot<MockSolidStorageSpace> {
+        let space = Arc::new(MockSolidStorageSpace::new_from_valid_root_uri_str(
+            "http://ex.org/",
+        ));
+        SolidResourceSlot::try_new(
+            SolidResourceSlotId {
+                space,
+                uri: uri(res_uri_str),
+            },
+            SolidResourceKind::NonContainer,
+            Some(SlotRevLink {
+                target: uri(container_uri_str),
+                rev_rel_type: SlotRelationType::Contains,
+            }),
+        )
+        .expect("Must be valid")
+    }
+
+    #[rstest]
+    #[case(
+        ResourceChangeKind::Created,
+        "http://ex.org/c/a",
+        ActivityType::Create,
+        None
+    )]
+    #[case(
+        ResourceChangeKind::Updated,
+        "http://ex.org/c/a",
+        ActivityType::Update,
+        None
+    )]
+    #[case(
+        ResourceChangeKind::Deleted,
+        "http://ex.org/c/a",
+        ActivityType::Delete,
+        None
+    )]
+    #[case(
+        ResourceChangeKind::Created,
+        "http://ex.org/c/",
+        ActivityType::Add,
+        Some("http://ex.org/c/")
+    )]
+    #[case(
+        ResourceChangeKind::Deleted,
+        "http://ex.org/c/",
+        ActivityType::Remove,
+        Some("http://ex.org/c/")
+    )]
+    fn relevant_event_resolves_correct_notification(
+        #[case] kind: ResourceChangeKind,
+        #[case] topic_str: &str,
+        #[case] expected_activity_type: ActivityType,
+        #[case] expected_target: Option<&str>,
+    ) {
+        let event = ResourceChangeEvent::new_now(
+            contained_slot("http://ex.org/c/a", "http://ex.org/c/"),
+            kind,
+        );
+        let notification = assert_some!(Notification::resolve_for_topic(&event, &uri(topic_str)));
+        assert_eq!(notification.activity_type, expected_activity_type);
+        assert_eq!(notification.object, "http://ex.org/c/a");
+        assert_eq!(notification.target.as_deref(), expected_target);
+    }
+
+    #[rstest]
+    #[case(ResourceChangeKind::Updated, "http://ex.org/c/")]
+    #[case(ResourceChangeKind::Created, "http://ex.org/c/b")]
+    #[case(ResourceChangeKind::Deleted, "http://ex.org/")]
+    fn irrelevant_event_resolves_none(#[case] kind: ResourceChangeKind, #[case] topic_str: &str) {
+        let event = ResourceChangeEvent::new_now(
+            contained_slot("http://ex.org/c/a", "http://ex.org/c/"),
+            kind,
+        );
+        assert_none!(Notification::resolve_for_topic(&event, &uri(topic_str)));
+    }
+}
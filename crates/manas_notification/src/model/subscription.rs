@@ -0,0 +1,125 @@
+//! I define types to represent subscription requests, and
+//! validated subscriptions.
+//!
+
+use chrono::{DateTime, Utc};
+use manas_space::resource::uri::SolidResourceUri;
+use serde::{Deserialize, Deserializer};
+use webid::WebId;
+
+use crate::vocab::notify;
+
+/// A struct for representing a subscription request.
+///
+/// Only compact json-ld documents, that use the solid
+/// notifications context are supported.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SubscriptionRequest {
+    /// Type of the requested channel. It can be a term
+    /// defined in notifications context, a `notify:`
+    /// prefixed name, or an absolute iri.
+    #[serde(rename = "type", alias = "@type")]
+    pub channel_type: String,
+
+    /// Topic of the subscription.
+    #[serde(deserialize_with = "deserialize_one_or_first")]
+    pub topic: String,
+
+    /// Requested expiry time of the channel.
+    #[serde(rename = "endAt", default)]
+    pub end_at: Option<DateTime<Utc>>,
+
+    /// Uri to which notifications are to be sent, for
+    /// channel types that push notifications.
+    #[serde(rename = "sendTo", default)]
+    pub send_to: Option<String>,
+}
+
+impl SubscriptionRequest {
+    /// Try to parse a subscription request from given json
+    /// bytes.
+    #[inline]
+    pub fn try_from_json(bytes: &[u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice(bytes)
+    }
+
+    /// Get the expanded iri of the requested channel type.
+    pub fn channel_type_iri(&self) -> String {
+        let prefix = notify::PREFIX.as_str();
+        if let Some(local_name) = self.channel_type.strip_prefix("notify:") {
+            format!("{}{}", prefix, local_name)
+        } else if !self.channel_type.contains(':') {
+            format!("{}{}", prefix, self.channel_type)
+        } else {
+            self.channel_type.clone()
+        }
+    }
+}
+
+fn deserialize_one_or_first<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(v) => Ok(v),
+        OneOrMany::Many(mut vs) if vs.len() == 1 => Ok(vs.remove(0)),
+        OneOrMany::Many(_) => Err(serde::de::Error::custom("Exactly one topic is supported.")),
+    }
+}
+
+/// A struct for representing a validated subscription.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subscription {
+    /// Topic resource uri.
+    pub topic: SolidResourceUri,
+
+    /// Expiry time of the channel.
+    pub end_at: Option<DateTime<Utc>>,
+
+    /// Uri to which notifications are to be sent.
+    pub send_to: Option<String>,
+
+    /// WebId of the subscribing agent, if authenticated.
+    pub subscriber: Option<WebId>,
+}
+
+#[cfg(test)]
+mod tests {
+    use claims::{assert_err, assert_ok};
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case::term(r#"{"type": "WebSocketChannel2023", "topic": "http://ex.org/a"}"#)]
+    #[case::prefixed(r#"{"type": "notify:WebSocketChannel2023", "topic": ["http://ex.org/a"]}"#)]
+    #[case::iri(
+        r#"{"@context": ["https://www.w3.org/ns/solid/notification/v1"], "@type": "http://www.w3.org/ns/solid/notifications#WebSocketChannel2023", "topic": "http://ex.org/a"}"#
+    )]
+    fn valid_request_parses_correctly(#[case] json: &str) {
+        let request = assert_ok!(SubscriptionRequest::try_from_json(json.as_bytes()));
+        assert_eq!(
+            request.channel_type_iri(),
+            notify::WebSocketChannel2023.to_string()
+        );
+        assert_eq!(request.topic, "http://ex.org/a");
+    }
+
+    #[rstest]
+    #[case::no_topic(r#"{"type": "WebSocketChannel2023"}"#)]
+    #[case::many_topics(
+        r#"{"type": "WebSocketChannel2023", "topic": ["http://ex.org/a", "http://ex.org/b"]}"#
+    )]
+    #[case::invalid_end_at(
+        r#"{"type": "WebSocketChannel2023", "topic": "http://ex.org/a", "endAt": "tomorrow"}"#
+    )]
+    fn invalid_request_errors(#[case] json: &str) {
+        assert_err!(SubscriptionRequest::try_from_json(json.as_bytes()));
+    }
+}
@@ -0,0 +1,207 @@
+//! I define http services for solid notifications protocol.
+//!
+
+use std::{convert::Infallible, ops::Deref, sync::Arc, task::Poll};
+
+use http::{Request, Response, StatusCode};
+use http_api_problem::ApiError;
+use manas_http::{
+    body::Body,
+    problem::ApiErrorExt,
+    service::{namespaced::NamespacedHttpService, BoxHttpResponseFuture, HttpService},
+    uri::invariant::NormalAbsoluteHttpUri,
+};
+use manas_space::{resource::uri::SolidResourceUri, SolidStorageSpace};
+use manas_storage::{SolidStorage, SolidStorageExt};
+use rdf_utils::model::{term::ArcTerm, triple::ArcTriple};
+use sophia_api::term::{IriRef, Term};
+use tower::Service;
+use tracing::{error, info};
+
+use crate::{channel::NotificationChannel, vocab::notify};
+
+mod subscription;
+
+pub use subscription::*;
+
+/// Path of the notifications namespace, relative to storage
+/// root.
+pub const NOTIFICATIONS_NS_REL_PATH: &str = "_/notifications/";
+
+/// Resolve uri of the subscription resource of the channel
+/// type with given slug, in given storage space.
+pub fn resolve_subscription_res_uri<Space: SolidStorageSpace>(
+    space: &Space,
+    channel_slug: &str,
+) -> SolidResourceUri {
+    SolidResourceUri::try_new_from(
+        format!(
+            "{}{}{}/",
+            space.root_res_uri().as_str(),
+            NOTIFICATIONS_NS_REL_PATH,
+            channel_slug
+        )
+        .as_str(),
+    )
+    .expect("Must be valid.")
+}
+
+/// Resolve storage description statements, that advertise
+/// subscription resource of given channel type in given
+/// storage space.
+pub fn resolve_description_statements<Channel: NotificationChannel>(
+    space: &Channel::StSpace,
+) -> Vec<ArcTriple> {
+    let root_res_term = space.root_res_uri().deref().into_term::<ArcTerm>();
+    let subscription_res_term = resolve_subscription_res_uri(space, Channel::SLUG)
+        .deref()
+        .into_term::<ArcTerm>();
+
+    vec![
+        [
+            root_res_term,
+            notify::subscription.into_term(),
+            subscription_res_term.clone(),
+        ],
+        [
+            subscription_res_term,
+            notify::channelType.into_term(),
+            IriRef::new_unchecked(Channel::CHANNEL_TYPE_IRI).into_term(),
+        ],
+    ]
+}
+
+/// A route in notifications service.
+#[derive(Clone)]
+struct ChannelRoute {
+    /// Uri of the subscription resource of the channel.
+    subscription_res_uri: SolidResourceUri,
+
+    /// Subscription service of the channel.
+    subscription_svc: Box<dyn HttpService<Body, Body>>,
+
+    /// Service for channel specific endpoints.
+    endpoint_svc: Option<Box<dyn HttpService<Body, Body>>>,
+}
+
+/// An implementation of [`NamespacedHttpService`], that
+/// serves subscription resources and channel endpoints of
+/// configured notification channels over a storage.
+///
+/// It's namespace is the notifications namespace of the
+/// storage space.
+pub struct SolidNotificationsService<Storage> {
+    storage: Arc<Storage>,
+    ns_uri_prefix: Arc<str>,
+    routes: Arc<Vec<ChannelRoute>>,
+}
+
+impl<Storage> Clone for SolidNotificationsService<Storage> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            storage: self.storage.clone(),
+            ns_uri_prefix: self.ns_uri_prefix.clone(),
+            routes: self.routes.clone(),
+        }
+    }
+}
+
+impl<Storage> std::fmt::Debug for SolidNotificationsService<Storage> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SolidNotificationsService")
+            .field("ns_uri_prefix", &self.ns_uri_prefix)
+            .finish()
+    }
+}
+
+impl<Storage: SolidStorage> SolidNotificationsService<Storage> {
+    /// Create a new [`SolidNotificationsService`] over given
+    /// storage, without any channels.
+    pub fn new(storage: Arc<Storage>) -> Self {
+        let ns_uri_prefix = format!(
+            "{}{}",
+            storage.space().root_res_uri().as_str(),
+            NOTIFICATIONS_NS_REL_PATH
+        );
+        Self {
+            storage,
+            ns_uri_prefix: ns_uri_prefix.into(),
+            routes: Default::default(),
+        }
+    }
+
+    /// Get a new service with given channel type added.
+    pub fn with_channel<Channel>(mut self, channel: Channel) -> Self
+    where
+        Channel: NotificationChannel<StSpace = Storage::StSpace>,
+    {
+        let route = ChannelRoute {
+            subscription_res_uri: channel.subscription_res_uri().clone(),
+            endpoint_svc: channel.endpoint_service(),
+            subscription_svc: Box::new(SubscriptionService::new(self.storage.clone(), channel)),
+        };
+        Arc::make_mut(&mut self.routes).push(route);
+        self
+    }
+
+    /// Get the storage.
+    #[inline]
+    pub fn storage(&self) -> &Arc<Storage> {
+        &self.storage
+    }
+}
+
+impl<Storage: SolidStorage> Service<Request<Body>> for SolidNotificationsService<Storage> {
+    type Response = Response<Body>;
+
+    type Error = Infallible;
+
+    type Future = BoxHttpResponseFuture<Body>;
+
+    #[inline]
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[tracing::instrument(skip_all, name = "SolidNotificationsService::call")]
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let uri_str = req
+            .extensions()
+            .get::<NormalAbsoluteHttpUri>()
+            .expect("Service must be called after handling uri normal validity check.")
+            .as_str();
+
+        for route in self.routes.iter() {
+            let subscription_res_uri_str = route.subscription_res_uri.as_str();
+
+            if uri_str == subscription_res_uri_str {
+                info!("Request targets subscription resource.");
+                return route.subscription_svc.clone().call(req);
+            }
+
+            if uri_str.starts_with(subscription_res_uri_str) {
+                if let Some(endpoint_svc) = route.endpoint_svc.as_ref() {
+                    info!("Request targets channel endpoint.");
+                    return endpoint_svc.clone().call(req);
+                }
+            }
+        }
+
+        error!("No notifications resource at request target.");
+        Box::pin(futures::future::ready(Ok(ApiError::builder(
+            StatusCode::NOT_FOUND,
+        )
+        .finish()
+        .into_http_response())))
+    }
+}
+
+impl<Storage: SolidStorage> NamespacedHttpService<Body, Body>
+    for SolidNotificationsService<Storage>
+{
+    #[inline]
+    fn has_in_uri_ns(&self, uri: &NormalAbsoluteHttpUri) -> bool {
+        uri.as_str().starts_with(self.ns_uri_prefix.as_ref())
+    }
+}
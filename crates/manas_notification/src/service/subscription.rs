@@ -0,0 +1,261 @@
+//! I define an http service, that handles requests to
+//! subscription resource of a notification channel type.
+//!
+
+use std::{convert::Infallible, sync::Arc, task::Poll};
+
+use chrono::Utc;
+use dyn_problem::Problem;
+use http::{header, Method, Request, Response, StatusCode};
+use http_api_problem::ApiError;
+use http_body_util::{BodyExt, Limited};
+use manas_authentication::common::credentials::{AgentCredentials, RequestCredentials};
+use manas_http::{body::Body, problem::ApiErrorExt, service::BoxHttpResponseFuture};
+use manas_repo::{
+    service::resource_operator::{
+        common::problem::ACCESS_DENIED, reader::rep_preferences::RepresentationPreferences,
+    },
+    RepoExt,
+};
+use manas_space::{resource::uri::SolidResourceUri, SolidStorageSpace};
+use manas_storage::{SgCredentials, SolidStorage, SolidStorageExt};
+use tower::Service;
+use tracing::{error, info};
+use webid::WebId;
+
+use crate::{
+    channel::{NotificationChannel, CHANNEL_LIMIT_EXCEEDED, INVALID_SUBSCRIPTION},
+    model::subscription::{Subscription, SubscriptionRequest},
+};
+
+/// Maximum size of subscription request body.
+const MAX_SUBSCRIPTION_REQUEST_SIZE: usize = 64 * 1024;
+
+/// A service that handles subscription requests for a
+/// notification channel type.
+///
+/// It ensures that the subscriber has read access over the
+/// topic resource, before opening a channel.
+#[derive(Debug)]
+pub struct SubscriptionService<Storage, Channel> {
+    /// Storage.
+    pub storage: Arc<Storage>,
+
+    /// Notification channel.
+    pub channel: Channel,
+}
+
+impl<Storage, Channel: Clone> Clone for SubscriptionService<Storage, Channel> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            storage: self.storage.clone(),
+            channel: self.channel.clone(),
+        }
+    }
+}
+
+impl<Storage, Channel> SubscriptionService<Storage, Channel>
+where
+    Storage: SolidStorage,
+    Channel: NotificationChannel<StSpace = Storage::StSpace>,
+{
+    /// Create a new [`SubscriptionService`].
+    #[inline]
+    pub fn new(storage: Arc<Storage>, channel: Channel) -> Self {
+        Self { storage, channel }
+    }
+
+    /// Handle the subscription request.
+    async fn apply(
+        storage: Arc<Storage>,
+        channel: Channel,
+        mut req: Request<Body>,
+    ) -> Result<Response<Body>, ApiError> {
+        if req.method() != Method::POST {
+            error!("Method not allowed on subscription resource.");
+            return Err(ApiError::builder(StatusCode::METHOD_NOT_ALLOWED)
+                .message("Subscription resource only supports POST method.")
+                .finish());
+        }
+
+        // Ensure json content type.
+        let is_json = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<mime::Mime>().ok())
+            .map_or(false, |m| {
+                m.type_() == mime::APPLICATION
+                    && (m.subtype() == mime::JSON || m.suffix() == Some(mime::JSON))
+            });
+        if !is_json {
+            error!("Unsupported subscription request content type.");
+            return Err(ApiError::builder(StatusCode::UNSUPPORTED_MEDIA_TYPE)
+                .message("Subscription requests must be json-ld documents.")
+                .finish());
+        }
+
+        let credentials = req
+            .extensions_mut()
+            .remove::<SgCredentials<Storage>>()
+            .unwrap_or_default();
+
+        // Parse the request.
+        let body = Limited::new(req.into_body(), MAX_SUBSCRIPTION_REQUEST_SIZE)
+            .collect()
+            .await
+            .map_err(|e| {
+                error!("Error in reading subscription request body. Error:\n {}", e);
+                ApiError::builder(StatusCode::BAD_REQUEST)
+                    .message("Error in reading subscription request body.")
+                    .finish()
+            })?
+            .to_bytes();
+
+        let sub_request = SubscriptionRequest::try_from_json(&body).map_err(|e| {
+            error!("Invalid subscription request. Error:\n {}", e);
+            ApiError::builder(StatusCode::BAD_REQUEST)
+                .message(format!("Invalid subscription request. {}", e))
+                .finish()
+        })?;
+
+        let subscriber = credentials.of_agent().map(|agent| agent.webid().clone());
+        let subscription = Self::validate(storage.as_ref(), sub_request, subscriber)?;
+
+        // Ensure subscriber has read access over the topic.
+        match storage
+            .repo()
+            .read_basic(
+                subscription.topic.clone(),
+                credentials,
+                RepresentationPreferences::new_light(),
+            )
+            .await
+        {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                error!("Topic resource doesn't exist.");
+                return Err(ApiError::builder(StatusCode::NOT_FOUND)
+                    .message("Topic resource doesn't exist.")
+                    .finish());
+            }
+            Err(problem) => return Err(Self::map_read_problem(problem)),
+        }
+
+        let description = channel.open(subscription).await.map_err(|e| {
+            error!("Error in opening the channel. Error:\n {}", e);
+            if INVALID_SUBSCRIPTION.is_type_of(&e) {
+                ApiError::builder(StatusCode::UNPROCESSABLE_ENTITY)
+                    .message(e.message().unwrap_or_default())
+                    .finish()
+            } else if CHANNEL_LIMIT_EXCEEDED.is_type_of(&e) {
+                ApiError::builder(StatusCode::TOO_MANY_REQUESTS)
+                    .message(e.message().unwrap_or_default())
+                    .finish()
+            } else {
+                ApiError::builder(StatusCode::INTERNAL_SERVER_ERROR).finish()
+            }
+        })?;
+
+        info!("Subscription succeeded. Channel: {}", description.id);
+
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "application/ld+json")
+            .body(Body::from(
+                serde_json::to_string(&description).expect("Must be serializable."),
+            ))
+            .expect("Must be valid."))
+    }
+
+    /// Validate subscription request.
+    #[allow(clippy::result_large_err)]
+    fn validate(
+        storage: &Storage,
+        sub_request: SubscriptionRequest,
+        subscriber: Option<WebId>,
+    ) -> Result<Subscription, ApiError> {
+        if sub_request.channel_type_iri() != Channel::CHANNEL_TYPE_IRI {
+            error!("Unsupported channel type.");
+            return Err(ApiError::builder(StatusCode::UNPROCESSABLE_ENTITY)
+                .message(format!(
+                    "Subscription resource only supports {} channel type.",
+                    Channel::CHANNEL_TYPE_TERM
+                ))
+                .finish());
+        }
+
+        let topic = SolidResourceUri::try_new_from(sub_request.topic.as_str())
+            .ok()
+            .filter(|topic| {
+                topic
+                    .as_str()
+                    .starts_with(storage.space().root_res_uri().as_str())
+            })
+            .ok_or_else(|| {
+                error!("Invalid topic.");
+                ApiError::builder(StatusCode::UNPROCESSABLE_ENTITY)
+                    .message("Topic must be a resource in the storage.")
+                    .finish()
+            })?;
+
+        if sub_request
+            .end_at
+            .map_or(false, |end_at| end_at <= Utc::now())
+        {
+            error!("Requested expiry time is in the past.");
+            return Err(ApiError::builder(StatusCode::UNPROCESSABLE_ENTITY)
+                .message("Requested expiry time is in the past.")
+                .finish());
+        }
+
+        Ok(Subscription {
+            topic,
+            end_at: sub_request.end_at,
+            send_to: sub_request.send_to,
+            subscriber,
+        })
+    }
+
+    /// Map problem in reading topic to api error.
+    fn map_read_problem(problem: Problem) -> ApiError {
+        if ACCESS_DENIED.is_type_of(&problem) {
+            error!("Subscriber doesn't have read access over the topic.");
+            return ApiError::builder(StatusCode::FORBIDDEN)
+                .message("Subscriber doesn't have read access over the topic.")
+                .finish();
+        }
+
+        error!("Error in reading the topic. Error:\n {}", problem);
+        ApiError::builder(StatusCode::INTERNAL_SERVER_ERROR).finish()
+    }
+}
+
+impl<Storage, Channel> Service<Request<Body>> for SubscriptionService<Storage, Channel>
+where
+    Storage: SolidStorage,
+    Channel: NotificationChannel<StSpace = Storage::StSpace>,
+{
+    type Response = Response<Body>;
+
+    type Error = Infallible;
+
+    type Future = BoxHttpResponseFuture<Body>;
+
+    #[inline]
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[tracing::instrument(skip_all, name = "SubscriptionService::call")]
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let storage = self.storage.clone();
+        let channel = self.channel.clone();
+        Box::pin(async move {
+            Ok(Self::apply(storage, channel, req)
+                .await
+                .unwrap_or_else(|e| e.into_http_response()))
+        })
+    }
+}
@@ -0,0 +1,23 @@
+//! I define vocabularies used in solid notifications protocol.
+//!
+
+/// Uri of the json-ld context of solid notifications protocol.
+pub const NOTIFICATION_CONTEXT_URI: &str = "https://www.w3.org/ns/solid/notification/v1";
+
+/// Uri of the json-ld context of activity streams.
+pub const ACTIVITY_STREAMS_CONTEXT_URI: &str = "https://www.w3.org/ns/activitystreams";
+
+/// Solid notifications namespace.
+pub mod notify {
+    sophia_api::namespace! {
+        "http://www.w3.org/ns/solid/notifications#",
+        subscription,
+        channelType,
+        topic,
+        receiveFrom,
+        sendTo,
+        endAt,
+        WebSocketChannel2023,
+        WebhookChannel2023;
+    }
+}
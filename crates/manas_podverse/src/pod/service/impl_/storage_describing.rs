@@ -15,12 +15,12 @@ use manas_http::{
     service::{namespaced::NamespacedHttpService, BoxHttpResponseFuture},
 };
 use manas_space::{resource::uri::SolidResourceUri, SolidStorageSpace};
-use manas_storage::SolidStorageExt;
+use manas_storage::{SolidStorage, SolidStorageExt};
 use rdf_dynsyn::{
     serializer::triples::DynSynTripleSerializerFactory,
     syntax::invariant::triples_serializable::TS_TURTLE,
 };
-use rdf_utils::model::term::ArcTerm;
+use rdf_utils::model::{term::ArcTerm, triple::ArcTriple};
 use rdf_vocabularies::ns;
 use sophia_api::{
     serializer::{Stringifier, TripleSerializer},
@@ -28,12 +28,39 @@ use sophia_api::{
 };
 use tower::{Service, ServiceExt};
 use tracing::{error, info};
+use typed_record::{TypedRecord, TypedRecordKey};
 
 use crate::pod::{
     service::{PodService, PodServiceFactory},
     Pod,
 };
 
+/// A typed record key for additional statements, that are to
+/// be included in storage description. Storages can set it
+/// in their extensions to advertise services like
+/// notification channels.
+#[derive(Debug, Clone)]
+pub struct KAdditionalStorageDescriptionStatements;
+
+impl TypedRecordKey for KAdditionalStorageDescriptionStatements {
+    type Value = Arc<Vec<ArcTriple>>;
+}
+
+/// Type of functions, that resolve storage description
+/// statements at the time of description.
+pub type StorageDescriptionStatementsResolver = Arc<dyn Fn() -> Vec<ArcTriple> + Send + Sync>;
+
+/// A typed record key for resolvers of dynamic statements,
+/// that are to be included in storage description. Storages
+/// can set it in their extensions to advertise current state
+/// like storage usage.
+#[derive(Debug, Clone)]
+pub struct KDynamicStorageDescriptionStatements;
+
+impl TypedRecordKey for KDynamicStorageDescriptionStatements {
+    type Value = Vec<StorageDescriptionStatementsResolver>;
+}
+
 /// An implementation of [`PodService`], that wraps another pod-service,
 /// and intercepts requests targeting storage description resource, and serves them.
 #[derive(Debug, Clone)]
@@ -85,7 +112,7 @@ where
 
                 // Otherwise create description response.
                 // TODO must use existing representation stack.
-                let statements = vec![[
+                let mut statements = vec![[
                     storage
                         .space()
                         .root_res_uri()
@@ -94,6 +121,23 @@ where
                     ns::rdf::type_.into_term(),
                     ns::pim::Storage.into_term(),
                 ]];
+
+                if let Some(additional_statements) = storage
+                    .extensions()
+                    .get_rv::<KAdditionalStorageDescriptionStatements>(
+                ) {
+                    statements.extend(additional_statements.iter().cloned());
+                }
+
+                if let Some(resolvers) = storage
+                    .extensions()
+                    .get_rv::<KDynamicStorageDescriptionStatements>()
+                {
+                    for resolve in resolvers {
+                        statements.extend(resolve());
+                    }
+                }
+
                 let body = DynSynTripleSerializerFactory::default()
                     .new_stringifier(TS_TURTLE)
                     .serialize_triples(statements.into_iter().map(Result::<_, Infallible>::Ok))
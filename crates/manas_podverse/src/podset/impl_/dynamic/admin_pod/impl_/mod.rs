@@ -1,4 +1,5 @@
 //! I provide few implementations of the [`AdminPod`](super::AdminPod).
-//! 
+//!
 
+#[cfg(feature = "impl-podset-templated")]
 pub mod template_driven;
@@ -1,30 +1,440 @@
-//! I provide an implementation of [`AdminPod`] that manages 
+//! I provide an implementation of [`AdminPod`] that manages
 //! member pods based on a configured template.
-//! 
+//!
 
-use std::sync::Arc;
+use std::{borrow::Borrow, fmt::Debug, path::PathBuf, sync::Arc, time::Duration};
 
-use dashmap::DashSet;
+use dyn_problem::{
+    type_::{INTERNAL_ERROR, UNKNOWN_IO_ERROR},
+    ProbFuture, Problem,
+};
+use futures::{future::BoxFuture, TryFutureExt};
+use manas_space::resource::uri::SolidResourceUri;
+use moka::future::Cache;
+use name_locker::{impl_::InmemNameLocker, LockKind, NameLocker};
+use tracing::{error, info};
 
-use crate::pod::impl_::BasicPod;
-
-use self::pod_template::PodTemplate;
+use self::{pod_template::PodTemplate, registry::ProvisionedKeysRegistry};
+use crate::{
+    pod::{Pod, PodExt},
+    podset::{
+        impl_::dynamic::admin_pod::{
+            AdminPod, INVALID_MEMBER_POD_KEY, MEMBER_POD_ALREADY_PROVISIONED,
+        },
+        POD_IS_UNPROVISIONED, POD_NOT_IN_SET, TARGET_IN_UNPROVISIONED_POD_NAMESPACE,
+        TARGET_NOT_IN_NAMESPACE,
+    },
+};
 
 pub mod pod_template;
+pub mod registry;
+
+/// Configuration for [`TemplateDrivenAdminPod`].
+#[derive(Debug, Clone)]
+pub struct TemplateDrivenAdminPodConfig {
+    /// Uri of the provisions container. If not provided,
+    /// root container of the admin pod will be used.
+    pub provisions_container_uri: Option<SolidResourceUri>,
+
+    /// Path of the file in which provisioned member keys
+    /// are persisted. If not provided, they are kept in
+    /// memory only.
+    pub registry_path: Option<PathBuf>,
 
+    /// Maximum number of live member pods to be cached.
+    pub member_pod_cache_capacity: u64,
+
+    /// Time after which an unused member pod is evicted from
+    /// the cache.
+    pub member_pod_cache_tti: Duration,
+}
+
+impl Default for TemplateDrivenAdminPodConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            provisions_container_uri: None,
+            registry_path: None,
+            member_pod_cache_capacity: 1024,
+            member_pod_cache_tti: Duration::from_secs(30 * 60),
+        }
+    }
+}
 
-/// An implementation of [`AdminPod`] that manages 
+/// An implementation of [`AdminPod`] that manages
 /// member pods based on a configured template.
-#[derive(Debug, Clone)]
 pub struct TemplateDrivenAdminPod<Inner, MTemplate: PodTemplate> {
     /// Inner pod.
     inner: Inner,
-    
+
     /// Member template.
-    member_template: MTemplate,
+    member_template: Arc<MTemplate>,
+
+    /// Uri of the provisions container.
+    provisions_container_uri: SolidResourceUri,
 
     /// Provisioned member keys.
-    provisioned_member_keys: Arc<DashSet<MTemplate::PodKey>>,
+    provisioned_member_keys: Arc<ProvisionedKeysRegistry<MTemplate::PodKey>>,
+
+    /// Cache of live member pods.
+    member_pod_cache: Cache<MTemplate::PodKey, Arc<MTemplate::RenderedPod>>,
+
+    /// Locker to serialize provisioning of each member pod,
+    /// keyed by member pod key.
+    provisioning_locker: Arc<InmemNameLocker<String>>,
+}
+
+impl<Inner: Clone, MTemplate: PodTemplate> Clone for TemplateDrivenAdminPod<Inner, MTemplate> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            member_template: self.member_template.clone(),
+            provisions_container_uri: self.provisions_container_uri.clone(),
+            provisioned_member_keys: self.provisioned_member_keys.clone(),
+            member_pod_cache: self.member_pod_cache.clone(),
+            provisioning_locker: self.provisioning_locker.clone(),
+        }
+    }
+}
+
+impl<Inner: Debug, MTemplate: PodTemplate> Debug for TemplateDrivenAdminPod<Inner, MTemplate> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TemplateDrivenAdminPod")
+            .field("inner", &self.inner)
+            .field("member_template", &self.member_template)
+            .field("provisions_container_uri", &self.provisions_container_uri)
+            .finish()
+    }
+}
+
+impl<Inner: Pod, MTemplate: PodTemplate> TemplateDrivenAdminPod<Inner, MTemplate> {
+    /// Create a new [`TemplateDrivenAdminPod`] with given
+    /// inner pod, member template, and config.
+    ///
+    /// Persisted provisioned keys are loaded on
+    /// initialization of the pod.
+    pub fn new(
+        inner: Inner,
+        member_template: MTemplate,
+        config: TemplateDrivenAdminPodConfig,
+    ) -> Self {
+        let provisions_container_uri = config
+            .provisions_container_uri
+            .unwrap_or_else(|| inner.id().clone());
+
+        Self {
+            inner,
+            member_template: Arc::new(member_template),
+            provisions_container_uri,
+            provisioned_member_keys: Arc::new(ProvisionedKeysRegistry::new(config.registry_path)),
+            member_pod_cache: Cache::builder()
+                .max_capacity(config.member_pod_cache_capacity)
+                .time_to_idle(config.member_pod_cache_tti)
+                .build(),
+            provisioning_locker: Default::default(),
+        }
+    }
+
+    /// Get the inner pod.
+    #[inline]
+    pub fn inner(&self) -> &Inner {
+        &self.inner
+    }
+
+    /// Get the member template.
+    #[inline]
+    pub fn member_template(&self) -> &Arc<MTemplate> {
+        &self.member_template
+    }
+
+    /// Resolve the live provisioned member pod with given
+    /// key, from cache, or by rendering it.
+    fn resolve_live_member_pod(
+        &self,
+        key: MTemplate::PodKey,
+    ) -> ProbFuture<'static, Arc<MTemplate::RenderedPod>> {
+        let member_template = self.member_template.clone();
+        let member_pod_cache = self.member_pod_cache.clone();
+
+        Box::pin(async move {
+            // Concurrent resolutions of an uncached pod share a
+            // single rendition.
+            member_pod_cache
+                .try_get_with(key.clone(), async move {
+                    member_template
+                        .render(&key)
+                        .await
+                        .map(Arc::new)
+                        .map_err(|e| {
+                            error!("Error in rendering member pod. Error:\n {}", e);
+                            e
+                        })
+                })
+                .await
+                .map_err(unshare_problem)
+        })
+    }
+
+    /// Provision the member pod with given key, while
+    /// holding the lock on the key.
+    async fn provision_member_pod_locked(
+        member_template: Arc<MTemplate>,
+        provisioned_member_keys: Arc<ProvisionedKeysRegistry<MTemplate::PodKey>>,
+        member_pod_cache: Cache<MTemplate::PodKey, Arc<MTemplate::RenderedPod>>,
+        member_pod_key: String,
+    ) -> Result<Arc<MTemplate::RenderedPod>, Problem> {
+        let key = Self::resolve_provisionable_key(
+            &member_template,
+            &provisioned_member_keys,
+            &member_pod_key,
+        )?;
+
+        let pod = Arc::new(member_template.render(&key).await.map_err(|e| {
+            error!("Error in rendering member pod. Error:\n {}", e);
+            e
+        })?);
+
+        pod.initialize().await.map_err(|e| {
+            error!("Error in initializing member pod. Error:\n {}", e);
+            e
+        })?;
+
+        // Registry rolls back failed records, hence pod
+        // stays unprovisioned on error, and is
+        // re-initialized on next provisioning attempt.
+        provisioned_member_keys
+            .record(key.clone())
+            .await
+            .map_err(|e| {
+                error!("Error in recording provisioned key. Error:\n {}", e);
+                UNKNOWN_IO_ERROR
+                    .new_problem_builder()
+                    .message("Error in recording provisioned key.")
+                    .source(e)
+                    .finish()
+            })?;
+
+        member_pod_cache.insert(key, pod.clone()).await;
+
+        info!("Provisioned member pod {}", pod.id().as_str());
+        Ok(pod)
+    }
+
+    /// Parse and check given member pod key for being
+    /// provisionable.
+    #[allow(clippy::result_large_err)]
+    fn resolve_provisionable_key(
+        member_template: &MTemplate,
+        provisioned_member_keys: &ProvisionedKeysRegistry<MTemplate::PodKey>,
+        member_pod_key: &str,
+    ) -> Result<MTemplate::PodKey, Problem> {
+        let key = member_template
+            .parse_pod_key(member_pod_key)
+            .ok_or_else(|| {
+                error!("Invalid member pod key: {}", member_pod_key);
+                INVALID_MEMBER_POD_KEY.new_problem()
+            })?;
+
+        if provisioned_member_keys.contains(&key) {
+            error!("Member pod is already provisioned: {}", member_pod_key);
+            return Err(MEMBER_POD_ALREADY_PROVISIONED.new_problem());
+        }
+
+        Ok(key)
+    }
+}
+
+/// Resolve an owned problem from given shared problem,
+/// preserving it's type, title, message and extensions. Shared
+/// problem is set as the source of the resolved problem.
+fn unshare_problem(problem: Arc<Problem>) -> Problem {
+    Arc::try_unwrap(problem).unwrap_or_else(|problem| {
+        let mut builder = Problem::builder();
+        if let Some(type_url) = problem.type_url() {
+            builder = builder.type_url(type_url);
+        }
+        if let Some(title) = problem.title() {
+            builder = builder.title(title);
+        }
+        if let Some(message) = problem.message() {
+            builder = builder.message(message);
+        }
+        let extensions = problem.extensions().clone();
+        builder
+            .with_extensions(|_| extensions)
+            .source(problem)
+            .finish()
+    })
+}
+
+impl<Inner: Pod, MTemplate: PodTemplate> Pod for TemplateDrivenAdminPod<Inner, MTemplate> {
+    type Storage = Inner::Storage;
+
+    #[inline]
+    fn storage(&self) -> &Arc<Self::Storage> {
+        self.inner.storage()
+    }
+
+    fn initialize(&self) -> BoxFuture<'static, Result<(), Problem>> {
+        let inner_init_fut = self.inner.initialize();
+        let member_template = self.member_template.clone();
+        let provisioned_member_keys = self.provisioned_member_keys.clone();
+
+        Box::pin(async move {
+            inner_init_fut.await?;
+
+            provisioned_member_keys
+                .load(|key_str| member_template.parse_pod_key(key_str))
+                .await
+                .map_err(|e| {
+                    error!("Error in loading provisioned keys registry. Error:\n {}", e);
+                    UNKNOWN_IO_ERROR
+                        .new_problem_builder()
+                        .message("Error in loading provisioned keys registry.")
+                        .source(e)
+                        .finish()
+                })
+        })
+    }
+}
+
+impl<Inner: Pod, MTemplate: PodTemplate> AdminPod for TemplateDrivenAdminPod<Inner, MTemplate> {
+    type MemberPod = MTemplate::RenderedPod;
+
+    #[inline]
+    fn has_in_members_uri_ns(&self, uri: &SolidResourceUri) -> bool {
+        self.member_template.has_in_uri_ns(uri)
+    }
+
+    fn resolve_target_member_pod(
+        &self,
+        req_target: &SolidResourceUri,
+    ) -> BoxFuture<'static, Result<Arc<Self::MemberPod>, Problem>> {
+        let Some(key) = self.member_template.resolve_target_pod_key(req_target) else {
+            return Box::pin(futures::future::ready(Err(
+                TARGET_NOT_IN_NAMESPACE.new_problem()
+            )));
+        };
+
+        if !self.provisioned_member_keys.contains(&key) {
+            return Box::pin(futures::future::ready(Err(
+                TARGET_IN_UNPROVISIONED_POD_NAMESPACE.new_problem(),
+            )));
+        }
+
+        self.resolve_live_member_pod(key)
+    }
+
+    fn get_member_pod(
+        &self,
+        member_pod_id: &SolidResourceUri,
+    ) -> BoxFuture<'static, Result<Arc<Self::MemberPod>, Problem>> {
+        let Some(key) = self.member_template.resolve_target_pod_key(member_pod_id) else {
+            return Box::pin(futures::future::ready(Err(POD_NOT_IN_SET.new_problem())));
+        };
+
+        if !self.provisioned_member_keys.contains(&key) {
+            return Box::pin(futures::future::ready(Err(
+                POD_IS_UNPROVISIONED.new_problem()
+            )));
+        }
+
+        let member_pod_id = member_pod_id.clone();
+        Box::pin(
+            self.resolve_live_member_pod(key)
+                .and_then(|pod| async move {
+                    // Given id must be the pod id, not just any uri
+                    // in it's namespace.
+                    if pod.id() == &member_pod_id {
+                        Ok(pod)
+                    } else {
+                        Err(POD_NOT_IN_SET.new_problem())
+                    }
+                }),
+        )
+    }
+
+    #[inline]
+    fn provisions_container_uri(&self) -> &SolidResourceUri {
+        &self.provisions_container_uri
+    }
+
+    #[inline]
+    fn check_provisionable(&self, member_pod_key: &str) -> Result<(), Problem> {
+        Self::resolve_provisionable_key(
+            &self.member_template,
+            &self.provisioned_member_keys,
+            member_pod_key,
+        )
+        .map(|_| ())
+    }
+
+    fn provision_member_pod(
+        &self,
+        member_pod_key: &str,
+    ) -> ProbFuture<'static, Arc<Self::MemberPod>> {
+        let member_template = self.member_template.clone();
+        let provisioned_member_keys = self.provisioned_member_keys.clone();
+        let member_pod_cache = self.member_pod_cache.clone();
+        let provisioning_locker = self.provisioning_locker.clone();
+        let member_pod_key = member_pod_key.to_owned();
+
+        let Some(key) = member_template.parse_pod_key(&member_pod_key) else {
+            error!("Invalid member pod key: {}", member_pod_key);
+            return Box::pin(futures::future::ready(Err(
+                INVALID_MEMBER_POD_KEY.new_problem()
+            )));
+        };
+        let lock_name = Borrow::<str>::borrow(&key).to_owned();
+
+        Box::pin(async move {
+            // Serialize provisioning of the pod, so that no
+            // two provisioning of same pod race. Provisioning
+            // of other pods proceeds concurrently.
+            provisioning_locker
+                .poll_with_lock(
+                    Self::provision_member_pod_locked(
+                        member_template,
+                        provisioned_member_keys,
+                        member_pod_cache,
+                        member_pod_key,
+                    ),
+                    Some(lock_name),
+                    LockKind::Exclusive,
+                )
+                .await
+                .unwrap_or_else(|e| {
+                    error!("Error in locking member pod key. Error:\n {}", e);
+                    Err(INTERNAL_ERROR
+                        .new_problem_builder()
+                        .message("Error in locking member pod key.")
+                        .source(e)
+                        .finish())
+                })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unshared_problem_preserves_extensions_and_source() {
+        let problem = Arc::new(
+            UNKNOWN_IO_ERROR
+                .new_problem_builder()
+                .message("Shared problem.")
+                .extension(7u8)
+                .finish(),
+        );
+        let _shared = problem.clone();
 
-    // provisioned_pod_cache: 
+        let unshared = unshare_problem(problem);
+        assert!(UNKNOWN_IO_ERROR.is_type_of(&unshared));
+        assert_eq!(unshared.message(), Some("Shared problem."));
+        assert_eq!(unshared.extensions().get::<u8>(), Some(&7));
+        assert!(std::error::Error::source(&unshared).is_some());
+    }
 }
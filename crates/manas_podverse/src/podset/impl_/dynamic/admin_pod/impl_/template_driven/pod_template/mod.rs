@@ -24,6 +24,10 @@ pub trait PodTemplate: Debug + Send + Sync + 'static {
     /// Resolve target pod key.
     fn resolve_target_pod_key(&self, req_target: &SolidResourceUri) -> Option<Self::PodKey>;
 
+    /// Parse pod key from given string. Returns `None`, if
+    /// given string is not a valid pod key.
+    fn parse_pod_key(&self, key_str: &str) -> Option<Self::PodKey>;
+
     /// Try to render the pod corresponding to given pod key.
     fn render(&self, key: &Self::PodKey) -> ProbFuture<'static, Self::RenderedPod>;
 }
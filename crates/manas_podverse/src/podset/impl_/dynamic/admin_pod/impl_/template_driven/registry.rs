@@ -0,0 +1,154 @@
+//! I define a registry of provisioned member pod keys.
+//!
+
+use std::{borrow::Borrow, hash::Hash, io, path::PathBuf};
+
+use dashmap::DashSet;
+use futures::lock::Mutex;
+use tokio::io::AsyncWriteExt;
+use tracing::warn;
+
+/// A registry of provisioned member pod keys.
+///
+/// If a file path is configured, registry is persisted to
+/// that file with one key per line, so that it survives
+/// restarts.
+#[derive(Debug)]
+pub struct ProvisionedKeysRegistry<PodKey: Eq + Hash> {
+    /// Path of the registry file.
+    path: Option<PathBuf>,
+
+    /// Provisioned keys.
+    keys: DashSet<PodKey>,
+
+    /// Lock to serialize writes to the registry file.
+    write_lock: Mutex<()>,
+}
+
+impl<PodKey> ProvisionedKeysRegistry<PodKey>
+where
+    PodKey: Eq + Hash + Clone + Borrow<str>,
+{
+    /// Create a new empty [`ProvisionedKeysRegistry`], that
+    /// persists to given optional file path.
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            keys: DashSet::new(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Load the persisted keys, parsing them with given
+    /// parser. Invalid keys are skipped.
+    pub async fn load<F>(&self, parse: F) -> io::Result<()>
+    where
+        F: Fn(&str) -> Option<PodKey>,
+    {
+        let Some(path) = self.path.as_ref() else {
+            return Ok(());
+        };
+
+        let content = match tokio::fs::read_to_string(path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        for line in content.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            match parse(line) {
+                Some(key) => {
+                    self.keys.insert(key);
+                }
+                None => warn!("Skipping invalid provisioned key: {}", line),
+            }
+        }
+        Ok(())
+    }
+
+    /// Check if given key is provisioned.
+    #[inline]
+    pub fn contains(&self, key: &PodKey) -> bool {
+        self.keys.contains::<PodKey>(key)
+    }
+
+    /// Record given key as provisioned. Returns `false`, if
+    /// it is already recorded.
+    ///
+    /// If persisting the key fails, registry file is
+    /// truncated back to it's previous length, so that key is
+    /// recorded neither in memory, nor in the file.
+    pub async fn record(&self, key: PodKey) -> io::Result<bool> {
+        let _guard = self.write_lock.lock().await;
+
+        if self.keys.contains::<PodKey>(&key) {
+            return Ok(false);
+        }
+
+        if let Some(path) = self.path.as_ref() {
+            let mut file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .await?;
+            let prev_len = file.metadata().await?.len();
+
+            let write_result = async {
+                file.write_all(format!("{}\n", key.borrow()).as_bytes())
+                    .await?;
+                file.sync_data().await
+            }
+            .await;
+
+            if let Err(e) = write_result {
+                if let Err(truncate_err) = file.set_len(prev_len).await {
+                    warn!(
+                        "Error in rolling back partial registry write. Error:\n {}",
+                        truncate_err
+                    );
+                }
+                return Err(e);
+            }
+        }
+
+        self.keys.insert(key);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn registry_survives_reload() {
+        let dir = tempfile::tempdir().expect("Must create temp dir.");
+        let path = dir.path().join("registry");
+
+        let registry = ProvisionedKeysRegistry::<String>::new(Some(path.clone()));
+        assert!(registry.record("alice".to_owned()).await.unwrap());
+        assert!(registry.record("bob".to_owned()).await.unwrap());
+        assert!(!registry.record("alice".to_owned()).await.unwrap());
+
+        let reloaded = ProvisionedKeysRegistry::<String>::new(Some(path));
+        reloaded
+            .load(|s| (s != "bob").then(|| s.to_owned()))
+            .await
+            .unwrap();
+
+        assert!(reloaded.contains(&"alice".to_owned()));
+        assert!(!reloaded.contains(&"bob".to_owned()));
+        assert!(!reloaded.contains(&"carol".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn failed_record_is_not_recorded() {
+        let dir = tempfile::tempdir().expect("Must create temp dir.");
+        // Registry path is a directory, and hence can't be
+        // written to.
+        let registry = ProvisionedKeysRegistry::<String>::new(Some(dir.path().to_owned()));
+
+        assert!(registry.record("alice".to_owned()).await.is_err());
+        assert!(!registry.contains(&"alice".to_owned()));
+    }
+}
@@ -1,15 +1,16 @@
-//! i provide trait and few implementation helpers for admin pods.
+//! I provide trait and few implementation helpers for admin pods.
 //!
 
 use std::sync::Arc;
 
-use dyn_problem::Problem;
+use dyn_problem::{define_anon_problem_types, ProbFuture, Problem};
 use futures::future::BoxFuture;
 use manas_space::resource::uri::SolidResourceUri;
 
 use crate::pod::Pod;
 
 pub mod impl_;
+pub mod service;
 
 /// A trait for admin pods.
 /// Admin pods along with satisfying generic pod interface,
@@ -36,9 +37,9 @@ pub trait AdminPod: Pod {
     /// ## Errors:
     /// Should return following problems on specified cases.
     ///
-    /// - [`TARGET_NOT_IN_NAMESPACE`], if request target is not in namespace of the member pods.
+    /// - [`TARGET_NOT_IN_NAMESPACE`](crate::podset::TARGET_NOT_IN_NAMESPACE), if request target is not in namespace of the member pods.
     ///
-    /// - [`TARGET_IN_UNPROVISIONED_POD_NAMESPACE`], If request target
+    /// - [`TARGET_IN_UNPROVISIONED_POD_NAMESPACE`](crate::podset::TARGET_IN_UNPROVISIONED_POD_NAMESPACE), If request target
     /// is in namespace of an unprovisioned member pod.
     fn resolve_target_member_pod(
         &self,
@@ -46,8 +47,49 @@ pub trait AdminPod: Pod {
     ) -> BoxFuture<'static, Result<Arc<Self::MemberPod>, Problem>>;
 
     /// Get the member pod with given id.
+    ///
+    /// ## Errors:
+    /// Should return following problems on specified cases.
+    ///
+    /// - [`POD_NOT_IN_SET`](crate::podset::POD_NOT_IN_SET), If pod with given id is not a member pod.
+    ///
+    /// - [`POD_IS_UNPROVISIONED`](crate::podset::POD_IS_UNPROVISIONED), If member pod with given id is not yet provisioned.
     fn get_member_pod(
         &self,
         member_pod_id: &SolidResourceUri,
     ) -> BoxFuture<'static, Result<Arc<Self::MemberPod>, Problem>>;
+
+    /// Get uri of the provisions container.
+    fn provisions_container_uri(&self) -> &SolidResourceUri;
+
+    /// Check if a member pod with given key can be
+    /// provisioned.
+    ///
+    /// ## Errors:
+    /// Should return following problems on specified cases.
+    ///
+    /// - [`INVALID_MEMBER_POD_KEY`], If given key is not a valid member pod key.
+    ///
+    /// - [`MEMBER_POD_ALREADY_PROVISIONED`], If member pod with given key is already provisioned.
+    #[allow(clippy::result_large_err)]
+    fn check_provisionable(&self, member_pod_key: &str) -> Result<(), Problem>;
+
+    /// Provision the member pod with given key.
+    ///
+    /// ## Errors:
+    /// Should return problems as specified for
+    /// [`check_provisionable`](Self::check_provisionable) on
+    /// respective cases.
+    fn provision_member_pod(
+        &self,
+        member_pod_key: &str,
+    ) -> ProbFuture<'static, Arc<Self::MemberPod>>;
 }
+
+define_anon_problem_types!(
+    /// Invalid member pod key.
+    INVALID_MEMBER_POD_KEY: ("Invalid member pod key.");
+
+    /// Member pod already provisioned.
+    MEMBER_POD_ALREADY_PROVISIONED: ("Member pod already provisioned.");
+);
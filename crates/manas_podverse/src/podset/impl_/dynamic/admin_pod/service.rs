@@ -0,0 +1,339 @@
+//! I define an implementation of [`PodService`] for admin
+//! pods, that executes provisioning of member pods on
+//! posting provision resources to the provisions container.
+//!
+
+use std::{convert::Infallible, sync::Arc, task::Poll};
+
+use dyn_problem::{ProbFuture, Problem};
+use headers::HeaderMapExt;
+use http::{header::LOCATION, Extensions, HeaderValue, Method, Request, Response, StatusCode};
+use http_api_problem::ApiError;
+use http_uri::invariant::AbsoluteHttpUri;
+use manas_http::{
+    body::Body,
+    header::slug::Slug,
+    problem::ApiErrorExt,
+    service::{namespaced::NamespacedHttpService, BoxHttpResponseFuture},
+};
+use manas_space::resource::uri::SolidResourceUri;
+use name_locker::{impl_::InmemNameLocker, LockKind, NameLocker};
+use tower::{Service, ServiceExt};
+use tracing::{error, info, warn};
+
+use super::{AdminPod, INVALID_MEMBER_POD_KEY, MEMBER_POD_ALREADY_PROVISIONED};
+use crate::pod::service::{PodService, PodServiceFactory};
+
+/// An implementation of [`PodService`] for admin pods.
+///
+/// On a `POST` request to the provisions container, it
+/// provisions the member pod with key given in the `Slug`
+/// header, after the inner service creates the provision
+/// resource. Thus provisioning is subjected to the access
+/// control of the provisions container. All other requests
+/// are delegated to the inner service.
+///
+/// Provisioning of a member pod is serialized under a lock
+/// on it's key. If provisioning fails after the provision
+/// resource is created, the provision resource is deleted,
+/// so that no provision resource is recorded for an
+/// unprovisioned pod.
+#[derive(Clone)]
+pub struct AdminPodService<Inner> {
+    inner: Inner,
+    provisioning_locker: Arc<InmemNameLocker<String>>,
+}
+
+impl<Inner: std::fmt::Debug> std::fmt::Debug for AdminPodService<Inner> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdminPodService")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<Inner> AdminPodService<Inner>
+where
+    Inner: PodService + Clone + Send,
+    Inner::Pod: AdminPod,
+{
+    /// Create a new [`AdminPodService`] with given inner
+    /// service.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self::new_with_locker(inner, Default::default())
+    }
+
+    /// Create a new [`AdminPodService`] with given inner
+    /// service, and locker to serialize provisioning with.
+    #[inline]
+    pub fn new_with_locker(
+        inner: Inner,
+        provisioning_locker: Arc<InmemNameLocker<String>>,
+    ) -> Self {
+        Self {
+            inner,
+            provisioning_locker,
+        }
+    }
+
+    /// Check if given request is a provision request.
+    fn is_provision_request(&self, req: &Request<Body>) -> bool {
+        req.method() == Method::POST
+            && req
+                .extensions()
+                .get::<SolidResourceUri>()
+                .map_or(false, |uri| {
+                    uri == self.inner.pod().provisions_container_uri()
+                })
+    }
+
+    /// Handle the provision request.
+    async fn apply_provision(
+        inner: Inner,
+        provisioning_locker: Arc<InmemNameLocker<String>>,
+        req: Request<Body>,
+    ) -> Result<Response<Body>, ApiError> {
+        let member_pod_key = req
+            .headers()
+            .typed_get::<Slug>()
+            .ok_or_else(|| {
+                error!("Provision request doesn't specify member pod key.");
+                ApiError::builder(StatusCode::BAD_REQUEST)
+                    .message("Provision requests must specify member pod key in Slug header.")
+                    .finish()
+            })?
+            .to_string();
+
+        // Serialize provisioning of the member pod, so that
+        // concurrent requests for same key cannot race
+        // between the check and the provisioning.
+        provisioning_locker
+            .poll_with_lock(
+                Self::apply_provision_locked(inner, member_pod_key.clone(), req),
+                Some(member_pod_key),
+                LockKind::Exclusive,
+            )
+            .await
+            .unwrap_or_else(|e| {
+                error!("Error in locking member pod key. Error:\n {}", e);
+                Err(ApiError::builder(StatusCode::SERVICE_UNAVAILABLE).finish())
+            })
+    }
+
+    /// Handle the provision request, while holding the lock
+    /// on member pod key.
+    async fn apply_provision_locked(
+        inner: Inner,
+        member_pod_key: String,
+        req: Request<Body>,
+    ) -> Result<Response<Body>, ApiError> {
+        let admin_pod = inner.pod().clone();
+
+        // Check early, to avoid creating provision resources
+        // for unprovisionable pods.
+        admin_pod
+            .check_provisionable(&member_pod_key)
+            .map_err(Self::map_provision_problem)?;
+
+        let req_extensions = req.extensions().clone();
+
+        let resp = inner
+            .clone()
+            .oneshot(req)
+            .await
+            .expect("Must be infallible.");
+        if resp.status() != StatusCode::CREATED {
+            error!(
+                "Provision resource is not created. Status: {}",
+                resp.status()
+            );
+            return Ok(resp);
+        }
+
+        if let Err(problem) = admin_pod.provision_member_pod(&member_pod_key).await {
+            let location = resp.headers().get(LOCATION).cloned();
+            Self::rollback_provision_resource(inner, location, req_extensions).await;
+            return Err(Self::map_provision_problem(problem));
+        }
+
+        info!("Provisioned member pod with key {}", member_pod_key);
+        Ok(resp)
+    }
+
+    /// Delete the provision resource at given location, on
+    /// behalf of the provision request with given
+    /// extensions.
+    async fn rollback_provision_resource(
+        inner: Inner,
+        location: Option<HeaderValue>,
+        mut req_extensions: Extensions,
+    ) {
+        let Some((res_uri, abs_res_uri)) = location
+            .as_ref()
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| {
+                Some((
+                    SolidResourceUri::try_new_from(v).ok()?,
+                    AbsoluteHttpUri::try_new_from(v).ok()?,
+                ))
+            })
+        else {
+            warn!("Cannot resolve the provision resource to roll back.");
+            return;
+        };
+
+        let mut req = Request::builder()
+            .method(Method::DELETE)
+            .uri(res_uri.as_str())
+            .body(Body::empty())
+            .expect("Must be valid.");
+
+        req_extensions.insert(res_uri);
+        req_extensions.insert(abs_res_uri);
+        *req.extensions_mut() = req_extensions;
+
+        let resp = inner.oneshot(req).await.expect("Must be infallible.");
+        if resp.status().is_success() {
+            info!("Rolled back the provision resource.");
+        } else {
+            warn!(
+                "Error in rolling back the provision resource. Status: {}",
+                resp.status()
+            );
+        }
+    }
+
+    /// Map provisioning problem to api error.
+    fn map_provision_problem(problem: Problem) -> ApiError {
+        error!("Error in provisioning member pod. Error:\n {}", problem);
+        if INVALID_MEMBER_POD_KEY.is_type_of(&problem) {
+            ApiError::builder(StatusCode::BAD_REQUEST)
+                .message("Invalid member pod key.")
+                .finish()
+        } else if MEMBER_POD_ALREADY_PROVISIONED.is_type_of(&problem) {
+            ApiError::builder(StatusCode::CONFLICT)
+                .message("Member pod with given key is already provisioned.")
+                .finish()
+        } else {
+            ApiError::builder(StatusCode::INTERNAL_SERVER_ERROR).finish()
+        }
+    }
+}
+
+impl<Inner> Service<Request<Body>> for AdminPodService<Inner>
+where
+    Inner: PodService + Clone + Send,
+    Inner::Pod: AdminPod,
+{
+    type Response = Response<Body>;
+
+    type Error = Infallible;
+
+    type Future = BoxHttpResponseFuture<Body>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Service::<Request<Body>>::poll_ready(&mut self.inner, cx)
+    }
+
+    #[tracing::instrument(skip_all, name = "AdminPodService::call")]
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        if !self.is_provision_request(&req) {
+            return self.inner.call(req);
+        }
+
+        let inner = self.inner.clone();
+        let provisioning_locker = self.provisioning_locker.clone();
+        Box::pin(async move {
+            Ok(Self::apply_provision(inner, provisioning_locker, req)
+                .await
+                .unwrap_or_else(|e| e.into_http_response()))
+        })
+    }
+}
+
+impl<Inner> NamespacedHttpService<Body, Body> for AdminPodService<Inner>
+where
+    Inner: PodService + Clone + Send,
+    Inner::Pod: AdminPod,
+{
+    #[inline]
+    fn has_in_uri_ns(&self, uri: &SolidResourceUri) -> bool {
+        self.inner.has_in_uri_ns(uri)
+    }
+}
+
+impl<Inner> PodService for AdminPodService<Inner>
+where
+    Inner: PodService + Clone + Send,
+    Inner::Pod: AdminPod,
+{
+    type Pod = Inner::Pod;
+
+    #[inline]
+    fn pod(&self) -> &Arc<Self::Pod> {
+        self.inner.pod()
+    }
+}
+
+impl<Inner> Service<()> for AdminPodService<Inner>
+where
+    Inner: PodService,
+{
+    type Response = bool;
+
+    type Error = Problem;
+
+    type Future = ProbFuture<'static, bool>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Service::<()>::poll_ready(&mut self.inner, cx)
+    }
+
+    #[inline]
+    #[tracing::instrument(skip_all, name = "AdminPodService::call")]
+    fn call(&mut self, req: ()) -> Self::Future {
+        self.inner.call(req)
+    }
+}
+
+/// A [`AdminPodServiceFactory`] resolves a [`AdminPodService`]
+/// for each admin pod.
+#[derive(Clone)]
+pub struct AdminPodServiceFactory<InnerFactory> {
+    /// Inner factory.
+    pub inner_factory: Arc<InnerFactory>,
+
+    /// Locker to serialize provisioning of member pods with,
+    /// across resolved services.
+    pub provisioning_locker: Arc<InmemNameLocker<String>>,
+}
+
+impl<InnerFactory: std::fmt::Debug> std::fmt::Debug for AdminPodServiceFactory<InnerFactory> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdminPodServiceFactory")
+            .field("inner_factory", &self.inner_factory)
+            .finish()
+    }
+}
+
+impl<InnerFactory> PodServiceFactory for AdminPodServiceFactory<InnerFactory>
+where
+    InnerFactory: PodServiceFactory,
+    InnerFactory::Service: Clone + Send,
+    InnerFactory::Pod: AdminPod,
+    <InnerFactory::Service as PodService>::Pod: AdminPod,
+{
+    type Pod = InnerFactory::Pod;
+    type Service = AdminPodService<InnerFactory::Service>;
+
+    #[inline]
+    fn new_service(&self, pod: Arc<InnerFactory::Pod>) -> Self::Service {
+        AdminPodService::new_with_locker(
+            self.inner_factory.new_service(pod),
+            self.provisioning_locker.clone(),
+        )
+    }
+}
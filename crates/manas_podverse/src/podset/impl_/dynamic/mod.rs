@@ -9,7 +9,7 @@ use futures::{future::BoxFuture, TryFutureExt};
 use manas_space::resource::uri::SolidResourceUri;
 use tracing::error;
 
-use crate::{pod::Pod, podset::PodSet};
+use crate::podset::PodSet;
 
 use self::admin_pod::AdminPod;
 
@@ -17,6 +17,12 @@ pub mod admin_pod;
 
 /// An implementation of [`PodSet`] that is dynamic
 /// over it's member pods.
+///
+/// Member pods are managed by the admin pod. The admin pod
+/// itself is not a member of the set, and should be served
+/// separately, for example with an
+/// [`AdminPodService`](admin_pod::service::AdminPodService)
+/// overriding the podset service.
 #[derive(Debug, Clone)]
 pub struct DynamicPodSet<AdmPod> {
     /// Admin pod.
@@ -29,6 +35,12 @@ impl<AdmPod: AdminPod> DynamicPodSet<AdmPod> {
     pub fn new(admin_pod: Arc<AdmPod>) -> Self {
         Self { admin_pod }
     }
+
+    /// Get the admin pod.
+    #[inline]
+    pub fn admin_pod(&self) -> &Arc<AdmPod> {
+        &self.admin_pod
+    }
 }
 
 impl<AdmPod: AdminPod> PodSet for DynamicPodSet<AdmPod> {
@@ -40,21 +52,24 @@ impl<AdmPod: AdminPod> PodSet for DynamicPodSet<AdmPod> {
         }))
     }
 
+    #[inline]
     fn has_in_uri_ns(&self, uri: &SolidResourceUri) -> bool {
-        todo!()
+        self.admin_pod.has_in_members_uri_ns(uri)
     }
 
+    #[inline]
     fn resolve_target_pod(
         &self,
         req_target: &SolidResourceUri,
     ) -> BoxFuture<'static, Result<Arc<Self::Pod>, Problem>> {
-        todo!()
+        self.admin_pod.resolve_target_member_pod(req_target)
     }
 
+    #[inline]
     fn get_pod(
         &self,
         pod_id: &SolidResourceUri,
     ) -> BoxFuture<'static, Result<Arc<Self::Pod>, Problem>> {
-        todo!()
+        self.admin_pod.get_member_pod(pod_id)
     }
 }
@@ -1,5 +1,5 @@
 //! I define few implementations of [`PodSet`](super::PodSet).
 //!
 
-// pub mod dynamic;
+pub mod dynamic;
 pub mod static_;
@@ -36,6 +36,9 @@ define_anon_problem_types!(
     /// Payload too large.
     PAYLOAD_TOO_LARGE: ("Payload too large.");
 
+    /// Storage quota exceeded.
+    QUOTA_EXCEEDED: ("Storage quota exceeded.");
+
     /// Custom constrain violation.
     CUSTOM_CONSTRAIN_VIOLATION: ("Custom constrain violation.");
 );
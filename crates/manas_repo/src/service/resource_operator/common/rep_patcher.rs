@@ -25,4 +25,7 @@ define_anon_problem_types!(
 
     /// Patch semantics error.
     PATCH_SEMANTICS_ERROR: ("Patch semantics error.");
+
+    /// Patch conflicts with the target resource state.
+    PATCH_CONFLICT: ("Patch conflicts with the target resource state.");
 );
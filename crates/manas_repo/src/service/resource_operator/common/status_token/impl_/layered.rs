@@ -3,8 +3,10 @@
 
 use std::{fmt::Debug, marker::PhantomData, sync::Arc};
 
-use manas_http::representation::metadata::RepresentationMetadata;
-use manas_space::resource::{slot::SolidResourceSlot, uri::SolidResourceUri};
+use manas_http::{
+    header::common::media_type::MediaType, representation::metadata::RepresentationMetadata,
+};
+use manas_space::resource::{slot::SolidResourceSlot, slot_link::AuxLink, uri::SolidResourceUri};
 
 use crate::{
     layer::LayeredRepo,
@@ -80,6 +82,21 @@ where
     fn rep_validators(&self) -> RepresentationMetadata {
         self.inner.rep_validators()
     }
+
+    #[inline]
+    fn aux_links_index(&self) -> Vec<AuxLink<LR::StSpace>> {
+        self.inner.aux_links_index()
+    }
+
+    #[inline]
+    fn rep_content_length(&self) -> Option<u64> {
+        self.inner.rep_content_length()
+    }
+
+    #[inline]
+    fn rep_content_type(&self) -> Option<MediaType> {
+        self.inner.rep_content_type()
+    }
 }
 
 impl<T, LR> ExistingNonRepresentedResourceToken for Layered<T, LR>
@@ -5,8 +5,10 @@
 
 use std::{fmt::Debug, sync::Arc};
 
-use manas_http::representation::metadata::RepresentationMetadata;
-use manas_space::resource::{slot::SolidResourceSlot, uri::SolidResourceUri};
+use manas_http::{
+    header::common::media_type::MediaType, representation::metadata::RepresentationMetadata,
+};
+use manas_space::resource::{slot::SolidResourceSlot, slot_link::AuxLink, uri::SolidResourceUri};
 
 use crate::Repo;
 
@@ -36,6 +38,31 @@ pub trait ExistingRepresentedResourceToken: RepoResourceStatusTokenBase {
 
     /// Get representation validators.
     fn rep_validators(&self) -> RepresentationMetadata;
+
+    /// Get links to the supported auxiliary resources of the
+    /// resource.
+    ///
+    /// Default implementation returns an empty index. Tokens
+    /// of repos that support auxiliary resources should
+    /// override it.
+    #[inline]
+    fn aux_links_index(&self) -> Vec<AuxLink<<Self::Repo as Repo>::StSpace>> {
+        Vec::new()
+    }
+
+    /// Get complete content length of the representation, if
+    /// it can be known without reading the representation.
+    #[inline]
+    fn rep_content_length(&self) -> Option<u64> {
+        None
+    }
+
+    /// Get content-type of the representation, if it can be
+    /// known without reading the representation.
+    #[inline]
+    fn rep_content_type(&self) -> Option<MediaType> {
+        None
+    }
 }
 
 /// A trait to represent non-existing, but mutex existing
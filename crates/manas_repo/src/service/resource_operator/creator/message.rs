@@ -3,6 +3,7 @@
 
 use std::sync::Arc;
 
+use manas_authentication::common::credentials::CreatorCredentials;
 use manas_space::{
     resource::{
         kind::SolidResourceKind,
@@ -13,7 +14,7 @@ use manas_space::{
     },
     SpcKnownAuxRelType,
 };
-use typed_record::ClonableTypedRecord;
+use typed_record::{ClonableTypedRecord, TypedRecordKey};
 
 use crate::{
     context::RepoContext,
@@ -194,3 +195,14 @@ impl<R: Repo> ResourceCreateResponse<R> {
         }
     }
 }
+
+/// A [`TypedRecordKey`] for credentials of the agent
+/// creating the resource. It is set in extensions of
+/// resource create requests, so that repos can record the
+/// resource creator.
+#[derive(Clone)]
+pub struct KCreatorCredentials {}
+
+impl TypedRecordKey for KCreatorCredentials {
+    type Value = CreatorCredentials;
+}
@@ -68,6 +68,10 @@ pub mod impl_;
 /// If representation is being created through patch, and if
 /// there is a patch semantics error.
 ///
+/// - [`PATCH_CONFLICT`](super::common::rep_patcher::PATCH_CONFLICT):
+/// If representation is being created through patch, and if
+/// patch conflicts with the existing representation.
+///
 /// - [`PAYLOAD_TOO_LARGE`](super::common::problem::PAYLOAD_TOO_LARGE):
 /// If representation data payload is too large.
 ///
@@ -2,7 +2,7 @@
 //!
 
 use manas_space::resource::{slot::SolidResourceSlot, slot_link::AuxLink};
-use typed_record::ClonableTypedRecord;
+use typed_record::{ClonableTypedRecord, TypedRecordKey};
 
 use crate::{
     layer::LayeredRepo,
@@ -96,3 +96,14 @@ impl<R: Repo> ResourceDeleteResponse<R> {
         }
     }
 }
+
+/// A [`TypedRecordKey`] for marking a resource delete request
+/// as a dry run. It is set in extensions of resource delete
+/// requests, when caller wants to know whether the delete
+/// would be allowed, without deleting anything.
+#[derive(Debug, Clone, Copy)]
+pub struct KDryRunDelete;
+
+impl TypedRecordKey for KDryRunDelete {
+    type Value = ();
+}
@@ -24,6 +24,12 @@ pub mod impl_;
 /// container representation by removing corresponding
 /// containment triple.
 ///
+/// - If request extensions has [`KDryRunDelete`] item, MUST
+/// evaluate preconditions and access control as usual, but
+/// MUST NOT modify any state. Container emptiness check is
+/// skipped in dry runs, as callers dry run deletes of
+/// containers whose contents are yet to be deleted.
+///
 /// ### Errors:
 ///
 /// Service MUST return errors with following problem types in
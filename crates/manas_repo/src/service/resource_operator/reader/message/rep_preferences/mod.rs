@@ -1,11 +1,17 @@
 //! I define types for representation preferences.
 //!
 
+use std::num::NonZeroUsize;
+
 use typed_record::TypedRecordKey;
 
-use self::range_negotiator::{impl_::CompleteRangeNegotiator, DynRangeNegotiator};
+use self::{
+    range_negotiator::{impl_::CompleteRangeNegotiator, DynRangeNegotiator},
+    version::RepVersionPreference,
+};
 
 pub mod range_negotiator;
+pub mod version;
 
 /// A struct representing representation preferences.
 #[derive(Debug, Clone)]
@@ -13,8 +19,16 @@ pub struct RepresentationPreferences {
     /// Representation preference, if resource is a container.
     pub container_rep_preference: ContainerRepresentationPreference,
 
+    /// Page preference, if resource is a container.
+    /// If `None`, complete index will be included.
+    pub container_page_preference: Option<ContainerPagePreference>,
+
     /// Rep range negotiator, if resource is a non-container.
     pub non_container_rep_range_negotiator: Box<DynRangeNegotiator>,
+
+    /// Version preference. Repos that don't version resources
+    /// will ignore it.
+    pub version_preference: RepVersionPreference,
 }
 
 impl RepresentationPreferences {
@@ -24,7 +38,9 @@ impl RepresentationPreferences {
     pub fn new_light() -> Self {
         Self {
             container_rep_preference: ContainerRepresentationPreference::Minimal,
+            container_page_preference: None,
             non_container_rep_range_negotiator: Box::new(CompleteRangeNegotiator),
+            version_preference: RepVersionPreference::Current,
         }
     }
 }
@@ -51,3 +67,39 @@ pub struct KAppliedContainerRepPref {}
 impl TypedRecordKey for KAppliedContainerRepPref {
     type Value = ContainerRepresentationPreference;
 }
+
+/// A struct representing page preference for container
+/// representations.
+///
+/// Pages are delimited in backend listing order of contained
+/// resources. Hence cursors are opaque, and are valid only
+/// for the repo that issued them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContainerPagePreference {
+    /// Cursor of the preferred page. If `None`, first page
+    /// is preferred.
+    pub cursor: Option<String>,
+
+    /// Max number of contained resources per page.
+    pub max_page_size: NonZeroUsize,
+}
+
+/// A struct representing resolved page of a container
+/// representation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContainerRepPage {
+    /// Cursor of the previous page, if any.
+    pub prev_cursor: Option<String>,
+
+    /// Cursor of the next page, if any.
+    pub next_cursor: Option<String>,
+}
+
+/// A [`TypedRecordKey`] for resolved container rep page.
+/// It is set in metadata of paged container representations.
+#[derive(Clone)]
+pub struct KContainerRepPage {}
+
+impl TypedRecordKey for KContainerRepPage {
+    type Value = ContainerRepPage;
+}
@@ -0,0 +1,71 @@
+//! I define types for representation version preferences.
+//!
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use typed_record::TypedRecordKey;
+
+/// Name of the query param, that specifies datetime of a
+/// memento in it's uri, as seconds since unix epoch.
+pub const MEMENTO_QUERY_PARAM: &str = "memento";
+
+/// Query, that identifies timemap of a resource in it's uri.
+pub const TIMEMAP_QUERY: &str = "timemap";
+
+/// An enum representing preference on version of the
+/// representation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RepVersionPreference {
+    /// Current representation.
+    #[default]
+    Current,
+
+    /// Representation of the version that was current at
+    /// given datetime, as per memento datetime negotiation.
+    ///
+    /// If there is no such version, the earliest version is
+    /// preferred.
+    AtDatetime(SystemTime),
+
+    /// Timemap of the resource, that lists all of it's
+    /// mementos.
+    TimeMap,
+}
+
+/// A struct representing resolved version of a
+/// representation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RepVersion {
+    /// Datetime of the memento, if representation is a
+    /// memento, selected with datetime negotiation.
+    pub memento_datetime: Option<SystemTime>,
+}
+
+/// A [`TypedRecordKey`] for resolved rep version. It is set
+/// in metadata of representations of versioned resources.
+#[derive(Clone)]
+pub struct KRepVersion {}
+
+impl TypedRecordKey for KRepVersion {
+    type Value = RepVersion;
+}
+
+/// Get uri of the memento of resource with given uri, at
+/// given datetime.
+pub fn memento_uri(res_uri_str: &str, datetime: SystemTime) -> String {
+    format!(
+        "{}?{}={}",
+        res_uri_str,
+        MEMENTO_QUERY_PARAM,
+        datetime
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default()
+    )
+}
+
+/// Get uri of the timemap of resource with given uri.
+#[inline]
+pub fn timemap_uri(res_uri_str: &str) -> String {
+    format!("{}?{}", res_uri_str, TIMEMAP_QUERY)
+}
@@ -59,6 +59,10 @@ pub mod impl_;
 /// If representation is being updated through patch, and if
 /// there is a patch semantics error.
 ///
+/// - [`PATCH_CONFLICT`](super::common::rep_patcher::PATCH_CONFLICT):
+/// If representation is being updated through patch, and if
+/// patch conflicts with the existing representation.
+///
 /// - [`INVALID_ENCODED_SOURCE_REP`](super::common::rep_patcher::INVALID_ENCODED_SOURCE_REP):
 /// If representation is being updated through patch, and
 /// existing representation is invalid encoded.
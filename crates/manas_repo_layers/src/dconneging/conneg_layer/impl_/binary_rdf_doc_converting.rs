@@ -2,7 +2,7 @@
 //! inner resolved rdf representation.
 //!
 
-use std::{collections::HashSet, marker::PhantomData, ops::Deref, sync::Arc};
+use std::{collections::HashSet, marker::PhantomData, ops::Deref, sync::Arc, time::Duration};
 
 use dyn_problem::{type_::UNKNOWN_IO_ERROR, ProbFuture, ProbResult, Problem};
 use futures::{FutureExt, TryFutureExt};
@@ -14,28 +14,37 @@ use manas_http::{
     },
     representation::{
         impl_::{
-            basic::BasicRepresentation, binary::BinaryRepresentation,
-            common::data::bytes_inmem::BytesInmem,
+            basic::BasicRepresentation,
+            binary::BinaryRepresentation,
+            common::data::{bytes_inmem::BytesInmem, quads_inmem::QuadsInmem},
         },
-        metadata::{KDerivedETag, KLastModified},
+        metadata::{derived_etag::DerivedETag, KDerivedETag, KLastModified},
+        Representation,
     },
 };
 use manas_repo::{
     service::resource_operator::reader::{
-        rep_preferences::range_negotiator::impl_::{
-            DConnegLayeredRangeNegotiator, DContentTypeNegotiator,
+        rep_preferences::{
+            range_negotiator::impl_::{DConnegLayeredRangeNegotiator, DContentTypeNegotiator},
+            KContainerRepPage,
         },
         FlexibleResourceReader, ResourceReadRequest, ResourceReadResponse,
     },
     Repo,
 };
-use manas_space::resource::state::invariant::RepresentedSolidResourceState;
+use manas_space::resource::{
+    state::invariant::RepresentedSolidResourceState, uri::SolidResourceUri,
+};
+use moka::future::{Cache, CacheBuilder};
 use once_cell::sync::Lazy;
 use rdf_dynsyn::{
     correspondence::{Correspondent, SYNTAX_TO_MEDIA_TYPE_CORRESPONDENCE},
-    syntax::invariant::{
-        parsable::DynSynParsableSyntax,
-        serializable::{DynSynSerializableSyntax, S_ALL},
+    syntax::{
+        invariant::{
+            parsable::DynSynParsableSyntax,
+            serializable::{DynSynSerializableSyntax, S_ALL},
+        },
+        RdfSyntax,
     },
     DynSynFactorySet,
 };
@@ -192,6 +201,174 @@ where
 pub struct BinaryRdfDocContentNegotiationConfig {
     /// Dynsyn factories.
     pub dynsyn_factories: DynSynFactorySet,
+
+    /// Optional cache of converted representations.
+    /// If not set, representations are converted on every
+    /// request.
+    pub converted_rep_cache: Option<ConvertedRepCache>,
+}
+
+/// A cached item, valid as long as base representation's
+/// derived etag is not changed.
+#[derive(Debug)]
+struct CachedItem<V> {
+    /// Derived etag of the base representation.
+    base_etag: DerivedETag,
+
+    /// Cached value.
+    value: V,
+}
+
+impl<V> CachedItem<V> {
+    /// Get the cached value, if it is valid for given base
+    /// etag.
+    #[inline]
+    fn valid_value(&self, base_etag: &DerivedETag) -> Option<&V> {
+        (AsRef::<str>::as_ref(&self.base_etag) == AsRef::<str>::as_ref(base_etag))
+            .then_some(&self.value)
+    }
+}
+
+/// Type of converted representations.
+type ConvertedRep = BasicRepresentation<BytesInmem>;
+
+/// Type of parsed datasets.
+type ParsedQuads = QuadsInmem<EcoDataset<ArcQuad>>;
+
+/// A bounded cache of representations converted by
+/// [`BinaryRdfDocContentNegotiatingResourceReader`], and
+/// optionally of datasets parsed from the base
+/// representations.
+///
+/// Items are keyed by resource uri, and are valid only as
+/// long as the base representation's derived etag is not
+/// changed. Thus a modification of the resource invalidates
+/// them implicitly.
+///
+/// Container representations are not cached, as their
+/// etags doesn't account for container representation
+/// preferences.
+#[derive(Clone)]
+pub struct ConvertedRepCache {
+    /// Cache of converted reps, keyed by resource uri and
+    /// target syntax.
+    reps: Cache<(SolidResourceUri, RdfSyntax), Arc<CachedItem<ConvertedRep>>>,
+
+    /// Optional cache of parsed datasets, keyed by resource
+    /// uri.
+    datasets: Option<Cache<SolidResourceUri, Arc<CachedItem<ParsedQuads>>>>,
+}
+
+impl std::fmt::Debug for ConvertedRepCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConvertedRepCache")
+            .field("reps_weighted_size", &self.reps.weighted_size())
+            .field(
+                "datasets_weighted_size",
+                &self.datasets.as_ref().map(|d| d.weighted_size()),
+            )
+            .finish()
+    }
+}
+
+impl ConvertedRepCache {
+    /// Create a new [`ConvertedRepCache`] with given
+    /// capacities and time to live.
+    ///
+    /// `max_reps_size` bounds the total size of cached
+    /// converted representations in bytes, and
+    /// `opt_max_dataset_quads` bounds the total number of
+    /// quads in cached parsed datasets. If latter is `None`,
+    /// parsed datasets will not be cached.
+    pub fn new(
+        max_reps_size: u64,
+        opt_max_dataset_quads: Option<u64>,
+        time_to_live: Duration,
+    ) -> Self {
+        Self {
+            reps: CacheBuilder::new(max_reps_size)
+                .weigher(|_, item: &Arc<CachedItem<ConvertedRep>>| {
+                    item.value.data.size().try_into().unwrap_or(u32::MAX)
+                })
+                .time_to_live(time_to_live)
+                .build(),
+            datasets: opt_max_dataset_quads.map(|max_dataset_quads| {
+                CacheBuilder::new(max_dataset_quads)
+                    .weigher(|_, item: &Arc<CachedItem<ParsedQuads>>| {
+                        item.value.0 .0.len().try_into().unwrap_or(u32::MAX)
+                    })
+                    .time_to_live(time_to_live)
+                    .build()
+            }),
+        }
+    }
+
+    /// Get cached converted rep of the resource with given
+    /// uri, in given syntax.
+    async fn get_rep(
+        &self,
+        res_uri: &SolidResourceUri,
+        syntax: RdfSyntax,
+        base_etag: &DerivedETag,
+    ) -> Option<ConvertedRep> {
+        self.reps
+            .get(&(res_uri.clone(), syntax))
+            .await
+            .and_then(|item| item.valid_value(base_etag).cloned())
+    }
+
+    /// Cache converted rep of the resource with given uri,
+    /// in given syntax.
+    async fn insert_rep(
+        &self,
+        res_uri: SolidResourceUri,
+        syntax: RdfSyntax,
+        base_etag: DerivedETag,
+        rep: ConvertedRep,
+    ) {
+        self.reps
+            .insert(
+                (res_uri, syntax),
+                Arc::new(CachedItem {
+                    base_etag,
+                    value: rep,
+                }),
+            )
+            .await
+    }
+
+    /// Get cached parsed dataset of the resource with given uri.
+    async fn get_dataset(
+        &self,
+        res_uri: &SolidResourceUri,
+        base_etag: &DerivedETag,
+    ) -> Option<ParsedQuads> {
+        self.datasets
+            .as_ref()?
+            .get(res_uri)
+            .await
+            .and_then(|item| item.valid_value(base_etag).cloned())
+    }
+
+    /// Cache parsed dataset of the resource with given uri.
+    async fn insert_dataset(
+        &self,
+        res_uri: SolidResourceUri,
+        base_etag: DerivedETag,
+        dataset: ParsedQuads,
+    ) {
+        if let Some(datasets) = self.datasets.as_ref() {
+            datasets
+                .insert(
+                    res_uri,
+                    Arc::new(CachedItem {
+                        base_etag,
+                        value: dataset,
+                    }),
+                )
+                .await
+        }
+    }
 }
 
 /// Resolve negotiated response.
@@ -234,8 +411,31 @@ pub(super) async fn resolve_negotiated_response<R: Repo>(
         .expect("Must be ok, as dconneger guarantees.")
         .value;
 
-    // Compute converted rep.
-    // TODO Must optimize.
+    // Resolve cache context, if caching is applicable.
+    let opt_cache_context = if_chain! {
+        if let Some(cache) = conneg_config.converted_rep_cache.as_ref();
+        if !slot.is_container_slot();
+        if let Some(base_etag) = inner_rep.metadata().get_rv::<KDerivedETag>();
+        then {
+            Some((cache, slot.id().uri.clone(), base_etag.clone()))
+        }
+        else {
+            None
+        }
+    };
+
+    // Serve from cache, if available.
+    if let Some((cache, res_uri, base_etag)) = opt_cache_context.as_ref() {
+        if let Some(cached_rep) = cache.get_rep(res_uri, *dsyntax, base_etag).await {
+            debug!("Serving converted rep from cache.");
+            return Ok(ResourceReadResponse {
+                state: RepresentedSolidResourceState::new(slot, cached_rep.into_binary()),
+                aux_links_index: inner_resp.aux_links_index,
+                tokens: inner_resp.tokens,
+                extensions: inner_resp.extensions,
+            });
+        }
+    }
 
     // Convert into inmemory rep.
     let rep_inmem: BasicRepresentation<BytesInmem> =
@@ -249,23 +449,39 @@ pub(super) async fn resolve_negotiated_response<R: Repo>(
                     .finish()
             })?;
 
+    // Resolve parsed quads, from cache if available.
+    let mut opt_quads_inmem = None;
+    if let Some((cache, res_uri, base_etag)) = opt_cache_context.as_ref() {
+        opt_quads_inmem = cache.get_dataset(res_uri, base_etag).await;
+    }
+
+    if opt_quads_inmem.is_none() {
+        if let Some(Ok(quads_inmem)) = rep_inmem
+            .try_parse_quads::<EcoDataset<ArcQuad>>(conneg_config.dynsyn_factories.parser.clone())
+            .inspect(|v| {
+                match v.as_ref() {
+                    Some(Err(e)) => {
+                        warn!("Error in parsing quads. {}", e);
+                    }
+                    None => {
+                        warn!("Negotiator intervened even as the source syntax is not parsable.");
+                    }
+                    _ => (),
+                };
+            })
+            .await
+        {
+            if let Some((cache, res_uri, base_etag)) = opt_cache_context.as_ref() {
+                cache
+                    .insert_dataset(res_uri.clone(), base_etag.clone(), quads_inmem.clone())
+                    .await;
+            }
+            opt_quads_inmem = Some(quads_inmem);
+        }
+    }
+
     let mut effective_rep = None;
-    if let Some(Ok(quads_inmem)) = rep_inmem
-        .try_parse_quads::<EcoDataset<ArcQuad>>(conneg_config.dynsyn_factories.parser.clone())
-        .inspect(|v| {
-            match v.as_ref() {
-                Some(Err(e)) => {
-                    warn!("Error in parsing quads. {}", e);
-                }
-                None => {
-                    warn!("Negotiator intervened even as the source syntax is not parsable.");
-                }
-                _ => (),
-            };
-        })
-        .await
-    {
-        // TODO must cache rdf data.
+    if let Some(quads_inmem) = opt_quads_inmem {
         // Serialize resultant quads.
         if let Ok(mut converted_rep_inmem) = BasicRepresentation::try_from_wrap_serializing_quads(
             quads_inmem,
@@ -287,7 +503,16 @@ pub(super) async fn resolve_negotiated_response<R: Repo>(
                         .get_rv::<KDerivedETag>()
                         .map(|base_etag| base_etag.derived_rep_etag(("rdf_serializing", &dsyntax))),
                 )
-                .with_opt::<KLastModified>(rep_inmem.metadata.get_rv::<KLastModified>().copied());
+                .with_opt::<KLastModified>(rep_inmem.metadata.get_rv::<KLastModified>().copied())
+                .with_opt::<KContainerRepPage>(
+                    rep_inmem.metadata.get_rv::<KContainerRepPage>().cloned(),
+                );
+
+            if let Some((cache, res_uri, base_etag)) = opt_cache_context {
+                cache
+                    .insert_rep(res_uri, *dsyntax, base_etag, converted_rep_inmem.clone())
+                    .await;
+            }
 
             effective_rep = Some(converted_rep_inmem)
         }
@@ -368,3 +593,129 @@ impl DContentTypeNegotiator for BinaryRdfDocContentTypeNegotiator {
         base_content_type.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use claims::*;
+    use manas_http::representation::metadata::RepresentationMetadata;
+    use rdf_dynsyn::syntax::{JSON_LD, N_TRIPLES};
+
+    use super::*;
+
+    fn res_uri(uri: &str) -> SolidResourceUri {
+        SolidResourceUri::try_new_from(uri).expect("Must be valid.")
+    }
+
+    fn etag(etag: &str) -> DerivedETag {
+        etag.parse().expect("Must be valid.")
+    }
+
+    fn rep(content: &str) -> ConvertedRep {
+        BasicRepresentation {
+            data: BytesInmem::from(content.to_owned()),
+            metadata: RepresentationMetadata::new(),
+            base_uri: None,
+        }
+    }
+
+    /// Get content of given rep.
+    fn content(rep: &ConvertedRep) -> String {
+        String::from_utf8(
+            rep.data
+                .bytes()
+                .iter()
+                .flat_map(|chunk| chunk.iter().copied())
+                .collect(),
+        )
+        .expect("Must be utf8.")
+    }
+
+    fn new_cache() -> ConvertedRepCache {
+        ConvertedRepCache::new(1024 * 1024, Some(1024), Duration::from_secs(60))
+    }
+
+    #[tokio::test]
+    async fn cached_rep_is_served_for_same_base_etag() {
+        let cache = new_cache();
+        let uri = res_uri("http://pod.example.org/a");
+
+        assert_none!(cache.get_rep(&uri, N_TRIPLES, &etag("\"v1\"")).await);
+
+        cache
+            .insert_rep(uri.clone(), N_TRIPLES, etag("\"v1\""), rep("nt-v1"))
+            .await;
+
+        let cached = assert_some!(cache.get_rep(&uri, N_TRIPLES, &etag("\"v1\"")).await);
+        assert_eq!(content(&cached), "nt-v1");
+
+        // Other resources are not affected.
+        assert_none!(
+            cache
+                .get_rep(
+                    &res_uri("http://pod.example.org/b"),
+                    N_TRIPLES,
+                    &etag("\"v1\"")
+                )
+                .await
+        );
+    }
+
+    #[tokio::test]
+    async fn cached_rep_is_invalidated_when_base_etag_changes() {
+        let cache = new_cache();
+        let uri = res_uri("http://pod.example.org/a");
+
+        cache
+            .insert_rep(uri.clone(), N_TRIPLES, etag("\"v1\""), rep("nt-v1"))
+            .await;
+        assert_none!(cache.get_rep(&uri, N_TRIPLES, &etag("\"v2\"")).await);
+
+        // Rep converted from new base rep replaces the stale one.
+        cache
+            .insert_rep(uri.clone(), N_TRIPLES, etag("\"v2\""), rep("nt-v2"))
+            .await;
+        let cached = assert_some!(cache.get_rep(&uri, N_TRIPLES, &etag("\"v2\"")).await);
+        assert_eq!(content(&cached), "nt-v2");
+        assert_none!(cache.get_rep(&uri, N_TRIPLES, &etag("\"v1\"")).await);
+    }
+
+    #[tokio::test]
+    async fn reps_are_cached_per_syntax() {
+        let cache = new_cache();
+        let uri = res_uri("http://pod.example.org/a");
+        let base_etag = etag("\"v1\"");
+
+        cache
+            .insert_rep(uri.clone(), N_TRIPLES, base_etag.clone(), rep("nt"))
+            .await;
+        assert_none!(cache.get_rep(&uri, JSON_LD, &base_etag).await);
+
+        cache
+            .insert_rep(uri.clone(), JSON_LD, base_etag.clone(), rep("jsonld"))
+            .await;
+
+        let cached = assert_some!(cache.get_rep(&uri, N_TRIPLES, &base_etag).await);
+        assert_eq!(content(&cached), "nt");
+        let cached = assert_some!(cache.get_rep(&uri, JSON_LD, &base_etag).await);
+        assert_eq!(content(&cached), "jsonld");
+    }
+
+    #[tokio::test]
+    async fn datasets_are_cached_only_when_enabled() {
+        let uri = res_uri("http://pod.example.org/a");
+        let base_etag = etag("\"v1\"");
+
+        let cache = new_cache();
+        cache
+            .insert_dataset(uri.clone(), base_etag.clone(), Default::default())
+            .await;
+        assert_some!(cache.get_dataset(&uri, &base_etag).await);
+        assert_none!(cache.get_dataset(&uri, &etag("\"v2\"")).await);
+
+        let cache = ConvertedRepCache::new(1024 * 1024, None, Duration::from_secs(60));
+        cache
+            .insert_dataset(uri.clone(), base_etag.clone(), Default::default())
+            .await;
+        assert_none!(cache.get_dataset(&uri, &base_etag).await);
+    }
+}
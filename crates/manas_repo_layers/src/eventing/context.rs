@@ -0,0 +1,48 @@
+//! I define an implementation of [`RepoContext`] for [`EventingRepo`](super::EventingRepo).
+//!
+
+use std::sync::Arc;
+
+use manas_repo::{
+    context::{LayeredRepoContext, RepoContext},
+    Repo,
+};
+
+use super::{event::ResourceChangeEventBus, MRepo};
+
+/// An implementation of [`RepoContext`] for [`EventingRepo`](super::EventingRepo).
+#[derive(Debug)]
+pub struct EventingRepoContext<IR>
+where
+    IR: Repo,
+{
+    /// Inner repo config.
+    pub inner: Arc<IR::Context>,
+
+    /// Event bus, to which change events are published.
+    pub event_bus: ResourceChangeEventBus<IR::StSpace>,
+}
+
+impl<IR> RepoContext for EventingRepoContext<IR>
+where
+    IR: Repo,
+{
+    type Repo = MRepo<IR>;
+
+    #[inline]
+    fn storage_space(&self) -> &Arc<IR::StSpace> {
+        self.inner.storage_space()
+    }
+}
+
+impl<IR> LayeredRepoContext for EventingRepoContext<IR>
+where
+    IR: Repo,
+{
+    type InnerRepo = IR;
+
+    #[inline]
+    fn inner(&self) -> &Arc<IR::Context> {
+        &self.inner
+    }
+}
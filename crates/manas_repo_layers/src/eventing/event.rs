@@ -0,0 +1,122 @@
+//! I define types for resource change events, that
+//! [`EventingRepo`](super::EventingRepo) publishes on
+//! successful mutations.
+//!
+
+use std::{fmt::Debug, sync::Arc, time::SystemTime};
+
+use manas_space::{
+    resource::{slot::SolidResourceSlot, slot_link::AuxLink},
+    SolidStorageSpace,
+};
+use tokio::sync::broadcast;
+
+/// An enum of kinds of resource changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceChangeKind {
+    /// Resource is created.
+    Created,
+
+    /// Resource's representation is updated.
+    Updated,
+
+    /// Resource is deleted.
+    Deleted,
+}
+
+/// A struct for representing a resource change event.
+#[derive(Debug, Clone)]
+pub struct ResourceChangeEvent<Space: SolidStorageSpace> {
+    /// Slot of the changed resource.
+    pub res_slot: SolidResourceSlot<Space>,
+
+    /// Kind of the change.
+    pub kind: ResourceChangeKind,
+
+    /// Links to aux resources, that are deleted along with
+    /// the resource.
+    pub deleted_aux_res_links: Vec<AuxLink<Space>>,
+
+    /// Time of the change.
+    pub time: SystemTime,
+}
+
+impl<Space: SolidStorageSpace> ResourceChangeEvent<Space> {
+    /// Create a new [`ResourceChangeEvent`] with current time.
+    #[inline]
+    pub fn new_now(res_slot: SolidResourceSlot<Space>, kind: ResourceChangeKind) -> Self {
+        Self {
+            res_slot,
+            kind,
+            deleted_aux_res_links: Vec::new(),
+            time: SystemTime::now(),
+        }
+    }
+}
+
+/// A bus for broadcasting resource change events to
+/// interested subscribers.
+///
+/// Subscribers that lag behind by more than the capacity of
+/// the bus will miss events.
+pub struct ResourceChangeEventBus<Space: SolidStorageSpace> {
+    sender: broadcast::Sender<Arc<ResourceChangeEvent<Space>>>,
+}
+
+impl<Space: SolidStorageSpace> Clone for ResourceChangeEventBus<Space> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<Space: SolidStorageSpace> Debug for ResourceChangeEventBus<Space> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResourceChangeEventBus")
+            .field("receiver_count", &self.sender.receiver_count())
+            .finish()
+    }
+}
+
+impl<Space: SolidStorageSpace> Default for ResourceChangeEventBus<Space> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl<Space: SolidStorageSpace> ResourceChangeEventBus<Space> {
+    /// Default capacity of the bus.
+    pub const DEFAULT_CAPACITY: usize = 1024;
+
+    /// Create a new [`ResourceChangeEventBus`] with given
+    /// capacity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if capacity is zero.
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Publish given event to current subscribers. Returns
+    /// number of subscribers the event is delivered to.
+    pub fn publish(&self, event: ResourceChangeEvent<Space>) -> usize {
+        self.sender.send(Arc::new(event)).unwrap_or(0)
+    }
+
+    /// Subscribe to events published after this call.
+    #[inline]
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<ResourceChangeEvent<Space>>> {
+        self.sender.subscribe()
+    }
+
+    /// Get the number of current subscribers.
+    #[inline]
+    pub fn receiver_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
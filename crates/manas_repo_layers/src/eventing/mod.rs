@@ -0,0 +1,165 @@
+//! I provide an implementation of [`Repo`] that publishes
+//! resource change events on successful mutations.
+//!
+
+use std::{marker::PhantomData, sync::Arc};
+
+use manas_repo::{
+    layer::RepoLayer,
+    policy::uri::impl_::DelegatedUriPolicy,
+    service::{
+        initializer::impl_::DelegatedRepoInitializer,
+        patcher_resolver::impl_::DelegatedRepPatcherResolver,
+        resource_operator::{
+            common::{
+                impl_::DelegatingOperator,
+                status_token::impl_::layered::LayeredResourceStatusTokenTypes,
+            },
+            status_token_resolver::impl_::LayeredResourceStatusTokenResolver,
+        },
+    },
+    Repo, RepoInitializerService, RepoRepPatcherResolver, RepoResourceReader,
+    RepoResourceStatusTokenResolver, RepoServices,
+};
+
+use self::{
+    context::EventingRepoContext,
+    event::ResourceChangeEventBus,
+    service::resource_operator::{
+        creator::EventingRepoResourceCreator, deleter::EventingRepoResourceDeleter,
+        updater::EventingRepoResourceUpdater,
+    },
+};
+
+pub mod context;
+pub mod event;
+pub mod service;
+
+/// A layered implementation of [`Repo`] that publishes
+/// [`ResourceChangeEvent`](event::ResourceChangeEvent)s to
+/// an event bus, on successful resource create, update and
+/// delete operations.
+///
+/// Events are published after the inner repo completes the
+/// operation, hence they are published for any mutation
+/// through the repo, irrespective of whether it is made
+/// through the storage's http services. Dry run deletes
+/// publish no events.
+///
+/// NOTE: Only mutations made through this layer are
+/// observed. Mutations to the backend by other processes
+/// publish no events to this process's bus.
+#[derive(Debug, Clone)]
+pub struct EventingRepo<IR>
+where
+    IR: Repo,
+{
+    context: Arc<EventingRepoContext<IR>>,
+}
+
+impl<IR> Repo for EventingRepo<IR>
+where
+    IR: Repo,
+{
+    type StSpace = IR::StSpace;
+
+    type Representation = IR::Representation;
+
+    type Context = EventingRepoContext<IR>;
+
+    type UriPolicy = DelegatedUriPolicy<IR::UriPolicy, Self>;
+
+    type ResourceStatusTokenTypes =
+        LayeredResourceStatusTokenTypes<IR::ResourceStatusTokenTypes, Self>;
+
+    type RepPatcher = IR::RepPatcher;
+
+    type Services = EventingRepoServices<IR>;
+
+    type Credentials = IR::Credentials;
+
+    #[inline]
+    fn new(context: Arc<Self::Context>) -> Self {
+        Self { context }
+    }
+
+    #[inline]
+    fn context(&self) -> &Arc<Self::Context> {
+        &self.context
+    }
+}
+
+/// Quick alias for `EventingRepo`
+pub(crate) type MRepo<IR> = EventingRepo<IR>;
+
+/// Services for [`EventingRepo`].
+#[derive(Debug, Clone)]
+pub struct EventingRepoServices<IR> {
+    _phantom: PhantomData<fn(IR)>,
+}
+
+impl<IR> RepoServices for EventingRepoServices<IR>
+where
+    IR: Repo,
+{
+    type Repo = MRepo<IR>;
+
+    type Initializer = DelegatedRepoInitializer<RepoInitializerService<IR>, MRepo<IR>>;
+
+    type RepPatcherResolver = DelegatedRepPatcherResolver<RepoRepPatcherResolver<IR>, MRepo<IR>>;
+
+    type ResourceStatusTokenResolver =
+        LayeredResourceStatusTokenResolver<RepoResourceStatusTokenResolver<IR>, MRepo<IR>>;
+
+    type ResourceReader = DelegatingOperator<RepoResourceReader<IR>, MRepo<IR>>;
+
+    type ResourceCreator = EventingRepoResourceCreator<IR>;
+
+    type ResourceUpdater = EventingRepoResourceUpdater<IR>;
+
+    type ResourceDeleter = EventingRepoResourceDeleter<IR>;
+}
+
+/// An implementation of [`RepoLayer`] that layers change
+/// event publishing functionality over repos.
+#[derive(Debug, Clone)]
+pub struct EventingRepoLayer<IR>
+where
+    IR: Repo,
+{
+    event_bus: ResourceChangeEventBus<IR::StSpace>,
+    _phantom: PhantomData<fn(IR)>,
+}
+
+impl<IR> EventingRepoLayer<IR>
+where
+    IR: Repo,
+{
+    /// Create a new [`EventingRepoLayer`] with given event
+    /// bus.
+    #[inline]
+    pub fn new(event_bus: ResourceChangeEventBus<IR::StSpace>) -> Self {
+        Self {
+            event_bus,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<IR> RepoLayer<IR> for EventingRepoLayer<IR>
+where
+    IR: Repo,
+{
+    type LayeredRepo = EventingRepo<IR>;
+
+    #[inline]
+    fn layer_context(
+        &self,
+        inner_context: Arc<<IR as Repo>::Context>,
+    ) -> <Self::LayeredRepo as Repo>::Context {
+        EventingRepoContext {
+            inner: inner_context,
+            event_bus: self.event_bus.clone(),
+        }
+    }
+}
@@ -0,0 +1,4 @@
+//! I provide repo service implementations for [`EventingRepo`](super::EventingRepo).
+//!
+
+pub mod resource_operator;
@@ -0,0 +1,82 @@
+//! I provide an implementation of [`ResourceCreator`] for [`EventingRepo`].
+//!
+
+use std::task::Poll;
+
+use dyn_problem::{ProbFuture, Problem};
+use manas_repo::{
+    service::resource_operator::creator::{
+        ResourceCreateRequest, ResourceCreateResponse, ResourceCreator,
+    },
+    Repo, RepoResourceCreator,
+};
+use tower::{Service, ServiceExt};
+
+use crate::eventing::{
+    event::{ResourceChangeEvent, ResourceChangeKind},
+    EventingRepo,
+};
+
+/// An implementation of [`ResourceCreator`] for [`EventingRepo`]
+#[derive(Debug)]
+pub struct EventingRepoResourceCreator<IR: Repo> {
+    inner: RepoResourceCreator<IR>,
+}
+
+impl<IR: Repo> Default for EventingRepoResourceCreator<IR> {
+    fn default() -> Self {
+        Self {
+            inner: Default::default(),
+        }
+    }
+}
+
+impl<IR: Repo> Clone for EventingRepoResourceCreator<IR> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<IR> Service<ResourceCreateRequest<EventingRepo<IR>>> for EventingRepoResourceCreator<IR>
+where
+    IR: Repo,
+{
+    type Response = ResourceCreateResponse<EventingRepo<IR>>;
+
+    type Error = Problem;
+
+    type Future = ProbFuture<'static, Self::Response>;
+
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[tracing::instrument(skip_all, name = "EventingRepoResourceCreator::call")]
+    fn call(&mut self, req: ResourceCreateRequest<EventingRepo<IR>>) -> Self::Future {
+        let layer_context = req.tokens.repo_context().clone();
+        let inner_req = req.unlayer_tokens::<IR>();
+        let mut inner_svc = self.inner.clone();
+
+        Box::pin(async move {
+            let resp = inner_svc.ready().await?.call(inner_req).await?;
+
+            layer_context
+                .event_bus
+                .publish(ResourceChangeEvent::new_now(
+                    resp.created_resource_slot.clone(),
+                    ResourceChangeKind::Created,
+                ));
+
+            Ok(resp.map_repo())
+        })
+    }
+}
+
+impl<IR> ResourceCreator for EventingRepoResourceCreator<IR>
+where
+    IR: Repo,
+{
+    type Repo = EventingRepo<IR>;
+}
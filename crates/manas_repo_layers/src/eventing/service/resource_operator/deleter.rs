@@ -0,0 +1,87 @@
+//! I provide an implementation of [`ResourceDeleter`] for [`EventingRepo`].
+//!
+
+use std::{task::Poll, time::SystemTime};
+
+use dyn_problem::{ProbFuture, Problem};
+use manas_repo::{
+    service::resource_operator::{
+        common::status_token::RepoResourceStatusTokenBase,
+        deleter::{KDryRunDelete, ResourceDeleteRequest, ResourceDeleteResponse, ResourceDeleter},
+    },
+    Repo, RepoResourceDeleter,
+};
+use tower::{Service, ServiceExt};
+use typed_record::TypedRecord;
+
+use crate::eventing::{
+    event::{ResourceChangeEvent, ResourceChangeKind},
+    EventingRepo,
+};
+
+/// An implementation of [`ResourceDeleter`] for [`EventingRepo`]
+#[derive(Debug)]
+pub struct EventingRepoResourceDeleter<IR: Repo> {
+    inner: RepoResourceDeleter<IR>,
+}
+
+impl<IR: Repo> Default for EventingRepoResourceDeleter<IR> {
+    fn default() -> Self {
+        Self {
+            inner: Default::default(),
+        }
+    }
+}
+
+impl<IR: Repo> Clone for EventingRepoResourceDeleter<IR> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<IR> Service<ResourceDeleteRequest<EventingRepo<IR>>> for EventingRepoResourceDeleter<IR>
+where
+    IR: Repo,
+{
+    type Response = ResourceDeleteResponse<EventingRepo<IR>>;
+
+    type Error = Problem;
+
+    type Future = ProbFuture<'static, Self::Response>;
+
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[tracing::instrument(skip_all, name = "EventingRepoResourceDeleter::call")]
+    fn call(&mut self, req: ResourceDeleteRequest<EventingRepo<IR>>) -> Self::Future {
+        let layer_context = req.tokens.res_token.repo_context().clone();
+        let is_dry_run = req.extensions.get_rv::<KDryRunDelete>().is_some();
+        let inner_req = req.unlayer_tokens::<IR>();
+        let mut inner_svc = self.inner.clone();
+
+        Box::pin(async move {
+            let resp = inner_svc.ready().await?.call(inner_req).await?;
+
+            if !is_dry_run {
+                layer_context.event_bus.publish(ResourceChangeEvent {
+                    res_slot: resp.deleted_res_slot.clone(),
+                    kind: ResourceChangeKind::Deleted,
+                    deleted_aux_res_links: resp.deleted_aux_res_links.clone(),
+                    time: SystemTime::now(),
+                });
+            }
+
+            Ok(resp.map_repo())
+        })
+    }
+}
+
+impl<IR> ResourceDeleter for EventingRepoResourceDeleter<IR>
+where
+    IR: Repo,
+{
+    type Repo = EventingRepo<IR>;
+}
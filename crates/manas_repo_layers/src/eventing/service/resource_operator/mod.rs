@@ -0,0 +1,6 @@
+//! I provide resource operator service implementations for [`EventingRepo`](super::super::EventingRepo).
+//!
+
+pub mod creator;
+pub mod deleter;
+pub mod updater;
@@ -0,0 +1,83 @@
+//! I provide an implementation of [`ResourceUpdater`] for [`EventingRepo`].
+//!
+
+use std::task::Poll;
+
+use dyn_problem::{ProbFuture, Problem};
+use manas_repo::{
+    service::resource_operator::updater::{
+        ResourceUpdateRequest, ResourceUpdateResponse, ResourceUpdater,
+    },
+    Repo, RepoResourceUpdater,
+};
+use tower::{Service, ServiceExt};
+
+use crate::eventing::{
+    event::{ResourceChangeEvent, ResourceChangeKind},
+    EventingRepo,
+};
+
+/// An implementation of [`ResourceUpdater`] for [`EventingRepo`]
+#[derive(Debug)]
+pub struct EventingRepoResourceUpdater<IR: Repo> {
+    inner: RepoResourceUpdater<IR>,
+}
+
+impl<IR: Repo> Default for EventingRepoResourceUpdater<IR> {
+    fn default() -> Self {
+        Self {
+            inner: Default::default(),
+        }
+    }
+}
+
+impl<IR: Repo> Clone for EventingRepoResourceUpdater<IR> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<IR> Service<ResourceUpdateRequest<EventingRepo<IR>>> for EventingRepoResourceUpdater<IR>
+where
+    IR: Repo,
+{
+    type Response = ResourceUpdateResponse;
+
+    type Error = Problem;
+
+    type Future = ProbFuture<'static, Self::Response>;
+
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[tracing::instrument(skip_all, name = "EventingRepoResourceUpdater::call")]
+    fn call(&mut self, req: ResourceUpdateRequest<EventingRepo<IR>>) -> Self::Future {
+        let layer_context = req.tokens.res_token.repo_context().clone();
+        let res_slot = req.tokens.res_token.slot().clone();
+        let inner_req = req.unlayer_tokens::<IR>();
+        let mut inner_svc = self.inner.clone();
+
+        Box::pin(async move {
+            let resp = inner_svc.ready().await?.call(inner_req).await?;
+
+            layer_context
+                .event_bus
+                .publish(ResourceChangeEvent::new_now(
+                    res_slot,
+                    ResourceChangeKind::Updated,
+                ));
+
+            Ok(resp)
+        })
+    }
+}
+
+impl<IR> ResourceUpdater for EventingRepoResourceUpdater<IR>
+where
+    IR: Repo,
+{
+    type Repo = EventingRepo<IR>;
+}
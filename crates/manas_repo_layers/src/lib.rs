@@ -11,8 +11,17 @@ pub mod dconneging;
 
 pub mod delegating;
 
+#[cfg(feature = "eventing")]
+pub mod eventing;
+
 #[cfg(feature = "patching")]
 pub mod patching;
 
+#[cfg(feature = "quota")]
+pub mod quota;
+
 #[cfg(feature = "validating")]
 pub mod validating;
+
+#[cfg(feature = "versioning")]
+pub mod versioning;
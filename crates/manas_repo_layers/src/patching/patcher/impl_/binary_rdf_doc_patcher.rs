@@ -7,7 +7,7 @@ use std::{collections::HashSet, marker::PhantomData, sync::Arc, task::Poll};
 use dyn_problem::{type_::UNKNOWN_IO_ERROR, ProbFuture, ProbResult, Problem};
 use futures::TryFutureExt;
 use manas_http::{
-    header::common::media_type::TEXT_TURTLE,
+    header::common::media_type::{MediaType, TEXT_TURTLE},
     representation::{
         impl_::{
             basic::BasicRepresentation,
@@ -18,11 +18,8 @@ use manas_http::{
         Representation,
     },
 };
-use manas_repo::service::{
-    patcher_resolver::impl_::UnsupportedRepPatcher,
-    resource_operator::common::rep_patcher::{
-        RepPatcher, INCOMPATIBLE_PATCH_SOURCE_CONTENT_TYPE, INVALID_ENCODED_SOURCE_REP,
-    },
+use manas_repo::service::resource_operator::common::rep_patcher::{
+    RepPatcher, INCOMPATIBLE_PATCH_SOURCE_CONTENT_TYPE, INVALID_ENCODED_SOURCE_REP,
 };
 use manas_space::{
     resource::{operation::SolidResourceOperation, state::SolidResourceState},
@@ -31,7 +28,7 @@ use manas_space::{
 use rdf_dynsyn::{syntax::invariant::serializable::DynSynSerializableSyntax, DynSynFactorySet};
 use rdf_utils::model::dataset::InfallibleMutableDataset;
 use tower::{Service, ServiceExt};
-use tracing::{error, warn};
+use tracing::error;
 
 use crate::patching::patcher::DirectRepPatcher;
 
@@ -57,13 +54,6 @@ impl<StSpace, Inner: Clone, D> Clone for BinaryRdfDocPatcher<StSpace, Inner, D>
     }
 }
 
-impl<StSpace, Inner, D> From<BinaryRdfDocPatcher<StSpace, Inner, D>> for UnsupportedRepPatcher {
-    fn from(_value: BinaryRdfDocPatcher<StSpace, Inner, D>) -> Self {
-        warn!("Into unsupported patcher called.");
-        unimplemented!()
-    }
-}
-
 impl<StSpace, Inner, D> BinaryRdfDocPatcher<StSpace, Inner, D> {
     /// Create a new [`BinaryRdfDocPatcher`].
     #[inline]
@@ -210,6 +200,11 @@ where
 {
     type ResolutionConfig = BinaryRdfDocPatcherResolutionConfig<Inner::ResolutionConfig>;
 
+    #[inline]
+    fn supports_patch_doc_content_type(content_type: &MediaType) -> bool {
+        Inner::supports_patch_doc_content_type(content_type)
+    }
+
     fn try_resolve(
         patch_doc_rep: BinaryRepresentation,
         config: Arc<Self::ResolutionConfig>,
@@ -287,6 +282,11 @@ where
 {
     type ResolutionConfig = BinaryRdfDocPatcherResolutionConfig<Inner::ResolutionConfig>;
 
+    #[inline]
+    fn supports_patch_doc_content_type(content_type: &MediaType) -> bool {
+        Inner::supports_patch_doc_content_type(content_type)
+    }
+
     fn try_resolve(
         patch_doc_rep: BinaryRepresentation,
         config: Arc<Self::ResolutionConfig>,
@@ -0,0 +1,118 @@
+//! I provide an implementation of [`DirectRepPatcher`] that
+//! dispatches to one of two patchers, based on patch doc
+//! content type.
+//!
+
+use std::{collections::HashSet, sync::Arc, task::Poll};
+
+use dyn_problem::{ProbFuture, Problem};
+use manas_http::{
+    header::common::media_type::MediaType,
+    representation::{impl_::binary::BinaryRepresentation, Representation},
+};
+use manas_repo::service::resource_operator::common::rep_patcher::RepPatcher;
+use manas_space::{
+    resource::{operation::SolidResourceOperation, state::SolidResourceState},
+    SolidStorageSpace,
+};
+use tower::Service;
+
+use crate::patching::patcher::DirectRepPatcher;
+
+/// An implementation of [`DirectRepPatcher`] that is either
+/// of two patchers.
+///
+/// It resolves to left patcher, if it supports the patch doc
+/// content type, else to the right patcher.
+#[derive(Debug, Clone)]
+pub enum EitherRepPatcher<L, R> {
+    /// Left patcher.
+    Left(L),
+
+    /// Right patcher.
+    Right(R),
+}
+
+impl<L: RepPatcher, R: RepPatcher> RepPatcher for EitherRepPatcher<L, R> {
+    #[inline]
+    fn effective_ops(&self) -> HashSet<SolidResourceOperation> {
+        match self {
+            Self::Left(l) => l.effective_ops(),
+            Self::Right(r) => r.effective_ops(),
+        }
+    }
+}
+
+impl<StSpace, Rep, L, R> Service<SolidResourceState<StSpace, Rep>> for EitherRepPatcher<L, R>
+where
+    StSpace: SolidStorageSpace,
+    Rep: Representation + Send + 'static,
+    L: DirectRepPatcher<StSpace, Rep>,
+    R: DirectRepPatcher<StSpace, Rep>,
+{
+    type Response = Rep;
+
+    type Error = Problem;
+
+    type Future = ProbFuture<'static, Rep>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self {
+            Self::Left(l) => l.poll_ready(cx),
+            Self::Right(r) => r.poll_ready(cx),
+        }
+    }
+
+    #[inline]
+    fn call(&mut self, res_state: SolidResourceState<StSpace, Rep>) -> Self::Future {
+        match self {
+            Self::Left(l) => l.call(res_state),
+            Self::Right(r) => r.call(res_state),
+        }
+    }
+}
+
+impl<StSpace, Rep, L, R> DirectRepPatcher<StSpace, Rep> for EitherRepPatcher<L, R>
+where
+    StSpace: SolidStorageSpace,
+    Rep: Representation + Send + 'static,
+    L: DirectRepPatcher<StSpace, Rep>,
+    R: DirectRepPatcher<StSpace, Rep>,
+{
+    type ResolutionConfig =
+        EitherRepPatcherResolutionConfig<L::ResolutionConfig, R::ResolutionConfig>;
+
+    #[inline]
+    fn supports_patch_doc_content_type(content_type: &MediaType) -> bool {
+        L::supports_patch_doc_content_type(content_type)
+            || R::supports_patch_doc_content_type(content_type)
+    }
+
+    fn try_resolve(
+        patch_doc_rep: BinaryRepresentation,
+        config: Arc<Self::ResolutionConfig>,
+    ) -> ProbFuture<'static, Self> {
+        Box::pin(async move {
+            if L::supports_patch_doc_content_type(patch_doc_rep.metadata().content_type()) {
+                Ok(Self::Left(
+                    L::try_resolve(patch_doc_rep, config.left.clone()).await?,
+                ))
+            } else {
+                Ok(Self::Right(
+                    R::try_resolve(patch_doc_rep, config.right.clone()).await?,
+                ))
+            }
+        })
+    }
+}
+
+/// Resolution config for [`EitherRepPatcher`].
+#[derive(Debug, Clone)]
+pub struct EitherRepPatcherResolutionConfig<LConfig, RConfig> {
+    /// Left patcher config.
+    pub left: Arc<LConfig>,
+
+    /// Right patcher config.
+    pub right: Arc<RConfig>,
+}
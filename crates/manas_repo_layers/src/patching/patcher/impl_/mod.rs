@@ -2,4 +2,6 @@
 //!
 
 pub mod binary_rdf_doc_patcher;
+pub mod either_patcher;
 pub mod solid_insert_delete_patcher;
+pub mod sparql_update_patcher;
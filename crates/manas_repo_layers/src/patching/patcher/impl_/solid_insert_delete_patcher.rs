@@ -10,6 +10,7 @@ use dyn_problem::{
     ProbFuture, Problem,
 };
 use manas_http::{
+    header::common::media_type::MediaType,
     representation::{
         impl_::{
             basic::BasicRepresentation,
@@ -21,9 +22,7 @@ use manas_http::{
     BoxError,
 };
 use manas_repo::service::{
-    patcher_resolver::{
-        impl_::UnsupportedRepPatcher, INVALID_ENCODED_PATCH, UNKNOWN_PATCH_DOC_CONTENT_TYPE,
-    },
+    patcher_resolver::{INVALID_ENCODED_PATCH, UNKNOWN_PATCH_DOC_CONTENT_TYPE},
     resource_operator::common::{
         problem::PAYLOAD_TOO_LARGE,
         rep_patcher::{RepPatcher, PATCH_SEMANTICS_ERROR},
@@ -44,7 +43,7 @@ use rdf_utils::{
 use sophia_api::dataset::SetDataset;
 use tokio::task::spawn_blocking;
 use tower::Service;
-use tracing::{error, warn};
+use tracing::error;
 
 use crate::patching::patcher::DirectRepPatcher;
 
@@ -77,13 +76,6 @@ impl<StSpace, SD> Clone for SolidInsertDeletePatcher<StSpace, SD> {
     }
 }
 
-impl<StSpace, SD> From<SolidInsertDeletePatcher<StSpace, SD>> for UnsupportedRepPatcher {
-    fn from(_value: SolidInsertDeletePatcher<StSpace, SD>) -> Self {
-        warn!("Into unsupported patcher called.");
-        unimplemented!()
-    }
-}
-
 impl<StSpace, SD: InfallibleMutableDataset + SetDataset + Send + 'static>
     SolidInsertDeletePatcher<StSpace, SD>
 {
@@ -170,13 +162,18 @@ where
 {
     type ResolutionConfig = SolidInsertDeletePatcherResolutionConfig;
 
+    #[inline]
+    fn supports_patch_doc_content_type(content_type: &MediaType) -> bool {
+        content_type.essence_str() == TEXT_N3.essence_str()
+    }
+
     fn try_resolve(
         mut patch_doc_rep: BinaryRepresentation,
         config: Arc<Self::ResolutionConfig>,
     ) -> ProbFuture<'static, Self> {
         Box::pin(async move {
             // If it is not n3 document, return error.
-            if patch_doc_rep.metadata().content_type().essence_str() != TEXT_N3.essence_str() {
+            if !Self::supports_patch_doc_content_type(patch_doc_rep.metadata().content_type()) {
                 error!("Patch doc content type is not that of n3.");
                 return Err(UNKNOWN_PATCH_DOC_CONTENT_TYPE.new_problem());
             }
@@ -0,0 +1,248 @@
+//! I define an implementation of [`DirectRepPatcher`]
+//! confirming to sparql update patch.
+//!
+
+use std::{collections::HashSet, io::Read, marker::PhantomData, sync::Arc, task::Poll};
+
+use capped_stream::OutOfSizeLimitError;
+use dyn_problem::{
+    type_::{INTERNAL_ERROR, UNKNOWN_IO_ERROR},
+    ProbFuture, Problem,
+};
+use manas_http::{
+    header::common::media_type::MediaType,
+    representation::{
+        impl_::{
+            basic::BasicRepresentation,
+            binary::BinaryRepresentation,
+            common::data::{bytes_inmem::BytesInmem, quads_inmem::QuadsInmem},
+        },
+        Representation,
+    },
+    BoxError,
+};
+use manas_repo::service::{
+    patcher_resolver::{INVALID_ENCODED_PATCH, UNKNOWN_PATCH_DOC_CONTENT_TYPE},
+    resource_operator::common::{
+        problem::PAYLOAD_TOO_LARGE,
+        rep_patcher::{RepPatcher, PATCH_CONFLICT, PATCH_SEMANTICS_ERROR},
+    },
+};
+use manas_space::{
+    resource::{operation::SolidResourceOperation, state::SolidResourceState},
+    SolidStorageSpace,
+};
+use rdf_utils::{
+    model::dataset::InfallibleMutableDataset,
+    patch::{
+        sparql_update::{InvalidSparqlUpdatePatch, SparqlUpdatePatch, APPLICATION_SPARQL_UPDATE},
+        PatchEffectiveOperation,
+    },
+};
+use sophia_api::dataset::SetDataset;
+use tokio::task::spawn_blocking;
+use tower::Service;
+use tracing::error;
+
+use crate::patching::patcher::DirectRepPatcher;
+
+/// An implementation of [`RepPatcher`] svc that patches
+/// an rdf source representation using configured sparql
+/// update patch.
+///
+pub struct SparqlUpdatePatcher<StSpace, SD> {
+    /// Patch.
+    pub patch: SparqlUpdatePatch,
+
+    _phantom: PhantomData<fn(StSpace, SD)>,
+}
+
+impl<StSpace, SD> std::fmt::Debug for SparqlUpdatePatcher<StSpace, SD> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SparqlUpdatePatcher")
+            .field("patch", &self.patch)
+            .finish()
+    }
+}
+
+impl<StSpace, SD> Clone for SparqlUpdatePatcher<StSpace, SD> {
+    fn clone(&self) -> Self {
+        Self {
+            patch: self.patch.clone(),
+            _phantom: self._phantom,
+        }
+    }
+}
+
+impl<StSpace, SD: InfallibleMutableDataset + SetDataset + Send + 'static>
+    SparqlUpdatePatcher<StSpace, SD>
+{
+    /// Create a new [`SparqlUpdatePatcher`] with given patch.
+    #[inline]
+    pub fn new(patch: SparqlUpdatePatch) -> Self {
+        Self {
+            patch,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<StSpace, D> RepPatcher for SparqlUpdatePatcher<StSpace, D>
+where
+    StSpace: SolidStorageSpace,
+    D: Send + 'static,
+{
+    #[inline]
+    fn effective_ops(&self) -> HashSet<SolidResourceOperation> {
+        self.patch
+            .effective_ops()
+            .iter()
+            .map(|op| match op {
+                PatchEffectiveOperation::Read => SolidResourceOperation::READ,
+                PatchEffectiveOperation::Append => SolidResourceOperation::APPEND,
+                PatchEffectiveOperation::Write => SolidResourceOperation::WRITE,
+            })
+            .collect()
+    }
+}
+
+impl<StSpace, D> Service<SolidResourceState<StSpace, BasicRepresentation<QuadsInmem<D>>>>
+    for SparqlUpdatePatcher<StSpace, D>
+where
+    StSpace: SolidStorageSpace,
+    D: Default + InfallibleMutableDataset + SetDataset + Send + 'static,
+{
+    type Response = BasicRepresentation<QuadsInmem<D>>;
+
+    type Error = Problem;
+
+    type Future = ProbFuture<'static, Self::Response>;
+
+    #[inline]
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(
+        &mut self,
+        res_state: SolidResourceState<StSpace, BasicRepresentation<QuadsInmem<D>>>,
+    ) -> Self::Future {
+        let mut rep = res_state.representation.unwrap_or_default();
+
+        let patch = self.patch.clone();
+
+        Box::pin(async move {
+            // Apply patch.
+            let patched_dataset = patch.apply(rep.data.into_inner()).map_err(|e| {
+                error!("Error in patching target rep dataset. Error:\n {}", e);
+                if e.is_conflict() {
+                    PATCH_CONFLICT.new_problem_builder().source(e).finish()
+                } else {
+                    PATCH_SEMANTICS_ERROR
+                        .new_problem_builder()
+                        .source(e)
+                        .finish()
+                }
+            })?;
+
+            rep.data = QuadsInmem::new(patched_dataset);
+            Ok(rep)
+        })
+    }
+}
+
+impl<StSpace, D> DirectRepPatcher<StSpace, BasicRepresentation<QuadsInmem<D>>>
+    for SparqlUpdatePatcher<StSpace, D>
+where
+    StSpace: SolidStorageSpace,
+    D: Default + InfallibleMutableDataset + SetDataset + Send + 'static,
+{
+    type ResolutionConfig = SparqlUpdatePatcherResolutionConfig;
+
+    #[inline]
+    fn supports_patch_doc_content_type(content_type: &MediaType) -> bool {
+        content_type.essence_str() == APPLICATION_SPARQL_UPDATE.essence_str()
+    }
+
+    fn try_resolve(
+        mut patch_doc_rep: BinaryRepresentation,
+        config: Arc<Self::ResolutionConfig>,
+    ) -> ProbFuture<'static, Self> {
+        Box::pin(async move {
+            // If it is not sparql update, return error.
+            if !Self::supports_patch_doc_content_type(patch_doc_rep.metadata().content_type()) {
+                error!("Patch doc content type is not that of sparql update.");
+                return Err(UNKNOWN_PATCH_DOC_CONTENT_TYPE.new_problem());
+            }
+
+            // Size cap the patch doc rep.
+            if let Some(max_patch_doc_payload_size) = config.max_patch_doc_payload_size {
+                patch_doc_rep = patch_doc_rep.into_stream_size_capped(max_patch_doc_payload_size);
+            }
+
+            // Convert into inmem rep.
+            let patch_rep_inmem: BinaryRepresentation<BytesInmem> =
+                async_convert::TryFrom::try_from(patch_doc_rep)
+                    .await
+                    .map_err(|e: BoxError| {
+                        error!("Error in loading patch body into memory. Error:\n {}", e);
+                        if e.downcast_ref::<OutOfSizeLimitError>().is_some() {
+                            PAYLOAD_TOO_LARGE
+                                .new_problem_builder()
+                                .message("Patch payload too large.")
+                                .finish()
+                        } else {
+                            UNKNOWN_IO_ERROR
+                                .new_problem_builder()
+                                .message("Unknown io error in loading patch rep.")
+                                .source_in_a_box(e)
+                                .finish()
+                        }
+                    })?;
+
+            // Parse [`SparqlUpdatePatch`].
+            let patch = spawn_blocking(move || {
+                let mut update = String::new();
+                patch_rep_inmem
+                    .data()
+                    .as_read()
+                    .read_to_string(&mut update)
+                    .map_err(|_| InvalidSparqlUpdatePatch::InvalidSparqlUpdate)?;
+                SparqlUpdatePatch::parse(
+                    &update,
+                    patch_rep_inmem.base_uri().as_ref().map(Into::into),
+                )
+            })
+            .await
+            .map_err(|e| {
+                error!("Error in spawned parse worker. Error:\n {}", e);
+                INTERNAL_ERROR.new_problem()
+            })?
+            .map_err(|e| {
+                error!("Invalid sparql update patch. Error:\n {}", e);
+                // Syntactically valid, but unsupported
+                // updates are semantic errors.
+                if matches!(e, InvalidSparqlUpdatePatch::InvalidSparqlUpdate) {
+                    INVALID_ENCODED_PATCH
+                        .new_problem_builder()
+                        .source(e)
+                        .finish()
+                } else {
+                    PATCH_SEMANTICS_ERROR
+                        .new_problem_builder()
+                        .source(e)
+                        .finish()
+                }
+            })?;
+
+            Ok(SparqlUpdatePatcher::new(patch))
+        })
+    }
+}
+
+/// Resolution config for [`SparqlUpdatePatcher`].
+#[derive(Debug, Clone)]
+pub struct SparqlUpdatePatcherResolutionConfig {
+    /// Maximum patch doc payload size.
+    pub max_patch_doc_payload_size: Option<u64>,
+}
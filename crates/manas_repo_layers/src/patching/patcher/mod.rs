@@ -4,11 +4,11 @@
 use std::{fmt::Debug, sync::Arc};
 
 use dyn_problem::{ProbFuture, Problem};
-use manas_http::representation::{impl_::binary::BinaryRepresentation, Representation};
-use manas_repo::service::{
-    patcher_resolver::impl_::UnsupportedRepPatcher,
-    resource_operator::common::rep_patcher::RepPatcher,
+use manas_http::{
+    header::common::media_type::MediaType,
+    representation::{impl_::binary::BinaryRepresentation, Representation},
 };
+use manas_repo::service::resource_operator::common::rep_patcher::RepPatcher;
 use manas_space::{resource::state::SolidResourceState, SolidStorageSpace};
 use tower::Service;
 
@@ -32,6 +32,9 @@ pub mod util;
 ///
 /// - [`PATCH_SEMANTICS_ERROR`](manas_repo::service::resource_operator::common::rep_patcher::PATCH_SEMANTICS_ERROR):
 /// If any semantic error in patch application.
+///
+/// - [`PATCH_CONFLICT`](manas_repo::service::resource_operator::common::rep_patcher::PATCH_CONFLICT):
+/// If patch conflicts with the target representation.
 pub trait DirectRepPatcher<StSpace, Rep>:
     RepPatcher
     + Service<
@@ -42,7 +45,6 @@ pub trait DirectRepPatcher<StSpace, Rep>:
     > + Sized
     + Debug
     + Clone
-    + Into<UnsupportedRepPatcher>
 where
     StSpace: SolidStorageSpace,
     Rep: Representation + Send + 'static,
@@ -50,6 +52,10 @@ where
     /// Type of resolution config.
     type ResolutionConfig: Debug + Send + Sync + 'static;
 
+    /// Get if patcher can be resolved from patch docs of given
+    /// content type.
+    fn supports_patch_doc_content_type(content_type: &MediaType) -> bool;
+
     /// Try to resolve the patcher from given patch doc representation.
     fn try_resolve(
         patch_doc_rep: BinaryRepresentation,
@@ -10,8 +10,8 @@ use manas_repo::{
             status_token::{ExistingNonRepresentedResourceToken, ExistingResourceToken},
         },
         reader::rep_preferences::{
-            range_negotiator::impl_::CompleteRangeNegotiator, ContainerRepresentationPreference,
-            RepresentationPreferences,
+            range_negotiator::impl_::CompleteRangeNegotiator, version::RepVersionPreference,
+            ContainerRepresentationPreference, RepresentationPreferences,
         },
     },
     Repo, RepoExistingResourceToken, RepoExt,
@@ -89,7 +89,9 @@ where
                 RepresentationPreferences {
                     // TODO must decide clearly.
                     container_rep_preference: ContainerRepresentationPreference::Minimal,
+                    container_page_preference: None,
                     non_container_rep_range_negotiator: Box::new(CompleteRangeNegotiator),
+                    version_preference: RepVersionPreference::Current,
                 },
             )
             .map_err(|e| {
@@ -9,7 +9,7 @@ use manas_repo::{
     service::{
         patcher_resolver::impl_::UnsupportedRepPatcher,
         resource_operator::{
-            common::rep_update_action::RepUpdateAction,
+            common::{rep_update_action::RepUpdateAction, status_token::impl_::layered::Layered},
             creator::{
                 ResourceCreateRequest, ResourceCreateResponse, ResourceCreator,
                 SLOT_REL_SUBJECT_CONSTRAIN_VIOLATION,
@@ -69,7 +69,7 @@ where
     }
 
     #[tracing::instrument(skip_all, name = "PatchingRepoResourceCreator::call")]
-    fn call(&mut self, mut req: ResourceCreateRequest<PatchingRepo<IR, P>>) -> Self::Future {
+    fn call(&mut self, req: ResourceCreateRequest<PatchingRepo<IR, P>>) -> Self::Future {
         let mut inner_svc = self.inner.clone();
         Box::pin(async move {
             let new_res_slot = req.try_equivalent_res_slot().map_err(|e| {
@@ -77,21 +77,28 @@ where
                 SLOT_REL_SUBJECT_CONSTRAIN_VIOLATION.new_problem()
             })?;
 
-            // Modify the update action to `set_with` variant by resolving patch.
-            req.rep_update_action = RepUpdateAction::SetWith(
-                try_resolve_effective_rep(
-                    SolidResourceState {
-                        slot: new_res_slot,
-                        representation: None,
-                    },
-                    req.rep_update_action,
-                )
-                .inspect_err(|e| error!("Error in resolving patch effective rep. {e}"))
-                .await?,
-            );
+            // Resolve the effective rep by resolving patch.
+            let effective_rep = try_resolve_effective_rep(
+                SolidResourceState {
+                    slot: new_res_slot,
+                    representation: None,
+                },
+                req.rep_update_action,
+            )
+            .inspect_err(|e| error!("Error in resolving patch effective rep. {e}"))
+            .await?;
 
-            // Then pass on to inner service.
-            let inner_req = req.unlayer_tokens();
+            // Then pass on set_with action to inner service. As the
+            // patch is resolved here, no patcher is passed on.
+            let inner_req = ResourceCreateRequest::<IR> {
+                tokens: Layered::from(req.tokens).inner,
+                resource_kind: req.resource_kind,
+                slot_rev_rel_type: req.slot_rev_rel_type,
+                rep_update_action: RepUpdateAction::SetWith(effective_rep),
+                host_preconditions: req.host_preconditions,
+                credentials: req.credentials,
+                extensions: req.extensions,
+            };
             Ok(inner_svc
                 .ready()
                 .and_then(|svc| svc.call(inner_req))
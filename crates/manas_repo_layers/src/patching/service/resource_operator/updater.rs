@@ -9,7 +9,7 @@ use manas_repo::{
     service::{
         patcher_resolver::impl_::UnsupportedRepPatcher,
         resource_operator::{
-            common::rep_update_action::RepUpdateAction,
+            common::{rep_update_action::RepUpdateAction, status_token::impl_::layered::Layered},
             updater::{
                 ResourceUpdateRequest, ResourceUpdateResponse, ResourceUpdateTokenSet,
                 ResourceUpdater,
@@ -68,7 +68,7 @@ where
     }
 
     #[tracing::instrument(skip_all, name = "PatchingRepoResourceUpdater::call")]
-    fn call(&mut self, mut req: ResourceUpdateRequest<PatchingRepo<IR, P>>) -> Self::Future {
+    fn call(&mut self, req: ResourceUpdateRequest<PatchingRepo<IR, P>>) -> Self::Future {
         let mut inner_svc = self.inner.clone();
 
         Box::pin(async move {
@@ -79,10 +79,15 @@ where
                     })
                     .await?;
 
-            req.tokens = ResourceUpdateTokenSet { res_token };
-            req.rep_update_action = RepUpdateAction::SetWith(effective_rep);
-
-            let inner_req = req.unlayer_tokens();
+            // Pass on set_with action to inner service. As the
+            // patch is resolved here, no patcher is passed on.
+            let inner_req = ResourceUpdateRequest::<IR> {
+                tokens: Layered::from(ResourceUpdateTokenSet { res_token }).inner,
+                rep_update_action: RepUpdateAction::SetWith(effective_rep),
+                preconditions: req.preconditions,
+                credentials: req.credentials,
+                extensions: req.extensions,
+            };
             inner_svc.ready().await?.call(inner_req).await
         })
     }
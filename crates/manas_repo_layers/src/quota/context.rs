@@ -0,0 +1,49 @@
+//! I define an implementation of [`RepoContext`] for [`QuotaEnforcingRepo`](super::QuotaEnforcingRepo).
+//!
+
+use std::sync::Arc;
+
+use manas_http::representation::impl_::binary::BinaryRepresentation;
+use manas_repo::{
+    context::{LayeredRepoContext, RepoContext},
+    Repo,
+};
+
+use super::{tracker::QuotaUsageTracker, MRepo};
+
+/// An implementation of [`RepoContext`] for [`QuotaEnforcingRepo`](super::QuotaEnforcingRepo).
+#[derive(Debug)]
+pub struct QuotaEnforcingRepoContext<IR>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+{
+    /// Inner repo config.
+    pub inner: Arc<IR::Context>,
+
+    /// Usage tracker.
+    pub tracker: Arc<QuotaUsageTracker>,
+}
+
+impl<IR> RepoContext for QuotaEnforcingRepoContext<IR>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+{
+    type Repo = MRepo<IR>;
+
+    #[inline]
+    fn storage_space(&self) -> &Arc<IR::StSpace> {
+        self.inner.storage_space()
+    }
+}
+
+impl<IR> LayeredRepoContext for QuotaEnforcingRepoContext<IR>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+{
+    type InnerRepo = IR;
+
+    #[inline]
+    fn inner(&self) -> &Arc<IR::Context> {
+        &self.inner
+    }
+}
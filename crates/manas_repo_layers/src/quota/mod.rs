@@ -0,0 +1,182 @@
+//! I provide an implementation of [`Repo`] that enforces
+//! storage quota over representation update operations.
+//!
+
+use std::{marker::PhantomData, sync::Arc};
+
+use manas_http::representation::impl_::binary::BinaryRepresentation;
+use manas_repo::{
+    layer::RepoLayer,
+    policy::uri::impl_::DelegatedUriPolicy,
+    service::{
+        initializer::impl_::DelegatedRepoInitializer,
+        patcher_resolver::impl_::{UnsupportedRepPatcher, UnsupportedRepPatcherResolver},
+        resource_operator::{
+            common::{
+                impl_::DelegatingOperator,
+                status_token::impl_::layered::LayeredResourceStatusTokenTypes,
+            },
+            status_token_resolver::impl_::LayeredResourceStatusTokenResolver,
+        },
+    },
+    Repo, RepoInitializerService, RepoResourceReader, RepoResourceStatusTokenResolver,
+    RepoServices,
+};
+
+use self::{
+    context::QuotaEnforcingRepoContext,
+    service::resource_operator::{
+        creator::QuotaEnforcingRepoResourceCreator, deleter::QuotaEnforcingRepoResourceDeleter,
+        updater::QuotaEnforcingRepoResourceUpdater,
+    },
+    tracker::QuotaUsageTracker,
+};
+
+pub mod context;
+pub mod service;
+pub mod tracker;
+pub mod usage_scanner;
+
+#[cfg(feature = "versioning")]
+pub mod version_store;
+
+/// A layered implementation of [`Repo`] that enforces
+/// storage quota over representation update operations.
+///
+/// It tracks usage incrementally on resource create, update
+/// and delete operations, and rejects operations that would
+/// exceed the quota with [`QUOTA_EXCEEDED`](manas_repo::service::resource_operator::common::problem::QUOTA_EXCEEDED)
+/// problem.
+///
+/// Aux resources deleted along with a resource are released
+/// by resolving their status before the delete. Versions
+/// saved by a versioning layer below this layer are
+/// accounted only if it's version store is wrapped in a
+/// [`QuotaMeteredVersionStore`](version_store::QuotaMeteredVersionStore).
+///
+/// Usage is tracked by a [`QuotaUsageTracker`] in process
+/// memory. Hence it is accurate only if this process is the
+/// only writer to the backend. With multiple replicas, each
+/// replica enforces the quota over it's own writes, and
+/// counters should be periodically rebuilt using a
+/// [`QuotaUsageScanner`](usage_scanner::QuotaUsageScanner).
+///
+/// NOTE: This layer must be wrapped by a patching layer, like
+/// `PatchingRepo`, to
+/// support patching. Patching layer resolves patches into
+/// complete representations, that this layer can meter. This
+/// layer rejects any patch requests that reach it.
+#[derive(Debug, Clone)]
+pub struct QuotaEnforcingRepo<IR>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+{
+    context: Arc<QuotaEnforcingRepoContext<IR>>,
+}
+
+impl<IR> Repo for QuotaEnforcingRepo<IR>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+{
+    type StSpace = IR::StSpace;
+
+    type Representation = IR::Representation;
+
+    type Context = QuotaEnforcingRepoContext<IR>;
+
+    type UriPolicy = DelegatedUriPolicy<IR::UriPolicy, Self>;
+
+    type ResourceStatusTokenTypes =
+        LayeredResourceStatusTokenTypes<IR::ResourceStatusTokenTypes, Self>;
+
+    type RepPatcher = UnsupportedRepPatcher;
+
+    type Services = QuotaEnforcingRepoServices<IR>;
+
+    type Credentials = IR::Credentials;
+
+    #[inline]
+    fn new(context: Arc<Self::Context>) -> Self {
+        Self { context }
+    }
+
+    #[inline]
+    fn context(&self) -> &Arc<Self::Context> {
+        &self.context
+    }
+}
+
+/// Quick alias for `QuotaEnforcingRepo`
+pub(crate) type MRepo<IR> = QuotaEnforcingRepo<IR>;
+
+/// Services for [`QuotaEnforcingRepo`].
+#[derive(Debug, Clone)]
+pub struct QuotaEnforcingRepoServices<IR> {
+    _phantom: PhantomData<fn(IR)>,
+}
+
+impl<IR> RepoServices for QuotaEnforcingRepoServices<IR>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+{
+    type Repo = MRepo<IR>;
+
+    type Initializer = DelegatedRepoInitializer<RepoInitializerService<IR>, MRepo<IR>>;
+
+    type RepPatcherResolver = UnsupportedRepPatcherResolver<MRepo<IR>>;
+
+    type ResourceStatusTokenResolver =
+        LayeredResourceStatusTokenResolver<RepoResourceStatusTokenResolver<IR>, MRepo<IR>>;
+
+    type ResourceReader = DelegatingOperator<RepoResourceReader<IR>, MRepo<IR>>;
+
+    type ResourceCreator = QuotaEnforcingRepoResourceCreator<IR>;
+
+    type ResourceUpdater = QuotaEnforcingRepoResourceUpdater<IR>;
+
+    type ResourceDeleter = QuotaEnforcingRepoResourceDeleter<IR>;
+}
+
+/// An implementation of [`RepoLayer`] that layers quota
+/// enforcement functionality over repos.
+#[derive(Debug, Clone)]
+pub struct QuotaEnforcingRepoLayer<IR>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+{
+    tracker: Arc<QuotaUsageTracker>,
+    _phantom: PhantomData<fn(IR)>,
+}
+
+impl<IR> QuotaEnforcingRepoLayer<IR>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+{
+    /// Create a new [`QuotaEnforcingRepoLayer`] with given
+    /// usage tracker.
+    #[inline]
+    pub fn new(tracker: Arc<QuotaUsageTracker>) -> Self {
+        Self {
+            tracker,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<IR> RepoLayer<IR> for QuotaEnforcingRepoLayer<IR>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+{
+    type LayeredRepo = QuotaEnforcingRepo<IR>;
+
+    #[inline]
+    fn layer_context(
+        &self,
+        inner_context: Arc<<IR as Repo>::Context>,
+    ) -> <Self::LayeredRepo as Repo>::Context {
+        QuotaEnforcingRepoContext {
+            inner: inner_context,
+            tracker: self.tracker.clone(),
+        }
+    }
+}
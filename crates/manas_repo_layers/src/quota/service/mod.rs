@@ -0,0 +1,4 @@
+//! I provide repo service implementations for [`QuotaEnforcingRepo`](super::QuotaEnforcingRepo).
+//!
+
+pub mod resource_operator;
@@ -0,0 +1,114 @@
+//! I provide an implementation of [`ResourceCreator`] for [`QuotaEnforcingRepo`].
+//!
+
+use std::task::Poll;
+
+use dyn_problem::{ProbFuture, Problem};
+use manas_http::representation::impl_::binary::BinaryRepresentation;
+use manas_repo::{
+    service::resource_operator::{
+        common::{
+            problem::UNSUPPORTED_OPERATION, rep_update_action::RepUpdateAction,
+            status_token::impl_::layered::Layered,
+        },
+        creator::{ResourceCreateRequest, ResourceCreateResponse, ResourceCreator},
+    },
+    Repo, RepoResourceCreator,
+};
+use tower::{Service, ServiceExt};
+use tracing::error;
+
+use super::metering::RepWriteMeter;
+use crate::quota::{tracker::QuotaUsage, QuotaEnforcingRepo};
+
+/// An implementation of [`ResourceCreator`] for [`QuotaEnforcingRepo`]
+#[derive(Debug)]
+pub struct QuotaEnforcingRepoResourceCreator<IR: Repo> {
+    inner: RepoResourceCreator<IR>,
+}
+
+impl<IR: Repo> Default for QuotaEnforcingRepoResourceCreator<IR> {
+    fn default() -> Self {
+        Self {
+            inner: Default::default(),
+        }
+    }
+}
+
+impl<IR: Repo> Clone for QuotaEnforcingRepoResourceCreator<IR> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<IR> Service<ResourceCreateRequest<QuotaEnforcingRepo<IR>>>
+    for QuotaEnforcingRepoResourceCreator<IR>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+{
+    type Response = ResourceCreateResponse<QuotaEnforcingRepo<IR>>;
+
+    type Error = Problem;
+
+    type Future = ProbFuture<'static, Self::Response>;
+
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[tracing::instrument(skip_all, name = "QuotaEnforcingRepoResourceCreator::call")]
+    fn call(&mut self, req: ResourceCreateRequest<QuotaEnforcingRepo<IR>>) -> Self::Future {
+        let mut inner_svc = self.inner.clone();
+        Box::pin(async move {
+            let new_rep = match req.rep_update_action {
+                RepUpdateAction::SetWith(rep) => rep,
+                // Patches are resolved into complete
+                // representations by the patching layer, that
+                // must wrap this layer.
+                RepUpdateAction::PatchWith(_) => {
+                    error!("Quota enforcing repo doesn't support patch operation. It must be wrapped by a patching layer.");
+                    return Err(UNSUPPORTED_OPERATION.new_problem());
+                }
+            };
+
+            let Layered {
+                inner: inner_req_tokens,
+                layer_context,
+            } = Layered::from(req.tokens);
+
+            // Reserve usage for the new resource.
+            let (metered_rep, meter) = RepWriteMeter::try_new(
+                new_rep,
+                layer_context.tracker.clone(),
+                QuotaUsage::default(),
+                1,
+            )?;
+
+            let inner_req = ResourceCreateRequest {
+                tokens: inner_req_tokens,
+                resource_kind: req.resource_kind,
+                slot_rev_rel_type: req.slot_rev_rel_type,
+                rep_update_action: RepUpdateAction::SetWith(metered_rep),
+                host_preconditions: req.host_preconditions,
+                credentials: req.credentials,
+                extensions: req.extensions,
+            };
+
+            let result = match inner_svc.ready().await {
+                Ok(svc) => svc.call(inner_req).await,
+                Err(e) => Err(e),
+            };
+
+            Ok(meter.settle(result)?.map_repo())
+        })
+    }
+}
+
+impl<IR> ResourceCreator for QuotaEnforcingRepoResourceCreator<IR>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+{
+    type Repo = QuotaEnforcingRepo<IR>;
+}
@@ -0,0 +1,162 @@
+//! I provide an implementation of [`ResourceDeleter`] for [`QuotaEnforcingRepo`].
+//!
+
+use std::{sync::Arc, task::Poll};
+
+use dyn_problem::{ProbFuture, Problem};
+use manas_http::representation::impl_::binary::BinaryRepresentation;
+use manas_repo::{
+    service::resource_operator::{
+        common::status_token::{
+            impl_::layered::Layered, ExistingRepresentedResourceToken, ExistingResourceToken,
+            RepoResourceStatusTokenBase, ResourceStatusToken,
+        },
+        deleter::{
+            KDryRunDelete, ResourceDeleteRequest, ResourceDeleteResponse, ResourceDeleteTokenSet,
+            ResourceDeleter,
+        },
+    },
+    Repo, RepoExt, RepoResourceDeleter,
+};
+use manas_space::resource::slot_link::AuxLink;
+use tower::{Service, ServiceExt};
+use tracing::warn;
+use typed_record::TypedRecord;
+
+use crate::quota::{tracker::QuotaUsage, QuotaEnforcingRepo};
+
+/// An implementation of [`ResourceDeleter`] for [`QuotaEnforcingRepo`]
+#[derive(Debug)]
+pub struct QuotaEnforcingRepoResourceDeleter<IR: Repo> {
+    inner: RepoResourceDeleter<IR>,
+}
+
+impl<IR: Repo> Default for QuotaEnforcingRepoResourceDeleter<IR> {
+    fn default() -> Self {
+        Self {
+            inner: Default::default(),
+        }
+    }
+}
+
+impl<IR: Repo> Clone for QuotaEnforcingRepoResourceDeleter<IR> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<IR> Service<ResourceDeleteRequest<QuotaEnforcingRepo<IR>>>
+    for QuotaEnforcingRepoResourceDeleter<IR>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+{
+    type Response = ResourceDeleteResponse<QuotaEnforcingRepo<IR>>;
+
+    type Error = Problem;
+
+    type Future = ProbFuture<'static, Self::Response>;
+
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[tracing::instrument(skip_all, name = "QuotaEnforcingRepoResourceDeleter::call")]
+    fn call(&mut self, req: ResourceDeleteRequest<QuotaEnforcingRepo<IR>>) -> Self::Future {
+        let mut inner_svc = self.inner.clone();
+
+        Box::pin(async move {
+            let Layered {
+                inner: inner_req_tokens,
+                layer_context,
+            } = Layered::from(req.tokens);
+
+            // Usage of the current representation.
+            let rep_bytes = inner_req_tokens
+                .res_token
+                .rep_content_length()
+                .unwrap_or_else(|| {
+                    warn!("Current representation size is unknown.");
+                    0
+                });
+
+            let is_dry_run = req.extensions.get_rv::<KDryRunDelete>().is_some();
+
+            // Usage of represented aux resources, that will be
+            // deleted along with the resource.
+            let aux_usage = if is_dry_run {
+                QuotaUsage::default()
+            } else {
+                resolve_aux_usage::<IR>(
+                    inner_req_tokens.res_token.repo_context().clone(),
+                    inner_req_tokens.res_token.aux_links_index(),
+                )
+                .await
+            };
+
+            let inner_req = ResourceDeleteRequest::<IR> {
+                tokens: ResourceDeleteTokenSet {
+                    res_token: inner_req_tokens.res_token,
+                },
+                preconditions: req.preconditions,
+                credentials: req.credentials,
+                extensions: req.extensions,
+            };
+
+            let resp = inner_svc.ready().await?.call(inner_req).await?;
+
+            if is_dry_run {
+                return Ok(resp.map_repo());
+            }
+
+            // Release usage of the resource, and of deleted aux
+            // resources.
+            layer_context.tracker.release(QuotaUsage {
+                bytes: rep_bytes + aux_usage.bytes,
+                resources: 1 + aux_usage.resources,
+            });
+
+            Ok(resp.map_repo())
+        })
+    }
+}
+
+/// Resolve usage of represented aux resources with given
+/// links.
+async fn resolve_aux_usage<IR>(
+    repo_context: Arc<IR::Context>,
+    aux_links: Vec<AuxLink<IR::StSpace>>,
+) -> QuotaUsage
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+{
+    let repo = IR::new(repo_context);
+    let mut usage = QuotaUsage::default();
+
+    for aux_link in aux_links {
+        match repo.resolve_status_token(aux_link.target.clone()).await {
+            Ok(ResourceStatusToken::Existing(ExistingResourceToken::Represented(token))) => {
+                usage.resources += 1;
+                usage.bytes += token.rep_content_length().unwrap_or_else(|| {
+                    warn!("Aux representation size is unknown.");
+                    0
+                });
+            }
+            // Non-represented aux resources use nothing.
+            Ok(_) => {}
+            Err(e) => {
+                warn!("Error in resolving aux resource status. Error:\n {}", e);
+            }
+        }
+    }
+
+    usage
+}
+
+impl<IR> ResourceDeleter for QuotaEnforcingRepoResourceDeleter<IR>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+{
+    type Repo = QuotaEnforcingRepo<IR>;
+}
@@ -0,0 +1,299 @@
+//! I define utilities to meter representation writes against
+//! quota.
+//!
+
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc,
+};
+
+use dyn_problem::Problem;
+use either::Either;
+use futures::StreamExt;
+use manas_http::{
+    representation::{impl_::binary::BinaryRepresentation, Representation},
+    BoxError,
+};
+use manas_repo::service::resource_operator::common::problem::QUOTA_EXCEEDED;
+use tracing::error;
+
+use crate::quota::tracker::{QuotaExceededError, QuotaUsage, QuotaUsageTracker};
+
+/// A meter for a representation write.
+///
+/// It reserves the declared usage of the new representation
+/// up front, enforces the quota over the streamed bytes, and
+/// settles the reservation with the actual usage once the
+/// write completes.
+pub(crate) struct RepWriteMeter {
+    tracker: Arc<QuotaUsageTracker>,
+
+    /// Usage being replaced.
+    prior: QuotaUsage,
+
+    /// Reserved usage.
+    reserved: QuotaUsage,
+
+    /// Resulting resource count.
+    next_resources: u64,
+
+    /// Size of written representation. Either exact size, or
+    /// counter of streamed bytes.
+    written: Either<u64, Arc<AtomicU64>>,
+
+    /// Whether streamed bytes exceeded the quota.
+    exceeded: Arc<AtomicBool>,
+}
+
+impl RepWriteMeter {
+    /// Try to create a new meter for writing given
+    /// representation, replacing given prior usage. Returns
+    /// the metered representation along with the meter.
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn try_new(
+        rep: BinaryRepresentation,
+        tracker: Arc<QuotaUsageTracker>,
+        prior: QuotaUsage,
+        next_resources: u64,
+    ) -> Result<(BinaryRepresentation, Self), Problem> {
+        // Declared size of the new representation. If upper
+        // bound is unknown, lower bound is reserved, and
+        // streamed bytes are limited by available quota.
+        let size_hint = rep.data().size_hint();
+        let (declared_bytes, is_upper_bound) = match size_hint.upper() {
+            Some(upper) => (upper, true),
+            None => (size_hint.lower(), false),
+        };
+
+        let reserved = QuotaUsage {
+            bytes: declared_bytes.saturating_sub(prior.bytes),
+            resources: next_resources.saturating_sub(prior.resources),
+        };
+
+        tracker.try_reserve(reserved).map_err(|e| {
+            error!("Representation write would exceed quota.");
+            QUOTA_EXCEEDED.new_problem_builder().source(e).finish()
+        })?;
+
+        let exceeded = Arc::new(AtomicBool::new(false));
+
+        let (rep, written) = match rep.into_either() {
+            Either::Left(rep) => {
+                // Bytes allowed for the streamed representation.
+                let allowed_bytes = if is_upper_bound {
+                    Some(prior.bytes + reserved.bytes)
+                } else {
+                    tracker
+                        .available_bytes()
+                        .map(|available| prior.bytes + reserved.bytes + available)
+                };
+
+                let counter = Arc::new(AtomicU64::new(0));
+                let mut rep = rep.into_basic();
+                let (stream_counter, stream_exceeded) = (counter.clone(), exceeded.clone());
+                rep.data.stream = rep
+                    .data
+                    .stream
+                    .map(move |chunk_result| {
+                        let chunk = chunk_result?;
+                        let streamed = stream_counter
+                            .fetch_add(chunk.len() as u64, Ordering::AcqRel)
+                            + chunk.len() as u64;
+
+                        if allowed_bytes.map_or(false, |allowed| streamed > allowed) {
+                            stream_exceeded.store(true, Ordering::Release);
+                            return Err(Box::new(QuotaExceededError) as BoxError);
+                        }
+                        Ok(chunk)
+                    })
+                    .boxed();
+
+                (rep.into_binary(), Either::Right(counter))
+            }
+            Either::Right(rep) => (rep.into(), Either::Left(declared_bytes)),
+        };
+
+        Ok((
+            rep,
+            Self {
+                tracker,
+                prior,
+                reserved,
+                next_resources,
+                written,
+                exceeded,
+            },
+        ))
+    }
+
+    /// Settle the reservation with the result of the write.
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn settle<T>(self, result: Result<T, Problem>) -> Result<T, Problem> {
+        self.tracker.release(self.reserved);
+
+        match result {
+            Ok(v) => {
+                self.tracker.record(QuotaUsage {
+                    bytes: match &self.written {
+                        Either::Left(size) => *size,
+                        Either::Right(counter) => counter.load(Ordering::Acquire),
+                    },
+                    resources: self.next_resources,
+                });
+                self.tracker.release(self.prior);
+                Ok(v)
+            }
+            Err(e) => {
+                if self.exceeded.load(Ordering::Acquire) {
+                    error!("Streamed representation exceeded quota.");
+                    Err(QUOTA_EXCEEDED
+                        .new_problem_builder()
+                        .source(QuotaExceededError)
+                        .finish())
+                } else {
+                    Err(e)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use claims::*;
+    use futures::{stream, TryStreamExt};
+    use http_body::SizeHint;
+    use manas_http::representation::{
+        impl_::{
+            basic::BasicRepresentation,
+            common::data::{bytes_inmem::BytesInmem, bytes_stream::BytesStream},
+        },
+        metadata::RepresentationMetadata,
+    };
+    use manas_repo::service::resource_operator::common::problem::UNSUPPORTED_OPERATION;
+    use rstest::*;
+
+    use super::*;
+    use crate::quota::tracker::QuotaLimits;
+
+    fn usage(bytes: u64, resources: u64) -> QuotaUsage {
+        QuotaUsage { bytes, resources }
+    }
+
+    fn tracker(max_bytes: u64, initial: QuotaUsage) -> Arc<QuotaUsageTracker> {
+        Arc::new(QuotaUsageTracker::new(
+            QuotaLimits {
+                max_bytes: Some(max_bytes),
+                max_resources: None,
+            },
+            initial,
+        ))
+    }
+
+    fn inmem_rep(size: usize) -> BinaryRepresentation {
+        BasicRepresentation {
+            metadata: RepresentationMetadata::new(),
+            data: BytesInmem::from(Bytes::from(vec![0u8; size])),
+            base_uri: None,
+        }
+        .into()
+    }
+
+    /// Get a streaming rep of given chunk sizes, with unknown
+    /// size.
+    fn streaming_rep(chunk_sizes: &[usize]) -> BinaryRepresentation {
+        let chunks = chunk_sizes
+            .iter()
+            .map(|size| Ok(Bytes::from(vec![0u8; *size])))
+            .collect::<Vec<_>>();
+
+        BasicRepresentation {
+            metadata: RepresentationMetadata::new(),
+            data: BytesStream {
+                stream: Box::pin(stream::iter(chunks)),
+                size_hint: SizeHint::new(),
+            },
+            base_uri: None,
+        }
+        .into()
+    }
+
+    /// Consume the data of given rep, as a writer does.
+    async fn consume(rep: BinaryRepresentation) -> Result<u64, Problem> {
+        rep.into_streaming()
+            .into_basic()
+            .data
+            .stream
+            .try_fold(0, |n, chunk| async move { Ok(n + chunk.len() as u64) })
+            .await
+            .map_err(|_| UNSUPPORTED_OPERATION.new_problem())
+    }
+
+    #[rstest]
+    #[case::new_resource(inmem_rep(40), usage(0, 0), usage(90, 6))]
+    #[case::replacing_rep(inmem_rep(10), usage(30, 1), usage(30, 5))]
+    #[case::streamed_rep(streaming_rep(&[10, 20]), usage(0, 0), usage(80, 6))]
+    #[tokio::test]
+    async fn settled_write_records_actual_usage(
+        #[case] rep: BinaryRepresentation,
+        #[case] prior: QuotaUsage,
+        #[case] expected: QuotaUsage,
+    ) {
+        let tracker = tracker(100, usage(50, 5));
+
+        let (metered_rep, meter) =
+            assert_ok!(RepWriteMeter::try_new(rep, tracker.clone(), prior, 1));
+        assert_ok!(consume(metered_rep).await);
+        assert_ok!(meter.settle(Ok(())));
+
+        assert_eq!(tracker.usage(), expected);
+    }
+
+    #[tokio::test]
+    async fn declared_excess_is_rejected_before_write() {
+        let tracker = tracker(100, usage(50, 5));
+
+        let Err(e) = RepWriteMeter::try_new(inmem_rep(60), tracker.clone(), usage(0, 0), 1) else {
+            panic!("Write must be rejected.");
+        };
+        assert!(QUOTA_EXCEEDED.is_type_of(&e));
+        assert_eq!(tracker.usage(), usage(50, 5));
+    }
+
+    #[tokio::test]
+    async fn streamed_excess_is_rejected() {
+        let tracker = tracker(100, usage(50, 5));
+
+        let (metered_rep, meter) = assert_ok!(RepWriteMeter::try_new(
+            streaming_rep(&[30, 30]),
+            tracker.clone(),
+            usage(0, 0),
+            1
+        ));
+
+        let write_result = consume(metered_rep).await;
+        assert!(write_result.is_err());
+
+        let e = assert_err!(meter.settle(write_result));
+        assert!(QUOTA_EXCEEDED.is_type_of(&e));
+        assert_eq!(tracker.usage(), usage(50, 5));
+    }
+
+    #[tokio::test]
+    async fn failed_write_releases_reservation() {
+        let tracker = tracker(100, usage(50, 5));
+
+        let (_, meter) = assert_ok!(RepWriteMeter::try_new(
+            inmem_rep(40),
+            tracker.clone(),
+            usage(0, 0),
+            1
+        ));
+        assert_eq!(tracker.usage(), usage(90, 6));
+
+        let e = assert_err!(meter.settle::<()>(Err(UNSUPPORTED_OPERATION.new_problem())));
+        assert!(UNSUPPORTED_OPERATION.is_type_of(&e));
+        assert_eq!(tracker.usage(), usage(50, 5));
+    }
+}
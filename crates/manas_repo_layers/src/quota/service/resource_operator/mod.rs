@@ -0,0 +1,8 @@
+//! I provide resource operator service implementations for [`QuotaEnforcingRepo`](super::super::QuotaEnforcingRepo).
+//!
+
+pub mod creator;
+pub mod deleter;
+pub mod updater;
+
+pub(crate) mod metering;
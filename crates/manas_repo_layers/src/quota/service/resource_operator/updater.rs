@@ -0,0 +1,129 @@
+//! I provide an implementation of [`ResourceUpdater`] for [`QuotaEnforcingRepo`].
+//!
+
+use std::task::Poll;
+
+use dyn_problem::{ProbFuture, Problem};
+use manas_http::representation::impl_::binary::BinaryRepresentation;
+use manas_repo::{
+    service::resource_operator::{
+        common::{
+            problem::UNSUPPORTED_OPERATION,
+            rep_update_action::RepUpdateAction,
+            status_token::{
+                impl_::layered::Layered, ExistingRepresentedResourceToken, ExistingResourceToken,
+            },
+        },
+        updater::{
+            ResourceUpdateRequest, ResourceUpdateResponse, ResourceUpdateTokenSet, ResourceUpdater,
+        },
+    },
+    Repo, RepoResourceUpdater,
+};
+use tower::{Service, ServiceExt};
+use tracing::{error, warn};
+
+use super::metering::RepWriteMeter;
+use crate::quota::{tracker::QuotaUsage, QuotaEnforcingRepo};
+
+/// An implementation of [`ResourceUpdater`] for [`QuotaEnforcingRepo`]
+#[derive(Debug)]
+pub struct QuotaEnforcingRepoResourceUpdater<IR: Repo> {
+    inner: RepoResourceUpdater<IR>,
+}
+
+impl<IR: Repo> Default for QuotaEnforcingRepoResourceUpdater<IR> {
+    fn default() -> Self {
+        Self {
+            inner: Default::default(),
+        }
+    }
+}
+
+impl<IR: Repo> Clone for QuotaEnforcingRepoResourceUpdater<IR> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<IR> Service<ResourceUpdateRequest<QuotaEnforcingRepo<IR>>>
+    for QuotaEnforcingRepoResourceUpdater<IR>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+{
+    type Response = ResourceUpdateResponse;
+
+    type Error = Problem;
+
+    type Future = ProbFuture<'static, Self::Response>;
+
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[tracing::instrument(skip_all, name = "QuotaEnforcingRepoResourceUpdater::call")]
+    fn call(&mut self, req: ResourceUpdateRequest<QuotaEnforcingRepo<IR>>) -> Self::Future {
+        let mut inner_svc = self.inner.clone();
+
+        Box::pin(async move {
+            let new_rep = match req.rep_update_action {
+                RepUpdateAction::SetWith(rep) => rep,
+                // Patches are resolved into complete
+                // representations by the patching layer, that
+                // must wrap this layer.
+                RepUpdateAction::PatchWith(_) => {
+                    error!("Quota enforcing repo doesn't support patch operation. It must be wrapped by a patching layer.");
+                    return Err(UNSUPPORTED_OPERATION.new_problem());
+                }
+            };
+
+            let Layered {
+                inner: inner_req_tokens,
+                layer_context,
+            } = Layered::from(req.tokens);
+
+            // Usage of the current representation, if any.
+            // Non-represented resources use nothing.
+            let prior = match &inner_req_tokens.res_token {
+                ExistingResourceToken::Represented(token) => QuotaUsage {
+                    bytes: token.rep_content_length().unwrap_or_else(|| {
+                        warn!("Current representation size is unknown.");
+                        0
+                    }),
+                    resources: 1,
+                },
+                ExistingResourceToken::NonRepresented(_) => QuotaUsage::default(),
+            };
+
+            // Reserve usage for the new representation.
+            let (metered_rep, meter) =
+                RepWriteMeter::try_new(new_rep, layer_context.tracker.clone(), prior, 1)?;
+
+            let inner_req = ResourceUpdateRequest::<IR> {
+                tokens: ResourceUpdateTokenSet {
+                    res_token: inner_req_tokens.res_token,
+                },
+                rep_update_action: RepUpdateAction::SetWith(metered_rep),
+                preconditions: req.preconditions,
+                credentials: req.credentials,
+                extensions: req.extensions,
+            };
+
+            let result = match inner_svc.ready().await {
+                Ok(svc) => svc.call(inner_req).await,
+                Err(e) => Err(e),
+            };
+
+            meter.settle(result)
+        })
+    }
+}
+
+impl<IR> ResourceUpdater for QuotaEnforcingRepoResourceUpdater<IR>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+{
+    type Repo = QuotaEnforcingRepo<IR>;
+}
@@ -0,0 +1,334 @@
+//! I define [`QuotaUsageTracker`], that tracks storage usage
+//! against configured quota limits.
+//!
+
+use std::{
+    ops::Deref,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use dyn_problem::Problem;
+use manas_space::resource::uri::SolidResourceUri;
+use rdf_utils::model::{
+    term::{ArcTerm, CompatTerm},
+    triple::ArcTriple,
+};
+use rdf_vocabularies::ns;
+use sophia_api::term::Term;
+use tracing::info;
+
+use super::usage_scanner::QuotaUsageScanner;
+
+/// Quota limits of a storage.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QuotaLimits {
+    /// Maximum total size of resource representations in
+    /// bytes.
+    pub max_bytes: Option<u64>,
+
+    /// Maximum number of resources.
+    pub max_resources: Option<u64>,
+}
+
+/// Usage of a storage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuotaUsage {
+    /// Total size of resource representations in bytes.
+    pub bytes: u64,
+
+    /// Number of represented resources. Auxiliary resources
+    /// that exist without a representation are not counted.
+    pub resources: u64,
+}
+
+/// An error to represent that an operation would exceed the
+/// quota.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Operation would exceed storage quota.")]
+pub struct QuotaExceededError;
+
+/// A tracker of storage usage against quota limits.
+///
+/// Usage is tracked with reservations. Writers reserve
+/// required usage before writing, and settle reservations
+/// after the write completes. Thus concurrent writers can
+/// never collectively exceed the limits.
+///
+/// Usage is tracked in process memory. Tracker doesn't
+/// observe writes by other processes sharing the backend,
+/// like other replicas. In such deployments, each process
+/// limits only it's own writes, and usage should be rebuilt
+/// periodically with [`Self::rebuild`].
+#[derive(Debug, Default)]
+pub struct QuotaUsageTracker {
+    /// Quota limits.
+    limits: QuotaLimits,
+
+    /// Used bytes.
+    bytes: AtomicU64,
+
+    /// Used resource count.
+    resources: AtomicU64,
+}
+
+impl QuotaUsageTracker {
+    /// Create a new [`QuotaUsageTracker`] with given limits,
+    /// and given initial usage.
+    pub fn new(limits: QuotaLimits, usage: QuotaUsage) -> Self {
+        Self {
+            limits,
+            bytes: AtomicU64::new(usage.bytes),
+            resources: AtomicU64::new(usage.resources),
+        }
+    }
+
+    /// Get quota limits.
+    #[inline]
+    pub fn limits(&self) -> &QuotaLimits {
+        &self.limits
+    }
+
+    /// Get current usage, including any pending reservations.
+    #[inline]
+    pub fn usage(&self) -> QuotaUsage {
+        QuotaUsage {
+            bytes: self.bytes.load(Ordering::Acquire),
+            resources: self.resources.load(Ordering::Acquire),
+        }
+    }
+
+    /// Get number of bytes available under the quota, if
+    /// bytes are limited.
+    #[inline]
+    pub fn available_bytes(&self) -> Option<u64> {
+        self.limits
+            .max_bytes
+            .map(|max_bytes| max_bytes.saturating_sub(self.bytes.load(Ordering::Acquire)))
+    }
+
+    /// Reset usage to given value.
+    ///
+    /// Any pending reservations will be lost. Hence it
+    /// should be called only when storage is quiescent.
+    pub fn reset(&self, usage: QuotaUsage) {
+        self.bytes.store(usage.bytes, Ordering::Release);
+        self.resources.store(usage.resources, Ordering::Release);
+    }
+
+    /// Rebuild usage counters from the usage scanned by given
+    /// scanner.
+    ///
+    /// Any pending reservations will be lost. Hence it
+    /// should be called only when storage is quiescent, for
+    /// example at startup.
+    pub async fn rebuild<S: QuotaUsageScanner>(&self, scanner: &S) -> Result<QuotaUsage, Problem> {
+        let usage = scanner.scan_usage().await?;
+        info!("Rebuilt quota usage: {:?}", usage);
+        self.reset(usage);
+        Ok(usage)
+    }
+
+    /// Try to reserve given usage.
+    pub fn try_reserve(&self, usage: QuotaUsage) -> Result<(), QuotaExceededError> {
+        Self::try_add(&self.bytes, usage.bytes, self.limits.max_bytes)?;
+
+        if let Err(e) = Self::try_add(&self.resources, usage.resources, self.limits.max_resources) {
+            // Rollback bytes reservation.
+            Self::sub(&self.bytes, usage.bytes);
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Release given usage.
+    #[inline]
+    pub fn release(&self, usage: QuotaUsage) {
+        Self::sub(&self.bytes, usage.bytes);
+        Self::sub(&self.resources, usage.resources);
+    }
+
+    /// Record given usage. Recording is not limited, as the
+    /// usage has already happened.
+    #[inline]
+    pub fn record(&self, usage: QuotaUsage) {
+        self.bytes.fetch_add(usage.bytes, Ordering::AcqRel);
+        self.resources.fetch_add(usage.resources, Ordering::AcqRel);
+    }
+
+    /// Get storage description statements advertising
+    /// current usage and quota of the storage with given root.
+    pub fn description_statements(&self, storage_root_uri: &SolidResourceUri) -> Vec<ArcTriple> {
+        let root: ArcTerm = storage_root_uri.deref().into_term();
+        let mut statements = vec![[
+            root.clone(),
+            ns::solid::storageUsage.into_term(),
+            CompatTerm(self.bytes.load(Ordering::Acquire)).into_term(),
+        ]];
+
+        if let Some(max_bytes) = self.limits.max_bytes {
+            statements.push([
+                root,
+                ns::solid::storageQuota.into_term(),
+                CompatTerm(max_bytes).into_term(),
+            ]);
+        }
+
+        statements
+    }
+
+    fn try_add(
+        counter: &AtomicU64,
+        delta: u64,
+        limit: Option<u64>,
+    ) -> Result<(), QuotaExceededError> {
+        counter
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |current| {
+                let next = current.checked_add(delta)?;
+                match limit {
+                    Some(limit) if next > limit && delta > 0 => None,
+                    _ => Some(next),
+                }
+            })
+            .map(|_| ())
+            .map_err(|_| QuotaExceededError)
+    }
+
+    #[inline]
+    fn sub(counter: &AtomicU64, delta: u64) {
+        let _ = counter.fetch_update(Ordering::AcqRel, Ordering::Acquire, |current| {
+            Some(current.saturating_sub(delta))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use claims::*;
+    use rstest::*;
+
+    use super::*;
+
+    fn usage(bytes: u64, resources: u64) -> QuotaUsage {
+        QuotaUsage { bytes, resources }
+    }
+
+    fn tracker(max_bytes: Option<u64>, max_resources: Option<u64>) -> QuotaUsageTracker {
+        QuotaUsageTracker::new(
+            QuotaLimits {
+                max_bytes,
+                max_resources,
+            },
+            usage(60, 6),
+        )
+    }
+
+    #[rstest]
+    #[case(usage(40, 4), true)]
+    #[case(usage(41, 0), false)]
+    #[case(usage(0, 5), false)]
+    #[case(usage(0, 0), true)]
+    fn try_reserve_enforces_limits(#[case] delta: QuotaUsage, #[case] expected_ok: bool) {
+        let tracker = tracker(Some(100), Some(10));
+
+        if expected_ok {
+            assert_ok!(tracker.try_reserve(delta));
+            assert_eq!(
+                tracker.usage(),
+                usage(60 + delta.bytes, 6 + delta.resources)
+            );
+        } else {
+            assert_err!(tracker.try_reserve(delta));
+            // Failed reservations reserve nothing.
+            assert_eq!(tracker.usage(), usage(60, 6));
+        }
+    }
+
+    #[test]
+    fn unlimited_usage_is_always_reservable() {
+        let tracker = tracker(None, None);
+        assert_ok!(tracker.try_reserve(usage(u32::MAX as u64, u32::MAX as u64)));
+        assert_none!(tracker.available_bytes());
+    }
+
+    #[test]
+    fn release_saturates_at_zero() {
+        let tracker = tracker(Some(100), Some(10));
+        tracker.release(usage(70, 2));
+        assert_eq!(tracker.usage(), usage(0, 4));
+    }
+
+    #[test]
+    fn record_is_not_limited() {
+        let tracker = tracker(Some(100), Some(10));
+        tracker.record(usage(50, 5));
+        assert_eq!(tracker.usage(), usage(110, 11));
+        assert_eq!(tracker.available_bytes(), Some(0));
+
+        // Any further usage is rejected.
+        assert_err!(tracker.try_reserve(usage(1, 0)));
+        assert_ok!(tracker.try_reserve(usage(0, 0)));
+    }
+
+    #[test]
+    fn concurrent_reservations_never_exceed_limits() {
+        let tracker = Arc::new(tracker(Some(100), None));
+
+        let handles = (0..8)
+            .map(|_| {
+                let tracker = tracker.clone();
+                std::thread::spawn(move || {
+                    (0..100)
+                        .filter(|_| tracker.try_reserve(usage(1, 0)).is_ok())
+                        .count()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let reserved: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+        assert_eq!(reserved, 40);
+        assert_eq!(tracker.usage().bytes, 100);
+    }
+
+    #[derive(Debug)]
+    struct MockScanner(QuotaUsage);
+
+    impl QuotaUsageScanner for MockScanner {
+        fn scan_usage(&self) -> dyn_problem::ProbFuture<'static, QuotaUsage> {
+            let usage = self.0;
+            Box::pin(async move { Ok(usage) })
+        }
+    }
+
+    #[tokio::test]
+    async fn rebuild_resets_usage_to_scanned_usage() {
+        let tracker = tracker(Some(100), Some(10));
+        assert_ok!(tracker.try_reserve(usage(10, 1)));
+
+        assert_eq!(
+            assert_ok!(tracker.rebuild(&MockScanner(usage(25, 3))).await),
+            usage(25, 3)
+        );
+        assert_eq!(tracker.usage(), usage(25, 3));
+        assert_eq!(tracker.available_bytes(), Some(75));
+    }
+
+    #[rstest]
+    #[case(Some(100), 2)]
+    #[case(None, 1)]
+    fn description_statements_advertise_quota_if_limited(
+        #[case] max_bytes: Option<u64>,
+        #[case] expected_count: usize,
+    ) {
+        let tracker = tracker(max_bytes, None);
+        let root_uri =
+            SolidResourceUri::try_new_from("http://pod.example.org/").expect("Must be valid.");
+
+        assert_eq!(
+            tracker.description_statements(&root_uri).len(),
+            expected_count
+        );
+    }
+}
@@ -0,0 +1,22 @@
+//! I define [`QuotaUsageScanner`] trait, for rebuilding quota
+//! usage counters from the backend state.
+//!
+
+use std::fmt::Debug;
+
+use dyn_problem::ProbFuture;
+
+use super::tracker::QuotaUsage;
+
+/// A trait for scanners, that can compute usage of a storage
+/// by scanning it's backend.
+///
+/// Usage reported must be consistent with that tracked by
+/// the quota enforcing layer. I.e. bytes must be the total
+/// size of resource representations and saved versions,
+/// and resources must be the number of represented
+/// resources.
+pub trait QuotaUsageScanner: Debug + Send + Sync + 'static {
+    /// Scan the backend, and compute usage.
+    fn scan_usage(&self) -> ProbFuture<'static, QuotaUsage>;
+}
@@ -0,0 +1,289 @@
+//! I define [`QuotaMeteredVersionStore`], that accounts
+//! versions saved by versioning layer against quota.
+//!
+
+use std::{sync::Arc, time::SystemTime};
+
+use dyn_problem::ProbFuture;
+use manas_http::representation::impl_::binary::BinaryRepresentation;
+use manas_space::resource::uri::SolidResourceUri;
+
+use super::{
+    service::resource_operator::metering::RepWriteMeter,
+    tracker::{QuotaUsage, QuotaUsageTracker},
+};
+use crate::versioning::version_store::VersionStore;
+
+/// An implementation of [`VersionStore`], that meters the
+/// versions saved into, and deleted from inner store
+/// against quota.
+///
+/// Versioning layer snapshots prior representations below
+/// the quota enforcing layer. Hence it's version store must
+/// be wrapped in this, so that versions consume the storage
+/// quota. Versions are accounted as bytes, but not as
+/// resources.
+#[derive(Debug)]
+pub struct QuotaMeteredVersionStore<VS> {
+    inner: Arc<VS>,
+    tracker: Arc<QuotaUsageTracker>,
+}
+
+impl<VS: VersionStore> QuotaMeteredVersionStore<VS> {
+    /// Create a new [`QuotaMeteredVersionStore`] with given
+    /// inner store and usage tracker.
+    #[inline]
+    pub fn new(inner: Arc<VS>, tracker: Arc<QuotaUsageTracker>) -> Self {
+        Self { inner, tracker }
+    }
+
+    /// Get the inner store.
+    #[inline]
+    pub fn inner(&self) -> &Arc<VS> {
+        &self.inner
+    }
+}
+
+impl<VS: VersionStore> VersionStore for QuotaMeteredVersionStore<VS> {
+    #[inline]
+    fn list_versions(&self, res_uri: &SolidResourceUri) -> ProbFuture<'static, Vec<SystemTime>> {
+        self.inner.list_versions(res_uri)
+    }
+
+    fn save_version(
+        &self,
+        res_uri: &SolidResourceUri,
+        datetime: SystemTime,
+        rep: BinaryRepresentation,
+    ) -> ProbFuture<'static, ()> {
+        let inner = self.inner.clone();
+        let tracker = self.tracker.clone();
+        let res_uri = res_uri.clone();
+
+        Box::pin(async move {
+            // Version with same datetime will be replaced.
+            let prior_bytes = inner
+                .version_size(&res_uri, datetime)
+                .await?
+                .unwrap_or_default();
+
+            let (metered_rep, meter) = RepWriteMeter::try_new(
+                rep,
+                tracker,
+                QuotaUsage {
+                    bytes: prior_bytes,
+                    resources: 0,
+                },
+                0,
+            )?;
+
+            meter.settle(inner.save_version(&res_uri, datetime, metered_rep).await)
+        })
+    }
+
+    #[inline]
+    fn read_version(
+        &self,
+        res_uri: &SolidResourceUri,
+        datetime: SystemTime,
+    ) -> ProbFuture<'static, BinaryRepresentation> {
+        self.inner.read_version(res_uri, datetime)
+    }
+
+    #[inline]
+    fn version_size(
+        &self,
+        res_uri: &SolidResourceUri,
+        datetime: SystemTime,
+    ) -> ProbFuture<'static, Option<u64>> {
+        self.inner.version_size(res_uri, datetime)
+    }
+
+    fn delete_version(
+        &self,
+        res_uri: &SolidResourceUri,
+        datetime: SystemTime,
+    ) -> ProbFuture<'static, ()> {
+        let inner = self.inner.clone();
+        let tracker = self.tracker.clone();
+        let res_uri = res_uri.clone();
+
+        Box::pin(async move {
+            let bytes = inner
+                .version_size(&res_uri, datetime)
+                .await?
+                .unwrap_or_default();
+
+            inner.delete_version(&res_uri, datetime).await?;
+            tracker.release(QuotaUsage {
+                bytes,
+                resources: 0,
+            });
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        sync::Mutex,
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    use bytes::Bytes;
+    use claims::*;
+    use dyn_problem::type_::UNKNOWN_IO_ERROR;
+    use manas_http::representation::{
+        impl_::{basic::BasicRepresentation, common::data::bytes_inmem::BytesInmem},
+        metadata::RepresentationMetadata,
+    };
+
+    use super::*;
+    use crate::quota::tracker::QuotaLimits;
+
+    /// An in-memory version store.
+    #[derive(Debug, Default)]
+    struct MockVersionStore {
+        versions: Arc<Mutex<BTreeMap<SystemTime, BasicRepresentation<BytesInmem>>>>,
+    }
+
+    impl VersionStore for MockVersionStore {
+        fn list_versions(&self, _: &SolidResourceUri) -> ProbFuture<'static, Vec<SystemTime>> {
+            let versions = self.versions.lock().unwrap().keys().copied().collect();
+            Box::pin(async move { Ok(versions) })
+        }
+
+        fn save_version(
+            &self,
+            _: &SolidResourceUri,
+            datetime: SystemTime,
+            rep: BinaryRepresentation,
+        ) -> ProbFuture<'static, ()> {
+            let versions = self.versions.clone();
+            Box::pin(async move {
+                let rep: BinaryRepresentation<BytesInmem> =
+                    async_convert::TryFrom::try_from(rep).await.map_err(|e| {
+                        UNKNOWN_IO_ERROR
+                            .new_problem_builder()
+                            .source_in_a_box(e)
+                            .finish()
+                    })?;
+                versions.lock().unwrap().insert(datetime, rep.into_basic());
+                Ok(())
+            })
+        }
+
+        fn read_version(
+            &self,
+            _: &SolidResourceUri,
+            datetime: SystemTime,
+        ) -> ProbFuture<'static, BinaryRepresentation> {
+            let rep = self.versions.lock().unwrap().get(&datetime).cloned();
+            Box::pin(async move {
+                rep.map(Into::into)
+                    .ok_or_else(|| UNKNOWN_IO_ERROR.new_problem())
+            })
+        }
+
+        fn version_size(
+            &self,
+            _: &SolidResourceUri,
+            datetime: SystemTime,
+        ) -> ProbFuture<'static, Option<u64>> {
+            let size = self
+                .versions
+                .lock()
+                .unwrap()
+                .get(&datetime)
+                .map(|rep| rep.data.size());
+            Box::pin(async move { Ok(size) })
+        }
+
+        fn delete_version(
+            &self,
+            _: &SolidResourceUri,
+            datetime: SystemTime,
+        ) -> ProbFuture<'static, ()> {
+            self.versions.lock().unwrap().remove(&datetime);
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    fn rep(size: usize) -> BinaryRepresentation {
+        BasicRepresentation {
+            metadata: RepresentationMetadata::new(),
+            data: BytesInmem::from(Bytes::from(vec![0u8; size])),
+            base_uri: None,
+        }
+        .into()
+    }
+
+    fn store(max_bytes: u64) -> QuotaMeteredVersionStore<MockVersionStore> {
+        QuotaMeteredVersionStore::new(
+            Default::default(),
+            Arc::new(QuotaUsageTracker::new(
+                QuotaLimits {
+                    max_bytes: Some(max_bytes),
+                    max_resources: None,
+                },
+                QuotaUsage {
+                    bytes: 50,
+                    resources: 5,
+                },
+            )),
+        )
+    }
+
+    fn res_uri() -> SolidResourceUri {
+        SolidResourceUri::try_new_from("http://pod.example.org/a.ttl").expect("Must be valid.")
+    }
+
+    fn usage(bytes: u64) -> QuotaUsage {
+        QuotaUsage {
+            bytes,
+            resources: 5,
+        }
+    }
+
+    #[tokio::test]
+    async fn versions_are_metered() {
+        let store = store(100);
+        let (v1, v2) = (UNIX_EPOCH, UNIX_EPOCH + Duration::from_secs(1));
+
+        assert_ok!(store.save_version(&res_uri(), v1, rep(20)).await);
+        assert_ok!(store.save_version(&res_uri(), v2, rep(10)).await);
+        assert_eq!(store.tracker.usage(), usage(80));
+
+        // Replaced version releases it's prior size.
+        assert_ok!(store.save_version(&res_uri(), v2, rep(15)).await);
+        assert_eq!(store.tracker.usage(), usage(85));
+        let saved = assert_ok!(store.read_version(&res_uri(), v2).await);
+        assert_eq!(
+            saved.into_streaming().into_basic().data.size_hint.exact(),
+            Some(15)
+        );
+
+        assert_ok!(store.delete_version(&res_uri(), v1).await);
+        assert_eq!(store.tracker.usage(), usage(65));
+
+        // Deleting non-existing version releases nothing.
+        assert_ok!(store.delete_version(&res_uri(), v1).await);
+        assert_eq!(store.tracker.usage(), usage(65));
+    }
+
+    #[tokio::test]
+    async fn versions_exceeding_quota_are_rejected() {
+        let store = store(100);
+
+        let e = assert_err!(store.save_version(&res_uri(), UNIX_EPOCH, rep(60)).await);
+        assert!(
+            manas_repo::service::resource_operator::common::problem::QUOTA_EXCEEDED.is_type_of(&e)
+        );
+        assert_eq!(store.tracker.usage(), usage(50));
+        assert_none!(assert_ok!(
+            store.inner().version_size(&res_uri(), UNIX_EPOCH).await
+        ));
+    }
+}
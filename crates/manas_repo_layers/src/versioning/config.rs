@@ -0,0 +1,64 @@
+//! I define configuration types for [`VersioningRepo`](super::VersioningRepo).
+//!
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Configuration for [`VersioningRepo`](super::VersioningRepo).
+#[derive(Debug, Clone, Default)]
+pub struct VersioningConfig {
+    /// Whether versioning is enabled. If not, the layer is
+    /// transparent.
+    pub enabled: bool,
+
+    /// Retention policy of versions.
+    pub retention: VersionRetention,
+}
+
+/// Retention policy of versions of a resource.
+#[derive(Debug, Clone, Default)]
+pub struct VersionRetention {
+    /// Max number of versions to retain per resource. If
+    /// `None`, number of versions is not limited.
+    pub max_count: Option<usize>,
+
+    /// Max age of versions to retain. If `None`, versions
+    /// never expire.
+    pub max_age: Option<Duration>,
+}
+
+impl VersionRetention {
+    /// Get the versions that are not to be retained, from
+    /// given versions in ascending order.
+    pub fn expired_versions(&self, versions: &[SystemTime], now: SystemTime) -> Vec<SystemTime> {
+        let excess_count = self
+            .max_count
+            .map(|max_count| versions.len().saturating_sub(max_count))
+            .unwrap_or_default();
+
+        versions
+            .iter()
+            .enumerate()
+            .filter(|(i, version)| {
+                *i < excess_count
+                    || self.max_age.map_or(false, |max_age| {
+                        now.duration_since(**version)
+                            .map_or(false, |age| age > max_age)
+                    })
+            })
+            .map(|(_, version)| *version)
+            .collect()
+    }
+}
+
+/// Truncate given datetime to the resolution of version
+/// datetimes.
+#[inline]
+pub(crate) fn truncate_to_secs(datetime: SystemTime) -> SystemTime {
+    UNIX_EPOCH
+        + Duration::from_secs(
+            datetime
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        )
+}
@@ -0,0 +1,55 @@
+//! I define an implementation of [`RepoContext`] for [`VersioningRepo`](super::VersioningRepo).
+//!
+
+use std::sync::Arc;
+
+use manas_http::representation::impl_::binary::BinaryRepresentation;
+use manas_repo::{
+    context::{LayeredRepoContext, RepoContext},
+    Repo,
+};
+
+use super::{config::VersioningConfig, version_store::VersionStore, MRepo};
+
+/// An implementation of [`RepoContext`] for [`VersioningRepo`](super::VersioningRepo).
+#[derive(Debug)]
+pub struct VersioningRepoContext<IR, VS>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+    VS: VersionStore,
+{
+    /// Inner repo config.
+    pub inner: Arc<IR::Context>,
+
+    /// Version store.
+    pub version_store: Arc<VS>,
+
+    /// Versioning config.
+    pub config: VersioningConfig,
+}
+
+impl<IR, VS> RepoContext for VersioningRepoContext<IR, VS>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+    VS: VersionStore,
+{
+    type Repo = MRepo<IR, VS>;
+
+    #[inline]
+    fn storage_space(&self) -> &Arc<IR::StSpace> {
+        self.inner.storage_space()
+    }
+}
+
+impl<IR, VS> LayeredRepoContext for VersioningRepoContext<IR, VS>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+    VS: VersionStore,
+{
+    type InnerRepo = IR;
+
+    #[inline]
+    fn inner(&self) -> &Arc<IR::Context> {
+        &self.inner
+    }
+}
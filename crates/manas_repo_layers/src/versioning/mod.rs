@@ -0,0 +1,179 @@
+//! I provide an implementation of [`Repo`] that versions
+//! resource representations, and supports memento
+//! ([rfc7089](https://www.rfc-editor.org/rfc/rfc7089.html))
+//! datetime negotiation over them.
+//!
+
+use std::{marker::PhantomData, sync::Arc};
+
+use manas_http::representation::impl_::binary::BinaryRepresentation;
+use manas_repo::{
+    layer::RepoLayer,
+    policy::uri::impl_::DelegatedUriPolicy,
+    service::{
+        initializer::impl_::DelegatedRepoInitializer,
+        patcher_resolver::impl_::DelegatedRepPatcherResolver,
+        resource_operator::{
+            common::{
+                impl_::DelegatingOperator,
+                status_token::impl_::layered::LayeredResourceStatusTokenTypes,
+            },
+            status_token_resolver::impl_::LayeredResourceStatusTokenResolver,
+        },
+    },
+    Repo, RepoInitializerService, RepoRepPatcherResolver, RepoResourceCreator,
+    RepoResourceStatusTokenResolver, RepoServices,
+};
+
+use self::{
+    config::VersioningConfig,
+    context::VersioningRepoContext,
+    service::resource_operator::{
+        deleter::VersioningRepoResourceDeleter, reader::VersioningRepoResourceReader,
+        updater::VersioningRepoResourceUpdater,
+    },
+    version_store::VersionStore,
+};
+
+pub mod config;
+pub mod context;
+pub mod service;
+pub mod version_store;
+
+/// A layered implementation of [`Repo`] that versions
+/// resource representations.
+///
+/// On every update or delete operation, it snapshots the
+/// prior representation into it's [`VersionStore`], and
+/// prunes versions as per configured retention policy.
+///
+/// It resolves [`RepVersionPreference`](manas_repo::service::resource_operator::reader::rep_preferences::version::RepVersionPreference)
+/// on read operations, serving mementos selected by datetime
+/// negotiation, and timemaps of resources.
+///
+/// Versions of containers only include their minimal
+/// representations, without containment triples.
+#[derive(Debug, Clone)]
+pub struct VersioningRepo<IR, VS>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+    VS: VersionStore,
+{
+    context: Arc<VersioningRepoContext<IR, VS>>,
+}
+
+impl<IR, VS> Repo for VersioningRepo<IR, VS>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+    VS: VersionStore,
+{
+    type StSpace = IR::StSpace;
+
+    type Representation = IR::Representation;
+
+    type Context = VersioningRepoContext<IR, VS>;
+
+    type UriPolicy = DelegatedUriPolicy<IR::UriPolicy, Self>;
+
+    type ResourceStatusTokenTypes =
+        LayeredResourceStatusTokenTypes<IR::ResourceStatusTokenTypes, Self>;
+
+    type RepPatcher = IR::RepPatcher;
+
+    type Services = VersioningRepoServices<IR, VS>;
+
+    type Credentials = IR::Credentials;
+
+    #[inline]
+    fn new(context: Arc<Self::Context>) -> Self {
+        Self { context }
+    }
+
+    #[inline]
+    fn context(&self) -> &Arc<Self::Context> {
+        &self.context
+    }
+}
+
+/// Quick alias for `VersioningRepo`
+pub(crate) type MRepo<IR, VS> = VersioningRepo<IR, VS>;
+
+/// Services for [`VersioningRepo`].
+#[derive(Debug, Clone)]
+pub struct VersioningRepoServices<IR, VS> {
+    _phantom: PhantomData<fn(IR, VS)>,
+}
+
+impl<IR, VS> RepoServices for VersioningRepoServices<IR, VS>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+    VS: VersionStore,
+{
+    type Repo = MRepo<IR, VS>;
+
+    type Initializer = DelegatedRepoInitializer<RepoInitializerService<IR>, MRepo<IR, VS>>;
+
+    type RepPatcherResolver =
+        DelegatedRepPatcherResolver<RepoRepPatcherResolver<IR>, MRepo<IR, VS>>;
+
+    type ResourceStatusTokenResolver =
+        LayeredResourceStatusTokenResolver<RepoResourceStatusTokenResolver<IR>, MRepo<IR, VS>>;
+
+    type ResourceReader = VersioningRepoResourceReader<IR, VS>;
+
+    type ResourceCreator = DelegatingOperator<RepoResourceCreator<IR>, MRepo<IR, VS>>;
+
+    type ResourceUpdater = VersioningRepoResourceUpdater<IR, VS>;
+
+    type ResourceDeleter = VersioningRepoResourceDeleter<IR, VS>;
+}
+
+/// An implementation of [`RepoLayer`] that layers versioning
+/// functionality over repos.
+#[derive(Debug, Clone)]
+pub struct VersioningRepoLayer<IR, VS>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+    VS: VersionStore,
+{
+    version_store: Arc<VS>,
+    config: VersioningConfig,
+    _phantom: PhantomData<fn(IR)>,
+}
+
+impl<IR, VS> VersioningRepoLayer<IR, VS>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+    VS: VersionStore,
+{
+    /// Create a new [`VersioningRepoLayer`] with given
+    /// version store and config.
+    #[inline]
+    pub fn new(version_store: Arc<VS>, config: VersioningConfig) -> Self {
+        Self {
+            version_store,
+            config,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<IR, VS> RepoLayer<IR> for VersioningRepoLayer<IR, VS>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+    VS: VersionStore,
+{
+    type LayeredRepo = VersioningRepo<IR, VS>;
+
+    #[inline]
+    fn layer_context(
+        &self,
+        inner_context: Arc<<IR as Repo>::Context>,
+    ) -> <Self::LayeredRepo as Repo>::Context {
+        VersioningRepoContext {
+            inner: inner_context,
+            version_store: self.version_store.clone(),
+            config: self.config.clone(),
+        }
+    }
+}
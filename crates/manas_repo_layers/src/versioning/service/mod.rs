@@ -0,0 +1,4 @@
+//! I provide repo service implementations for [`VersioningRepo`](super::VersioningRepo).
+//!
+
+pub mod resource_operator;
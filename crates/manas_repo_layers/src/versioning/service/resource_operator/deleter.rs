@@ -0,0 +1,120 @@
+//! I provide an implementation of [`ResourceDeleter`] for [`VersioningRepo`].
+//!
+
+use std::{marker::PhantomData, task::Poll};
+
+use dyn_problem::{ProbFuture, Problem};
+use manas_http::representation::impl_::binary::BinaryRepresentation;
+use manas_repo::{
+    service::resource_operator::{
+        common::status_token::impl_::layered::Layered,
+        deleter::{
+            KDryRunDelete, ResourceDeleteRequest, ResourceDeleteResponse, ResourceDeleteTokenSet,
+            ResourceDeleter,
+        },
+    },
+    Repo, RepoResourceDeleter,
+};
+use tower::{Service, ServiceExt};
+use typed_record::TypedRecord;
+
+use super::snapshot::RepSnapshot;
+use crate::versioning::{version_store::VersionStore, VersioningRepo};
+
+/// An implementation of [`ResourceDeleter`] for [`VersioningRepo`]
+#[derive(Debug)]
+pub struct VersioningRepoResourceDeleter<IR: Repo, VS> {
+    inner: RepoResourceDeleter<IR>,
+    _phantom: PhantomData<fn(VS)>,
+}
+
+impl<IR: Repo, VS> Default for VersioningRepoResourceDeleter<IR, VS> {
+    fn default() -> Self {
+        Self {
+            inner: Default::default(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<IR: Repo, VS> Clone for VersioningRepoResourceDeleter<IR, VS> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<IR, VS> Service<ResourceDeleteRequest<VersioningRepo<IR, VS>>>
+    for VersioningRepoResourceDeleter<IR, VS>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+    VS: VersionStore,
+{
+    type Response = ResourceDeleteResponse<VersioningRepo<IR, VS>>;
+
+    type Error = Problem;
+
+    type Future = ProbFuture<'static, Self::Response>;
+
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[tracing::instrument(skip_all, name = "VersioningRepoResourceDeleter::call")]
+    fn call(&mut self, req: ResourceDeleteRequest<VersioningRepo<IR, VS>>) -> Self::Future {
+        let mut inner_svc = self.inner.clone();
+
+        Box::pin(async move {
+            let Layered {
+                inner: inner_req_tokens,
+                layer_context,
+            } = Layered::from(req.tokens);
+
+            // Snapshot the current representation. Dry runs
+            // modify nothing, and need no snapshot.
+            let (snapshot, res_token) = if layer_context.config.enabled
+                && req.extensions.get_rv::<KDryRunDelete>().is_none()
+            {
+                let (snapshot, token) = RepSnapshot::take(
+                    inner_req_tokens.res_token,
+                    &layer_context,
+                    req.credentials.clone(),
+                    req.extensions.clone(),
+                )
+                .await?;
+                (Some(snapshot), token)
+            } else {
+                (None, inner_req_tokens.res_token)
+            };
+
+            let inner_req = ResourceDeleteRequest::<IR> {
+                tokens: ResourceDeleteTokenSet { res_token },
+                preconditions: req.preconditions,
+                credentials: req.credentials,
+                extensions: req.extensions,
+            };
+
+            let result = match inner_svc.ready().await {
+                Ok(svc) => svc.call(inner_req).await,
+                Err(e) => Err(e),
+            };
+
+            let resp = match snapshot {
+                Some(snapshot) => snapshot.settle(result).await,
+                None => result,
+            }?;
+
+            Ok(resp.map_repo())
+        })
+    }
+}
+
+impl<IR, VS> ResourceDeleter for VersioningRepoResourceDeleter<IR, VS>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+    VS: VersionStore,
+{
+    type Repo = VersioningRepo<IR, VS>;
+}
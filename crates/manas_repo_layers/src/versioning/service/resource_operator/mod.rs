@@ -0,0 +1,8 @@
+//! I provide resource operator service implementations for [`VersioningRepo`](super::super::VersioningRepo).
+//!
+
+pub mod deleter;
+pub mod reader;
+pub mod updater;
+
+mod snapshot;
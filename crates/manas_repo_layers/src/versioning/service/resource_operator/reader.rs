@@ -0,0 +1,287 @@
+//! I provide an implementation of [`ResourceReader`] for [`VersioningRepo`](crate::versioning::VersioningRepo).
+//!
+
+use std::{marker::PhantomData, time::SystemTime};
+
+use dyn_problem::{ProbFuture, Problem};
+use futures::TryFutureExt;
+use headers::ContentLength;
+use httpdate::fmt_http_date;
+use manas_http::{
+    header::common::media_type::MediaType,
+    representation::{
+        impl_::{
+            basic::BasicRepresentation, binary::BinaryRepresentation,
+            common::data::bytes_inmem::BytesInmem,
+        },
+        metadata::{KCompleteContentLength, KContentType, KLastModified, RepresentationMetadata},
+        Representation,
+    },
+};
+use manas_repo::{
+    service::resource_operator::reader::{
+        rep_preferences::{
+            version::{memento_uri, timemap_uri, KRepVersion, RepVersion, RepVersionPreference},
+            RepresentationPreferences,
+        },
+        FlexibleResourceReader, ResourceReadRequest, ResourceReadResponse, ResourceReader,
+    },
+    Repo, RepoResourceReader,
+};
+use once_cell::sync::Lazy;
+use tower::{Service, ServiceExt};
+use tracing::error;
+use typed_record::TypedRecord;
+
+use crate::versioning::{config::truncate_to_secs, version_store::VersionStore, MRepo};
+
+/// An implementation of [`ResourceReader`] for [`VersioningRepo`](crate::versioning::VersioningRepo).
+#[derive(Debug)]
+pub struct VersioningRepoResourceReader<IR: Repo, VS> {
+    inner: RepoResourceReader<IR>,
+    _phantom: PhantomData<fn(VS)>,
+}
+
+impl<IR: Repo, VS> Default for VersioningRepoResourceReader<IR, VS> {
+    fn default() -> Self {
+        Self {
+            inner: Default::default(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<IR: Repo, VS> Clone for VersioningRepoResourceReader<IR, VS> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<IR, VS> Service<ResourceReadRequest<MRepo<IR, VS>>> for VersioningRepoResourceReader<IR, VS>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+    VS: VersionStore,
+{
+    type Response = ResourceReadResponse<MRepo<IR, VS>, BinaryRepresentation>;
+
+    type Error = Problem;
+
+    type Future = ProbFuture<'static, Self::Response>;
+
+    #[inline]
+    fn poll_ready(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    #[tracing::instrument(skip_all, name = "VersioningRepoResourceReader::call")]
+    fn call(&mut self, req: ResourceReadRequest<MRepo<IR, VS>>) -> Self::Future {
+        let mut inner_svc = self.inner.clone();
+        let layer_context = req.tokens.res_token.layer_context.clone();
+        let version_preference = req.rep_preferences.version_preference;
+        let mut inner_req = req.unlayer_tokens();
+
+        Box::pin(async move {
+            // Delegate, if versioning is not enabled.
+            if !layer_context.config.enabled {
+                return inner_svc
+                    .ready()
+                    .and_then(|svc| svc.call(inner_req))
+                    .await
+                    .map(|resp| resp.layer_tokens(layer_context));
+            }
+
+            // Timemap requires only the resource state.
+            if version_preference == RepVersionPreference::TimeMap {
+                inner_req.rep_preferences = RepresentationPreferences::new_light();
+            }
+
+            let resp = inner_svc
+                .ready()
+                .and_then(|svc| svc.call(inner_req))
+                .await?;
+
+            let res_uri = resp.state.slot.id().uri.clone();
+            let version_store = layer_context.version_store.clone();
+
+            // Datetime of the current representation.
+            let current_datetime = truncate_to_secs(
+                resp.state
+                    .representation()
+                    .metadata()
+                    .get_rv::<KLastModified>()
+                    .copied()
+                    .map(SystemTime::from)
+                    .unwrap_or_else(SystemTime::now),
+            );
+
+            let resp = match version_preference {
+                RepVersionPreference::Current => {
+                    resp.map_representation(|rep| with_rep_version(rep, RepVersion::default()))
+                }
+
+                RepVersionPreference::AtDatetime(datetime) => {
+                    let versions = version_store
+                        .list_versions(&res_uri)
+                        .inspect_err(|e| error!("Error in listing versions. Error:\n {}", e))
+                        .await?;
+
+                    match select_version(&versions, current_datetime, datetime) {
+                        Some(version) => {
+                            let mut version_rep = version_store
+                                .read_version(&res_uri, version)
+                                .inspect_err(|e| error!("Error in reading version. Error:\n {}", e))
+                                .await?
+                                .into_basic();
+
+                            resp.map_representation(|rep| {
+                                version_rep.base_uri = rep.into_basic().base_uri;
+                                with_rep_version(
+                                    version_rep.into(),
+                                    RepVersion {
+                                        memento_datetime: Some(version),
+                                    },
+                                )
+                            })
+                        }
+                        None => resp.map_representation(|rep| {
+                            with_rep_version(
+                                rep,
+                                RepVersion {
+                                    memento_datetime: Some(current_datetime),
+                                },
+                            )
+                        }),
+                    }
+                }
+
+                RepVersionPreference::TimeMap => {
+                    let versions = version_store
+                        .list_versions(&res_uri)
+                        .inspect_err(|e| error!("Error in listing versions. Error:\n {}", e))
+                        .await?;
+
+                    resp.map_representation(|_| {
+                        timemap_rep(res_uri.as_str(), &versions, current_datetime)
+                    })
+                }
+            };
+
+            Ok(resp.layer_tokens(layer_context))
+        })
+    }
+}
+
+impl<IR, VS> FlexibleResourceReader<MRepo<IR, VS>, BinaryRepresentation>
+    for VersioningRepoResourceReader<IR, VS>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+    VS: VersionStore,
+{
+}
+
+impl<IR, VS> ResourceReader for VersioningRepoResourceReader<IR, VS>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+    VS: VersionStore,
+{
+    type Repo = MRepo<IR, VS>;
+}
+
+/// Link format media type.
+static APPLICATION_LINK_FORMAT: Lazy<MediaType> =
+    Lazy::new(|| "application/link-format".parse().expect("Must be valid"));
+
+/// Select the version that was current at given datetime,
+/// from given versions in ascending order.
+///
+/// Returns `None` if current representation is to be
+/// selected.
+fn select_version(
+    versions: &[SystemTime],
+    current_datetime: SystemTime,
+    datetime: SystemTime,
+) -> Option<SystemTime> {
+    if datetime >= current_datetime {
+        return None;
+    }
+
+    let prior_versions = versions
+        .iter()
+        .copied()
+        .filter(|version| *version < current_datetime)
+        .collect::<Vec<_>>();
+
+    prior_versions
+        .iter()
+        .rev()
+        .find(|version| **version <= datetime)
+        .or(prior_versions.first())
+        .copied()
+}
+
+/// Get given representation with given rep version recorded
+/// in it's metadata.
+fn with_rep_version(rep: BinaryRepresentation, rep_version: RepVersion) -> BinaryRepresentation {
+    let mut rep = rep.into_basic();
+    rep.metadata.insert_rec_item::<KRepVersion>(rep_version);
+    rep.into()
+}
+
+/// Get timemap representation in link format, for resource
+/// with given uri.
+fn timemap_rep(
+    res_uri_str: &str,
+    versions: &[SystemTime],
+    current_datetime: SystemTime,
+) -> BinaryRepresentation {
+    // Current representation is the last memento.
+    let mut mementos = versions
+        .iter()
+        .copied()
+        .filter(|version| *version < current_datetime)
+        .collect::<Vec<_>>();
+    mementos.push(current_datetime);
+
+    let mut entries = vec![
+        format!("<{}>;rel=\"original timegate\"", res_uri_str),
+        format!(
+            "<{}>;rel=\"self\";type=\"{}\"",
+            timemap_uri(res_uri_str),
+            APPLICATION_LINK_FORMAT.essence_str()
+        ),
+    ];
+
+    let last_index = mementos.len() - 1;
+    for (i, memento) in mementos.into_iter().enumerate() {
+        let rel = match (i == 0, i == last_index) {
+            (true, true) => "first last memento",
+            (true, false) => "first memento",
+            (false, true) => "last memento",
+            (false, false) => "memento",
+        };
+        entries.push(format!(
+            "<{}>;rel=\"{}\";datetime=\"{}\"",
+            memento_uri(res_uri_str, memento),
+            rel,
+            fmt_http_date(memento)
+        ));
+    }
+
+    let content = entries.join(",\n");
+
+    BasicRepresentation {
+        metadata: RepresentationMetadata::new()
+            .with::<KContentType>(APPLICATION_LINK_FORMAT.clone())
+            .with::<KCompleteContentLength>(ContentLength(content.len() as u64))
+            .with::<KRepVersion>(RepVersion::default()),
+        data: BytesInmem::from(content),
+        base_uri: None,
+    }
+    .into()
+}
@@ -0,0 +1,147 @@
+//! I define [`RepSnapshot`], that snapshots prior
+//! representations of resources into version store.
+//!
+
+use std::{sync::Arc, time::SystemTime};
+
+use dyn_problem::Problem;
+use futures::TryFutureExt;
+use httpdate::fmt_http_date;
+use manas_http::representation::{
+    impl_::binary::BinaryRepresentation, metadata::KLastModified, Representation,
+};
+use manas_repo::{
+    service::resource_operator::reader::{
+        rep_preferences::RepresentationPreferences, ResourceReadRequest, ResourceReadTokenSet,
+    },
+    Repo, RepoRepresentedResourceToken, RepoResourceReader,
+};
+use manas_space::resource::uri::SolidResourceUri;
+use tower::{Service, ServiceExt};
+use tracing::{error, info};
+use typed_record::{ClonableTypedRecord, TypedRecord};
+
+use crate::versioning::{
+    config::{truncate_to_secs, VersionRetention},
+    context::VersioningRepoContext,
+    version_store::VersionStore,
+};
+
+/// A snapshot of prior representation of a resource, taken
+/// before an operation over it.
+pub(super) struct RepSnapshot<VS> {
+    version_store: Arc<VS>,
+    retention: VersionRetention,
+    res_uri: SolidResourceUri,
+    datetime: SystemTime,
+
+    /// Whether the version is newly created by the snapshot.
+    is_new: bool,
+
+    /// Versions of the resource, including the snapshot.
+    versions: Vec<SystemTime>,
+}
+
+impl<VS: VersionStore> RepSnapshot<VS> {
+    /// Take snapshot of the representation of the resource
+    /// with given token. Returns the snapshot, along with
+    /// token for further operations.
+    pub(super) async fn take<IR>(
+        res_token: RepoRepresentedResourceToken<IR>,
+        layer_context: &VersioningRepoContext<IR, VS>,
+        credentials: IR::Credentials,
+        extensions: ClonableTypedRecord,
+    ) -> Result<(Self, RepoRepresentedResourceToken<IR>), Problem>
+    where
+        IR: Repo<Representation = BinaryRepresentation>,
+    {
+        // Read complete prior representation.
+        let resp = RepoResourceReader::<IR>::default()
+            .ready()
+            .await?
+            .call(ResourceReadRequest {
+                tokens: ResourceReadTokenSet::new(res_token),
+                rep_preferences: RepresentationPreferences::new_light(),
+                rep_conneg_params: Default::default(),
+                preconditions: Box::new(()),
+                credentials,
+                extensions,
+            })
+            .inspect_err(|e| error!("Error in reading prior representation. Error:\n {}", e))
+            .await?;
+
+        let (slot, rep) = resp.state.into_parts();
+        let res_uri = slot.id().uri.clone();
+
+        // Version is identified by modification time of the
+        // prior representation.
+        let datetime = truncate_to_secs(
+            rep.metadata()
+                .get_rv::<KLastModified>()
+                .copied()
+                .map(SystemTime::from)
+                .unwrap_or_else(SystemTime::now),
+        );
+
+        let version_store = layer_context.version_store.clone();
+
+        let mut versions = version_store.list_versions(&res_uri).await?;
+        let is_new = !versions.contains(&datetime);
+
+        version_store
+            .save_version(&res_uri, datetime, rep)
+            .inspect_err(|e| error!("Error in saving version. Error:\n {}", e))
+            .await?;
+        info!(
+            "Saved version of {} at {}.",
+            res_uri.as_str(),
+            fmt_http_date(datetime)
+        );
+
+        if is_new {
+            versions.push(datetime);
+            versions.sort();
+        }
+
+        Ok((
+            Self {
+                version_store,
+                retention: layer_context.config.retention.clone(),
+                res_uri,
+                datetime,
+                is_new,
+                versions,
+            },
+            resp.tokens.res_token,
+        ))
+    }
+
+    /// Settle the snapshot with the result of the operation.
+    ///
+    /// On success, versions expired as per retention policy
+    /// will be pruned. On failure, newly created version will
+    /// be discarded.
+    pub(super) async fn settle<T>(self, result: Result<T, Problem>) -> Result<T, Problem> {
+        let stale_versions = if result.is_ok() {
+            self.retention
+                .expired_versions(&self.versions, SystemTime::now())
+        } else if self.is_new {
+            vec![self.datetime]
+        } else {
+            vec![]
+        };
+
+        for version in stale_versions {
+            if let Err(e) = self
+                .version_store
+                .delete_version(&self.res_uri, version)
+                .await
+            {
+                // Operation is not failed for stale versions.
+                error!("Error in deleting stale version. Error:\n {}", e);
+            }
+        }
+
+        result
+    }
+}
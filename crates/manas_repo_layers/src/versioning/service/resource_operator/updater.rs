@@ -0,0 +1,115 @@
+//! I provide an implementation of [`ResourceUpdater`] for [`VersioningRepo`].
+//!
+
+use std::{marker::PhantomData, task::Poll};
+
+use dyn_problem::{ProbFuture, Problem};
+use manas_http::representation::impl_::binary::BinaryRepresentation;
+use manas_repo::{
+    service::resource_operator::{
+        common::status_token::{impl_::layered::Layered, ExistingResourceToken},
+        updater::{
+            ResourceUpdateRequest, ResourceUpdateResponse, ResourceUpdateTokenSet, ResourceUpdater,
+        },
+    },
+    Repo, RepoResourceUpdater,
+};
+use tower::{Service, ServiceExt};
+
+use super::snapshot::RepSnapshot;
+use crate::versioning::{version_store::VersionStore, VersioningRepo};
+
+/// An implementation of [`ResourceUpdater`] for [`VersioningRepo`]
+#[derive(Debug)]
+pub struct VersioningRepoResourceUpdater<IR: Repo, VS> {
+    inner: RepoResourceUpdater<IR>,
+    _phantom: PhantomData<fn(VS)>,
+}
+
+impl<IR: Repo, VS> Default for VersioningRepoResourceUpdater<IR, VS> {
+    fn default() -> Self {
+        Self {
+            inner: Default::default(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<IR: Repo, VS> Clone for VersioningRepoResourceUpdater<IR, VS> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<IR, VS> Service<ResourceUpdateRequest<VersioningRepo<IR, VS>>>
+    for VersioningRepoResourceUpdater<IR, VS>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+    VS: VersionStore,
+{
+    type Response = ResourceUpdateResponse;
+
+    type Error = Problem;
+
+    type Future = ProbFuture<'static, Self::Response>;
+
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[tracing::instrument(skip_all, name = "VersioningRepoResourceUpdater::call")]
+    fn call(&mut self, req: ResourceUpdateRequest<VersioningRepo<IR, VS>>) -> Self::Future {
+        let mut inner_svc = self.inner.clone();
+
+        Box::pin(async move {
+            let Layered {
+                inner: inner_req_tokens,
+                layer_context,
+            } = Layered::from(req.tokens);
+
+            // Snapshot the current representation, if any.
+            let (snapshot, res_token) = match inner_req_tokens.res_token {
+                ExistingResourceToken::Represented(token) if layer_context.config.enabled => {
+                    let (snapshot, token) = RepSnapshot::take(
+                        token,
+                        &layer_context,
+                        req.credentials.clone(),
+                        req.extensions.clone(),
+                    )
+                    .await?;
+                    (Some(snapshot), ExistingResourceToken::Represented(token))
+                }
+                token => (None, token),
+            };
+
+            let inner_req = ResourceUpdateRequest::<IR> {
+                tokens: ResourceUpdateTokenSet { res_token },
+                rep_update_action: req.rep_update_action.map_repo(),
+                preconditions: req.preconditions,
+                credentials: req.credentials,
+                extensions: req.extensions,
+            };
+
+            let result = match inner_svc.ready().await {
+                Ok(svc) => svc.call(inner_req).await,
+                Err(e) => Err(e),
+            };
+
+            match snapshot {
+                Some(snapshot) => snapshot.settle(result).await,
+                None => result,
+            }
+        })
+    }
+}
+
+impl<IR, VS> ResourceUpdater for VersioningRepoResourceUpdater<IR, VS>
+where
+    IR: Repo<Representation = BinaryRepresentation>,
+    VS: VersionStore,
+{
+    type Repo = VersioningRepo<IR, VS>;
+}
@@ -0,0 +1,55 @@
+//! I define [`VersionStore`] trait for stores of prior
+//! versions of resources.
+//!
+
+use std::{fmt::Debug, time::SystemTime};
+
+use dyn_problem::ProbFuture;
+use manas_http::representation::impl_::binary::BinaryRepresentation;
+use manas_space::resource::uri::SolidResourceUri;
+
+/// A trait for stores of prior versions of resource
+/// representations.
+///
+/// Versions of a resource are identified by their datetimes,
+/// with a resolution of seconds.
+pub trait VersionStore: Debug + Send + Sync + 'static {
+    /// List datetimes of versions of the resource with given
+    /// uri, in ascending order.
+    fn list_versions(&self, res_uri: &SolidResourceUri) -> ProbFuture<'static, Vec<SystemTime>>;
+
+    /// Save given representation as the version of the
+    /// resource with given uri at given datetime. Any existing
+    /// version with same datetime will be replaced.
+    fn save_version(
+        &self,
+        res_uri: &SolidResourceUri,
+        datetime: SystemTime,
+        rep: BinaryRepresentation,
+    ) -> ProbFuture<'static, ()>;
+
+    /// Read representation of the version of the resource with
+    /// given uri at given datetime.
+    fn read_version(
+        &self,
+        res_uri: &SolidResourceUri,
+        datetime: SystemTime,
+    ) -> ProbFuture<'static, BinaryRepresentation>;
+
+    /// Resolve size in bytes of the version of the resource
+    /// with given uri at given datetime. Returns `None` if no
+    /// such version exists.
+    fn version_size(
+        &self,
+        res_uri: &SolidResourceUri,
+        datetime: SystemTime,
+    ) -> ProbFuture<'static, Option<u64>>;
+
+    /// Delete the version of the resource with given uri at
+    /// given datetime.
+    fn delete_version(
+        &self,
+        res_uri: &SolidResourceUri,
+        datetime: SystemTime,
+    ) -> ProbFuture<'static, ()>;
+}
@@ -0,0 +1,366 @@
+//! I define typed, serde-tagged configurations of backend
+//! implementations.
+//!
+
+use std::fmt::Debug;
+
+#[cfg(feature = "backend-embedded")]
+use super::embedded::EmbeddedBackendConfig;
+#[cfg(feature = "backend-fs")]
+use super::fs::FsBackendConfig;
+#[cfg(feature = "backend-gcs")]
+use super::gcs::GcsBackendConfig;
+#[cfg(feature = "backend-s3")]
+use super::s3::S3BackendConfig;
+
+/// A secret value in backend configuration. It can be
+/// either specified inline, or as a reference to an
+/// environment variable like `{ env = "AWS_SECRET_ACCESS_KEY" }`.
+///
+/// Inline secrets are redacted in both `Debug` and
+/// `Serialize` outputs. Thus serialized configs with inline
+/// secrets don't round trip.
+#[derive(Clone, serde::Deserialize)]
+#[serde(untagged)]
+pub enum SecretValue {
+    /// Secret to be read from given environment variable.
+    Env {
+        /// Name of the environment variable.
+        env: String,
+    },
+
+    /// Inline secret.
+    Inline(String),
+}
+
+/// Placeholder of redacted inline secrets.
+const REDACTED: &str = "<redacted>";
+
+impl Debug for SecretValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Env { env } => f.debug_struct("Env").field("env", env).finish(),
+            Self::Inline(_) => f.debug_tuple("Inline").field(&REDACTED).finish(),
+        }
+    }
+}
+
+impl serde::Serialize for SecretValue {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        match self {
+            Self::Env { env } => {
+                let mut state = serializer.serialize_struct("Env", 1)?;
+                state.serialize_field("env", env)?;
+                state.end()
+            }
+            Self::Inline(_) => serializer.serialize_str(REDACTED),
+        }
+    }
+}
+
+impl SecretValue {
+    /// Resolve the secret.
+    pub fn resolve(&self) -> Result<String, BackendConfigError> {
+        match self {
+            Self::Env { env } => {
+                std::env::var(env).map_err(|_| BackendConfigError::MissingEnvVar(env.clone()))
+            }
+            Self::Inline(value) => Ok(value.clone()),
+        }
+    }
+}
+
+/// An error type for invalid backend configurations.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum BackendConfigError {
+    /// Required field is empty.
+    #[error("Required field `{0}` is empty.")]
+    EmptyField(&'static str),
+
+    /// Field has invalid value.
+    #[error("Invalid value for field `{field}`. {reason}")]
+    InvalidField {
+        /// Name of the field.
+        field: &'static str,
+
+        /// Reason.
+        reason: String,
+    },
+
+    /// Environment variable of a secret is not set.
+    #[error("Environment variable `{0}` of a secret is not set.")]
+    MissingEnvVar(String),
+
+    /// Config is of a different backend type.
+    #[error("Expected `{expected}` backend config, but found `{found}` backend config.")]
+    MismatchedBackendType {
+        /// Expected backend type.
+        expected: &'static str,
+
+        /// Found backend type.
+        found: &'static str,
+    },
+}
+
+/// Ensure given required field value is not empty.
+pub(crate) fn ensure_non_empty(field: &'static str, value: &str) -> Result<(), BackendConfigError> {
+    if value.trim().is_empty() {
+        return Err(BackendConfigError::EmptyField(field));
+    }
+    Ok(())
+}
+
+/// Ensure given optional endpoint value is an http url.
+#[cfg(any(feature = "backend-s3", feature = "backend-gcs"))]
+pub(crate) fn ensure_http_endpoint(
+    field: &'static str,
+    value: Option<&str>,
+) -> Result<(), BackendConfigError> {
+    match value {
+        Some(endpoint)
+            if !(endpoint.starts_with("http://") || endpoint.starts_with("https://")) =>
+        {
+            Err(BackendConfigError::InvalidField {
+                field,
+                reason: "Endpoint must be an http(s) url.".to_owned(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Typed configuration of a backend, tagged with backend
+/// type. Configuration is validated on deserialization.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "lowercase",
+    try_from = "ODRBackendConfigRepr"
+)]
+pub enum ODRBackendConfig {
+    /// File system backend config.
+    #[cfg(feature = "backend-fs")]
+    Fs(FsBackendConfig),
+
+    /// S3 backend config.
+    #[cfg(feature = "backend-s3")]
+    S3(S3BackendConfig),
+
+    /// Gcs backend config.
+    #[cfg(feature = "backend-gcs")]
+    Gcs(GcsBackendConfig),
+
+    /// Embedded backend config.
+    #[cfg(feature = "backend-embedded")]
+    Embedded(EmbeddedBackendConfig),
+}
+
+impl ODRBackendConfig {
+    /// Get the backend type name.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "backend-fs")]
+            Self::Fs(_) => "fs",
+            #[cfg(feature = "backend-s3")]
+            Self::S3(_) => "s3",
+            #[cfg(feature = "backend-gcs")]
+            Self::Gcs(_) => "gcs",
+            #[cfg(feature = "backend-embedded")]
+            Self::Embedded(_) => "embedded",
+        }
+    }
+
+    /// Validate the config.
+    pub fn validate(&self) -> Result<(), BackendConfigError> {
+        match self {
+            #[cfg(feature = "backend-fs")]
+            Self::Fs(config) => config.validate(),
+            #[cfg(feature = "backend-s3")]
+            Self::S3(config) => config.validate(),
+            #[cfg(feature = "backend-gcs")]
+            Self::Gcs(config) => config.validate(),
+            #[cfg(feature = "backend-embedded")]
+            Self::Embedded(config) => config.validate(),
+        }
+    }
+}
+
+/// Unvalidated representation of [`ODRBackendConfig`].
+#[derive(serde::Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum ODRBackendConfigRepr {
+    #[cfg(feature = "backend-fs")]
+    Fs(FsBackendConfig),
+
+    #[cfg(feature = "backend-s3")]
+    S3(S3BackendConfig),
+
+    #[cfg(feature = "backend-gcs")]
+    Gcs(GcsBackendConfig),
+
+    #[cfg(feature = "backend-embedded")]
+    Embedded(EmbeddedBackendConfig),
+}
+
+impl TryFrom<ODRBackendConfigRepr> for ODRBackendConfig {
+    type Error = BackendConfigError;
+
+    fn try_from(repr: ODRBackendConfigRepr) -> Result<Self, Self::Error> {
+        let config = match repr {
+            #[cfg(feature = "backend-fs")]
+            ODRBackendConfigRepr::Fs(config) => Self::Fs(config),
+            #[cfg(feature = "backend-s3")]
+            ODRBackendConfigRepr::S3(config) => Self::S3(config),
+            #[cfg(feature = "backend-gcs")]
+            ODRBackendConfigRepr::Gcs(config) => Self::Gcs(config),
+            #[cfg(feature = "backend-embedded")]
+            ODRBackendConfigRepr::Embedded(config) => Self::Embedded(config),
+        };
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+/// Define conversion from [`ODRBackendConfig`] into opendal
+/// builder of given backend variant.
+macro_rules! impl_builder_try_from_config {
+    ($variant:ident, $type_name:literal, $builder:ty $(, $generic:ident: $bound:path)?) => {
+        impl$(<$generic: $bound>)? TryFrom<ODRBackendConfig> for $builder {
+            type Error = BackendConfigError;
+
+            fn try_from(config: ODRBackendConfig) -> Result<Self, Self::Error> {
+                #[allow(unreachable_patterns)]
+                match config {
+                    ODRBackendConfig::$variant(config) => Self::try_from(&config),
+                    config => Err(BackendConfigError::MismatchedBackendType {
+                        expected: $type_name,
+                        found: config.type_name(),
+                    }),
+                }
+            }
+        }
+    };
+}
+
+#[cfg(feature = "backend-fs")]
+impl_builder_try_from_config!(Fs, "fs", opendal::services::Fs);
+
+#[cfg(feature = "backend-s3")]
+impl_builder_try_from_config!(S3, "s3", opendal::services::S3);
+
+#[cfg(feature = "backend-gcs")]
+impl_builder_try_from_config!(Gcs, "gcs", opendal::services::Gcs);
+
+#[cfg(feature = "backend-embedded")]
+impl_builder_try_from_config!(
+    Embedded,
+    "embedded",
+    super::embedded::service::Embedded<Assets>,
+    Assets: rust_embed::RustEmbed
+);
+
+#[cfg(test)]
+mod tests {
+    use claims::*;
+    use rstest::*;
+
+    use super::*;
+
+    #[test]
+    fn secrets_are_deserialized_from_inline_and_env_forms() {
+        let secret: SecretValue = assert_ok!(serde_json::from_str(r#""s3cret""#));
+        assert_matches!(secret, SecretValue::Inline(value) if value == "s3cret");
+
+        let secret: SecretValue = assert_ok!(serde_json::from_str(r#"{"env": "SECRET_VAR"}"#));
+        assert_matches!(secret, SecretValue::Env { env } if env == "SECRET_VAR");
+    }
+
+    #[test]
+    fn secrets_are_resolved_from_env() {
+        std::env::set_var("MANAS_TEST_BACKEND_SECRET", "from-env");
+
+        let secret = SecretValue::Env {
+            env: "MANAS_TEST_BACKEND_SECRET".to_owned(),
+        };
+        assert_ok_eq!(secret.resolve(), "from-env".to_owned());
+
+        let secret = SecretValue::Inline("inline".to_owned());
+        assert_ok_eq!(secret.resolve(), "inline".to_owned());
+    }
+
+    #[test]
+    fn missing_env_secret_is_an_error() {
+        let secret = SecretValue::Env {
+            env: "MANAS_TEST_BACKEND_SECRET_UNSET".to_owned(),
+        };
+        assert_matches!(
+            secret.resolve(),
+            Err(BackendConfigError::MissingEnvVar(env)) if env == "MANAS_TEST_BACKEND_SECRET_UNSET"
+        );
+    }
+
+    #[test]
+    fn inline_secrets_are_redacted() {
+        let secret = SecretValue::Inline("s3cret".to_owned());
+        assert!(!format!("{:?}", secret).contains("s3cret"));
+        assert_ok_eq!(serde_json::to_string(&secret), format!("\"{}\"", REDACTED));
+
+        let secret = SecretValue::Env {
+            env: "SECRET_VAR".to_owned(),
+        };
+        assert_ok_eq!(
+            serde_json::to_string(&secret),
+            r#"{"env":"SECRET_VAR"}"#.to_owned()
+        );
+    }
+
+    #[rstest]
+    #[case::empty("", false)]
+    #[case::blank("  ", false)]
+    #[case::non_empty("a", true)]
+    fn required_fields_must_be_non_empty(#[case] value: &str, #[case] expected_ok: bool) {
+        assert_eq!(ensure_non_empty("f", value).is_ok(), expected_ok);
+    }
+
+    #[cfg(feature = "backend-fs")]
+    #[rstest]
+    #[case::valid(r#"{"type": "fs", "root": "/srv/pod"}"#, true)]
+    #[case::empty_root(r#"{"type": "fs", "root": ""}"#, false)]
+    #[case::empty_atomic_write_dir(
+        r#"{"type": "fs", "root": "/srv/pod", "atomic_write_dir": " "}"#,
+        false
+    )]
+    #[case::missing_type(r#"{"root": "/srv/pod"}"#, false)]
+    #[case::unknown_type(r#"{"type": "ftp", "root": "/srv/pod"}"#, false)]
+    fn fs_config_is_validated_on_deserialization(#[case] json: &str, #[case] expected_ok: bool) {
+        let result = serde_json::from_str::<ODRBackendConfig>(json);
+        assert_eq!(result.is_ok(), expected_ok, "{:?}", result);
+    }
+
+    #[cfg(feature = "backend-fs")]
+    #[test]
+    fn fs_builder_is_resolved_from_config() {
+        let config: ODRBackendConfig = assert_ok!(serde_json::from_str(
+            r#"{"type": "fs", "root": "/srv/pod"}"#
+        ));
+        assert_eq!(config.type_name(), "fs");
+        assert_ok!(opendal::services::Fs::try_from(config));
+    }
+
+    #[cfg(all(feature = "backend-fs", feature = "backend-s3"))]
+    #[test]
+    fn mismatched_backend_type_is_an_error() {
+        let config: ODRBackendConfig = assert_ok!(serde_json::from_str(
+            r#"{"type": "fs", "root": "/srv/pod"}"#
+        ));
+        assert_matches!(
+            opendal::services::S3::try_from(config),
+            Err(BackendConfigError::MismatchedBackendType {
+                expected: "s3",
+                found: "fs"
+            })
+        );
+    }
+}
@@ -7,6 +7,7 @@ use opendal::Operator;
 use rust_embed::RustEmbed;
 
 use self::service::Embedded;
+use super::config::{ensure_non_empty, BackendConfigError};
 use crate::object_store::backend::{
     path_es::impl_::pct_decoded::PctDecodedBackendObjectPathEncodingScheme, BackendExtraCapability,
     BuildableODRObjectStoreBackend, ODRObjectStoreBackend,
@@ -52,3 +53,26 @@ impl<Assets: RustEmbed + Send + Sync + 'static> BuildableODRObjectStoreBackend<E
     for EmbeddedBackend
 {
 }
+
+/// Configuration for [`EmbeddedBackend`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct EmbeddedBackendConfig {
+    /// Name of the embedded assets.
+    pub name: String,
+}
+
+impl EmbeddedBackendConfig {
+    /// Validate the config.
+    pub fn validate(&self) -> Result<(), BackendConfigError> {
+        ensure_non_empty("name", &self.name)
+    }
+}
+
+impl<Assets> TryFrom<&EmbeddedBackendConfig> for Embedded<Assets> {
+    type Error = BackendConfigError;
+
+    fn try_from(config: &EmbeddedBackendConfig) -> Result<Self, Self::Error> {
+        config.validate()?;
+        Ok(Embedded::new().with_name(config.name.clone()))
+    }
+}
@@ -5,6 +5,7 @@
 use flagset::FlagSet;
 use opendal::{services::Fs, Operator};
 
+use super::config::{ensure_non_empty, BackendConfigError};
 use crate::object_store::backend::{
     path_es::impl_::pct_decoded::PctDecodedBackendObjectPathEncodingScheme, BackendExtraCapability,
     BuildableODRObjectStoreBackend, ODRObjectStoreBackend,
@@ -44,33 +45,40 @@ impl TryFrom<Fs> for FsBackend {
 
 impl BuildableODRObjectStoreBackend<Fs> for FsBackend {}
 
-// impl TryFrom<FsBackendConfig> for FsBackend {
-//     type Error = opendal::Error;
-
-//     #[inline]
-//     fn try_from(config: FsBackendConfig) -> Result<Self, Self::Error> {
-//         Fs::from(&config).try_into()
-//     }
-// }
-
-// /// Configuration for [`FsBackend`].
-// #[derive(Debug, Clone)]
-// pub struct FsBackendConfig {
-//     /// Root directory for the backend.
-//     pub root_dir: String,
-
-//     /// Atomic write dir.
-//     pub atomic_write_dir: Option<String>,
-// }
-
-// impl From<&FsBackendConfig> for Fs {
-//     fn from(config: &FsBackendConfig) -> Self {
-//         let mut builder = Fs::default();
-//         builder.root(&config.root_dir);
-
-//         if let Some(awd) = &config.atomic_write_dir {
-//             builder.atomic_write_dir(awd);
-//         }
-//         builder
-//     }
-// }
+/// Configuration for [`FsBackend`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FsBackendConfig {
+    /// Root directory for the backend.
+    pub root: String,
+
+    /// Atomic write dir.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub atomic_write_dir: Option<String>,
+}
+
+impl FsBackendConfig {
+    /// Validate the config.
+    pub fn validate(&self) -> Result<(), BackendConfigError> {
+        ensure_non_empty("root", &self.root)?;
+        if let Some(awd) = &self.atomic_write_dir {
+            ensure_non_empty("atomic_write_dir", awd)?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&FsBackendConfig> for Fs {
+    type Error = BackendConfigError;
+
+    fn try_from(config: &FsBackendConfig) -> Result<Self, Self::Error> {
+        config.validate()?;
+
+        let mut builder = Fs::default();
+        builder.root(&config.root);
+
+        if let Some(awd) = &config.atomic_write_dir {
+            builder.atomic_write_dir(awd);
+        }
+        Ok(builder)
+    }
+}
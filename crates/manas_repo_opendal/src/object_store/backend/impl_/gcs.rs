@@ -5,6 +5,7 @@
 use flagset::FlagSet;
 use opendal::{services::Gcs, Operator};
 
+use super::config::{ensure_http_endpoint, ensure_non_empty, BackendConfigError, SecretValue};
 use crate::object_store::backend::{
     path_es::impl_::identical::IdenticalBackendObjectPathEncodingScheme, BackendExtraCapability,
     BuildableODRObjectStoreBackend, ODRObjectStoreBackend,
@@ -49,3 +50,92 @@ impl TryFrom<Gcs> for GcsBackend {
 }
 
 impl BuildableODRObjectStoreBackend<Gcs> for GcsBackend {}
+
+/// Configuration for [`GcsBackend`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GcsBackendConfig {
+    /// Name of the bucket.
+    pub bucket: String,
+
+    /// Root path in the bucket.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+
+    /// Endpoint of the gcs service.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+
+    /// Oauth scope.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+
+    /// Service account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_account: Option<String>,
+
+    /// Base64 encoded credential.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential: Option<SecretValue>,
+
+    /// Path to the credential file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_path: Option<String>,
+
+    /// Default storage class of objects.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_storage_class: Option<String>,
+}
+
+impl GcsBackendConfig {
+    /// Validate the config.
+    pub fn validate(&self) -> Result<(), BackendConfigError> {
+        ensure_non_empty("bucket", &self.bucket)?;
+        ensure_http_endpoint("endpoint", self.endpoint.as_deref())?;
+
+        if self.credential.is_some() && self.credential_path.is_some() {
+            return Err(BackendConfigError::InvalidField {
+                field: "credential",
+                reason: "Only one of credential and credential path can be provided.".to_owned(),
+            });
+        }
+
+        if let Some(credential) = &self.credential {
+            credential.resolve()?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&GcsBackendConfig> for Gcs {
+    type Error = BackendConfigError;
+
+    fn try_from(config: &GcsBackendConfig) -> Result<Self, Self::Error> {
+        config.validate()?;
+
+        let mut builder = Gcs::default();
+        builder.bucket(&config.bucket);
+
+        if let Some(root) = &config.root {
+            builder.root(root);
+        }
+        if let Some(endpoint) = &config.endpoint {
+            builder.endpoint(endpoint);
+        }
+        if let Some(scope) = &config.scope {
+            builder.scope(scope);
+        }
+        if let Some(service_account) = &config.service_account {
+            builder.service_account(service_account);
+        }
+        if let Some(credential) = &config.credential {
+            builder.credential(&credential.resolve()?);
+        }
+        if let Some(credential_path) = &config.credential_path {
+            builder.credential_path(credential_path);
+        }
+        if let Some(default_storage_class) = &config.default_storage_class {
+            builder.default_storage_class(default_storage_class);
+        }
+        Ok(builder)
+    }
+}
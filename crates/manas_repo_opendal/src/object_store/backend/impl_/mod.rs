@@ -3,6 +3,14 @@
 
 pub mod common;
 
+#[cfg(any(
+    feature = "backend-fs",
+    feature = "backend-s3",
+    feature = "backend-gcs",
+    feature = "backend-embedded"
+))]
+pub mod config;
+
 #[cfg(feature = "backend-fs")]
 pub mod fs;
 
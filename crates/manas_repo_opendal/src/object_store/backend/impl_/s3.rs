@@ -5,6 +5,7 @@
 use flagset::FlagSet;
 use opendal::{services::S3, Operator};
 
+use super::config::{ensure_http_endpoint, ensure_non_empty, BackendConfigError, SecretValue};
 use crate::object_store::backend::{
     path_es::impl_::identical::IdenticalBackendObjectPathEncodingScheme, BackendExtraCapability,
     BuildableODRObjectStoreBackend, ODRObjectStoreBackend,
@@ -49,3 +50,231 @@ impl TryFrom<S3> for S3Backend {
 }
 
 impl BuildableODRObjectStoreBackend<S3> for S3Backend {}
+
+/// Configuration for [`S3Backend`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct S3BackendConfig {
+    /// Name of the bucket.
+    pub bucket: String,
+
+    /// Root path in the bucket.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+
+    /// Endpoint of the s3 service.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+
+    /// Region of the bucket.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+
+    /// Access key id.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_key_id: Option<SecretValue>,
+
+    /// Secret access key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_access_key: Option<SecretValue>,
+
+    /// Session token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security_token: Option<SecretValue>,
+
+    /// Whether to use virtual host style requests.
+    #[serde(default)]
+    pub enable_virtual_host_style: bool,
+
+    /// Whether to disable loading config from environment.
+    #[serde(default)]
+    pub disable_config_load: bool,
+
+    /// Whether to allow anonymous requests.
+    #[serde(default)]
+    pub allow_anonymous: bool,
+}
+
+impl S3BackendConfig {
+    /// Validate the config.
+    pub fn validate(&self) -> Result<(), BackendConfigError> {
+        ensure_non_empty("bucket", &self.bucket)?;
+        ensure_http_endpoint("endpoint", self.endpoint.as_deref())?;
+
+        match (&self.access_key_id, &self.secret_access_key) {
+            (Some(_), None) | (None, Some(_)) => {
+                return Err(BackendConfigError::InvalidField {
+                    field: "access_key_id",
+                    reason: "Access key id and secret access key must be provided together."
+                        .to_owned(),
+                })
+            }
+            _ => {}
+        }
+
+        for secret in [
+            &self.access_key_id,
+            &self.secret_access_key,
+            &self.security_token,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            secret.resolve()?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&S3BackendConfig> for S3 {
+    type Error = BackendConfigError;
+
+    fn try_from(config: &S3BackendConfig) -> Result<Self, Self::Error> {
+        config.validate()?;
+
+        let mut builder = S3::default();
+        builder.bucket(&config.bucket);
+
+        if let Some(root) = &config.root {
+            builder.root(root);
+        }
+        if let Some(endpoint) = &config.endpoint {
+            builder.endpoint(endpoint);
+        }
+        if let Some(region) = &config.region {
+            builder.region(region);
+        }
+        if let Some(access_key_id) = &config.access_key_id {
+            builder.access_key_id(&access_key_id.resolve()?);
+        }
+        if let Some(secret_access_key) = &config.secret_access_key {
+            builder.secret_access_key(&secret_access_key.resolve()?);
+        }
+        if let Some(security_token) = &config.security_token {
+            builder.security_token(&security_token.resolve()?);
+        }
+        if config.enable_virtual_host_style {
+            builder.enable_virtual_host_style();
+        }
+        if config.disable_config_load {
+            builder.disable_config_load();
+        }
+        if config.allow_anonymous {
+            builder.allow_anonymous();
+        }
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use claims::*;
+
+    use super::*;
+    use crate::object_store::backend::impl_::config::ODRBackendConfig;
+
+    fn new_config() -> S3BackendConfig {
+        S3BackendConfig {
+            bucket: "pods".to_owned(),
+            root: None,
+            endpoint: Some("https://s3.example.org".to_owned()),
+            region: Some("us-east-1".to_owned()),
+            access_key_id: Some(SecretValue::Inline("key-id".to_owned())),
+            secret_access_key: Some(SecretValue::Env {
+                env: "MANAS_TEST_S3_SECRET_ACCESS_KEY".to_owned(),
+            }),
+            security_token: None,
+            enable_virtual_host_style: false,
+            disable_config_load: true,
+            allow_anonymous: false,
+        }
+    }
+
+    #[test]
+    fn valid_config_resolves_builder() {
+        std::env::set_var("MANAS_TEST_S3_SECRET_ACCESS_KEY", "secret");
+        assert_ok!(new_config().validate());
+        assert_ok!(S3::try_from(&new_config()));
+    }
+
+    #[test]
+    fn empty_bucket_is_rejected() {
+        std::env::set_var("MANAS_TEST_S3_SECRET_ACCESS_KEY", "secret");
+        let config = S3BackendConfig {
+            bucket: "".to_owned(),
+            ..new_config()
+        };
+        assert_matches!(
+            config.validate(),
+            Err(BackendConfigError::EmptyField("bucket"))
+        );
+    }
+
+    #[test]
+    fn non_http_endpoint_is_rejected() {
+        std::env::set_var("MANAS_TEST_S3_SECRET_ACCESS_KEY", "secret");
+        let config = S3BackendConfig {
+            endpoint: Some("s3.example.org".to_owned()),
+            ..new_config()
+        };
+        assert_matches!(
+            config.validate(),
+            Err(BackendConfigError::InvalidField {
+                field: "endpoint",
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn unpaired_access_key_is_rejected() {
+        let config = S3BackendConfig {
+            secret_access_key: None,
+            ..new_config()
+        };
+        assert_matches!(
+            config.validate(),
+            Err(BackendConfigError::InvalidField {
+                field: "access_key_id",
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn unresolvable_secret_is_rejected() {
+        let config = S3BackendConfig {
+            security_token: Some(SecretValue::Env {
+                env: "MANAS_TEST_S3_SECURITY_TOKEN_UNSET".to_owned(),
+            }),
+            ..new_config()
+        };
+        assert_matches!(config.validate(), Err(BackendConfigError::MissingEnvVar(_)));
+        assert_err!(S3::try_from(&config));
+    }
+
+    #[test]
+    fn config_is_validated_on_deserialization() {
+        assert_ok!(serde_json::from_str::<ODRBackendConfig>(
+            r#"{"type": "s3", "bucket": "pods", "allow_anonymous": true}"#
+        ));
+        assert_err!(serde_json::from_str::<ODRBackendConfig>(
+            r#"{"type": "s3", "bucket": "pods", "access_key_id": "key-id"}"#
+        ));
+        assert_err!(serde_json::from_str::<ODRBackendConfig>(
+            r#"{"type": "s3", "bucket": "pods", "endpoint": "ftp://s3.example.org"}"#
+        ));
+    }
+
+    #[test]
+    fn serialized_config_redacts_inline_secrets() {
+        let config = S3BackendConfig {
+            access_key_id: Some(SecretValue::Inline("key-id".to_owned())),
+            secret_access_key: Some(SecretValue::Inline("s3cret".to_owned())),
+            ..new_config()
+        };
+        let serialized = assert_ok!(serde_json::to_string(&config));
+        assert!(!serialized.contains("key-id"), "{serialized}");
+        assert!(!serialized.contains("s3cret"), "{serialized}");
+        assert!(serialized.contains(r#""bucket":"pods""#), "{serialized}");
+    }
+}
@@ -204,6 +204,14 @@ pub trait ODRNamespaceObjectExt<OstSetup: ODRObjectStoreSetup>: seal::Sealed {
 
     /// List odr objects in this namespace.
     async fn list(&self) -> Result<DecodedODRObjectStream<OstSetup>, opendal::Error>;
+
+    /// List odr objects in this namespace, that are after
+    /// the object with given backend path in listing order.
+    /// Backend must have `list_with_start_after` capability.
+    async fn list_after(
+        &self,
+        start_after: &str,
+    ) -> Result<DecodedODRObjectStream<OstSetup>, opendal::Error>;
 }
 
 #[async_trait]
@@ -218,40 +226,64 @@ impl<'id, OstSetup: ODRObjectStoreSetup> ODRNamespaceObjectExt<OstSetup>
             .await
     }
 
+    #[inline]
     async fn list(&self) -> Result<DecodedODRObjectStream<OstSetup>, opendal::Error> {
-        let backend_listing: Lister = self
-            .backend
-            .operator()
-            .lister_with(self.backend_entry.path())
-            .metakey(*ODR_OBJECT_METAKEY)
-            .inspect_err(|_| error!("Error in getting backend entries."))
-            .await?;
+        list_namespace_objects(self, None).await
+    }
 
-        let backend = self.backend.clone();
-        let object_space = self.id.space.clone();
+    #[inline]
+    async fn list_after(
+        &self,
+        start_after: &str,
+    ) -> Result<DecodedODRObjectStream<OstSetup>, opendal::Error> {
+        list_namespace_objects(self, Some(start_after)).await
+    }
+}
+
+/// List odr objects in given namespace, optionally after
+/// given backend path.
+async fn list_namespace_objects<OstSetup: ODRObjectStoreSetup>(
+    ns_object: &ODRNamespaceObject<'_, OstSetup>,
+    start_after: Option<&str>,
+) -> Result<DecodedODRObjectStream<OstSetup>, opendal::Error> {
+    let mut backend_lister = ns_object
+        .backend
+        .operator()
+        .lister_with(ns_object.backend_entry.path())
+        .metakey(*ODR_OBJECT_METAKEY);
+
+    if let Some(start_after) = start_after {
+        backend_lister = backend_lister.start_after(start_after);
+    }
 
-        Ok(Box::pin(async_stream::try_stream! {
-            for await entry in backend_listing {
-                let entry = entry?;
-                // Get mode from metadata.
-                let mode = entry.metadata().mode();
+    let backend_listing: Lister = backend_lister
+        .inspect_err(|_| error!("Error in getting backend entries."))
+        .await?;
 
-                // If unknown mode, yield error.
-                if mode ==EntryMode::Unknown {
-                    Err(ODRObjectYieldError::InvalidBackendObjectMode)?;
-                }
+    let backend = ns_object.backend.clone();
+    let object_space = ns_object.id.space.clone();
 
-                // Decode odr object.
-                let odr_object: ODRObject<'static, OstSetup> = ODRObject::try_new_from_cached_entry(
-                    entry,
-                    backend.clone(),
-                    object_space.clone()
-                ).map_err(|e| ODRObjectYieldError::BackendPathDecodeError(e))?;
+    Ok(Box::pin(async_stream::try_stream! {
+        for await entry in backend_listing {
+            let entry = entry?;
+            // Get mode from metadata.
+            let mode = entry.metadata().mode();
 
-                yield odr_object;
+            // If unknown mode, yield error.
+            if mode ==EntryMode::Unknown {
+                Err(ODRObjectYieldError::InvalidBackendObjectMode)?;
             }
-        }))
-    }
+
+            // Decode odr object.
+            let odr_object: ODRObject<'static, OstSetup> = ODRObject::try_new_from_cached_entry(
+                entry,
+                backend.clone(),
+                object_space.clone()
+            ).map_err(|e| ODRObjectYieldError::BackendPathDecodeError(e))?;
+
+            yield odr_object;
+        }
+    }))
 }
 
 /// An error type for representing error in yielding an odr object in namespace listing.
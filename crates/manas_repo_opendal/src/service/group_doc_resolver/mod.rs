@@ -0,0 +1,180 @@
+//! I define an implementation of [`AgentGroupDocResolver`] backed by
+//! odr, that resolves group documents in the repo's storage.
+//!
+
+use std::sync::Arc;
+
+use capped_stream::OutOfSizeLimitError;
+use dyn_problem::{type_::UNKNOWN_IO_ERROR, ProbFuture};
+use futures::{TryFutureExt, TryStreamExt};
+use manas_access_control::model::pdp::impl_::wac::group_doc_resolver::{
+    AgentGroupDoc, AgentGroupDocResolver, AGENT_GROUP_DOC_TOO_LARGE, INVALID_AGENT_GROUP_DOC,
+    UNRESOLVABLE_AGENT_GROUP_DOC,
+};
+use manas_http::representation::{
+    impl_::{binary::BinaryRepresentation, common::data::bytes_inmem::BytesInmem},
+    Representation,
+};
+use manas_repo::{
+    context::RepoContextual,
+    service::resource_operator::reader::rep_preferences::{
+        range_negotiator::impl_::CompleteRangeNegotiator, version::RepVersionPreference,
+        ContainerRepresentationPreference, RepresentationPreferences,
+    },
+};
+use manas_space::resource::uri::SolidResourceUri;
+use rdf_dynsyn::syntax::invariant::parsable::DynSynParsableSyntax;
+use rdf_utils::model::{quad::ArcQuad, term::ArcTerm};
+use tower::BoxError;
+use tracing::error;
+
+use super::resource_operator::reader::ODRResourceReader;
+use crate::{
+    context::ODRContext,
+    resource_context::{invariant::ODRClassifiedResourceContext, ODRResourceContext},
+    setup::ODRSetup,
+    status_token::{
+        inputs::ODRResourceStatusTokenInputs, variant::ODRExistingRepresentedResourceToken,
+    },
+    OpendalRepo,
+};
+
+/// An implementation of [`AgentGroupDocResolver`] backed by
+/// odr, that resolves group documents in the repo's storage.
+#[derive(Debug, Clone)]
+pub struct ODRAgentGroupDocResolver<Setup: ODRSetup> {
+    /// Repo context.
+    repo_context: Arc<ODRContext<Setup>>,
+
+    /// Max size of group documents in bytes.
+    max_doc_size: u64,
+}
+
+impl<Setup> RepoContextual for ODRAgentGroupDocResolver<Setup>
+where
+    Setup: ODRSetup,
+{
+    type Repo = OpendalRepo<Setup>;
+
+    #[inline]
+    fn new_with_context(repo_context: Arc<ODRContext<Setup>>) -> Self {
+        Self {
+            repo_context,
+            // 1 MiB by default.
+            max_doc_size: 1024 * 1024,
+        }
+    }
+
+    #[inline]
+    fn repo_context(&self) -> &Arc<ODRContext<Setup>> {
+        &self.repo_context
+    }
+}
+
+impl<Setup: ODRSetup> ODRAgentGroupDocResolver<Setup> {
+    /// Set max size of group documents in bytes.
+    #[inline]
+    pub fn with_max_doc_size(mut self, max_doc_size: u64) -> Self {
+        self.max_doc_size = max_doc_size;
+        self
+    }
+}
+
+impl<Setup: ODRSetup> AgentGroupDocResolver for ODRAgentGroupDocResolver<Setup> {
+    #[tracing::instrument(skip_all, name = "ODRAgentGroupDocResolver::resolve", fields(doc_uri))]
+    fn resolve(&self, doc_uri: SolidResourceUri) -> ProbFuture<'static, Arc<AgentGroupDoc>> {
+        let repo_context = self.repo_context.clone();
+        let max_doc_size = self.max_doc_size;
+
+        Box::pin(async move {
+            // Check backend capabilities.
+            ODRResourceReader::<Setup>::ensure_backend_caps(&repo_context)?;
+
+            // Decode resource context.
+            let res_context = ODRClassifiedResourceContext::new(Arc::new(
+                ODRResourceContext::try_new(doc_uri, repo_context).map_err(|e| {
+                    error!("Error in decoding context for group doc. Error:\n {}", e);
+                    UNRESOLVABLE_AGENT_GROUP_DOC
+                        .new_problem_builder()
+                        .source(e)
+                        .finish()
+                })?,
+            ));
+
+            let status_token_inputs =
+                ODRResourceStatusTokenInputs::try_current(res_context.clone())
+                    .await
+                    .map_err(|e| {
+                        error!("Error in resolving group doc status inputs. Error:\n {}", e);
+                        UNKNOWN_IO_ERROR.new_problem_builder().source(e).finish()
+                    })?;
+
+            // Get represented status token.
+            let er_token = ODRExistingRepresentedResourceToken::try_from(status_token_inputs)
+                .map_err(|_| {
+                    error!("Group doc is not represented.");
+                    UNRESOLVABLE_AGENT_GROUP_DOC.new_problem()
+                })?;
+
+            // Get representation.
+            let rep: BinaryRepresentation = er_token
+                .try_resolve_representation(RepresentationPreferences {
+                    container_rep_preference: ContainerRepresentationPreference::Minimal,
+                    container_page_preference: None,
+                    non_container_rep_range_negotiator: Box::new(CompleteRangeNegotiator),
+                    version_preference: RepVersionPreference::Current,
+                })
+                .await
+                .map_err(ODRResourceReader::<Setup>::map_state_resolution_err)?;
+
+            let parsable_syntax = rep
+                .metadata()
+                .rdf_syntax::<DynSynParsableSyntax>()
+                .ok_or_else(|| {
+                    error!("Group doc rep content type is not quads parsable.");
+                    INVALID_AGENT_GROUP_DOC.new_problem()
+                })?
+                .value;
+
+            // Load the rep, while enforcing the limit.
+            let rep_inmem: BinaryRepresentation<BytesInmem> =
+                async_convert::TryFrom::try_from(rep.into_stream_size_capped(max_doc_size))
+                    .map_err(|e: BoxError| {
+                        error!("Error in loading group doc. Error:\n {}", e);
+                        if e.downcast_ref::<OutOfSizeLimitError>().is_some() {
+                            AGENT_GROUP_DOC_TOO_LARGE.new_problem()
+                        } else {
+                            UNKNOWN_IO_ERROR
+                                .new_problem_builder()
+                                .source_in_a_box(e)
+                                .finish()
+                        }
+                    })
+                    .await?;
+
+            // Parse the doc.
+            let rep = BinaryRepresentation::from(rep_inmem);
+            let rep_base_uri = rep.base_uri().as_ref().map(Into::into);
+            let doc = res_context
+                .repo_context()
+                .config
+                .dynsyn_factories
+                .parser
+                .parse_quads_from_bytes_stream::<_, ArcTerm>(
+                    rep.into_streaming().into_parts().0.stream,
+                    rep_base_uri,
+                    parsable_syntax,
+                )
+                .await
+                .map_ok(|q: ArcQuad| q.0)
+                .try_collect::<AgentGroupDoc>()
+                .await
+                .map_err(|_| {
+                    error!("Error in parsing group doc as rdf source doc.");
+                    INVALID_AGENT_GROUP_DOC.new_problem()
+                })?;
+
+            Ok(Arc::new(doc))
+        })
+    }
+}
@@ -10,3 +10,20 @@ pub mod initializer;
 #[cfg(feature = "access-prp")]
 /// I define access control policy retrieval point for ODR.
 pub mod prp;
+
+#[cfg(feature = "access-group-doc-resolver")]
+/// I define agent group doc resolver for ODR.
+pub mod group_doc_resolver;
+
+/// Name of the hidden namespace under storage root, that
+/// holds versions of resources.
+#[cfg(any(feature = "quota-usage-scanner", feature = "version-store"))]
+pub(crate) const VERSIONS_NAMESPACE: &str = ".__versions/";
+
+#[cfg(feature = "quota-usage-scanner")]
+/// I define quota usage scanner for ODR.
+pub mod quota_usage_scanner;
+
+#[cfg(feature = "version-store")]
+/// I define version store for ODR.
+pub mod version_store;
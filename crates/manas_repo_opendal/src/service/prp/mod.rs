@@ -8,6 +8,7 @@ use manas_access_control::model::{
     pdp::UNKNOWN_TARGET_RESOURCE,
     prp::{PolicyRetrievalPoint, SlotAcrChain, SlotAcrChainItem},
 };
+use manas_authentication::common::credentials::CreatorCredentials;
 use manas_http::representation::{impl_::binary::BinaryRepresentation, Representation};
 use manas_repo::{
     context::RepoContextual,
@@ -17,8 +18,8 @@ use manas_repo::{
             UNSUPPORTED_OPERATION,
         },
         reader::rep_preferences::{
-            range_negotiator::impl_::CompleteRangeNegotiator, ContainerRepresentationPreference,
-            RepresentationPreferences,
+            range_negotiator::impl_::CompleteRangeNegotiator, version::RepVersionPreference,
+            ContainerRepresentationPreference, RepresentationPreferences,
         },
     },
 };
@@ -37,7 +38,8 @@ use crate::{
     resource_context::{invariant::ODRClassifiedResourceContext, ODRResourceContext},
     setup::{aux_rep_policy::ODRAuxResourcePolicyExt, ODRSetup},
     status_token::{
-        inputs::ODRResourceStatusTokenInputs, variant::ODRExistingRepresentedResourceToken,
+        inputs::{read_altfm_obj_content, ODRResourceStatusTokenInputs},
+        variant::ODRExistingRepresentedResourceToken,
         ODRBaseResourceStatusToken,
     },
     OpendalRepo,
@@ -162,6 +164,76 @@ where
                 >)
         })
     }
+
+    #[tracing::instrument(
+        skip_all,
+        name = "ODRPolicyRetrievalPoint::resolve_creator",
+        fields(resource_uri)
+    )]
+    fn resolve_creator(
+        &self,
+        resource_uri: &SolidResourceUri,
+    ) -> ProbFuture<'static, Option<CreatorCredentials>> {
+        let repo_context = self.repo_context.clone();
+        let res_uri = resource_uri.clone();
+
+        Box::pin(async move {
+            // Decode resource context.
+            let res_context = ODRClassifiedResourceContext::new(Arc::new(
+                ODRResourceContext::try_new(res_uri, repo_context).map_err(|e| {
+                    error!("Error in decoding context for resource. Error:\n {}", e);
+                    UNKNOWN_TARGET_RESOURCE
+                        .new_problem_builder()
+                        .source(e)
+                        .finish()
+                })?,
+            ));
+
+            let is_cty_capable_backend = res_context
+                .repo_context()
+                .object_store
+                .is_cty_capable_backend();
+
+            let mut res_status_token_inputs =
+                ODRResourceStatusTokenInputs::try_current(res_context)
+                    .await
+                    .map_err(|e| {
+                        error!("Error in resolving resource status inputs. Error:\n {}", e);
+                        UNKNOWN_IO_ERROR.new_problem_builder().source(e).finish()
+                    })?;
+
+            // Status inputs skip altfm with cty capable
+            // backends. But it still records the creator.
+            if is_cty_capable_backend && res_status_token_inputs.base_obj_metadata.is_some() {
+                res_status_token_inputs.altfm_obj_content =
+                    read_altfm_obj_content(res_status_token_inputs.res_context.clone())
+                        .await
+                        .map_err(|e| {
+                            error!("Error in reading altfm object. Error:\n {}", e);
+                            UNKNOWN_IO_ERROR.new_problem_builder().source(e).finish()
+                        })?;
+            }
+
+            // Get represented status token.
+            let res_er_token = if let Ok(token) =
+                ODRExistingRepresentedResourceToken::try_from(res_status_token_inputs)
+            {
+                token
+            } else {
+                info!("Resource is not represented.");
+                return Ok(None);
+            };
+
+            Ok(res_er_token
+                .try_resolve_effective_alt_metadata()
+                // Invalid altfm object records no creator.
+                .unwrap_or_else(|e| {
+                    error!("Invalid altfm object. Error:\n {}", e);
+                    None
+                })
+                .and_then(|alt_metadata| alt_metadata.creator))
+        })
+    }
 }
 
 impl<Setup, G> ODRPolicyRetrievalPoint<Setup, G>
@@ -195,7 +267,9 @@ where
         let rep: BinaryRepresentation = acl_er_token
             .try_resolve_representation(RepresentationPreferences {
                 container_rep_preference: ContainerRepresentationPreference::Minimal,
+                container_page_preference: None,
                 non_container_rep_range_negotiator: Box::new(CompleteRangeNegotiator),
+                version_preference: RepVersionPreference::Current,
             })
             .await
             .map_err(ODRResourceReader::<Setup>::map_state_resolution_err)?;
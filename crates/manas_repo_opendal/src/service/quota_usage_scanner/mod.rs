@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use dyn_problem::{type_::UNKNOWN_IO_ERROR, ProbFuture};
+use futures::TryStreamExt;
+use manas_repo::context::{RepoContext, RepoContextual};
+use manas_repo_layers::quota::{tracker::QuotaUsage, usage_scanner::QuotaUsageScanner};
+use manas_space::SolidStorageSpace;
+use opendal::{EntryMode, Metakey};
+use tracing::{error, warn};
+
+use super::VERSIONS_NAMESPACE;
+use crate::{
+    context::ODRContext,
+    object_store::{
+        backend::ODRObjectStoreBackend,
+        object_space::assoc::rel_type::{sidecar::SidecarRelType, AssocRelType},
+    },
+    setup::ODRSetup,
+    OpendalRepo,
+};
+
+/// An implementation of [`QuotaUsageScanner`] backed by odr,
+/// that computes usage by listing all objects in the repo's
+/// backend.
+///
+/// Resources are counted by their base objects, and bytes by
+/// their representation content objects. Version objects in
+/// the hidden versions namespace are accounted as bytes, but
+/// not as resources. Other associated objects are not
+/// accounted.
+#[derive(Debug, Clone)]
+pub struct ODRQuotaUsageScanner<Setup: ODRSetup> {
+    /// Repo context.
+    repo_context: Arc<ODRContext<Setup>>,
+}
+
+impl<Setup> RepoContextual for ODRQuotaUsageScanner<Setup>
+where
+    Setup: ODRSetup,
+{
+    type Repo = OpendalRepo<Setup>;
+
+    #[inline]
+    fn new_with_context(repo_context: Arc<ODRContext<Setup>>) -> Self {
+        Self { repo_context }
+    }
+
+    #[inline]
+    fn repo_context(&self) -> &Arc<ODRContext<Setup>> {
+        &self.repo_context
+    }
+}
+
+impl<Setup: ODRSetup> QuotaUsageScanner for ODRQuotaUsageScanner<Setup> {
+    #[tracing::instrument(skip_all, name = "ODRQuotaUsageScanner::scan_usage")]
+    fn scan_usage(&self) -> ProbFuture<'static, QuotaUsage> {
+        let repo_context = self.repo_context.clone();
+
+        Box::pin(async move {
+            let object_store = &repo_context.object_store;
+
+            // Resolve base object of the storage root.
+            let root_object = object_store
+                .assoc_odr_object(
+                    repo_context.storage_space().root_res_uri(),
+                    AssocRelType::Base,
+                )
+                .map_err(|e| {
+                    error!("Error in resolving storage root object. Error:\n {}", e);
+                    UNKNOWN_IO_ERROR.new_problem_builder().source(e).finish()
+                })?;
+            let root_path = root_object.backend_entry().path().to_owned();
+            let versions_path = format!("{}{}", root_path, VERSIONS_NAMESPACE);
+
+            let mut lister = object_store
+                .backend
+                .operator()
+                .lister_with(&root_path)
+                .recursive(true)
+                .metakey(Metakey::ContentLength | Metakey::Mode)
+                .await
+                .map_err(|e| {
+                    error!("Error in listing backend objects. Error:\n {}", e);
+                    UNKNOWN_IO_ERROR.new_problem_builder().source(e).finish()
+                })?;
+
+            // Storage root is not included in the listing.
+            let mut usage = QuotaUsage {
+                bytes: 0,
+                resources: 1,
+            };
+
+            while let Some(entry) = lister.try_next().await.map_err(|e| {
+                error!("Error in listing backend objects. Error:\n {}", e);
+                UNKNOWN_IO_ERROR.new_problem_builder().source(e).finish()
+            })? {
+                if entry.path() == root_path {
+                    continue;
+                }
+
+                let is_file = entry.metadata().mode() == EntryMode::FILE;
+                let content_length = entry.metadata().content_length();
+
+                // Versions are not resources, but consume
+                // storage.
+                if entry.path().starts_with(&versions_path) {
+                    if is_file {
+                        usage.bytes += content_length;
+                    }
+                    continue;
+                }
+
+                let assoc_rev_link = match object_store
+                    .odr_object_from_backend_entry(entry)
+                    .ok()
+                    .and_then(|odr_object| odr_object.assoc_rev_link().ok())
+                {
+                    Some(rev_link) => rev_link,
+                    None => {
+                        warn!("Skipping backend object with no associated resource.");
+                        continue;
+                    }
+                };
+
+                match assoc_rev_link.rev_rel_type {
+                    AssocRelType::Base => {
+                        usage.resources += 1;
+                        // Base objects of non-containers hold
+                        // their representations.
+                        if is_file {
+                            usage.bytes += content_length;
+                        }
+                    }
+                    // Altcontent objects hold representations
+                    // of containers.
+                    AssocRelType::Sidecar(SidecarRelType::AltContent) => {
+                        usage.bytes += content_length;
+                    }
+                    _ => {}
+                }
+            }
+
+            Ok(usage)
+        })
+    }
+}
@@ -2,6 +2,7 @@
 //! fat objects of alt-metadata of an object.
 //!
 
+use manas_authentication::common::credentials::CreatorCredentials;
 use manas_http::header::common::media_type::MediaType;
 use opendal::Metadata;
 use serde::{Deserialize, Serialize};
@@ -16,6 +17,10 @@ pub struct AltMetadata {
     /// Content-type of the alt representation.
     #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
     pub content_type: Option<MediaType>,
+
+    /// Credentials of the resource creator.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creator: Option<CreatorCredentials>,
 }
 
 /// Type alias for alt fat metadata.
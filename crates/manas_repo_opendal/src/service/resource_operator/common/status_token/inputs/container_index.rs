@@ -1,10 +1,10 @@
 //! I define types for resolving container index.
 //!
 
-use std::{ops::Deref, sync::Arc};
+use std::{collections::VecDeque, ops::Deref, sync::Arc};
 
 use dashmap::DashMap;
-use futures::TryFutureExt;
+use futures::{stream::BoxStream, StreamExt};
 use manas_http::{
     header::{common::media_type::MediaType, last_modified::LastModifiedExt},
     representation::{
@@ -12,11 +12,15 @@ use manas_http::{
         metadata::{KCompleteContentLength, KContentType, KLastModified},
     },
 };
+use manas_repo::service::resource_operator::reader::rep_preferences::{
+    ContainerPagePreference, ContainerRepPage,
+};
 use manas_space::resource::kind::SolidResourceKind;
 use once_cell::sync::Lazy;
 use rdf_utils::model::term::{ArcIri, ArcTerm, CompatTerm};
 use rdf_vocabularies::ns;
 use sophia_api::{ns::NsTerm, prelude::Iri, term::Term};
+use tower::BoxError;
 use tracing::{error, warn};
 use typed_record::TypedRecord;
 
@@ -24,10 +28,11 @@ use super::{query_altfm_obj_content, ODRResourceStatusTokenInputs};
 use crate::{
     object_store::{
         object::{
-            invariant::{DecodedODRObjectStream, ODRNamespaceObjectExt},
+            invariant::{DecodedODRObjectStream, ODRNamespaceObject, ODRNamespaceObjectExt},
             ODRObject,
         },
         object_space::assoc::rel_type::AssocRelType,
+        ODRObjectStoreSetup,
     },
     resource_context::{
         invariant::{ODRClassifiedResourceContext, ODRContainerContext},
@@ -75,6 +80,19 @@ pub struct ODRContainerIndexInputs<Setup: ODRSetup> {
     pub c_res_context: ODRContainerContext<Setup>,
 }
 
+/// Type of stream of contained resources of a container,
+/// with their associated base objects.
+type ChildStream<Setup> = BoxStream<
+    'static,
+    Result<
+        (
+            ODRObject<'static, <Setup as ODRSetup>::ObjectStoreSetup>,
+            ODRClassifiedResourceContext<Setup>,
+        ),
+        BoxError,
+    >,
+>;
+
 impl<Setup: ODRSetup> ODRContainerIndexInputs<Setup> {
     /// Resolve container index.
     #[tracing::instrument(
@@ -85,32 +103,154 @@ impl<Setup: ODRSetup> ODRContainerIndexInputs<Setup> {
         )
     )]
     pub async fn resolve(&self) -> Result<BoxQuadsStream, opendal::Error> {
-        let c_res_context = self.c_res_context.as_ref().as_ref();
+        let children = self.resolve_children(None).await?;
+        Ok(self.index_quads(children))
+    }
 
-        let repo_context = c_res_context.repo_context().clone();
+    /// Resolve a page of container index, as per given page
+    /// preference.
+    ///
+    /// Pages are delimited in backend listing order, and
+    /// cursor of a page is the backend path of the base object
+    /// of the last contained resource of the preceding page,
+    /// relative to the container's namespace object. Like
+    /// opendal's `start_after`, a page contains the resources
+    /// listed after it's cursor.
+    ///
+    /// If backend supports `start_after` listing, it is used
+    /// to skip preceding resources. Then the previous page is
+    /// not resolved, as preceding resources are not listed.
+    /// Otherwise, listing is scanned up to the cursor, and if
+    /// cursor doesn't correspond to any contained resource,
+    /// resolved page will be empty.
+    #[tracing::instrument(
+        skip_all,
+        name = "ODRContainerIndexInputs::resolve_page",
+        fields(
+            res_uri = self.c_res_context.uri().as_str()
+        )
+    )]
+    pub async fn resolve_page(
+        &self,
+        page_preference: &ContainerPagePreference,
+    ) -> Result<(BoxQuadsStream, ContainerRepPage), opendal::Error> {
+        let max_page_size = page_preference.max_page_size.get();
+        let ns_path = self.indicator_object().backend_entry().path().to_owned();
 
-        let container_uri = c_res_context.uri().clone();
+        let lists_after_cursor = page_preference.cursor.is_some()
+            && self
+                .c_res_context
+                .as_ref()
+                .as_ref()
+                .repo_context()
+                .backend_caps()
+                .list_with_start_after;
 
-        // Resolve container name.
-        let container_name = container_uri.deref().into_term::<ArcTerm>();
+        let mut children = self
+            .resolve_children(
+                page_preference
+                    .cursor
+                    .as_ref()
+                    .filter(|_| lists_after_cursor)
+                    .map(|cursor| format!("{}{}", ns_path, cursor))
+                    .as_deref(),
+            )
+            .await?;
 
-        // Get predicate terms.
-        let p_contains: ArcTerm = ns::ldp::contains.into_term();
-        let p_size: ArcTerm = ns::stat::size.into_term();
-        let p_type: ArcTerm = ns::rdf::type_.into_term();
-        let p_modified: ArcTerm = ns::dcterms::modified.into_term();
+        // Cursors of children preceding the page, bounded to
+        // page size and the cursor of the previous page.
+        let mut preceding_cursors = VecDeque::with_capacity(max_page_size + 1);
+        let mut page_children = Vec::new();
+        let mut page_size = 0;
+        let mut is_in_page = page_preference.cursor.is_none() || lists_after_cursor;
+        let mut last_cursor = None;
+        let mut has_next_page = false;
+
+        while let Some(child) = children.next().await {
+            let child_cursor = child
+                .as_ref()
+                .ok()
+                .map(|(child_object, _)| child_page_cursor(&ns_path, child_object));
+
+            if !is_in_page {
+                // Skip errors preceding the page.
+                if let Some(cursor) = child_cursor {
+                    is_in_page = Some(&cursor) == page_preference.cursor.as_ref();
+                    if preceding_cursors.len() == max_page_size + 1 {
+                        preceding_cursors.pop_front();
+                    }
+                    preceding_cursors.push_back(cursor);
+                }
+                continue;
+            }
+
+            // If page is full, next child starts the next page.
+            if page_size == max_page_size {
+                if child_cursor.is_some() {
+                    has_next_page = true;
+                    break;
+                }
+                continue;
+            }
+
+            if child_cursor.is_some() {
+                page_size += 1;
+                last_cursor = child_cursor;
+            }
+            page_children.push(child);
+        }
+
+        if !is_in_page {
+            warn!("Page cursor doesn't correspond to any contained resource.");
+            preceding_cursors.clear();
+        }
+
+        // Previous page starts after the child preceding it's
+        // children. If there is no such child, previous page is
+        // the first page, that is linked anyway.
+        let prev_cursor = if preceding_cursors.len() == max_page_size + 1 {
+            preceding_cursors.pop_front()
+        } else {
+            None
+        };
+
+        Ok((
+            self.index_quads(Box::pin(futures::stream::iter(page_children))),
+            ContainerRepPage {
+                prev_cursor,
+                next_cursor: last_cursor.filter(|_| has_next_page),
+            },
+        ))
+    }
 
-        // Get container indicator object, i.e base object.
-        let indicator_object = c_res_context
+    /// Get the container indicator object, i.e base object.
+    fn indicator_object(&self) -> &ODRNamespaceObject<'static, Setup::ObjectStoreSetup> {
+        self.c_res_context
+            .as_ref()
+            .as_ref()
             .assoc_odr_object_map()
             .base_object()
             .as_left_classified()
-            .expect("A container's associated base object must be a namespace object.");
+            .expect("A container's associated base object must be a namespace object.")
+    }
 
-        let ns_listing: DecodedODRObjectStream<Setup::ObjectStoreSetup> = indicator_object
-            .list()
-            .inspect_err(|_| error!("error in getting associated objects of contained resources."))
-            .await?;
+    /// Resolve stream of contained resources, optionally
+    /// after given backend path in listing order.
+    async fn resolve_children(
+        &self,
+        start_after: Option<&str>,
+    ) -> Result<ChildStream<Setup>, opendal::Error> {
+        let c_res_context = self.c_res_context.as_ref().as_ref();
+
+        let repo_context = c_res_context.repo_context().clone();
+
+        let indicator_object = self.indicator_object();
+
+        let ns_listing: DecodedODRObjectStream<Setup::ObjectStoreSetup> = match start_after {
+            Some(start_after) => indicator_object.list_after(start_after).await,
+            None => indicator_object.list().await,
+        }
+        .inspect_err(|_| error!("error in getting associated objects of contained resources."))?;
 
         Ok(Box::pin(async_stream::stream! {
             for await item in ns_listing {
@@ -150,8 +290,36 @@ impl<Setup: ODRSetup> ODRContainerIndexInputs<Setup> {
                     }
                 };
 
-                let child_name = assoc_res_context.uri().deref().into_term::<ArcTerm>();
+                yield Ok((odr_object, assoc_res_context));
+            }
+        }))
+    }
+
+    /// Get index quads for given contained resources.
+    fn index_quads(&self, children: ChildStream<Setup>) -> BoxQuadsStream {
+        let container_uri = self.c_res_context.uri().clone();
+
+        // Resolve container name.
+        let container_name = container_uri.deref().into_term::<ArcTerm>();
 
+        // Get predicate terms.
+        let p_contains: ArcTerm = ns::ldp::contains.into_term();
+        let p_size: ArcTerm = ns::stat::size.into_term();
+        let p_type: ArcTerm = ns::rdf::type_.into_term();
+        let p_modified: ArcTerm = ns::dcterms::modified.into_term();
+
+        Box::pin(async_stream::stream! {
+            for await child in children {
+                let (odr_object, assoc_res_context) = match child {
+                    Ok(child) => child,
+                    Err(e) => {
+                        // Yield error.
+                        yield Err(e);
+                        continue
+                    }
+                };
+
+                let child_name = assoc_res_context.uri().deref().into_term::<ArcTerm>();
                 /// Yield the containment quad.
                 yield Ok(([container_name.clone(), p_contains.clone(), child_name.clone()], None));
 
@@ -230,6 +398,19 @@ impl<Setup: ODRSetup> ODRContainerIndexInputs<Setup> {
                     }
                 }
             }
-        }))
+        })
     }
 }
+
+/// Get page cursor of a child resource with given base
+/// object, that is listed in namespace with given path.
+fn child_page_cursor<OstSetup: ODRObjectStoreSetup>(
+    ns_path: &str,
+    child_object: &ODRObject<'_, OstSetup>,
+) -> String {
+    let child_path = child_object.backend_entry().path();
+    child_path
+        .strip_prefix(ns_path)
+        .unwrap_or(child_path)
+        .to_owned()
+}
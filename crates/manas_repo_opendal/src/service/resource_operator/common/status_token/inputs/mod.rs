@@ -158,6 +158,15 @@ pub(crate) async fn query_altfm_obj_content<Setup: ODRSetup>(
         return Ok(None);
     }
 
+    read_altfm_obj_content(res_context).await
+}
+
+/// Read the associated altfm object content, even with cty
+/// capable backends. With them, altfm records only the
+/// resource creator.
+pub(crate) async fn read_altfm_obj_content<Setup: ODRSetup>(
+    res_context: ODRClassifiedResourceContext<Setup>,
+) -> Result<Option<Vec<u8>>, opendal::Error> {
     res_context
         .as_ref()
         .as_ref()
@@ -32,12 +32,14 @@ use manas_http::{
 };
 use manas_repo::service::resource_operator::{
     common::status_token::{ExistingRepresentedResourceToken, RepoResourceStatusTokenBase},
-    reader::rep_preferences::{ContainerRepresentationPreference, RepresentationPreferences},
+    reader::rep_preferences::{
+        ContainerRepresentationPreference, KContainerRepPage, RepresentationPreferences,
+    },
 };
 use manas_space::{
     resource::{
-        kind::SolidResourceKind, slot::SolidResourceSlot, slot_rel_type::SlotRelationType,
-        state::SolidResourceState,
+        kind::SolidResourceKind, slot::SolidResourceSlot, slot_link::AuxLink,
+        slot_rel_type::SlotRelationType, state::SolidResourceState,
     },
     SolidStorageSpace,
 };
@@ -112,6 +114,21 @@ impl<Setup: ODRSetup> ExistingRepresentedResourceToken
     fn rep_validators(&self) -> RepresentationMetadata {
         self.resolve_rep_validators()
     }
+
+    #[inline]
+    fn aux_links_index(&self) -> Vec<AuxLink<Setup::StSpace>> {
+        self.0.res_context.supported_aux_links().collect()
+    }
+
+    #[inline]
+    fn rep_content_length(&self) -> Option<u64> {
+        Some(self.resolve_rep_complete_content_length())
+    }
+
+    #[inline]
+    fn rep_content_type(&self) -> Option<MediaType> {
+        self.resolve_effective_rep_content_type().ok()
+    }
 }
 
 impl<Setup: ODRSetup> TryFrom<ODRResourceStatusTokenInputs<Setup>>
@@ -142,6 +159,22 @@ impl<Setup: ODRSetup> ODRExistingRepresentedResourceToken<Setup> {
             .expect("Invariant guarantees it to be some.")
     }
 
+    /// Resolve complete content length of the representation.
+    pub fn resolve_rep_complete_content_length(&self) -> u64 {
+        let inputs = &self.0;
+
+        // For containers, content is in altcontent object.
+        if inputs.res_context.is_right_classified() {
+            self.inputs_base_obj_metadata().content_length()
+        } else {
+            inputs
+                .altcontent_obj_metadata
+                .as_ref()
+                .map(|m| m.content_length())
+                .unwrap_or_default()
+        }
+    }
+
     /// Resolve validators for the representation.
     pub fn resolve_rep_validators(&self) -> RepresentationMetadata {
         let inputs = &self.0;
@@ -188,6 +221,38 @@ impl<Setup: ODRSetup> ODRExistingRepresentedResourceToken<Setup> {
             .with_opt::<KDerivedETag>(etag)
     }
 
+    /// Resolve validators for the representation with given
+    /// preferences.
+    ///
+    /// Container pages other than the first one are distinct
+    /// representations. Hence their etags are augmented with
+    /// the page cursor. Augmented, but not derived etags, as
+    /// derivations are normalized away in evaluating
+    /// preconditions.
+    pub fn resolve_preferred_rep_validators(
+        &self,
+        rep_preferences: &RepresentationPreferences,
+    ) -> RepresentationMetadata {
+        let mut rep_validators = self.resolve_rep_validators();
+
+        if_chain! {
+            if self.res_context.is_left_classified();
+            if let Some(cursor) = rep_preferences
+                .container_page_preference
+                .as_ref()
+                .and_then(|page_preference| page_preference.cursor.as_ref());
+            if let Some(etag) = rep_validators.remove_rec_item::<KDerivedETag>();
+
+            then {
+                rep_validators.insert_rec_item::<KDerivedETag>(
+                    etag.augmented_rep_etag(("page", cursor)),
+                );
+            }
+        }
+
+        rep_validators
+    }
+
     /// Try resolve effective alt metadata.
     pub fn try_resolve_effective_alt_metadata(
         &self,
@@ -261,8 +326,6 @@ impl<Setup: ODRSetup> ODRExistingRepresentedResourceToken<Setup> {
     pub fn try_resolve_user_supplied_rep_metadata(
         &self,
     ) -> Result<RepresentationMetadata, ODRResourceStateResolutionError> {
-        let inputs = &self.0;
-
         // Get effective rep content type.
         let effective_rep_content_type =
             self.resolve_effective_rep_content_type().map_err(|e| {
@@ -275,15 +338,7 @@ impl<Setup: ODRSetup> ODRExistingRepresentedResourceToken<Setup> {
 
         // Rep complete content length.
         let rep_complete_content_length =
-            Some(ContentLength(if inputs.res_context.is_right_classified() {
-                self.inputs_base_obj_metadata().content_length()
-            } else {
-                inputs
-                    .altcontent_obj_metadata
-                    .as_ref()
-                    .map(|m| m.content_length())
-                    .unwrap_or_default()
-            }));
+            Some(ContentLength(self.resolve_rep_complete_content_length()));
 
         // Construct and return rep metadata.
         Ok(self
@@ -315,7 +370,12 @@ impl<Setup: ODRSetup> ODRExistingRepresentedResourceToken<Setup> {
 
             let c_rep_preference = rep_preferences.container_rep_preference;
 
-            let mut rep_metadata = self.try_resolve_user_supplied_rep_metadata()?;
+            let mut rep_metadata = self
+                .try_resolve_user_supplied_rep_metadata()?
+                .with_opt::<KDerivedETag>(
+                    self.resolve_preferred_rep_validators(&rep_preferences)
+                        .remove_rec_item::<KDerivedETag>(),
+                );
 
             // Content type of user supplied rep.
             let us_rep_content_type = rep_metadata
@@ -436,14 +496,26 @@ impl<Setup: ODRSetup> ODRExistingRepresentedResourceToken<Setup> {
             ]
             .contains(&c_rep_preference)
             {
-                // Resolve container index quads.
-                let container_index_quads = ODRContainerIndexInputs { c_res_context }
-                    .resolve()
-                    .await
-                    .map_err(|e| {
-                        error!("Io error in resolving container index. Error:\n {}", e);
-                        ODRResourceStateResolutionError::UnknownIoError(e)
-                    })?;
+                let container_index_inputs = ODRContainerIndexInputs { c_res_context };
+
+                // Resolve container index quads, paged if preferred.
+                let container_index_quads = if let Some(page_preference) =
+                    rep_preferences.container_page_preference.as_ref()
+                {
+                    container_index_inputs
+                        .resolve_page(page_preference)
+                        .await
+                        .map(|(quads, page)| {
+                            rep_metadata.insert_rec_item::<KContainerRepPage>(page);
+                            quads
+                        })
+                } else {
+                    container_index_inputs.resolve().await
+                }
+                .map_err(|e| {
+                    error!("Io error in resolving container index. Error:\n {}", e);
+                    ODRResourceStateResolutionError::UnknownIoError(e)
+                })?;
 
                 // Include containment statements and
                 // contained metadata statements in effective rep.
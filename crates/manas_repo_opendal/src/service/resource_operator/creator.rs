@@ -5,6 +5,7 @@ use std::{marker::PhantomData, task::Poll};
 
 use dyn_problem::{type_::UNKNOWN_IO_ERROR, ProbFuture, Problem, ProblemBuilderExt};
 use futures::TryFutureExt;
+use manas_authentication::common::credentials::CreatorCredentials;
 use manas_http::{
     header::common::media_type::{MediaType, APPLICATION_JSON},
     representation::{
@@ -17,7 +18,9 @@ use manas_repo::service::resource_operator::{
         problem::{PRECONDITIONS_NOT_SATISFIED, UNSUPPORTED_OPERATION, URI_POLICY_VIOLATION},
         rep_update_action::RepUpdateAction,
     },
-    creator::{ResourceCreateRequest, ResourceCreateResponse, ResourceCreator},
+    creator::{
+        KCreatorCredentials, ResourceCreateRequest, ResourceCreateResponse, ResourceCreator,
+    },
 };
 use manas_space::resource::slot_rev_link::SlotRevLink;
 use tower::Service;
@@ -195,8 +198,12 @@ impl<Setup: ODRSetup> Service<ResourceCreateRequest<OpendalRepo<Setup>>>
                 effective_rep_content_type.essence_str() != decoded_content_type.essence_str();
 
             // First create altfm if required.
-            let _altfm_created =
-                Self::create_altfm(&res_context, &effective_rep_content_type).await?;
+            let _altfm_created = Self::create_altfm(
+                &res_context,
+                &effective_rep_content_type,
+                req.extensions.get_rv::<KCreatorCredentials>().cloned(),
+            )
+            .await?;
 
             let assoc_odr_obj_map = res_context.as_ref().as_ref().assoc_odr_object_map();
 
@@ -301,6 +308,7 @@ impl<Setup: ODRSetup> ODRResourceCreator<Setup> {
     async fn create_altfm(
         res_context: &ODRClassifiedResourceContext<Setup>,
         effective_rep_content_type: &MediaType,
+        opt_creator: Option<CreatorCredentials>,
     ) -> Result<bool, Problem> {
         let res_context = res_context.as_ref();
 
@@ -327,7 +335,10 @@ impl<Setup: ODRSetup> ODRResourceCreator<Setup> {
         let is_diverging_content_type =
             effective_rep_content_type.essence_str() != decoded_content_type.essence_str();
 
-        let should_create_alt_fm = is_diverging_content_type && !is_cty_capable_backend;
+        let should_override_content_type = is_diverging_content_type && !is_cty_capable_backend;
+
+        // Creator, if any, is always recorded.
+        let should_create_alt_fm = should_override_content_type || opt_creator.is_some();
 
         // Return if no need to create alt fm.
         if !should_create_alt_fm {
@@ -336,7 +347,9 @@ impl<Setup: ODRSetup> ODRResourceCreator<Setup> {
 
         let alt_fm = AltFatMetadata {
             live: AltMetadata {
-                content_type: Some(effective_rep_content_type.clone()),
+                content_type: should_override_content_type
+                    .then(|| effective_rep_content_type.clone()),
+                creator: opt_creator,
             },
             prev_backup: None,
         };
@@ -13,12 +13,13 @@ use manas_repo::service::resource_operator::{
         status_token::RepoResourceStatusTokenBase,
     },
     deleter::{
-        ResourceDeleteRequest, ResourceDeleteResponse, ResourceDeleter,
+        KDryRunDelete, ResourceDeleteRequest, ResourceDeleteResponse, ResourceDeleter,
         DELETE_TARGETS_NON_EMPTY_CONTAINER,
     },
 };
 use tower::Service;
 use tracing::{error, info, warn};
+use typed_record::TypedRecord;
 
 use crate::{
     object_store::{
@@ -101,6 +102,16 @@ impl<Setup: ODRSetup> Service<ResourceDeleteRequest<OpendalRepo<Setup>>>
                     .finish());
             }
 
+            // Nothing more to evaluate in a dry run.
+            if req.extensions.get_rv::<KDryRunDelete>().is_some() {
+                info!("Dry run delete is allowed.");
+                return Ok(ResourceDeleteResponse {
+                    deleted_res_slot: res_context.slot().clone(),
+                    deleted_aux_res_links: res_context.supported_aux_links().collect(),
+                    extensions: Default::default(),
+                });
+            }
+
             // If res is a container, ensure it is empty.
             if let Some(c_res_context) = res_context.as_left_classified() {
                 let mut container_index_quads: BoxQuadsStream = (ODRContainerIndexInputs {
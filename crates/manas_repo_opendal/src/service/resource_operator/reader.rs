@@ -61,7 +61,7 @@ impl<Setup: ODRSetup> Service<ResourceReadRequest<OpendalRepo<Setup>>>
             Self::ensure_backend_caps(er_token.repo_context())?;
 
             // Evaluate preconditions.
-            let rep_validators = er_token.resolve_rep_validators();
+            let rep_validators = er_token.resolve_preferred_rep_validators(&req.rep_preferences);
 
             let pc_eval_result = req.preconditions.evaluate(Some(&rep_validators));
 
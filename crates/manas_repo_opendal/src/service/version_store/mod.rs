@@ -0,0 +1,357 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use bytes::Bytes;
+use dyn_problem::{type_::UNKNOWN_IO_ERROR, ProbFuture, Problem};
+use futures::StreamExt;
+use headers::{ContentLength, LastModified};
+use manas_http::{
+    header::common::media_type::MediaType,
+    representation::{
+        impl_::{
+            basic::BasicRepresentation, binary::BinaryRepresentation,
+            common::data::bytes_inmem::BytesInmem,
+        },
+        metadata::{KCompleteContentLength, KContentType, KLastModified, RepresentationMetadata},
+        Representation,
+    },
+};
+use manas_repo::context::{RepoContext, RepoContextual};
+use manas_repo_layers::versioning::version_store::VersionStore;
+use manas_space::{resource::uri::SolidResourceUri, SolidStorageSpace};
+use opendal::{EntryMode, ErrorKind};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, NON_ALPHANUMERIC};
+use tracing::{error, warn};
+
+use super::VERSIONS_NAMESPACE;
+use crate::{
+    context::ODRContext,
+    object_store::{backend::ODRObjectStoreBackend, object_space::assoc::rel_type::AssocRelType},
+    setup::ODRSetup,
+    OpendalRepo,
+};
+
+/// Suffix of the directory, that holds versions of a resource.
+const MEMENTOS_DIR_SUFFIX: &str = ".__mementos/";
+
+/// An implementation of [`VersionStore`] backed by odr, that
+/// stores versions in a hidden namespace in repo's backend.
+///
+/// Versions of a resource are stored under
+/// `.__versions/<base object path>.__mementos/`, each as an
+/// object named with it's datetime and content-type.
+#[derive(Debug, Clone)]
+pub struct ODRVersionStore<Setup: ODRSetup> {
+    /// Repo context.
+    repo_context: Arc<ODRContext<Setup>>,
+}
+
+impl<Setup> RepoContextual for ODRVersionStore<Setup>
+where
+    Setup: ODRSetup,
+{
+    type Repo = OpendalRepo<Setup>;
+
+    #[inline]
+    fn new_with_context(repo_context: Arc<ODRContext<Setup>>) -> Self {
+        Self { repo_context }
+    }
+
+    #[inline]
+    fn repo_context(&self) -> &Arc<ODRContext<Setup>> {
+        &self.repo_context
+    }
+}
+
+impl<Setup: ODRSetup> ODRVersionStore<Setup> {
+    /// Resolve backend path of the directory, that holds
+    /// versions of the resource with given uri.
+    #[allow(clippy::result_large_err)]
+    fn versions_dir_path(&self, res_uri: &SolidResourceUri) -> Result<String, Problem> {
+        let object_store = &self.repo_context.object_store;
+
+        let base_obj_path = |uri: &SolidResourceUri| {
+            object_store
+                .assoc_odr_object(uri, AssocRelType::Base)
+                .map(|obj| obj.backend_entry().path().to_owned())
+                .map_err(|e| {
+                    error!("Error in resolving base object. Error:\n {}", e);
+                    UNKNOWN_IO_ERROR.new_problem_builder().source(e).finish()
+                })
+        };
+
+        let root_path = base_obj_path(self.repo_context.storage_space().root_res_uri())?;
+        let res_path = base_obj_path(res_uri)?;
+        let res_rel_path = res_path.strip_prefix(&root_path).unwrap_or(&res_path);
+
+        Ok(format!(
+            "{}{}{}{}",
+            root_path, VERSIONS_NAMESPACE, res_rel_path, MEMENTOS_DIR_SUFFIX
+        ))
+    }
+
+    /// List version objects of the resource with given uri,
+    /// as `(datetime, object path)` pairs.
+    async fn list_version_objects(
+        &self,
+        res_uri: &SolidResourceUri,
+    ) -> Result<Vec<(SystemTime, String)>, Problem> {
+        let dir_path = self.versions_dir_path(res_uri)?;
+        let operator = self.repo_context.object_store.backend.operator();
+
+        let entries = match operator.list(&dir_path).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => {
+                error!("Error in listing version objects. Error:\n {}", e);
+                return Err(UNKNOWN_IO_ERROR.new_problem_builder().source(e).finish());
+            }
+        };
+
+        let mut version_objects = entries
+            .into_iter()
+            .filter(|entry| entry.metadata().mode() == EntryMode::FILE)
+            .filter_map(|entry| match decode_version_name(entry.name()) {
+                Some((datetime, _)) => Some((datetime, entry.path().to_owned())),
+                None => {
+                    warn!("Skipping invalid version object {}.", entry.path());
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        version_objects.sort_by_key(|(datetime, _)| *datetime);
+        Ok(version_objects)
+    }
+
+    /// Resolve path of the version object of the resource
+    /// with given uri, at given datetime.
+    async fn version_object_path(
+        &self,
+        res_uri: &SolidResourceUri,
+        datetime: SystemTime,
+    ) -> Result<Option<String>, Problem> {
+        Ok(self
+            .list_version_objects(res_uri)
+            .await?
+            .into_iter()
+            .find(|(version, _)| *version == datetime)
+            .map(|(_, path)| path))
+    }
+}
+
+impl<Setup: ODRSetup> VersionStore for ODRVersionStore<Setup> {
+    #[tracing::instrument(skip_all, name = "ODRVersionStore::list_versions")]
+    fn list_versions(&self, res_uri: &SolidResourceUri) -> ProbFuture<'static, Vec<SystemTime>> {
+        let this = self.clone();
+        let res_uri = res_uri.clone();
+
+        Box::pin(async move {
+            Ok(this
+                .list_version_objects(&res_uri)
+                .await?
+                .into_iter()
+                .map(|(datetime, _)| datetime)
+                .collect())
+        })
+    }
+
+    #[tracing::instrument(skip_all, name = "ODRVersionStore::save_version")]
+    fn save_version(
+        &self,
+        res_uri: &SolidResourceUri,
+        datetime: SystemTime,
+        rep: BinaryRepresentation,
+    ) -> ProbFuture<'static, ()> {
+        let this = self.clone();
+        let res_uri = res_uri.clone();
+
+        Box::pin(async move {
+            let existing_path = this.version_object_path(&res_uri, datetime).await?;
+            let obj_path = format!(
+                "{}{}",
+                this.versions_dir_path(&res_uri)?,
+                encode_version_name(datetime, rep.metadata().content_type())
+            );
+
+            let operator = this.repo_context.object_store.backend.operator();
+            let on_write_error = |e: opendal::Error| {
+                error!("Error in writing version object. Error:\n {}", e);
+                UNKNOWN_IO_ERROR.new_problem_builder().source(e).finish()
+            };
+
+            let mut writer = operator.writer(&obj_path).await.map_err(on_write_error)?;
+            let mut data = rep.into_streaming().into_parts().0.stream;
+            let mut written_once = false;
+
+            while let Some(r) = data.next().await {
+                let write_result = match r {
+                    Ok(bs) => writer.write(bs).await,
+                    // Source error.
+                    Err(_) => Err(opendal::Error::new(ErrorKind::Unexpected, "Source error")),
+                };
+                if let Err(e) = write_result {
+                    if let Err(abort_err) = writer.abort().await {
+                        warn!("Error in aborting write operation. Error:\n {}", abort_err);
+                    }
+                    return Err(on_write_error(e));
+                }
+                written_once = true;
+            }
+
+            if !written_once {
+                // Empty content.
+                writer
+                    .write(Bytes::default())
+                    .await
+                    .map_err(on_write_error)?;
+            }
+            writer.close().await.map_err(on_write_error)?;
+
+            // Remove version with same datetime, but different name.
+            if let Some(existing_path) = existing_path.filter(|path| *path != obj_path) {
+                operator.delete(&existing_path).await.map_err(|e| {
+                    error!("Error in deleting replaced version object. Error:\n {}", e);
+                    UNKNOWN_IO_ERROR.new_problem_builder().source(e).finish()
+                })?;
+            }
+
+            Ok(())
+        })
+    }
+
+    #[tracing::instrument(skip_all, name = "ODRVersionStore::read_version")]
+    fn read_version(
+        &self,
+        res_uri: &SolidResourceUri,
+        datetime: SystemTime,
+    ) -> ProbFuture<'static, BinaryRepresentation> {
+        let this = self.clone();
+        let res_uri = res_uri.clone();
+
+        Box::pin(async move {
+            let obj_path = this
+                .version_object_path(&res_uri, datetime)
+                .await?
+                .ok_or_else(|| {
+                    error!("Version object not found.");
+                    UNKNOWN_IO_ERROR.new_problem_builder().finish()
+                })?;
+
+            let content_type = obj_path
+                .rsplit('/')
+                .next()
+                .and_then(decode_version_name)
+                .map(|(_, content_type)| content_type)
+                .expect("Must be valid, as listed.");
+
+            let content = this
+                .repo_context
+                .object_store
+                .backend
+                .operator()
+                .read(&obj_path)
+                .await
+                .map_err(|e| {
+                    error!("Error in reading version object. Error:\n {}", e);
+                    UNKNOWN_IO_ERROR.new_problem_builder().source(e).finish()
+                })?;
+
+            Ok(BasicRepresentation {
+                metadata: RepresentationMetadata::new()
+                    .with::<KContentType>(content_type)
+                    .with::<KCompleteContentLength>(ContentLength(content.len() as u64))
+                    .with::<KLastModified>(LastModified::from(datetime)),
+                data: BytesInmem::from(Bytes::from(content)),
+                base_uri: None,
+            }
+            .into())
+        })
+    }
+
+    #[tracing::instrument(skip_all, name = "ODRVersionStore::version_size")]
+    fn version_size(
+        &self,
+        res_uri: &SolidResourceUri,
+        datetime: SystemTime,
+    ) -> ProbFuture<'static, Option<u64>> {
+        let this = self.clone();
+        let res_uri = res_uri.clone();
+
+        Box::pin(async move {
+            let obj_path = match this.version_object_path(&res_uri, datetime).await? {
+                Some(obj_path) => obj_path,
+                None => return Ok(None),
+            };
+
+            let metadata = this
+                .repo_context
+                .object_store
+                .backend
+                .operator()
+                .stat(&obj_path)
+                .await
+                .map_err(|e| {
+                    error!("Error in stating version object. Error:\n {}", e);
+                    UNKNOWN_IO_ERROR.new_problem_builder().source(e).finish()
+                })?;
+
+            Ok(Some(metadata.content_length()))
+        })
+    }
+
+    #[tracing::instrument(skip_all, name = "ODRVersionStore::delete_version")]
+    fn delete_version(
+        &self,
+        res_uri: &SolidResourceUri,
+        datetime: SystemTime,
+    ) -> ProbFuture<'static, ()> {
+        let this = self.clone();
+        let res_uri = res_uri.clone();
+
+        Box::pin(async move {
+            if let Some(obj_path) = this.version_object_path(&res_uri, datetime).await? {
+                this.repo_context
+                    .object_store
+                    .backend
+                    .operator()
+                    .delete(&obj_path)
+                    .await
+                    .map_err(|e| {
+                        error!("Error in deleting version object. Error:\n {}", e);
+                        UNKNOWN_IO_ERROR.new_problem_builder().source(e).finish()
+                    })?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Encode name of the version object with given datetime
+/// and content-type.
+fn encode_version_name(datetime: SystemTime, content_type: &MediaType) -> String {
+    let secs = datetime
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    format!(
+        "{}_{}",
+        secs,
+        utf8_percent_encode(content_type.essence_str(), NON_ALPHANUMERIC)
+    )
+}
+
+/// Decode datetime and content-type from name of a version
+/// object.
+fn decode_version_name(name: &str) -> Option<(SystemTime, MediaType)> {
+    let (secs, encoded_content_type) = name.split_once('_')?;
+    let secs = secs.parse::<u64>().ok()?;
+    let content_type = percent_decode_str(encoded_content_type)
+        .decode_utf8()
+        .ok()?
+        .parse()
+        .ok()?;
+    Some((UNIX_EPOCH + Duration::from_secs(secs), content_type))
+}
@@ -0,0 +1,6 @@
+use manas_server::recipe::{impl_::multi_pod::MultiPodFsWacRecipe, RecipeExt};
+
+#[tokio::main]
+pub async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    MultiPodFsWacRecipe::default().main().await
+}
@@ -34,7 +34,7 @@ static DATABROWSER_TEMPLATE_ENGINE: Lazy<Engine> = Lazy::new(|| {
 });
 
 /// A struct for representing databrowser context.
-#[derive(serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct DatabrowserContext {
     /// Mashlib js uri.
     pub mashlib_js_uri: AbsoluteHttpUri,
@@ -0,0 +1,495 @@
+//! I define a pod service, that serves explanations of
+//! access control resolutions to storage owners.
+//!
+
+use std::{convert::Infallible, sync::Arc, task::Poll};
+
+use dyn_problem::Problem;
+use futures::{future::BoxFuture, TryFutureExt};
+use http::{header, Method, Request, Response, StatusCode};
+use http_api_problem::ApiError;
+use http_uri::invariant::AbsoluteHttpUri;
+use manas_access_control::model::pep::PolicyEnforcementPoint;
+use manas_authentication::common::credentials::{
+    impl_::basic::{
+        BasicAgentCredentials, BasicClientCredentials, BasicIssuerCredentials,
+        BasicRequestCredentials,
+    },
+    AgentCredentials, RequestCredentials,
+};
+use manas_http::{
+    body::Body,
+    problem::ApiErrorExt,
+    service::{namespaced::NamespacedHttpService, BoxHttpResponseFuture},
+};
+use manas_podverse::pod::{
+    service::{PodService, PodServiceFactory},
+    Pod,
+};
+use manas_repo::Repo;
+use manas_space::{resource::uri::SolidResourceUri, SolidStorageSpace};
+use manas_storage::SolidStorageExt;
+use rdf_dynsyn::{
+    serializer::triples::DynSynTripleSerializerFactory,
+    syntax::invariant::triples_serializable::TS_TURTLE,
+};
+use sophia_api::serializer::{Stringifier, TripleSerializer};
+use tower::{Service, ServiceExt};
+use tracing::{error, info};
+use webid::WebId;
+
+use crate::storage::{RcpStorage, RcpStorageSetup};
+
+/// Query param, that marks a request to the storage root as
+/// an access control explanation request.
+///
+/// Explanations are served in a query mode of the storage
+/// root, so that no resource path is claimed by them.
+pub const EXPLAIN_QUERY_PARAM: &str = "explain";
+
+/// Query param for the target resource uri.
+pub const TARGET_QUERY_PARAM: &str = "target";
+
+/// Query param for the webid of the hypothetical agent.
+pub const AGENT_QUERY_PARAM: &str = "agent";
+
+/// Query param for the client id of the hypothetical client.
+pub const CLIENT_QUERY_PARAM: &str = "client";
+
+/// Query param for the uri of the hypothetical issuer.
+pub const ISSUER_QUERY_PARAM: &str = "issuer";
+
+/// An implementation of [`PodService`], that wraps another
+/// pod-service, and intercepts access control explanation
+/// requests, that target the storage root with `explain`
+/// query param.
+///
+/// On a `GET` request from the storage owner, it resolves
+/// access control on the resource given by `target` query
+/// param, for hypothetical credentials given by `agent`,
+/// `client` and `issuer` query params. And responds with the
+/// explanation as turtle, that includes evaluated context and
+/// acr chain, matched policies and matchers, and resulting
+/// grants.
+#[derive(Debug, Clone)]
+pub struct ExplainingPodService<Inner> {
+    /// Inner svc.
+    pub inner: Inner,
+}
+
+impl<Inner, StSetup> Service<Request<Body>> for ExplainingPodService<Inner>
+where
+    Inner: PodService + Clone,
+    Inner::Pod: Pod<Storage = RcpStorage<StSetup>>,
+    StSetup: RcpStorageSetup,
+{
+    type Response = Response<Body>;
+
+    type Error = Infallible;
+
+    type Future = BoxHttpResponseFuture<Body>;
+
+    #[inline]
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    #[allow(unused_qualifications)]
+    #[tracing::instrument(skip_all, name = "ExplainingPodService::call")]
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let storage = self.inner.pod().storage().clone();
+        let mut inner = self.inner.clone();
+
+        Box::pin(async move {
+            let res_uri = req
+                .extensions()
+                .get::<SolidResourceUri>()
+                .expect("Must be called after uri normal validity check.");
+
+            let (res_uri_path, res_uri_query) = res_uri
+                .as_str()
+                .split_once('?')
+                .unwrap_or((res_uri.as_str(), ""));
+
+            // If request is an explanation request, then handle it.
+            if res_uri_path == storage.space().root_res_uri().as_str()
+                && form_urlencoded::parse(res_uri_query.as_bytes())
+                    .any(|(name, _)| name == EXPLAIN_QUERY_PARAM)
+            {
+                info!("Request is an access control explanation request.");
+                Ok(Self::apply(storage, req)
+                    .await
+                    .unwrap_or_else(|e| e.into_http_response()))
+            }
+            // Else, delegate to inner service.
+            else {
+                ServiceExt::<Request<Body>>::ready(&mut inner)
+                    .and_then(|svc| svc.call(req))
+                    .await
+            }
+        })
+    }
+}
+
+impl<Inner> ExplainingPodService<Inner> {
+    /// Handle the explanation request.
+    async fn apply<StSetup: RcpStorageSetup>(
+        storage: Arc<RcpStorage<StSetup>>,
+        req: Request<Body>,
+    ) -> Result<Response<Body>, ApiError> {
+        if ![Method::GET, Method::HEAD].contains(req.method()) {
+            error!("Method not allowed on explanation endpoint.");
+            return Err(ApiError::builder(StatusCode::METHOD_NOT_ALLOWED)
+                .message("Explanation endpoint only supports GET and HEAD methods.")
+                .finish());
+        }
+
+        // Ensure requester is the storage owner.
+        let requester_id = req
+            .extensions()
+            .get::<BasicRequestCredentials>()
+            .and_then(|credentials| credentials.of_agent())
+            .map(|agent| agent.webid().clone())
+            .ok_or_else(|| {
+                error!("Explanation request is not authenticated.");
+                ApiError::builder(StatusCode::UNAUTHORIZED)
+                    .message("Explanation requests must be authenticated.")
+                    .finish()
+            })?;
+
+        if &requester_id != storage.space().owner_id() {
+            error!("Explanation requester is not the storage owner.");
+            return Err(ApiError::builder(StatusCode::FORBIDDEN)
+                .message("Only the storage owner can request explanations.")
+                .finish());
+        }
+
+        let (target, credentials) = Self::resolve_query(
+            storage.space().root_res_uri(),
+            req.uri().query().unwrap_or_default(),
+        )?;
+
+        let explanation = storage
+            .repo
+            .context()
+            .pep
+            .explain_access_control(target, credentials)
+            .await
+            .map_err(|e| {
+                error!("Error in explaining access control. Error:\n {}", e);
+                ApiError::builder(StatusCode::INTERNAL_SERVER_ERROR).finish()
+            })?
+            .ok_or_else(|| {
+                error!("Access control explanation is not supported by the storage.");
+                ApiError::builder(StatusCode::NOT_IMPLEMENTED)
+                    .message("Access control explanation is not supported by the storage.")
+                    .finish()
+            })?;
+
+        let body = DynSynTripleSerializerFactory::default()
+            .new_stringifier(TS_TURTLE)
+            .serialize_triples(
+                explanation
+                    .to_statements()
+                    .into_iter()
+                    .map(Result::<_, Infallible>::Ok),
+            )
+            .map_err(|e| {
+                error!("Error in serializing explanation. Error:\n {}", e);
+                ApiError::builder(StatusCode::INTERNAL_SERVER_ERROR).finish()
+            })?
+            .as_str()
+            .to_string();
+
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "text/turtle")
+            .body(if req.method() == Method::HEAD {
+                Body::empty()
+            } else {
+                Body::from(body)
+            })
+            .expect("Must be valid."))
+    }
+
+    /// Resolve the target uri, and the hypothetical
+    /// credentials from given query.
+    #[allow(clippy::result_large_err)]
+    fn resolve_query(
+        root_res_uri: &SolidResourceUri,
+        query: &str,
+    ) -> Result<(SolidResourceUri, BasicRequestCredentials), ApiError> {
+        let mut target = None;
+        let mut credentials = BasicRequestCredentials {
+            of_agent: None,
+            of_client: None,
+            of_issuer: None,
+            of_vcs: vec![],
+        };
+
+        for (name, value) in form_urlencoded::parse(query.as_bytes()) {
+            match name.as_ref() {
+                TARGET_QUERY_PARAM => {
+                    target = Some(
+                        SolidResourceUri::try_new_from(value.as_ref())
+                            .ok()
+                            // Target must be in the storage.
+                            .filter(|uri| uri.as_str().starts_with(root_res_uri.as_str()))
+                            .ok_or_else(|| Self::invalid_param_error(&name, &value))?,
+                    );
+                }
+                AGENT_QUERY_PARAM => {
+                    credentials.of_agent = Some(BasicAgentCredentials {
+                        webid: WebId::try_from(value.as_ref())
+                            .map_err(|_| Self::invalid_param_error(&name, &value))?,
+                        bound_key_jkt: None,
+                    });
+                }
+                CLIENT_QUERY_PARAM => {
+                    credentials.of_client = Some(BasicClientCredentials {
+                        client_web_id: WebId::try_from(value.as_ref()).ok(),
+                        client_id: value.into_owned(),
+                    });
+                }
+                ISSUER_QUERY_PARAM => {
+                    credentials.of_issuer = Some(BasicIssuerCredentials {
+                        uri: AbsoluteHttpUri::try_new_from(value.as_ref())
+                            .map_err(|_| Self::invalid_param_error(&name, &value))?,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let target = target.ok_or_else(|| {
+            error!("Explanation request has no target.");
+            ApiError::builder(StatusCode::BAD_REQUEST)
+                .message(format!("Query param `{}` is required.", TARGET_QUERY_PARAM))
+                .finish()
+        })?;
+
+        Ok((target, credentials))
+    }
+
+    /// Get an error for invalid query param.
+    fn invalid_param_error(name: &str, value: &str) -> ApiError {
+        error!("Invalid query param. Name: {}, value: {}", name, value);
+        ApiError::builder(StatusCode::BAD_REQUEST)
+            .message(format!("Invalid value for query param `{}`.", name))
+            .finish()
+    }
+}
+
+impl<Inner, StSetup> PodService for ExplainingPodService<Inner>
+where
+    Inner: PodService + Clone,
+    Inner::Pod: Pod<Storage = RcpStorage<StSetup>>,
+    StSetup: RcpStorageSetup,
+{
+    type Pod = Inner::Pod;
+
+    #[inline]
+    fn pod(&self) -> &Arc<Self::Pod> {
+        self.inner.pod()
+    }
+}
+
+impl<Inner, StSetup> NamespacedHttpService<Body, Body> for ExplainingPodService<Inner>
+where
+    Inner: PodService + Clone,
+    Inner::Pod: Pod<Storage = RcpStorage<StSetup>>,
+    StSetup: RcpStorageSetup,
+{
+    #[inline]
+    fn has_in_uri_ns(&self, uri: &SolidResourceUri) -> bool {
+        self.inner.has_in_uri_ns(uri)
+    }
+}
+
+impl<Inner> Service<()> for ExplainingPodService<Inner>
+where
+    Inner: PodService + Clone,
+{
+    type Response = bool;
+
+    type Error = Problem;
+
+    type Future = BoxFuture<'static, Result<bool, Problem>>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Service::<()>::poll_ready(&mut self.inner, cx)
+    }
+
+    #[inline]
+    fn call(&mut self, _req: ()) -> Self::Future {
+        self.inner.call(())
+    }
+}
+
+/// A [`ExplainingPodServiceFactory`] resolves an
+/// [`ExplainingPodService`] for each pod.
+#[derive(Debug, Clone, Default)]
+pub struct ExplainingPodServiceFactory<InnerFactory> {
+    /// Inner factory.
+    pub inner_factory: Arc<InnerFactory>,
+}
+
+impl<InnerFactory> PodServiceFactory for ExplainingPodServiceFactory<InnerFactory>
+where
+    InnerFactory: PodServiceFactory,
+    InnerFactory::Service: Clone,
+    ExplainingPodService<InnerFactory::Service>: PodService<Pod = InnerFactory::Pod>,
+{
+    type Pod = InnerFactory::Pod;
+    type Service = ExplainingPodService<InnerFactory::Service>;
+
+    #[inline]
+    fn new_service(&self, pod: Arc<InnerFactory::Pod>) -> Self::Service {
+        Self::Service {
+            inner: self.inner_factory.new_service(pod),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "backend-fs", feature = "pdp-wac"))]
+mod tests {
+    use claims::*;
+    use manas_podverse::pod::impl_::BasicPod;
+    use rstest::*;
+
+    use super::*;
+    use crate::{
+        podverse::static_::RcpPodServiceFactory,
+        recipe::impl_::single_pod::{setup::impl_::FsWacRecipeSetup, SinglePodStorageSetup},
+        test_utils::{
+            build_request, send_with, TestPod, TestResponse, OTHER_ID, OWNER_ID, ROOT_URI,
+        },
+        CW,
+    };
+
+    /// Encode given query param value.
+    fn encode(value: &str) -> String {
+        form_urlencoded::byte_serialize(value.as_bytes()).collect()
+    }
+
+    /// Send a request to the recipe pod service of given pod.
+    async fn send(
+        pod: &TestPod,
+        method: Method,
+        path: &str,
+        agent_id: Option<&str>,
+    ) -> TestResponse {
+        let svc = CW::<RcpPodServiceFactory<SinglePodStorageSetup<FsWacRecipeSetup>>>::new(false)
+            .new_service(Arc::new(BasicPod {
+                storage: pod.storage.clone(),
+            }));
+        send_with(svc, build_request(method, path, &[], "", agent_id)).await
+    }
+
+    /// Resolve path of explanation request with given query
+    /// suffix.
+    fn explain_path(query_suffix: &str) -> String {
+        format!(
+            "?{}&{}={}{}",
+            EXPLAIN_QUERY_PARAM,
+            TARGET_QUERY_PARAM,
+            encode(ROOT_URI),
+            query_suffix
+        )
+    }
+
+    #[tokio::test]
+    async fn owner_gets_turtle_explanation() {
+        let pod = TestPod::new().await;
+
+        let resp = send(
+            &pod,
+            Method::GET,
+            &explain_path(&format!("&{}={}", AGENT_QUERY_PARAM, encode(OWNER_ID))),
+            Some(OWNER_ID),
+        )
+        .await;
+
+        assert_eq!(resp.status, StatusCode::OK);
+        assert_eq!(
+            assert_some!(resp.headers.get(header::CONTENT_TYPE)),
+            "text/turtle"
+        );
+        assert!(resp.body.contains("AccessGrant"));
+        assert!(resp.body.contains(OWNER_ID));
+
+        let resp = send(&pod, Method::HEAD, &explain_path(""), Some(OWNER_ID)).await;
+        assert_eq!(resp.status, StatusCode::OK);
+        assert!(resp.body.is_empty());
+    }
+
+    #[rstest]
+    #[case::anonymous(None, StatusCode::UNAUTHORIZED)]
+    #[case::non_owner(Some(OTHER_ID), StatusCode::FORBIDDEN)]
+    #[tokio::test]
+    async fn non_owners_are_denied(
+        #[case] agent_id: Option<&str>,
+        #[case] expected_status: StatusCode,
+    ) {
+        let pod = TestPod::new().await;
+        let resp = send(&pod, Method::GET, &explain_path(""), agent_id).await;
+        assert_eq!(resp.status, expected_status);
+    }
+
+    #[rstest]
+    #[case::no_target("?explain")]
+    #[case::out_of_storage_target("?explain&target=http%3A%2F%2Fother.example.org%2Fa")]
+    #[case::invalid_target("?explain&target=not+a+uri")]
+    #[case::invalid_agent("?explain&target=http%3A%2F%2Fpod.example.org%2F&agent=not+a+webid")]
+    #[tokio::test]
+    async fn invalid_query_is_rejected(#[case] path: &str) {
+        let pod = TestPod::new().await;
+        let resp = send(&pod, Method::GET, path, Some(OWNER_ID)).await;
+        assert_eq!(resp.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[rstest]
+    #[case(Method::POST)]
+    #[case(Method::PUT)]
+    #[case(Method::PATCH)]
+    #[case(Method::DELETE)]
+    #[tokio::test]
+    async fn only_get_and_head_are_allowed(#[case] method: Method) {
+        let pod = TestPod::new().await;
+        let resp = send(&pod, method, &explain_path(""), Some(OWNER_ID)).await;
+        assert_eq!(resp.status, StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[tokio::test]
+    async fn other_requests_are_delegated() {
+        let pod = TestPod::new().await;
+
+        // No resource path is claimed by explanations.
+        assert!(pod
+            .put_turtle("_/explain", "<#a> <#b> <#c>.")
+            .await
+            .status
+            .is_success());
+        let resp = send(&pod, Method::GET, "_/explain", Some(OWNER_ID)).await;
+        assert_eq!(resp.status, StatusCode::OK);
+        assert!(!resp.body.contains("AccessGrant"));
+
+        let resp = send(&pod, Method::GET, "", Some(OWNER_ID)).await;
+        assert_eq!(resp.status, StatusCode::OK);
+        assert!(!resp.body.contains("AccessGrant"));
+    }
+
+    #[test]
+    fn query_values_are_form_decoded() {
+        let root_res_uri = SolidResourceUri::try_new_from(ROOT_URI).expect("Must be valid.");
+        let (target, credentials) = assert_ok!(ExplainingPodService::<()>::resolve_query(
+            &root_res_uri,
+            "explain&target=http%3A%2F%2Fpod.example.org%2Fa%20b&client=my+app"
+        ));
+
+        assert_eq!(target.as_str(), "http://pod.example.org/a%20b");
+        assert_eq!(assert_some!(credentials.of_client).client_id, "my app");
+    }
+}
@@ -8,14 +8,21 @@
 use std::ops::Deref;
 
 pub mod dtbr;
+pub mod explain;
+pub mod locker;
+pub mod notification;
 pub mod pep;
 pub mod podverse;
+pub mod quota;
 pub mod recipe;
 pub mod repo;
 pub mod space;
 pub mod storage;
 pub mod tracing;
 
+#[cfg(all(test, feature = "backend-fs", feature = "pdp-wac"))]
+pub(crate) mod test_utils;
+
 /// Crate level wrapper type for quick extensions.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct CW<T>(pub T);
@@ -0,0 +1,152 @@
+//! I define resource locker for recipe storages.
+//!
+
+#[cfg(any(unix, feature = "locker-redis"))]
+use std::time::Duration;
+use std::{future::Future, io};
+
+use futures::{future::BoxFuture, stream::BoxStream, Stream};
+#[cfg(unix)]
+use name_locker::impl_::FlockNameLocker;
+#[cfg(feature = "locker-redis")]
+use name_locker::impl_::{RedisNameLocker, RedisNameLockerConfig};
+use name_locker::{impl_::InmemNameLocker, LockError, LockKind, NameLocker};
+use tracing::{error, info};
+
+use crate::recipe::impl_::common::config::RcpLockerConfig;
+
+/// An implementation of [`NameLocker`] for recipe storages,
+/// that dispatches to the locker chosen through config.
+///
+/// Clones share the underlying lock table, and hence can be
+/// shared across storages of a recipe.
+#[derive(Clone)]
+pub enum RcpResourceLocker {
+    /// In memory locker. It cannot lock resources across
+    /// processes.
+    Inmem(InmemNameLocker<String>),
+
+    /// Lock file based locker. It can lock resources across
+    /// processes sharing the lock directory.
+    #[cfg(unix)]
+    Flock(FlockNameLocker<String>),
+
+    /// Redis based locker. It can lock resources across
+    /// hosts sharing the redis server.
+    #[cfg(feature = "locker-redis")]
+    Redis(RedisNameLocker<String>),
+}
+
+impl Default for RcpResourceLocker {
+    #[inline]
+    fn default() -> Self {
+        Self::Inmem(Default::default())
+    }
+}
+
+impl RcpResourceLocker {
+    /// Try to create a new [`RcpResourceLocker`] from given
+    /// config.
+    pub fn try_new(config: &RcpLockerConfig) -> io::Result<Self> {
+        Ok(match config {
+            RcpLockerConfig::Inmem => Self::default(),
+            #[cfg(unix)]
+            RcpLockerConfig::Flock {
+                lock_dir,
+                acquire_timeout_secs,
+            } => {
+                let mut locker = FlockNameLocker::try_new(lock_dir.clone())
+                    .inspect_err(|e| error!("Error in creating lock directory. Error:\n {}", e))?;
+                if let Some(secs) = acquire_timeout_secs {
+                    locker = locker.with_acquire_timeout(Duration::from_secs(*secs));
+                }
+
+                // Remove lock files left over by earlier runs.
+                let removed_count = locker.remove_stale_lock_files().inspect_err(|e| {
+                    error!("Error in removing stale lock files. Error:\n {}", e)
+                })?;
+                info!("Removed {} stale lock files.", removed_count);
+
+                Self::Flock(locker)
+            }
+            #[cfg(feature = "locker-redis")]
+            RcpLockerConfig::Redis {
+                url,
+                key_prefix,
+                lease_ttl_secs,
+                acquire_timeout_secs,
+            } => {
+                let default_config = RedisNameLockerConfig::default();
+                Self::Redis(
+                    RedisNameLocker::try_new(
+                        url,
+                        RedisNameLockerConfig {
+                            key_prefix: key_prefix.clone().unwrap_or(default_config.key_prefix),
+                            lease_ttl: lease_ttl_secs
+                                .map(Duration::from_secs)
+                                .unwrap_or(default_config.lease_ttl),
+                            acquire_timeout: acquire_timeout_secs
+                                .map(Duration::from_secs)
+                                .unwrap_or(default_config.acquire_timeout),
+                        },
+                    )
+                    .map_err(|e| {
+                        error!("Invalid redis locker config. Error:\n {}", e);
+                        io::Error::new(io::ErrorKind::InvalidInput, e)
+                    })?,
+                )
+            }
+            #[cfg(not(unix))]
+            RcpLockerConfig::Flock { .. } => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "Flock locker is supported only on unix.",
+                ))
+            }
+        })
+    }
+}
+
+impl NameLocker for RcpResourceLocker {
+    type Name = String;
+
+    #[inline]
+    fn poll_with_lock<Output, Task>(
+        &self,
+        task: Task,
+        name: Option<Self::Name>,
+        lock_kind: LockKind,
+    ) -> BoxFuture<'static, Result<Output, LockError>>
+    where
+        Task: Future<Output = Output> + Send + 'static,
+        Output: 'static,
+    {
+        match self {
+            Self::Inmem(locker) => locker.poll_with_lock(task, name, lock_kind),
+            #[cfg(unix)]
+            Self::Flock(locker) => locker.poll_with_lock(task, name, lock_kind),
+            #[cfg(feature = "locker-redis")]
+            Self::Redis(locker) => locker.poll_with_lock(task, name, lock_kind),
+        }
+    }
+
+    #[inline]
+    fn poll_read_with_lock<'s, S>(
+        &self,
+        stream: S,
+        name: Option<Self::Name>,
+        lock_kind: LockKind,
+    ) -> BoxStream<'s, Result<S::Item, LockError>>
+    where
+        S: Stream + Send + 's,
+        <S as Stream>::Item: Send,
+    {
+        match self {
+            Self::Inmem(locker) => locker.poll_read_with_lock(stream, name, lock_kind),
+            #[cfg(unix)]
+            Self::Flock(locker) => locker.poll_read_with_lock(stream, name, lock_kind),
+            #[cfg(feature = "locker-redis")]
+            Self::Redis(locker) => locker.poll_read_with_lock(stream, name, lock_kind),
+        }
+    }
+}
@@ -0,0 +1,454 @@
+//! I define notification related concrete types for recipes.
+//!
+
+use std::{convert::Infallible, fmt::Debug, io, sync::Arc, task::Poll};
+
+use dyn_problem::{ProbFuture, Problem};
+use futures::TryFutureExt;
+use http::{Request, Response};
+use manas_http::{
+    body::Body,
+    service::{namespaced::NamespacedHttpService, BoxHttpResponseFuture},
+    uri::invariant::NormalAbsoluteHttpUri,
+};
+use manas_notification::{
+    channel::impl_::{WebSocketChannel2023, WebhookChannel2023, WebhookChannelConfig},
+    service::{resolve_description_statements, SolidNotificationsService},
+};
+use manas_podverse::{
+    pod::{
+        service::{impl_::KAdditionalStorageDescriptionStatements, PodService, PodServiceFactory},
+        Pod,
+    },
+    podset::service::impl_::OverridenPodSetService,
+};
+use manas_repo_layers::eventing::event::ResourceChangeEventBus;
+use manas_space::resource::uri::SolidResourceUri;
+use manas_storage::SolidStorageExt;
+use tower::{Service, ServiceExt};
+use tracing::info;
+use typed_record::{TypedRecord, TypedRecordKey};
+
+use crate::{
+    podverse::static_::{RcpPod, RcpStaticPodSetService},
+    recipe::impl_::common::config::RcpNotificationsConfig,
+    space::RcpStorageSpace,
+    storage::{RcpStorage, RcpStorageSetup},
+    CW,
+};
+
+/// Type of websocket notification channels for recipes.
+pub type RcpWebSocketChannel = WebSocketChannel2023<RcpStorageSpace>;
+
+/// Type of webhook notification channels for recipes.
+pub type RcpWebhookChannel = WebhookChannel2023<RcpStorageSpace>;
+
+/// Type of notifications services for recipes.
+pub type RcpNotificationsService<StSetup> = SolidNotificationsService<RcpStorage<StSetup>>;
+
+/// Type of static podset services, with notifications
+/// service overriding the notifications namespace.
+pub type RcpNotificationsOverridenStaticPodSetService<StSetup> =
+    OverridenPodSetService<RcpStaticPodSetService<StSetup>, RcpNotificationsService<StSetup>>;
+
+/// Notification channels of a storage.
+#[derive(Debug, Clone)]
+pub struct RcpNotificationChannels {
+    /// Change event bus of the storage, to which channels
+    /// subscribe.
+    pub event_bus: ResourceChangeEventBus<RcpStorageSpace>,
+
+    /// Websocket channel type.
+    pub websocket: RcpWebSocketChannel,
+
+    /// Webhook channel type.
+    pub webhook: RcpWebhookChannel,
+}
+
+impl RcpNotificationChannels {
+    /// Try to create notification channels for the storage
+    /// with given space and event bus, with given config.
+    pub async fn try_new(
+        space: &RcpStorageSpace,
+        event_bus: ResourceChangeEventBus<RcpStorageSpace>,
+        config: RcpNotificationsConfig,
+    ) -> io::Result<Self> {
+        let websocket = RcpWebSocketChannel::new(space, event_bus.clone(), Default::default());
+
+        let webhook = RcpWebhookChannel::try_new(
+            space,
+            event_bus.clone(),
+            WebhookChannelConfig {
+                state_dir: config.webhook_state_dir,
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        Ok(Self {
+            event_bus,
+            websocket,
+            webhook,
+        })
+    }
+
+    /// Get a notifications service over given storage, that
+    /// serves these channels.
+    pub fn new_service<StSetup: RcpStorageSetup>(
+        &self,
+        storage: Arc<RcpStorage<StSetup>>,
+    ) -> RcpNotificationsService<StSetup> {
+        SolidNotificationsService::new(storage)
+            .with_channel(self.websocket.clone())
+            .with_channel(self.webhook.clone())
+    }
+}
+
+/// A typed record key for notification channels of a
+/// storage. Storages of dynamically provisioned pods set it
+/// in their extensions, to get their notifications served by
+/// [`NotifyingPodService`].
+#[derive(Debug, Clone)]
+pub struct KRcpNotificationChannels;
+
+impl TypedRecordKey for KRcpNotificationChannels {
+    type Value = RcpNotificationChannels;
+}
+
+/// Configure given storage to advertise supported
+/// notification channels in it's description.
+pub fn configure_notifications<StSetup: RcpStorageSetup>(storage: &mut RcpStorage<StSetup>) {
+    let mut statements = resolve_description_statements::<RcpWebSocketChannel>(storage.space());
+    statements.extend(resolve_description_statements::<RcpWebhookChannel>(
+        storage.space(),
+    ));
+
+    storage
+        .extensions
+        .insert_rec_item::<KAdditionalStorageDescriptionStatements>(Arc::new(statements));
+}
+
+impl<StSetup: RcpStorageSetup> CW<RcpNotificationsService<StSetup>> {
+    /// Try to get a new notifications service over given
+    /// storage, with given config. Channels subscribe to the
+    /// storage's change event bus.
+    pub async fn try_new(
+        storage: Arc<RcpStorage<StSetup>>,
+        config: RcpNotificationsConfig,
+    ) -> io::Result<RcpNotificationsService<StSetup>> {
+        let channels = RcpNotificationChannels::try_new(
+            storage.space().as_ref(),
+            storage.event_bus().clone(),
+            config,
+        )
+        .await?;
+
+        Ok(channels.new_service(storage))
+    }
+}
+
+impl<StSetup: RcpStorageSetup> CW<RcpNotificationsOverridenStaticPodSetService<StSetup>> {
+    /// Try to get a new podset service serving given static
+    /// pod, along with it's notifications.
+    pub async fn try_new_for_static(
+        pod: Arc<RcpPod<StSetup>>,
+        dev_mode: bool,
+        notifications_config: RcpNotificationsConfig,
+    ) -> io::Result<RcpNotificationsOverridenStaticPodSetService<StSetup>> {
+        let notifications_svc = CW::<RcpNotificationsService<StSetup>>::try_new(
+            pod.storage.clone(),
+            notifications_config,
+        )
+        .await?;
+        let inner = CW::<RcpStaticPodSetService<StSetup>>::new_for_static(vec![pod], dev_mode);
+
+        Ok(OverridenPodSetService::new(inner, notifications_svc))
+    }
+}
+
+/// An implementation of [`PodService`], that wraps another
+/// pod-service, and serves notifications of the pod's
+/// storage, if it's notification channels are set with
+/// [`KRcpNotificationChannels`] key in storage extensions.
+///
+/// Requests to the notifications namespace of such storages
+/// are handled by the notifications service, while others
+/// are delegated to the inner service.
+pub struct NotifyingPodService<Inner, StSetup: RcpStorageSetup> {
+    /// Inner svc.
+    pub inner: Inner,
+
+    /// Notifications service of the pod, if any.
+    pub opt_notifications_svc: Option<RcpNotificationsService<StSetup>>,
+}
+
+impl<Inner: Clone, StSetup: RcpStorageSetup> Clone for NotifyingPodService<Inner, StSetup> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            opt_notifications_svc: self.opt_notifications_svc.clone(),
+        }
+    }
+}
+
+impl<Inner: Debug, StSetup: RcpStorageSetup> Debug for NotifyingPodService<Inner, StSetup> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NotifyingPodService")
+            .field("inner", &self.inner)
+            .field("notifying", &self.opt_notifications_svc.is_some())
+            .finish()
+    }
+}
+
+impl<Inner, StSetup> Service<Request<Body>> for NotifyingPodService<Inner, StSetup>
+where
+    Inner: PodService + Clone,
+    Inner::Pod: Pod<Storage = RcpStorage<StSetup>>,
+    StSetup: RcpStorageSetup,
+{
+    type Response = Response<Body>;
+
+    type Error = Infallible;
+
+    type Future = BoxHttpResponseFuture<Body>;
+
+    #[inline]
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[tracing::instrument(skip_all, name = "NotifyingPodService::call")]
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let uri = req
+            .extensions()
+            .get::<NormalAbsoluteHttpUri>()
+            .expect("Must be called after uri normal validity check.");
+
+        if let Some(notifications_svc) = self
+            .opt_notifications_svc
+            .as_mut()
+            .filter(|svc| svc.has_in_uri_ns(uri))
+        {
+            info!("Request target is in notifications namespace.");
+            return notifications_svc.call(req);
+        }
+
+        let mut inner = self.inner.clone();
+        Box::pin(async move {
+            ServiceExt::<Request<Body>>::ready(&mut inner)
+                .and_then(|svc| svc.call(req))
+                .await
+        })
+    }
+}
+
+impl<Inner, StSetup> PodService for NotifyingPodService<Inner, StSetup>
+where
+    Inner: PodService + Clone,
+    Inner::Pod: Pod<Storage = RcpStorage<StSetup>>,
+    StSetup: RcpStorageSetup,
+{
+    type Pod = Inner::Pod;
+
+    #[inline]
+    fn pod(&self) -> &Arc<Self::Pod> {
+        self.inner.pod()
+    }
+}
+
+impl<Inner, StSetup> NamespacedHttpService<Body, Body> for NotifyingPodService<Inner, StSetup>
+where
+    Inner: PodService + Clone,
+    Inner::Pod: Pod<Storage = RcpStorage<StSetup>>,
+    StSetup: RcpStorageSetup,
+{
+    #[inline]
+    fn has_in_uri_ns(&self, uri: &SolidResourceUri) -> bool {
+        self.inner.has_in_uri_ns(uri)
+    }
+}
+
+impl<Inner, StSetup> Service<()> for NotifyingPodService<Inner, StSetup>
+where
+    Inner: PodService + Clone,
+    StSetup: RcpStorageSetup,
+{
+    type Response = bool;
+
+    type Error = Problem;
+
+    type Future = ProbFuture<'static, bool>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Service::<()>::poll_ready(&mut self.inner, cx)
+    }
+
+    #[inline]
+    fn call(&mut self, _req: ()) -> Self::Future {
+        self.inner.call(())
+    }
+}
+
+/// A [`NotifyingPodServiceFactory`] resolves a
+/// [`NotifyingPodService`] for each pod.
+#[derive(Debug, Clone, Default)]
+pub struct NotifyingPodServiceFactory<InnerFactory> {
+    /// Inner factory.
+    pub inner_factory: Arc<InnerFactory>,
+}
+
+impl<InnerFactory, StSetup> PodServiceFactory for NotifyingPodServiceFactory<InnerFactory>
+where
+    InnerFactory: PodServiceFactory,
+    InnerFactory::Pod: Pod<Storage = RcpStorage<StSetup>>,
+    InnerFactory::Service: Clone,
+    StSetup: RcpStorageSetup,
+    NotifyingPodService<InnerFactory::Service, StSetup>: PodService<Pod = InnerFactory::Pod>,
+{
+    type Pod = InnerFactory::Pod;
+    type Service = NotifyingPodService<InnerFactory::Service, StSetup>;
+
+    #[inline]
+    fn new_service(&self, pod: Arc<InnerFactory::Pod>) -> Self::Service {
+        let storage = pod.storage().clone();
+        Self::Service {
+            opt_notifications_svc: storage
+                .extensions
+                .get_rv::<KRcpNotificationChannels>()
+                .map(|channels| channels.new_service(storage.clone())),
+            inner: self.inner_factory.new_service(pod),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use claims::*;
+    use http::{Method, StatusCode};
+    use manas_podverse::pod::impl_::BasicPod;
+    use manas_repo_layers::eventing::event::ResourceChangeKind;
+
+    use super::*;
+    use crate::{
+        podverse::static_::RcpPodServiceFactory,
+        recipe::impl_::single_pod::{setup::impl_::FsWacRecipeSetup, SinglePodStorageSetup},
+        test_utils::{build_request, send_with, TestPod, OWNER_ID, ROOT_URI},
+    };
+
+    #[tokio::test]
+    async fn writes_publish_change_events() {
+        let pod = TestPod::new().await;
+        let mut events = pod.storage.event_bus().subscribe();
+
+        assert!(pod
+            .put_turtle("a.ttl", "<#a> <#b> <#c>.")
+            .await
+            .status
+            .is_success());
+        assert!(pod
+            .put_turtle("a.ttl", "<#a> <#b> <#d>.")
+            .await
+            .status
+            .is_success());
+        assert!(pod
+            .send(Method::DELETE, "a.ttl", &[], "", Some(OWNER_ID))
+            .await
+            .status
+            .is_success());
+
+        let res_uri = format!("{}a.ttl", ROOT_URI);
+        for expected_kind in [
+            ResourceChangeKind::Created,
+            ResourceChangeKind::Updated,
+            ResourceChangeKind::Deleted,
+        ] {
+            let event = assert_ok!(events.try_recv());
+            assert_eq!(event.kind, expected_kind);
+            assert_eq!(event.res_slot.id().uri.as_str(), res_uri);
+        }
+        assert_err!(events.try_recv());
+    }
+
+    #[tokio::test]
+    async fn notifying_pod_service_serves_notifications_of_configured_pods() {
+        let mut pod = TestPod::new().await;
+        let channels = assert_ok!(
+            RcpNotificationChannels::try_new(
+                pod.storage.space(),
+                pod.storage.event_bus().clone(),
+                Default::default(),
+            )
+            .await
+        );
+        let storage = Arc::get_mut(&mut pod.storage).expect("Storage must not be shared.");
+        configure_notifications(storage);
+        storage
+            .extensions
+            .insert_rec_item::<KRcpNotificationChannels>(channels);
+
+        let factory = NotifyingPodServiceFactory {
+            inner_factory: Arc::new(CW::<
+                RcpPodServiceFactory<SinglePodStorageSetup<FsWacRecipeSetup>>,
+            >::new(false)),
+        };
+        let svc = factory.new_service(Arc::new(BasicPod {
+            storage: pod.storage.clone(),
+        }));
+        assert_some!(svc.opt_notifications_svc.as_ref());
+
+        // Subscription requests are served.
+        let resp = send_with(
+            svc.clone(),
+            build_request(
+                Method::POST,
+                "_/notifications/websocket/",
+                &[("content-type", "application/ld+json")],
+                &format!(
+                    r#"{{"type": "WebSocketChannel2023", "topic": "{}"}}"#,
+                    ROOT_URI
+                ),
+                Some(OWNER_ID),
+            ),
+        )
+        .await;
+        assert_eq!(resp.status, StatusCode::OK);
+        assert!(resp.body.contains("receiveFrom"));
+
+        // Other requests are delegated.
+        let resp = send_with(svc, build_request(Method::GET, "", &[], "", Some(OWNER_ID))).await;
+        assert_eq!(resp.status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn notifying_pod_service_delegates_for_other_pods() {
+        let pod = TestPod::new().await;
+
+        let factory = NotifyingPodServiceFactory {
+            inner_factory: Arc::new(CW::<
+                RcpPodServiceFactory<SinglePodStorageSetup<FsWacRecipeSetup>>,
+            >::new(false)),
+        };
+        let svc = factory.new_service(Arc::new(BasicPod {
+            storage: pod.storage.clone(),
+        }));
+        assert_none!(svc.opt_notifications_svc.as_ref());
+
+        let resp = send_with(
+            svc,
+            build_request(
+                Method::POST,
+                "_/notifications/websocket/",
+                &[("content-type", "application/ld+json")],
+                &format!(
+                    r#"{{"type": "WebSocketChannel2023", "topic": "{}"}}"#,
+                    ROOT_URI
+                ),
+                Some(OWNER_ID),
+            ),
+        )
+        .await;
+        assert_ne!(resp.status, StatusCode::OK);
+    }
+}
@@ -1,13 +1,18 @@
 //! I define concrete types for the policy enforcement points for recipes.
 //!
 
-use std::{collections::HashSet, sync::Arc};
+use std::{collections::HashSet, sync::Arc, time::Duration};
 
+#[cfg(feature = "pdp-acp")]
+use manas_access_control::model::pdp::impl_::acp::AcpDecisionPoint;
+#[cfg(feature = "pdp-wac")]
+use manas_access_control::model::pdp::impl_::wac::WacDecisionPoint;
 use manas_access_control::model::{
     pdp::PolicyDecisionPoint,
     pep::impl_::{
         solid_compat::SimplePolicyEnforcementPoint, trivial::TrivialPolicyEnforcementPoint,
     },
+    prp::impl_::CacheLayeredPolicyRetrievalPoint,
 };
 use manas_authentication::common::credentials::impl_::basic::BasicRequestCredentials;
 use manas_http::{
@@ -21,10 +26,16 @@ use manas_http::{
     },
     uri::invariant::HierarchicalTrailingSlashHttpUri,
 };
+#[cfg(feature = "pdp-wac")]
+use manas_repo::context::{RepoContext, RepoContextual};
+#[cfg(feature = "pdp-wac")]
+use manas_repo_opendal::service::group_doc_resolver::ODRAgentGroupDocResolver;
 use manas_repo_opendal::{
     object_store::backend::ODRObjectStoreBackend, service::prp::ODRPolicyRetrievalPoint,
 };
 use manas_space::resource::uri::SolidResourceUri;
+#[cfg(feature = "pdp-wac")]
+use manas_space::SolidStorageSpace;
 use rdf_utils::model::triple::ArcTriple;
 use serde::Serialize;
 use upon::Engine;
@@ -32,8 +43,24 @@ use webid::WebId;
 
 use crate::{repo::RcpBaseRepoSetup, space::RcpStorageSpace, storage::RcpStorageSetup};
 
+/// Type of base access control policy retrieval point for the recipes.
+pub type RcpBasePRP<Backend> =
+    ODRPolicyRetrievalPoint<RcpBaseRepoSetup<Backend>, HashSet<ArcTriple>>;
+
 /// Type of access control policy retrieval point for the recipes.
-pub type RcpPRP<Backend> = ODRPolicyRetrievalPoint<RcpBaseRepoSetup<Backend>, HashSet<ArcTriple>>;
+pub type RcpPRP<Backend> = CacheLayeredPolicyRetrievalPoint<RcpBasePRP<Backend>>;
+
+/// Max capacity of the prp cache, in number of acr chain items.
+pub const RCP_PRP_CACHE_MAX_CAPACITY: u64 = 10_000;
+
+/// Time to live of items in the prp cache.
+///
+/// The cache is per process. Acr modifications made through
+/// other replicas of a horizontally scaled deployment, or
+/// directly in the backend, are not invalidated in it, and
+/// are seen only after this much time. Hence it is kept
+/// short.
+pub const RCP_PRP_CACHE_TTL: Duration = Duration::from_secs(30);
 
 /// Type of trivial policy enforcement point for the recipe.
 pub type RcpTrivialPEP = TrivialPolicyEnforcementPoint<RcpStorageSpace, BasicRequestCredentials>;
@@ -42,6 +69,52 @@ pub type RcpTrivialPEP = TrivialPolicyEnforcementPoint<RcpStorageSpace, BasicReq
 pub type RcpSimplePEP<Backend, PDP> =
     SimplePolicyEnforcementPoint<RcpStorageSpace, RcpPRP<Backend>, PDP>;
 
+/// Type of guards of pdp configuration for a storage. The
+/// configuration is released, when the guard is dropped.
+pub type RcpPDPStorageGuard = Arc<dyn std::any::Any + Send + Sync>;
+
+/// A trait for access control policy decision points for
+/// the recipes.
+pub trait RcpPDP:
+    PolicyDecisionPoint<StSpace = RcpStorageSpace, Graph = HashSet<ArcTriple>>
+{
+    /// Configure the pdp for a storage with given prp.
+    ///
+    /// Pdps can use it to resolve policy information local
+    /// to the storage through it's repo. Returned guard must
+    /// be kept alive as long as the storage is.
+    #[allow(unused_variables)]
+    fn configure_for_storage<Backend: ODRObjectStoreBackend>(
+        &self,
+        prp: &RcpPRP<Backend>,
+    ) -> Option<RcpPDPStorageGuard> {
+        None
+    }
+}
+
+#[cfg(feature = "pdp-acp")]
+impl RcpPDP for AcpDecisionPoint<RcpStorageSpace, HashSet<ArcTriple>> {}
+
+#[cfg(feature = "pdp-wac")]
+impl RcpPDP for WacDecisionPoint<RcpStorageSpace, HashSet<ArcTriple>> {
+    fn configure_for_storage<Backend: ODRObjectStoreBackend>(
+        &self,
+        prp: &RcpPRP<Backend>,
+    ) -> Option<RcpPDPStorageGuard> {
+        // Resolve agent groups in the storage through it's
+        // repo, while the storage is alive.
+        let agent_group_doc_router = self.agent_group_doc_router()?;
+        let repo_context = prp.inner().repo_context();
+        let registration = agent_group_doc_router.register_local(
+            repo_context.storage_space().root_res_uri().clone(),
+            Arc::new(ODRAgentGroupDocResolver::new_with_context(
+                repo_context.clone(),
+            )),
+        );
+        Some(Arc::new(registration))
+    }
+}
+
 /// An alias trait for recipe storage setup with [`RcpSimplePEP`] as pep.
 pub trait SimpleAccessRcpStorageSetup:
     RcpStorageSetup<PEP = RcpSimplePEP<Self::Backend_, Self::PDP>, Backend = Self::Backend_>
@@ -50,12 +123,12 @@ pub trait SimpleAccessRcpStorageSetup:
     type Backend_: ODRObjectStoreBackend;
 
     /// Type of the pdp.
-    type PDP: PolicyDecisionPoint<StSpace = RcpStorageSpace, Graph = HashSet<ArcTriple>>;
+    type PDP: RcpPDP;
 }
 
 impl<
         Backend: ODRObjectStoreBackend,
-        PDP: PolicyDecisionPoint<StSpace = RcpStorageSpace, Graph = HashSet<ArcTriple>>,
+        PDP: RcpPDP,
         S: RcpStorageSetup<PEP = RcpSimplePEP<Backend, PDP>, Backend = Backend>,
     > SimpleAccessRcpStorageSetup for S
 {
@@ -121,3 +194,91 @@ pub fn resolve_initial_root_acr_rep_factory(
         Some(rep.into_binary())
     })
 }
+
+#[cfg(all(test, feature = "backend-fs", feature = "pdp-wac"))]
+mod tests {
+    use std::io;
+
+    use claims::*;
+    use futures::future::BoxFuture;
+    use http::{Method, StatusCode};
+    use manas_access_control::audit::{sink::AuditSink, AuditDecision, AuditEmitter, AuditEvent};
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::{
+        recipe::impl_::single_pod::RcpStorageOptions,
+        test_utils::{TestPod, OTHER_ID, OWNER_ID, ROOT_URI},
+    };
+
+    /// An [`AuditSink`] that forwards events to a channel.
+    #[derive(Debug)]
+    struct ChannelAuditSink(mpsc::UnboundedSender<AuditEvent>);
+
+    impl AuditSink for ChannelAuditSink {
+        fn write<'s>(&'s mut self, event: &'s AuditEvent) -> BoxFuture<'s, io::Result<()>> {
+            Box::pin(async move {
+                self.0
+                    .send(event.clone())
+                    .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "Receiver is closed."))
+            })
+        }
+    }
+
+    /// Wait for an audit event that satisfies given predicate.
+    async fn wait_for_event(
+        rx: &mut mpsc::UnboundedReceiver<AuditEvent>,
+        predicate: impl Fn(&AuditEvent) -> bool,
+    ) -> AuditEvent {
+        let wait = async {
+            loop {
+                let event = assert_some!(rx.recv().await);
+                if predicate(&event) {
+                    return event;
+                }
+            }
+        };
+        assert_ok!(tokio::time::timeout(Duration::from_secs(10), wait).await)
+    }
+
+    #[tokio::test]
+    async fn access_decisions_are_audited() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let pod = TestPod::new_with_options(
+            RcpStorageOptions {
+                opt_audit_emitter: Some(Arc::new(AuditEmitter::new(
+                    vec![Box::new(ChannelAuditSink(tx))],
+                    64,
+                ))),
+                ..Default::default()
+            },
+            |_| {},
+        )
+        .await;
+        let target = format!("{}a.ttl", ROOT_URI);
+
+        let resp = pod.put_turtle("a.ttl", "<#it> a <#Thing>.").await;
+        assert!(resp.status.is_success(), "{:?}", resp);
+        let event = wait_for_event(&mut rx, |event| {
+            event.target == target && event.agent.as_deref() == Some(OWNER_ID)
+        })
+        .await;
+        assert_eq!(event.decision, AuditDecision::Allow);
+        assert!(event.denied_ops.is_empty());
+        assert!(!event.requested_ops.is_empty());
+        assert!(!event.grants.is_empty());
+        assert!(!event.matched_policies.is_empty());
+
+        let resp = pod
+            .send(Method::GET, "a.ttl", &[], "", Some(OTHER_ID))
+            .await;
+        assert_eq!(resp.status, StatusCode::FORBIDDEN);
+        let event = wait_for_event(&mut rx, |event| {
+            event.target == target && event.agent.as_deref() == Some(OTHER_ID)
+        })
+        .await;
+        assert_eq!(event.decision, AuditDecision::Deny);
+        assert!(!event.denied_ops.is_empty());
+        assert!(event.grants.is_empty());
+    }
+}
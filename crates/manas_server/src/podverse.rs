@@ -18,6 +18,7 @@ pub mod static_ {
     use manas_storage::service::impl_::DefaultStorageServiceFactory;
 
     use crate::{
+        explain::{ExplainingPodService, ExplainingPodServiceFactory},
         storage::{RcpStorage, RcpStorageService, RcpStorageServiceFactory, RcpStorageSetup},
         CW,
     };
@@ -26,12 +27,15 @@ pub mod static_ {
     pub type RcpPod<StSetup> = BasicPod<RcpStorage<StSetup>>;
 
     /// Type of pod services for recipes.
-    pub type RcpPodService<StSetup> =
-        StorageDescribingPodService<BasicPodService<RcpPod<StSetup>, RcpStorageService<StSetup>>>;
+    pub type RcpPodService<StSetup> = ExplainingPodService<
+        StorageDescribingPodService<BasicPodService<RcpPod<StSetup>, RcpStorageService<StSetup>>>,
+    >;
 
     /// Type of pod service factories for recipes.
-    pub type RcpPodServiceFactory<StSetup> = StorageDescribingPodServiceFactory<
-        BasicPodServiceFactory<RcpPod<StSetup>, RcpStorageServiceFactory<StSetup>>,
+    pub type RcpPodServiceFactory<StSetup> = ExplainingPodServiceFactory<
+        StorageDescribingPodServiceFactory<
+            BasicPodServiceFactory<RcpPod<StSetup>, RcpStorageServiceFactory<StSetup>>,
+        >,
     >;
 
     /// Type of static podsets for recipes.
@@ -45,10 +49,12 @@ pub mod static_ {
         /// Get a new pod service factory.
         #[allow(clippy::new_ret_no_self)]
         pub fn new(dev_mode: bool) -> RcpPodServiceFactory<StSetup> {
-            StorageDescribingPodServiceFactory {
-                inner_factory: Arc::new(BasicPodServiceFactory::new(Arc::new(
-                    DefaultStorageServiceFactory::new(dev_mode),
-                ))),
+            ExplainingPodServiceFactory {
+                inner_factory: Arc::new(StorageDescribingPodServiceFactory {
+                    inner_factory: Arc::new(BasicPodServiceFactory::new(Arc::new(
+                        DefaultStorageServiceFactory::new(dev_mode),
+                    ))),
+                }),
             }
         }
     }
@@ -168,6 +174,10 @@ pub mod assets_overriden_static {
                 Default::default(),
                 Arc::new(|_| None),
                 Default::default(),
+                Default::default(),
+                Default::default(),
+                None,
+                Default::default(),
             );
 
             let assets_pod = BasicPod {
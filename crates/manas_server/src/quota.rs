@@ -0,0 +1,115 @@
+//! I define quota related utilities for recipes.
+//!
+
+use std::sync::Arc;
+
+use dyn_problem::Problem;
+use manas_podverse::pod::service::impl_::{
+    KDynamicStorageDescriptionStatements, StorageDescriptionStatementsResolver,
+};
+use manas_repo_layers::quota::tracker::QuotaLimits;
+use manas_space::SolidStorageSpace;
+use manas_storage::SolidStorageExt;
+use tracing::info;
+use typed_record::TypedRecord;
+
+use crate::{
+    recipe::impl_::common::config::RcpQuotaConfig,
+    storage::{RcpStorage, RcpStorageSetup},
+};
+
+impl From<RcpQuotaConfig> for QuotaLimits {
+    #[inline]
+    fn from(config: RcpQuotaConfig) -> Self {
+        Self {
+            max_bytes: config.max_bytes,
+            max_resources: config.max_resources,
+        }
+    }
+}
+
+/// Configure given storage to enforce it's quota with
+/// accurate usage, and to advertise it's usage in it's
+/// description.
+///
+/// It rebuilds usage counters by scanning the backend. Hence
+/// it should be called before the storage starts serving.
+pub async fn configure_quota<StSetup: RcpStorageSetup>(
+    storage: &mut RcpStorage<StSetup>,
+) -> Result<(), Problem> {
+    let usage = storage.rebuild_quota_usage().await?;
+    info!("Storage quota usage: {:?}", usage);
+
+    advertise_quota(storage);
+    Ok(())
+}
+
+/// Configure given storage to advertise it's usage and quota
+/// in it's description, without rebuilding usage counters.
+pub fn advertise_quota<StSetup: RcpStorageSetup>(storage: &mut RcpStorage<StSetup>) {
+    let tracker = storage.quota_tracker().clone();
+    let root_res_uri = storage.space().root_res_uri().clone();
+    let resolver: StorageDescriptionStatementsResolver =
+        Arc::new(move || tracker.description_statements(&root_res_uri));
+
+    let mut resolvers = storage
+        .extensions
+        .get_rv::<KDynamicStorageDescriptionStatements>()
+        .cloned()
+        .unwrap_or_default();
+    resolvers.push(resolver);
+
+    storage
+        .extensions
+        .insert_rec_item::<KDynamicStorageDescriptionStatements>(resolvers);
+}
+
+#[cfg(all(test, feature = "backend-fs", feature = "pdp-wac"))]
+mod tests {
+    use claims::*;
+    use http::Method;
+    use manas_repo_layers::versioning::config::VersioningConfig;
+
+    use crate::test_utils::{TestPod, OWNER_ID};
+
+    const DOC: &str = "<#it> <http://example.org/p> \"o\".";
+
+    #[tokio::test]
+    async fn tracked_usage_matches_scanned_usage() {
+        let pod = TestPod::new_with_versioning(
+            VersioningConfig {
+                enabled: true,
+                retention: Default::default(),
+            },
+            |_| {},
+        )
+        .await;
+        let initial = assert_ok!(pod.storage.rebuild_quota_usage().await);
+
+        for (path, body) in [
+            ("c/a.ttl", DOC),
+            // Snapshots prior version.
+            ("c/a.ttl", "<#it> <http://example.org/p> \"changed\"."),
+            ("c/b.ttl", DOC),
+        ] {
+            assert!(pod.put_turtle(path, body).await.status.is_success());
+        }
+        pod.put_acl("c/a.ttl", &[(OWNER_ID, "acl:Read, acl:Write, acl:Control")])
+            .await;
+
+        // Deletes aux acl along with the resource, and
+        // snapshots it's last version.
+        let resp = pod
+            .send(Method::DELETE, "c/a.ttl", &[], "", Some(OWNER_ID))
+            .await;
+        assert!(resp.status.is_success(), "{:?}", resp);
+
+        let tracked = pod.storage.quota_tracker().usage();
+        let scanned = assert_ok!(pod.storage.rebuild_quota_usage().await);
+
+        assert_eq!(tracked, scanned);
+        // Container `c/` and `c/b.ttl` remain.
+        assert_eq!(scanned.resources, initial.resources + 2);
+        assert!(scanned.bytes > initial.bytes + DOC.len() as u64);
+    }
+}
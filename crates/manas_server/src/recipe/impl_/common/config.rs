@@ -1,9 +1,22 @@
 //! I provide few common types for recipe configurations.
 //!
 
-use std::{net::SocketAddr, path::PathBuf};
+use std::{net::SocketAddr, num::NonZeroU64, path::PathBuf, time::Duration};
 
 use http::HeaderName;
+use manas_access_control::audit::{
+    emitter::DEFAULT_AUDIT_BUFFER_CAPACITY,
+    sink::{
+        impl_::{
+            json_lines_file::{RotatingJsonLinesFileSink, RotatingJsonLinesFileSinkConfig},
+            tracing::TracingAuditSink,
+        },
+        AuditSink,
+    },
+    AuditEmitter,
+};
+use manas_repo_layers::versioning::config::{VersionRetention, VersioningConfig};
+use manas_storage::service::cors::CorsPolicy;
 use serde_with::{serde_as, DisplayFromStr};
 
 /// Recipe tls config.
@@ -31,4 +44,340 @@ pub struct RcpServerConfig {
     #[serde(default)]
     #[serde_as(as = "Vec<DisplayFromStr>")]
     pub trusted_proxy_headers: Vec<HeaderName>,
+
+    /// Cors config.
+    #[serde(default)]
+    pub cors: RcpCorsConfig,
+
+    /// Verifiable presentation config.
+    #[serde(default)]
+    pub vc_presentation: RcpVcPresentationConfig,
+
+    /// Solid-oidc dpop config.
+    #[serde(default)]
+    pub dpop: RcpDPoPConfig,
+
+    /// Http message signatures config.
+    #[serde(default)]
+    pub httpsig: RcpHttpSigConfig,
+}
+
+/// Recipe solid-oidc dpop config.
+///
+/// Defaults to not requiring server provided nonces in dpop
+/// proofs.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct RcpDPoPConfig {
+    /// Rotation interval of server provided dpop nonces, in
+    /// seconds. If provided, dpop proofs must contain a nonce
+    /// issued by the server in `DPoP-Nonce` header. It must
+    /// not be zero.
+    #[serde(default)]
+    pub nonce_rotation_secs: Option<NonZeroU64>,
+}
+
+/// Recipe http message signatures config.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct RcpHttpSigConfig {
+    /// Maximum size of request bodies in bytes, that are
+    /// buffered to verify their `Content-Digest`. Defaults to
+    /// 1 MiB. Requests with larger bodies and a
+    /// `Content-Digest` field are rejected.
+    #[serde(default)]
+    pub max_digest_verifiable_body_size: Option<usize>,
+}
+
+/// Recipe cors config.
+///
+/// Defaults to liberal policy, that allows any origin with
+/// credentials.
+#[serde_as]
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct RcpCorsConfig {
+    /// Allowed origin patterns. A pattern can have `*`
+    /// wildcards. If empty, any origin is allowed.
+    pub allowed_origins: Vec<String>,
+
+    /// Whether to allow credentials.
+    pub allow_credentials: bool,
+
+    /// Max age of preflight responses, in seconds.
+    pub max_age_secs: Option<u64>,
+
+    /// Whether to allow any request headers.
+    pub allow_any_headers: bool,
+
+    /// Extra headers to be allowed.
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    pub extra_allowed_headers: Vec<HeaderName>,
+
+    /// Extra headers to be exposed.
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    pub extra_exposed_headers: Vec<HeaderName>,
+}
+
+impl Default for RcpCorsConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            allowed_origins: vec![],
+            allow_credentials: true,
+            max_age_secs: None,
+            allow_any_headers: true,
+            extra_allowed_headers: vec![],
+            extra_exposed_headers: vec![],
+        }
+    }
+}
+
+impl From<RcpCorsConfig> for CorsPolicy {
+    #[inline]
+    fn from(config: RcpCorsConfig) -> Self {
+        Self {
+            allowed_origin_patterns: config.allowed_origins,
+            allow_credentials: config.allow_credentials,
+            max_age: config.max_age_secs.map(Duration::from_secs),
+            mirror_request_headers: config.allow_any_headers,
+            extra_allowed_headers: config.extra_allowed_headers,
+            extra_exposed_headers: config.extra_exposed_headers,
+        }
+    }
+}
+
+/// Recipe verifiable presentation config.
+///
+/// Defaults to no trusted issuers, in which case no
+/// presented credential is accepted.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct RcpVcPresentationConfig {
+    /// Trusted credential issuers.
+    #[serde(default)]
+    pub trusted_issuers: Vec<RcpTrustedVcIssuerConfig>,
+
+    /// Max lifetime of a presentation in seconds. Defaults
+    /// to 300 seconds.
+    #[serde(default)]
+    pub max_lifetime_secs: Option<u64>,
+}
+
+/// Recipe trusted credential issuer config.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct RcpTrustedVcIssuerConfig {
+    /// Id of the issuer, as in `iss` claim of credentials.
+    pub id: String,
+
+    /// Path to the json file of issuer's jwks.
+    pub jwks_path: PathBuf,
+}
+
+#[cfg(feature = "layer-authentication")]
+impl RcpVcPresentationConfig {
+    /// Resolve the presentation verifier.
+    pub fn resolve_verifier(
+        &self,
+    ) -> Result<
+        manas_authentication::vc_presentation::verifier::VcPresentationVerifier,
+        manas_space::BoxError,
+    > {
+        use manas_authentication::vc_presentation::{
+            trusted_issuers::TrustedVcIssuers,
+            verifier::{
+                VcPresentationVerifier, DEFAULT_MAX_PRESENTATION_LIFETIME, DEFAULT_TIME_LEEWAY,
+            },
+        };
+
+        let mut trusted_issuers = TrustedVcIssuers::default();
+        for issuer in &self.trusted_issuers {
+            let jwks_json = std::fs::read_to_string(&issuer.jwks_path)?;
+            trusted_issuers =
+                trusted_issuers.try_with_issuer_jwks_json(issuer.id.clone(), &jwks_json)?;
+        }
+        Ok(VcPresentationVerifier::new_with_limits(
+            std::sync::Arc::new(trusted_issuers),
+            self.max_lifetime_secs
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_MAX_PRESENTATION_LIFETIME),
+            DEFAULT_TIME_LEEWAY,
+        ))
+    }
+}
+
+/// Recipe notifications config.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct RcpNotificationsConfig {
+    /// Directory in which webhook subscriptions and pending
+    /// deliveries are persisted. If not provided, they are
+    /// kept in memory only.
+    #[serde(default)]
+    pub webhook_state_dir: Option<PathBuf>,
+}
+
+/// Recipe resource locker config.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RcpLockerConfig {
+    /// In memory locker. Only a single server process can
+    /// serve the storage safely with it.
+    #[default]
+    Inmem,
+
+    /// Lock file based locker. Multiple server processes
+    /// can serve the storage safely with it, if they share
+    /// the lock directory.
+    Flock {
+        /// Directory in which lock files are kept.
+        lock_dir: PathBuf,
+
+        /// Max duration to wait for acquiring a lock, in
+        /// seconds.
+        #[serde(default)]
+        acquire_timeout_secs: Option<u64>,
+    },
+
+    /// Redis based locker. Multiple server processes across
+    /// hosts can serve the storage safely with it, if they
+    /// share the redis server.
+    #[cfg(feature = "locker-redis")]
+    Redis {
+        /// Url of the redis server.
+        url: String,
+
+        /// Prefix of the redis keys of locks.
+        #[serde(default)]
+        key_prefix: Option<String>,
+
+        /// Ttl of the lock leases, in seconds.
+        #[serde(default)]
+        lease_ttl_secs: Option<u64>,
+
+        /// Max duration to wait for acquiring a lock, in
+        /// seconds.
+        #[serde(default)]
+        acquire_timeout_secs: Option<u64>,
+    },
+}
+
+/// Recipe storage quota config.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct RcpQuotaConfig {
+    /// Maximum total size of resource representations in
+    /// bytes.
+    #[serde(default)]
+    pub max_bytes: Option<u64>,
+
+    /// Maximum number of resources.
+    #[serde(default)]
+    pub max_resources: Option<u64>,
+}
+
+/// Recipe storage versioning config.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct RcpVersioningConfig {
+    /// Maximum number of versions to retain per resource.
+    #[serde(default)]
+    pub max_versions: Option<usize>,
+
+    /// Maximum age of versions to retain, in seconds.
+    #[serde(default)]
+    pub max_age_secs: Option<u64>,
+}
+
+impl From<RcpVersioningConfig> for VersioningConfig {
+    #[inline]
+    fn from(config: RcpVersioningConfig) -> Self {
+        Self {
+            enabled: true,
+            retention: VersionRetention {
+                max_count: config.max_versions,
+                max_age: config.max_age_secs.map(Duration::from_secs),
+            },
+        }
+    }
+}
+
+/// Recipe access decision audit config.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct RcpAuditConfig {
+    /// Path of the audit log file, to which audit events are
+    /// appended as json lines. If not set, audit events are
+    /// not written to files.
+    #[serde(default)]
+    pub log_path: Option<PathBuf>,
+
+    /// Max size of an audit log file in bytes, after which
+    /// it is rotated.
+    #[serde(default)]
+    pub max_file_bytes: Option<u64>,
+
+    /// Max number of rotated audit log files to retain.
+    #[serde(default)]
+    pub max_rotated_files: Option<usize>,
+
+    /// Whether to log audit events as tracing events.
+    #[serde(default)]
+    pub tracing: bool,
+
+    /// Capacity of the audit events buffer. Events are
+    /// dropped, if it is full.
+    #[serde(default)]
+    pub buffer_capacity: Option<usize>,
+}
+
+impl RcpAuditConfig {
+    /// Resolve the audit emitter. It must be called in the
+    /// context of a tokio runtime.
+    pub fn resolve_emitter(&self) -> AuditEmitter {
+        let mut sinks: Vec<Box<dyn AuditSink>> = vec![];
+
+        if let Some(log_path) = self.log_path.clone() {
+            let mut sink_config = RotatingJsonLinesFileSinkConfig::new(log_path);
+            if let Some(max_file_bytes) = self.max_file_bytes {
+                sink_config.max_file_bytes = max_file_bytes;
+            }
+            if let Some(max_rotated_files) = self.max_rotated_files {
+                sink_config.max_rotated_files = max_rotated_files;
+            }
+            sinks.push(Box::new(RotatingJsonLinesFileSink::new(sink_config)));
+        }
+
+        if self.tracing {
+            sinks.push(Box::new(TracingAuditSink));
+        }
+
+        AuditEmitter::new(
+            sinks,
+            self.buffer_capacity
+                .unwrap_or(DEFAULT_AUDIT_BUFFER_CAPACITY),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use claims::*;
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case(serde_json::json!({}), None)]
+    #[case(serde_json::json!({ "nonce_rotation_secs": 300 }), Some(300))]
+    fn valid_dpop_config_will_be_accepted(
+        #[case] value: serde_json::Value,
+        #[case] expected_rotation_secs: Option<u64>,
+    ) {
+        let config: RcpDPoPConfig = assert_ok!(serde_json::from_value(value));
+        assert_eq!(
+            config.nonce_rotation_secs.map(NonZeroU64::get),
+            expected_rotation_secs
+        );
+    }
+
+    #[test]
+    fn zero_nonce_rotation_interval_will_be_rejected() {
+        assert_err!(serde_json::from_value::<RcpDPoPConfig>(
+            serde_json::json!({ "nonce_rotation_secs": 0 })
+        ));
+    }
 }
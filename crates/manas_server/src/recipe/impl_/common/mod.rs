@@ -19,12 +19,17 @@ use manas_http::{
         HttpService,
     },
 };
-use manas_storage::service::cors::LiberalCors;
+use manas_repo_opendal::object_store::backend::{
+    impl_::config::{BackendConfigError, ODRBackendConfig},
+    BuildableODRObjectStoreBackend,
+};
+use manas_space::BoxError;
+use manas_storage::service::cors::{CorsPolicy, LiberalCors};
 use tower::{make::Shared, Layer};
 use tower_http::catch_panic::CatchPanic;
 use tracing::error;
 
-use self::config::RcpServerConfig;
+use self::config::{RcpDPoPConfig, RcpHttpSigConfig, RcpServerConfig};
 
 pub mod config;
 
@@ -39,17 +44,30 @@ impl<S> SendMakeService for S where
 {
 }
 
+/// Resolve backend from given typed backend config.
+pub fn resolve_backend<Builder, Backend>(config: ODRBackendConfig) -> Result<Backend, BoxError>
+where
+    Builder: opendal::Builder + TryFrom<ODRBackendConfig, Error = BackendConfigError>,
+    Backend: BuildableODRObjectStoreBackend<Builder>,
+{
+    Ok(Backend::try_from(Builder::try_from(config)?)?)
+}
+
 /// Resolve service maker for given podset service.
 pub fn resolve_svc_maker(
     podset_svc: impl HttpService<Body, Body> + Clone,
     uri_reconstruction_params: UriReconstructionParams,
+    cors_policy: &CorsPolicy,
 ) -> impl SendMakeService {
-    Shared::new(AdaptIncomingBody::new(CatchPanic::new(LiberalCors::new(
-        ReconstructTargetUri::new(
-            uri_reconstruction_params,
-            NormalValidateTargetUri::new(podset_svc),
+    Shared::new(AdaptIncomingBody::new(CatchPanic::new(
+        LiberalCors::new_with_policy(
+            ReconstructTargetUri::new(
+                uri_reconstruction_params,
+                NormalValidateTargetUri::new(podset_svc),
+            ),
+            cors_policy,
         ),
-    ))))
+    )))
 }
 
 /// Resolve service maker for given podset service.
@@ -57,14 +75,34 @@ pub fn resolve_svc_maker(
 pub fn resolve_authenticating_svc_maker(
     podset_svc: impl HttpService<Body, Body> + Clone,
     uri_reconstruction_params: UriReconstructionParams,
+    cors_policy: &CorsPolicy,
+    vc_verifier: manas_authentication::vc_presentation::verifier::VcPresentationVerifier,
+    dpop_config: &RcpDPoPConfig,
+    httpsig_config: &RcpHttpSigConfig,
 ) -> impl SendMakeService {
-    resolve_svc_maker(manas_authentication::challenge_response_framework::service::HttpCRAuthenticationLayer::<
-        _,
-        _,
-        Body,
-        BasicRequestAuthenticator<BasicRequestCredentials>,
-    >::new(
-            manas_authentication::challenge_response_framework::scheme::impl_::solid_oidc::DefaultSolidOidcDpopScheme::default(),
+    use manas_authentication::{
+        challenge_response_framework::scheme::impl_::httpsig::content_digest::{
+            ContentDigestVerifyingLayer, DEFAULT_MAX_VERIFIABLE_BODY_SIZE,
+        },
+        vc_presentation::service::HttpVcPresentationLayer,
+    };
+
+    // Content digests are verified before authentication, as
+    // message signatures cover bodies only through them.
+    resolve_svc_maker(
+        ContentDigestVerifyingLayer::new(
+            httpsig_config
+                .max_digest_verifiable_body_size
+                .unwrap_or(DEFAULT_MAX_VERIFIABLE_BODY_SIZE),
+        )
+        .layer(
+        manas_authentication::challenge_response_framework::service::HttpCRAuthenticationLayer::<
+            _,
+            _,
+            Body,
+            BasicRequestAuthenticator<BasicRequestCredentials>,
+        >::new(
+            resolve_authentication_scheme(dpop_config),
             Arc::new(vec![
                 Method::POST,
                 Method::PATCH,
@@ -72,11 +110,60 @@ pub fn resolve_authenticating_svc_maker(
                 Method::DELETE,
             ]),
         )
-        .layer(podset_svc),
-        uri_reconstruction_params
+        .layer(
+            HttpVcPresentationLayer::<BasicRequestCredentials>::new(vc_verifier).layer(podset_svc),
+        ),
+        ),
+        uri_reconstruction_params,
+        cors_policy,
     )
 }
 
+/// Resolve the authentication scheme, that accepts both
+/// solid-oidc and http message signatures credentials.
+#[cfg(feature = "layer-authentication")]
+pub fn resolve_authentication_scheme(
+    dpop_config: &RcpDPoPConfig,
+) -> manas_authentication::challenge_response_framework::scheme::impl_::union::UnionCRAuthenticationScheme<
+    BasicRequestCredentials,
+>{
+    use manas_authentication::challenge_response_framework::scheme::{
+        impl_::{
+            httpsig::{DefaultHttpSigScheme, HTTPSIG_SCHEME},
+            solid_oidc::{nonce::DPoPNonceIssuer, DefaultSolidOidcDpopScheme},
+            union::UnionCRAuthenticationScheme,
+        },
+        DynCRAuthenticationScheme,
+    };
+
+    let dpop_scheme = DefaultSolidOidcDpopScheme {
+        nonce_issuer: dpop_config.nonce_rotation_secs.map(|secs| {
+            Arc::new(DPoPNonceIssuer::new(std::time::Duration::from_secs(
+                secs.get(),
+            )))
+        }),
+        ..Default::default()
+    };
+
+    let schemes: Vec<(
+        &str,
+        Arc<DynCRAuthenticationScheme<BasicRequestCredentials>>,
+    )> = vec![
+        ("DPoP", Arc::new(dpop_scheme)),
+        (HTTPSIG_SCHEME, Arc::new(DefaultHttpSigScheme::default())),
+    ];
+
+    let schemes = schemes
+        .into_iter()
+        .map(|(name, scheme)| (name.parse().expect("Must be a valid token."), scheme))
+        .collect::<std::collections::HashMap<_, _>>();
+
+    UnionCRAuthenticationScheme {
+        default_challenge_schemes: schemes.keys().cloned().collect(),
+        schemes,
+    }
+}
+
 /// Serve the recipe.
 pub async fn serve_recipe(
     config: RcpServerConfig,
@@ -7,3 +7,6 @@ pub mod single_pod_noauth;
 
 #[cfg(feature = "layer-authentication")]
 pub mod single_pod;
+
+#[cfg(feature = "layer-authentication")]
+pub mod multi_pod;
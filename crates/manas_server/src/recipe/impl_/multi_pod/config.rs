@@ -0,0 +1,193 @@
+//! I provide types to represent configuration for
+//! multi-pod, databrowser enabled recipes.
+//!
+
+use std::{num::NonZeroUsize, path::PathBuf};
+
+use manas_http::uri::invariant::HierarchicalTrailingSlashHttpUri;
+use manas_repo_opendal::object_store::backend::impl_::config::ODRBackendConfig;
+use webid::WebId;
+
+use crate::recipe::impl_::common::config::{
+    RcpAuditConfig, RcpLockerConfig, RcpNotificationsConfig, RcpQuotaConfig, RcpServerConfig,
+    RcpVersioningConfig,
+};
+
+/// Recipe admin pod config.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RcpAdminPodConfig {
+    /// Root uri of the admin pod.
+    pub root_uri: HierarchicalTrailingSlashHttpUri,
+
+    /// Id of the admin. Admin owns the admin pod, and the
+    /// member pods provisioned through the admin pod's
+    /// provisions container.
+    pub owner_id: WebId,
+
+    /// Backend config of the admin pod.
+    pub backend: ODRBackendConfig,
+}
+
+/// Recipe member pods config.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RcpMemberPodsConfig {
+    /// Template of the member pod root uris. It must contain
+    /// exactly one `{pod}` placeholder, either in the
+    /// host (like `https://{pod}.example.org/`), or in the
+    /// path (like `https://example.org/pods/{pod}/`).
+    pub uri_template: String,
+
+    /// Backend config template of the member pods. Each
+    /// `{pod}` placeholder in string values is substituted
+    /// with the pod name, to derive backend config of each pod.
+    pub backend: RcpBackendConfigTemplate,
+}
+
+/// Backend config template of the member pods.
+///
+/// It retains the raw template, so that pod configs can be
+/// derived from it without serializing the config, which
+/// redacts inline secrets. It is validated as a backend
+/// config on deserialization, and is serialized as one.
+#[derive(Clone)]
+pub struct RcpBackendConfigTemplate {
+    /// Raw template.
+    raw: serde_json::Value,
+
+    /// Template, parsed as a backend config.
+    config: ODRBackendConfig,
+}
+
+impl std::fmt::Debug for RcpBackendConfigTemplate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Raw template is not logged, as it may have inline
+        // secrets.
+        self.config.fmt(f)
+    }
+}
+
+impl TryFrom<serde_json::Value> for RcpBackendConfigTemplate {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: serde_json::Value) -> Result<Self, Self::Error> {
+        Ok(Self {
+            config: serde_json::from_value(raw.clone())?,
+            raw,
+        })
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for RcpBackendConfigTemplate {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = serde_json::Value::deserialize(deserializer)?;
+        Self::try_from(raw).map_err(serde::de::Error::custom)
+    }
+}
+
+impl serde::Serialize for RcpBackendConfigTemplate {
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.config.serialize(serializer)
+    }
+}
+
+impl RcpBackendConfigTemplate {
+    /// Get the raw template.
+    #[inline]
+    pub fn raw(&self) -> &serde_json::Value {
+        &self.raw
+    }
+}
+
+/// Recipe self-service signup config.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RcpSignupConfig {
+    /// Maximum number of pods an agent can provision
+    /// through signup.
+    #[serde(default = "RcpSignupConfig::default_max_pods_per_agent")]
+    pub max_pods_per_agent: usize,
+
+    /// Maximum number of pods that can be provisioned
+    /// through signup, across all agents. If not set, it is
+    /// not limited.
+    #[serde(default)]
+    pub max_pods: Option<usize>,
+}
+
+impl Default for RcpSignupConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            max_pods_per_agent: Self::default_max_pods_per_agent(),
+            max_pods: None,
+        }
+    }
+}
+
+impl RcpSignupConfig {
+    #[inline]
+    fn default_max_pods_per_agent() -> usize {
+        1
+    }
+}
+
+/// Recipe config.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RcpConfig {
+    /// Admin pod config.
+    pub admin: RcpAdminPodConfig,
+
+    /// Member pods config.
+    pub pods: RcpMemberPodsConfig,
+
+    /// Directory in which registries of provisioned pods are
+    /// persisted.
+    pub state_dir: PathBuf,
+
+    /// Self-service signup config.
+    #[serde(default)]
+    pub signup: RcpSignupConfig,
+
+    /// Weather databrowser is enabled.
+    #[serde(default)]
+    pub databrowser_enabled: bool,
+
+    /// Max number of contained resources per page of
+    /// container representations of admin and member pods. If
+    /// not set, container representations will not be paged.
+    #[serde(default)]
+    pub max_container_page_size: Option<NonZeroUsize>,
+
+    /// Resource locker config. Admin and member pods share
+    /// the same locker.
+    #[serde(default)]
+    pub locker: RcpLockerConfig,
+
+    /// Storage quota config of each member pod. If not set,
+    /// member pod usage is neither limited, nor advertised.
+    #[serde(default)]
+    pub quota: Option<RcpQuotaConfig>,
+
+    /// Storage versioning config of member pods. If not set,
+    /// resources are not versioned.
+    #[serde(default)]
+    pub versioning: Option<RcpVersioningConfig>,
+
+    /// Access decision audit config of admin and member pods.
+    /// If not set, access decisions are not audited.
+    #[serde(default)]
+    pub audit: Option<RcpAuditConfig>,
+
+    /// Notifications config of member pods. Webhook state
+    /// of each pod is persisted in a sub directory named
+    /// after the pod.
+    #[serde(default)]
+    pub notifications: RcpNotificationsConfig,
+
+    /// Recipe server config.
+    pub server: RcpServerConfig,
+
+    /// Wether to run in dev mode.
+    #[serde(default)]
+    pub dev_mode: bool,
+}
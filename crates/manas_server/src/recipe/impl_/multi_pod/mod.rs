@@ -0,0 +1,349 @@
+//! I provide utilities to construct multi pod recipes, that
+//! serve dynamically provisioned pods.
+//!
+
+use std::{borrow::Cow, marker::PhantomData, sync::Arc};
+
+use futures::future::{BoxFuture, TryFutureExt};
+use http::uri::Scheme;
+use manas_http::service::impl_::UriReconstructionParams;
+use manas_podverse::{
+    pod::{
+        service::{
+            impl_::{BasicPodServiceFactory, StorageDescribingPodServiceFactory},
+            PodServiceFactory,
+        },
+        Pod, PodExt,
+    },
+    podset::{
+        impl_::dynamic::{
+            admin_pod::{
+                impl_::template_driven::{TemplateDrivenAdminPod, TemplateDrivenAdminPodConfig},
+                service::AdminPodServiceFactory,
+            },
+            DynamicPodSet,
+        },
+        service::impl_::{BasicPodSetService, OverridenPodSetService},
+    },
+};
+use manas_space::{resource::uri::SolidResourceUri, BoxError};
+use manas_storage::service::impl_::DefaultStorageServiceFactory;
+use name_locker::impl_::InmemNameLocker;
+use tracing::error;
+
+use self::{
+    config::{RcpConfig, RcpSignupConfig},
+    signup::RcpSignupService,
+    template::{RcpMemberPodTemplate, RcpPodOwnersRegistry},
+};
+use super::{
+    common::{resolve_authenticating_svc_maker, resolve_backend, serve_recipe},
+    single_pod::{
+        config::RcpStorageSpaceConfig, setup::SinglePodRecipeSetup, RcpStorageOptions,
+        SinglePodRecipe, SinglePodStorageSetup,
+    },
+};
+use crate::{
+    dtbr::DatabrowserContext,
+    locker::RcpResourceLocker,
+    notification::NotifyingPodServiceFactory,
+    podverse::static_::{RcpPod, RcpPodServiceFactory},
+    recipe::Recipe,
+    storage::RcpStorageServiceFactory,
+    CW,
+};
+
+pub mod config;
+pub mod signup;
+pub mod template;
+
+/// Name of the file in which provisioned pod names are
+/// persisted.
+const PROVISIONED_PODS_REGISTRY_FILE_NAME: &str = "provisioned_pods";
+
+/// Name of the file in which pod owners are persisted.
+const POD_OWNERS_REGISTRY_FILE_NAME: &str = "pod_owners.json";
+
+/// Path of the signup endpoint, relative to admin pod root.
+pub const SIGNUP_ENDPOINT_REL_PATH: &str = "_/signup";
+
+/// Type of the admin pods for multi pod recipes.
+pub type RcpAdminPod<RSetup> =
+    TemplateDrivenAdminPod<RcpPod<SinglePodStorageSetup<RSetup>>, RcpMemberPodTemplate<RSetup>>;
+
+/// Type of the admin pod service factories for multi pod
+/// recipes.
+pub type RcpAdminPodServiceFactory<RSetup> = AdminPodServiceFactory<
+    StorageDescribingPodServiceFactory<
+        BasicPodServiceFactory<
+            RcpAdminPod<RSetup>,
+            RcpStorageServiceFactory<SinglePodStorageSetup<RSetup>>,
+        >,
+    >,
+>;
+
+/// Type of the dynamic podset services for multi pod
+/// recipes. Member pod services serve notifications of
+/// their pods.
+pub type RcpDynamicPodSetService<RSetup> = BasicPodSetService<
+    DynamicPodSet<RcpAdminPod<RSetup>>,
+    NotifyingPodServiceFactory<RcpPodServiceFactory<SinglePodStorageSetup<RSetup>>>,
+>;
+
+/// Type of the podset services for multi pod recipes, in
+/// which admin pod service and signup service override
+/// dynamic podset service.
+pub type RcpMultiPodSetService<RSetup> = OverridenPodSetService<
+    OverridenPodSetService<
+        RcpDynamicPodSetService<RSetup>,
+        <RcpAdminPodServiceFactory<RSetup> as PodServiceFactory>::Service,
+    >,
+    RcpSignupService<RSetup>,
+>;
+
+/// An implementation of [`Recipe`] that serves multiple
+/// pods, provisioned through an admin pod, or through a
+/// self-service signup endpoint.
+///
+/// The signup endpoint is at `_/signup` relative to the
+/// admin pod root. An authenticated agent can `POST` a json
+/// document like `{"name": "alice"}` to it, to get a pod
+/// provisioned with it as the owner. Admin can also
+/// provision pods owned by itself, by posting to the admin
+/// pod root with pod name in the `Slug` header.
+pub struct MultiPodRecipe<RSetup: SinglePodRecipeSetup> {
+    _phantom: PhantomData<fn(RSetup)>,
+}
+
+impl<RSetup: SinglePodRecipeSetup> Default for MultiPodRecipe<RSetup> {
+    fn default() -> Self {
+        Self {
+            _phantom: Default::default(),
+        }
+    }
+}
+
+impl<RSetup: SinglePodRecipeSetup> MultiPodRecipe<RSetup> {
+    /// Resolve the initialized admin pod.
+    async fn resolve_initialized_admin_pod(
+        config: &RcpConfig,
+    ) -> Result<Arc<RcpAdminPod<RSetup>>, BoxError> {
+        tokio::fs::create_dir_all(&config.state_dir)
+            .inspect_err(|e| error!("Error in creating state dir. Error:\n {}", e))
+            .await?;
+
+        let opt_databrowser_context = config
+            .databrowser_enabled
+            .then(DatabrowserContext::new_from_unpkg);
+
+        let admin_backend = resolve_backend::<RSetup::BackendBuilder, RSetup::Backend>(
+            config.admin.backend.clone(),
+        )
+        .map_err(|e| {
+            error!("Error in resolving admin pod backend. Error: {}", e);
+            e
+        })?;
+
+        // Storage options shared by admin and member pods.
+        let storage_options = RcpStorageOptions {
+            opt_databrowser_context,
+            max_container_page_size: config.max_container_page_size,
+            resource_locker: RcpResourceLocker::try_new(&config.locker)?,
+            opt_audit_emitter: config
+                .audit
+                .as_ref()
+                .map(|audit_config| Arc::new(audit_config.resolve_emitter())),
+            ..Default::default()
+        };
+
+        let admin_storage = SinglePodRecipe::<RSetup>::resolve_storage(
+            RcpStorageSpaceConfig {
+                root_uri: config.admin.root_uri.clone(),
+                owner_id: config.admin.owner_id.clone(),
+            },
+            admin_backend,
+            Default::default(),
+            RSetup::INITIAL_ROOT_ACR_TEMPLATE,
+            storage_options.clone(),
+        );
+
+        let owners =
+            RcpPodOwnersRegistry::load(Some(config.state_dir.join(POD_OWNERS_REGISTRY_FILE_NAME)))
+                .inspect_err(|e| error!("Error in loading pod owners registry. Error:\n {}", e))
+                .await?;
+
+        let member_template = RcpMemberPodTemplate::<RSetup>::try_new(
+            config.pods.clone(),
+            config.admin.root_uri.clone(),
+            config.admin.owner_id.clone(),
+            Arc::new(owners),
+            RcpStorageOptions {
+                quota_limits: config.quota.clone().map(Into::into).unwrap_or_default(),
+                versioning_config: config
+                    .versioning
+                    .clone()
+                    .map(Into::into)
+                    .unwrap_or_default(),
+                ..storage_options
+            },
+            config.quota.is_some(),
+            config.notifications.clone(),
+        )
+        .map_err(|e| {
+            error!("Invalid member pods config. Error: {}", e);
+            e
+        })?;
+
+        let admin_pod = TemplateDrivenAdminPod::new(
+            RcpPod {
+                storage: Arc::new(admin_storage),
+            },
+            member_template,
+            TemplateDrivenAdminPodConfig {
+                registry_path: Some(config.state_dir.join(PROVISIONED_PODS_REGISTRY_FILE_NAME)),
+                ..Default::default()
+            },
+        );
+
+        // Initialize the admin pod.
+        admin_pod
+            .initialize()
+            .inspect_err(|e| error!("Error in initializing the admin pod. Error:\n {}", e))
+            .await?;
+
+        Ok(Arc::new(admin_pod))
+    }
+
+    /// Resolve the podset service serving given admin pod,
+    /// and it's members.
+    fn resolve_podset_svc(
+        admin_pod: Arc<RcpAdminPod<RSetup>>,
+        signup_config: RcpSignupConfig,
+        dev_mode: bool,
+    ) -> RcpMultiPodSetService<RSetup> {
+        // Provisioning through admin pod and signup is
+        // serialized with the same locker.
+        let provisioning_locker = Arc::new(InmemNameLocker::default());
+
+        let members_svc = RcpDynamicPodSetService::<RSetup> {
+            pod_set: Arc::new(DynamicPodSet::new(admin_pod.clone())),
+            pod_service_factory: Arc::new(NotifyingPodServiceFactory {
+                inner_factory: Arc::new(CW::<RcpPodServiceFactory<_>>::new(dev_mode)),
+            }),
+        };
+
+        let admin_pod_svc = RcpAdminPodServiceFactory::<RSetup> {
+            inner_factory: Arc::new(StorageDescribingPodServiceFactory {
+                inner_factory: Arc::new(BasicPodServiceFactory::new(Arc::new(
+                    DefaultStorageServiceFactory::new(dev_mode),
+                ))),
+            }),
+            provisioning_locker: provisioning_locker.clone(),
+        }
+        .new_service(admin_pod.clone());
+
+        let signup_endpoint_uri = SolidResourceUri::try_new_from(
+            format!("{}{}", admin_pod.id().as_str(), SIGNUP_ENDPOINT_REL_PATH).as_str(),
+        )
+        .expect("Must be valid.");
+        let signup_svc = RcpSignupService::new(
+            signup_endpoint_uri,
+            admin_pod,
+            signup_config,
+            provisioning_locker,
+        );
+
+        OverridenPodSetService::new(
+            OverridenPodSetService::new(members_svc, admin_pod_svc),
+            signup_svc,
+        )
+    }
+}
+
+impl<RSetup: SinglePodRecipeSetup> Recipe for MultiPodRecipe<RSetup> {
+    type Config = RcpConfig;
+
+    fn cli_name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!(
+            "manas_server_multi_{}_{}",
+            RSetup::BACKEND_NAME,
+            RSetup::PDP_NAME
+        ))
+    }
+
+    fn description(&self) -> Cow<'static, str> {
+        Cow::Owned(format!("Manas solid server that serves multiple dynamically provisioned pods with {} backend and with {} access control system.", RSetup::BACKEND_NAME.to_uppercase(), RSetup::PDP_NAME.to_uppercase()))
+    }
+
+    fn serve(&self, config: Self::Config) -> BoxFuture<'static, Result<(), BoxError>> {
+        Box::pin(async move {
+            let admin_pod = Self::resolve_initialized_admin_pod(&config).await?;
+
+            let podset_svc =
+                Self::resolve_podset_svc(admin_pod, config.signup.clone(), config.dev_mode);
+
+            let uri_reconstruction_params = UriReconstructionParams {
+                default_scheme: if config.server.tls.is_some() {
+                    Scheme::HTTPS
+                } else {
+                    Scheme::HTTP
+                },
+                trusted_proxy_headers: config.server.trusted_proxy_headers.clone(),
+            };
+
+            let vc_verifier = config
+                .server
+                .vc_presentation
+                .resolve_verifier()
+                .map_err(|e| {
+                    error!("Error in resolving presentation verifier. Error: {}", e);
+                    e
+                })?;
+
+            let svc_maker = resolve_authenticating_svc_maker(
+                podset_svc,
+                uri_reconstruction_params,
+                &config.server.cors.clone().into(),
+                vc_verifier,
+                &config.server.dpop,
+                &config.server.httpsig,
+            );
+
+            tracing::info!("Serving at {}", config.server.addr);
+            tracing::info!("Admin pod root uri: {}", config.admin.root_uri.as_str());
+            tracing::info!("Member pod uri template: {}", config.pods.uri_template);
+
+            Ok(serve_recipe(config.server, svc_maker).await?)
+        })
+    }
+}
+
+#[cfg(all(feature = "backend-fs", feature = "pdp-wac"))]
+/// Recipe that serves multiple pods with FS backend, and WAC
+/// access control system.
+pub type MultiPodFsWacRecipe = MultiPodRecipe<super::single_pod::setup::impl_::FsWacRecipeSetup>;
+
+#[cfg(all(feature = "backend-fs", feature = "pdp-acp"))]
+/// Recipe that serves multiple pods with FS backend, and ACP
+/// access control system.
+pub type MultiPodFsAcpRecipe = MultiPodRecipe<super::single_pod::setup::impl_::FsAcpRecipeSetup>;
+
+#[cfg(all(feature = "backend-s3", feature = "pdp-wac"))]
+/// Recipe that serves multiple pods with S3 backend, and WAC
+/// access control system.
+pub type MultiPodS3WacRecipe = MultiPodRecipe<super::single_pod::setup::impl_::S3WacRecipeSetup>;
+
+#[cfg(all(feature = "backend-s3", feature = "pdp-acp"))]
+/// Recipe that serves multiple pods with S3 backend, and ACP
+/// access control system.
+pub type MultiPodS3AcpRecipe = MultiPodRecipe<super::single_pod::setup::impl_::S3AcpRecipeSetup>;
+
+#[cfg(all(feature = "backend-gcs", feature = "pdp-wac"))]
+/// Recipe that serves multiple pods with GCS backend, and WAC
+/// access control system.
+pub type MultiPodGcsWacRecipe = MultiPodRecipe<super::single_pod::setup::impl_::GcsWacRecipeSetup>;
+
+#[cfg(all(feature = "backend-gcs", feature = "pdp-acp"))]
+/// Recipe that serves multiple pods with GCS backend, and ACP
+/// access control system.
+pub type MultiPodGcsAcpRecipe = MultiPodRecipe<super::single_pod::setup::impl_::GcsAcpRecipeSetup>;
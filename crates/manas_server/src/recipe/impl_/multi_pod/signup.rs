@@ -0,0 +1,407 @@
+//! I define an http service, that handles self-service
+//! signup requests for member pods.
+//!
+
+use std::{
+    convert::Infallible,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::Poll,
+};
+
+use dyn_problem::Problem;
+use http::{header, Method, Request, Response, StatusCode};
+use http_api_problem::ApiError;
+use http_body_util::{BodyExt, Limited};
+use manas_authentication::common::credentials::{
+    impl_::basic::BasicRequestCredentials, AgentCredentials, RequestCredentials,
+};
+use manas_http::{
+    body::Body,
+    problem::ApiErrorExt,
+    service::{namespaced::NamespacedHttpService, BoxHttpResponseFuture},
+};
+use manas_podverse::{
+    pod::PodExt,
+    podset::impl_::dynamic::admin_pod::{
+        AdminPod, INVALID_MEMBER_POD_KEY, MEMBER_POD_ALREADY_PROVISIONED,
+    },
+};
+use manas_space::resource::uri::SolidResourceUri;
+use name_locker::{impl_::InmemNameLocker, LockKind, NameLocker};
+use tower::Service;
+use tracing::{error, info, warn};
+use webid::WebId;
+
+use super::{config::RcpSignupConfig, RcpAdminPod};
+use crate::recipe::impl_::single_pod::setup::SinglePodRecipeSetup;
+
+/// Maximum size of signup request body.
+const MAX_SIGNUP_REQUEST_SIZE: usize = 4 * 1024;
+
+/// A signup request.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct SignupRequest {
+    /// Name of the pod to be provisioned.
+    pub name: String,
+}
+
+/// A signup response.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SignupResponse {
+    /// Root uri of the provisioned pod.
+    pub pod: String,
+
+    /// Id of the pod owner.
+    pub owner: String,
+}
+
+/// A service that handles self-service signup requests.
+///
+/// On a `POST` request with a json [`SignupRequest`], it
+/// provisions a member pod with requested name, owned by the
+/// authenticated agent. Each agent can provision only up to
+/// configured number of pods, and total number of pods
+/// provisioned through signup can be limited.
+pub struct RcpSignupService<RSetup: SinglePodRecipeSetup> {
+    /// Uri of the signup endpoint.
+    endpoint_uri: SolidResourceUri,
+
+    /// Admin pod.
+    admin_pod: Arc<RcpAdminPod<RSetup>>,
+
+    /// Signup config.
+    config: Arc<RcpSignupConfig>,
+
+    /// Locker to serialize signups of each agent, keyed by
+    /// agent id, so that an agent cannot exceed it's quota
+    /// with racing signups.
+    signup_locker: Arc<InmemNameLocker<String>>,
+
+    /// Number of signups in progress. They are counted
+    /// against the global pod limit, so that racing signups
+    /// of different agents cannot exceed it.
+    pending_signups: Arc<AtomicUsize>,
+
+    /// Locker to serialize provisioning of pods with. It is
+    /// shared with the admin pod service, so that owner of a
+    /// pod is not overwritten by a racing provisioning.
+    provisioning_locker: Arc<InmemNameLocker<String>>,
+}
+
+impl<RSetup: SinglePodRecipeSetup> Clone for RcpSignupService<RSetup> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            endpoint_uri: self.endpoint_uri.clone(),
+            admin_pod: self.admin_pod.clone(),
+            config: self.config.clone(),
+            signup_locker: self.signup_locker.clone(),
+            pending_signups: self.pending_signups.clone(),
+            provisioning_locker: self.provisioning_locker.clone(),
+        }
+    }
+}
+
+impl<RSetup: SinglePodRecipeSetup> std::fmt::Debug for RcpSignupService<RSetup> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RcpSignupService")
+            .field("endpoint_uri", &self.endpoint_uri)
+            .finish()
+    }
+}
+
+impl<RSetup: SinglePodRecipeSetup> RcpSignupService<RSetup> {
+    /// Create a new [`RcpSignupService`].
+    #[inline]
+    pub fn new(
+        endpoint_uri: SolidResourceUri,
+        admin_pod: Arc<RcpAdminPod<RSetup>>,
+        config: RcpSignupConfig,
+        provisioning_locker: Arc<InmemNameLocker<String>>,
+    ) -> Self {
+        Self {
+            endpoint_uri,
+            admin_pod,
+            config: Arc::new(config),
+            signup_locker: Default::default(),
+            pending_signups: Default::default(),
+            provisioning_locker,
+        }
+    }
+
+    /// Handle the signup request.
+    async fn apply(self, req: Request<Body>) -> Result<Response<Body>, ApiError> {
+        if req.method() != Method::POST {
+            error!("Method not allowed on signup endpoint.");
+            return Err(ApiError::builder(StatusCode::METHOD_NOT_ALLOWED)
+                .message("Signup endpoint only supports POST method.")
+                .finish());
+        }
+
+        let owner_id = req
+            .extensions()
+            .get::<BasicRequestCredentials>()
+            .and_then(|credentials| credentials.of_agent())
+            .map(|agent| agent.webid().clone())
+            .ok_or_else(|| {
+                error!("Signup request is not authenticated.");
+                ApiError::builder(StatusCode::UNAUTHORIZED)
+                    .message("Signup requests must be authenticated.")
+                    .finish()
+            })?;
+
+        let body = Limited::new(req.into_body(), MAX_SIGNUP_REQUEST_SIZE)
+            .collect()
+            .await
+            .map_err(|e| {
+                error!("Error in reading signup request body. Error:\n {}", e);
+                ApiError::builder(StatusCode::BAD_REQUEST)
+                    .message("Error in reading signup request body.")
+                    .finish()
+            })?
+            .to_bytes();
+
+        let signup_request: SignupRequest = serde_json::from_slice(&body).map_err(|e| {
+            error!("Invalid signup request. Error:\n {}", e);
+            ApiError::builder(StatusCode::BAD_REQUEST)
+                .message(format!("Invalid signup request. {}", e))
+                .finish()
+        })?;
+
+        let pod = self
+            .signup_locker
+            .poll_with_lock(
+                self.clone().signup(signup_request.name, owner_id.clone()),
+                Some(owner_id.as_str().to_owned()),
+                LockKind::Exclusive,
+            )
+            .await
+            .unwrap_or_else(|e| {
+                error!("Error in locking signups of the agent. Error:\n {}", e);
+                Err(ApiError::builder(StatusCode::SERVICE_UNAVAILABLE).finish())
+            })?;
+
+        info!(
+            "Provisioned pod {} for {}",
+            pod.id().as_str(),
+            owner_id.as_str()
+        );
+
+        let resp = SignupResponse {
+            pod: pod.id().as_str().to_owned(),
+            owner: owner_id.as_str().to_owned(),
+        };
+
+        Ok(Response::builder()
+            .status(StatusCode::CREATED)
+            .header(header::LOCATION, pod.id().as_str())
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::to_string(&resp).expect("Must be serializable."),
+            ))
+            .expect("Must be valid."))
+    }
+
+    /// Provision the pod with given name for given agent,
+    /// if neither agent's quota, nor global pod limit is
+    /// exhausted. Signups of the agent must be serialized by
+    /// the caller.
+    async fn signup(
+        self,
+        pod_name: String,
+        owner_id: WebId,
+    ) -> Result<Arc<<RcpAdminPod<RSetup> as AdminPod>::MemberPod>, ApiError> {
+        let owners = self.admin_pod.member_template().owners();
+
+        if owners.count_owned_by(&owner_id) >= self.config.max_pods_per_agent {
+            error!("Signup quota of the agent is exhausted.");
+            return Err(ApiError::builder(StatusCode::FORBIDDEN)
+                .message("Maximum number of pods per agent are already provisioned.")
+                .finish());
+        }
+
+        let _slot = PendingSignupSlot::try_reserve(
+            self.pending_signups.clone(),
+            || owners.count(),
+            self.config.max_pods,
+        )
+        .ok_or_else(|| {
+            error!("Global pod limit is reached.");
+            ApiError::builder(StatusCode::FORBIDDEN)
+                .message("Maximum number of pods are already provisioned.")
+                .finish()
+        })?;
+
+        self.provisioning_locker
+            .poll_with_lock(
+                Self::provision(self.admin_pod.clone(), pod_name.clone(), owner_id),
+                Some(pod_name),
+                LockKind::Exclusive,
+            )
+            .await
+            .unwrap_or_else(|e| {
+                error!("Error in locking pod name. Error:\n {}", e);
+                Err(ApiError::builder(StatusCode::SERVICE_UNAVAILABLE).finish())
+            })
+    }
+
+    /// Provision the pod with given name, owned by given
+    /// agent. Owner record is rolled back, if provisioning
+    /// fails.
+    async fn provision(
+        admin_pod: Arc<RcpAdminPod<RSetup>>,
+        pod_name: String,
+        owner_id: WebId,
+    ) -> Result<Arc<<RcpAdminPod<RSetup> as AdminPod>::MemberPod>, ApiError> {
+        admin_pod
+            .check_provisionable(&pod_name)
+            .map_err(Self::map_provision_problem)?;
+
+        // Owner must be recorded before provisioning, as it
+        // is resolved while rendering the pod.
+        let owners = admin_pod.member_template().owners();
+        owners
+            .record(pod_name.clone(), &owner_id)
+            .await
+            .map_err(|e| {
+                error!("Error in recording pod owner. Error:\n {}", e);
+                ApiError::builder(StatusCode::INTERNAL_SERVER_ERROR).finish()
+            })?;
+
+        match admin_pod.provision_member_pod(&pod_name).await {
+            Ok(pod) => Ok(pod),
+            Err(problem) => {
+                if let Err(e) = owners.remove(&pod_name).await {
+                    warn!("Error in rolling back pod owner record. Error:\n {}", e);
+                }
+                Err(Self::map_provision_problem(problem))
+            }
+        }
+    }
+
+    /// Map provisioning problem to api error.
+    fn map_provision_problem(problem: Problem) -> ApiError {
+        error!("Error in provisioning pod. Error:\n {}", problem);
+        if INVALID_MEMBER_POD_KEY.is_type_of(&problem) {
+            ApiError::builder(StatusCode::BAD_REQUEST)
+                .message("Invalid pod name. Pod names must be lowercase dns labels.")
+                .finish()
+        } else if MEMBER_POD_ALREADY_PROVISIONED.is_type_of(&problem) {
+            ApiError::builder(StatusCode::CONFLICT)
+                .message("Pod with given name already exists.")
+                .finish()
+        } else {
+            ApiError::builder(StatusCode::INTERNAL_SERVER_ERROR).finish()
+        }
+    }
+}
+
+/// A slot reserved for a pending signup, under the global
+/// pod limit. It is released on drop.
+struct PendingSignupSlot {
+    pending_signups: Arc<AtomicUsize>,
+}
+
+impl PendingSignupSlot {
+    /// Try to reserve a slot, if provisioned pods along with
+    /// pending signups are within given limit.
+    ///
+    /// A signup records the owner before releasing it's slot,
+    /// and provisioned pods are counted after loading pending
+    /// signups. Hence a racing signup is never missed.
+    fn try_reserve(
+        pending_signups: Arc<AtomicUsize>,
+        count_provisioned: impl Fn() -> usize,
+        opt_max_pods: Option<usize>,
+    ) -> Option<Self> {
+        pending_signups
+            .fetch_update(
+                Ordering::AcqRel,
+                Ordering::Acquire,
+                |pending| match opt_max_pods {
+                    Some(max_pods) if count_provisioned() + pending >= max_pods => None,
+                    _ => Some(pending + 1),
+                },
+            )
+            .ok()?;
+
+        Some(Self { pending_signups })
+    }
+}
+
+impl Drop for PendingSignupSlot {
+    #[inline]
+    fn drop(&mut self) {
+        self.pending_signups.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl<RSetup: SinglePodRecipeSetup> Service<Request<Body>> for RcpSignupService<RSetup> {
+    type Response = Response<Body>;
+
+    type Error = Infallible;
+
+    type Future = BoxHttpResponseFuture<Body>;
+
+    #[inline]
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[tracing::instrument(skip_all, name = "RcpSignupService::call")]
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let this = self.clone();
+        Box::pin(async move {
+            Ok(this
+                .apply(req)
+                .await
+                .unwrap_or_else(|e| e.into_http_response()))
+        })
+    }
+}
+
+impl<RSetup: SinglePodRecipeSetup> NamespacedHttpService<Body, Body> for RcpSignupService<RSetup> {
+    #[inline]
+    fn has_in_uri_ns(&self, uri: &SolidResourceUri) -> bool {
+        uri == &self.endpoint_uri
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use claims::*;
+
+    use super::*;
+
+    #[test]
+    fn pending_signups_are_counted_against_pod_limit() {
+        let pending_signups = Arc::new(AtomicUsize::new(0));
+
+        let slot = assert_some!(PendingSignupSlot::try_reserve(
+            pending_signups.clone(),
+            || 1,
+            Some(2)
+        ));
+        assert_none!(PendingSignupSlot::try_reserve(
+            pending_signups.clone(),
+            || 1,
+            Some(2)
+        ));
+
+        drop(slot);
+        assert_eq!(pending_signups.load(Ordering::Acquire), 0);
+        assert_some!(PendingSignupSlot::try_reserve(
+            pending_signups.clone(),
+            || 1,
+            Some(2)
+        ));
+        assert_some!(PendingSignupSlot::try_reserve(
+            pending_signups,
+            || 100,
+            None
+        ));
+    }
+}
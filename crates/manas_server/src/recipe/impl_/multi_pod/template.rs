@@ -0,0 +1,802 @@
+//! I define the member pod template for multi-pod recipes.
+//!
+
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+};
+
+use dyn_problem::{type_::INTERNAL_ERROR, ProbFuture};
+use manas_http::uri::invariant::HierarchicalTrailingSlashHttpUri;
+use manas_podverse::podset::impl_::dynamic::admin_pod::impl_::template_driven::pod_template::PodTemplate;
+use manas_repo_layers::quota::tracker::QuotaUsageTracker;
+use manas_repo_opendal::object_store::backend::impl_::config::ODRBackendConfig;
+use manas_space::{resource::uri::SolidResourceUri, BoxError};
+use manas_storage::SolidStorageExt;
+use tracing::error;
+use typed_record::TypedRecord;
+use webid::WebId;
+
+use super::config::{RcpBackendConfigTemplate, RcpMemberPodsConfig};
+use crate::{
+    notification::{configure_notifications, KRcpNotificationChannels, RcpNotificationChannels},
+    podverse::static_::RcpPod,
+    quota::advertise_quota,
+    recipe::impl_::{
+        common::{config::RcpNotificationsConfig, resolve_backend},
+        single_pod::{
+            config::RcpStorageSpaceConfig, setup::SinglePodRecipeSetup, RcpStorageOptions,
+            SinglePodRecipe, SinglePodStorageSetup,
+        },
+    },
+};
+
+/// Placeholder for pod name in templates.
+pub const POD_PLACEHOLDER: &str = "{pod}";
+
+/// Substitute pod placeholders in string values of given
+/// json value with given pod name.
+fn substitute_pod_placeholder(value: &mut serde_json::Value, pod_name: &str) {
+    match value {
+        serde_json::Value::String(s) => *s = s.replace(POD_PLACEHOLDER, pod_name),
+        serde_json::Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| substitute_pod_placeholder(item, pod_name)),
+        serde_json::Value::Object(entries) => entries
+            .values_mut()
+            .for_each(|entry| substitute_pod_placeholder(entry, pod_name)),
+        _ => {}
+    }
+}
+
+impl RcpBackendConfigTemplate {
+    /// Resolve backend config of the pod with given name,
+    /// by substituting pod placeholders in the raw template.
+    pub fn resolve(&self, pod_name: &str) -> Result<ODRBackendConfig, serde_json::Error> {
+        let mut config = self.raw().clone();
+        substitute_pod_placeholder(&mut config, pod_name);
+        serde_json::from_value(config)
+    }
+}
+
+/// Maximum length of pod names.
+const MAX_POD_NAME_LEN: usize = 63;
+
+/// A template of pod root uris, with a single `{pod}`
+/// placeholder either in the host, or in the path.
+#[derive(Debug, Clone)]
+pub struct RcpPodUriTemplate {
+    /// Part of the template before the placeholder.
+    prefix: String,
+
+    /// Part of the template after the placeholder.
+    suffix: String,
+}
+
+impl RcpPodUriTemplate {
+    /// Try to parse a pod uri template from given string.
+    pub fn try_new(template: &str) -> Result<Self, String> {
+        let (prefix, suffix) = template
+            .split_once(POD_PLACEHOLDER)
+            .ok_or_else(|| format!("Pod uri template must contain {POD_PLACEHOLDER}."))?;
+
+        if suffix.contains(POD_PLACEHOLDER) {
+            return Err(format!(
+                "Pod uri template must contain exactly one {POD_PLACEHOLDER}."
+            ));
+        }
+
+        // Placeholder must occupy either an entire host
+        // label, or an entire path segment.
+        let in_authority = prefix
+            .split_once("://")
+            .map_or(false, |(_, authority)| !authority.contains('/'));
+        let is_entire_label = if in_authority {
+            (prefix.ends_with("://") || prefix.ends_with('.'))
+                && (suffix.starts_with('.') || suffix.starts_with('/') || suffix.starts_with(':'))
+        } else {
+            prefix.ends_with('/') && suffix.starts_with('/')
+        };
+
+        if !is_entire_label {
+            return Err(format!(
+                "{POD_PLACEHOLDER} must be an entire host label or path segment."
+            ));
+        }
+
+        let this = Self {
+            prefix: prefix.to_owned(),
+            suffix: suffix.to_owned(),
+        };
+
+        // Ensure template renders valid pod root uris.
+        this.render("pod")
+            .ok_or_else(|| "Pod uri template must render valid pod root uris.".to_owned())?;
+
+        Ok(this)
+    }
+
+    /// Check if given string is a valid pod name. Valid pod
+    /// names are lowercase dns labels.
+    pub fn is_valid_pod_name(name: &str) -> bool {
+        !name.is_empty()
+            && name.len() <= MAX_POD_NAME_LEN
+            && !name.starts_with('-')
+            && !name.ends_with('-')
+            && name
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+    }
+
+    /// Render root uri of the pod with given name.
+    pub fn render(&self, pod_name: &str) -> Option<HierarchicalTrailingSlashHttpUri> {
+        format!("{}{}{}", self.prefix, pod_name, self.suffix)
+            .parse()
+            .ok()
+    }
+
+    /// Resolve name of the pod, in whose namespace given uri
+    /// is in.
+    pub fn resolve_pod_name<'u>(&self, uri: &'u str) -> Option<&'u str> {
+        let rest = uri.strip_prefix(self.prefix.as_str())?;
+        let name_len = rest
+            .bytes()
+            .take_while(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || *b == b'-')
+            .count();
+        let (name, rest) = rest.split_at(name_len);
+
+        // Pod root uri may be targeted without it's trailing
+        // slash.
+        let in_ns = rest.starts_with(self.suffix.as_str()) || format!("{rest}/") == self.suffix;
+
+        (in_ns && Self::is_valid_pod_name(name)).then_some(name)
+    }
+
+    /// Check if given uri is in namespace of this template.
+    /// Uri is in the namespace, only if it is in the namespace
+    /// of some pod rendered by this template.
+    #[inline]
+    pub fn has_in_ns(&self, uri: &str) -> bool {
+        self.resolve_pod_name(uri).is_some()
+    }
+}
+
+/// A registry of owners of pods, persisted as a json map.
+#[derive(Debug)]
+pub struct RcpPodOwnersRegistry {
+    /// Path of the persisted map.
+    path: Option<PathBuf>,
+
+    /// Owners of pods.
+    owners: RwLock<HashMap<String, String>>,
+}
+
+impl RcpPodOwnersRegistry {
+    /// Load the registry from given path.
+    pub async fn load(path: Option<PathBuf>) -> io::Result<Self> {
+        let mut owners = HashMap::new();
+        if let Some(path) = path.as_ref() {
+            match tokio::fs::read(path).await {
+                Ok(content) => {
+                    owners = serde_json::from_slice(&content)
+                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                }
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(Self {
+            path,
+            owners: RwLock::new(owners),
+        })
+    }
+
+    /// Get owner of the pod with given name.
+    pub fn get(&self, pod_name: &str) -> Option<WebId> {
+        self.owners
+            .read()
+            .expect("Lock must not be poisoned.")
+            .get(pod_name)
+            .and_then(|owner| owner.parse().ok())
+    }
+
+    /// Record owner of the pod with given name.
+    pub async fn record(&self, pod_name: String, owner: &WebId) -> io::Result<()> {
+        let content = {
+            let mut owners = self.owners.write().expect("Lock must not be poisoned.");
+            owners.insert(pod_name, owner.as_str().to_owned());
+            serde_json::to_vec_pretty(&*owners).expect("Must be serializable.")
+        };
+
+        if let Some(path) = self.path.as_ref() {
+            Self::persist(path, &content).await?;
+        }
+        Ok(())
+    }
+
+    /// Remove owner record of the pod with given name.
+    pub async fn remove(&self, pod_name: &str) -> io::Result<()> {
+        let content = {
+            let mut owners = self.owners.write().expect("Lock must not be poisoned.");
+            if owners.remove(pod_name).is_none() {
+                return Ok(());
+            }
+            serde_json::to_vec_pretty(&*owners).expect("Must be serializable.")
+        };
+
+        if let Some(path) = self.path.as_ref() {
+            Self::persist(path, &content).await?;
+        }
+        Ok(())
+    }
+
+    /// Count the pods that have recorded owners.
+    pub fn count(&self) -> usize {
+        self.owners
+            .read()
+            .expect("Lock must not be poisoned.")
+            .len()
+    }
+
+    /// Count the pods owned by given agent.
+    pub fn count_owned_by(&self, owner: &WebId) -> usize {
+        self.owners
+            .read()
+            .expect("Lock must not be poisoned.")
+            .values()
+            .filter(|o| o.as_str() == owner.as_str())
+            .count()
+    }
+
+    /// Atomically persist given content to given path.
+    async fn persist(path: &Path, content: &[u8]) -> io::Result<()> {
+        let tmp_path = path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, content).await?;
+        tokio::fs::rename(&tmp_path, path).await
+    }
+}
+
+/// State of a member pod, that is shared by it's
+/// renditions, so that re-rendering an evicted pod neither
+/// rescans it's quota usage, nor recreates it's notification
+/// channels.
+#[derive(Debug, Clone)]
+struct RcpMemberPodState {
+    /// Quota usage tracker.
+    quota_tracker: Arc<QuotaUsageTracker>,
+
+    /// Notification channels. Storage renditions publish to
+    /// their event bus.
+    channels: RcpNotificationChannels,
+}
+
+/// Slot of the state of a member pod. It is empty until the
+/// first successful rendition of the pod.
+type RcpMemberPodStateSlot = Arc<futures::lock::Mutex<Option<RcpMemberPodState>>>;
+
+/// An implementation of [`PodTemplate`] for member pods of
+/// multi-pod recipes.
+pub struct RcpMemberPodTemplate<RSetup: SinglePodRecipeSetup> {
+    /// Pod uri template.
+    uri_template: RcpPodUriTemplate,
+
+    /// Backend config template.
+    backend_template: RcpBackendConfigTemplate,
+
+    /// Root uri of the admin pod. No member pod can overlap
+    /// with it's namespace.
+    admin_root_uri: HierarchicalTrailingSlashHttpUri,
+
+    /// Default owner of the member pods.
+    default_owner_id: WebId,
+
+    /// Registry of pod owners.
+    owners: Arc<RcpPodOwnersRegistry>,
+
+    /// Storage options of the member pods. Resource locker,
+    /// and audit emitter are shared across the pods.
+    storage_options: RcpStorageOptions,
+
+    /// Whether quota is enforced on member pods.
+    quota_enabled: bool,
+
+    /// Policy decision point.
+    pdp: Arc<RSetup::PDP>,
+
+    /// Notifications config of the member pods.
+    notifications_config: RcpNotificationsConfig,
+
+    /// State slots of rendered member pods, keyed by pod
+    /// name.
+    pod_states: Mutex<HashMap<String, RcpMemberPodStateSlot>>,
+}
+
+impl<RSetup: SinglePodRecipeSetup> Debug for RcpMemberPodTemplate<RSetup> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RcpMemberPodTemplate")
+            .field("uri_template", &self.uri_template)
+            .field("admin_root_uri", &self.admin_root_uri)
+            .finish()
+    }
+}
+
+impl<RSetup: SinglePodRecipeSetup> RcpMemberPodTemplate<RSetup> {
+    /// Try to create a new [`RcpMemberPodTemplate`].
+    ///
+    /// Member pods are rendered with given storage options,
+    /// each with it's own event bus. If quota is enabled,
+    /// usage of each pod is rebuilt on it's first rendition.
+    pub(crate) fn try_new(
+        config: RcpMemberPodsConfig,
+        admin_root_uri: HierarchicalTrailingSlashHttpUri,
+        default_owner_id: WebId,
+        owners: Arc<RcpPodOwnersRegistry>,
+        storage_options: RcpStorageOptions,
+        quota_enabled: bool,
+        notifications_config: RcpNotificationsConfig,
+    ) -> Result<Self, String> {
+        let uri_template = RcpPodUriTemplate::try_new(&config.uri_template)?;
+
+        // Admin pod must not shadow member pods.
+        if uri_template.prefix.starts_with(admin_root_uri.as_str()) {
+            return Err("Member pods must not be in the namespace of the admin pod.".to_owned());
+        }
+
+        Ok(Self {
+            uri_template,
+            backend_template: config.backend,
+            admin_root_uri,
+            default_owner_id,
+            owners,
+            storage_options,
+            quota_enabled,
+            pdp: Default::default(),
+            notifications_config,
+            pod_states: Default::default(),
+        })
+    }
+
+    /// Get the pod uri template.
+    #[inline]
+    pub fn uri_template(&self) -> &RcpPodUriTemplate {
+        &self.uri_template
+    }
+
+    /// Get the registry of pod owners.
+    #[inline]
+    pub fn owners(&self) -> &Arc<RcpPodOwnersRegistry> {
+        &self.owners
+    }
+
+    /// Resolve the state slot of the pod with given name.
+    fn resolve_pod_state_slot(&self, pod_name: &str) -> RcpMemberPodStateSlot {
+        self.pod_states
+            .lock()
+            .expect("Lock must not be poisoned.")
+            .entry(pod_name.to_owned())
+            .or_default()
+            .clone()
+    }
+
+    /// Resolve backend config of the pod with given name.
+    pub fn resolve_backend_config(
+        &self,
+        pod_name: &str,
+    ) -> Result<ODRBackendConfig, serde_json::Error> {
+        self.backend_template.resolve(pod_name)
+    }
+}
+
+impl<RSetup: SinglePodRecipeSetup> PodTemplate for RcpMemberPodTemplate<RSetup> {
+    type RenderedPod = RcpPod<SinglePodStorageSetup<RSetup>>;
+
+    type PodKey = String;
+
+    #[inline]
+    fn has_in_uri_ns(&self, uri: &SolidResourceUri) -> bool {
+        self.resolve_target_pod_key(uri).is_some()
+    }
+
+    #[inline]
+    fn resolve_target_pod_key(&self, req_target: &SolidResourceUri) -> Option<Self::PodKey> {
+        self.uri_template
+            .resolve_pod_name(req_target.as_str())
+            .and_then(|name| self.parse_pod_key(name))
+    }
+
+    fn parse_pod_key(&self, key_str: &str) -> Option<Self::PodKey> {
+        if !RcpPodUriTemplate::is_valid_pod_name(key_str) {
+            return None;
+        }
+
+        // Reject pods overlapping with the admin pod.
+        let root_uri = self.uri_template.render(key_str)?;
+        if root_uri.as_str().starts_with(self.admin_root_uri.as_str())
+            || self.admin_root_uri.as_str().starts_with(root_uri.as_str())
+        {
+            return None;
+        }
+
+        Some(key_str.to_owned())
+    }
+
+    fn render(&self, key: &Self::PodKey) -> ProbFuture<'static, Self::RenderedPod> {
+        let root_uri = self
+            .uri_template
+            .render(key)
+            .expect("Key must render a valid uri.");
+        let owner_id = self
+            .owners
+            .get(key)
+            .unwrap_or_else(|| self.default_owner_id.clone());
+        let backend_config = self.resolve_backend_config(key);
+        let storage_options = RcpStorageOptions {
+            // Each pod publishes to it's own bus.
+            event_bus: Default::default(),
+            ..self.storage_options.clone()
+        };
+        let quota_enabled = self.quota_enabled;
+        let pdp = self.pdp.clone();
+        let mut notifications_config = self.notifications_config.clone();
+        notifications_config.webhook_state_dir = notifications_config
+            .webhook_state_dir
+            .map(|dir| dir.join(key));
+        let state_slot = self.resolve_pod_state_slot(key);
+
+        Box::pin(async move {
+            let backend = backend_config
+                .map_err(BoxError::from)
+                .and_then(resolve_backend::<RSetup::BackendBuilder, RSetup::Backend>)
+                .map_err(|e| {
+                    error!("Error in resolving member pod backend. Error:\n {}", e);
+                    INTERNAL_ERROR
+                        .new_problem_builder()
+                        .message("Error in resolving member pod backend.")
+                        .source_in_a_box(e)
+                        .finish()
+                })?;
+
+            // Serialize renditions of the pod, so that it's
+            // state is resolved only once.
+            let mut opt_state = state_slot.lock().await;
+
+            let storage_options = match opt_state.as_ref() {
+                Some(state) => RcpStorageOptions {
+                    opt_quota_tracker: Some(state.quota_tracker.clone()),
+                    event_bus: state.channels.event_bus.clone(),
+                    ..storage_options
+                },
+                None => storage_options,
+            };
+
+            let mut storage = SinglePodRecipe::<RSetup>::resolve_storage(
+                RcpStorageSpaceConfig { root_uri, owner_id },
+                backend,
+                pdp,
+                RSetup::INITIAL_ROOT_ACR_TEMPLATE,
+                storage_options,
+            );
+
+            let state = match opt_state.as_ref() {
+                Some(state) => state.clone(),
+                None => {
+                    // Enforce quota with accurate usage, if
+                    // configured.
+                    if quota_enabled {
+                        storage.rebuild_quota_usage().await.map_err(|e| {
+                            error!("Error in rebuilding member pod quota usage. Error:\n {}", e);
+                            e
+                        })?;
+                    }
+
+                    // Channels outlive pod renditions, so that
+                    // subscriptions survive eviction of the pod
+                    // from the member pod cache.
+                    let channels = RcpNotificationChannels::try_new(
+                        storage.space().as_ref(),
+                        storage.event_bus().clone(),
+                        notifications_config,
+                    )
+                    .await
+                    .map_err(|e| {
+                        error!(
+                            "Error in resolving member pod notification channels. Error:\n {}",
+                            e
+                        );
+                        INTERNAL_ERROR
+                            .new_problem_builder()
+                            .message("Error in resolving member pod notification channels.")
+                            .source(e)
+                            .finish()
+                    })?;
+
+                    opt_state
+                        .insert(RcpMemberPodState {
+                            quota_tracker: storage.quota_tracker().clone(),
+                            channels,
+                        })
+                        .clone()
+                }
+            };
+            drop(opt_state);
+
+            // Advertise usage, if configured.
+            if quota_enabled {
+                advertise_quota(&mut storage);
+            }
+
+            // Advertise and serve notification channels.
+            configure_notifications(&mut storage);
+            storage
+                .extensions
+                .insert_rec_item::<KRcpNotificationChannels>(state.channels);
+
+            Ok(RcpPod {
+                storage: Arc::new(storage),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use claims::*;
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case::host_label("https://{pod}.example.org/")]
+    #[case::path_segment("https://example.org/pods/{pod}/")]
+    #[case::nested_path_segment("https://example.org/{pod}/storage/")]
+    fn valid_template_will_be_accepted(#[case] template: &str) {
+        assert_ok!(RcpPodUriTemplate::try_new(template));
+    }
+
+    #[rstest]
+    #[case::no_placeholder("https://example.org/pods/")]
+    #[case::multiple_placeholders("https://{pod}.example.org/{pod}/")]
+    #[case::partial_host_label("https://pod-{pod}.example.org/")]
+    #[case::partial_path_segment("https://example.org/pods/{pod}.d/")]
+    #[case::placeholder_in_port("https://example.org:{pod}/")]
+    #[case::no_trailing_slash("https://example.org/pods/{pod}")]
+    fn invalid_template_will_be_rejected(#[case] template: &str) {
+        assert_err!(RcpPodUriTemplate::try_new(template));
+    }
+
+    #[rstest]
+    #[case::host_root(
+        "https://{pod}.example.org/",
+        "https://alice.example.org/",
+        Some("alice")
+    )]
+    #[case::host_descendant(
+        "https://{pod}.example.org/",
+        "https://alice.example.org/a/b.ttl",
+        Some("alice")
+    )]
+    #[case::host_other_domain("https://{pod}.example.org/", "https://alice.example.com/", None)]
+    #[case::path_root(
+        "https://example.org/pods/{pod}/",
+        "https://example.org/pods/bob/",
+        Some("bob")
+    )]
+    #[case::path_root_sans_slash(
+        "https://example.org/pods/{pod}/",
+        "https://example.org/pods/bob",
+        Some("bob")
+    )]
+    #[case::path_descendant(
+        "https://example.org/pods/{pod}/",
+        "https://example.org/pods/bob-1/c/",
+        Some("bob-1")
+    )]
+    #[case::path_name_prefix(
+        "https://example.org/pods/{pod}/",
+        "https://example.org/pods/bob.ttl",
+        None
+    )]
+    #[case::path_container("https://example.org/pods/{pod}/", "https://example.org/pods/", None)]
+    #[case::path_invalid_name(
+        "https://example.org/pods/{pod}/",
+        "https://example.org/pods/-bob/",
+        None
+    )]
+    #[case::path_uppercase_name(
+        "https://example.org/pods/{pod}/",
+        "https://example.org/pods/Bob/",
+        None
+    )]
+    fn pod_name_resolves_correctly(
+        #[case] template: &str,
+        #[case] uri: &str,
+        #[case] expected_name: Option<&str>,
+    ) {
+        let template = assert_ok!(RcpPodUriTemplate::try_new(template));
+        assert_eq!(template.resolve_pod_name(uri), expected_name);
+    }
+
+    #[rstest]
+    #[case("alice", true)]
+    #[case("a-1", true)]
+    #[case("", false)]
+    #[case("-a", false)]
+    #[case("a-", false)]
+    #[case("Alice", false)]
+    #[case("a.b", false)]
+    #[case(&"a".repeat(64), false)]
+    fn pod_name_validity_is_checked_correctly(#[case] name: &str, #[case] expected: bool) {
+        assert_eq!(RcpPodUriTemplate::is_valid_pod_name(name), expected);
+    }
+
+    #[tokio::test]
+    async fn owner_records_persist_and_roll_back() {
+        let dir = tempfile::tempdir().expect("Must create temp dir.");
+        let path = dir.path().join("owners.json");
+        let alice: WebId = "https://alice.example.org/#me".parse().unwrap();
+        let bob: WebId = "https://bob.example.org/#me".parse().unwrap();
+
+        let owners = assert_ok!(RcpPodOwnersRegistry::load(Some(path.clone())).await);
+        assert_ok!(owners.record("a1".to_owned(), &alice).await);
+        assert_ok!(owners.record("a2".to_owned(), &alice).await);
+        assert_ok!(owners.record("b1".to_owned(), &bob).await);
+        assert_eq!(owners.count_owned_by(&alice), 2);
+
+        assert_ok!(owners.remove("a2").await);
+        assert_ok!(owners.remove("unknown").await);
+
+        let reloaded = assert_ok!(RcpPodOwnersRegistry::load(Some(path)).await);
+        assert_eq!(reloaded.count_owned_by(&alice), 1);
+        assert_eq!(reloaded.count_owned_by(&bob), 1);
+        assert_none!(reloaded.get("a2"));
+        assert_some_eq!(reloaded.get("a1"), alice);
+    }
+
+    #[rstest]
+    #[case::host_pod("https://{pod}.example.org/", "https://alice.example.org/a", true)]
+    #[case::host_other_domain("https://{pod}.example.org/", "https://example.com/", false)]
+    #[case::host_other_suffix("https://{pod}.example.org/", "https://alice.example.com/", false)]
+    #[case::path_pod(
+        "https://example.org/pods/{pod}/",
+        "https://example.org/pods/bob/",
+        true
+    )]
+    #[case::path_container("https://example.org/pods/{pod}/", "https://example.org/pods/", false)]
+    fn namespace_is_checked_correctly(
+        #[case] template: &str,
+        #[case] uri: &str,
+        #[case] expected: bool,
+    ) {
+        let template = assert_ok!(RcpPodUriTemplate::try_new(template));
+        assert_eq!(template.has_in_ns(uri), expected);
+    }
+
+    #[cfg(feature = "backend-s3")]
+    #[test]
+    fn backend_config_is_resolved_with_inline_secrets() {
+        let template: RcpBackendConfigTemplate =
+            assert_ok!(serde_json::from_value(serde_json::json!({
+                "type": "s3",
+                "bucket": "pods",
+                "root": "/{pod}/",
+                "access_key_id": "key-id",
+                "secret_access_key": "s3cret",
+            })));
+
+        // Template doesn't leak inline secrets.
+        let serialized = assert_ok!(serde_json::to_string(&template));
+        assert!(!serialized.contains("s3cret"), "{serialized}");
+        assert!(!format!("{:?}", template).contains("s3cret"));
+
+        let config = assert_ok!(template.resolve("alice"));
+        let ODRBackendConfig::S3(config) = config else {
+            panic!("Must be an s3 config.");
+        };
+        assert_some_eq!(config.root.as_deref(), "/alice/");
+        assert_ok_eq!(
+            assert_some!(config.secret_access_key.as_ref()).resolve(),
+            "s3cret".to_owned()
+        );
+    }
+
+    #[test]
+    fn invalid_backend_config_template_is_rejected() {
+        assert_err!(serde_json::from_value::<RcpBackendConfigTemplate>(
+            serde_json::json!({ "type": "unknown" })
+        ));
+    }
+
+    #[cfg(all(feature = "backend-fs", feature = "pdp-wac"))]
+    #[tokio::test]
+    async fn pods_are_rendered_with_shared_storage_options() {
+        use manas_repo_layers::{
+            quota::tracker::QuotaLimits, versioning::config::VersioningConfig,
+        };
+        use manas_storage::service::method::get::base::KMementoEnabled;
+
+        use crate::recipe::impl_::single_pod::setup::impl_::FsWacRecipeSetup;
+
+        let dir = tempfile::tempdir().expect("Must create temp dir.");
+        let quota_limits = QuotaLimits {
+            max_bytes: Some(1024),
+            max_resources: None,
+        };
+        let template = assert_ok!(RcpMemberPodTemplate::<FsWacRecipeSetup>::try_new(
+            RcpMemberPodsConfig {
+                uri_template: "http://example.org/pods/{pod}/".to_owned(),
+                backend: serde_json::from_value(serde_json::json!({
+                    "type": "fs",
+                    "root": format!("{}/{{pod}}/", dir.path().display()),
+                }))
+                .unwrap(),
+            },
+            "http://example.org/admin/".parse().unwrap(),
+            "http://example.org/admin/owner#me".parse().unwrap(),
+            Arc::new(RcpPodOwnersRegistry::load(None).await.unwrap()),
+            RcpStorageOptions {
+                quota_limits: quota_limits.clone(),
+                versioning_config: VersioningConfig {
+                    enabled: true,
+                    retention: Default::default(),
+                },
+                ..Default::default()
+            },
+            true,
+            Default::default(),
+        ));
+
+        let alice = assert_ok!(template.render(&"alice".to_owned()).await);
+        let bob = assert_ok!(template.render(&"bob".to_owned()).await);
+
+        for pod in [&alice, &bob] {
+            assert_eq!(pod.storage.quota_tracker().limits(), &quota_limits);
+            assert_some_eq!(pod.storage.extensions.get_rv::<KMementoEnabled>(), &true);
+            assert_some!(pod.storage.extensions.get_rv::<KRcpNotificationChannels>());
+        }
+
+        // Each pod has its own event bus.
+        let bob_count = bob.storage.event_bus().receiver_count();
+        let _events = alice.storage.event_bus().subscribe();
+        assert_eq!(bob.storage.event_bus().receiver_count(), bob_count);
+    }
+    #[tokio::test]
+    async fn rerendered_pods_share_state_with_prior_renditions() {
+        use crate::recipe::impl_::single_pod::setup::impl_::FsWacRecipeSetup;
+
+        let dir = tempfile::tempdir().expect("Must create temp dir.");
+        let template = assert_ok!(RcpMemberPodTemplate::<FsWacRecipeSetup>::try_new(
+            RcpMemberPodsConfig {
+                uri_template: "http://example.org/pods/{pod}/".to_owned(),
+                backend: serde_json::from_value(serde_json::json!({
+                    "type": "fs",
+                    "root": format!("{}/{{pod}}/", dir.path().display()),
+                }))
+                .unwrap(),
+            },
+            "http://example.org/admin/".parse().unwrap(),
+            "http://example.org/admin/owner#me".parse().unwrap(),
+            Arc::new(RcpPodOwnersRegistry::load(None).await.unwrap()),
+            Default::default(),
+            true,
+            Default::default(),
+        ));
+
+        let first = assert_ok!(template.render(&"alice".to_owned()).await);
+        let second = assert_ok!(template.render(&"alice".to_owned()).await);
+
+        assert!(Arc::ptr_eq(
+            first.storage.quota_tracker(),
+            second.storage.quota_tracker()
+        ));
+
+        // Renditions publish to the same bus, to which the
+        // channels are subscribed.
+        let count = second.storage.event_bus().receiver_count();
+        let _events = first.storage.event_bus().subscribe();
+        assert_eq!(second.storage.event_bus().receiver_count(), count + 1);
+    }
+}
@@ -1,21 +1,28 @@
 //! I provide utilities to construct single pod recipes.
 //!
 
-// TODO Allow custom locker for distribution.
-
-use std::{borrow::Cow, marker::PhantomData, sync::Arc};
+use std::{borrow::Cow, marker::PhantomData, num::NonZeroUsize, sync::Arc, time::Duration};
 
 use futures::future::{BoxFuture, TryFutureExt};
 use http::uri::Scheme;
 use http_cache_reqwest::{Cache, CacheMode, HttpCache, MokaManager};
+use manas_access_control::audit::AuditEmitter;
 use manas_http::service::impl_::UriReconstructionParams;
 use manas_repo::RepoExt;
-use manas_repo_layers::dconneging::conneg_layer::impl_::binary_rdf_doc_converting::BinaryRdfDocContentNegotiationConfig;
+use manas_repo_layers::{
+    dconneging::conneg_layer::impl_::binary_rdf_doc_converting::{
+        BinaryRdfDocContentNegotiationConfig, ConvertedRepCache,
+    },
+    eventing::event::ResourceChangeEventBus,
+    quota::tracker::{QuotaLimits, QuotaUsageTracker},
+    versioning::config::VersioningConfig,
+};
 use manas_repo_opendal::config::ODRConfig;
 use manas_space::BoxError;
-use manas_storage::service::impl_::{KPreferredReqTargetQueryParamMode, ReqTargetQueryParamMode};
-use name_locker::impl_::InmemNameLocker;
-use opendal::Builder as _;
+use manas_storage::service::{
+    impl_::{KPreferredReqTargetQueryParamMode, ReqTargetQueryParamMode},
+    method::get::base::KMaxContainerPageSize,
+};
 use rdf_dynsyn::{
     parser::config::{
         jsonld::{
@@ -35,12 +42,18 @@ use self::{
     config::{RcpConfig, RcpStorageSpaceConfig},
     setup::SinglePodRecipeSetup,
 };
-use super::common::{resolve_authenticating_svc_maker, serve_recipe};
+use super::common::{resolve_authenticating_svc_maker, resolve_backend, serve_recipe};
 use crate::{
     dtbr::{adapt_dconneg_layer_config, DatabrowserContext, RcpDatabrowserAdaptedRdfSourceCNL},
+    locker::RcpResourceLocker,
+    notification::{configure_notifications, RcpNotificationsOverridenStaticPodSetService},
     pep::{resolve_initial_root_acr_rep_factory, InitialRootAcrTemplateContext, RcpSimplePEP},
-    podverse::static_::{RcpPod, RcpStaticPodSetService},
-    recipe::Recipe,
+    podverse::static_::RcpPod,
+    quota::configure_quota,
+    recipe::{
+        impl_::common::config::{RcpAuditConfig, RcpQuotaConfig, RcpVersioningConfig},
+        Recipe,
+    },
     space::RcpStorageSpace,
     storage::{RcpStorage, RcpStorageSetup},
     CW,
@@ -58,7 +71,7 @@ pub struct SinglePodStorageSetup<RSetup> {
 impl<RSetup: SinglePodRecipeSetup> RcpStorageSetup for SinglePodStorageSetup<RSetup> {
     type Backend = RSetup::Backend;
 
-    type ResourceLocker = InmemNameLocker<String>;
+    type ResourceLocker = RcpResourceLocker;
 
     type CNL = RcpDatabrowserAdaptedRdfSourceCNL<RSetup::Backend>;
 
@@ -79,7 +92,44 @@ impl<RSetup: SinglePodRecipeSetup> Default for SinglePodRecipe<RSetup> {
 }
 
 /// Type of storage for [`SinglePodRecipe`].
-type SinglePodStorage<RSetup> = RcpStorage<SinglePodStorageSetup<RSetup>>;
+pub(crate) type SinglePodStorage<RSetup> = RcpStorage<SinglePodStorageSetup<RSetup>>;
+
+/// Optional params to resolve a storage of
+/// [`SinglePodRecipe`] with. Defaults to a storage without
+/// databrowser, container paging, quota, versioning and
+/// audit, and with an in memory resource locker.
+///
+/// Clones share the resource locker, audit emitter, quota
+/// tracker, and the event bus.
+#[derive(Clone, Default)]
+pub(crate) struct RcpStorageOptions {
+    /// Databrowser context, if databrowser is enabled.
+    pub opt_databrowser_context: Option<DatabrowserContext>,
+
+    /// Max number of contained resources per page of
+    /// container representations, if they are paged.
+    pub max_container_page_size: Option<NonZeroUsize>,
+
+    /// Resource locker.
+    pub resource_locker: RcpResourceLocker,
+
+    /// Quota limits.
+    pub quota_limits: QuotaLimits,
+
+    /// Quota usage tracker of the storage, if it is to be
+    /// shared with prior renditions of the storage. If not
+    /// set, a new tracker with quota limits is used.
+    pub opt_quota_tracker: Option<Arc<QuotaUsageTracker>>,
+
+    /// Versioning config.
+    pub versioning_config: VersioningConfig,
+
+    /// Audit emitter, if auditing is enabled.
+    pub opt_audit_emitter: Option<Arc<AuditEmitter>>,
+
+    /// Resource change event bus of the storage.
+    pub event_bus: ResourceChangeEventBus<RcpStorageSpace>,
+}
 
 impl<RSetup: SinglePodRecipeSetup> SinglePodRecipe<RSetup> {
     fn resolve_dynsyn_factory_set() -> DynSynFactorySet {
@@ -116,14 +166,26 @@ impl<RSetup: SinglePodRecipeSetup> SinglePodRecipe<RSetup> {
         )
     }
 
-    async fn resolve_initialized_pod(
+    /// Resolve the storage with given params. Returned
+    /// storage is not initialized.
+    pub(crate) fn resolve_storage(
         space_config: RcpStorageSpaceConfig,
         backend: RSetup::Backend,
-        opt_databrowser_context: Option<DatabrowserContext>,
         pdp: Arc<RSetup::PDP>,
         initial_root_acr_template_str: &'static str,
-    ) -> Result<RcpPod<SinglePodStorageSetup<RSetup>>, BoxError> {
-        // Box::pin(async move {
+        options: RcpStorageOptions,
+    ) -> SinglePodStorage<RSetup> {
+        let RcpStorageOptions {
+            opt_databrowser_context,
+            max_container_page_size,
+            resource_locker,
+            quota_limits,
+            opt_quota_tracker,
+            versioning_config,
+            opt_audit_emitter,
+            event_bus,
+        } = options;
+
         let st_descr_uri = format!("{}_/description.ttl", space_config.root_uri.as_str())
             .as_str()
             .parse()
@@ -149,10 +211,18 @@ impl<RSetup: SinglePodRecipeSetup> SinglePodRecipe<RSetup> {
             adapt_dconneg_layer_config(
                 Arc::new(BinaryRdfDocContentNegotiationConfig {
                     dynsyn_factories: dynsyn_factories.as_ref().clone(),
+                    // Cache up to 64 MiB of converted reps,
+                    // and up to 1M quads of parsed datasets.
+                    converted_rep_cache: Some(ConvertedRepCache::new(
+                        64 * 1024 * 1024,
+                        Some(1024 * 1024),
+                        Duration::from_secs(10 * 60),
+                    )),
                 }),
                 opt_databrowser_context,
             ),
             pdp,
+            RSetup::mode_resolver(),
             resolve_initial_root_acr_rep_factory(
                 initial_root_acr_template_str,
                 &InitialRootAcrTemplateContext {
@@ -160,7 +230,13 @@ impl<RSetup: SinglePodRecipeSetup> SinglePodRecipe<RSetup> {
                     owner_id: space_config.owner_id,
                 },
             ),
-            Default::default(),
+            resource_locker,
+            opt_quota_tracker.unwrap_or_else(|| {
+                Arc::new(QuotaUsageTracker::new(quota_limits, Default::default()))
+            }),
+            versioning_config,
+            opt_audit_emitter,
+            event_bus,
         );
 
         // To let databrowser interpret redirect uris with
@@ -173,6 +249,50 @@ impl<RSetup: SinglePodRecipeSetup> SinglePodRecipe<RSetup> {
                 );
         }
 
+        // Page container representations, if configured.
+        if let Some(max_container_page_size) = max_container_page_size {
+            storage
+                .extensions
+                .insert_rec_item::<KMaxContainerPageSize>(max_container_page_size);
+        }
+
+        storage
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn resolve_initialized_pod(
+        space_config: RcpStorageSpaceConfig,
+        backend: RSetup::Backend,
+        opt_databrowser_context: Option<DatabrowserContext>,
+        pdp: Arc<RSetup::PDP>,
+        initial_root_acr_template_str: &'static str,
+        resource_locker: RcpResourceLocker,
+        max_container_page_size: Option<NonZeroUsize>,
+        opt_quota_config: Option<RcpQuotaConfig>,
+        opt_versioning_config: Option<RcpVersioningConfig>,
+        opt_audit_config: Option<RcpAuditConfig>,
+    ) -> Result<RcpPod<SinglePodStorageSetup<RSetup>>, BoxError> {
+        let mut storage = Self::resolve_storage(
+            space_config,
+            backend,
+            pdp,
+            initial_root_acr_template_str,
+            RcpStorageOptions {
+                opt_databrowser_context,
+                max_container_page_size,
+                resource_locker,
+                quota_limits: opt_quota_config.clone().map(Into::into).unwrap_or_default(),
+                opt_quota_tracker: None,
+                versioning_config: opt_versioning_config.map(Into::into).unwrap_or_default(),
+                opt_audit_emitter: opt_audit_config
+                    .map(|config| Arc::new(config.resolve_emitter())),
+                event_bus: Default::default(),
+            },
+        );
+
+        // Advertise notification channels.
+        configure_notifications(&mut storage);
+
         // Initialize the repo.
         storage
             .repo
@@ -180,10 +300,17 @@ impl<RSetup: SinglePodRecipeSetup> SinglePodRecipe<RSetup> {
             .inspect_err(|e| error!("Error in initializing the repo. Error:\n {}", e))
             .await?;
 
+        // Enforce quota with accurate usage, and advertise
+        // usage, if configured.
+        if opt_quota_config.is_some() {
+            configure_quota(&mut storage)
+                .inspect_err(|e| error!("Error in configuring quota. Error:\n {}", e))
+                .await?;
+        }
+
         Ok(RcpPod {
             storage: Arc::new(storage),
         })
-        // })
     }
 }
 
@@ -205,9 +332,9 @@ impl<RSetup: SinglePodRecipeSetup> Recipe for SinglePodRecipe<RSetup> {
     fn serve(&self, config: Self::Config) -> BoxFuture<'static, Result<(), BoxError>> {
         Box::pin(async move {
             let space_config = config.storage.space.clone();
-            let backend = RSetup::Backend::try_from(RSetup::BackendBuilder::from_map(
+            let backend = resolve_backend::<RSetup::BackendBuilder, RSetup::Backend>(
                 config.storage.repo.backend,
-            ))
+            )
             .map_err(|e| {
                 error!("Error in resolving backend. Error: {}", e);
                 e
@@ -223,13 +350,21 @@ impl<RSetup: SinglePodRecipeSetup> Recipe for SinglePodRecipe<RSetup> {
                     .then_some(DatabrowserContext::new_from_unpkg()),
                 Default::default(),
                 RSetup::INITIAL_ROOT_ACR_TEMPLATE,
+                RcpResourceLocker::try_new(&config.storage.locker)?,
+                config.storage.repo.max_container_page_size,
+                config.storage.repo.quota,
+                config.storage.repo.versioning,
+                config.storage.repo.audit,
             )
             .await?;
 
-            let podset_svc = CW::<RcpStaticPodSetService<_>>::new_for_static(
-                vec![Arc::new(pod)],
-                config.dev_mode,
-            );
+            let podset_svc =
+                CW::<RcpNotificationsOverridenStaticPodSetService<_>>::try_new_for_static(
+                    Arc::new(pod),
+                    config.dev_mode,
+                    config.notifications,
+                )
+                .await?;
 
             let uri_reconstruction_params = UriReconstructionParams {
                 default_scheme: if config.server.tls.is_some() {
@@ -240,7 +375,23 @@ impl<RSetup: SinglePodRecipeSetup> Recipe for SinglePodRecipe<RSetup> {
                 trusted_proxy_headers: config.server.trusted_proxy_headers.clone(),
             };
 
-            let svc_maker = resolve_authenticating_svc_maker(podset_svc, uri_reconstruction_params);
+            let vc_verifier = config
+                .server
+                .vc_presentation
+                .resolve_verifier()
+                .map_err(|e| {
+                    error!("Error in resolving presentation verifier. Error: {}", e);
+                    e
+                })?;
+
+            let svc_maker = resolve_authenticating_svc_maker(
+                podset_svc,
+                uri_reconstruction_params,
+                &config.server.cors.clone().into(),
+                vc_verifier,
+                &config.server.dpop,
+                &config.server.httpsig,
+            );
 
             tracing::info!("Serving at {}", config.server.addr);
             tracing::info!(
@@ -1,13 +1,17 @@
 //! I define traits and implementations for setup of [`SinglePodRecipe``](super::SinglePodRecipe`).
 //!
 
-use std::{collections::HashSet, fmt::Debug};
+use std::{fmt::Debug, sync::Arc};
 
-use manas_access_control::model::pdp::PolicyDecisionPoint;
-use manas_repo_opendal::object_store::backend::BuildableODRObjectStoreBackend;
-use rdf_utils::model::triple::ArcTriple;
+use manas_access_control::model::pep::{
+    impl_::solid_compat::DEFAULT_LEAST_PRIVILEGE_MAP, mode_resolver::AccessModeResolver,
+};
+use manas_repo_opendal::object_store::backend::{
+    impl_::config::{BackendConfigError, ODRBackendConfig},
+    BuildableODRObjectStoreBackend,
+};
 
-use crate::space::RcpStorageSpace;
+use crate::pep::RcpPDP;
 
 pub mod impl_;
 
@@ -23,14 +27,22 @@ pub trait SinglePodRecipeSetup: Debug + Send + Sync + 'static {
     const INITIAL_ROOT_ACR_TEMPLATE: &'static str;
 
     /// Type of the pdp.
-    type PDP: PolicyDecisionPoint<StSpace = RcpStorageSpace, Graph = HashSet<ArcTriple>>
+    type PDP: RcpPDP
         +
         // For now.
         Default;
 
     /// Type of the backend builder.
-    type BackendBuilder: opendal::Builder;
+    type BackendBuilder: opendal::Builder + TryFrom<ODRBackendConfig, Error = BackendConfigError>;
 
     /// Type of the backend.
     type Backend: BuildableODRObjectStoreBackend<Self::BackendBuilder>;
+
+    /// Get the resolver of access modes required for resource
+    /// operations. Setups can override it to require custom
+    /// access modes declared by their pdp.
+    #[inline]
+    fn mode_resolver() -> Arc<dyn AccessModeResolver> {
+        DEFAULT_LEAST_PRIVILEGE_MAP.clone()
+    }
 }
@@ -2,12 +2,15 @@
 //! single-pod, databrowser enabled recipes.
 //!
 
-use std::collections::HashMap;
+use std::num::NonZeroUsize;
 
 use manas_http::uri::invariant::HierarchicalTrailingSlashHttpUri;
+use manas_repo_opendal::object_store::backend::impl_::config::ODRBackendConfig;
 use webid::WebId;
 
-use crate::recipe::impl_::common::config::RcpServerConfig;
+use crate::recipe::impl_::common::config::{
+    RcpLockerConfig, RcpNotificationsConfig, RcpQuotaConfig, RcpServerConfig, RcpVersioningConfig,
+};
 
 /// Recipe storage space config.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -23,12 +26,27 @@ pub struct RcpStorageSpaceConfig {
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct RcpRepoConfig {
     /// Backend config.
-    // TODO must be concrete struct.
-    pub backend: HashMap<String, String>,
+    pub backend: ODRBackendConfig,
 
     /// Weather databrowser is enabled.
     #[serde(default)]
     pub databrowser_enabled: bool,
+
+    /// Max number of contained resources per page of
+    /// container representations. If not set, container
+    /// representations will not be paged.
+    #[serde(default)]
+    pub max_container_page_size: Option<NonZeroUsize>,
+
+    /// Storage quota config. If not set, storage usage is
+    /// neither limited, nor advertised.
+    #[serde(default)]
+    pub quota: Option<RcpQuotaConfig>,
+
+    /// Storage versioning config. If not set, resources are
+    /// not versioned.
+    #[serde(default)]
+    pub versioning: Option<RcpVersioningConfig>,
 }
 
 /// Recipe storage config.
@@ -39,6 +57,10 @@ pub struct RcpStorageConfig {
 
     /// Storage repo config.
     pub repo: RcpRepoConfig,
+
+    /// Resource locker config.
+    #[serde(default)]
+    pub locker: RcpLockerConfig,
 }
 
 /// Recipe storage config.
@@ -50,6 +72,10 @@ pub struct RcpConfig {
     /// Recipe server config.
     pub server: RcpServerConfig,
 
+    /// Recipe notifications config.
+    #[serde(default)]
+    pub notifications: RcpNotificationsConfig,
+
     /// Wether to run in dev mode.
     #[serde(default)]
     pub dev_mode: bool,
@@ -1,21 +1,22 @@
 //! I provide utilities to construct single pod recipes without authentication or access control.
 //!
 
-// TODO Allow custom locker for distribution.
-
-use std::{borrow::Cow, marker::PhantomData, sync::Arc};
+use std::{borrow::Cow, marker::PhantomData, num::NonZeroUsize, sync::Arc, time::Duration};
 
 use futures::future::{BoxFuture, TryFutureExt};
 use http::uri::Scheme;
 use http_cache_reqwest::{Cache, CacheMode, HttpCache, MokaManager};
 use manas_http::service::impl_::UriReconstructionParams;
 use manas_repo::RepoExt;
-use manas_repo_layers::dconneging::conneg_layer::impl_::binary_rdf_doc_converting::BinaryRdfDocContentNegotiationConfig;
+use manas_repo_layers::dconneging::conneg_layer::impl_::binary_rdf_doc_converting::{
+    BinaryRdfDocContentNegotiationConfig, ConvertedRepCache,
+};
 use manas_repo_opendal::config::ODRConfig;
 use manas_space::BoxError;
-use manas_storage::service::impl_::{KPreferredReqTargetQueryParamMode, ReqTargetQueryParamMode};
-use name_locker::impl_::InmemNameLocker;
-use opendal::Builder as _;
+use manas_storage::service::{
+    impl_::{KPreferredReqTargetQueryParamMode, ReqTargetQueryParamMode},
+    method::get::base::KMaxContainerPageSize,
+};
 use rdf_dynsyn::{
     parser::config::{
         jsonld::{
@@ -35,12 +36,18 @@ use self::{
     config::{RcpConfig, RcpStorageSpaceConfig},
     setup::SinglePodNoAuthRecipeSetup,
 };
-use super::common::{resolve_svc_maker, serve_recipe};
+use super::common::{resolve_backend, resolve_svc_maker, serve_recipe};
 use crate::{
     dtbr::{adapt_dconneg_layer_config, DatabrowserContext, RcpDatabrowserAdaptedRdfSourceCNL},
+    locker::RcpResourceLocker,
+    notification::{configure_notifications, RcpNotificationsOverridenStaticPodSetService},
     pep::RcpTrivialPEP,
-    podverse::static_::{RcpPod, RcpStaticPodSetService},
-    recipe::Recipe,
+    podverse::static_::RcpPod,
+    quota::configure_quota,
+    recipe::{
+        impl_::common::config::{RcpQuotaConfig, RcpVersioningConfig},
+        Recipe,
+    },
     space::RcpStorageSpace,
     storage::{RcpStorage, RcpStorageSetup},
     CW,
@@ -58,7 +65,7 @@ pub struct SinglePodStorageSetup<RSetup> {
 impl<RSetup: SinglePodNoAuthRecipeSetup> RcpStorageSetup for SinglePodStorageSetup<RSetup> {
     type Backend = RSetup::Backend;
 
-    type ResourceLocker = InmemNameLocker<String>;
+    type ResourceLocker = RcpResourceLocker;
 
     type CNL = RcpDatabrowserAdaptedRdfSourceCNL<RSetup::Backend>;
 
@@ -120,6 +127,10 @@ impl<RSetup: SinglePodNoAuthRecipeSetup> SinglePodNoAuthRecipe<RSetup> {
         space_config: RcpStorageSpaceConfig,
         backend: RSetup::Backend,
         opt_databrowser_context: Option<DatabrowserContext>,
+        resource_locker: RcpResourceLocker,
+        max_container_page_size: Option<NonZeroUsize>,
+        opt_quota_config: Option<RcpQuotaConfig>,
+        opt_versioning_config: Option<RcpVersioningConfig>,
     ) -> Result<RcpPod<SinglePodStorageSetup<RSetup>>, BoxError> {
         // Box::pin(async move {
         let st_descr_uri = format!("{}_/description.ttl", space_config.root_uri.as_str())
@@ -147,11 +158,22 @@ impl<RSetup: SinglePodNoAuthRecipeSetup> SinglePodNoAuthRecipe<RSetup> {
             adapt_dconneg_layer_config(
                 Arc::new(BinaryRdfDocContentNegotiationConfig {
                     dynsyn_factories: dynsyn_factories.as_ref().clone(),
+                    // Cache up to 64 MiB of converted reps,
+                    // and up to 1M quads of parsed datasets.
+                    converted_rep_cache: Some(ConvertedRepCache::new(
+                        64 * 1024 * 1024,
+                        Some(1024 * 1024),
+                        Duration::from_secs(10 * 60),
+                    )),
                 }),
                 opt_databrowser_context,
             ),
             Arc::new(Default::default()),
             Arc::new(|_| None),
+            resource_locker,
+            opt_quota_config.clone().map(Into::into).unwrap_or_default(),
+            opt_versioning_config.map(Into::into).unwrap_or_default(),
+            None,
             Default::default(),
         );
 
@@ -165,6 +187,16 @@ impl<RSetup: SinglePodNoAuthRecipeSetup> SinglePodNoAuthRecipe<RSetup> {
                 );
         }
 
+        // Page container representations, if configured.
+        if let Some(max_container_page_size) = max_container_page_size {
+            storage
+                .extensions
+                .insert_rec_item::<KMaxContainerPageSize>(max_container_page_size);
+        }
+
+        // Advertise notification channels.
+        configure_notifications(&mut storage);
+
         // Initialize the repo.
         storage
             .repo
@@ -172,6 +204,14 @@ impl<RSetup: SinglePodNoAuthRecipeSetup> SinglePodNoAuthRecipe<RSetup> {
             .inspect_err(|e| error!("Error in initializing the repo. Error:\n {}", e))
             .await?;
 
+        // Enforce quota with accurate usage, and advertise
+        // usage, if configured.
+        if opt_quota_config.is_some() {
+            configure_quota(&mut storage)
+                .inspect_err(|e| error!("Error in configuring quota. Error:\n {}", e))
+                .await?;
+        }
+
         Ok(RcpPod {
             storage: Arc::new(storage),
         })
@@ -196,9 +236,9 @@ impl<RSetup: SinglePodNoAuthRecipeSetup> Recipe for SinglePodNoAuthRecipe<RSetup
     fn serve(&self, config: Self::Config) -> BoxFuture<'static, Result<(), BoxError>> {
         Box::pin(async move {
             let space_config = config.storage.space.clone();
-            let backend = RSetup::Backend::try_from(RSetup::BackendBuilder::from_map(
+            let backend = resolve_backend::<RSetup::BackendBuilder, RSetup::Backend>(
                 config.storage.repo.backend,
-            ))
+            )
             .map_err(|e| {
                 error!("Error in resolving backend. Error: {}", e);
                 e
@@ -212,13 +252,20 @@ impl<RSetup: SinglePodNoAuthRecipeSetup> Recipe for SinglePodNoAuthRecipe<RSetup
                     .repo
                     .databrowser_enabled
                     .then_some(DatabrowserContext::new_from_unpkg()),
+                RcpResourceLocker::try_new(&config.storage.locker)?,
+                config.storage.repo.max_container_page_size,
+                config.storage.repo.quota,
+                config.storage.repo.versioning,
             )
             .await?;
 
-            let podset_svc = CW::<RcpStaticPodSetService<_>>::new_for_static(
-                vec![Arc::new(pod)],
-                config.dev_mode,
-            );
+            let podset_svc =
+                CW::<RcpNotificationsOverridenStaticPodSetService<_>>::try_new_for_static(
+                    Arc::new(pod),
+                    config.dev_mode,
+                    config.notifications,
+                )
+                .await?;
 
             let uri_reconstruction_params = UriReconstructionParams {
                 default_scheme: if config.server.tls.is_some() {
@@ -229,7 +276,11 @@ impl<RSetup: SinglePodNoAuthRecipeSetup> Recipe for SinglePodNoAuthRecipe<RSetup
                 trusted_proxy_headers: config.server.trusted_proxy_headers.clone(),
             };
 
-            let svc_maker = resolve_svc_maker(podset_svc, uri_reconstruction_params);
+            let svc_maker = resolve_svc_maker(
+                podset_svc,
+                uri_reconstruction_params,
+                &config.server.cors.clone().into(),
+            );
 
             tracing::info!("Serving at {}", config.server.addr);
             tracing::info!(
@@ -3,7 +3,10 @@
 
 use std::fmt::Debug;
 
-use manas_repo_opendal::object_store::backend::BuildableODRObjectStoreBackend;
+use manas_repo_opendal::object_store::backend::{
+    impl_::config::{BackendConfigError, ODRBackendConfig},
+    BuildableODRObjectStoreBackend,
+};
 
 pub mod impl_;
 
@@ -13,7 +16,7 @@ pub trait SinglePodNoAuthRecipeSetup: Debug + Send + Sync + 'static {
     const BACKEND_NAME: &'static str;
 
     /// Type of the backend builder.
-    type BackendBuilder: opendal::Builder;
+    type BackendBuilder: opendal::Builder + TryFrom<ODRBackendConfig, Error = BackendConfigError>;
 
     /// Type of the backend.
     type Backend: BuildableODRObjectStoreBackend<Self::BackendBuilder>;
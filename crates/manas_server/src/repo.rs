@@ -14,13 +14,16 @@ use manas_repo_layers::{
         },
         DerivedContentNegotiatingRepo,
     },
+    eventing::EventingRepo,
     patching::{
         patcher::impl_::{
-            binary_rdf_doc_patcher::BinaryRdfDocPatcher,
+            binary_rdf_doc_patcher::BinaryRdfDocPatcher, either_patcher::EitherRepPatcher,
             solid_insert_delete_patcher::SolidInsertDeletePatcher,
+            sparql_update_patcher::SparqlUpdatePatcher,
         },
         PatchingRepo,
     },
+    quota::{version_store::QuotaMeteredVersionStore, QuotaEnforcingRepo},
     validating::{
         update_validator::impl_::{
             aux_protecting::AuxProtectingRepUpdateValidator,
@@ -29,6 +32,7 @@ use manas_repo_layers::{
         },
         ValidatingRepo,
     },
+    versioning::VersioningRepo,
 };
 use manas_repo_opendal::{
     object_store::{
@@ -38,7 +42,7 @@ use manas_repo_opendal::{
         },
         setup::impl_::BasicODRObjectStoreSetup,
     },
-    service::resource_operator::reader::ODRResourceReader,
+    service::{resource_operator::reader::ODRResourceReader, version_store::ODRVersionStore},
     setup::{aux_rep_policy::impl_::default::DefaultODRAuxResourcePolicy, ODRSetup},
     OpendalRepo,
 };
@@ -116,22 +120,47 @@ pub type RcpRepValidator<Backend, CNL> = MultiRepUpdateValidator<
 /// Type of representation patcher for the recipe.
 pub type RcpRepPatcher = BinaryRdfDocPatcher<
     RcpStorageSpace,
-    // N3 solid-insert-delete patcher.
-    SolidInsertDeletePatcher<RcpStorageSpace, HashSet<ArcQuad>>,
+    EitherRepPatcher<
+        // N3 solid-insert-delete patcher.
+        SolidInsertDeletePatcher<RcpStorageSpace, HashSet<ArcQuad>>,
+        // Sparql update patcher.
+        SparqlUpdatePatcher<RcpStorageSpace, HashSet<ArcQuad>>,
+    >,
     HashSet<ArcQuad>,
 >;
 
 /// Type of the repo for the recipe.
-/// Recipe uses access-control, rep-patching, rep-validating, and
-/// conneg layered opendal repo as it's repo.
+/// Recipe uses access-control, eventing, rep-patching,
+/// quota-enforcing, versioning, rep-validating, and conneg
+/// layered opendal repo as it's repo.
+///
+/// Eventing layer stays right below the access control layer,
+/// so that change events are published for every effective
+/// mutation, including those made by recipe internals.
+///
+/// Patching layer must stay above the quota enforcing layer,
+/// as the latter rejects patches, and meters only complete
+/// representations resolved by the former.
 pub type RcpRepo<Backend, CNL, PEP> = AccessControlledRepo<
-    PatchingRepo<
-        ValidatingRepo<RcpConnegingRepo<Backend, CNL>, RcpRepValidator<Backend, CNL>>,
-        RcpRepPatcher,
+    EventingRepo<
+        PatchingRepo<
+            QuotaEnforcingRepo<
+                VersioningRepo<
+                    ValidatingRepo<RcpConnegingRepo<Backend, CNL>, RcpRepValidator<Backend, CNL>>,
+                    RcpVersionStore<Backend>,
+                >,
+            >,
+            RcpRepPatcher,
+        >,
     >,
     PEP,
 >;
 
+/// Type of version store for the recipe. Versions are
+/// metered against the storage quota.
+pub type RcpVersionStore<Backend> =
+    QuotaMeteredVersionStore<ODRVersionStore<RcpBaseRepoSetup<Backend>>>;
+
 /// Type of rdf source conneg layer for recipe.
 pub type RcpRdfSourceCNL<Backend> = BinaryRdfDocContentNegotiationLayer<
     RcpBaseRepo<Backend>,
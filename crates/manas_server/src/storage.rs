@@ -1,50 +1,72 @@
 //! I define concrete types for the storages for recipes.
 //!
 
-use std::{collections::HashSet, fmt::Debug, marker::PhantomData, sync::Arc};
+use std::{fmt::Debug, marker::PhantomData, sync::Arc};
 
 use dyn_problem::Problem;
 use frunk_core::hlist;
 use futures::{future::BoxFuture, FutureExt, TryFutureExt};
 use manas_access_control::{
+    audit::AuditEmitter,
     layered_repo::context::AccessControlledRepoContext,
-    model::{pdp::PolicyDecisionPoint, pep::PolicyEnforcementPoint},
+    model::pep::{mode_resolver::AccessModeResolver, PolicyEnforcementPoint},
 };
+use manas_authentication::common::credentials::impl_::basic::BasicRequestCredentials;
 use manas_http::representation::impl_::binary::BinaryRepresentation;
 use manas_repo::{context::RepoContextual, Repo, RepoExt};
 use manas_repo_layers::{
     dconneging::{
         conneg_layer::DerivedContentNegotiationLayer, context::DerivedContentNegotiatingRepoContext,
     },
+    eventing::{context::EventingRepoContext, event::ResourceChangeEventBus},
     patching::{
         context::PatchingRepoContext,
         patcher::impl_::{
             binary_rdf_doc_patcher::BinaryRdfDocPatcherResolutionConfig,
+            either_patcher::EitherRepPatcherResolutionConfig,
             solid_insert_delete_patcher::SolidInsertDeletePatcherResolutionConfig,
+            sparql_update_patcher::SparqlUpdatePatcherResolutionConfig,
         },
     },
+    quota::{
+        context::QuotaEnforcingRepoContext,
+        tracker::{QuotaLimits, QuotaUsage, QuotaUsageTracker},
+        version_store::QuotaMeteredVersionStore,
+    },
     validating::{
         context::ValidatingRepoContext,
         update_validator::impl_::{
             common::RdfSourceRepUpdateValidatorConfig, multi::MultiRepUpdateValidatorConfig,
         },
     },
+    versioning::{config::VersioningConfig, context::VersioningRepoContext},
 };
 use manas_repo_opendal::{
-    config::ODRConfig, context::ODRContext, object_store::backend::ODRObjectStoreBackend,
-    service::resource_operator::reader::ODRResourceReader,
+    config::ODRConfig,
+    context::ODRContext,
+    object_store::backend::ODRObjectStoreBackend,
+    service::{
+        quota_usage_scanner::ODRQuotaUsageScanner, resource_operator::reader::ODRResourceReader,
+        version_store::ODRVersionStore,
+    },
 };
 use manas_storage::{
     policy::method::impl_::RdfPatchingMethodPolicy,
-    service::impl_::{DefaultStorageService, DefaultStorageServiceFactory},
+    service::{
+        impl_::{DefaultStorageService, DefaultStorageServiceFactory},
+        method::get::base::KMementoEnabled,
+    },
     SolidStorage,
 };
 use name_locker::NameLocker;
-use rdf_utils::model::triple::ArcTriple;
 use tracing::error;
+use typed_record::TypedRecord;
 
 use crate::{
-    pep::{InitialRootAcrRepFactory, RcpPRP, RcpSimplePEP, SimpleAccessRcpStorageSetup},
+    pep::{
+        InitialRootAcrRepFactory, RcpBasePRP, RcpPDP, RcpPRP, RcpSimplePEP,
+        SimpleAccessRcpStorageSetup, RCP_PRP_CACHE_MAX_CAPACITY, RCP_PRP_CACHE_TTL,
+    },
     repo::{RcpBaseRepo, RcpBaseRepoSetup, RcpCNLConfig, RcpRepo},
     space::RcpStorageSpace,
 };
@@ -65,7 +87,10 @@ pub trait RcpStorageSetup: Debug + Send + 'static + Sized {
     >;
 
     /// Type of policy enforcement point.
-    type PEP: PolicyEnforcementPoint<StSpace = RcpStorageSpace>;
+    type PEP: PolicyEnforcementPoint<
+        StSpace = RcpStorageSpace,
+        Credentials = BasicRequestCredentials,
+    >;
 }
 
 /// A generic implementation of [`RcpStorageSetup`].
@@ -88,7 +113,7 @@ where
         ODRResourceReader<RcpBaseRepoSetup<Backend>>,
     >,
     RL: NameLocker<Name = String> + Unpin,
-    PEP: PolicyEnforcementPoint<StSpace = RcpStorageSpace>,
+    PEP: PolicyEnforcementPoint<StSpace = RcpStorageSpace, Credentials = BasicRequestCredentials>,
 {
     type Backend = Backend;
 
@@ -156,20 +181,30 @@ impl<StSetup: RcpStorageSetup> SolidStorage for RcpStorage<StSetup> {
 }
 
 impl<StSetup: RcpStorageSetup> RcpStorage<StSetup> {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn _new(
         odr_context: Arc<ODRContext<RcpBaseRepoSetup<StSetup::Backend>>>,
         conneg_layer_config: Arc<RcpCNLConfig<StSetup::CNL, StSetup::Backend>>,
         pep: Arc<StSetup::PEP>,
         initial_root_acr_rep_factory: InitialRootAcrRepFactory,
         resource_locker: StSetup::ResourceLocker,
+        quota_tracker: Arc<QuotaUsageTracker>,
+        versioning_config: VersioningConfig,
+        opt_audit_emitter: Option<Arc<AuditEmitter>>,
+        event_bus: ResourceChangeEventBus<RcpStorageSpace>,
     ) -> Self {
         let dynsyn_factories = odr_context.as_ref().config.dynsyn_factories.clone();
 
         let patcher_resolution_config = Arc::new(BinaryRdfDocPatcherResolutionConfig {
             dynsyn_factories: dynsyn_factories.clone(),
-            inner: Arc::new(SolidInsertDeletePatcherResolutionConfig {
-                dynsyn_parser_factories: dynsyn_factories.as_ref().parser.clone(),
-                max_patch_doc_payload_size: Some(4 * 1024 * 1024),
+            inner: Arc::new(EitherRepPatcherResolutionConfig {
+                left: Arc::new(SolidInsertDeletePatcherResolutionConfig {
+                    dynsyn_parser_factories: dynsyn_factories.as_ref().parser.clone(),
+                    max_patch_doc_payload_size: Some(4 * 1024 * 1024),
+                }),
+                right: Arc::new(SparqlUpdatePatcherResolutionConfig {
+                    max_patch_doc_payload_size: Some(4 * 1024 * 1024),
+                }),
             }),
         });
 
@@ -183,30 +218,51 @@ impl<StSetup: RcpStorageSetup> RcpStorage<StSetup> {
             rdf_source_rep_validator_config
         ]));
 
+        let quota_tracker = Arc::new(QuotaUsageTracker::new(quota_limits, Default::default()));
+
         let repo_context = Arc::new(AccessControlledRepoContext {
             pep,
-            inner: Arc::new(PatchingRepoContext {
-                inner: Arc::new(ValidatingRepoContext {
-                    inner: Arc::new(DerivedContentNegotiatingRepoContext {
-                        inner: odr_context,
-                        dconneg_layer_config: conneg_layer_config,
+            inner: Arc::new(EventingRepoContext {
+                inner: Arc::new(PatchingRepoContext {
+                    inner: Arc::new(QuotaEnforcingRepoContext {
+                        inner: Arc::new(VersioningRepoContext {
+                            inner: Arc::new(ValidatingRepoContext {
+                                inner: Arc::new(DerivedContentNegotiatingRepoContext {
+                                    inner: odr_context.clone(),
+                                    dconneg_layer_config: conneg_layer_config,
+                                }),
+                                rep_update_validator_config,
+                            }),
+                            version_store: Arc::new(QuotaMeteredVersionStore::new(
+                                Arc::new(ODRVersionStore::new_with_context(odr_context)),
+                                quota_tracker.clone(),
+                            )),
+                            config: versioning_config.clone(),
+                        }),
+                        tracker: quota_tracker,
                     }),
-                    rep_update_validator_config,
+                    patcher_resolution_config,
                 }),
-                patcher_resolution_config,
+                event_bus,
             }),
             initial_root_acr_rep_factory,
+            audit_emitter: opt_audit_emitter,
         });
 
+        let mut extensions = http::Extensions::new();
+        // Negotiate mementos, if versioning is enabled.
+        extensions.insert_rec_item::<KMementoEnabled>(versioning_config.enabled);
+
         Self {
             method_policy: Default::default(),
             repo: RcpRepo::new(repo_context),
             resource_locker,
-            extensions: Default::default(),
+            extensions,
         }
     }
 
     /// Create a new [`RcpStorage`] with given params.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         storage_space: Arc<RcpStorageSpace>,
         backend: StSetup::Backend,
@@ -215,6 +271,10 @@ impl<StSetup: RcpStorageSetup> RcpStorage<StSetup> {
         pep: Arc<StSetup::PEP>,
         initial_root_acr_rep_factory: InitialRootAcrRepFactory,
         resource_locker: StSetup::ResourceLocker,
+        quota_limits: QuotaLimits,
+        versioning_config: VersioningConfig,
+        opt_audit_emitter: Option<Arc<AuditEmitter>>,
+        event_bus: ResourceChangeEventBus<RcpStorageSpace>,
     ) -> Self {
         let odr_context = Arc::new(ODRContext::new(storage_space, backend, odr_config));
 
@@ -224,42 +284,96 @@ impl<StSetup: RcpStorageSetup> RcpStorage<StSetup> {
             pep,
             initial_root_acr_rep_factory,
             resource_locker,
+            Arc::new(QuotaUsageTracker::new(quota_limits, Default::default())),
+            versioning_config,
+            opt_audit_emitter,
+            event_bus,
         )
     }
 
     /// Create a new [`RcpStorage`] with [``RcpSimplePEP`] as pep..
+    ///
+    /// Given quota tracker can be shared with prior
+    /// renditions of the storage, to carry over it's usage.
+    #[allow(clippy::too_many_arguments)]
     pub fn new_with_simple_pep<
         // Backend: ODRObjectStoreBackend,
-        PDP: PolicyDecisionPoint<StSpace = RcpStorageSpace, Graph = HashSet<ArcTriple>>,
+        PDP: RcpPDP,
     >(
         storage_space: Arc<RcpStorageSpace>,
         backend: <StSetup as RcpStorageSetup>::Backend,
         odr_config: ODRConfig,
         conneg_layer_config: Arc<RcpCNLConfig<StSetup::CNL, StSetup::Backend>>,
         pdp: Arc<PDP>,
+        mode_resolver: Arc<dyn AccessModeResolver>,
         initial_root_acr_rep_factory: InitialRootAcrRepFactory,
         resource_locker: StSetup::ResourceLocker,
+        quota_tracker: Arc<QuotaUsageTracker>,
+        versioning_config: VersioningConfig,
+        opt_audit_emitter: Option<Arc<AuditEmitter>>,
+        event_bus: ResourceChangeEventBus<RcpStorageSpace>,
     ) -> Self
     where
         StSetup: SimpleAccessRcpStorageSetup<PDP = PDP>,
     {
         let odr_context = Arc::new(ODRContext::new(storage_space.clone(), backend, odr_config));
 
+        let prp = RcpPRP::<StSetup::Backend>::new(
+            Arc::new(RcpBasePRP::new_with_context(odr_context.clone())),
+            RCP_PRP_CACHE_MAX_CAPACITY,
+            RCP_PRP_CACHE_TTL,
+        );
+        let opt_pdp_guard = pdp.configure_for_storage(&prp);
+
         let pep = RcpSimplePEP {
             storage_space,
             pdp,
-            prp: Arc::new(RcpPRP::<StSetup::Backend>::new_with_context(
-                odr_context.clone(),
-            )),
+            prp: Arc::new(prp),
+            mode_resolver,
         };
 
-        Self::_new(
+        let mut storage = Self::_new(
             odr_context,
             conneg_layer_config,
             Arc::new(pep),
             initial_root_acr_rep_factory,
             resource_locker,
-        )
+            quota_tracker,
+            versioning_config,
+            opt_audit_emitter,
+            event_bus,
+        );
+
+        // Release pdp configuration along with the storage,
+        // like when a member pod is evicted.
+        if let Some(pdp_guard) = opt_pdp_guard {
+            storage.extensions.insert(pdp_guard);
+        }
+        storage
+    }
+
+    /// Get the resource change event bus of the storage.
+    #[inline]
+    pub fn event_bus(&self) -> &ResourceChangeEventBus<RcpStorageSpace> {
+        &self.repo.context().inner.event_bus
+    }
+
+    /// Get the quota usage tracker of the storage.
+    #[inline]
+    pub fn quota_tracker(&self) -> &Arc<QuotaUsageTracker> {
+        &self.repo.context().inner.inner.inner.tracker
+    }
+
+    /// Rebuild quota usage counters of the storage, by
+    /// scanning it's backend.
+    pub async fn rebuild_quota_usage(&self) -> Result<QuotaUsage, Problem> {
+        let quota_context = &self.repo.context().inner.inner.inner;
+        let odr_context = &quota_context.inner.inner.inner.inner;
+
+        quota_context
+            .tracker
+            .rebuild(&ODRQuotaUsageScanner::new_with_context(odr_context.clone()))
+            .await
     }
 }
 
@@ -268,3 +382,593 @@ pub type RcpStorageService<StSetup> = DefaultStorageService<RcpStorage<StSetup>>
 
 /// Type of storage service factories for the recipes.
 pub type RcpStorageServiceFactory<StSetup> = DefaultStorageServiceFactory<RcpStorage<StSetup>>;
+
+#[cfg(all(test, feature = "backend-fs", feature = "pdp-wac"))]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use claims::*;
+    use http::{Method, StatusCode};
+    use manas_repo_layers::versioning::config::VersioningConfig;
+    use manas_storage::service::method::get::base::KMaxContainerPageSize;
+    use rstest::rstest;
+    use typed_record::TypedRecord;
+
+    use crate::test_utils::{TestPod, TestResponse, OTHER_ID, OWNER_ID, ROOT_URI};
+
+    const DOC: &str = "<#it> <http://example.org/p> \"o\".";
+
+    const RECURSIVE_DELETE: (&str, &str) = ("prefer", "delete=recursive");
+
+    /// Create a pod with a container tree at `c/d/`.
+    async fn new_pod_with_tree() -> TestPod {
+        let pod = TestPod::new().await;
+        for path in ["c/d/a.ttl", "c/d/e/b.ttl", "c/d/e/f/g.ttl"] {
+            assert!(pod.put_turtle(path, DOC).await.status.is_success());
+        }
+        pod
+    }
+
+    /// Assert that no resource in the tree is deleted.
+    async fn assert_tree_intact(pod: &TestPod) {
+        for path in [
+            "c/d/",
+            "c/d/a.ttl",
+            "c/d/e/",
+            "c/d/e/b.ttl",
+            "c/d/e/f/g.ttl",
+        ] {
+            assert_eq!(pod.owner_get_status(path).await, StatusCode::OK, "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn recursive_delete_deletes_entire_tree() {
+        let pod = new_pod_with_tree().await;
+
+        let resp = pod
+            .send(
+                Method::DELETE,
+                "c/d/",
+                &[RECURSIVE_DELETE],
+                "",
+                Some(OWNER_ID),
+            )
+            .await;
+        assert!(resp.status.is_success(), "{:?}", resp);
+
+        for path in ["c/d/", "c/d/a.ttl", "c/d/e/", "c/d/e/f/g.ttl"] {
+            assert_eq!(pod.owner_get_status(path).await, StatusCode::NOT_FOUND);
+        }
+        assert_eq!(pod.owner_get_status("c/").await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn recursive_delete_with_failing_preconditions_deletes_nothing() {
+        let pod = new_pod_with_tree().await;
+
+        let resp = pod
+            .send(
+                Method::DELETE,
+                "c/d/",
+                &[RECURSIVE_DELETE, ("if-match", "\"non-matching\"")],
+                "",
+                Some(OWNER_ID),
+            )
+            .await;
+        assert_eq!(resp.status, StatusCode::PRECONDITION_FAILED);
+
+        assert_tree_intact(&pod).await;
+    }
+
+    #[tokio::test]
+    async fn recursive_delete_with_matching_preconditions_deletes_entire_tree() {
+        let pod = new_pod_with_tree().await;
+
+        let resp = pod.send(Method::GET, "c/d/", &[], "", Some(OWNER_ID)).await;
+        assert_eq!(resp.status, StatusCode::OK);
+        let etag = assert_some!(resp.headers.get("etag"))
+            .to_str()
+            .unwrap()
+            .to_owned();
+
+        let resp = pod
+            .send(
+                Method::DELETE,
+                "c/d/",
+                &[RECURSIVE_DELETE, ("if-match", etag.as_str())],
+                "",
+                Some(OWNER_ID),
+            )
+            .await;
+        assert!(resp.status.is_success(), "{:?}", resp);
+
+        for path in ["c/d/", "c/d/a.ttl", "c/d/e/", "c/d/e/f/g.ttl"] {
+            assert_eq!(pod.owner_get_status(path).await, StatusCode::NOT_FOUND);
+        }
+    }
+
+    #[tokio::test]
+    async fn recursive_delete_without_access_deletes_nothing() {
+        let pod = new_pod_with_tree().await;
+
+        let resp = pod
+            .send(
+                Method::DELETE,
+                "c/d/",
+                &[RECURSIVE_DELETE],
+                "",
+                Some(OTHER_ID),
+            )
+            .await;
+        assert_eq!(resp.status, StatusCode::FORBIDDEN);
+
+        assert_tree_intact(&pod).await;
+    }
+
+    #[tokio::test]
+    async fn recursive_delete_with_partial_access_deletes_nothing() {
+        let pod = new_pod_with_tree().await;
+
+        // Other agent can delete everything in `c/`, except
+        // for `c/d/e/f/g.ttl`.
+        pod.put_acl(
+            "c/",
+            &[
+                (OWNER_ID, "acl:Read, acl:Write, acl:Control"),
+                (OTHER_ID, "acl:Read, acl:Write"),
+            ],
+        )
+        .await;
+        pod.put_acl(
+            "c/d/e/f/g.ttl",
+            &[(OWNER_ID, "acl:Read, acl:Write, acl:Control")],
+        )
+        .await;
+
+        let resp = pod
+            .send(
+                Method::DELETE,
+                "c/d/",
+                &[RECURSIVE_DELETE],
+                "",
+                Some(OTHER_ID),
+            )
+            .await;
+        assert_eq!(resp.status, StatusCode::FORBIDDEN);
+        assert_some!(resp.body.find("c/d/e/f/g.ttl"));
+
+        assert_tree_intact(&pod).await;
+    }
+
+    #[tokio::test]
+    async fn recursive_delete_without_read_access_to_subcontainer_deletes_nothing() {
+        let pod = new_pod_with_tree().await;
+
+        // Other agent can delete everything in `c/`, but
+        // cannot list `c/d/e/`.
+        pod.put_acl(
+            "c/",
+            &[
+                (OWNER_ID, "acl:Read, acl:Write, acl:Control"),
+                (OTHER_ID, "acl:Read, acl:Write"),
+            ],
+        )
+        .await;
+        pod.put_acl(
+            "c/d/e/",
+            &[
+                (OWNER_ID, "acl:Read, acl:Write, acl:Control"),
+                (OTHER_ID, "acl:Write"),
+            ],
+        )
+        .await;
+
+        let resp = pod
+            .send(
+                Method::DELETE,
+                "c/d/",
+                &[RECURSIVE_DELETE],
+                "",
+                Some(OTHER_ID),
+            )
+            .await;
+        assert_eq!(resp.status, StatusCode::FORBIDDEN);
+
+        assert_tree_intact(&pod).await;
+    }
+
+    /// Get the values of header with given name, joined.
+    fn header_str(resp: &TestResponse, name: &str) -> String {
+        resp.headers
+            .get_all(name)
+            .iter()
+            .map(|v| v.to_str().expect("Must be valid."))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    #[tokio::test]
+    async fn options_discloses_resource_options_to_readers() {
+        let pod = new_pod_with_tree().await;
+
+        let resp = pod
+            .send(Method::OPTIONS, "c/d/", &[], "", Some(OWNER_ID))
+            .await;
+        assert_eq!(resp.status, StatusCode::NO_CONTENT);
+        assert!(header_str(&resp, "allow").contains("POST"));
+        assert!(header_str(&resp, "link").contains("rel=\"acl\""));
+    }
+
+    #[tokio::test]
+    async fn options_falls_back_to_generic_options_for_non_readers() {
+        let pod = new_pod_with_tree().await;
+
+        let generic_resp = pod
+            .send(Method::OPTIONS, "c/d/x.ttl", &[], "", Some(OWNER_ID))
+            .await;
+        assert_eq!(generic_resp.status, StatusCode::NO_CONTENT);
+
+        for (path, agent_id) in [("c/d/", Some(OTHER_ID)), ("c/d/a.ttl", None)] {
+            let resp = pod.send(Method::OPTIONS, path, &[], "", agent_id).await;
+            assert_eq!(resp.status, StatusCode::NO_CONTENT);
+            assert_eq!(
+                header_str(&resp, "allow"),
+                header_str(&generic_resp, "allow")
+            );
+            assert_eq!(header_str(&resp, "link"), header_str(&generic_resp, "link"));
+            assert!(!header_str(&resp, "link").contains("rel=\"acl\""));
+        }
+    }
+
+    #[tokio::test]
+    async fn options_discloses_resource_options_to_granted_agents() {
+        let pod = new_pod_with_tree().await;
+        pod.put_acl(
+            "c/d/a.ttl",
+            &[
+                (OWNER_ID, "acl:Read, acl:Write, acl:Control"),
+                (OTHER_ID, "acl:Read"),
+            ],
+        )
+        .await;
+
+        let resp = pod
+            .send(Method::OPTIONS, "c/d/a.ttl", &[], "", Some(OTHER_ID))
+            .await;
+        assert_eq!(resp.status, StatusCode::NO_CONTENT);
+        assert!(header_str(&resp, "link").contains("rel=\"acl\""));
+    }
+
+    #[tokio::test]
+    async fn versioned_resource_advertises_memento_links() {
+        let pod = TestPod::new_with_versioning(
+            VersioningConfig {
+                enabled: true,
+                retention: Default::default(),
+            },
+            |_| {},
+        )
+        .await;
+        assert!(pod.put_turtle("a.ttl", DOC).await.status.is_success());
+
+        let resp = pod
+            .send(Method::HEAD, "a.ttl", &[], "", Some(OWNER_ID))
+            .await;
+        assert_eq!(resp.status, StatusCode::OK);
+
+        let links = header_str(&resp, "link");
+        for rel in ["original", "timegate", "timemap"] {
+            assert!(links.contains(&format!("rel=\"{rel}\"")), "{links}");
+        }
+    }
+
+    /// Resolve path of the link with given rel, if any.
+    fn link_path(resp: &TestResponse, rel: &str) -> Option<String> {
+        header_str(resp, "link")
+            .split(", ")
+            .find(|link| link.ends_with(&format!("rel=\"{rel}\"")))
+            .and_then(|link| {
+                let uri = link.strip_prefix('<')?.split_once('>')?.0;
+                uri.strip_prefix(ROOT_URI).map(ToOwned::to_owned)
+            })
+    }
+
+    #[tokio::test]
+    async fn container_pages_partition_contained_resources() {
+        let pod = TestPod::new_with(|storage| {
+            storage
+                .extensions
+                .insert_rec_item::<KMaxContainerPageSize>(NonZeroUsize::new(2).unwrap());
+        })
+        .await;
+
+        let names: Vec<_> = (0..5).map(|i| format!("r{i}.ttl")).collect();
+        for name in &names {
+            let path = format!("c/{name}");
+            assert!(pod.put_turtle(&path, DOC).await.status.is_success());
+        }
+
+        let mut page_path = Some("c/".to_owned());
+        let mut pages = Vec::new();
+        while let Some(path) = page_path.take() {
+            let resp = pod
+                .send(
+                    Method::GET,
+                    &path,
+                    &[("accept", "application/n-triples")],
+                    "",
+                    Some(OWNER_ID),
+                )
+                .await;
+            assert_eq!(resp.status, StatusCode::OK, "{:?}", resp);
+            assert_eq!(link_path(&resp, "first").as_deref(), Some("c/"));
+
+            let page_names: Vec<_> = names
+                .iter()
+                .filter(|name| resp.body.contains(&format!("<{ROOT_URI}c/{name}>")))
+                .cloned()
+                .collect();
+            assert!(!page_names.is_empty() && page_names.len() <= 2);
+
+            page_path = link_path(&resp, "next");
+            pages.push((path, link_path(&resp, "prev"), page_names));
+        }
+
+        // Each contained resource is in exactly one page.
+        let mut paged_names: Vec<_> = pages
+            .iter()
+            .flat_map(|(_, _, page_names)| page_names.clone())
+            .collect();
+        paged_names.sort();
+        assert_eq!(paged_names, names);
+        assert_eq!(pages.len(), 3);
+
+        // Previous page of the second page is the first page,
+        // which is linked as first.
+        assert_none!(&pages[0].1);
+        assert_none!(&pages[1].1);
+        assert_eq!(pages[2].1.as_ref(), Some(&pages[1].0));
+    }
+
+    #[tokio::test]
+    async fn container_pages_have_distinct_validators() {
+        let pod = TestPod::new_with(|storage| {
+            storage
+                .extensions
+                .insert_rec_item::<KMaxContainerPageSize>(NonZeroUsize::new(1).unwrap());
+        })
+        .await;
+        for path in ["c/a.ttl", "c/b.ttl", "c/c.ttl"] {
+            assert!(pod.put_turtle(path, DOC).await.status.is_success());
+        }
+
+        // Collect etags of all pages.
+        let mut page_path = Some("c/".to_owned());
+        let mut pages = Vec::new();
+        while let Some(path) = page_path.take() {
+            let resp = pod
+                .send(Method::GET, &path, &[NT_ACCEPT], "", Some(OWNER_ID))
+                .await;
+            assert_eq!(resp.status, StatusCode::OK, "{:?}", resp);
+
+            page_path = link_path(&resp, "next");
+            pages.push((path, header_str(&resp, "etag")));
+        }
+        assert_eq!(pages.len(), 3);
+
+        let mut etags: Vec<_> = pages.iter().map(|(_, etag)| etag.clone()).collect();
+        etags.sort();
+        etags.dedup();
+        assert_eq!(etags.len(), 3);
+
+        // Validator of a page doesn't validate other pages.
+        let resp = pod
+            .send(
+                Method::GET,
+                &pages[1].0,
+                &[NT_ACCEPT, ("if-none-match", pages[0].1.as_str())],
+                "",
+                Some(OWNER_ID),
+            )
+            .await;
+        assert_eq!(resp.status, StatusCode::OK);
+
+        // But validates the page itself.
+        let resp = pod
+            .send(
+                Method::GET,
+                &pages[1].0,
+                &[NT_ACCEPT, ("if-none-match", pages[1].1.as_str())],
+                "",
+                Some(OWNER_ID),
+            )
+            .await;
+        assert_eq!(resp.status, StatusCode::NOT_MODIFIED);
+    }
+
+    #[tokio::test]
+    async fn unknown_container_page_cursor_resolves_empty_page() {
+        let pod = TestPod::new_with(|storage| {
+            storage
+                .extensions
+                .insert_rec_item::<KMaxContainerPageSize>(NonZeroUsize::new(2).unwrap());
+        })
+        .await;
+        assert!(pod.put_turtle("c/a.ttl", DOC).await.status.is_success());
+
+        let resp = pod
+            .send(
+                Method::GET,
+                "c/?page=unknown",
+                &[("accept", "application/n-triples")],
+                "",
+                Some(OWNER_ID),
+            )
+            .await;
+        assert_eq!(resp.status, StatusCode::OK, "{:?}", resp);
+        assert!(!resp.body.contains("a.ttl"), "{}", resp.body);
+        assert_none!(link_path(&resp, "next"));
+        assert_none!(link_path(&resp, "prev"));
+    }
+
+    const NT_ACCEPT: (&str, &str) = ("accept", "application/n-triples");
+
+    const LDP_CONTAINS: &str = "<http://www.w3.org/ns/ldp#contains>";
+
+    #[tokio::test]
+    async fn converted_rep_is_invalidated_on_modification() {
+        let pod = TestPod::new().await;
+        assert!(pod.put_turtle("a.ttl", DOC).await.status.is_success());
+
+        for _ in 0..2 {
+            let resp = pod
+                .send(Method::GET, "a.ttl", &[NT_ACCEPT], "", Some(OWNER_ID))
+                .await;
+            assert_eq!(resp.status, StatusCode::OK);
+            assert!(resp.body.contains("\"o\""), "{}", resp.body);
+        }
+
+        let resp = pod
+            .put_turtle("a.ttl", "<#it> <http://example.org/p> \"o2\".")
+            .await;
+        assert!(resp.status.is_success());
+
+        let resp = pod
+            .send(Method::GET, "a.ttl", &[NT_ACCEPT], "", Some(OWNER_ID))
+            .await;
+        assert_eq!(resp.status, StatusCode::OK);
+        assert!(resp.body.contains("\"o2\""), "{}", resp.body);
+        assert!(!resp.body.contains("\"o\""), "{}", resp.body);
+    }
+
+    #[tokio::test]
+    async fn converted_container_reps_honour_preferences() {
+        let pod = TestPod::new().await;
+        assert!(pod.put_turtle("c/a.ttl", DOC).await.status.is_success());
+
+        let omit_containment = (
+            "prefer",
+            "return=representation; omit=\"http://www.w3.org/ns/ldp#PreferContainment\"",
+        );
+
+        // Converted container reps are not cached, as their
+        // etags don't account for representation preferences.
+        for _ in 0..2 {
+            let resp = pod
+                .send(
+                    Method::GET,
+                    "c/",
+                    &[NT_ACCEPT, omit_containment],
+                    "",
+                    Some(OWNER_ID),
+                )
+                .await;
+            assert_eq!(resp.status, StatusCode::OK);
+            assert!(!resp.body.contains(LDP_CONTAINS), "{}", resp.body);
+
+            let resp = pod
+                .send(Method::GET, "c/", &[NT_ACCEPT], "", Some(OWNER_ID))
+                .await;
+            assert_eq!(resp.status, StatusCode::OK);
+            assert!(resp.body.contains(LDP_CONTAINS), "{}", resp.body);
+        }
+    }
+
+    const LDP_BASIC_CONTAINER: &str = "<http://www.w3.org/ns/ldp#BasicContainer>";
+
+    const CONTAINER_LABEL: &str = "<http://example.org/label>";
+
+    #[rstest]
+    #[case::none(None, true, true)]
+    #[case::include_containment(
+        Some("include=\"http://www.w3.org/ns/ldp#PreferContainment\""),
+        true,
+        true
+    )]
+    #[case::omit_containment(
+        Some("omit=\"http://www.w3.org/ns/ldp#PreferContainment\""),
+        false,
+        true
+    )]
+    #[case::include_minimal(
+        Some("include=\"http://www.w3.org/ns/ldp#PreferMinimalContainer\""),
+        false,
+        true
+    )]
+    #[case::omit_minimal(
+        Some("omit=\"http://www.w3.org/ns/ldp#PreferMinimalContainer\""),
+        true,
+        false
+    )]
+    #[tokio::test]
+    async fn container_rep_honours_ldp_preferences(
+        #[case] ldp_pref: Option<&str>,
+        #[case] expected_containment: bool,
+        #[case] expected_minimal: bool,
+    ) {
+        let pod = TestPod::new().await;
+        let resp = pod
+            .put_turtle("c/", &format!("<> {CONTAINER_LABEL} \"C\"."))
+            .await;
+        assert!(resp.status.is_success(), "{:?}", resp);
+        assert!(pod.put_turtle("c/a.ttl", DOC).await.status.is_success());
+
+        let prefer = ldp_pref.map(|ldp_pref| format!("return=representation; {ldp_pref}"));
+        let mut headers = vec![NT_ACCEPT];
+        if let Some(prefer) = prefer.as_deref() {
+            headers.push(("prefer", prefer));
+        }
+
+        let resp = pod
+            .send(Method::GET, "c/", &headers, "", Some(OWNER_ID))
+            .await;
+        assert_eq!(resp.status, StatusCode::OK, "{:?}", resp);
+
+        assert!(resp.body.contains(LDP_BASIC_CONTAINER), "{}", resp.body);
+        assert_eq!(
+            resp.body.contains(LDP_CONTAINS),
+            expected_containment,
+            "{}",
+            resp.body
+        );
+        assert_eq!(
+            resp.body.contains(CONTAINER_LABEL),
+            expected_minimal,
+            "{}",
+            resp.body
+        );
+
+        // Applied preference is acknowledged.
+        if ldp_pref.is_some() {
+            assert_eq!(
+                header_str(&resp, "preference-applied"),
+                "return=representation"
+            );
+        } else {
+            assert_none!(resp.headers.get("preference-applied"));
+        }
+    }
+
+    #[tokio::test]
+    async fn ldp_preferences_are_not_applied_to_non_containers() {
+        let pod = TestPod::new().await;
+        assert!(pod.put_turtle("a.ttl", DOC).await.status.is_success());
+
+        let resp = pod
+            .send(
+                Method::GET,
+                "a.ttl",
+                &[(
+                    "prefer",
+                    "return=representation; omit=\"http://www.w3.org/ns/ldp#PreferContainment\"",
+                )],
+                "",
+                Some(OWNER_ID),
+            )
+            .await;
+        assert_eq!(resp.status, StatusCode::OK);
+        assert!(resp.body.contains("\"o\""), "{}", resp.body);
+        assert_none!(resp.headers.get("preference-applied"));
+    }
+}
@@ -0,0 +1,275 @@
+//! I provide utilities to test recipe storages, with fs
+//! backend and wac access control.
+//!
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::TryStreamExt;
+use http::{Method, Request, Response, StatusCode};
+use manas_authentication::common::credentials::impl_::basic::{
+    BasicAgentCredentials, BasicRequestCredentials,
+};
+use manas_http::{
+    body::Body,
+    uri::invariant::{AbsoluteHttpUri, NormalAbsoluteHttpUri},
+};
+use manas_repo::RepoExt;
+use manas_repo_layers::versioning::config::VersioningConfig;
+use manas_repo_opendal::object_store::backend::impl_::fs::FsBackend;
+use manas_storage::service::SolidStorageServiceFactory;
+use tempfile::TempDir;
+use tower::{Service, ServiceExt};
+
+use crate::{
+    pep::WAC_INITIAL_ROOT_ACR_TEMPLATE_STR,
+    recipe::impl_::single_pod::{
+        config::RcpStorageSpaceConfig, setup::impl_::FsWacRecipeSetup, RcpStorageOptions,
+        SinglePodRecipe, SinglePodStorage,
+    },
+    storage::RcpStorageServiceFactory,
+};
+
+/// Root uri of the test pod.
+pub(crate) const ROOT_URI: &str = "http://pod.example.org/";
+
+/// WebId of the test pod owner.
+pub(crate) const OWNER_ID: &str = "http://id.example.org/owner#me";
+
+/// WebId of an agent other than the test pod owner.
+pub(crate) const OTHER_ID: &str = "http://id.example.org/other#me";
+
+/// Type of the test pod storage.
+pub(crate) type TestStorage = SinglePodStorage<FsWacRecipeSetup>;
+
+/// A pod with fs backend in a temporary directory.
+pub(crate) struct TestPod {
+    /// Storage of the pod.
+    pub storage: Arc<TestStorage>,
+
+    _root_dir: TempDir,
+}
+
+/// A simplified response from the test pod.
+#[derive(Debug)]
+pub(crate) struct TestResponse {
+    /// Response status.
+    pub status: StatusCode,
+
+    /// Response headers.
+    pub headers: http::HeaderMap,
+
+    /// Response body.
+    pub body: String,
+}
+
+impl TestPod {
+    /// Create a new initialized test pod.
+    pub(crate) async fn new() -> Self {
+        Self::new_with(|_| {}).await
+    }
+
+    /// Create a new initialized test pod, after configuring
+    /// it's storage with given function.
+    pub(crate) async fn new_with(configure: impl FnOnce(&mut TestStorage)) -> Self {
+        Self::new_with_versioning(Default::default(), configure).await
+    }
+
+    /// Create a new initialized test pod with given versioning
+    /// config, after configuring it's storage with given
+    /// function.
+    pub(crate) async fn new_with_versioning(
+        versioning_config: VersioningConfig,
+        configure: impl FnOnce(&mut TestStorage),
+    ) -> Self {
+        Self::new_with_options(
+            RcpStorageOptions {
+                versioning_config,
+                ..Default::default()
+            },
+            configure,
+        )
+        .await
+    }
+
+    /// Create a new initialized test pod with given storage
+    /// options, after configuring it's storage with given
+    /// function.
+    pub(crate) async fn new_with_options(
+        options: RcpStorageOptions,
+        configure: impl FnOnce(&mut TestStorage),
+    ) -> Self {
+        let root_dir = tempfile::tempdir().expect("Must be able to create temp dir.");
+
+        let mut fs_builder = opendal::services::Fs::default();
+        fs_builder.root(root_dir.path().to_str().expect("Must be utf8."));
+
+        let mut storage = SinglePodRecipe::<FsWacRecipeSetup>::resolve_storage(
+            RcpStorageSpaceConfig {
+                root_uri: ROOT_URI.parse().expect("Must be valid."),
+                owner_id: OWNER_ID.parse().expect("Must be valid."),
+            },
+            FsBackend::try_from(fs_builder).expect("Must be valid."),
+            Default::default(),
+            WAC_INITIAL_ROOT_ACR_TEMPLATE_STR,
+            options,
+        );
+
+        configure(&mut storage);
+
+        storage
+            .repo
+            .initialize()
+            .await
+            .expect("Must be able to initialize the repo.");
+
+        Self {
+            storage: Arc::new(storage),
+            _root_dir: root_dir,
+        }
+    }
+
+    /// Send a request to the pod as the agent with given
+    /// webid, if any.
+    pub(crate) async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        headers: &[(&str, &str)],
+        body: &str,
+        agent_id: Option<&str>,
+    ) -> TestResponse {
+        let svc = RcpStorageServiceFactory::<_>::default().new_service(self.storage.clone());
+        send_with(svc, build_request(method, path, headers, body, agent_id)).await
+    }
+
+    /// Put a turtle representation at given path as owner.
+    pub(crate) async fn put_turtle(&self, path: &str, body: &str) -> TestResponse {
+        self.send(
+            Method::PUT,
+            path,
+            &[("content-type", "text/turtle")],
+            body,
+            Some(OWNER_ID),
+        )
+        .await
+    }
+
+    /// Get status of the resource at given path as owner.
+    pub(crate) async fn owner_get_status(&self, path: &str) -> StatusCode {
+        self.send(Method::GET, path, &[], "", Some(OWNER_ID))
+            .await
+            .status
+    }
+
+    /// Resolve path of the acl of the resource at given path,
+    /// from it's advertised links.
+    pub(crate) async fn acl_path(&self, path: &str) -> String {
+        let resp = self.send(Method::HEAD, path, &[], "", Some(OWNER_ID)).await;
+
+        resp.headers
+            .get_all("link")
+            .iter()
+            .flat_map(|value| value.to_str().expect("Must be valid.").split(','))
+            .find(|link| link.contains("rel=\"acl\""))
+            .and_then(|link| {
+                let uri = link.trim().strip_prefix('<')?.split_once('>')?.0;
+                uri.strip_prefix(ROOT_URI).map(ToOwned::to_owned)
+            })
+            .expect("Acl link must be advertised.")
+    }
+
+    /// Put an acl for the resource at given path, granting
+    /// given modes to given agents.
+    pub(crate) async fn put_acl(&self, path: &str, grants: &[(&str, &str)]) {
+        let res_uri = format!("{}{}", ROOT_URI, path);
+        let is_container = path.is_empty() || path.ends_with('/');
+
+        let acl = grants
+            .iter()
+            .enumerate()
+            .map(|(i, (agent_id, modes))| {
+                format!(
+                    "<#auth{i}> a acl:Authorization; acl:agent <{agent_id}>; acl:accessTo <{res_uri}>; {default} acl:mode {modes}.\n",
+                    default = if is_container {
+                        format!("acl:default <{res_uri}>;")
+                    } else {
+                        String::new()
+                    }
+                )
+            })
+            .fold(
+                "@prefix acl: <http://www.w3.org/ns/auth/acl#>.\n".to_owned(),
+                |acl, auth| acl + &auth,
+            );
+
+        let acl_path = self.acl_path(path).await;
+        let resp = self.put_turtle(&acl_path, &acl).await;
+        assert!(resp.status.is_success(), "Acl put failed: {:?}", resp);
+    }
+}
+
+/// Build a request to the test pod as the agent with given
+/// webid, if any.
+pub(crate) fn build_request(
+    method: Method,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: &str,
+    agent_id: Option<&str>,
+) -> Request<Body> {
+    let uri = format!("{}{}", ROOT_URI, path);
+
+    let mut req_builder = Request::builder().method(method).uri(uri.as_str());
+    for (name, value) in headers {
+        req_builder = req_builder.header(*name, *value);
+    }
+    let mut req = req_builder
+        .body(Body::from(body.to_owned()))
+        .expect("Must be valid.");
+
+    req.extensions_mut()
+        .insert(AbsoluteHttpUri::try_new_from(uri.as_str()).expect("Must be valid."));
+    req.extensions_mut()
+        .insert(NormalAbsoluteHttpUri::try_new_from(uri.as_str()).expect("Must be valid."));
+    req.extensions_mut().insert(BasicRequestCredentials {
+        of_agent: agent_id.map(|id| BasicAgentCredentials {
+            webid: id.parse().expect("Must be valid."),
+            bound_key_jkt: None,
+        }),
+        ..Default::default()
+    });
+
+    req
+}
+
+/// Send given request with given service, and collect the
+/// response.
+pub(crate) async fn send_with<S>(mut svc: S, req: Request<Body>) -> TestResponse
+where
+    S: Service<Request<Body>, Response = Response<Body>>,
+    S::Error: std::fmt::Debug,
+{
+    let resp: Response<Body> = ServiceExt::<Request<Body>>::ready(&mut svc)
+        .await
+        .expect("Must be ready.")
+        .call(req)
+        .await
+        .expect("Must be infallible.");
+
+    let (parts, body) = resp.into_parts();
+    let body = body
+        .into_data_stream()
+        .try_fold(Vec::new(), |mut data, bytes: Bytes| async move {
+            data.extend_from_slice(&bytes);
+            Ok(data)
+        })
+        .await
+        .expect("Must be able to read body.");
+
+    TestResponse {
+        status: parts.status,
+        headers: parts.headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    }
+}
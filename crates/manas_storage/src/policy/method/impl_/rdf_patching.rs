@@ -4,7 +4,7 @@ use http::Method;
 use if_chain::if_chain;
 use manas_http::header::{accept_patch::AcceptPatch, common::media_type::MediaType};
 use rdf_dynsyn::{correspondence::Correspondent, syntax::RdfSyntax};
-use vec1::Vec1;
+use vec1::{vec1, Vec1};
 
 use crate::policy::method::MethodPolicy;
 
@@ -31,10 +31,13 @@ impl Default for RdfPatchingMethodPolicy {
                 Method::POST,
                 Method::DELETE,
             ],
-            // Support n3 patch by default.
-            supported_rdf_patch_types: Some(Vec1::new(
+            // Support n3 patch, and sparql update by default.
+            supported_rdf_patch_types: Some(vec1![
                 "text/n3".parse().expect("Must be valid media type"),
-            )),
+                "application/sparql-update"
+                    .parse()
+                    .expect("Must be valid media type"),
+            ]),
         }
     }
 }
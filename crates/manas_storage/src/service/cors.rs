@@ -1,12 +1,13 @@
 //! I define middleware to handle cors semantics.
 //!
 
-use std::convert::Infallible;
+use std::{convert::Infallible, sync::Arc, time::Duration};
 
 use http::{
     header::{
         ACCEPT, ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD, ALLOW,
-        AUTHORIZATION, ETAG, LAST_MODIFIED, LINK, LOCATION, ORIGIN, WWW_AUTHENTICATE,
+        AUTHORIZATION, CONTENT_TYPE, ETAG, IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH,
+        IF_UNMODIFIED_SINCE, LAST_MODIFIED, LINK, LOCATION, ORIGIN, RANGE, WWW_AUTHENTICATE,
     },
     HeaderName, Request, Response,
 };
@@ -14,14 +15,171 @@ use manas_http::body::Body;
 use manas_http::{
     header::{
         accept_patch::ACCEPT_PATCH, accept_post::ACCEPT_POST, accept_put::ACCEPT_PUT,
-        preference_applied::PREFERENCE_APPLIED, wac_allow::WAC_ALLOW,
+        memento::ACCEPT_DATETIME, prefer::PREFER, preference_applied::PREFERENCE_APPLIED,
+        slug::SLUG, wac_allow::WAC_ALLOW,
     },
     service::BoxHttpResponseFuture,
 };
 use tower::{Layer, Service};
-use tower_http::cors::{Cors, CorsLayer};
+use tower_http::cors::{AllowCredentials, AllowHeaders, AllowOrigin, Cors, CorsLayer};
+
+/// A struct to represent cors policy of storage services.
+///
+/// Default policy is liberal. It mirrors request origin
+/// and request headers, and allows credentials.
+#[derive(Debug, Clone)]
+pub struct CorsPolicy {
+    /// Allowed origin patterns. A pattern can have `*`
+    /// wildcards, that match any sequence of characters
+    /// within a single dot separated label. Hence
+    /// `https://*.example.org` doesn't match
+    /// `https://a.b.example.org`. If empty, any origin is
+    /// allowed.
+    pub allowed_origin_patterns: Vec<String>,
+
+    /// Whether to allow credentials.
+    pub allow_credentials: bool,
+
+    /// Max age of preflight responses.
+    pub max_age: Option<Duration>,
+
+    /// Whether to mirror request headers as allowed headers.
+    /// If false, only a base set of headers used by solid
+    /// protocol, and extra allowed headers are allowed.
+    pub mirror_request_headers: bool,
+
+    /// Extra headers to be allowed, in addition to base
+    /// headers.
+    pub extra_allowed_headers: Vec<HeaderName>,
+
+    /// Extra headers to be exposed, in addition to headers
+    /// specified by solid protocol.
+    pub extra_exposed_headers: Vec<HeaderName>,
+}
+
+impl Default for CorsPolicy {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            allowed_origin_patterns: vec![],
+            allow_credentials: true,
+            max_age: None,
+            mirror_request_headers: true,
+            extra_allowed_headers: vec![],
+            extra_exposed_headers: vec![],
+        }
+    }
+}
+
+impl CorsPolicy {
+    /// Resolve the cors layer, that applies this policy.
+    pub fn resolve_layer(&self) -> CorsLayer {
+        let allow_origin = if self.allowed_origin_patterns.is_empty() {
+            AllowOrigin::mirror_request()
+        } else {
+            let patterns = Arc::new(self.allowed_origin_patterns.clone());
+            AllowOrigin::predicate(move |origin, _| {
+                origin.to_str().map_or(false, |origin| {
+                    patterns
+                        .iter()
+                        .any(|pattern| matches_origin_pattern(pattern, origin))
+                })
+            })
+        };
+
+        let allow_headers = if self.mirror_request_headers {
+            AllowHeaders::mirror_request()
+        } else {
+            AllowHeaders::list(
+                [
+                    ACCEPT,
+                    ACCEPT_DATETIME.clone(),
+                    AUTHORIZATION,
+                    CONTENT_TYPE,
+                    IF_MATCH,
+                    IF_MODIFIED_SINCE,
+                    IF_NONE_MATCH,
+                    IF_UNMODIFIED_SINCE,
+                    LINK,
+                    PREFER.clone(),
+                    RANGE,
+                    SLUG.clone(),
+                    HeaderName::from_static("dpop"),
+                    HeaderName::from_static("signature"),
+                    HeaderName::from_static("signature-input"),
+                    HeaderName::from_static("content-digest"),
+                    HeaderName::from_static("vp-token"),
+                ]
+                .into_iter()
+                .chain(self.extra_allowed_headers.iter().cloned()),
+            )
+        };
+
+        let mut cors_layer = CorsLayer::very_permissive()
+            .allow_origin(allow_origin)
+            .allow_headers(allow_headers)
+            .allow_credentials(AllowCredentials::from(self.allow_credentials))
+            .expose_headers(
+                [
+                    ACCEPT_PATCH.clone(),
+                    ACCEPT_POST.clone(),
+                    ACCEPT_PUT.clone(),
+                    ALLOW,
+                    ETAG,
+                    LAST_MODIFIED,
+                    LINK,
+                    LOCATION,
+                    PREFERENCE_APPLIED.clone(),
+                    WWW_AUTHENTICATE,
+                    WAC_ALLOW.clone(),
+                    HeaderName::from_static("updates-via"),
+                    HeaderName::from_static("dpop-nonce"),
+                ]
+                .into_iter()
+                .chain(self.extra_exposed_headers.iter().cloned())
+                .collect::<Vec<_>>(),
+            )
+            // Cors layer currently overrides any inner vary.
+            // So we cannot preserve inner value for now.
+            .vary([
+                ORIGIN,
+                ACCEPT,
+                AUTHORIZATION,
+                ACCESS_CONTROL_REQUEST_METHOD,
+                ACCESS_CONTROL_REQUEST_HEADERS,
+            ]);
+
+        if let Some(max_age) = self.max_age {
+            cors_layer = cors_layer.max_age(max_age);
+        }
+
+        cors_layer
+    }
+}
+
+/// Check if given origin matches given origin pattern.
+fn matches_origin_pattern(pattern: &str, origin: &str) -> bool {
+    let Some((prefix, rest_pattern)) = pattern.split_once('*') else {
+        // Pattern has no wildcards.
+        return pattern == origin;
+    };
+
+    let Some(rest) = origin.strip_prefix(prefix) else {
+        return false;
+    };
+
+    // Wildcard matches any sequence of characters, that
+    // doesn't cross a label boundary.
+    let label_len = rest.find('.').unwrap_or(rest.len());
+    rest[..label_len]
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain([label_len])
+        .any(|i| matches_origin_pattern(rest_pattern, &rest[i..]))
+}
 
 /// A middleware to handle cors semantics in liberal way.
+/// Policy can be restricted using [`CorsPolicy`].
 ///
 /// From spec:
 /// A server MUST implement the CORS protocol such that,
@@ -36,37 +194,18 @@ pub struct LiberalCors<S> {
 }
 
 impl<S> LiberalCors<S> {
-    /// Wrap a given service to get middleware applied service.
+    /// Wrap a given service to get middleware applied service,
+    /// with default liberal policy.
+    #[inline]
     pub fn new(inner: S) -> Self {
-        let cors_layer = CorsLayer::very_permissive()
-            .expose_headers([
-                ACCEPT_PATCH.clone(),
-                ACCEPT_POST.clone(),
-                ACCEPT_PUT.clone(),
-                ALLOW,
-                ETAG,
-                LAST_MODIFIED,
-                LINK,
-                LOCATION,
-                PREFERENCE_APPLIED.clone(),
-                WWW_AUTHENTICATE,
-                WAC_ALLOW.clone(),
-                HeaderName::from_static("updates-via"),
-            ])
-            // Cors layer currently overrides any inner vary.
-            // So we cannot preserve inner value for now.
-            .vary([
-                ORIGIN,
-                ACCEPT,
-                AUTHORIZATION,
-                ACCESS_CONTROL_REQUEST_METHOD,
-                ACCESS_CONTROL_REQUEST_HEADERS,
-            ]);
-
-        // TODO allow custom config.
+        Self::new_with_policy(inner, &CorsPolicy::default())
+    }
 
+    /// Wrap a given service to get middleware applied service,
+    /// with given cors policy.
+    pub fn new_with_policy(inner: S, policy: &CorsPolicy) -> Self {
         Self {
-            inner: cors_layer.layer(inner),
+            inner: policy.resolve_layer().layer(inner),
         }
     }
 }
@@ -93,3 +232,103 @@ where
         Box::pin(self.inner.call(req))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use http::{header::ACCESS_CONTROL_ALLOW_HEADERS, Method, StatusCode};
+    use rstest::rstest;
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    #[rstest]
+    #[case("https://app.example.org", "https://app.example.org", true)]
+    #[case("https://app.example.org", "https://app.example.org:8443", false)]
+    #[case("https://*.example.org", "https://app.example.org", true)]
+    #[case("https://*.example.org", "https://a.b.example.org", false)]
+    #[case("https://*.example.org", "https://evil.org/.example.org", false)]
+    #[case("https://*.example.org", "https://app.example.org.evil.org", false)]
+    #[case("https://app-*.example.org", "https://app-1.example.org", true)]
+    #[case("https://app-*.example.org", "https://app.example.org", false)]
+    #[case("https://*.*.example.org", "https://a.b.example.org", true)]
+    #[case("http://localhost:*", "http://localhost:3000", true)]
+    #[case("*", "https://example.org", false)]
+    fn origin_pattern_matching_works_correctly(
+        #[case] pattern: &str,
+        #[case] origin: &str,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(matches_origin_pattern(pattern, origin), expected);
+    }
+
+    async fn preflight(policy: &CorsPolicy, origin: &str, req_headers: &str) -> Response<Body> {
+        let svc = LiberalCors::new_with_policy(
+            service_fn(|_: Request<Body>| async {
+                Ok::<_, Infallible>(Response::new(Body::empty()))
+            }),
+            policy,
+        );
+
+        let req = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("https://pod.example.org/")
+            .header(ORIGIN, origin)
+            .header(ACCESS_CONTROL_REQUEST_METHOD, "PUT")
+            .header(ACCESS_CONTROL_REQUEST_HEADERS, req_headers)
+            .body(Body::empty())
+            .expect("Must be valid.");
+
+        svc.oneshot(req).await.expect("Must be infallible.")
+    }
+
+    #[tokio::test]
+    async fn base_allowed_headers_include_authentication_headers() {
+        let policy = CorsPolicy {
+            mirror_request_headers: false,
+            ..Default::default()
+        };
+
+        let resp = preflight(&policy, "https://app.example.org", "signature").await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let allowed_headers = resp
+            .headers()
+            .get(ACCESS_CONTROL_ALLOW_HEADERS)
+            .expect("Allowed headers must be set.")
+            .to_str()
+            .expect("Must be valid.");
+
+        for name in [
+            "authorization",
+            "dpop",
+            "signature",
+            "signature-input",
+            "content-digest",
+            "vp-token",
+        ] {
+            assert!(
+                allowed_headers.split(',').any(|h| h.trim() == name),
+                "{allowed_headers}"
+            );
+        }
+    }
+
+    #[rstest]
+    #[case("https://app.example.org", true)]
+    #[case("https://a.app.example.org", false)]
+    #[tokio::test]
+    async fn only_matching_origins_are_allowed(#[case] origin: &str, #[case] expected: bool) {
+        let policy = CorsPolicy {
+            allowed_origin_patterns: vec!["https://*.example.org".to_owned()],
+            ..Default::default()
+        };
+
+        let resp = preflight(&policy, origin, "authorization").await;
+        assert_eq!(
+            resp.headers()
+                .get(http::header::ACCESS_CONTROL_ALLOW_ORIGIN)
+                .map(|v| v.as_bytes()),
+            expected.then_some(origin.as_bytes())
+        );
+    }
+}
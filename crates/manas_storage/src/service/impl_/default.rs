@@ -4,7 +4,7 @@
 use std::{collections::HashMap, convert::Infallible, marker::PhantomData, sync::Arc, task::Poll};
 
 use dyn_problem::{type_::INFALLIBLE, Problem};
-use futures::future::BoxFuture;
+use futures::{future::BoxFuture, FutureExt};
 use http::{Method, Request, Response};
 use manas_http::{
     body::Body,
@@ -28,6 +28,7 @@ use typed_record::{TypedRecord, TypedRecordKey};
 use crate::{
     service::{
         method::{
+            common::problem::lock_problem,
             delete::{
                 base::BaseDeleteService,
                 marshaller::default::{
@@ -43,6 +44,13 @@ use crate::{
                     head_only::HeadOnlyBaseGetResponseMarshaller,
                 },
             },
+            options::{
+                base::BaseOptionsService,
+                marshaller::default::{
+                    DefaultBaseOptionsResponseMarshaller,
+                    DefaultBaseOptionsResponseMarshallerConfig,
+                },
+            },
             post::{
                 base::BasePostService,
                 marshaller::default::{
@@ -217,6 +225,21 @@ impl<Storage: SolidStorage> SolidStorageServiceFactory for DefaultStorageService
             ),
         };
 
+        // Options svc.
+        let options_svc = MethodService {
+            base_method_svc: BaseOptionsService::new(storage.clone()),
+            marshaller: DefaultBaseOptionsResponseMarshaller::new(
+                storage.clone(),
+                storage
+                    .extensions()
+                    .get::<DefaultBaseOptionsResponseMarshallerConfig>()
+                    .cloned()
+                    .unwrap_or(DefaultBaseOptionsResponseMarshallerConfig {
+                        dev_mode: self.dev_mode,
+                    }),
+            ),
+        };
+
         let mut method_services = HashMap::<Method, Box<dyn HttpService<Body, Body>>>::new();
 
         method_services.insert(Method::HEAD, Box::new(head_svc));
@@ -225,6 +248,7 @@ impl<Storage: SolidStorage> SolidStorageServiceFactory for DefaultStorageService
         method_services.insert(Method::PUT, Box::new(put_svc));
         method_services.insert(Method::PATCH, Box::new(patch_svc));
         method_services.insert(Method::DELETE, Box::new(delete_svc));
+        method_services.insert(Method::OPTIONS, Box::new(options_svc));
 
         let method_router = RouteByMethod::new(Arc::new(method_services));
 
@@ -349,11 +373,14 @@ impl<Storage: SolidStorage> Service<()> for DefaultStorageService<Storage> {
 
     #[inline]
     fn call(&mut self, _req: ()) -> Self::Future {
-        NameLocker::poll_with_lock(
-            self.storage.resource_locker(),
-            RepoExt::initialize(self.storage.repo()),
-            Some(self.storage.space().root_res_uri().to_string()),
-            LockKind::Exclusive,
+        Box::pin(
+            NameLocker::poll_with_lock(
+                self.storage.resource_locker(),
+                RepoExt::initialize(self.storage.repo()),
+                Some(self.storage.space().root_res_uri().to_string()),
+                LockKind::Exclusive,
+            )
+            .map(|result| result.unwrap_or_else(|e| Err(lock_problem(e)))),
         )
     }
 }
@@ -1,2 +1,30 @@
 //! I define few common problem types for method services.
 //!
+
+use dyn_problem::{define_anon_problem_types, Problem};
+use http::StatusCode;
+use http_api_problem::ApiError;
+use name_locker::LockError;
+use tracing::error;
+
+define_anon_problem_types!(
+    /// Resource lock is unavailable.
+    RESOURCE_LOCK_UNAVAILABLE: ("Resource lock is unavailable.");
+);
+
+/// Get the problem for given resource lock error.
+pub fn lock_problem(e: LockError) -> Problem {
+    error!("Error in locking the resource. Error:\n {}", e);
+    RESOURCE_LOCK_UNAVAILABLE
+        .new_problem_builder()
+        .message(e.to_string())
+        .finish()
+}
+
+/// Get the api error for given resource lock error.
+pub fn lock_api_error(e: LockError) -> ApiError {
+    error!("Error in locking the resource. Error:\n {}", e);
+    RESOURCE_LOCK_UNAVAILABLE
+        .new_api_error_builder(StatusCode::SERVICE_UNAVAILABLE)
+        .finish()
+}
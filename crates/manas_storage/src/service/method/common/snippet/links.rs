@@ -0,0 +1,101 @@
+//! I provide few snippets to resolve `Link` header values for
+//! resources.
+//!
+
+use iri_string::types::UriReferenceStr;
+use manas_http::header::link::{LinkRel, LinkTarget, LinkValue};
+use manas_space::{
+    resource::{slot::SolidResourceSlot, slot_link::AuxLink},
+    SolidStorageSpace,
+};
+use rdf_vocabularies::ns;
+
+/// Push ldp resource type links for the resource with given
+/// slot.
+pub fn push_ldp_type_links<StSpace: SolidStorageSpace>(
+    links: &mut Vec<LinkValue>,
+    res_slot: &SolidResourceSlot<StSpace>,
+) {
+    // Set ldp resource types.
+    links.push(
+        LinkValue::try_new_basic(ns::ldp::Resource.to_string(), "type").expect("Must be valid"),
+    );
+    // If is container.
+    if res_slot.is_container_slot() {
+        links.push(
+            LinkValue::try_new_basic(ns::ldp::BasicContainer.to_string(), "type")
+                .expect("Must be valid"),
+        );
+    }
+}
+
+/// Get storage description link for given storage space.
+///
+/// Req: Servers MUST include the Link header with
+/// rel="http://www.w3.org/ns/solid/terms#storageDescription"
+/// targeting the URI of the storage description resource
+/// in the response of HTTP GET, HEAD and OPTIONS
+/// requests targeting a resource in a storage.
+pub fn storage_description_link<StSpace: SolidStorageSpace>(space: &StSpace) -> LinkValue {
+    LinkValue::try_new_basic(
+        space.description_res_uri().as_str(),
+        "http://www.w3.org/ns/solid/terms#storageDescription",
+    )
+    .expect("Must be valid")
+}
+
+/// Push links specified by solid protocol for the resource
+/// with given slot and aux links index.
+pub fn push_solid_links<StSpace: SolidStorageSpace>(
+    links: &mut Vec<LinkValue>,
+    res_slot: &SolidResourceSlot<StSpace>,
+    aux_links_index: &[AuxLink<StSpace>],
+) {
+    let space = res_slot.space();
+
+    // Set slot reverse links.
+    if let Some(slot_rev_link) = res_slot.slot_rev_link() {
+        links.push(LinkValue::new(
+            // Target as resource itself
+            LinkTarget(AsRef::<UriReferenceStr>::as_ref(&*res_slot.id().uri).to_owned()),
+            LinkRel::new(slot_rev_link.rev_rel_type.clone().into()),
+            // Host resource as anchor.
+            Some(AsRef::<UriReferenceStr>::as_ref(&*slot_rev_link.target).to_owned()),
+        ))
+    }
+
+    // Push storage description link.
+    links.push(storage_description_link(space.as_ref()));
+
+    // If resource is storage root container,
+    if res_slot.is_root_slot() {
+        // Push storage type link.
+        // Req: Servers exposing the storage resource MUST
+        // advertise by including the HTTP Link header with rel="type"
+        // targeting http://www.w3.org/ns/pim/space#Storage
+        // when responding to storage’s request URI.
+        links.push(
+            LinkValue::try_new_basic(ns::pim::Storage.to_string(), "type").expect("Must be valid"),
+        );
+
+        // Push storage owner link.
+        // Req: When a server wants to advertise the owner
+        // of a storage, the server MUST include the Link
+        // header with rel="http://www.w3.org/ns/solid/terms#owner"
+        // targeting the URI of the owner in the response of
+        // HTTP HEAD or GET requests targeting the root container.
+        links.push(
+            LinkValue::try_new_basic(space.owner_id().as_str(), ns::solid::owner.to_string())
+                .expect("Must be valid"),
+        );
+    }
+
+    // Push links to auxiliary resources.
+    // Req: Servers MUST advertise auxiliary resources
+    // associated with a subject resource by responding to
+    // HEAD and GET requests by including the HTTP Link
+    // header with the rel parameter [RFC8288].
+    for item in aux_links_index {
+        links.push(item.clone().into())
+    }
+}
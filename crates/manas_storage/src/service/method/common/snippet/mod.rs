@@ -2,6 +2,7 @@
 //!
 
 pub mod authorization;
+pub mod links;
 pub mod op_req;
 pub mod req_headers;
 pub mod status_token;
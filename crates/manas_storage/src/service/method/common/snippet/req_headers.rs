@@ -12,15 +12,24 @@ use http::{
 use http_api_problem::ApiError;
 use http_body::SizeHint;
 use manas_http::{
-    header::common::media_type::MediaType, representation::metadata::derived_etag::DerivedETag,
+    header::{
+        common::media_type::MediaType,
+        prefer::{
+            Prefer, PARAM_NAME_INCLUDE, PARAM_NAME_OMIT, TOKEN_NAME_RETURN,
+            TOKEN_VALUE_REPRESENTATION,
+        },
+    },
+    representation::metadata::derived_etag::DerivedETag,
 };
-use manas_repo::service::resource_operator::common::preconditions::{
-    impl_::http::HttpPreconditionsEvalResult, KPreconditionsEvalResult,
+use manas_repo::service::resource_operator::{
+    common::preconditions::{impl_::http::HttpPreconditionsEvalResult, KPreconditionsEvalResult},
+    reader::rep_preferences::ContainerRepresentationPreference,
 };
 use manas_specs::{
     protocol::{REQ_CLIENT_CONTENT_TYPE, REQ_SERVER_CONTENT_TYPE},
     SpecProblem,
 };
+use rdf_vocabularies::ns;
 use tracing::debug;
 use typed_record::TypedRecord;
 
@@ -114,3 +123,123 @@ pub fn resolve_preconditions_eval_status(problem: &Problem) -> Option<StatusCode
         .and_then(|r| r.as_any().downcast_ref::<HttpPreconditionsEvalResult>())
         .and_then(|r| r.as_return().cloned())
 }
+
+/// Resolve container representation preference from
+/// `Prefer: return=representation` header with ldp
+/// `include` / `omit` preferences, if any.
+///
+/// Containment triples and minimal container triples can be
+/// included or omitted. As basic containers have no membership
+/// triples, `ldp:PreferMembership` is ignored. If both are
+/// omitted, minimal container triples are still returned.
+pub fn resolve_container_rep_preference(
+    headers: &HeaderMap,
+) -> Option<ContainerRepresentationPreference> {
+    let prefer = headers.typed_get::<Prefer>()?;
+    let preference = prefer.preferences.iter().find(|p| {
+        p.token_name == *TOKEN_NAME_RETURN && p.token_value == *TOKEN_VALUE_REPRESENTATION
+    })?;
+
+    // Get whitespace separated preference iris of given param.
+    let pref_iris = |param_name| -> Vec<String> {
+        preference
+            .params
+            .get_value(param_name)
+            .map(|v| v.split_ascii_whitespace().map(ToOwned::to_owned).collect())
+            .unwrap_or_default()
+    };
+    let included = pref_iris(&PARAM_NAME_INCLUDE);
+    let omitted = pref_iris(&PARAM_NAME_OMIT);
+
+    // Ignore, if no ldp preferences are expressed.
+    if included.is_empty() && omitted.is_empty() {
+        return None;
+    }
+
+    let containment_iri = ns::ldp::PreferContainment.to_string();
+    let minimal_iris = [
+        ns::ldp::PreferMinimalContainer.to_string(),
+        ns::ldp::PreferEmptyContainer.to_string(),
+    ];
+
+    let includes_minimal = included.iter().any(|iri| minimal_iris.contains(iri));
+    let omits_minimal = omitted.iter().any(|iri| minimal_iris.contains(iri));
+
+    // Containment triples are returned, unless omitted, or
+    // only minimal container triples are included.
+    let wants_containment = if included.contains(&containment_iri) {
+        true
+    } else {
+        !omitted.contains(&containment_iri) && !includes_minimal
+    };
+
+    Some(match (wants_containment, omits_minimal) {
+        (true, false) => ContainerRepresentationPreference::All,
+        (true, true) => ContainerRepresentationPreference::Containment,
+        (false, _) => ContainerRepresentationPreference::Minimal,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    const CONTAINMENT: &str = "http://www.w3.org/ns/ldp#PreferContainment";
+    const MINIMAL: &str = "http://www.w3.org/ns/ldp#PreferMinimalContainer";
+    const EMPTY: &str = "http://www.w3.org/ns/ldp#PreferEmptyContainer";
+    const MEMBERSHIP: &str = "http://www.w3.org/ns/ldp#PreferMembership";
+
+    #[rstest]
+    #[case::none(None, None)]
+    #[case::return_minimal(Some("return=minimal".to_owned()), None)]
+    #[case::no_ldp_prefs(Some("return=representation".to_owned()), None)]
+    #[case::include_containment(
+        Some(format!(r#"return=representation; include="{CONTAINMENT}""#)),
+        Some(ContainerRepresentationPreference::All)
+    )]
+    #[case::omit_containment(
+        Some(format!(r#"return=representation; omit="{CONTAINMENT}""#)),
+        Some(ContainerRepresentationPreference::Minimal)
+    )]
+    #[case::include_minimal(
+        Some(format!(r#"return=representation; include="{MINIMAL}""#)),
+        Some(ContainerRepresentationPreference::Minimal)
+    )]
+    #[case::include_empty(
+        Some(format!(r#"return=representation; include="{EMPTY}""#)),
+        Some(ContainerRepresentationPreference::Minimal)
+    )]
+    #[case::omit_minimal(
+        Some(format!(r#"return=representation; omit="{MINIMAL}""#)),
+        Some(ContainerRepresentationPreference::Containment)
+    )]
+    #[case::include_containment_omit_minimal(
+        Some(format!(r#"return=representation; include="{CONTAINMENT}"; omit="{MINIMAL}""#)),
+        Some(ContainerRepresentationPreference::Containment)
+    )]
+    #[case::include_containment_and_minimal(
+        Some(format!(r#"return=representation; include="{CONTAINMENT} {MINIMAL}""#)),
+        Some(ContainerRepresentationPreference::All)
+    )]
+    #[case::omit_containment_and_minimal(
+        Some(format!(r#"return=representation; omit="{CONTAINMENT} {MINIMAL}""#)),
+        Some(ContainerRepresentationPreference::Minimal)
+    )]
+    #[case::include_membership(
+        Some(format!(r#"return=representation; include="{MEMBERSHIP}""#)),
+        Some(ContainerRepresentationPreference::All)
+    )]
+    fn container_rep_preference_resolves_correctly(
+        #[case] prefer: Option<String>,
+        #[case] expected: Option<ContainerRepresentationPreference>,
+    ) {
+        let mut headers = HeaderMap::new();
+        if let Some(prefer) = prefer {
+            headers.insert("prefer", prefer.parse().expect("Must be valid."));
+        }
+
+        assert_eq!(resolve_container_rep_preference(&headers), expected);
+    }
+}
@@ -2,15 +2,25 @@
 //! for handling `DELETE` method over solid resources.
 //!
 
-use std::{sync::Arc, task::Poll};
+use std::{collections::HashSet, sync::Arc, task::Poll};
 
 use dyn_problem::{type_::INTERNAL_ERROR, Problem, ProblemBuilderExt};
-use futures::{future::BoxFuture, TryFutureExt};
-use http::{Method, Request, StatusCode};
+use futures::{future::BoxFuture, FutureExt, TryFutureExt, TryStreamExt};
+use headers::HeaderMapExt;
+use http::{HeaderMap, Method, Request, StatusCode};
 use http_api_problem::ApiError;
 use manas_access_control::model::{KResolvedAccessControl, KResolvedHostAccessControl};
 use manas_http::body::Body;
-use manas_http::uri::invariant::NormalAbsoluteHttpUri;
+use manas_http::{
+    header::{
+        common::field::rules::{
+            parameter_name::FieldParameterName, parameter_value::FieldParameterValue,
+        },
+        prefer::Prefer,
+    },
+    representation::impl_::{basic::BasicRepresentation, common::data::bytes_inmem::BytesInmem},
+    uri::invariant::NormalAbsoluteHttpUri,
+};
 use manas_repo::{
     policy::uri::RepoUriPolicy,
     service::resource_operator::{
@@ -20,13 +30,17 @@ use manas_repo::{
             status_token::ExistingRepresentedResourceToken,
         },
         deleter::{
-            ResourceDeleteRequest, ResourceDeleteResponse, ResourceDeleteTokenSet,
+            KDryRunDelete, ResourceDeleteRequest, ResourceDeleteResponse, ResourceDeleteTokenSet,
             DELETE_TARGETS_NON_EMPTY_CONTAINER, DELETE_TARGETS_STORAGE_ROOT,
         },
+        reader::{
+            rep_preferences::{ContainerRepresentationPreference, RepresentationPreferences},
+            ResourceReadRequest, ResourceReadTokenSet,
+        },
     },
     RepoExt,
 };
-use manas_space::resource::uri::SolidResourceUri;
+use manas_space::{resource::uri::SolidResourceUri, SolidStorageSpace};
 use manas_specs::{
     protocol::{
         REQ_SERVER_DELETE_PROTECT_NONEMPTY_CONTAINER, REQ_SERVER_DELETE_PROTECT_ROOT_CONTAINER,
@@ -34,19 +48,71 @@ use manas_specs::{
     SpecProblem,
 };
 use name_locker::{LockKind, NameLocker};
+use once_cell::sync::Lazy;
+use rdf_dynsyn::parser::DynSynParserFactorySet;
+use rdf_utils::model::quad::ArcQuad;
+use rdf_vocabularies::ns;
+use sophia_api::{quad::Quad, term::Term};
 use tower::{Service, ServiceExt};
-use tracing::error;
-use typed_record::{ClonableTypedRecord, TypedRecord};
+use tracing::{error, info, warn};
+use typed_record::{ClonableTypedRecord, TypedRecord, TypedRecordKey};
 
 use crate::{
-    service::method::common::snippet::{
-        op_req::KOpReqExtensions, req_headers::etag_base_normalized_conditional_headers,
-        status_token::resolve_status_token,
+    service::method::common::{
+        problem::{lock_api_error, lock_problem, RESOURCE_LOCK_UNAVAILABLE},
+        snippet::{
+            op_req::KOpReqExtensions, req_headers::etag_base_normalized_conditional_headers,
+            status_token::resolve_status_token,
+        },
     },
-    SgCredentials, SgRepo, SgResourceDeleter, SgResourceStatusToken, SolidStorage,
+    SgCredentials, SgRepo, SgResourceDeleter, SgResourceReader, SgResourceStatusToken,
+    SolidStorage, SolidStorageExt,
 };
 
+/// Static for "delete" preference token name.
+pub static TOKEN_NAME_DELETE: Lazy<FieldParameterName> =
+    Lazy::new(|| "delete".parse().expect("Must be valid."));
+
+/// Static for "recursive" preference token value.
+pub static TOKEN_VALUE_RECURSIVE: Lazy<FieldParameterValue> =
+    Lazy::new(|| "recursive".try_into().expect("Must be valid."));
+
+/// Maximum depth of container hierarchy that can be deleted
+/// recursively.
+pub const MAX_RECURSIVE_DELETE_DEPTH: usize = 64;
+
+/// Maximum number of descendants of a container, that can be
+/// deleted recursively.
+pub const MAX_RECURSIVE_DELETE_DESCENDANTS: usize = 10_000;
+
+/// A [`TypedRecordKey`] for recording that recursive delete
+/// preference is applied, in delete response extensions.
+#[derive(Debug, Clone, Copy)]
+pub struct KAppliedRecursiveDelete;
+
+impl TypedRecordKey for KAppliedRecursiveDelete {
+    type Value = ();
+}
+
+/// Parser factories to parse container representations.
+static CONTAINER_REP_PARSER_FACTORIES: Lazy<Arc<DynSynParserFactorySet>> =
+    Lazy::new(Default::default);
+
 /// A service that handles conditional `DELETE` request over a resource in solid compatible, concurrent safe way.
+///
+/// If request expresses `Prefer: delete=recursive` preference,
+/// and targets a non-root container, it deletes the container
+/// along with all of it's descendants, with exclusive lock on
+/// the container held for the whole operation. Descendants
+/// are deleted depth-first, each under it's own resource lock
+/// and subject to access control. Request preconditions apply
+/// only to the target container.
+///
+/// Before deleting anything, it checks the preconditions
+/// and access control of the container, and access control
+/// for deletion of every descendant. If any of them cannot be
+/// deleted, request fails with a problem listing the
+/// undeletable resources, with nothing deleted.
 #[derive(Debug)]
 pub struct BaseDeleteService<Storage> {
     /// Storage.
@@ -115,36 +181,470 @@ where
             .get::<NormalAbsoluteHttpUri>()
             .expect("BaseDeleteService must be called after confirming resource uri is normal absolute http uri.").clone();
 
-        // Uri policy of the repo.
-        let uri_policy = storage.repo().uri_policy();
+        let credentials = req
+            .extensions_mut()
+            .remove::<SgCredentials<Storage>>()
+            .unwrap_or_default();
+        let op_req_extensions = req
+            .extensions_mut()
+            .remove_rec_item::<KOpReqExtensions>()
+            .unwrap_or_default();
+
+        // Recursive delete applies only to non-root containers.
+        let is_recursive = is_recursive_delete_preferred(req.headers())
+            && res_uri.as_str().ends_with('/')
+            && &res_uri != storage.space().root_res_uri();
+
+        let preconditions = HttpPreconditions {
+            method: Method::DELETE,
+            preconditions: etag_base_normalized_conditional_headers(req.headers()),
+        };
 
+        if !is_recursive {
+            return Self::locked_delete(
+                storage,
+                res_uri,
+                preconditions,
+                credentials,
+                op_req_extensions,
+                None,
+            )
+            .await;
+        }
+
+        let mut resp = Self::locked_recursive_delete(
+            storage,
+            res_uri,
+            preconditions,
+            credentials,
+            op_req_extensions,
+        )
+        .await?;
+
+        resp.extensions
+            .insert_rec_item::<KAppliedRecursiveDelete>(());
+
+        Ok(resp)
+    }
+
+    /// Delete a resource conditionally, with exclusive lock on it.
+    ///
+    /// If `locked_container_uri` is given, exclusive lock on
+    /// that container must be already held by the caller, and
+    /// it will not be locked again.
+    async fn locked_delete(
+        storage: Arc<Storage>,
+        res_uri: SolidResourceUri,
+        preconditions: HttpPreconditions,
+        credentials: SgCredentials<Storage>,
+        op_req_extensions: ClonableTypedRecord,
+        locked_container_uri: Option<SolidResourceUri>,
+    ) -> Result<ResourceDeleteResponse<Storage::Repo>, ApiError> {
         // Get res lock name.
-        let res_lock_name = uri_policy.mutex_normal_res_uri_hash(&res_uri);
+        let res_lock_name = storage
+            .repo()
+            .uri_policy()
+            .mutex_normal_res_uri_hash(&res_uri);
 
         // Construct delete res future.
         let res_delete_fut = Box::pin(Self::conditional_delete(
             storage.clone(),
-            res_uri.clone(),
-            HttpPreconditions {
-                method: Method::DELETE,
-                preconditions: etag_base_normalized_conditional_headers(req.headers()),
-            },
-            req.extensions_mut()
-                .remove::<SgCredentials<Storage>>()
-                .unwrap_or_default(),
-            req.extensions_mut()
-                .remove_rec_item::<KOpReqExtensions>()
-                .unwrap_or_default(),
+            res_uri,
+            preconditions,
+            credentials,
+            op_req_extensions,
+            locked_container_uri,
         )) as BaseDeleteResponseFuture<Storage>;
 
-        // Perform delete with exclusive lock on request target.
+        // Perform delete with exclusive lock on the resource.
         storage
             .resource_locker()
             .poll_with_lock(res_delete_fut, Some(res_lock_name), LockKind::Exclusive)
             .await
+            .unwrap_or_else(|e| Err(lock_api_error(e)))
+    }
+
+    /// Delete the container with given uri recursively, with
+    /// exclusive lock on it held for the whole operation.
+    async fn locked_recursive_delete(
+        storage: Arc<Storage>,
+        container_uri: SolidResourceUri,
+        preconditions: HttpPreconditions,
+        credentials: SgCredentials<Storage>,
+        op_req_extensions: ClonableTypedRecord,
+    ) -> Result<ResourceDeleteResponse<Storage::Repo>, ApiError> {
+        // Get container lock name.
+        let container_lock_name = storage
+            .repo()
+            .uri_policy()
+            .mutex_normal_res_uri_hash(&container_uri);
+
+        // Construct recursive delete future.
+        let recursive_delete_fut = Box::pin(Self::recursive_delete(
+            storage.clone(),
+            container_uri,
+            preconditions,
+            credentials,
+            op_req_extensions,
+        )) as BaseDeleteResponseFuture<Storage>;
+
+        // Perform recursive delete with exclusive lock on the
+        // container.
+        storage
+            .resource_locker()
+            .poll_with_lock(
+                recursive_delete_fut,
+                Some(container_lock_name),
+                LockKind::Exclusive,
+            )
+            .await
+            .unwrap_or_else(|e| Err(lock_api_error(e)))
+    }
+
+    /// Delete the container with given uri along with all of
+    /// it's descendants. Exclusive lock on the container must
+    /// be held by the caller.
+    ///
+    /// Nothing is deleted, unless the container's
+    /// preconditions are satisfied, and deletion of the
+    /// container and of every descendant is allowed. For
+    /// that, it first checks the preconditions against the
+    /// state of the container before any deletion, resolves
+    /// the entire subtree, and dry runs the deletes over it.
+    /// Then it deletes the descendants depth-first, and
+    /// finally the container. Preconditions are not checked
+    /// again when deleting the container, as deleting the
+    /// descendants changes it's validators.
+    async fn recursive_delete(
+        storage: Arc<Storage>,
+        container_uri: SolidResourceUri,
+        preconditions: HttpPreconditions,
+        credentials: SgCredentials<Storage>,
+        op_req_extensions: ClonableTypedRecord,
+    ) -> Result<ResourceDeleteResponse<Storage::Repo>, ApiError> {
+        let dry_run_extensions = op_req_extensions.clone().with_rec_item::<KDryRunDelete>(());
+
+        // Check container's preconditions and access control.
+        Self::conditional_delete(
+            storage.clone(),
+            container_uri.clone(),
+            preconditions,
+            credentials.clone(),
+            dry_run_extensions.clone(),
+            Some(container_uri.clone()),
+        )
+        .await?;
+
+        // Resolve descendants, children after their parents.
+        let descendant_uris = Self::resolve_descendant_uris(
+            storage.clone(),
+            container_uri.clone(),
+            credentials.clone(),
+            op_req_extensions.clone(),
+        )
+        .await?;
+
+        // Check access control for deletion of all descendants.
+        let mut undeletable_uris = Vec::new();
+        let mut is_access_denied = false;
+
+        for descendant_uri in descendant_uris.iter() {
+            match Self::locked_delete(
+                storage.clone(),
+                descendant_uri.clone(),
+                Self::trivial_preconditions(),
+                credentials.clone(),
+                dry_run_extensions.clone(),
+                Some(container_uri.clone()),
+            )
+            .await
+            {
+                Ok(_) => {}
+                // Resource may have been deleted concurrently.
+                Err(e) if [StatusCode::NOT_FOUND, StatusCode::GONE].contains(&e.status()) => {}
+                Err(e) => {
+                    warn!(
+                        "Descendant {} cannot be deleted. Status: {}",
+                        descendant_uri.as_str(),
+                        e.status()
+                    );
+                    is_access_denied |= e.status() == StatusCode::FORBIDDEN;
+                    undeletable_uris.push(descendant_uri.clone());
+                }
+            }
+        }
+
+        if !undeletable_uris.is_empty() {
+            error!(
+                "Some of the descendants cannot be deleted. count: {}",
+                undeletable_uris.len()
+            );
+            return Err(Self::undeletable_descendants_error(
+                if is_access_denied {
+                    StatusCode::FORBIDDEN
+                } else {
+                    StatusCode::CONFLICT
+                },
+                &undeletable_uris,
+            ));
+        }
+
+        // Delete descendants, children before their parents.
+        for descendant_uri in descendant_uris.iter().rev() {
+            match Self::locked_delete(
+                storage.clone(),
+                descendant_uri.clone(),
+                Self::trivial_preconditions(),
+                credentials.clone(),
+                op_req_extensions.clone(),
+                Some(container_uri.clone()),
+            )
+            .await
+            {
+                Ok(_) => info!("Deleted descendant {}.", descendant_uri.as_str()),
+                // Resource may have been deleted concurrently.
+                Err(e) if [StatusCode::NOT_FOUND, StatusCode::GONE].contains(&e.status()) => {}
+                Err(e) => {
+                    warn!(
+                        "Descendant {} could not be deleted. Status: {}",
+                        descendant_uri.as_str(),
+                        e.status()
+                    );
+                    undeletable_uris.push(descendant_uri.clone());
+                }
+            }
+        }
+
+        if !undeletable_uris.is_empty() {
+            error!(
+                "Some of the descendants could not be deleted. count: {}",
+                undeletable_uris.len()
+            );
+            return Err(Self::undeletable_descendants_error(
+                StatusCode::CONFLICT,
+                &undeletable_uris,
+            ));
+        }
+
+        // Delete the container itself.
+        Self::conditional_delete(
+            storage,
+            container_uri.clone(),
+            Self::trivial_preconditions(),
+            credentials,
+            op_req_extensions,
+            Some(container_uri),
+        )
+        .await
+    }
+
+    /// Resolve uris of all descendants of the container with
+    /// given uri, such that each container precedes it's
+    /// descendants.
+    ///
+    /// Fails, if any container in the subtree cannot be
+    /// listed, if subtree is deeper than
+    /// [`MAX_RECURSIVE_DELETE_DEPTH`], or if it has more than
+    /// [`MAX_RECURSIVE_DELETE_DESCENDANTS`] descendants.
+    ///
+    /// Exclusive lock on the container must be held by the
+    /// caller.
+    async fn resolve_descendant_uris(
+        storage: Arc<Storage>,
+        container_uri: SolidResourceUri,
+        credentials: SgCredentials<Storage>,
+        op_req_extensions: ClonableTypedRecord,
+    ) -> Result<Vec<SolidResourceUri>, ApiError> {
+        let mut descendant_uris = Vec::new();
+        let mut pending_containers = vec![(container_uri.clone(), 1)];
+
+        while let Some((pending_container_uri, depth)) = pending_containers.pop() {
+            if depth > MAX_RECURSIVE_DELETE_DEPTH {
+                error!("Container hierarchy is too deep to delete recursively.");
+                return Err(ApiError::builder(StatusCode::CONFLICT)
+                    .message(format!(
+                        "Container hierarchy is deeper than {} levels, and cannot be deleted recursively.",
+                        MAX_RECURSIVE_DELETE_DEPTH
+                    ))
+                    .finish());
+            }
+
+            // Target container is already locked.
+            let is_locked = pending_container_uri == container_uri;
+
+            for contained_uri in Self::resolve_contained_uris(
+                storage.clone(),
+                pending_container_uri,
+                is_locked,
+                credentials.clone(),
+                op_req_extensions.clone(),
+            )
+            .await?
+            {
+                if descendant_uris.len() >= MAX_RECURSIVE_DELETE_DESCENDANTS {
+                    error!("Container has too many descendants to delete recursively.");
+                    return Err(ApiError::builder(StatusCode::CONFLICT)
+                        .message(format!(
+                            "Container has more than {} descendants, and cannot be deleted recursively.",
+                            MAX_RECURSIVE_DELETE_DESCENDANTS
+                        ))
+                        .finish());
+                }
+                if contained_uri.as_str().ends_with('/') {
+                    pending_containers.push((contained_uri.clone(), depth + 1));
+                }
+                descendant_uris.push(contained_uri);
+            }
+        }
+
+        Ok(descendant_uris)
+    }
+
+    /// Get trivial preconditions for deleting descendants.
+    #[inline]
+    fn trivial_preconditions() -> HttpPreconditions {
+        HttpPreconditions {
+            method: Method::DELETE,
+            preconditions: HeaderMap::new(),
+        }
+    }
+
+    /// Get error for undeletable descendants.
+    fn undeletable_descendants_error(
+        status: StatusCode,
+        undeletable_uris: &[SolidResourceUri],
+    ) -> ApiError {
+        ApiError::builder(status)
+            .message("Some of the contained resources cannot be deleted.")
+            .field(
+                "undeletable_resources",
+                undeletable_uris
+                    .iter()
+                    .map(|uri| uri.as_str().to_owned())
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+
+    /// Resolve uris of resources contained in the container
+    /// with given uri, by reading it's containment triples
+    /// with shared lock on it, unless it is already locked.
+    async fn resolve_contained_uris(
+        storage: Arc<Storage>,
+        container_uri: SolidResourceUri,
+        is_locked: bool,
+        credentials: SgCredentials<Storage>,
+        op_req_extensions: ClonableTypedRecord,
+    ) -> Result<Vec<SolidResourceUri>, ApiError> {
+        let res_lock_name = (!is_locked).then(|| {
+            storage
+                .repo()
+                .uri_policy()
+                .mutex_normal_res_uri_hash(&container_uri)
+        });
+
+        let read_storage = storage.clone();
+        let read_fut = Box::pin(async move {
+            let status_token: SgResourceStatusToken<Storage> =
+                resolve_status_token(read_storage.as_ref(), container_uri).await?;
+
+            // Container may have been deleted concurrently.
+            let er_token = match status_token.existing_represented() {
+                Some(er_token) => er_token,
+                None => return Ok(None),
+            };
+
+            SgResourceReader::<Storage>::default()
+                .ready()
+                .and_then(|svc| {
+                    svc.call(ResourceReadRequest {
+                        tokens: ResourceReadTokenSet::new(er_token),
+                        rep_preferences: RepresentationPreferences {
+                            container_rep_preference:
+                                ContainerRepresentationPreference::Containment,
+                            ..RepresentationPreferences::new_light()
+                        },
+                        rep_conneg_params: Default::default(),
+                        preconditions: Box::new(()),
+                        credentials,
+                        extensions: op_req_extensions,
+                    })
+                })
+                .await
+                .map(Some)
+                .map_err(|e| {
+                    error!("Error in reading container. Error:\n {}", e);
+                    Self::map_problem(e)
+                })
+        });
+
+        let resp = match storage
+            .resource_locker()
+            .poll_with_lock(read_fut, res_lock_name, LockKind::Shared)
+            .await
+            .map_err(lock_api_error)??
+        {
+            Some(resp) => resp,
+            None => return Ok(Vec::new()),
+        };
+
+        let (container_slot, rep) = resp.state.into_parts();
+        let rep = rep.into_basic().into_streaming();
+
+        // Collect rep data. Rdf docs are textual.
+        let data = rep
+            .data
+            .stream
+            .try_fold(Vec::new(), |mut data, bytes| async move {
+                data.extend_from_slice(&bytes);
+                Ok(data)
+            })
+            .await
+            .map_err(|e| {
+                error!("Error in reading container rep data. Error:\n {}", e);
+                INTERNAL_ERROR.new_api_error_builder(StatusCode::INTERNAL_SERVER_ERROR)
+            })
+            .and_then(|data| {
+                String::from_utf8(data).map_err(|_| {
+                    error!("Container rep data is not valid utf8.");
+                    INTERNAL_ERROR.new_api_error_builder(StatusCode::INTERNAL_SERVER_ERROR)
+                })
+            })
+            .map_err(|builder| builder.finish())?;
+
+        let quads = BasicRepresentation {
+            metadata: rep.metadata,
+            data: BytesInmem::from(data),
+            base_uri: rep.base_uri,
+        }
+        .try_parse_quads::<HashSet<ArcQuad>>(CONTAINER_REP_PARSER_FACTORIES.clone())
+        .await
+        .and_then(|result| result.ok())
+        .ok_or_else(|| {
+            error!("Error in parsing container rep.");
+            INTERNAL_ERROR
+                .new_api_error_builder(StatusCode::INTERNAL_SERVER_ERROR)
+                .finish()
+        })?
+        .into_inner();
+
+        let container_uri_str = container_slot.id().uri.as_str();
+
+        Ok(quads
+            .iter()
+            .filter(|quad| {
+                Term::eq(quad.p(), ns::ldp::contains)
+                    && quad.s().iri().map(|iri| iri.as_str() == container_uri_str) == Some(true)
+            })
+            .filter_map(|quad| SolidResourceUri::try_new_from(quad.o().iri()?.as_str()).ok())
+            .collect())
     }
 
     /// Delete a resource conditionally.
+    ///
+    /// If `locked_container_uri` is given, exclusive lock on
+    /// that container must be already held by the caller, and
+    /// it will not be locked again as host resource.
     #[tracing::instrument(
         skip_all,
         name = "BaseDeleteService:conditional_delete",
@@ -156,6 +656,7 @@ where
         preconditions: HttpPreconditions,
         credentials: SgCredentials<Storage>,
         op_req_extensions: ClonableTypedRecord,
+        locked_container_uri: Option<SolidResourceUri>,
     ) -> Result<ResourceDeleteResponse<Storage::Repo>, ApiError> {
         // Resolve resource status token.
         let status_token: SgResourceStatusToken<Storage> =
@@ -178,18 +679,23 @@ where
                     credentials,
                     extensions: op_req_extensions,
                 });
-                // If resource has a host resource, then
-                // proceed with exclusive lock on it.
-                if let Some(slot_rev_link) = res_slot.slot_rev_link() {
+                // If resource has a host resource, that is not
+                // already locked, then proceed with exclusive
+                // lock on it.
+                if let Some(slot_rev_link) = res_slot
+                    .slot_rev_link()
+                    .filter(|link| Some(&link.target) != locked_container_uri.as_ref())
+                {
                     let host_res_lock_name = storage
                         .repo()
                         .uri_policy()
                         .mutex_normal_res_uri_hash(&slot_rev_link.target);
 
-                    fut = storage.resource_locker().poll_with_lock(
-                        fut,
-                        Some(host_res_lock_name),
-                        LockKind::Exclusive,
+                    fut = Box::pin(
+                        storage
+                            .resource_locker()
+                            .poll_with_lock(fut, Some(host_res_lock_name), LockKind::Exclusive)
+                            .map(|result| result.unwrap_or_else(|e| Err(lock_problem(e)))),
                     )
                 }
 
@@ -239,6 +745,10 @@ where
         else if UNSUPPORTED_OPERATION.is_type_of(&problem) {
             error!("Unsupported operation.");
             ApiError::builder(StatusCode::METHOD_NOT_ALLOWED)
+        }
+        // If resource lock is unavailable.
+        else if RESOURCE_LOCK_UNAVAILABLE.is_type_of(&problem) {
+            RESOURCE_LOCK_UNAVAILABLE.new_api_error_builder(StatusCode::SERVICE_UNAVAILABLE)
         } else {
             INTERNAL_ERROR.new_api_error_builder(StatusCode::INTERNAL_SERVER_ERROR)
         }
@@ -257,3 +767,16 @@ where
         .finish()
     }
 }
+
+/// Check if `Prefer: delete=recursive` preference is
+/// expressed in given headers.
+pub fn is_recursive_delete_preferred(headers: &HeaderMap) -> bool {
+    headers
+        .typed_get::<Prefer>()
+        .map(|prefer| {
+            prefer.preferences.iter().any(|p| {
+                p.token_name == *TOKEN_NAME_DELETE && p.token_value == *TOKEN_VALUE_RECURSIVE
+            })
+        })
+        .unwrap_or_default()
+}
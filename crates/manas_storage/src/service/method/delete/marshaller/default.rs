@@ -4,15 +4,26 @@
 
 use std::{convert::Infallible, sync::Arc, task::Poll};
 
+use headers::HeaderMapExt;
 use http::{Response, StatusCode};
 use http_api_problem::ApiError;
 use manas_http::service::BoxHttpResponseFuture;
-use manas_http::{body::Body, problem::ApiErrorExt};
+use manas_http::{
+    body::Body,
+    header::preference_applied::{AppliedPref, PreferenceApplied},
+    problem::ApiErrorExt,
+};
 use manas_repo::service::resource_operator::deleter::ResourceDeleteResponse;
 use tower::Service;
+use typed_record::TypedRecord;
+use vec1::smallvec_v1::SmallVec1;
 
 use crate::{
-    service::method::common::snippet::authorization::attach_authorization_context, SolidStorage,
+    service::method::{
+        common::snippet::authorization::attach_authorization_context,
+        delete::base::{KAppliedRecursiveDelete, TOKEN_NAME_DELETE, TOKEN_VALUE_RECURSIVE},
+    },
+    SolidStorage,
 };
 
 /// Configuration for [DefaultBaseDeleteResponseMarshaller`].
@@ -91,9 +102,27 @@ where
     Storage: SolidStorage,
 {
     /// Marshal `BaseDelete`Service success response.
-    pub fn marshal_ok(&self, _response: ResourceDeleteResponse<Storage::Repo>) -> Response<Body> {
+    pub fn marshal_ok(&self, response: ResourceDeleteResponse<Storage::Repo>) -> Response<Body> {
         // Create builder, set status code to 204.
-        let builder = Response::builder().status(StatusCode::NO_CONTENT);
+        let mut builder = Response::builder().status(StatusCode::NO_CONTENT);
+
+        // Set Preference-Applied, if recursive delete
+        // preference is applied.
+        if response
+            .extensions
+            .get_rv::<KAppliedRecursiveDelete>()
+            .is_some()
+        {
+            builder
+                .headers_mut()
+                .expect("Must be valid")
+                .typed_insert(PreferenceApplied {
+                    applied_prefs: SmallVec1::new(AppliedPref::new(
+                        TOKEN_NAME_DELETE.clone(),
+                        TOKEN_VALUE_RECURSIVE.clone(),
+                    )),
+                });
+        }
 
         // TODO serialize the response.
 
@@ -3,19 +3,22 @@
 //!
 
 use std::{
+    num::NonZeroUsize,
     sync::Arc,
     task::{Context, Poll},
+    time::{Duration, UNIX_EPOCH},
 };
 
 use dyn_problem::{Problem, ProblemBuilderExt};
-use futures::{future::BoxFuture, TryFutureExt};
+use futures::{future::BoxFuture, StreamExt, TryFutureExt};
 use headers::{HeaderMap, HeaderMapExt};
-use http::{request::Parts, Method, Request, StatusCode};
+use http::{request::Parts, Method, Request, StatusCode, Uri};
 use http_api_problem::ApiError;
 use manas_access_control::model::KResolvedAccessControl;
 use manas_http::body::Body;
 use manas_http::{
     conditional_req::PreconditionsResolvedAction,
+    header::memento::AcceptDatetime,
     representation::impl_::{
         basic::BasicRepresentation, binary::BinaryRepresentation,
         common::data::bytes_stream::BytesStream,
@@ -33,7 +36,9 @@ use manas_repo::{
         reader::{
             rep_preferences::{
                 range_negotiator::impl_::ConditionalRangeNegotiator,
-                ContainerRepresentationPreference, RepresentationPreferences,
+                version::{RepVersionPreference, MEMENTO_QUERY_PARAM, TIMEMAP_QUERY},
+                ContainerPagePreference, ContainerRepresentationPreference,
+                KAppliedContainerRepPref, RepresentationPreferences,
             },
             ConnegParams, ResourceReadRequest, ResourceReadResponse, ResourceReadTokenSet,
             RANGE_NOT_SATISFIABLE,
@@ -42,18 +47,23 @@ use manas_repo::{
     RepoExt,
 };
 use name_locker::{LockKind, NameLocker};
+use percent_encoding::percent_decode_str;
 use tower::{Service, ServiceExt};
 use tracing::{debug, error, info};
-use typed_record::TypedRecord;
+use typed_record::{TypedRecord, TypedRecordKey};
 
 use crate::{
     service::method::{
-        common::snippet::{
-            op_req::KOpReqExtensions,
-            req_headers::{
-                etag_base_normalized_conditional_headers, resolve_preconditions_eval_status,
+        common::{
+            problem::lock_api_error,
+            snippet::{
+                op_req::KOpReqExtensions,
+                req_headers::{
+                    etag_base_normalized_conditional_headers, resolve_container_rep_preference,
+                    resolve_preconditions_eval_status,
+                },
+                status_token::resolve_status_token,
             },
-            status_token::resolve_status_token,
         },
         get::base::error_context::KExistingMutexResourceUri,
     },
@@ -62,6 +72,37 @@ use crate::{
 
 pub mod error_context;
 
+/// Name of the query param, that specifies container page
+/// cursor in request target.
+pub const CONTAINER_PAGE_QUERY_PARAM: &str = "page";
+
+/// A [`TypedRecordKey`] for recording max container page size
+/// for a storage.
+///
+/// If set, container representations will be paged, and a
+/// page can be requested with [`CONTAINER_PAGE_QUERY_PARAM`]
+/// in request target.
+#[derive(Debug, Clone, Copy)]
+pub struct KMaxContainerPageSize;
+
+impl TypedRecordKey for KMaxContainerPageSize {
+    type Value = NonZeroUsize;
+}
+
+/// A [`TypedRecordKey`] for recording whether memento
+/// negotiation is enabled for a storage.
+///
+/// If set to `true`, mementos of resources can be requested
+/// with `Accept-Datetime` header, or with
+/// [`MEMENTO_QUERY_PARAM`] in request target, and their
+/// timemaps with [`TIMEMAP_QUERY`].
+#[derive(Debug, Clone, Copy)]
+pub struct KMementoEnabled;
+
+impl TypedRecordKey for KMementoEnabled {
+    type Value = bool;
+}
+
 /// A service that handles conditional GET request over a
 /// solid resource by resolving it's metadata and
 /// selected-representation in concurrent safe way.
@@ -147,6 +188,14 @@ where
             .get::<NormalAbsoluteHttpUri>()
             .expect("BaseGetService must be called after ensuring resource uri is normal absolute http uri.").clone();
 
+        // Resolve version preference, if any.
+        let (res_uri, version_preference) =
+            Self::resolve_version_preference(storage.as_ref(), res_uri, &req);
+
+        // Resolve container page cursor, if any.
+        let (res_uri, container_page_cursor) =
+            Self::resolve_container_page_cursor(storage.as_ref(), res_uri, req.uri());
+
         // Get res lock name.
         let res_lock_name = storage
             .repo()
@@ -159,11 +208,119 @@ where
         storage
             .resource_locker()
             .poll_with_lock(
-                Self::conditional_get(storage.clone(), res_uri, res_lock_name.clone(), req_parts),
+                Self::conditional_get(
+                    storage.clone(),
+                    res_uri,
+                    container_page_cursor,
+                    version_preference,
+                    res_lock_name.clone(),
+                    req_parts,
+                ),
                 Some(res_lock_name.clone()),
                 LockKind::Shared,
             )
             .await
+            .unwrap_or_else(|e| Err(lock_api_error(e)))
+    }
+
+    /// Resolve container page cursor from request target, if
+    /// container paging is enabled for the storage.
+    ///
+    /// Page query param is recognized only if it is the sole
+    /// query param of a container target. Returns resource uri
+    /// with page query stripped, along with resolved cursor.
+    fn resolve_container_page_cursor(
+        storage: &Storage,
+        res_uri: NormalAbsoluteHttpUri,
+        req_uri: &Uri,
+    ) -> (NormalAbsoluteHttpUri, Option<String>) {
+        // Return, if paging is not enabled.
+        if storage
+            .extensions()
+            .get_rv::<KMaxContainerPageSize>()
+            .is_none()
+        {
+            return (res_uri, None);
+        }
+
+        let cursor = req_uri
+            .query()
+            .and_then(|query| query.strip_prefix(CONTAINER_PAGE_QUERY_PARAM))
+            .and_then(|param| param.strip_prefix('='))
+            .filter(|encoded| !encoded.contains('&'))
+            .and_then(|encoded| percent_decode_str(encoded).decode_utf8().ok())
+            .filter(|cursor| !cursor.is_empty());
+
+        let (stripped_uri_str, _) = res_uri
+            .as_str()
+            .split_once('?')
+            .unwrap_or((res_uri.as_str(), ""));
+
+        match cursor {
+            // Page query applies only to containers.
+            Some(cursor) if stripped_uri_str.ends_with('/') => (
+                NormalAbsoluteHttpUri::try_new_from(stripped_uri_str).expect("Must be valid"),
+                Some(cursor.into_owned()),
+            ),
+            _ => (res_uri, None),
+        }
+    }
+
+    /// Resolve version preference from request, if memento
+    /// negotiation is enabled for the storage.
+    ///
+    /// Memento and timemap queries are recognized only if they
+    /// are the sole query of the target. Otherwise
+    /// `Accept-Datetime` header is considered. Returns resource
+    /// uri with version query stripped, along with resolved
+    /// preference.
+    fn resolve_version_preference(
+        storage: &Storage,
+        res_uri: NormalAbsoluteHttpUri,
+        req: &Request<Body>,
+    ) -> (NormalAbsoluteHttpUri, RepVersionPreference) {
+        // Return, if memento negotiation is not enabled.
+        if !storage
+            .extensions()
+            .get_rv::<KMementoEnabled>()
+            .copied()
+            .unwrap_or_default()
+        {
+            return (res_uri, RepVersionPreference::Current);
+        }
+
+        let query_preference = req.uri().query().and_then(|query| {
+            if query == TIMEMAP_QUERY {
+                return Some(RepVersionPreference::TimeMap);
+            }
+            query
+                .strip_prefix(MEMENTO_QUERY_PARAM)
+                .and_then(|param| param.strip_prefix('='))
+                .and_then(|secs| secs.parse::<u64>().ok())
+                .map(|secs| {
+                    RepVersionPreference::AtDatetime(UNIX_EPOCH + Duration::from_secs(secs))
+                })
+        });
+
+        if let Some(preference) = query_preference {
+            let (stripped_uri_str, _) = res_uri
+                .as_str()
+                .split_once('?')
+                .unwrap_or((res_uri.as_str(), ""));
+
+            return (
+                NormalAbsoluteHttpUri::try_new_from(stripped_uri_str).expect("Must be valid"),
+                preference,
+            );
+        }
+
+        (
+            res_uri,
+            req.headers()
+                .typed_get::<AcceptDatetime>()
+                .map(|accept_datetime| RepVersionPreference::AtDatetime(accept_datetime.0))
+                .unwrap_or_default(),
+        )
     }
 
     /// Map the inner problem to api error
@@ -209,6 +366,8 @@ where
     async fn conditional_get(
         storage: Arc<Storage>,
         res_uri: NormalAbsoluteHttpUri,
+        container_page_cursor: Option<String>,
+        version_preference: RepVersionPreference,
         rep_stream_lock_name: String,
         mut req_parts: Parts,
     ) -> Result<BaseGetResponse<Storage>, ApiError> {
@@ -251,23 +410,37 @@ where
             }
         }?;
 
+        // Resolve container rep preference from `Prefer` header.
+        let preferred_container_rep_pref = resolve_container_rep_preference(&req_parts.headers);
+
         // Construct resource state request.
         let res_state_request = ResourceReadRequest {
             tokens: ResourceReadTokenSet::new(er_token),
             rep_preferences: RepresentationPreferences {
-                // For containers, request full representation.
-                // TODO should honour `Prefer` header.
+                // For containers, request preferred representation,
+                // defaulting to full representation.
                 container_rep_preference: if req_parts.method == Method::HEAD {
                     ContainerRepresentationPreference::Minimal
                 } else {
-                    ContainerRepresentationPreference::All
+                    preferred_container_rep_pref.unwrap_or(ContainerRepresentationPreference::All)
                 },
 
+                // For containers, request a page, if paging is enabled.
+                container_page_preference: storage
+                    .extensions()
+                    .get_rv::<KMaxContainerPageSize>()
+                    .map(|max_page_size| ContainerPagePreference {
+                        cursor: container_page_cursor,
+                        max_page_size: *max_page_size,
+                    }),
+
                 // For non-containers, use conditional range negotiator.
                 non_container_rep_range_negotiator: Box::new(ConditionalRangeNegotiator {
                     range: req_parts.headers.typed_get(),
                     if_range: base_normal_conditional_headers.typed_get(),
                 }),
+
+                version_preference,
             },
             rep_conneg_params: ConnegParams {
                 accept: req_parts.headers.typed_get(),
@@ -291,7 +464,7 @@ where
         let mut reader = SgResourceReader::<Storage>::default();
 
         // Query resource state.
-        let resp = reader
+        let mut resp = reader
             .ready()
             .and_then(|svc| svc.call(res_state_request))
             .inspect_err(|e| error!("Error in resolving resource state. Error:\n {}", e))
@@ -319,6 +492,15 @@ where
 
         debug!("Preconditions satisfied");
 
+        // Record applied container rep preference, if any.
+        if let (Some(pref), true) = (
+            preferred_container_rep_pref,
+            resp.state.slot.is_container_slot(),
+        ) {
+            resp.extensions
+                .insert_rec_item::<KAppliedContainerRepPref>(pref);
+        }
+
         // Lock resource name throughout rep content stream lifetime, with shared lock.
         // NOTE: must set timeout
         // TODO should provide map_data method.
@@ -328,10 +510,15 @@ where
                 metadata: rep.metadata,
                 base_uri: rep.base_uri,
                 data: BytesStream {
-                    stream: storage.resource_locker().poll_read_with_lock(
-                        rep.data.stream,
-                        Some(rep_stream_lock_name),
-                        LockKind::Shared,
+                    stream: Box::pin(
+                        storage
+                            .resource_locker()
+                            .poll_read_with_lock(
+                                rep.data.stream,
+                                Some(rep_stream_lock_name),
+                                LockKind::Shared,
+                            )
+                            .map(|result| result.unwrap_or_else(|e| Err(e.into()))),
                     ),
                     size_hint: rep.data.size_hint,
                 },
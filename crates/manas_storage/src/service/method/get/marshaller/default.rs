@@ -5,35 +5,64 @@
 use std::{convert::Infallible, sync::Arc, task::Poll};
 
 use headers::{AcceptRanges, ContentLength, ContentType, ETag, HeaderMapExt};
-use http::{header::VARY, Response, StatusCode};
+use http::{
+    header::{CONTENT_LOCATION, VARY},
+    Response, StatusCode,
+};
 use http_api_problem::ApiError;
 use if_chain::if_chain;
-use iri_string::types::UriReferenceStr;
 use manas_access_control::model::KResolvedAccessControl;
 use manas_http::{body::Body, problem::HttpApiProblemExt};
 use manas_http::{
-    header::link::{Link, LinkRel, LinkTarget, LinkValue},
+    header::{
+        link::{Link, LinkValue},
+        memento::MementoDatetime,
+        prefer::{TOKEN_NAME_RETURN, TOKEN_VALUE_REPRESENTATION},
+        preference_applied::{AppliedPref, PreferenceApplied},
+    },
     representation::{
         metadata::{KCompleteContentLength, KContentRange, KDerivedETag, KLastModified},
         Representation, RepresentationExt,
     },
     service::BoxHttpResponseFuture,
 };
-use manas_repo::service::resource_operator::common::preconditions::KEvaluatedRepValidators;
-use manas_space::{resource::slot::SolidResourceSlot, SolidStorageSpace};
+use manas_repo::service::resource_operator::{
+    common::preconditions::KEvaluatedRepValidators,
+    reader::rep_preferences::{
+        version::{memento_uri, timemap_uri, KRepVersion},
+        KAppliedContainerRepPref, KContainerRepPage,
+    },
+};
+use manas_space::resource::slot::SolidResourceSlot;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use rdf_vocabularies::ns;
 use tower::Service;
 use typed_record::TypedRecord;
+use vec1::smallvec_v1::SmallVec1;
 
 use crate::{
     policy::method::MethodPolicyExt,
     service::method::{
-        common::snippet::authorization::attach_authorization_context,
-        get::base::{error_context::KExistingMutexResourceUri, BaseGetResponse},
+        common::snippet::{
+            authorization::attach_authorization_context,
+            links::{push_ldp_type_links, push_solid_links},
+        },
+        get::base::{
+            error_context::KExistingMutexResourceUri, BaseGetResponse, CONTAINER_PAGE_QUERY_PARAM,
+        },
     },
     SgCredentials, SolidStorage,
 };
 
+/// Encode set for page cursors in query. Unreserved
+/// characters are not encoded, so that page links are
+/// already normal, and are not redirected.
+const PAGE_CURSOR_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
 /// Configuration fr [`DefaultBaseGetResponseMarshaller`].
 #[derive(Debug, Clone, Default)]
 pub struct DefaultBaseGetResponseMarshallerConfig {
@@ -175,93 +204,95 @@ where
         // List of links.
         let mut links = Vec::<LinkValue>::new();
 
-        // Set headers specified by ldp
+        // Set ldp resource type links.
+        push_ldp_type_links(&mut links, res_slot);
+
+        // If rep is a container page, set paging links.
+        if let Some(page) = rep.metadata().get_rv::<KContainerRepPage>() {
+            let res_uri_str = res_slot.id().uri.as_str();
 
-        // Set ldp resource types.
-        links.push(
-            LinkValue::try_new_basic(ns::ldp::Resource.to_string(), "type").expect("Must be valid"),
-        );
-        // If is container.
-        if res_slot.is_container_slot() {
             links.push(
-                LinkValue::try_new_basic(ns::ldp::BasicContainer.to_string(), "type")
-                    .expect("Must be valid"),
+                LinkValue::try_new_basic(ns::ldp::Page.to_string(), "type").expect("Must be valid"),
             );
+            links.push(LinkValue::try_new_basic(res_uri_str, "first").expect("Must be valid"));
+
+            for (cursor, rel) in [(&page.prev_cursor, "prev"), (&page.next_cursor, "next")] {
+                if let Some(cursor) = cursor {
+                    links.push(
+                        LinkValue::try_new_basic(
+                            format!(
+                                "{}?{}={}",
+                                res_uri_str,
+                                CONTAINER_PAGE_QUERY_PARAM,
+                                utf8_percent_encode(cursor, PAGE_CURSOR_ENCODE_SET)
+                            ),
+                            rel,
+                        )
+                        .expect("Must be valid"),
+                    );
+                }
+            }
         }
 
-        // Set headers specified by solid protocol.
-
-        let space = res_slot.space();
-
-        // Set slot reverse links.
-        if let Some(slot_rev_link) = res_slot.slot_rev_link() {
-            links.push(LinkValue::new(
-                // Target as resource itself
-                LinkTarget(AsRef::<UriReferenceStr>::as_ref(&*res_slot.id().uri).to_owned()),
-                LinkRel::new(slot_rev_link.rev_rel_type.clone().into()),
-                // Host resource as anchor.
-                Some(AsRef::<UriReferenceStr>::as_ref(&*slot_rev_link.target).to_owned()),
-            ))
-        }
-
-        // Push storage description link.
-        // Req: Servers MUST include the Link header with
-        // rel="http://www.w3.org/ns/solid/terms#storageDescription"
-        // targeting the URI of the storage description resource
-        // in the response of HTTP GET, HEAD and OPTIONS
-        // requests targeting a resource in a storage.
-        links.push(
-            LinkValue::try_new_basic(
-                space.description_res_uri().as_str(),
-                "http://www.w3.org/ns/solid/terms#storageDescription",
-            )
-            .expect("Must be valid"),
-        );
-
-        // If resource is storage root container,
-        if res_slot.is_root_slot() {
-            // Push storage type link.
-            // Req: Servers exposing the storage resource MUST
-            // advertise by including the HTTP Link header with rel="type"
-            // targeting http://www.w3.org/ns/pim/space#Storage
-            // when responding to storage’s request URI.
-            links.push(
-                LinkValue::try_new_basic(ns::pim::Storage.to_string(), "type")
-                    .expect("Must be valid"),
-            );
+        // If resource is versioned, set memento links.
+        let rep_version = rep.metadata().get_rv::<KRepVersion>().copied();
+        if rep_version.is_some() {
+            let res_uri_str = res_slot.id().uri.as_str();
 
-            // Push storage owner link.
-            // Req: When a server wants to advertise the owner
-            // of a storage, the server MUST include the Link
-            // header with rel="http://www.w3.org/ns/solid/terms#owner"
-            // targeting the URI of the owner in the response of
-            // HTTP HEAD or GET requests targeting the root container.
+            for rel in ["original", "timegate"] {
+                links.push(LinkValue::try_new_basic(res_uri_str, rel).expect("Must be valid"));
+            }
             links.push(
-                LinkValue::try_new_basic(space.owner_id().as_str(), ns::solid::owner.to_string())
+                LinkValue::try_new_basic(timemap_uri(res_uri_str), "timemap")
                     .expect("Must be valid"),
             );
         }
 
-        // Push links to auxiliary resources.
-        // Req: Servers MUST advertise auxiliary resources
-        // associated with a subject resource by responding to
-        // HEAD and GET requests by including the HTTP Link
-        // header with the rel parameter [RFC8288].
-        for item in &base_response.aux_links_index {
-            links.push(item.clone().into())
-        }
+        // Set links specified by solid protocol.
+        push_solid_links(&mut links, res_slot, &base_response.aux_links_index);
 
         // Set link header with collected links.
         headers.typed_insert(Link { values: links });
 
+        // Set Preference-Applied, if container rep preference
+        // is applied.
+        if base_response
+            .extensions
+            .get_rv::<KAppliedContainerRepPref>()
+            .is_some()
+        {
+            headers.typed_insert(PreferenceApplied {
+                applied_prefs: SmallVec1::new(AppliedPref::new(
+                    TOKEN_NAME_RETURN.clone(),
+                    TOKEN_VALUE_REPRESENTATION.clone(),
+                )),
+            });
+        }
+
         // TODO customizable?
         headers.insert(
             VARY,
-            "Accept, Authorization, Origin"
-                .parse()
-                .expect("Must be valid"),
+            if rep_version.is_some() {
+                "Accept, Authorization, Origin, Prefer, Accept-Datetime"
+            } else {
+                "Accept, Authorization, Origin, Prefer"
+            }
+            .parse()
+            .expect("Must be valid"),
         );
 
+        // Set Memento-Datetime and Content-Location, if rep is
+        // a memento.
+        if let Some(memento_datetime) = rep_version.and_then(|v| v.memento_datetime) {
+            headers.typed_insert(MementoDatetime(memento_datetime));
+            headers.insert(
+                CONTENT_LOCATION,
+                memento_uri(res_slot.id().uri.as_str(), memento_datetime)
+                    .parse()
+                    .expect("Must be valid"),
+            );
+        }
+
         // Set Wac-Allow.
         if let Some(resolved_acl) = base_response
             .extensions
@@ -339,7 +370,7 @@ where
             // TODO customizable?
             response.headers_mut().insert(
                 VARY,
-                "Accept, Authorization, Origin"
+                "Accept, Authorization, Origin, Prefer"
                     .parse()
                     .expect("Must be valid"),
             );
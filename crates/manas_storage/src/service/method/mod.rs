@@ -19,6 +19,7 @@ pub mod common;
 pub mod delete;
 pub mod get;
 pub mod head;
+pub mod options;
 pub mod post;
 pub mod put_or_patch;
 
@@ -0,0 +1,198 @@
+//! I define an implementation of [`BaseMethodService`](super::super::BaseMethodService)
+//! for handling `OPTIONS` method over solid resources.
+//!
+
+use std::{sync::Arc, task::Poll};
+
+use futures::future::BoxFuture;
+use http::{Method, Request};
+use http_api_problem::ApiError;
+use manas_http::{body::Body, uri::invariant::NormalAbsoluteHttpUri};
+use manas_repo::{
+    policy::uri::RepoUriPolicy,
+    service::resource_operator::{
+        common::{
+            problem::ACCESS_DENIED,
+            status_token::{ExistingResourceToken, ResourceStatusToken},
+        },
+        reader::rep_preferences::RepresentationPreferences,
+    },
+    RepoExt,
+};
+use name_locker::{LockKind, NameLocker};
+use tower::Service;
+use tracing::{error, info};
+
+use crate::{
+    service::method::common::{
+        problem::lock_api_error, snippet::status_token::resolve_status_token,
+    },
+    SgCredentials, SgRepo, SgResourceStatusToken, SolidStorage,
+};
+
+/// A service that handles non-preflight `OPTIONS` request
+/// over a resource in solid compatible, concurrent safe way.
+///
+/// It resolves status token of the target resource, from
+/// which communication options are then advertised.
+///
+/// Options specific to an existing resource are disclosed,
+/// only if the requester passes the same access check as
+/// for a `HEAD` request over it. Otherwise, generic options
+/// are advertised, as if the resource doesn't exist.
+#[derive(Debug)]
+pub struct BaseOptionsService<Storage> {
+    /// Storage.
+    pub storage: Arc<Storage>,
+}
+
+impl<Storage> Clone for BaseOptionsService<Storage> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            storage: self.storage.clone(),
+        }
+    }
+}
+
+/// Type of successful responses returned by
+/// [`BaseOptionsService`].
+#[derive(Debug)]
+pub struct BaseOptionsResponse<Storage: SolidStorage> {
+    /// Status token of the target resource. It is `None`, if
+    /// the requester is not allowed to know resource specific
+    /// options.
+    pub status_token: Option<SgResourceStatusToken<Storage>>,
+}
+
+/// Type of future returned by [`BaseOptionsService`].
+pub type BaseOptionsResponseFuture<Storage> =
+    BoxFuture<'static, Result<BaseOptionsResponse<Storage>, ApiError>>;
+
+impl<Storage> Service<Request<Body>> for BaseOptionsService<Storage>
+where
+    Storage: SolidStorage,
+{
+    type Response = BaseOptionsResponse<Storage>;
+
+    type Error = ApiError;
+
+    type Future = BaseOptionsResponseFuture<Storage>;
+
+    #[inline]
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // Will be always ready.
+        Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    #[tracing::instrument(skip_all, name = "BaseOptionsService::call")]
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        Box::pin(Self::apply(self.storage.clone(), req))
+    }
+}
+
+impl<Storage> BaseOptionsService<Storage>
+where
+    Storage: SolidStorage,
+{
+    /// Create a new [`BaseOptionsService`].
+    #[inline]
+    pub fn new(storage: Arc<Storage>) -> Self {
+        Self { storage }
+    }
+
+    /// Apply OPTIONS method.
+    async fn apply(
+        storage: Arc<Storage>,
+        mut req: Request<Body>,
+    ) -> Result<BaseOptionsResponse<Storage>, ApiError> {
+        // Ensure method is OPTIONS
+        if req.method() != Method::OPTIONS {
+            panic!("BaseOptionsService must be routed to for only OPTIONS requests");
+        }
+
+        // Get normal resource uri.
+        let res_uri = req
+            .extensions()
+            .get::<NormalAbsoluteHttpUri>()
+            .expect("BaseOptionsService must be called after confirming resource uri is normal absolute http uri.").clone();
+
+        // Get res lock name.
+        let res_lock_name = storage
+            .repo()
+            .uri_policy()
+            .mutex_normal_res_uri_hash(&res_uri);
+
+        let credentials = req
+            .extensions_mut()
+            .remove::<SgCredentials<Storage>>()
+            .unwrap_or_default();
+
+        let status_token_storage = storage.clone();
+
+        // Construct status token resolution future.
+        let status_token_fut = Box::pin(async move {
+            Ok(BaseOptionsResponse {
+                status_token: Self::resolve_disclosable_status_token(
+                    status_token_storage.as_ref(),
+                    res_uri,
+                    credentials,
+                )
+                .await?,
+            })
+        }) as BaseOptionsResponseFuture<Storage>;
+
+        // Resolve status token with shared lock on the resource.
+        storage
+            .resource_locker()
+            .poll_with_lock(status_token_fut, Some(res_lock_name), LockKind::Shared)
+            .await
+            .unwrap_or_else(|e| Err(lock_api_error(e)))
+    }
+
+    /// Resolve status token of the resource with given uri,
+    /// if it can be disclosed to the requester with given
+    /// credentials.
+    ///
+    /// Tokens of non-existing resources are always
+    /// disclosable. For existing resources, requester must be
+    /// able to read the resource, as in a `HEAD` request.
+    async fn resolve_disclosable_status_token(
+        storage: &Storage,
+        res_uri: NormalAbsoluteHttpUri,
+        credentials: SgCredentials<Storage>,
+    ) -> Result<Option<SgResourceStatusToken<Storage>>, ApiError> {
+        let er_token = match resolve_status_token(storage, res_uri.clone()).await? {
+            status_token @ ResourceStatusToken::NonExisting(_) => return Ok(Some(status_token)),
+            ResourceStatusToken::Existing(ExistingResourceToken::Represented(er_token)) => er_token,
+            ResourceStatusToken::Existing(ExistingResourceToken::NonRepresented(_)) => {
+                info!("Resource is not represented. Not disclosing it's options.");
+                return Ok(None);
+            }
+        };
+
+        // Status tokens are consumed by reads. Hence token is
+        // resolved again after the access check.
+        match SgRepo::<Storage>::read_basic_with_token(
+            er_token,
+            credentials,
+            RepresentationPreferences::new_light(),
+        )
+        .await
+        {
+            Ok(_) => Ok(Some(resolve_status_token(storage, res_uri).await?)),
+            Err(problem) => {
+                if ACCESS_DENIED.is_type_of(&problem) {
+                    info!("Requester is not allowed to read the resource.");
+                } else {
+                    error!(
+                        "Error in checking read access over the resource. Error:\n {}",
+                        problem
+                    );
+                }
+                Ok(None)
+            }
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! I define default implementation of [`BaseResponseMarshaller`](super::super::super::BaseResponseMarshaller)
+//! for marshalling [`BaseOptionsService`](super::super::base::BaseOptionsService) responses.
+//!
+
+use std::{convert::Infallible, sync::Arc, task::Poll};
+
+use headers::HeaderMapExt;
+use http::{Response, StatusCode};
+use http_api_problem::ApiError;
+use manas_http::{
+    body::Body,
+    header::{
+        common::media_type::{MediaType, APPLICATION_OCTET_STREAM, TEXT_TURTLE},
+        link::{Link, LinkValue},
+    },
+    problem::ApiErrorExt,
+    service::BoxHttpResponseFuture,
+};
+use manas_repo::service::resource_operator::common::status_token::{
+    ExistingRepresentedResourceToken, ExistingResourceToken, ResourceStatusToken,
+};
+use manas_space::{resource::slot::SolidResourceSlot, SolidStorageSpace};
+use tower::Service;
+
+use crate::{
+    policy::method::MethodPolicyExt,
+    service::method::{
+        common::snippet::{
+            authorization::attach_authorization_context,
+            links::{push_ldp_type_links, push_solid_links, storage_description_link},
+        },
+        options::base::BaseOptionsResponse,
+    },
+    SolidStorage, SolidStorageExt,
+};
+
+/// Configuration for [`DefaultBaseOptionsResponseMarshaller`].
+#[derive(Debug, Clone, Default)]
+pub struct DefaultBaseOptionsResponseMarshallerConfig {
+    /// If dev mode is enabled.
+    pub dev_mode: bool,
+}
+
+/// Default implementation of [`BaseResponseMarshaller`](super::super::super::BaseResponseMarshaller)
+/// for marshalling [`BaseOptionsService`](super::super::base::BaseOptionsService) responses.
+#[derive(Debug)]
+pub struct DefaultBaseOptionsResponseMarshaller<Storage> {
+    /// Storage.
+    pub storage: Arc<Storage>,
+
+    /// Marshal config.
+    pub marshal_config: DefaultBaseOptionsResponseMarshallerConfig,
+}
+
+impl<Storage: SolidStorage> Clone for DefaultBaseOptionsResponseMarshaller<Storage> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            storage: self.storage.clone(),
+            marshal_config: self.marshal_config.clone(),
+        }
+    }
+}
+
+impl<Storage> DefaultBaseOptionsResponseMarshaller<Storage> {
+    /// Get new [`DefaultBaseOptionsResponseMarshaller`].
+    #[inline]
+    pub fn new(
+        storage: Arc<Storage>,
+        marshal_config: DefaultBaseOptionsResponseMarshallerConfig,
+    ) -> Self {
+        Self {
+            storage,
+            marshal_config,
+        }
+    }
+}
+
+impl<Storage> Service<Result<BaseOptionsResponse<Storage>, ApiError>>
+    for DefaultBaseOptionsResponseMarshaller<Storage>
+where
+    Storage: SolidStorage,
+{
+    type Response = Response<Body>;
+
+    type Error = Infallible;
+
+    type Future = BoxHttpResponseFuture<Body>;
+
+    #[inline]
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    #[tracing::instrument(skip_all, name = "DefaultBaseOptionsResponseMarshaller::call")]
+    fn call(&mut self, req: Result<BaseOptionsResponse<Storage>, ApiError>) -> Self::Future {
+        Box::pin(futures::future::ready(Ok(match req {
+            Ok(resp) => self.marshal_ok(resp),
+            Err(err) => self.marshal_err(err),
+        })))
+    }
+}
+
+impl<Storage> DefaultBaseOptionsResponseMarshaller<Storage>
+where
+    Storage: SolidStorage,
+{
+    /// Marshal `BaseOptionsService` success response.
+    pub fn marshal_ok(&self, response: BaseOptionsResponse<Storage>) -> Response<Body> {
+        // Create builder, set status code to 204.
+        let mut builder = Response::builder().status(StatusCode::NO_CONTENT);
+        let headers = builder.headers_mut().expect("Must be valid");
+
+        // List of links.
+        let mut links = Vec::<LinkValue>::new();
+
+        match response.status_token {
+            Some(ResourceStatusToken::Existing(e_token)) => {
+                let (rep_content_type, aux_links_index) = match &e_token {
+                    ExistingResourceToken::Represented(er_token) => {
+                        (er_token.rep_content_type(), er_token.aux_links_index())
+                    }
+                    ExistingResourceToken::NonRepresented(_) => (None, vec![]),
+                };
+                let res_slot = e_token.slot();
+
+                // Allow, Accept-<Method>
+                self.storage
+                    .method_policy()
+                    .set_allow_accept_headers_for_existing(
+                        headers,
+                        res_slot,
+                        &rep_content_type
+                            .unwrap_or_else(|| default_rep_content_type(res_slot).clone()),
+                    );
+
+                // Set ldp resource type links.
+                push_ldp_type_links(&mut links, res_slot);
+
+                // Set links specified by solid protocol.
+                push_solid_links(&mut links, res_slot, &aux_links_index);
+            }
+
+            // Advertise generic options, if resource specific
+            // options are not to be disclosed.
+            Some(ResourceStatusToken::NonExisting(_)) | None => {
+                // Allow, Accept-<Method>
+                self.storage
+                    .method_policy()
+                    .set_allow_accept_headers_for_non_existing(headers);
+
+                // Set storage description link.
+                links.push(storage_description_link(self.storage.space().as_ref()));
+            }
+        }
+
+        // Set link header with collected links.
+        headers.typed_insert(Link { values: links });
+
+        // Set empty body, and return response.
+        builder
+            .body(Body::empty())
+            .expect("Must be valid hyper response.")
+    }
+
+    /// Marshal `BaseOptionsService` error.
+    pub fn marshal_err(&self, mut error: ApiError) -> Response<Body> {
+        if self.marshal_config.dev_mode {
+            attach_authorization_context::<Storage>(&mut error);
+        }
+        error.into_http_response()
+    }
+}
+
+/// Get default content-type of representation of resource
+/// with given slot, if it cannot be known from status token.
+fn default_rep_content_type<StSpace: SolidStorageSpace>(
+    res_slot: &SolidResourceSlot<StSpace>,
+) -> &'static MediaType {
+    if res_slot.is_container_slot() {
+        &TEXT_TURTLE
+    } else {
+        &APPLICATION_OCTET_STREAM
+    }
+}
@@ -0,0 +1,5 @@
+//! I define few implementations of [`BaseResponseMarshaller`](super::super::BaseResponseMarshaller)
+//! for marshalling [`BaseOptionsService`](super::base::BaseOptionsService) responses.
+//!
+
+pub mod default;
@@ -0,0 +1,12 @@
+//! I define a [`MethodService`] for handling `OPTIONS` method on solid resources.
+//!
+
+use self::{base::BaseOptionsService, marshaller::default::DefaultBaseOptionsResponseMarshaller};
+use super::MethodService;
+
+pub mod base;
+pub mod marshaller;
+
+/// Type alias for default `OPTIONS` service.
+pub type DefaultOptionsService<Storage> =
+    MethodService<BaseOptionsService<Storage>, DefaultBaseOptionsResponseMarshaller<Storage>>;
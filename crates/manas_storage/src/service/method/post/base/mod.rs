@@ -2,7 +2,7 @@
 //! for handling `POST` method over solid resources.
 //!
 
-use std::{sync::Arc, task::Poll};
+use std::{convert::identity, sync::Arc, task::Poll};
 
 use dyn_problem::{type_::INTERNAL_ERROR, Problem, ProblemBuilderExt};
 use futures::{future::BoxFuture, TryFutureExt};
@@ -10,6 +10,7 @@ use headers::HeaderMapExt;
 use http::{Method, Request, StatusCode};
 use http_api_problem::ApiError;
 use manas_access_control::model::{KResolvedAccessControl, KResolvedHostAccessControl};
+use manas_authentication::common::credentials::CreatorCredentials;
 use manas_http::body::Body;
 use manas_http::{
     header::{
@@ -30,13 +31,16 @@ use manas_repo::{
                 ACCESS_DENIED, INVALID_RDF_SOURCE_REPRESENTATION,
                 INVALID_USER_SUPPLIED_CONTAINED_RES_METADATA,
                 INVALID_USER_SUPPLIED_CONTAINMENT_TRIPLES, PAYLOAD_TOO_LARGE,
-                PRECONDITIONS_NOT_SATISFIED, UNSUPPORTED_MEDIA_TYPE, UNSUPPORTED_OPERATION,
-                URI_POLICY_VIOLATION,
+                PRECONDITIONS_NOT_SATISFIED, QUOTA_EXCEEDED, UNSUPPORTED_MEDIA_TYPE,
+                UNSUPPORTED_OPERATION, URI_POLICY_VIOLATION,
             },
             rep_update_action::RepUpdateAction,
             status_token::ExistingRepresentedResourceToken,
         },
-        creator::{ResourceCreateRequest, ResourceCreateResponse, ResourceCreateTokenSet},
+        creator::{
+            KCreatorCredentials, ResourceCreateRequest, ResourceCreateResponse,
+            ResourceCreateTokenSet,
+        },
     },
     RepoExt,
 };
@@ -57,13 +61,16 @@ use tracing::{debug, error, info};
 use typed_record::{ClonableTypedRecord, TypedRecord};
 
 use crate::{
-    service::method::common::snippet::{
-        op_req::KOpReqExtensions,
-        req_headers::{
-            etag_base_normalized_conditional_headers, resolve_preconditions_eval_status,
-            resolve_req_content_length_hint, resolve_req_content_type,
+    service::method::common::{
+        problem::lock_api_error,
+        snippet::{
+            op_req::KOpReqExtensions,
+            req_headers::{
+                etag_base_normalized_conditional_headers, resolve_preconditions_eval_status,
+                resolve_req_content_length_hint, resolve_req_content_type,
+            },
+            status_token::resolve_status_token,
         },
-        status_token::resolve_status_token,
     },
     SgCredentials, SgRepo, SgResourceCreator, SgResourceStatusToken, SolidStorage,
 };
@@ -265,6 +272,8 @@ where
                 LockKind::Exclusive,
             )
             .await
+            .and_then(identity)
+            .unwrap_or_else(|e| Err(lock_api_error(e)))
     }
 
     /// Create a new contained resource conditionally.
@@ -327,6 +336,12 @@ where
                     .finish()
             })?;
 
+        // Record the creator credentials, if any.
+        let creator_credentials = CreatorCredentials::from_request_credentials(&credentials);
+        let op_req_extensions = op_req_extensions.with_rec_item_opt::<KCreatorCredentials>(
+            (!creator_credentials.is_empty()).then_some(creator_credentials),
+        );
+
         // Construct the request.
         let new_res_create_request = ResourceCreateRequest::<Storage::Repo> {
             tokens: ResourceCreateTokenSet::try_new(new_res_cf_token, container_er_token)
@@ -395,6 +410,11 @@ where
         else if PAYLOAD_TOO_LARGE.is_type_of(&problem) {
             ApiError::builder(StatusCode::PAYLOAD_TOO_LARGE)
         }
+        // If storage quota would be exceeded.
+        else if QUOTA_EXCEEDED.is_type_of(&problem) {
+            ApiError::builder(StatusCode::INSUFFICIENT_STORAGE)
+                .message("Storage quota exceeded.")
+        }
         // If operation is not supported.
         else if UNSUPPORTED_OPERATION.is_type_of(&problem) {
             error!("Unsupported operation.");
@@ -5,11 +5,12 @@
 use std::{error::Error, sync::Arc, task::Poll};
 
 use dyn_problem::{type_::INTERNAL_ERROR, Problem, ProblemBuilderExt};
-use futures::{future::BoxFuture, TryFutureExt};
+use futures::{future::BoxFuture, FutureExt, TryFutureExt};
 use http::{Method, Request, StatusCode};
 use http_api_problem::{ApiError, ApiErrorBuilder};
 use http_body::SizeHint;
 use manas_access_control::model::{KResolvedAccessControl, KResolvedHostAccessControl};
+use manas_authentication::common::credentials::CreatorCredentials;
 use manas_http::{
     body::Body,
     conditional_req::PreconditionsResolvedAction,
@@ -31,12 +32,12 @@ use manas_repo::{
                     ACCESS_DENIED, INVALID_RDF_SOURCE_REPRESENTATION,
                     INVALID_USER_SUPPLIED_CONTAINED_RES_METADATA,
                     INVALID_USER_SUPPLIED_CONTAINMENT_TRIPLES, PAYLOAD_TOO_LARGE,
-                    PRECONDITIONS_NOT_SATISFIED, UNSUPPORTED_MEDIA_TYPE, UNSUPPORTED_OPERATION,
-                    URI_POLICY_VIOLATION,
+                    PRECONDITIONS_NOT_SATISFIED, QUOTA_EXCEEDED, UNSUPPORTED_MEDIA_TYPE,
+                    UNSUPPORTED_OPERATION, URI_POLICY_VIOLATION,
                 },
                 rep_patcher::{
                     INCOMPATIBLE_PATCH_SOURCE_CONTENT_TYPE, INVALID_ENCODED_SOURCE_REP,
-                    PATCH_SEMANTICS_ERROR,
+                    PATCH_CONFLICT, PATCH_SEMANTICS_ERROR,
                 },
                 rep_update_action::RepUpdateAction,
                 status_token::{
@@ -44,7 +45,10 @@ use manas_repo::{
                     NonExistingResourceToken, ResourceStatusToken,
                 },
             },
-            creator::{ResourceCreateRequest, ResourceCreateResponse, ResourceCreateTokenSet},
+            creator::{
+                KCreatorCredentials, ResourceCreateRequest, ResourceCreateResponse,
+                ResourceCreateTokenSet,
+            },
             updater::{ResourceUpdateRequest, ResourceUpdateResponse, ResourceUpdateTokenSet},
         },
     },
@@ -72,13 +76,16 @@ use typed_record::{ClonableTypedRecord, TypedRecord, TypedRecordKey};
 
 use super::marshaller::default::KPatchErrorContext;
 use crate::{
-    service::method::common::snippet::{
-        op_req::KOpReqExtensions,
-        req_headers::{
-            etag_base_normalized_conditional_headers, resolve_preconditions_eval_status,
-            resolve_req_content_length_hint, resolve_req_content_type,
+    service::method::common::{
+        problem::lock_api_error,
+        snippet::{
+            op_req::KOpReqExtensions,
+            req_headers::{
+                etag_base_normalized_conditional_headers, resolve_preconditions_eval_status,
+                resolve_req_content_length_hint, resolve_req_content_type,
+            },
+            status_token::resolve_status_token,
         },
-        status_token::resolve_status_token,
     },
     SgCredentials, SgRepPatcher, SgResourceConflictFreeToken, SgResourceCreator,
     SgResourceStatusToken, SgResourceUpdater, SolidStorage, SolidStorageExt,
@@ -275,6 +282,7 @@ where
                 LockKind::Exclusive,
             )
             .await
+            .unwrap_or_else(|e| Err(lock_api_error(e)))
     }
 
     /// Map the inner upsert problem to api-error
@@ -336,6 +344,15 @@ where
             }
             builder
         }
+        // If supplied patch conflicts with target state.
+        else if PATCH_CONFLICT.is_type_of(&e) {
+            let mut builder = ApiError::builder(StatusCode::CONFLICT)
+                .message("Patch conflicts with the target resource state.");
+            if let Some(source) = e.source() {
+                builder = builder.extend_with::<KPatchErrorContext>(source.to_string());
+            }
+            builder
+        }
         // If rep media type is not supported.
         else if UNSUPPORTED_MEDIA_TYPE.is_type_of(&e) {
             ApiError::builder(StatusCode::UNSUPPORTED_MEDIA_TYPE)
@@ -344,6 +361,10 @@ where
         else if PAYLOAD_TOO_LARGE.is_type_of(&e) {
             ApiError::builder(StatusCode::PAYLOAD_TOO_LARGE)
         }
+        // If storage quota would be exceeded.
+        else if QUOTA_EXCEEDED.is_type_of(&e) {
+            ApiError::builder(StatusCode::INSUFFICIENT_STORAGE).message("Storage quota exceeded.")
+        }
         // If operation is not supported.
         else if UNSUPPORTED_OPERATION.is_type_of(&e) {
             error!("Unsupported operation.");
@@ -454,7 +475,8 @@ where
                             Some(container_lock_name),
                             LockKind::Exclusive,
                         )
-                        .await?;
+                        .await
+                        .map_err(lock_api_error)??;
 
                     Ok(BasePutOrPatchResponse {
                         upserted_res_slot: create_resp.created_resource_slot,
@@ -495,10 +517,15 @@ where
                         let aux_subject_lock_name =
                             uri_policy.mutex_normal_res_uri_hash(&slot_rev_link.target);
 
-                        update_fut = storage.resource_locker().poll_with_lock(
-                            update_fut,
-                            Some(aux_subject_lock_name),
-                            LockKind::Exclusive,
+                        update_fut = Box::pin(
+                            storage
+                                .resource_locker()
+                                .poll_with_lock(
+                                    update_fut,
+                                    Some(aux_subject_lock_name),
+                                    LockKind::Exclusive,
+                                )
+                                .map(|result| result.unwrap_or_else(|e| Err(lock_api_error(e)))),
                         );
                     }
                 }
@@ -630,6 +657,12 @@ where
                     .finish());
             }
 
+            // Record the creator credentials, if any.
+            let creator_credentials = CreatorCredentials::from_request_credentials(&credentials);
+            let op_req_extensions = op_req_extensions.with_rec_item_opt::<KCreatorCredentials>(
+                (!creator_credentials.is_empty()).then_some(creator_credentials),
+            );
+
             // Construct resource create request.
             let new_res_create_request = ResourceCreateRequest::<Storage::Repo> {
                 tokens: ResourceCreateTokenSet::try_new(new_res_cf_token, container_er_token)
@@ -732,6 +765,15 @@ where
                         builder = builder.field("patch_doc_error_context", source.to_string());
                     }
                     builder
+                }
+                // If patch document is not supported semantically.
+                else if PATCH_SEMANTICS_ERROR.is_type_of(&e) {
+                    let mut builder = ApiError::builder(StatusCode::UNPROCESSABLE_ENTITY)
+                        .message("Unsupported patch document semantics.");
+                    if let Some(source) = e.source() {
+                        builder = builder.field("patch_doc_error_context", source.to_string());
+                    }
+                    builder
                 } else {
                     ApiError::builder(StatusCode::INTERNAL_SERVER_ERROR)
                 }
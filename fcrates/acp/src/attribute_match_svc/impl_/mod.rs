@@ -3,10 +3,14 @@
 
 mod agent;
 mod client;
+mod creator;
 mod issuer;
+mod owner;
 mod vc;
 
 pub use agent::*;
 pub use client::*;
+pub use creator::*;
 pub use issuer::*;
+pub use owner::*;
 pub use vc::*;
@@ -10,7 +10,11 @@ use std::{
 
 use dyn_problem::{define_anon_problem_types, Problem};
 use futures::{stream::FuturesUnordered, TryFutureExt, TryStreamExt};
-use rdf_utils::model::{description::DescriptionExt, graph::InfallibleGraph, term::ArcTerm};
+use rdf_utils::model::{
+    description::{Description, DescriptionExt},
+    graph::InfallibleGraph,
+    term::ArcTerm,
+};
 use rdf_vocabularies::ns;
 use sophia_api::term::Term;
 use tower::ServiceExt;
@@ -19,11 +23,15 @@ use tracing::{debug, error, log::info};
 use super::attribute_match_svc::{AttributeMatchRequest, BoxedAttributeMatchService};
 use crate::{
     attribute_match_svc::impl_::{
-        AgentMatchService, ClientMatchService, IssuerMatchService, VcMatchService,
+        AgentMatchService, ClientMatchService, CreatorMatchService, IssuerMatchService,
+        OwnerMatchService, VcMatchService,
     },
     model::{
-        access_mode::HAccessMode, acr::DAccessControlResource, context::DContext,
-        matcher::DMatcher, policy::DPolicy,
+        access_mode::HAccessMode,
+        acr::DAccessControlResource,
+        context::DContext,
+        matcher::{DMatcher, HMatcher},
+        policy::{DPolicy, HPolicy},
     },
 };
 
@@ -34,6 +42,21 @@ use crate::{
 /// Alias for type of set of access modes.
 pub type AccessGrantSet = HashSet<HAccessMode<ArcTerm>>;
 
+/// A struct to represent an access grant, along with the
+/// satisfied policies and matchers justifying it.
+#[derive(Debug, Clone, Default)]
+pub struct JustifiedAccessGrant {
+    /// Granted access modes.
+    pub grants: AccessGrantSet,
+
+    /// Satisfied effective policies.
+    pub satisfied_policies: Vec<HPolicy<ArcTerm>>,
+
+    /// Satisfied `allOf` and `anyOf` matchers of satisfied
+    /// policies.
+    pub satisfied_matchers: Vec<HMatcher<ArcTerm>>,
+}
+
 /// [`AcpEngine`] resolves permissions to access controlled resources
 /// in conformance with ACP access control resolution algorithm
 pub struct AcpEngine<G, WG>
@@ -84,6 +107,8 @@ where
                 (ns::acp::client, Box::new(ClientMatchService)),
                 (ns::acp::issuer, Box::new(IssuerMatchService)),
                 (ns::acp::vc, Box::new(VcMatchService)),
+                (ns::acp::creator, Box::new(CreatorMatchService)),
+                (ns::acp::owner, Box::new(OwnerMatchService)),
             ]
             .into_iter()
             .map(|(a, s)| (a.into_term(), s))
@@ -127,16 +152,32 @@ where
         ancestor_acrs: Vec<Option<DAccessControlResource<G, WG>>>,
         context: DContext<G, WG>,
     ) -> Result<AccessGrantSet, Problem> {
+        Ok(self
+            .resolve_justified_access_control(opt_acr, ancestor_acrs, context)
+            .await?
+            .grants)
+    }
+
+    /// Resolve access control for given context against given
+    /// acrs, along with satisfied policies and matchers
+    /// justifying the grants.
+    #[tracing::instrument(skip_all)]
+    pub async fn resolve_justified_access_control(
+        &self,
+        opt_acr: Option<DAccessControlResource<G, WG>>,
+        ancestor_acrs: Vec<Option<DAccessControlResource<G, WG>>>,
+        context: DContext<G, WG>,
+    ) -> Result<JustifiedAccessGrant, Problem> {
         // Gather effective policies.
         let effective_policies = self.gather_effective_policies(opt_acr, ancestor_acrs);
 
         // Resolve allowed access modes.
-        let allowed_access_modes = self
+        let justified_grant = self
             .grant_access_modes(effective_policies, context)
             .inspect_err(|e| error!("Error in resolving granted access modes. Error:\n {}", e))
             .await?;
 
-        Ok(allowed_access_modes)
+        Ok(justified_grant)
     }
 
     /// Gather [effective policies](https://solid.github.io/authorization-panel/acp-specification/#effective-policies).
@@ -180,18 +221,22 @@ where
         &self,
         policies: Vec<DPolicy<G, WG>>,
         context: DContext<G, WG>,
-    ) -> Result<AccessGrantSet, Problem> {
+    ) -> Result<JustifiedAccessGrant, Problem> {
         let mut allowed_access_modes = HashSet::new();
         let mut denied_access_modes = HashSet::new();
+        let mut satisfied_policies = Vec::new();
+        let mut satisfied_matchers = Vec::new();
 
         // Gather allowed and denied access modes from satisfied policies
         for policy in policies.into_iter() {
-            if self
-                .is_satisfied_policy(policy.clone(), context.clone())
+            if let Some(policy_satisfied_matchers) = self
+                .resolve_policy_satisfaction(policy.clone(), context.clone())
                 .await?
             {
                 allowed_access_modes.extend(policy.h_allow());
                 denied_access_modes.extend(policy.h_deny());
+                satisfied_policies.push(policy.handle().clone());
+                satisfied_matchers.extend(policy_satisfied_matchers);
             }
         }
 
@@ -200,63 +245,63 @@ where
             allowed_access_modes.remove(mode);
         });
 
-        Ok(allowed_access_modes)
+        Ok(JustifiedAccessGrant {
+            grants: allowed_access_modes,
+            satisfied_policies,
+            satisfied_matchers,
+        })
     }
 
-    /// Resolves if policy is satisfied.
+    /// Resolves if policy is satisfied. Returns satisfied
+    /// `allOf` and `anyOf` matchers, if it is satisfied.
     /// > A Policy MUST be satisfied if and only if:
     /// > - it references at least one Matcher via an acp:allOf or acp:anyOf property; and,
     /// > - all of its acp:allOf Matchers are satisfied; and,
     /// > - at least one of its acp:anyOf Matchers is satisfied; and,
     /// > - none of its acp:noneOf Matchers are satisfied.
-    async fn is_satisfied_policy(
+    async fn resolve_policy_satisfaction(
         &self,
         policy: DPolicy<G, WG>,
         context: DContext<G, WG>,
-    ) -> Result<bool, Problem> {
+    ) -> Result<Option<Vec<HMatcher<ArcTerm>>>, Problem> {
         // If any 'none of' matcher is satisfied then the policy is not satisfied.
         // For now evaluate all matchers without short circuit,
         // to simplify handling of fallible evaluation.
-        let none_of = policy.none_of().collect::<Vec<_>>();
-        if none_of
-            .into_iter()
-            .map(|matcher| self.is_satisfied_matcher(matcher, context.clone()))
-            .collect::<FuturesUnordered<_>>()
-            .try_collect::<Vec<_>>()
+        if self
+            .resolve_matchers_satisfaction(policy.none_of().collect(), context.clone())
             .await?
-            .contains(&true)
+            .iter()
+            .any(|(_, is_satisfied)| *is_satisfied)
         {
             debug!("Policy has a noneOf matcher that is satisfied.");
-            return Ok(false);
+            return Ok(None);
         }
 
         // If any 'all of' matcher is not satisfied then the policy is not satisfied.
-        let all_of = policy.all_of().collect::<Vec<_>>();
-        if all_of
-            .into_iter()
-            .map(|matcher| self.is_satisfied_matcher(matcher, context.clone()))
-            .collect::<FuturesUnordered<_>>()
-            .try_collect::<Vec<_>>()
-            .await?
-            .contains(&false)
-        {
+        let all_of = self
+            .resolve_matchers_satisfaction(policy.all_of().collect(), context.clone())
+            .await?;
+        if all_of.iter().any(|(_, is_satisfied)| !*is_satisfied) {
             debug!("Policy has an allOf matcher that is not satisfied.");
-            return Ok(false);
+            return Ok(None);
         }
+        let mut satisfied_matchers = all_of
+            .into_iter()
+            .map(|(matcher, _)| matcher)
+            .collect::<Vec<_>>();
 
         // If any 'any of' matcher is satisfied then the policy is satisfied.
-        let any_of = policy.any_of().collect::<Vec<_>>();
-        if any_of
-            .into_iter()
-            .map(|matcher| self.is_satisfied_matcher(matcher, context.clone()))
-            .collect::<FuturesUnordered<_>>()
-            .try_collect::<Vec<_>>()
+        let satisfied_any_of = self
+            .resolve_matchers_satisfaction(policy.any_of().collect(), context.clone())
             // Conservative on evaluation error, even though  few cases can be resolved.
             .await?
-            .contains(&true)
-        {
+            .into_iter()
+            .filter_map(|(matcher, is_satisfied)| is_satisfied.then_some(matcher))
+            .collect::<Vec<_>>();
+        if !satisfied_any_of.is_empty() {
             debug!("Policy has an anyOf matcher that is satisfied.");
-            return Ok(true);
+            satisfied_matchers.extend(satisfied_any_of);
+            return Ok(Some(satisfied_matchers));
         }
 
         // At this point there are
@@ -267,7 +312,29 @@ where
         // Hence, the policy is satisfied if it has
         // - an 'all of' condition and
         // - no 'any of' condition.
-        Ok(policy.has_any(&ns::acp::allOf) && !policy.has_any(&ns::acp::anyOf))
+        Ok(
+            (policy.has_any(&ns::acp::allOf) && !policy.has_any(&ns::acp::anyOf))
+                .then_some(satisfied_matchers),
+        )
+    }
+
+    /// Resolves satisfaction of given matchers against given
+    /// context, paired with their handles.
+    async fn resolve_matchers_satisfaction(
+        &self,
+        matchers: Vec<DMatcher<G, WG>>,
+        context: DContext<G, WG>,
+    ) -> Result<Vec<(HMatcher<ArcTerm>, bool)>, Problem> {
+        matchers
+            .into_iter()
+            .map(|matcher| {
+                let h_matcher = matcher.handle().clone();
+                self.is_satisfied_matcher(matcher, context.clone())
+                    .map_ok(move |is_satisfied| (h_matcher, is_satisfied))
+            })
+            .collect::<FuturesUnordered<_>>()
+            .try_collect::<Vec<_>>()
+            .await
     }
 
     /// Resolves if a matcher is satisfied against given context.
@@ -7,7 +7,7 @@ use itertools::Itertools;
 use crate::proof::payload::nonce::Nonce;
 
 /// A typed header for `DPoP-Nonce` header.
-pub struct DPoPNonce(Nonce);
+pub struct DPoPNonce(pub Nonce);
 
 /// Constant for `dpop`-nonce header name.
 pub static DPOP_NONCE: HeaderName = HeaderName::from_static("dpop-nonce");
@@ -59,6 +59,17 @@ impl Deref for Nonce {
     }
 }
 
+impl Nonce {
+    /// Create a new `nonce` from a generated uuid4.
+    #[inline]
+    pub fn new_from_uuid4() -> Self {
+        uuid::Uuid::new_v4()
+            .to_string()
+            .try_into()
+            .expect("Uuid must be a valid nonce.")
+    }
+}
+
 mod predicate {
     use std::borrow::Borrow;
 
@@ -119,4 +130,11 @@ mod tests {
     fn nonce_parse_works_correctly(#[case] input_str: &str, #[case] expected_is_valid: bool) {
         assert_eq!(Nonce::from_str(input_str).is_ok(), expected_is_valid);
     }
+
+    #[test]
+    fn generated_nonces_are_valid_and_distinct() {
+        let nonce = Nonce::new_from_uuid4();
+        assert!(Nonce::from_str(&nonce).is_ok());
+        assert_ne!(nonce, Nonce::new_from_uuid4());
+    }
 }
@@ -30,6 +30,9 @@ pub mod link;
 #[cfg(feature = "location")]
 pub mod location;
 
+#[cfg(feature = "memento")]
+pub mod memento;
+
 #[cfg(feature = "prefer")]
 pub mod prefer;
 #[cfg(feature = "prefer")]
@@ -51,7 +54,6 @@ pub mod wac_allow;
 #[cfg(feature = "www-authenticate")]
 pub mod www_authenticate;
 
-
 // Re exports
 #[cfg(feature = "media-type")]
 pub use mime;
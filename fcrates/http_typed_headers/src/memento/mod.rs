@@ -0,0 +1,157 @@
+//! I define [`AcceptDatetime`], [`MementoDatetime`] typed
+//! headers of memento protocol.
+//!
+
+use std::time::SystemTime;
+
+use headers::{Header, HeaderName, HeaderValue};
+use httpdate::{fmt_http_date, parse_http_date};
+
+/// `Accept-Datetime` header is defined in [`rfc7089`](https://www.rfc-editor.org/rfc/rfc7089.html#section-2.1.1)
+///
+/// The syntax of the Accept-Datetime header:
+///
+/// ```txt
+///     Accept-Datetime = HTTP-date
+///```
+/// It is used by a user agent to indicate it wants to access
+/// a past state of an original resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AcceptDatetime(pub SystemTime);
+
+/// Static for `accept-datetime` header-name.
+pub static ACCEPT_DATETIME: HeaderName = HeaderName::from_static("accept-datetime");
+
+impl Header for AcceptDatetime {
+    #[inline]
+    fn name() -> &'static HeaderName {
+        &ACCEPT_DATETIME
+    }
+
+    #[inline]
+    fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+    where
+        Self: Sized,
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        decode_http_date(values).map(Self)
+    }
+
+    #[inline]
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(std::iter::once(encode_http_date(self.0)))
+    }
+}
+
+/// `Memento-Datetime` header is defined in [`rfc7089`](https://www.rfc-editor.org/rfc/rfc7089.html#section-2.1.1)
+///
+/// The syntax of the Memento-Datetime header:
+///
+/// ```txt
+///     Memento-Datetime = HTTP-date
+///```
+/// It is used by a server to indicate that a response reflects
+/// a prior state of an original resource, and the datetime of
+/// that state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MementoDatetime(pub SystemTime);
+
+/// Static for `memento-datetime` header-name.
+pub static MEMENTO_DATETIME: HeaderName = HeaderName::from_static("memento-datetime");
+
+impl Header for MementoDatetime {
+    #[inline]
+    fn name() -> &'static HeaderName {
+        &MEMENTO_DATETIME
+    }
+
+    #[inline]
+    fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+    where
+        Self: Sized,
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        decode_http_date(values).map(Self)
+    }
+
+    #[inline]
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(std::iter::once(encode_http_date(self.0)))
+    }
+}
+
+/// Decode a single http-date header value.
+fn decode_http_date<'i, I>(values: &mut I) -> Result<SystemTime, headers::Error>
+where
+    I: Iterator<Item = &'i HeaderValue>,
+{
+    let value = values.next().ok_or_else(headers::Error::invalid)?;
+
+    // Ensure there are no more values.
+    if values.next().is_some() {
+        return Err(headers::Error::invalid());
+    }
+
+    value
+        .to_str()
+        .ok()
+        .and_then(|value_str| parse_http_date(value_str.trim()).ok())
+        .ok_or_else(headers::Error::invalid)
+}
+
+/// Encode given time as http-date header value.
+#[inline]
+fn encode_http_date(time: SystemTime) -> HeaderValue {
+    HeaderValue::from_str(&fmt_http_date(time)).expect("Must be valid header")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use claims::*;
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case(&["Thu, 01 Jan 1970 00:00:00 GMT"], 0)]
+    #[case(&["Tue, 11 Sep 2001 20:35:15 GMT"], 1000240515)]
+    #[case(&[" Tue, 11 Sep 2001 20:35:15 GMT "], 1000240515)]
+    fn decode_works_correctly(#[case] header_value_strs: &[&str], #[case] expected_secs: u64) {
+        let header_values: Vec<HeaderValue> = header_value_strs
+            .iter()
+            .map(|v| assert_ok!(HeaderValue::from_str(v)))
+            .collect();
+        let accept_datetime = assert_ok!(AcceptDatetime::decode(&mut header_values.iter()));
+        assert_eq!(
+            accept_datetime.0,
+            UNIX_EPOCH + Duration::from_secs(expected_secs)
+        );
+    }
+
+    #[rstest]
+    #[case(&[])]
+    #[case(&["yesterday"])]
+    #[case(&["Tue, 11 Sep 2001 20:35:15 GMT", "Thu, 01 Jan 1970 00:00:00 GMT"])]
+    fn decode_rejects_invalid(#[case] header_value_strs: &[&str]) {
+        let header_values: Vec<HeaderValue> = header_value_strs
+            .iter()
+            .map(|v| assert_ok!(HeaderValue::from_str(v)))
+            .collect();
+        assert_err!(AcceptDatetime::decode(&mut header_values.iter()));
+    }
+
+    #[rstest]
+    #[case(0, "Thu, 01 Jan 1970 00:00:00 GMT")]
+    #[case(1000240515, "Tue, 11 Sep 2001 20:35:15 GMT")]
+    fn encode_works_correctly(#[case] secs: u64, #[case] expected_header_str: &str) {
+        let memento_datetime = MementoDatetime(UNIX_EPOCH + Duration::from_secs(secs));
+        let mut headers = Vec::<HeaderValue>::new();
+        memento_datetime.encode(&mut headers);
+
+        let encoded_header = headers.first().expect("Memento-Datetime value not encoded");
+        let encoded_header_str = assert_ok!(encoded_header.to_str(), "Encoding corruption");
+        assert_eq!(encoded_header_str, expected_header_str, "Invalid encoding");
+    }
+}
@@ -43,6 +43,10 @@ pub static TOKEN_NAME_RETURN: Lazy<FieldParameterName> =
 pub static PARAM_NAME_INCLUDE: Lazy<FieldParameterName> =
     Lazy::new(|| "include".parse().expect("Must be valid."));
 
+/// Static for "omit" param name.
+pub static PARAM_NAME_OMIT: Lazy<FieldParameterName> =
+    Lazy::new(|| "omit".parse().expect("Must be valid."));
+
 /// Static for "representation" token value.
 pub static TOKEN_VALUE_REPRESENTATION: Lazy<FieldParameterValue> =
     Lazy::new(|| "representation".try_into().expect("Must be valid."));
@@ -161,6 +165,12 @@ pub(crate) mod tests_decode {
         "representation",
         &[("include", "http://www.w3.org/ns/ldp#PreferMembership http://www.w3.org/ns/ldp#PreferMinimalContainer")]
     )]
+    #[case(
+        r#"return=representation; omit="http://www.w3.org/ns/ldp#PreferContainment""#,
+        "return",
+        "representation",
+        &[("omit", "http://www.w3.org/ns/ldp#PreferContainment")]
+    )]
     fn valid_preference_will_be_round_tripped_correctly(
         #[case] preference_str: &str,
         #[case] expected_token_name: &str,
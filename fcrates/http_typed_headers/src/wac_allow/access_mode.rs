@@ -33,6 +33,26 @@ impl AccessMode {
 
     /// `control` access mode.
     pub const CONTROL: Self = Self(Ascii::new(EcoString::inline("control")));
+
+    /// Try to create a new extension access mode, other than
+    /// the standard ones.
+    ///
+    /// Extension access mode tokens must start with a
+    /// lowercase letter, and must only contain lowercase
+    /// letters, digits, and `-`. Standard access mode tokens
+    /// are rejected, so that an extension mode can never be
+    /// mistaken for a standard one.
+    pub fn try_new_extension(token: &str) -> Result<Self, InvalidAccessMode> {
+        let mut chars = token.chars();
+        if !ALL_MODES.contains(&Ascii::new(token))
+            && chars.next().is_some_and(|c| c.is_ascii_lowercase())
+            && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        {
+            Ok(Self(Ascii::new(token.into())))
+        } else {
+            Err(InvalidAccessMode {})
+        }
+    }
 }
 
 impl Display for AccessMode {
@@ -90,4 +110,28 @@ mod tests {
     fn valid_str_will_be_parsed(#[case] token_str: &str) {
         assert_ok!(AccessMode::from_str(token_str));
     }
+
+    #[rstest]
+    #[case("")]
+    #[case("Share")]
+    #[case("1share")]
+    #[case("share mode")]
+    #[case("read")]
+    #[case("write")]
+    #[case("append")]
+    #[case("control")]
+    fn invalid_extension_token_will_be_rejected(#[case] token_str: &str) {
+        assert_err_eq!(
+            AccessMode::try_new_extension(token_str),
+            InvalidAccessMode {}
+        );
+    }
+
+    #[rstest]
+    #[case("share")]
+    #[case("read-no-list")]
+    #[case("mode2")]
+    fn valid_extension_token_will_be_accepted(#[case] token_str: &str) {
+        assert_ok!(AccessMode::try_new_extension(token_str));
+    }
 }
@@ -0,0 +1,408 @@
+//! I define a filesystem backed implementation of [`NameLocker`].
+//!
+
+use std::{
+    convert::identity,
+    fmt::Debug,
+    fs::{self, File, OpenOptions},
+    future::Future,
+    hash::Hash,
+    io,
+    os::unix::{fs::MetadataExt, io::AsRawFd},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use async_stream::stream;
+use futures::{future::BoxFuture, stream::BoxStream, Stream, StreamExt};
+use sha2::{Digest, Sha256};
+use tokio::time::Instant;
+use tracing::{error, warn};
+
+use super::InmemNameLocker;
+use crate::{LockError, LockKind, NameLocker};
+
+/// Extension of lock files.
+const LOCK_FILE_EXTENSION: &str = "lock";
+
+/// Initial interval between attempts to acquire a lock.
+const INITIAL_RETRY_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Max interval between attempts to acquire a lock.
+const MAX_RETRY_INTERVAL: Duration = Duration::from_millis(64);
+
+/// Default max duration to wait for acquiring a lock file.
+pub const DEFAULT_FLOCK_ACQUIRE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// An implementation of [`NameLocker`], that uses advisory
+/// `flock` locks on lock files in a directory.
+///
+/// Lock file of a name is derived from sha256 hash of the
+/// name. As locks are held by the os, this can lock a name
+/// across processes sharing the lock directory, including
+/// over nfs. Locks of a crashed process are released by the
+/// os. Lock files are removed when there is no contention
+/// over them, and left over ones can be removed with
+/// [`remove_stale_lock_files`](Self::remove_stale_lock_files).
+///
+/// Names are additionally locked in memory, as some
+/// filesystems do not exclude lockers within same process.
+///
+/// If a lock file cannot be opened, or locked due to an io
+/// error, wrapper resolves to [`LockError::Io`] without
+/// polling the task. If lock file cannot be locked within
+/// the acquire timeout, it resolves to
+/// [`LockError::AcquireTimedOut`]. Blocking file operations
+/// are run on the blocking thread pool.
+#[derive(Clone)]
+pub struct FlockNameLocker<Name>
+where
+    Name: Ord + Hash + Clone + Send + Sync + 'static,
+{
+    /// Directory of lock files.
+    lock_dir: Arc<PathBuf>,
+
+    /// In process locker.
+    inmem_locker: InmemNameLocker<Name>,
+
+    /// Max duration to wait for acquiring a lock file.
+    acquire_timeout: Duration,
+}
+
+impl<Name> Debug for FlockNameLocker<Name>
+where
+    Name: Ord + Hash + Clone + Send + Sync + 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FlockNameLocker")
+            .field("lock_dir", &self.lock_dir)
+            .field("acquire_timeout", &self.acquire_timeout)
+            .finish()
+    }
+}
+
+impl<Name> FlockNameLocker<Name>
+where
+    Name: AsRef<[u8]> + Ord + Hash + Clone + Send + Sync + 'static,
+{
+    /// Create a new [`FlockNameLocker`], that keeps lock
+    /// files in given directory. Directory will be created,
+    /// if it doesn't exist. It waits for
+    /// [`DEFAULT_FLOCK_ACQUIRE_TIMEOUT`] to acquire a lock file.
+    pub fn try_new(lock_dir: PathBuf) -> io::Result<Self> {
+        fs::create_dir_all(&lock_dir)?;
+        Ok(Self {
+            lock_dir: Arc::new(lock_dir),
+            inmem_locker: InmemNameLocker::new(),
+            acquire_timeout: DEFAULT_FLOCK_ACQUIRE_TIMEOUT,
+        })
+    }
+
+    /// Set max duration to wait for acquiring a lock file.
+    #[inline]
+    pub fn with_acquire_timeout(mut self, acquire_timeout: Duration) -> Self {
+        self.acquire_timeout = acquire_timeout;
+        self
+    }
+
+    /// Get max duration to wait for acquiring a lock file.
+    #[inline]
+    pub fn acquire_timeout(&self) -> Duration {
+        self.acquire_timeout
+    }
+
+    /// Get the lock directory.
+    #[inline]
+    pub fn lock_dir(&self) -> &Path {
+        &self.lock_dir
+    }
+
+    /// Remove stale lock files, that are not locked by any
+    /// process. Returns number of removed lock files.
+    pub fn remove_stale_lock_files(&self) -> io::Result<usize> {
+        let mut count = 0;
+        for entry in fs::read_dir(self.lock_dir.as_path())? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(LOCK_FILE_EXTENSION) {
+                continue;
+            }
+
+            // Lock file is removed on dropping the guard.
+            if FlockGuard::try_acquire(&path, &LockKind::Exclusive)?.is_some() {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Resolve path of the lock file of given name.
+    fn lock_file_path(&self, name: &Name) -> PathBuf {
+        self.lock_dir.join(format!(
+            "{:x}.{}",
+            Sha256::digest(name.as_ref()),
+            LOCK_FILE_EXTENSION
+        ))
+    }
+}
+
+impl<Name> NameLocker for FlockNameLocker<Name>
+where
+    Name: AsRef<[u8]> + Ord + Hash + Clone + Send + Sync + 'static,
+{
+    type Name = Name;
+
+    fn poll_with_lock<Output, Task>(
+        &self,
+        task: Task,
+        name: Option<Self::Name>,
+        lock_kind: LockKind,
+    ) -> BoxFuture<'static, Result<Output, LockError>>
+    where
+        Task: Future<Output = Output> + Send + 'static,
+        Output: 'static,
+    {
+        if let Some(name) = name {
+            let lock_file_path = self.lock_file_path(&name);
+            let file_lock_kind = lock_kind.clone();
+            let acquire_timeout = self.acquire_timeout;
+
+            let locked_task = self.inmem_locker.poll_with_lock(
+                async move {
+                    // Acquire specified lock over lock file.
+                    // This guard lasts across an await point.
+                    let guard =
+                        FlockGuard::acquire(lock_file_path, file_lock_kind, acquire_timeout)
+                            .await?;
+
+                    // Await task
+                    let output = task.await;
+
+                    // Drop guard explicitly.
+                    drop(guard);
+                    Ok(output)
+                },
+                Some(name),
+                lock_kind,
+            );
+
+            Box::pin(async move { locked_task.await.and_then(identity) })
+        } else {
+            // If name is `None`, directly await task.
+            Box::pin(async move { Ok(task.await) })
+        }
+    }
+
+    fn poll_read_with_lock<'s, S>(
+        &self,
+        in_stream: S,
+        name: Option<Self::Name>,
+        lock_kind: LockKind,
+    ) -> BoxStream<'s, Result<S::Item, LockError>>
+    where
+        S: Stream + Send + 's,
+        <S as Stream>::Item: Send,
+    {
+        if let Some(name) = name {
+            let lock_file_path = self.lock_file_path(&name);
+            let file_lock_kind = lock_kind.clone();
+            let acquire_timeout = self.acquire_timeout;
+
+            Box::pin(
+                self.inmem_locker
+                    .poll_read_with_lock(
+                        stream! {
+                            // Acquire specified lock over lock file.
+                            // This guard lasts across an await point.
+                            let guard = match FlockGuard::acquire(
+                                lock_file_path,
+                                file_lock_kind,
+                                acquire_timeout,
+                            )
+                            .await
+                            {
+                                Ok(guard) => guard,
+                                Err(e) => {
+                                    yield Err(e);
+                                    return;
+                                }
+                            };
+
+                            // Yield items.
+                            for await item in in_stream {
+                                yield Ok(item);
+                            }
+
+                            // Drop guard explicitly.
+                            drop(guard);
+                        },
+                        Some(name),
+                        lock_kind,
+                    )
+                    .map(|result| result.and_then(identity)),
+            )
+        } else {
+            // If name is `None`, directly return stream.
+            Box::pin(in_stream.map(Ok))
+        }
+    }
+}
+
+/// A guard of a lock held over a lock file.
+///
+/// On drop, it removes the lock file if there is no
+/// contention, and releases the lock.
+struct FlockGuard {
+    /// Locked file.
+    file: File,
+
+    /// Path of the lock file.
+    path: PathBuf,
+}
+
+impl FlockGuard {
+    /// Acquire lock of given kind over lock file at given
+    /// path, waiting at most for given acquire timeout.
+    /// Blocking file operations are run on the blocking
+    /// thread pool.
+    async fn acquire(
+        path: PathBuf,
+        lock_kind: LockKind,
+        acquire_timeout: Duration,
+    ) -> Result<AsyncFlockGuard, LockError> {
+        let deadline = Instant::now() + acquire_timeout;
+        let path = Arc::new(path);
+        let mut retry_interval = INITIAL_RETRY_INTERVAL;
+        loop {
+            let attempt_path = path.clone();
+            let attempt_lock_kind = lock_kind.clone();
+            let attempt = tokio::task::spawn_blocking(move || {
+                Self::try_acquire(&attempt_path, &attempt_lock_kind)
+            })
+            .await
+            .map_err(io::Error::other)
+            .and_then(identity);
+
+            match attempt {
+                Ok(Some(guard)) => return Ok(AsyncFlockGuard(Some(guard))),
+                Ok(None) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        warn!(
+                            "Lock over lock file {:?} could not be acquired within {:?}.",
+                            path, acquire_timeout
+                        );
+                        return Err(LockError::AcquireTimedOut);
+                    }
+
+                    tokio::time::sleep(retry_interval.min(deadline - now)).await;
+                    retry_interval = (retry_interval * 2).min(MAX_RETRY_INTERVAL);
+                }
+                Err(e) => {
+                    error!(
+                        "Error in acquiring lock over lock file {:?}. Error:\n {}",
+                        path, e
+                    );
+                    return Err(LockError::Io(e));
+                }
+            }
+        }
+    }
+
+    /// Try to acquire lock of given kind over lock file at
+    /// given path, without blocking. Returns `None` if
+    /// lock is contended.
+    fn try_acquire(path: &Path, lock_kind: &LockKind) -> io::Result<Option<Self>> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        let operation = match lock_kind {
+            LockKind::Shared => libc::LOCK_SH,
+            LockKind::Exclusive => libc::LOCK_EX,
+        };
+
+        if !try_flock(&file, operation)? {
+            return Ok(None);
+        }
+
+        // Ensure lock file is not removed by previous holder,
+        // while we are acquiring the lock.
+        if !is_linked(&file, path)? {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            file,
+            path: path.to_owned(),
+        }))
+    }
+}
+
+impl Drop for FlockGuard {
+    fn drop(&mut self) {
+        // Remove lock file, if there is no contention.
+        // Lock file must be checked to be linked again, as
+        // upgrading a shared lock is not atomic.
+        if matches!(try_flock(&self.file, libc::LOCK_EX), Ok(true))
+            && matches!(is_linked(&self.file, &self.path), Ok(true))
+        {
+            if let Err(e) = fs::remove_file(&self.path) {
+                warn!(
+                    "Error in removing lock file {:?}. Error:\n {}",
+                    self.path, e
+                );
+            }
+        }
+        // Lock is released on closing the file.
+    }
+}
+
+/// A wrapper over [`FlockGuard`], that drops it on the
+/// blocking thread pool, as dropping it does blocking file
+/// operations.
+struct AsyncFlockGuard(Option<FlockGuard>);
+
+impl Drop for AsyncFlockGuard {
+    fn drop(&mut self) {
+        let Some(guard) = self.0.take() else {
+            return;
+        };
+
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn_blocking(move || drop(guard));
+            }
+            Err(_) => drop(guard),
+        }
+    }
+}
+
+/// Try to apply given flock operation on given file,
+/// without blocking. Returns `false` if lock is contended.
+fn try_flock(file: &File, operation: libc::c_int) -> io::Result<bool> {
+    // SAFETY: File descriptor is valid for lifetime of the file.
+    if unsafe { libc::flock(file.as_raw_fd(), operation | libc::LOCK_NB) } == 0 {
+        return Ok(true);
+    }
+
+    let e = io::Error::last_os_error();
+    match e.raw_os_error() {
+        Some(libc::EWOULDBLOCK) | Some(libc::EINTR) => Ok(false),
+        _ => Err(e),
+    }
+}
+
+/// Check if given opened file is still linked at given path.
+fn is_linked(file: &File, path: &Path) -> io::Result<bool> {
+    let file_metadata = file.metadata()?;
+    match fs::metadata(path) {
+        Ok(path_metadata) => Ok(file_metadata.dev() == path_metadata.dev()
+            && file_metadata.ino() == path_metadata.ino()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
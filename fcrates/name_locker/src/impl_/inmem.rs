@@ -5,10 +5,10 @@ use std::{future::Future, hash::Hash, sync::Arc};
 
 use async_stream::stream;
 use dashmap::DashMap;
-use futures::{future::BoxFuture, stream::BoxStream, Stream};
+use futures::{future::BoxFuture, stream::BoxStream, Stream, StreamExt};
 use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
-use crate::{LockKind, NameLocker};
+use crate::{LockError, LockKind, NameLocker};
 
 /// An enum to hold lock guard.
 #[allow(dead_code)]
@@ -21,6 +21,7 @@ enum LockGuard<'g> {
 ///
 /// As this uses inmemory lock table, it cannot lock a name across different processes.
 ///
+#[derive(Clone)]
 pub struct InmemNameLocker<Name>
 where
     Name: Ord + Hash + Clone + Send + Sync + 'static,
@@ -100,9 +101,10 @@ where
         task: Task,
         name: Option<Self::Name>,
         lock_kind: LockKind,
-    ) -> BoxFuture<'static, Output>
+    ) -> BoxFuture<'static, Result<Output, LockError>>
     where
         Task: Future<Output = Output> + Send + 'static,
+        Output: 'static,
     {
         if let Some(name) = name {
             let lock_table = self.lock_table.clone();
@@ -126,11 +128,11 @@ where
 
                 // Cleanup lock if no contention.
                 Self::_remove_lock_if_no_contention(lock_table, &name);
-                output
+                Ok(output)
             })
         } else {
             // If name is `None`, directly await task.
-            Box::pin(task)
+            Box::pin(async move { Ok(task.await) })
         }
     }
 
@@ -139,7 +141,7 @@ where
         in_stream: S,
         name: Option<Self::Name>,
         lock_kind: LockKind,
-    ) -> BoxStream<'s, S::Item>
+    ) -> BoxStream<'s, Result<S::Item, LockError>>
     where
         S: Stream + Send + 's,
         <S as Stream>::Item: Send,
@@ -159,7 +161,7 @@ where
 
                 // Yield items.
                 for await item in in_stream {
-                    yield item;
+                    yield Ok(item);
                 }
 
                 // Drop guard and arced lock explicitly..
@@ -171,7 +173,7 @@ where
             })
         } else {
             // If name is `None`, directly return stream.
-            Box::pin(in_stream)
+            Box::pin(in_stream.map(Ok))
         }
     }
 }
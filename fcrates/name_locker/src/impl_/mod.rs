@@ -8,3 +8,13 @@ pub use void::VoidNameLocker;
 mod inmem;
 #[cfg(feature = "inmem")]
 pub use inmem::InmemNameLocker;
+
+#[cfg(all(feature = "flock", unix))]
+mod flock;
+#[cfg(all(feature = "flock", unix))]
+pub use flock::{FlockNameLocker, DEFAULT_FLOCK_ACQUIRE_TIMEOUT};
+
+#[cfg(feature = "redis")]
+mod redis;
+#[cfg(feature = "redis")]
+pub use self::redis::{RedisNameLocker, RedisNameLockerConfig};
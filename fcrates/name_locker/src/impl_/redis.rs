@@ -0,0 +1,683 @@
+//! I define a redis backed implementation of [`NameLocker`].
+//!
+
+use std::{
+    convert::identity, fmt::Debug, future::Future, hash::Hash, io, sync::Arc, time::Duration,
+};
+
+use async_stream::stream;
+use futures::{
+    future::{select, BoxFuture, Either},
+    stream::BoxStream,
+    Stream, StreamExt,
+};
+use once_cell::sync::Lazy;
+use redis::{aio::MultiplexedConnection, Client, RedisResult, Script};
+use tokio::{sync::Mutex, task::JoinHandle, time::Instant};
+use tracing::{error, warn};
+
+use super::InmemNameLocker;
+use crate::{LockError, LockKind, NameLocker};
+
+/// Initial interval between attempts to acquire a lock.
+const INITIAL_RETRY_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Max interval between attempts to acquire a lock.
+const MAX_RETRY_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Script to issue a new ticket.
+///
+/// Ticket counter expires, when no ticket is issued or
+/// lease is renewed for it's ttl. As it's ttl is longer than
+/// lease ttl, it outlives all the leases with it's tickets.
+///
+/// KEYS: ticket counter key.
+/// ARGV: ticket counter ttl in millis.
+static ISSUE_TICKET_SCRIPT: Lazy<Script> = Lazy::new(|| {
+    Script::new(
+        r"
+        local ticket = redis.call('INCR', KEYS[1])
+        redis.call('PEXPIRE', KEYS[1], ARGV[1])
+        return ticket
+        ",
+    )
+});
+
+/// Script to acquire a shared lease.
+///
+/// KEYS: writer key, readers key, writer intent key.
+/// ARGV: lease ttl in millis, ticket.
+static ACQUIRE_SHARED_SCRIPT: Lazy<Script> = Lazy::new(|| {
+    Script::new(
+        r"
+        if redis.call('EXISTS', KEYS[1]) == 1 or redis.call('EXISTS', KEYS[3]) == 1 then
+            return 0
+        end
+        local t = redis.call('TIME')
+        local now = tonumber(t[1]) * 1000 + math.floor(tonumber(t[2]) / 1000)
+        redis.call('ZREMRANGEBYSCORE', KEYS[2], '-inf', now)
+        redis.call('ZADD', KEYS[2], now + tonumber(ARGV[1]), ARGV[2])
+        if redis.call('PTTL', KEYS[2]) < tonumber(ARGV[1]) then
+            redis.call('PEXPIRE', KEYS[2], ARGV[1])
+        end
+        return 1
+        ",
+    )
+});
+
+/// Script to acquire an exclusive lease.
+///
+/// If there are live shared leases, it records writer
+/// intent, so that no new shared leases are granted
+/// until the writer acquires it's lease.
+///
+/// KEYS: writer key, readers key, writer intent key.
+/// ARGV: lease ttl in millis, ticket.
+static ACQUIRE_EXCLUSIVE_SCRIPT: Lazy<Script> = Lazy::new(|| {
+    Script::new(
+        r"
+        if redis.call('EXISTS', KEYS[1]) == 1 then
+            return 0
+        end
+        local intent = redis.call('GET', KEYS[3])
+        if intent and intent ~= ARGV[2] then
+            return 0
+        end
+        local t = redis.call('TIME')
+        local now = tonumber(t[1]) * 1000 + math.floor(tonumber(t[2]) / 1000)
+        redis.call('ZREMRANGEBYSCORE', KEYS[2], '-inf', now)
+        if redis.call('ZCARD', KEYS[2]) > 0 then
+            redis.call('SET', KEYS[3], ARGV[2], 'PX', ARGV[1])
+            return 0
+        end
+        redis.call('SET', KEYS[1], ARGV[2], 'PX', ARGV[1])
+        redis.call('DEL', KEYS[3])
+        return 1
+        ",
+    )
+});
+
+/// Script to renew a lease. It fails if the lease with
+/// given ticket is expired. It also extends the ticket
+/// counter's expiry.
+///
+/// KEYS: writer key, readers key, ticket counter key.
+/// ARGV: lease ttl in millis, ticket, lease kind, ticket
+/// counter ttl in millis.
+static RENEW_SCRIPT: Lazy<Script> = Lazy::new(|| {
+    Script::new(
+        r"
+        if ARGV[3] == 'exclusive' then
+            if redis.call('GET', KEYS[1]) == ARGV[2] then
+                redis.call('PEXPIRE', KEYS[1], ARGV[1])
+                redis.call('PEXPIRE', KEYS[3], ARGV[4])
+                return 1
+            end
+            return 0
+        end
+        local t = redis.call('TIME')
+        local now = tonumber(t[1]) * 1000 + math.floor(tonumber(t[2]) / 1000)
+        local expiry = redis.call('ZSCORE', KEYS[2], ARGV[2])
+        if not expiry or tonumber(expiry) <= now then
+            return 0
+        end
+        redis.call('ZADD', KEYS[2], now + tonumber(ARGV[1]), ARGV[2])
+        if redis.call('PTTL', KEYS[2]) < tonumber(ARGV[1]) then
+            redis.call('PEXPIRE', KEYS[2], ARGV[1])
+        end
+        redis.call('PEXPIRE', KEYS[3], ARGV[4])
+        return 1
+        ",
+    )
+});
+
+/// Script to release a lease. It is a no-op, if the lease
+/// with given ticket is no longer held.
+///
+/// KEYS: writer key, readers key.
+/// ARGV: ticket, lease kind.
+static RELEASE_SCRIPT: Lazy<Script> = Lazy::new(|| {
+    Script::new(
+        r"
+        if ARGV[2] == 'exclusive' then
+            if redis.call('GET', KEYS[1]) == ARGV[1] then
+                redis.call('DEL', KEYS[1])
+            end
+        else
+            redis.call('ZREM', KEYS[2], ARGV[1])
+        end
+        return 1
+        ",
+    )
+});
+
+/// Configuration for [`RedisNameLocker`].
+#[derive(Debug, Clone)]
+pub struct RedisNameLockerConfig {
+    /// Prefix of the redis keys of locks.
+    pub key_prefix: String,
+
+    /// Ttl of the lock leases. Leases are renewed at third
+    /// of this interval, while the lock is held.
+    pub lease_ttl: Duration,
+
+    /// Max duration to wait for acquiring a lock.
+    pub acquire_timeout: Duration,
+}
+
+impl Default for RedisNameLockerConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            key_prefix: "name_locker:".to_owned(),
+            lease_ttl: Duration::from_secs(30),
+            acquire_timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+/// An implementation of [`NameLocker`], that uses reader
+/// writer locks over a redis compatible server.
+///
+/// It can lock a name across hosts sharing the redis
+/// server. Locks are held as leases, which are renewed
+/// while the wrapped task or stream is being run. Thus
+/// locks of a crashed process are released on lease
+/// expiry.
+///
+/// Each lease is identified by a monotonic ticket. A holder
+/// whose lease has expired cannot renew or release the
+/// lease of a later holder. If a lease is lost before the
+/// wrapped task completes, task is dropped, and wrapper
+/// resolves to [`LockError::LockLost`]. Wrapped streams
+/// yield the error, and end.
+///
+/// Waiting writers block new readers, so that writers are
+/// not starved.
+///
+/// Names are additionally locked in memory, to avoid
+/// round trips for contention within same process.
+///
+/// On errors in communicating with redis, acquisition is
+/// retried after logging the error, until the configured
+/// acquire timeout. Each attempt, including establishing the
+/// connection, is bounded by the same deadline, so that an
+/// unresponsive server cannot block acquisition past it.
+/// Lease is considered lost, if it cannot be renewed in time
+/// to leave a renewal interval before it's expiry.
+#[derive(Clone)]
+pub struct RedisNameLocker<Name>
+where
+    Name: Ord + Hash + Clone + Send + Sync + 'static,
+{
+    /// Redis connector.
+    connector: Arc<RedisConnector>,
+
+    /// Config.
+    config: Arc<RedisNameLockerConfig>,
+
+    /// In process locker.
+    inmem_locker: InmemNameLocker<Name>,
+}
+
+impl<Name> Debug for RedisNameLocker<Name>
+where
+    Name: Ord + Hash + Clone + Send + Sync + 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisNameLocker")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl<Name> RedisNameLocker<Name>
+where
+    Name: AsRef<str> + Ord + Hash + Clone + Send + Sync + 'static,
+{
+    /// Create a new [`RedisNameLocker`] with given redis
+    /// client and config.
+    pub fn new(client: Client, config: RedisNameLockerConfig) -> Self {
+        Self {
+            connector: Arc::new(RedisConnector {
+                client,
+                connection: Mutex::new(None),
+            }),
+            config: Arc::new(config),
+            inmem_locker: InmemNameLocker::new(),
+        }
+    }
+
+    /// Try to create a new [`RedisNameLocker`] with given
+    /// redis url and config.
+    pub fn try_new(url: &str, config: RedisNameLockerConfig) -> RedisResult<Self> {
+        Ok(Self::new(Client::open(url)?, config))
+    }
+
+    /// Get the config.
+    #[inline]
+    pub fn config(&self) -> &RedisNameLockerConfig {
+        &self.config
+    }
+
+    /// Resolve lock keys of given name.
+    fn lock_keys(&self, name: &Name) -> LockKeys {
+        // Hash tag keeps keys of a lock in same cluster slot.
+        let base = format!("{}{{{}}}", self.config.key_prefix, name.as_ref());
+        LockKeys {
+            writer: format!("{}:w", base),
+            readers: format!("{}:r", base),
+            writer_intent: format!("{}:i", base),
+            ticket_counter: format!("{}:t", base),
+        }
+    }
+}
+
+impl<Name> NameLocker for RedisNameLocker<Name>
+where
+    Name: AsRef<str> + Ord + Hash + Clone + Send + Sync + 'static,
+{
+    type Name = Name;
+
+    fn poll_with_lock<Output, Task>(
+        &self,
+        task: Task,
+        name: Option<Self::Name>,
+        lock_kind: LockKind,
+    ) -> BoxFuture<'static, Result<Output, LockError>>
+    where
+        Task: Future<Output = Output> + Send + 'static,
+        Output: 'static,
+    {
+        if let Some(name) = name {
+            let lease_request = LeaseRequest {
+                connector: self.connector.clone(),
+                config: self.config.clone(),
+                keys: Arc::new(self.lock_keys(&name)),
+                kind: lock_kind.clone(),
+            };
+
+            let locked_task = self.inmem_locker.poll_with_lock(
+                async move {
+                    // Acquire lease.
+                    // This guard lasts across an await point.
+                    let mut guard = lease_request.acquire().await?;
+
+                    // Await task, dropping it if lease is lost.
+                    let output = match select(Box::pin(task), &mut guard.renewal).await {
+                        Either::Left((output, _)) => output,
+                        Either::Right(_) => return Err(LockError::LockLost),
+                    };
+
+                    // Drop guard explicitly.
+                    drop(guard);
+                    Ok(output)
+                },
+                Some(name),
+                lock_kind,
+            );
+
+            Box::pin(async move { locked_task.await.and_then(identity) })
+        } else {
+            // If name is `None`, directly await task.
+            Box::pin(async move { Ok(task.await) })
+        }
+    }
+
+    fn poll_read_with_lock<'s, S>(
+        &self,
+        in_stream: S,
+        name: Option<Self::Name>,
+        lock_kind: LockKind,
+    ) -> BoxStream<'s, Result<S::Item, LockError>>
+    where
+        S: Stream + Send + 's,
+        <S as Stream>::Item: Send,
+    {
+        if let Some(name) = name {
+            let lease_request = LeaseRequest {
+                connector: self.connector.clone(),
+                config: self.config.clone(),
+                keys: Arc::new(self.lock_keys(&name)),
+                kind: lock_kind.clone(),
+            };
+
+            Box::pin(
+                self.inmem_locker
+                    .poll_read_with_lock(
+                        stream! {
+                            // Acquire lease.
+                            // This guard lasts across an await point.
+                            let mut guard = match lease_request.acquire().await {
+                                Ok(guard) => guard,
+                                Err(e) => {
+                                    yield Err(e);
+                                    return;
+                                }
+                            };
+
+                            // Yield items, until lease is lost.
+                            let mut in_stream = Box::pin(in_stream);
+                            loop {
+                                match select(in_stream.next(), &mut guard.renewal).await {
+                                    Either::Left((Some(item), _)) => yield Ok(item),
+                                    Either::Left((None, _)) => break,
+                                    Either::Right(_) => {
+                                        yield Err(LockError::LockLost);
+                                        break;
+                                    }
+                                }
+                            }
+
+                            // Drop guard explicitly.
+                            drop(guard);
+                        },
+                        Some(name),
+                        lock_kind,
+                    )
+                    .map(|result| result.and_then(identity)),
+            )
+        } else {
+            // If name is `None`, directly return stream.
+            Box::pin(in_stream.map(Ok))
+        }
+    }
+}
+
+/// A struct to hold a lazily established, shared redis
+/// connection.
+struct RedisConnector {
+    /// Redis client.
+    client: Client,
+
+    /// Established connection.
+    connection: Mutex<Option<MultiplexedConnection>>,
+}
+
+impl RedisConnector {
+    /// Get a connection, establishing it if required.
+    async fn connection(&self) -> RedisResult<MultiplexedConnection> {
+        let mut connection = self.connection.lock().await;
+        if let Some(connection) = connection.as_ref() {
+            return Ok(connection.clone());
+        }
+
+        let new_connection = self.client.get_multiplexed_tokio_connection().await?;
+        *connection = Some(new_connection.clone());
+        Ok(new_connection)
+    }
+
+    /// Discard the established connection, so that a new
+    /// one will be established on next use.
+    async fn reset(&self) {
+        *self.connection.lock().await = None;
+    }
+}
+
+/// Redis keys of a lock.
+struct LockKeys {
+    /// Key of the exclusive lease.
+    writer: String,
+
+    /// Key of the shared leases.
+    readers: String,
+
+    /// Key of the writer intent.
+    writer_intent: String,
+
+    /// Key of the ticket counter.
+    ticket_counter: String,
+}
+
+/// A request for a lease.
+struct LeaseRequest {
+    connector: Arc<RedisConnector>,
+    config: Arc<RedisNameLockerConfig>,
+    keys: Arc<LockKeys>,
+    kind: LockKind,
+}
+
+impl LeaseRequest {
+    /// Acquire the requested lease, retrying until the
+    /// acquire timeout.
+    async fn acquire(self) -> Result<LeaseGuard, LockError> {
+        let deadline = Instant::now() + self.config.acquire_timeout;
+        let mut retry_interval = INITIAL_RETRY_INTERVAL;
+        let mut ticket = None;
+
+        let acquired_at = loop {
+            let attempted_at = Instant::now();
+
+            // Bound the attempt by the deadline, as redis
+            // calls have no response timeout of their own.
+            let attempt =
+                match tokio::time::timeout_at(deadline, self.try_acquire(&mut ticket)).await {
+                    Ok(attempt) => attempt,
+                    Err(_) => {
+                        error!(
+                            "Lease over {} is not acquired in time, as redis is unresponsive.",
+                            self.keys.writer
+                        );
+                        self.connector.reset().await;
+                        return Err(LockError::AcquireTimedOut);
+                    }
+                };
+
+            let last_error = match attempt {
+                Ok(true) => break attempted_at,
+                Ok(false) => None,
+                Err(e) => {
+                    error!("Error in acquiring lease. Error:\n {}", e);
+                    self.connector.reset().await;
+                    Some(e)
+                }
+            };
+
+            if Instant::now() + retry_interval >= deadline {
+                error!("Lease over {} is not acquired in time.", self.keys.writer);
+                return Err(last_error
+                    .map(|e| LockError::Io(io::Error::other(e)))
+                    .unwrap_or(LockError::AcquireTimedOut));
+            }
+
+            tokio::time::sleep(retry_interval).await;
+            retry_interval = (retry_interval * 2).min(MAX_RETRY_INTERVAL);
+        };
+
+        let ticket = ticket.expect("Must be some, as lease is acquired.");
+
+        Ok(LeaseGuard {
+            renewal: tokio::spawn(self.clone_ref().keep_renewed(ticket, acquired_at)),
+            request: self,
+            ticket,
+        })
+    }
+
+    /// Try to acquire the lease once. A ticket is issued
+    /// on first attempt, and reused in later attempts.
+    async fn try_acquire(&self, ticket: &mut Option<u64>) -> RedisResult<bool> {
+        let mut connection = self.connector.connection().await?;
+
+        let ticket = match ticket {
+            Some(ticket) => *ticket,
+            None => *ticket.insert(
+                ISSUE_TICKET_SCRIPT
+                    .key(&self.keys.ticket_counter)
+                    .arg(self.ticket_counter_ttl_millis())
+                    .invoke_async(&mut connection)
+                    .await?,
+            ),
+        };
+
+        let script = match self.kind {
+            LockKind::Shared => &*ACQUIRE_SHARED_SCRIPT,
+            LockKind::Exclusive => &*ACQUIRE_EXCLUSIVE_SCRIPT,
+        };
+
+        let acquired: i64 = script
+            .key(&self.keys.writer)
+            .key(&self.keys.readers)
+            .key(&self.keys.writer_intent)
+            .arg(self.lease_ttl_millis())
+            .arg(ticket)
+            .invoke_async(&mut connection)
+            .await?;
+
+        Ok(acquired == 1)
+    }
+
+    /// Keep the lease with given ticket renewed, until it is
+    /// lost.
+    ///
+    /// Lease is considered lost, if it is not renewed and
+    /// less than a renewal interval is left before it's
+    /// expiry. Each renewal attempt is bounded to end half a
+    /// renewal interval before expiry. Thus loss is declared
+    /// while the lease is still held, and before any other
+    /// holder can acquire it.
+    ///
+    /// Lease expiry is measured from the time acquisition or
+    /// renewal is requested, which is no later than when the
+    /// server grants it.
+    async fn keep_renewed(self, ticket: u64, acquired_at: Instant) {
+        let renewal_interval = self.config.lease_ttl / 3;
+        let mut last_renewed = acquired_at;
+        loop {
+            tokio::time::sleep(renewal_interval).await;
+
+            let lease_expiry = last_renewed + self.config.lease_ttl;
+            let renewal_requested = Instant::now();
+
+            let renewal_deadline = lease_expiry - renewal_interval / 2;
+            let renewed: RedisResult<i64> = match tokio::time::timeout_at(renewal_deadline, async {
+                let mut connection = self.connector.connection().await?;
+                RENEW_SCRIPT
+                    .key(&self.keys.writer)
+                    .key(&self.keys.readers)
+                    .key(&self.keys.ticket_counter)
+                    .arg(self.lease_ttl_millis())
+                    .arg(ticket)
+                    .arg(self.kind_str())
+                    .arg(self.ticket_counter_ttl_millis())
+                    .invoke_async(&mut connection)
+                    .await
+            })
+            .await
+            {
+                Ok(renewed) => renewed,
+                Err(_) => Err(io::Error::from(io::ErrorKind::TimedOut).into()),
+            };
+
+            match renewed {
+                Ok(1) => last_renewed = renewal_requested,
+                Ok(_) => {
+                    error!(
+                        "Lease with ticket {} over {} is lost.",
+                        ticket, self.keys.writer
+                    );
+                    return;
+                }
+                Err(e) => {
+                    warn!("Error in renewing lease. Error:\n {}", e);
+                    self.connector.reset().await;
+
+                    if last_renewed.elapsed() + renewal_interval >= self.config.lease_ttl {
+                        error!(
+                            "Lease with ticket {} over {} is considered lost, as it is not renewed in time.",
+                            ticket, self.keys.writer
+                        );
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Release the lease with given ticket.
+    ///
+    /// Release is bounded by the lease ttl, as the lease
+    /// expires by then anyway.
+    async fn release(self, ticket: u64) {
+        let released: RedisResult<i64> = match tokio::time::timeout(self.config.lease_ttl, async {
+            let mut connection = self.connector.connection().await?;
+            RELEASE_SCRIPT
+                .key(&self.keys.writer)
+                .key(&self.keys.readers)
+                .arg(ticket)
+                .arg(self.kind_str())
+                .invoke_async(&mut connection)
+                .await
+        })
+        .await
+        {
+            Ok(released) => released,
+            Err(_) => {
+                self.connector.reset().await;
+                Err(io::Error::from(io::ErrorKind::TimedOut).into())
+            }
+        };
+
+        if let Err(e) = released {
+            warn!(
+                "Error in releasing lease. It will be released on expiry. Error:\n {}",
+                e
+            );
+        }
+    }
+
+    #[inline]
+    fn lease_ttl_millis(&self) -> u64 {
+        self.config.lease_ttl.as_millis() as u64
+    }
+
+    /// Ttl of the ticket counter. It must be longer than the
+    /// lease ttl.
+    #[inline]
+    fn ticket_counter_ttl_millis(&self) -> u64 {
+        self.lease_ttl_millis() * 2
+    }
+
+    #[inline]
+    fn kind_str(&self) -> &'static str {
+        match self.kind {
+            LockKind::Shared => "shared",
+            LockKind::Exclusive => "exclusive",
+        }
+    }
+
+    #[inline]
+    fn clone_ref(&self) -> Self {
+        Self {
+            connector: self.connector.clone(),
+            config: self.config.clone(),
+            keys: self.keys.clone(),
+            kind: self.kind.clone(),
+        }
+    }
+}
+
+/// A guard of an acquired lease.
+///
+/// It's renewal task completes only if the lease is lost.
+/// On drop, it stops renewing the lease, and releases it.
+struct LeaseGuard {
+    request: LeaseRequest,
+    ticket: u64,
+    renewal: JoinHandle<()>,
+}
+
+impl Drop for LeaseGuard {
+    fn drop(&mut self) {
+        self.renewal.abort();
+
+        // Release lease in background.
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(self.request.clone_ref().release(self.ticket));
+            }
+            Err(_) => {
+                warn!("No runtime to release the lease. It will be released on expiry.");
+            }
+        }
+    }
+}
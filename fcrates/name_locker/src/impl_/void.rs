@@ -3,9 +3,9 @@
 
 use std::{hash::Hash, marker::PhantomData};
 
-use futures::{future::BoxFuture, stream::BoxStream};
+use futures::{future::BoxFuture, stream::BoxStream, StreamExt};
 
-use crate::{LockKind, NameLocker};
+use crate::{LockError, LockKind, NameLocker};
 
 /// An implementation of [`NameLocker`] that doesn't apply any locking.
 #[derive(Debug, Default, Clone)]
@@ -24,11 +24,12 @@ where
         task: Task,
         _name: Option<Self::Name>,
         _lock_kind: LockKind,
-    ) -> BoxFuture<'static, Output>
+    ) -> BoxFuture<'static, Result<Output, LockError>>
     where
         Task: futures::Future<Output = Output> + Send + 'static,
+        Output: 'static,
     {
-        Box::pin(task)
+        Box::pin(async move { Ok(task.await) })
     }
 
     #[inline]
@@ -37,11 +38,11 @@ where
         stream: S,
         _name: Option<Self::Name>,
         _lock_kind: LockKind,
-    ) -> BoxStream<'s, S::Item>
+    ) -> BoxStream<'s, Result<S::Item, LockError>>
     where
         S: futures::Stream + Send + 's,
         <S as futures::Stream>::Item: Send,
     {
-        Box::pin(stream)
+        Box::pin(stream.map(Ok))
     }
 }
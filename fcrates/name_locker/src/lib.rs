@@ -1,14 +1,17 @@
 //! This crate defines trait for asynchronous name lockers,
 //! that can run an async task with advisory-lock on a given name.
 //!
-//! It also provides a default inmemory implementation.
+//! It also provides a default inmemory implementation, and
+//! a filesystem backed implementation, that can lock names
+//! across processes, and a redis backed implementation, that
+//! can lock names across hosts.
 //!
 
 #![warn(missing_docs)]
 #![cfg_attr(doc_cfg, feature(doc_auto_cfg))]
 #![deny(unused_qualifications)]
 
-use std::hash::Hash;
+use std::{fmt::Display, hash::Hash, io};
 
 pub use futures::{future::BoxFuture, stream::BoxStream, Future, Stream};
 
@@ -24,28 +27,76 @@ pub enum LockKind {
     Exclusive,
 }
 
+/// An error in locking a name.
+#[derive(Debug)]
+pub enum LockError {
+    /// Lock could not be acquired within the deadline.
+    AcquireTimedOut,
+
+    /// Lock is lost, before the task is completed.
+    LockLost,
+
+    /// Io error in locking.
+    Io(io::Error),
+}
+
+impl Display for LockError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AcquireTimedOut => write!(f, "Lock could not be acquired within the deadline."),
+            Self::LockLost => write!(f, "Lock is lost, before the task is completed."),
+            Self::Io(e) => write!(f, "Io error in locking.\n{}", e),
+        }
+    }
+}
+
+impl std::error::Error for LockError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for LockError {
+    #[inline]
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
 /// A trait for name locker.
 pub trait NameLocker: Send + Sync + 'static {
     /// Type of the names.
     type Name: Ord + Hash + Send + Sync + 'static;
 
     /// Create a wrapper task that polls given task with specified locking on specified name.
+    ///
+    /// If the lock cannot be acquired, wrapper resolves to an
+    /// error without polling the task. If the lock is lost
+    /// while polling the task, task is dropped, and wrapper
+    /// resolves to an error.
     fn poll_with_lock<Output, Task>(
         &self,
         task: Task,
         name: Option<Self::Name>,
         lock_kind: LockKind,
-    ) -> BoxFuture<'static, Output>
+    ) -> BoxFuture<'static, Result<Output, LockError>>
     where
-        Task: Future<Output = Output> + Send + 'static;
+        Task: Future<Output = Output> + Send + 'static,
+        Output: 'static;
 
     /// Create a wrapper stream, that wraps given stream with specified locking on specified name.
+    ///
+    /// If the lock cannot be acquired, or is lost before the
+    /// stream is exhausted, wrapper yields an error and ends.
     fn poll_read_with_lock<'s, S>(
         &self,
         stream: S,
         name: Option<Self::Name>,
         lock_kind: LockKind,
-    ) -> BoxStream<'s, S::Item>
+    ) -> BoxStream<'s, Result<S::Item, LockError>>
     where
         S: Stream + Send + 's,
         <S as Stream>::Item: Send;
@@ -0,0 +1,278 @@
+//! Tests for [`FlockNameLocker`].
+//!
+//! Lockers in tests don't share their in memory lock
+//! tables, so that contention is resolved through lock
+//! files, as it is across processes.
+//!
+
+#![cfg(all(feature = "flock", unix))]
+
+use std::{
+    fs,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use futures::{stream, StreamExt};
+use name_locker::{impl_::FlockNameLocker, LockError, LockKind, NameLocker};
+use tempfile::TempDir;
+
+/// Resolve lockers sharing given lock directory.
+fn test_lockers(count: usize, lock_dir: &Path) -> Vec<Arc<FlockNameLocker<String>>> {
+    (0..count)
+        .map(|_| {
+            Arc::new(FlockNameLocker::try_new(lock_dir.to_owned()).expect("Must be valid dir"))
+        })
+        .collect()
+}
+
+/// Resolve number of lock files in given directory.
+fn lock_file_count(lock_dir: &Path) -> usize {
+    fs::read_dir(lock_dir).unwrap().count()
+}
+
+/// Run tasks concurrently over given lockers with given
+/// lock kinds, and return max number of concurrently
+/// running tasks.
+async fn max_concurrency(
+    lockers: &[Arc<FlockNameLocker<String>>],
+    lock_kinds: impl Iterator<Item = LockKind>,
+    task_duration: Duration,
+) -> usize {
+    let active = Arc::new(AtomicUsize::new(0));
+    let max_active = Arc::new(AtomicUsize::new(0));
+
+    let handles = lock_kinds
+        .enumerate()
+        .map(|(i, lock_kind)| {
+            let active = active.clone();
+            let max_active = max_active.clone();
+            tokio::spawn(lockers[i % lockers.len()].poll_with_lock(
+                async move {
+                    let current = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_active.fetch_max(current, Ordering::SeqCst);
+                    tokio::time::sleep(task_duration).await;
+                    active.fetch_sub(1, Ordering::SeqCst);
+                },
+                Some("res".to_owned()),
+                lock_kind,
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        handle
+            .await
+            .expect("Task must not panic")
+            .expect("Lock must be acquired");
+    }
+
+    max_active.load(Ordering::SeqCst)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn exclusive_locks_are_mutually_exclusive() {
+    let lock_dir = TempDir::new().unwrap();
+    let lockers = test_lockers(4, lock_dir.path());
+
+    assert_eq!(
+        max_concurrency(
+            &lockers,
+            (0..16).map(|_| LockKind::Exclusive),
+            Duration::from_millis(10)
+        )
+        .await,
+        1
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn shared_locks_are_concurrent() {
+    let lock_dir = TempDir::new().unwrap();
+    let lockers = test_lockers(3, lock_dir.path());
+
+    assert!(
+        max_concurrency(
+            &lockers,
+            (0..6).map(|_| LockKind::Shared),
+            Duration::from_millis(200)
+        )
+        .await
+            > 1
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stream_holds_lock_until_exhausted() {
+    let lock_dir = TempDir::new().unwrap();
+    let lockers = test_lockers(2, lock_dir.path());
+
+    let active = Arc::new(AtomicUsize::new(0));
+
+    let reader = {
+        let active = active.clone();
+        lockers[0].poll_read_with_lock(
+            stream::iter(0..5).then(move |i| {
+                let active = active.clone();
+                async move {
+                    active.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    active.fetch_sub(1, Ordering::SeqCst);
+                    i
+                }
+            }),
+            Some("res".to_owned()),
+            LockKind::Shared,
+        )
+    };
+
+    let reader_handle = tokio::spawn(reader.map(Result::unwrap).collect::<Vec<_>>());
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    let writer_active = active.clone();
+    lockers[1]
+        .poll_with_lock(
+            async move {
+                assert_eq!(writer_active.load(Ordering::SeqCst), 0);
+            },
+            Some("res".to_owned()),
+            LockKind::Exclusive,
+        )
+        .await
+        .expect("Lock must be acquired");
+
+    assert_eq!(reader_handle.await.unwrap(), vec![0, 1, 2, 3, 4]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn lock_files_are_removed_after_release() {
+    let lock_dir = TempDir::new().unwrap();
+    let lockers = test_lockers(2, lock_dir.path());
+
+    max_concurrency(
+        &lockers,
+        (0..4).map(|_| LockKind::Exclusive),
+        Duration::from_millis(5),
+    )
+    .await;
+
+    // Lock files are removed on the blocking thread pool.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(lock_file_count(lock_dir.path()), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stale_lock_files_are_removed() {
+    let lock_dir = TempDir::new().unwrap();
+    let locker = test_lockers(1, lock_dir.path()).remove(0);
+
+    fs::write(lock_dir.path().join("stale.lock"), b"").unwrap();
+    fs::write(lock_dir.path().join("other.txt"), b"").unwrap();
+
+    // Hold a lock, while removing stale lock files.
+    let (locked_tx, locked_rx) = tokio::sync::oneshot::channel();
+    let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+    let holder_handle = tokio::spawn(locker.poll_with_lock(
+        async move {
+            locked_tx.send(()).unwrap();
+            release_rx.await.unwrap();
+        },
+        Some("res".to_owned()),
+        LockKind::Shared,
+    ));
+    locked_rx.await.unwrap();
+
+    assert_eq!(locker.remove_stale_lock_files().unwrap(), 1);
+    assert!(!lock_dir.path().join("stale.lock").exists());
+    assert!(lock_dir.path().join("other.txt").exists());
+    // Held lock file is not removed.
+    assert_eq!(lock_file_count(lock_dir.path()), 2);
+
+    release_tx.send(()).unwrap();
+    holder_handle.await.unwrap().unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn io_errors_fail_without_polling_task() {
+    let lock_dir = TempDir::new().unwrap();
+    let locker = test_lockers(1, lock_dir.path()).remove(0);
+
+    // Lock files cannot be created in a removed directory.
+    fs::remove_dir(lock_dir.path()).unwrap();
+
+    let is_polled = Arc::new(AtomicBool::new(false));
+    let task_is_polled = is_polled.clone();
+
+    let result = locker
+        .poll_with_lock(
+            async move { task_is_polled.store(true, Ordering::SeqCst) },
+            Some("res".to_owned()),
+            LockKind::Exclusive,
+        )
+        .await;
+
+    assert!(matches!(result, Err(LockError::Io(_))));
+    assert!(!is_polled.load(Ordering::SeqCst));
+
+    let items = locker
+        .poll_read_with_lock(stream::iter(0..3), Some("res".to_owned()), LockKind::Shared)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(items.len(), 1);
+    assert!(matches!(items[0], Err(LockError::Io(_))));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn acquire_times_out_without_polling_task() {
+    let lock_dir = TempDir::new().unwrap();
+    let holder = test_lockers(1, lock_dir.path()).remove(0);
+    let waiter = FlockNameLocker::<String>::try_new(lock_dir.path().to_owned())
+        .expect("Must be valid dir")
+        .with_acquire_timeout(Duration::from_millis(200));
+
+    // Hold the lock until released.
+    let (acquired_tx, acquired_rx) = tokio::sync::oneshot::channel();
+    let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+    let holder_handle = tokio::spawn(holder.poll_with_lock(
+        async move {
+            acquired_tx.send(()).unwrap();
+            release_rx.await.unwrap();
+        },
+        Some("res".to_owned()),
+        LockKind::Exclusive,
+    ));
+    acquired_rx.await.unwrap();
+
+    let is_polled = Arc::new(AtomicBool::new(false));
+    let task_is_polled = is_polled.clone();
+
+    let result = waiter
+        .poll_with_lock(
+            async move { task_is_polled.store(true, Ordering::SeqCst) },
+            Some("res".to_owned()),
+            LockKind::Shared,
+        )
+        .await;
+    assert!(matches!(result, Err(LockError::AcquireTimedOut)));
+    assert!(!is_polled.load(Ordering::SeqCst));
+
+    let items = waiter
+        .poll_read_with_lock(stream::iter(0..3), Some("res".to_owned()), LockKind::Shared)
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(items.len(), 1);
+    assert!(matches!(items[0], Err(LockError::AcquireTimedOut)));
+
+    // Lock is acquired once released.
+    release_tx.send(()).unwrap();
+    holder_handle.await.unwrap().unwrap();
+    assert!(waiter
+        .poll_with_lock(async {}, Some("res".to_owned()), LockKind::Exclusive)
+        .await
+        .is_ok());
+}
@@ -0,0 +1,440 @@
+//! Tests for [`RedisNameLocker`].
+//!
+//! Tests that need a server run against a `redis-server`
+//! spawned on a free local port for each test. Binary can
+//! be set through `NAME_LOCKER_REDIS_SERVER`.
+//!
+
+#![cfg(feature = "redis")]
+
+use std::{
+    net::{TcpListener, TcpStream},
+    process::{Child, Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use futures::{stream, StreamExt};
+use name_locker::{
+    impl_::{RedisNameLocker, RedisNameLockerConfig},
+    LockError, LockKind, NameLocker,
+};
+use redis::AsyncCommands;
+use tempfile::TempDir;
+
+/// A redis server, spawned for a test.
+struct TestServer {
+    /// Url of the server.
+    url: String,
+
+    /// Server process.
+    process: Child,
+
+    /// Working directory of the server.
+    _dir: TempDir,
+}
+
+impl TestServer {
+    /// Spawn a new server on a free local port, and wait
+    /// until it accepts connections.
+    fn spawn() -> Self {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .expect("Must get a free port")
+            .port();
+        let dir = tempfile::tempdir().expect("Must create temp dir");
+
+        let process = Command::new(
+            std::env::var("NAME_LOCKER_REDIS_SERVER").unwrap_or_else(|_| "redis-server".into()),
+        )
+        .args(["--port", &port.to_string(), "--bind", "127.0.0.1"])
+        .args(["--save", "", "--appendonly", "no"])
+        .current_dir(dir.path())
+        .stdout(Stdio::null())
+        .spawn()
+        .expect("redis-server must be installed to run redis tests");
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while TcpStream::connect(("127.0.0.1", port)).is_err() {
+            assert!(Instant::now() < deadline, "redis-server must start in time");
+            std::thread::sleep(Duration::from_millis(20));
+        }
+
+        Self {
+            url: format!("redis://127.0.0.1:{}/", port),
+            process,
+            _dir: dir,
+        }
+    }
+
+    /// Stop the server.
+    fn stop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+
+    /// Get a connection to the server.
+    async fn connection(&self) -> redis::aio::MultiplexedConnection {
+        redis::Client::open(self.url.as_str())
+            .unwrap()
+            .get_multiplexed_tokio_connection()
+            .await
+            .unwrap()
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Resolve a unique key prefix for a test.
+fn test_key_prefix() -> String {
+    format!(
+        "name_locker_test:{}:",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    )
+}
+
+/// Resolve lockers over given test server, sharing given
+/// key prefix.
+fn test_lockers_with_prefix(
+    server: &TestServer,
+    count: usize,
+    lease_ttl: Duration,
+    key_prefix: &str,
+) -> Vec<Arc<RedisNameLocker<String>>> {
+    (0..count)
+        .map(|_| {
+            Arc::new(
+                RedisNameLocker::try_new(
+                    &server.url,
+                    RedisNameLockerConfig {
+                        key_prefix: key_prefix.to_owned(),
+                        lease_ttl,
+                        ..Default::default()
+                    },
+                )
+                .expect("Must be valid url"),
+            )
+        })
+        .collect()
+}
+
+/// Resolve lockers over given test server, sharing a
+/// unique key prefix.
+fn test_lockers(
+    server: &TestServer,
+    count: usize,
+    lease_ttl: Duration,
+) -> Vec<Arc<RedisNameLocker<String>>> {
+    test_lockers_with_prefix(server, count, lease_ttl, &test_key_prefix())
+}
+
+/// Run tasks concurrently over given lockers with given
+/// lock kinds, and return max number of concurrently
+/// running tasks.
+async fn max_concurrency(
+    lockers: &[Arc<RedisNameLocker<String>>],
+    lock_kinds: impl Iterator<Item = LockKind>,
+    task_duration: Duration,
+) -> usize {
+    let active = Arc::new(AtomicUsize::new(0));
+    let max_active = Arc::new(AtomicUsize::new(0));
+
+    let handles = lock_kinds
+        .enumerate()
+        .map(|(i, lock_kind)| {
+            let active = active.clone();
+            let max_active = max_active.clone();
+            tokio::spawn(lockers[i % lockers.len()].poll_with_lock(
+                async move {
+                    let current = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_active.fetch_max(current, Ordering::SeqCst);
+                    tokio::time::sleep(task_duration).await;
+                    active.fetch_sub(1, Ordering::SeqCst);
+                },
+                Some("res".to_owned()),
+                lock_kind,
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        handle
+            .await
+            .expect("Task must not panic")
+            .expect("Lock must be acquired");
+    }
+
+    max_active.load(Ordering::SeqCst)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn acquisition_fails_after_timeout() {
+    // No server listens on this port.
+    let locker = RedisNameLocker::<String>::try_new(
+        "redis://127.0.0.1:1/",
+        RedisNameLockerConfig {
+            acquire_timeout: Duration::from_millis(100),
+            ..Default::default()
+        },
+    )
+    .expect("Must be valid url");
+
+    let is_polled = Arc::new(AtomicBool::new(false));
+    let task_is_polled = is_polled.clone();
+
+    let result = locker
+        .poll_with_lock(
+            async move { task_is_polled.store(true, Ordering::SeqCst) },
+            Some("res".to_owned()),
+            LockKind::Exclusive,
+        )
+        .await;
+
+    assert!(matches!(result, Err(LockError::Io(_))));
+    assert!(!is_polled.load(Ordering::SeqCst));
+
+    let items = locker
+        .poll_read_with_lock(stream::iter(0..3), Some("res".to_owned()), LockKind::Shared)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(items.len(), 1);
+    assert!(matches!(items[0], Err(LockError::Io(_))));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn acquisition_fails_in_time_against_unresponsive_server() {
+    // Listener accepts connections, but never responds.
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Must bind to a free port");
+    let locker = RedisNameLocker::<String>::try_new(
+        &format!("redis://{}/", listener.local_addr().unwrap()),
+        RedisNameLockerConfig {
+            acquire_timeout: Duration::from_millis(200),
+            ..Default::default()
+        },
+    )
+    .expect("Must be valid url");
+
+    let started = Instant::now();
+    let result = locker
+        .poll_with_lock(async {}, Some("res".to_owned()), LockKind::Exclusive)
+        .await;
+
+    assert!(matches!(result, Err(LockError::AcquireTimedOut)));
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn exclusive_locks_are_mutually_exclusive() {
+    let server = TestServer::spawn();
+    let lockers = test_lockers(&server, 3, Duration::from_secs(5));
+
+    assert_eq!(
+        max_concurrency(
+            &lockers,
+            (0..12).map(|_| LockKind::Exclusive),
+            Duration::from_millis(10)
+        )
+        .await,
+        1
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn shared_locks_are_concurrent() {
+    let server = TestServer::spawn();
+    let lockers = test_lockers(&server, 3, Duration::from_secs(5));
+
+    assert!(
+        max_concurrency(
+            &lockers,
+            (0..6).map(|_| LockKind::Shared),
+            Duration::from_millis(200)
+        )
+        .await
+            > 1
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn leases_are_renewed_while_task_runs() {
+    let server = TestServer::spawn();
+    let lockers = test_lockers(&server, 2, Duration::from_millis(300));
+
+    // Tasks outlive lease ttl many times.
+    assert_eq!(
+        max_concurrency(
+            &lockers,
+            (0..2).map(|_| LockKind::Exclusive),
+            Duration::from_millis(1200)
+        )
+        .await,
+        1
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn exclusive_acquisition_times_out_under_contention() {
+    let server = TestServer::spawn();
+    let key_prefix = test_key_prefix();
+    let holder =
+        test_lockers_with_prefix(&server, 1, Duration::from_secs(5), &key_prefix).remove(0);
+    let waiter = RedisNameLocker::<String>::try_new(
+        &server.url,
+        RedisNameLockerConfig {
+            key_prefix,
+            acquire_timeout: Duration::from_millis(200),
+            ..Default::default()
+        },
+    )
+    .expect("Must be valid url");
+
+    let holder_handle = tokio::spawn(holder.poll_with_lock(
+        tokio::time::sleep(Duration::from_secs(1)),
+        Some("res".to_owned()),
+        LockKind::Exclusive,
+    ));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert!(matches!(
+        waiter
+            .poll_with_lock(async {}, Some("res".to_owned()), LockKind::Exclusive)
+            .await,
+        Err(LockError::AcquireTimedOut)
+    ));
+    holder_handle.await.unwrap().expect("Lock must be acquired");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn task_is_dropped_on_lease_loss() {
+    let server = TestServer::spawn();
+    let key_prefix = test_key_prefix();
+    let locker =
+        test_lockers_with_prefix(&server, 1, Duration::from_millis(300), &key_prefix).remove(0);
+
+    let is_completed = Arc::new(AtomicBool::new(false));
+    let task_is_completed = is_completed.clone();
+
+    let handle = tokio::spawn(locker.poll_with_lock(
+        async move {
+            tokio::time::sleep(Duration::from_secs(2)).await;
+            task_is_completed.store(true, Ordering::SeqCst);
+        },
+        Some("res".to_owned()),
+        LockKind::Exclusive,
+    ));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // Expire the lease behind the holder's back.
+    let mut connection = server.connection().await;
+    let _: () = connection
+        .del(format!("{}{{res}}:w", key_prefix))
+        .await
+        .unwrap();
+
+    assert!(matches!(handle.await.unwrap(), Err(LockError::LockLost)));
+    assert!(!is_completed.load(Ordering::SeqCst));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn lease_loss_is_declared_before_expiry() {
+    let mut server = TestServer::spawn();
+    let lease_ttl = Duration::from_millis(600);
+    let locker = test_lockers(&server, 1, lease_ttl).remove(0);
+
+    let acquired_at = Arc::new(std::sync::Mutex::new(None));
+    let task_acquired_at = acquired_at.clone();
+
+    let handle = tokio::spawn(locker.poll_with_lock(
+        async move {
+            *task_acquired_at.lock().unwrap() = Some(Instant::now());
+            tokio::time::sleep(Duration::from_secs(3)).await;
+        },
+        Some("res".to_owned()),
+        LockKind::Exclusive,
+    ));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // Lease can no longer be renewed.
+    server.stop();
+
+    assert!(matches!(handle.await.unwrap(), Err(LockError::LockLost)));
+    let acquired_at = acquired_at
+        .lock()
+        .unwrap()
+        .expect("Task must have been polled");
+    assert!(acquired_at.elapsed() < lease_ttl);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn ticket_counter_expires() {
+    let server = TestServer::spawn();
+    let key_prefix = test_key_prefix();
+    let locker =
+        test_lockers_with_prefix(&server, 1, Duration::from_secs(5), &key_prefix).remove(0);
+
+    locker
+        .poll_with_lock(async {}, Some("res".to_owned()), LockKind::Shared)
+        .await
+        .expect("Lock must be acquired");
+
+    let mut connection = server.connection().await;
+    let pttl: i64 = connection
+        .pttl(format!("{}{{res}}:t", key_prefix))
+        .await
+        .unwrap();
+
+    assert!(pttl > 0 && pttl <= 10_000);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stream_holds_lock_until_exhausted() {
+    let server = TestServer::spawn();
+    let lockers = test_lockers(&server, 2, Duration::from_secs(5));
+
+    let active = Arc::new(AtomicUsize::new(0));
+
+    let reader = {
+        let active = active.clone();
+        lockers[0].poll_read_with_lock(
+            stream::iter(0..5).then(move |i| {
+                let active = active.clone();
+                async move {
+                    active.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    active.fetch_sub(1, Ordering::SeqCst);
+                    i
+                }
+            }),
+            Some("res".to_owned()),
+            LockKind::Shared,
+        )
+    };
+
+    let reader_handle = tokio::spawn(reader.map(Result::unwrap).collect::<Vec<_>>());
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    let writer_active = active.clone();
+    lockers[1]
+        .poll_with_lock(
+            async move {
+                assert_eq!(writer_active.load(Ordering::SeqCst), 0);
+            },
+            Some("res".to_owned()),
+            LockKind::Exclusive,
+        )
+        .await
+        .expect("Lock must be acquired");
+
+    assert_eq!(reader_handle.await.unwrap(), vec![0, 1, 2, 3, 4]);
+}
@@ -5,6 +5,9 @@
 #[cfg(feature = "solid-insert-delete-patch")]
 pub mod solid_insert_delete;
 
+#[cfg(feature = "sparql-update-patch")]
+pub mod sparql_update;
+
 /// An enum of operations that can be performed by a patcher.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PatchEffectiveOperation {
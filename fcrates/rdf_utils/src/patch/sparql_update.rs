@@ -0,0 +1,650 @@
+//! I define types for implementing sparql update patch.
+//!
+//! Only a subset of [SPARQL 1.1 Update](https://www.w3.org/TR/sparql11-update/)
+//! is supported, which operates over a single graph. It
+//! consists of `INSERT DATA`, `DELETE DATA` and
+//! `DELETE/INSERT ... WHERE` operations, with basic graph
+//! patterns as conditions.
+//!
+
+use std::{collections::HashSet, sync::Arc};
+
+use mime::Mime;
+use once_cell::sync::Lazy;
+use sophia_api::{
+    dataset::{Dataset, SetDataset},
+    graph::Graph,
+    term::{BnodeId, IriRef, LanguageTag, VarName},
+};
+use spargebra::{
+    algebra::GraphPattern,
+    term::{
+        BlankNode, GraphName, GraphNamePattern, GroundQuad, GroundQuadPattern, GroundSubject,
+        GroundTerm, GroundTermPattern, Literal, NamedNode, NamedNodePattern, Quad, QuadPattern,
+        Subject, Term, TermPattern, TriplePattern, Variable,
+    },
+    GraphUpdateOperation, Update,
+};
+use tracing::{debug, error, info};
+
+use super::PatchEffectiveOperation;
+use crate::{
+    model::{
+        dataset::{InfallibleDataset, InfallibleMutableDataset},
+        term::{ArcIri, ArcTerm},
+    },
+    query::{BindingMap, Query},
+};
+
+/// `application/sparql-update` media-type.
+pub static APPLICATION_SPARQL_UPDATE: Lazy<Mime> =
+    Lazy::new(|| "application/sparql-update".parse().expect("Must be valid"));
+
+/// Prefix of variable names, that blank nodes in conditions
+/// are translated into.
+const BNODE_VAR_PREFIX: &str = "_bnode_";
+
+/// Type of set of triples.
+type TripleSet = HashSet<[ArcTerm; 3]>;
+
+/// A struct representing a valid sparql update patch, that
+/// operates over a single graph.
+#[derive(Debug, Clone)]
+pub struct SparqlUpdatePatch {
+    /// Patch operations.
+    operations: Vec<SparqlUpdatePatchOperation>,
+}
+
+/// An enum of supported sparql update operations.
+#[derive(Debug, Clone)]
+enum SparqlUpdatePatchOperation {
+    /// `INSERT DATA` operation.
+    InsertData {
+        /// Triples to be inserted.
+        insertions: Vec<[ArcTerm; 3]>,
+    },
+
+    /// `DELETE DATA` operation.
+    DeleteData {
+        /// Triples to be deleted.
+        deletions: Vec<[ArcTerm; 3]>,
+    },
+
+    /// `DELETE/INSERT ... WHERE` operation.
+    DeleteInsert {
+        /// Deletion templates.
+        deletions: Vec<[ArcTerm; 3]>,
+
+        /// Insertion templates.
+        insertions: Vec<[ArcTerm; 3]>,
+
+        /// Conditions basic graph pattern.
+        conditions: Vec<[ArcTerm; 3]>,
+    },
+}
+
+impl SparqlUpdatePatch {
+    /// Parse [`SparqlUpdatePatch`] from given update string.
+    #[tracing::instrument(name = "parse_sparql_update_patch", skip_all)]
+    pub fn parse(update: &str, base_uri: Option<ArcIri>) -> Result<Self, InvalidSparqlUpdatePatch> {
+        let update =
+            Update::parse(update, base_uri.as_ref().map(|uri| uri.as_str())).map_err(|e| {
+                error!("Error in parsing sparql update. Error:\n {}", e);
+                InvalidSparqlUpdatePatch::InvalidSparqlUpdate
+            })?;
+
+        Ok(Self {
+            operations: update
+                .operations
+                .into_iter()
+                .map(SparqlUpdatePatchOperation::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Resolve effective ops of the patch operation.
+    pub fn effective_ops(&self) -> HashSet<PatchEffectiveOperation> {
+        let mut ops = HashSet::new();
+
+        for operation in &self.operations {
+            let (deletions, insertions, conditions) = match operation {
+                SparqlUpdatePatchOperation::InsertData { insertions } => {
+                    (&[][..], insertions.as_slice(), &[][..])
+                }
+                SparqlUpdatePatchOperation::DeleteData { deletions } => {
+                    (deletions.as_slice(), &[][..], &[][..])
+                }
+                SparqlUpdatePatchOperation::DeleteInsert {
+                    deletions,
+                    insertions,
+                    conditions,
+                } => (
+                    deletions.as_slice(),
+                    insertions.as_slice(),
+                    conditions.as_slice(),
+                ),
+            };
+
+            // Conditions are evaluated against the target.
+            if !conditions.is_empty() {
+                ops.insert(PatchEffectiveOperation::Read);
+            }
+
+            if !insertions.is_empty() {
+                ops.insert(PatchEffectiveOperation::Append);
+            }
+
+            // Deletions are conditional on target state.
+            if !deletions.is_empty() {
+                ops.insert(PatchEffectiveOperation::Read);
+                ops.insert(PatchEffectiveOperation::Write);
+            }
+        }
+
+        ops
+    }
+
+    /// Apply the patch against given target dataset.
+    /// Operations are applied in order, to the default graph
+    /// of the dataset.
+    ///
+    /// Unlike plain sparql update semantics, the patch fails
+    /// with a conflict, if any triple of a `DELETE DATA`
+    /// operation doesn't exist in the target, or if conditions
+    /// of a `DELETE/INSERT` operation doesn't match.
+    #[tracing::instrument(name = "apply_sparql_update_patch", skip_all)]
+    pub fn apply<TD: InfallibleMutableDataset + SetDataset>(
+        &self,
+        mut target_dataset: TD,
+    ) -> Result<TD, SparqlUpdatePatchError> {
+        for operation in &self.operations {
+            let (deletions, insertions, strict_deletions) = match operation {
+                SparqlUpdatePatchOperation::InsertData { insertions } => (
+                    HashSet::new(),
+                    Self::with_fresh_bnodes(insertions.iter().cloned()),
+                    false,
+                ),
+                SparqlUpdatePatchOperation::DeleteData { deletions } => {
+                    (deletions.iter().cloned().collect(), HashSet::new(), true)
+                }
+                SparqlUpdatePatchOperation::DeleteInsert {
+                    deletions,
+                    insertions,
+                    conditions,
+                } => {
+                    let (deletions, insertions) = Self::resolve_templates(
+                        &target_dataset,
+                        deletions,
+                        insertions,
+                        conditions,
+                    )?;
+                    (deletions, insertions, false)
+                }
+            };
+
+            if !deletions.is_empty() {
+                let removed = target_dataset
+                    .remove_all(deletions.as_dataset().quads())
+                    .map_err(|_| {
+                        error!("Error in removing triples from target dataset.");
+                        SparqlUpdatePatchError::TargetDatasetMutationError
+                    })?;
+
+                if strict_deletions && removed != deletions.len() {
+                    error!("Deleted data is not a subset of target graph.");
+                    return Err(SparqlUpdatePatchError::DeletionsIsNotSubSetOfTargetGraph);
+                }
+
+                debug!("Removed {} triples from target dataset.", removed);
+            }
+
+            target_dataset
+                .insert_all(insertions.as_dataset().quads())
+                .map_err(|_| {
+                    error!("Error in inserting triples into target dataset.");
+                    SparqlUpdatePatchError::TargetDatasetMutationError
+                })?;
+        }
+
+        info!("Patch application success.");
+
+        Ok(target_dataset)
+    }
+
+    /// Evaluate conditions, and resolve effective deletions
+    /// and insertions by instantiating templates for each
+    /// matched solution.
+    fn resolve_templates<TD: InfallibleMutableDataset>(
+        target_dataset: &TD,
+        deletions: &[[ArcTerm; 3]],
+        insertions: &[[ArcTerm; 3]],
+        conditions: &[[ArcTerm; 3]],
+    ) -> Result<(TripleSet, TripleSet), SparqlUpdatePatchError> {
+        let solutions = Query::Triples(conditions.to_vec())
+            .process(&target_dataset.graph_view(Option::<ArcTerm>::None))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| {
+                error!("Error in querying target dataset.");
+                SparqlUpdatePatchError::TargetDatasetQueryError
+            })?;
+
+        if solutions.is_empty() {
+            error!("No variable mappings matched the conditions.");
+            return Err(SparqlUpdatePatchError::NoMatchedVariableMappings);
+        }
+
+        let mut resolved_deletions = HashSet::new();
+        let mut resolved_insertions = HashSet::new();
+
+        for binding_map in &solutions {
+            resolved_deletions.extend(Self::instantiate(binding_map, deletions));
+            // Each solution gets fresh blank nodes.
+            resolved_insertions.extend(Self::with_fresh_bnodes(Self::instantiate(
+                binding_map,
+                insertions,
+            )));
+        }
+
+        Ok((resolved_deletions, resolved_insertions))
+    }
+
+    /// Instantiate given templates with given bindings.
+    /// Triples with unbound variables are skipped.
+    fn instantiate<'t>(
+        binding_map: &'t BindingMap,
+        templates: &'t [[ArcTerm; 3]],
+    ) -> impl Iterator<Item = [ArcTerm; 3]> + 't {
+        templates.iter().filter_map(|template| {
+            let mut triple = template.clone();
+            for term in triple.iter_mut() {
+                if let ArcTerm::Variable(var) = term {
+                    *term = binding_map.get(var.as_str())?.clone();
+                }
+            }
+            Some(triple)
+        })
+    }
+
+    /// Replace blank nodes in given triples with fresh ones.
+    fn with_fresh_bnodes(triples: impl Iterator<Item = [ArcTerm; 3]>) -> TripleSet {
+        // Get a random suffix to make any blank node
+        // identifiers random.
+        let bnode_random_suffix = rand::random::<u16>();
+
+        triples
+            .map(|mut triple| {
+                for term in triple.iter_mut() {
+                    if let ArcTerm::BlankNode(bnode_id) = term {
+                        *term = ArcTerm::BlankNode(BnodeId::new_unchecked(
+                            format!("{}_{}", bnode_id.as_str(), bnode_random_suffix).into(),
+                        ));
+                    }
+                }
+                triple
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<GraphUpdateOperation> for SparqlUpdatePatchOperation {
+    type Error = InvalidSparqlUpdatePatch;
+
+    fn try_from(operation: GraphUpdateOperation) -> Result<Self, Self::Error> {
+        match operation {
+            GraphUpdateOperation::InsertData { data } => Ok(Self::InsertData {
+                insertions: data
+                    .into_iter()
+                    .map(quad_triple)
+                    .collect::<Result<_, _>>()?,
+            }),
+            GraphUpdateOperation::DeleteData { data } => Ok(Self::DeleteData {
+                deletions: data
+                    .into_iter()
+                    .map(ground_quad_triple)
+                    .collect::<Result<_, _>>()?,
+            }),
+            GraphUpdateOperation::DeleteInsert {
+                delete,
+                insert,
+                using,
+                pattern,
+            } => {
+                if using.is_some() {
+                    error!("Sparql update operation specifies a dataset.");
+                    return Err(InvalidSparqlUpdatePatch::UnsupportedGraphTarget);
+                }
+
+                let conditions = match *pattern {
+                    GraphPattern::Bgp { patterns } => patterns
+                        .into_iter()
+                        .map(triple_pattern_triple)
+                        .collect::<Result<_, _>>()?,
+                    _ => {
+                        error!("Sparql update conditions is not a basic graph pattern.");
+                        return Err(InvalidSparqlUpdatePatch::UnsupportedGraphPattern);
+                    }
+                };
+
+                Ok(Self::DeleteInsert {
+                    deletions: delete
+                        .into_iter()
+                        .map(ground_quad_pattern_triple)
+                        .collect::<Result<_, _>>()?,
+                    insertions: insert
+                        .into_iter()
+                        .map(quad_pattern_triple)
+                        .collect::<Result<_, _>>()?,
+                    conditions,
+                })
+            }
+            _ => {
+                error!("Unsupported sparql update operation.");
+                Err(InvalidSparqlUpdatePatch::UnsupportedOperation)
+            }
+        }
+    }
+}
+
+/// Ensure given graph name is default graph.
+fn ensure_default_graph(is_default: bool) -> Result<(), InvalidSparqlUpdatePatch> {
+    if !is_default {
+        error!("Sparql update operation targets a named graph.");
+        return Err(InvalidSparqlUpdatePatch::UnsupportedGraphTarget);
+    }
+    Ok(())
+}
+
+fn quad_triple(quad: Quad) -> Result<[ArcTerm; 3], InvalidSparqlUpdatePatch> {
+    ensure_default_graph(quad.graph_name == GraphName::DefaultGraph)?;
+    Ok([
+        subject_term(quad.subject),
+        named_node_term(quad.predicate),
+        term_term(quad.object),
+    ])
+}
+
+fn ground_quad_triple(quad: GroundQuad) -> Result<[ArcTerm; 3], InvalidSparqlUpdatePatch> {
+    ensure_default_graph(quad.graph_name == GraphName::DefaultGraph)?;
+    Ok([
+        ground_subject_term(quad.subject),
+        named_node_term(quad.predicate),
+        ground_term_term(quad.object),
+    ])
+}
+
+fn quad_pattern_triple(pattern: QuadPattern) -> Result<[ArcTerm; 3], InvalidSparqlUpdatePatch> {
+    ensure_default_graph(pattern.graph_name == GraphNamePattern::DefaultGraph)?;
+    Ok([
+        term_pattern_term(pattern.subject, false),
+        named_node_pattern_term(pattern.predicate),
+        term_pattern_term(pattern.object, false),
+    ])
+}
+
+fn ground_quad_pattern_triple(
+    pattern: GroundQuadPattern,
+) -> Result<[ArcTerm; 3], InvalidSparqlUpdatePatch> {
+    ensure_default_graph(pattern.graph_name == GraphNamePattern::DefaultGraph)?;
+    Ok([
+        ground_term_pattern_term(pattern.subject),
+        named_node_pattern_term(pattern.predicate),
+        ground_term_pattern_term(pattern.object),
+    ])
+}
+
+fn triple_pattern_triple(pattern: TriplePattern) -> Result<[ArcTerm; 3], InvalidSparqlUpdatePatch> {
+    Ok([
+        // Blank nodes in conditions act as variables.
+        term_pattern_term(pattern.subject, true),
+        named_node_pattern_term(pattern.predicate),
+        term_pattern_term(pattern.object, true),
+    ])
+}
+
+fn named_node_term(node: NamedNode) -> ArcTerm {
+    ArcTerm::Iri(IriRef::new_unchecked(node.into_string().into()))
+}
+
+fn blank_node_term(node: BlankNode) -> ArcTerm {
+    ArcTerm::BlankNode(BnodeId::new_unchecked(node.into_string().into()))
+}
+
+fn variable_term(var: Variable) -> ArcTerm {
+    ArcTerm::Variable(VarName::new_unchecked(var.into_string().into()))
+}
+
+fn literal_term(literal: Literal) -> ArcTerm {
+    let value: Arc<str> = literal.value().into();
+    if let Some(language) = literal.language() {
+        ArcTerm::LiteralLanguage(value, LanguageTag::new_unchecked(language.into()))
+    } else {
+        ArcTerm::LiteralDatatype(
+            value,
+            IriRef::new_unchecked(literal.datatype().as_str().into()),
+        )
+    }
+}
+
+fn subject_term(subject: Subject) -> ArcTerm {
+    match subject {
+        Subject::NamedNode(node) => named_node_term(node),
+        Subject::BlankNode(node) => blank_node_term(node),
+    }
+}
+
+fn term_term(term: Term) -> ArcTerm {
+    match term {
+        Term::NamedNode(node) => named_node_term(node),
+        Term::BlankNode(node) => blank_node_term(node),
+        Term::Literal(literal) => literal_term(literal),
+    }
+}
+
+fn ground_subject_term(subject: GroundSubject) -> ArcTerm {
+    match subject {
+        GroundSubject::NamedNode(node) => named_node_term(node),
+    }
+}
+
+fn ground_term_term(term: GroundTerm) -> ArcTerm {
+    match term {
+        GroundTerm::NamedNode(node) => named_node_term(node),
+        GroundTerm::Literal(literal) => literal_term(literal),
+    }
+}
+
+fn named_node_pattern_term(pattern: NamedNodePattern) -> ArcTerm {
+    match pattern {
+        NamedNodePattern::NamedNode(node) => named_node_term(node),
+        NamedNodePattern::Variable(var) => variable_term(var),
+    }
+}
+
+fn term_pattern_term(pattern: TermPattern, bnode_as_var: bool) -> ArcTerm {
+    match pattern {
+        TermPattern::NamedNode(node) => named_node_term(node),
+        TermPattern::BlankNode(node) if bnode_as_var => ArcTerm::Variable(VarName::new_unchecked(
+            format!("{}{}", BNODE_VAR_PREFIX, node.as_str()).into(),
+        )),
+        TermPattern::BlankNode(node) => blank_node_term(node),
+        TermPattern::Literal(literal) => literal_term(literal),
+        TermPattern::Variable(var) => variable_term(var),
+    }
+}
+
+fn ground_term_pattern_term(pattern: GroundTermPattern) -> ArcTerm {
+    match pattern {
+        GroundTermPattern::NamedNode(node) => named_node_term(node),
+        GroundTermPattern::Literal(literal) => literal_term(literal),
+        GroundTermPattern::Variable(var) => variable_term(var),
+    }
+}
+
+/// An error denoting invalid sparql update patch.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum InvalidSparqlUpdatePatch {
+    /// Invalid sparql update.
+    #[error("Invalid sparql update.")]
+    InvalidSparqlUpdate,
+
+    /// Unsupported sparql update operation.
+    #[error("Unsupported sparql update operation. Only `INSERT DATA`, `DELETE DATA` and `DELETE/INSERT ... WHERE` are supported.")]
+    UnsupportedOperation,
+
+    /// Unsupported graph target.
+    #[error("Unsupported graph target. Only default graph can be targeted.")]
+    UnsupportedGraphTarget,
+
+    /// Unsupported graph pattern.
+    #[error("Unsupported graph pattern. Only basic graph patterns are supported as conditions.")]
+    UnsupportedGraphPattern,
+}
+
+/// Error in applying a sparql update patch against a target
+/// dataset.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum SparqlUpdatePatchError {
+    /// No variable mappings matched the conditions.
+    #[error("No variable mappings matched the conditions.")]
+    NoMatchedVariableMappings,
+
+    /// Deleted data is not a subset of target graph.
+    #[error("Deleted data is not a subset of target graph.")]
+    DeletionsIsNotSubSetOfTargetGraph,
+
+    /// Target dataset query error.
+    #[error("Target dataset query error.")]
+    TargetDatasetQueryError,
+
+    /// Target dataset mutation error.
+    #[error("Target dataset mutation error.")]
+    TargetDatasetMutationError,
+}
+
+impl SparqlUpdatePatchError {
+    /// Get if error is due to conflict with target state.
+    #[inline]
+    pub fn is_conflict(&self) -> bool {
+        matches!(
+            self,
+            Self::NoMatchedVariableMappings | Self::DeletionsIsNotSubSetOfTargetGraph
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EX: &str = "http://example.org/";
+
+    fn ex(name: &str) -> ArcTerm {
+        ArcTerm::Iri(IriRef::new_unchecked(format!("{}{}", EX, name).into()))
+    }
+
+    fn dataset(triples: &[[&str; 3]]) -> HashSet<([ArcTerm; 3], Option<ArcTerm>)> {
+        triples
+            .iter()
+            .map(|[s, p, o]| ([ex(s), ex(p), ex(o)], None))
+            .collect()
+    }
+
+    fn target() -> HashSet<([ArcTerm; 3], Option<ArcTerm>)> {
+        dataset(&[["a", "p", "b"], ["a", "p", "c"], ["b", "q", "c"]])
+    }
+
+    fn parse(update: &str) -> Result<SparqlUpdatePatch, InvalidSparqlUpdatePatch> {
+        SparqlUpdatePatch::parse(&format!("PREFIX ex: <{}> {}", EX, update), None)
+    }
+
+    #[test]
+    fn test_unsupported_updates_are_rejected() {
+        for update in [
+            "INSERT DATA { ex:a ex:p ",
+            "LOAD <http://example.org/g>",
+            "CLEAR DEFAULT",
+            "INSERT DATA { GRAPH ex:g { ex:a ex:p ex:b } }",
+            "DELETE { ex:a ex:p ?o } WHERE { { ex:a ex:p ?o } UNION { ex:a ex:q ?o } }",
+            "DELETE { ex:a ex:p ?o } USING ex:g WHERE { ex:a ex:p ?o }",
+        ] {
+            assert!(parse(update).is_err(), "{}", update);
+        }
+    }
+
+    #[test]
+    fn test_effective_ops() {
+        use PatchEffectiveOperation::*;
+
+        for (update, expected_ops) in [
+            ("INSERT DATA { ex:x ex:p ex:y }", vec![Append]),
+            ("DELETE DATA { ex:a ex:p ex:b }", vec![Read, Write]),
+            (
+                "INSERT { ?s ex:r ex:z } WHERE { ?s ex:p ex:b }",
+                vec![Read, Append],
+            ),
+        ] {
+            assert_eq!(
+                parse(update).unwrap().effective_ops(),
+                expected_ops.into_iter().collect(),
+                "{}",
+                update
+            );
+        }
+    }
+
+    #[test]
+    fn test_apply() {
+        for (update, expected) in [
+            (
+                "INSERT DATA { ex:x ex:p ex:y }",
+                dataset(&[
+                    ["a", "p", "b"],
+                    ["a", "p", "c"],
+                    ["b", "q", "c"],
+                    ["x", "p", "y"],
+                ]),
+            ),
+            (
+                "DELETE DATA { ex:a ex:p ex:b }",
+                dataset(&[["a", "p", "c"], ["b", "q", "c"]]),
+            ),
+            (
+                "DELETE { ex:a ex:p ?o } INSERT { ?o ex:r ex:a } WHERE { ex:a ex:p ?o }",
+                dataset(&[["b", "r", "a"], ["c", "r", "a"], ["b", "q", "c"]]),
+            ),
+            (
+                "DELETE WHERE { ?s ex:q ?o }",
+                dataset(&[["a", "p", "b"], ["a", "p", "c"]]),
+            ),
+            (
+                "DELETE DATA { ex:a ex:p ex:b } ; INSERT DATA { ex:a ex:p ex:d }",
+                dataset(&[["a", "p", "c"], ["b", "q", "c"], ["a", "p", "d"]]),
+            ),
+        ] {
+            assert_eq!(
+                parse(update).unwrap().apply(target()).unwrap(),
+                expected,
+                "{}",
+                update
+            );
+        }
+    }
+
+    #[test]
+    fn test_conflicting_updates_are_rejected() {
+        for update in [
+            "DELETE DATA { ex:a ex:p ex:x }",
+            "DELETE { ?s ex:p ?o } WHERE { ?s ex:p ?o . ?o ex:q ex:x }",
+        ] {
+            assert!(
+                parse(update)
+                    .unwrap()
+                    .apply(target())
+                    .unwrap_err()
+                    .is_conflict(),
+                "{}",
+                update
+            );
+        }
+    }
+}